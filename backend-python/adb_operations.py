@@ -2,10 +2,28 @@
 ADB Operations Module
 Handles all Android Debug Bridge operations
 """
+import os
 import subprocess
 import json
-from typing import List, Dict, Optional
+import threading
+import time
+from typing import Callable, List, Dict, Optional, Tuple
 import re
+from remediation import remediation_for
+from cache_policy import CacheStats, cache_report
+from device_class import DETECTION_SCRIPT, class_safety_level, parse_detection_output
+import fire_os
+from signatures import SIGNATURE_SCRIPT, platform_signed_packages, signature_info
+import oem_database
+import overlays
+import apex
+from shell_session import ShellSession, ShellSessionError, ShellSessionTimeout, can_use_session
+from provenance import parse_package_details, classify_install_source, partition_of, UNRESTORABLE_PARTITIONS
+from validation import is_overlay_id, is_package_name, is_permission_name
+from compatibility import (
+    parse_adb_version, parse_sdk_level, capabilities_for_sdk, build_probe_script,
+    parse_probe_output, unsupported_message, compatibility_warnings, FEATURE_MIN_SDK,
+)
 
 
 class ADBError(Exception):
@@ -13,10 +31,192 @@ class ADBError(Exception):
     pass
 
 
+class DeviceDisconnectedError(ADBError):
+    """The device went away (unplugged, offline, or rebooting)"""
+    pass
+
+
+class UnsupportedFeatureError(ADBError):
+    """The connected device's Android version is too old for a feature"""
+    pass
+
+
+class DevicePermissionError(ADBError):
+    """adb sees the device but may not open it (Linux udev rules or plugdev group)"""
+    pass
+
+
+class InvalidNameError(ADBError):
+    """A package, permission or overlay name that is malformed, refused before it reaches the shell"""
+    pass
+
+
+def require_package_name(package_name) -> str:
+    """`package_name` when it is a well-formed package name, else InvalidNameError"""
+    if not is_package_name(package_name):
+        raise InvalidNameError(f"Invalid package name: {package_name!r}")
+    return package_name
+
+
+def require_permission_name(permission) -> str:
+    """`permission` when it is a well-formed permission name, else InvalidNameError"""
+    if not is_permission_name(permission):
+        raise InvalidNameError(f"Invalid permission name: {permission!r}")
+    return permission
+
+
+# stderr fragments meaning the device is gone rather than the command failing
+DEVICE_LOST_MARKERS = (
+    "no devices found",
+    "no devices/emulators found",
+    "device not found",
+    "device offline",
+)
+
+# What adb prints for a USB device it is not allowed to open on Linux
+NO_PERMISSION_MARKERS = (
+    "no permissions",
+    "insufficient permissions",
+)
+
+NO_PERMISSION_MESSAGE = ("The device is connected but this computer's USB permissions do not let adb open it. "
+                         "A udev rule for the device fixes this.")
+
+# What each `pm uninstall` data choice means for the user
+KEEP_DATA_NOTE = ("App data and cache were kept on the device: reinstalling restores the app as it was, "
+                  "but the storage is not freed. Use wipe_package_data to remove it.")
+WIPE_DATA_NOTE = ("App data and cache were deleted: storage is freed, but reinstalling "
+                  "starts the app from scratch.")
+
+# Seconds to wait before verifying a batch removal, so OEM services that
+# immediately reinstall a package have had the chance to do it
+VERIFY_DELAY = 2.0
+
+# An `adb backup` archive with no app data is just the header and an empty compressed tar (~50 bytes)
+ADB_BACKUP_MIN_BYTES = 100
+
+# Safety tables, built once rather than on every package classified
+DANGEROUS_PACKAGES = frozenset({
+    'com.android.systemui',
+    'com.android.phone',
+    'com.android.settings',
+    'com.android.launcher',
+    'com.android.launcher3',
+    'com.android.vending',  # Play Store
+})
+EXPERT_PREFIXES = (
+    'com.google.android.gms',  # Google Play Services
+    'com.google.android.gsf',  # Google Services Framework
+    'com.android.bluetooth',
+    'com.android.nfc',
+)
+CAUTION_PREFIXES = (
+    'com.samsung.',
+    'com.xiaomi.',
+    'com.miui.',
+    'com.huawei.',
+    'com.oppo.',
+    'com.vivo.',
+    'com.realme.',
+    'com.oneplus.',
+)
+APP_NAME_PREFIXES = ('com.android.', 'com.google.', 'com.', 'org.', 'net.')
+
+# Separates the full and third-party listings in the combined list_packages call
+USER_PACKAGES_MARKER = "@@user"
+
+# `pm uninstall` failures caused by device management (MDM) policy
+POLICY_BLOCK_MARKERS = (
+    "DELETE_FAILED_DEVICE_POLICY_MANAGER",
+    "DELETE_FAILED_OWNER_BLOCKED",
+    "DELETE_FAILED_USER_RESTRICTED",
+)
+
+
+# `<serial>\t<state>[ product:... model:...]` lines of `adb devices`
+DEVICE_LINE_PATTERN = re.compile(
+    r'^(\S+)\s+(device|offline|unauthorized|authorizing|connecting|recovery|rescue|sideload|'
+    r'bootloader|host|no permissions|unknown)\b'
+)
+
+
+def parse_diskstats(output: str) -> Dict[str, Dict[str, int]]:
+    """
+    Parse per-package sizes from `dumpsys diskstats`.
+
+    The output holds parallel JSON arrays: `Package Names: [...]`,
+    `App Sizes: [...]`, `App Data Sizes: [...]`, `Cache Sizes: [...]`.
+    A package whose three sizes are not all whole numbers (an array cut
+    off or unreadable) is left out, so callers show its size as unknown.
+    """
+    arrays = {}
+    for line in output.split('\n'):
+        key, sep, value = line.partition(':')
+        if sep and value.strip().startswith('['):
+            try:
+                arrays[" ".join(key.split())] = json.loads(value.strip())
+            except json.JSONDecodeError:
+                continue
+
+    names = arrays.get("Package Names", [])
+    columns = [arrays.get(key, []) for key in ("App Sizes", "App Data Sizes", "Cache Sizes")]
+    sizes = {}
+    for index, name in enumerate(names):
+        values = [column[index] if index < len(column) else None for column in columns]
+        if not all(type(value) is int and value >= 0 for value in values):
+            continue
+        app, data, cache = values
+        sizes[name] = {
+            "appBytes": app,
+            "dataBytes": data,
+            "cacheBytes": cache,
+            "totalBytes": app + data + cache,
+        }
+    return sizes
+
+
+def parse_devices(output: str) -> List[Dict[str, str]]:
+    """
+    [{"serial", "state"}] from `adb devices` (with or without -l), skipping
+    the header and the daemon and version notices older adb prints on stdout
+    """
+    devices = []
+    for line in output.split('\n'):
+        match = DEVICE_LINE_PATTERN.match(line.strip())
+        if match:
+            devices.append({"serial": match.group(1), "state": match.group(2)})
+    return devices
+
+
+def parse_user_data_inode(output: str, user_id: int = 0) -> Optional[int]:
+    """
+    Inode of a package's credential-encrypted data directory for one user,
+    from the `User 0: ceDataInode=1234 installed=false ...` line of
+    `dumpsys package <pkg>`. 0 means the directory is gone; None means
+    the line is missing (Android 7 and older do not print it).
+    """
+    match = re.search(rf'^\s*User {user_id}:.*?\bceDataInode=(\d+)', output, re.MULTILINE)
+    return int(match.group(1)) if match else None
+
+
+SAFETY_LEVELS = ("Safe", "Caution", "Expert", "Dangerous")
+
+
+def stricter_safety_level(level: str, minimum: str) -> str:
+    """`level`, raised to `minimum` when that is stricter"""
+    return max(level, minimum, key=SAFETY_LEVELS.index)
+
+
+def parse_package_names(output: str) -> List[str]:
+    """Package names from `pm list packages` output, in listing order"""
+    return [line[len("package:"):].strip() for line in output.split('\n')
+            if line.startswith("package:") and line[len("package:"):].strip()]
+
+
 class ADBOperations:
     """Handle all ADB-related operations"""
     
-    def __init__(self):
+    def __init__(self, shell_sessions: bool = False, oem_db: Optional[oem_database.OemDatabase] = None):
         import shutil
         import os
         import sys
@@ -40,6 +240,34 @@ class ADBOperations:
             self.adb_path = r'C:\platform-tools\adb.exe'
         else:
             self.adb_path = 'adb'
+
+        self._adb_version: Optional[Dict] = None
+        self._sdk_levels: Dict[str, Optional[int]] = {}
+        # serial -> probed feature flags (see probe_device_capabilities)
+        self._probes: Dict[str, Dict[str, bool]] = {}
+        # serial -> detected device class (see device_class.py)
+        self._device_classes: Dict[str, Dict] = {}
+        # serial -> Fire OS version, None for other devices (see fire_os.py)
+        self._fire_os: Dict[str, Optional[str]] = {}
+        # serial -> OEM file of the maker, None for makers without one (see oem_database.py)
+        self._oems: Dict[str, Optional[str]] = {}
+        self.oem_db = oem_db or oem_database.OemDatabase()
+        # serial -> package -> platform key and signature permissions (see signatures.py)
+        self._signatures: Dict[str, Dict[str, Dict]] = {}
+        # serial -> overlay package -> package it restyles (see overlays.py)
+        self._overlays: Dict[str, Dict[str, str]] = {}
+        # serial -> APEX modules, and apps found inside them -> their module (see apex.py)
+        self._apex_modules: Dict[str, List[Dict]] = {}
+        self._apex_apps: Dict[str, Dict[str, str]] = {}
+        # package -> when the app last installed or removed it; "" for APK installs (see package_watcher.py)
+        self._own_package_changes: Dict[str, float] = {}
+        # Hits and misses of the per-device API level and probe caches
+        self.profile_stats = CacheStats()
+        # Run quick shell commands over a persistent session per device (see shell_session.py)
+        self.shell_sessions = shell_sessions
+        self._sessions: Dict[str, ShellSession] = {}
+        self._session_serial: Optional[str] = None
+        self._sessions_lock = threading.Lock()
     
     def _run_command(self, command: List[str], timeout: int = 30) -> str:
         """Run an ADB command and return output"""
@@ -57,12 +285,15 @@ class ADBOperations:
                 error_msg = result.stderr.strip() if result.stderr else "Unknown error"
                 
                 # Parse common ADB errors
-                if "no devices found" in error_msg.lower():
-                    raise ADBError("No Android device connected. Please connect via USB.")
-                elif "device unauthorized" in error_msg.lower():
+                lowered = error_msg.lower()
+                if any(marker in lowered for marker in NO_PERMISSION_MARKERS):
+                    raise DevicePermissionError(NO_PERMISSION_MESSAGE)
+                elif "device offline" in lowered:
+                    raise DeviceDisconnectedError("Device is offline. Please reconnect the device.")
+                elif any(marker in lowered for marker in DEVICE_LOST_MARKERS):
+                    raise DeviceDisconnectedError("No Android device connected. Please connect via USB.")
+                elif "device unauthorized" in lowered:
                     raise ADBError("Device unauthorized. Please check device for USB debugging prompt.")
-                elif "device offline" in error_msg.lower():
-                    raise ADBError("Device is offline. Please reconnect the device.")
                 else:
                     raise ADBError(f"ADB command failed: {error_msg}")
             
@@ -77,16 +308,15 @@ class ADBOperations:
         """Get information about connected Android device"""
         try:
             # Get device serial
-            devices = self._run_command([self.adb_path, "devices", "-l"])
-            lines = [l for l in devices.split('\n') if l.strip() and not l.startswith('List')]
+            devices = parse_devices(self._run_command([self.adb_path, "devices", "-l"]))
             
-            if not lines:
+            if not devices:
                 raise ADBError("No device connected")
             
-            # Parse first device
-            device_line = lines[0]
-            parts = device_line.split()
-            serial = parts[0]
+            # First device
+            serial = devices[0]["serial"]
+            if devices[0]["state"] == "no permissions":
+                raise DevicePermissionError(NO_PERMISSION_MESSAGE)
             
             # Get device properties
             model = self._get_property("ro.product.model")
@@ -103,9 +333,316 @@ class ADBOperations:
                 "androidVersion": android_version,
                 "state": "device"
             }
+        except DevicePermissionError:
+            raise
         except Exception as e:
             raise ADBError(str(e))
     
+    def shell(self, command: str, timeout: int = 30) -> str:
+        """Run a shell command on the device and return its output"""
+        if self.shell_sessions and can_use_session(command, timeout):
+            output = self._session_shell(command, timeout)
+            if output is not None:
+                return output
+        return self._run_command([self.adb_path, "shell", command], timeout=timeout)
+
+    def _current_session(self) -> ShellSession:
+        with self._sessions_lock:
+            session = self._sessions.get(self._session_serial)
+            if session is not None and session.alive:
+                return session
+            # The device changed or its session ended (unplugged, rebooted)
+            for stale in [s for s, sess in self._sessions.items() if not sess.alive]:
+                del self._sessions[stale]
+            serial = self.get_serial()
+            session = self._sessions.get(serial)
+            if session is None or not session.alive:
+                try:
+                    session = ShellSession([self.adb_path, "-s", serial, "shell"])
+                except OSError as e:
+                    raise ShellSessionError(str(e))
+                self._sessions[serial] = session
+            self._session_serial = serial
+            return session
+
+    def _session_shell(self, command: str, timeout: int) -> Optional[str]:
+        """
+        Output of `command` run over the device's session; None when the
+        one-shot invocation should be used instead (session busy or gone,
+        or a failing exit status, whose error one-shot adb reports)
+        """
+        try:
+            result = self._current_session().run(command, timeout)
+        except ShellSessionTimeout:
+            raise ADBError("ADB command timed out. Please check device connection.")
+        except (ShellSessionError, ADBError):
+            return None
+        if result is None or result[1] != 0:
+            return None
+        return result[0]
+
+    def close_sessions(self):
+        """End every persistent shell session"""
+        with self._sessions_lock:
+            for session in self._sessions.values():
+                session.close()
+            self._sessions.clear()
+            self._session_serial = None
+
+    def exec_out(self, command: str, timeout: int = 30) -> str:
+        """
+        Like shell(), but through `adb exec-out`: output arrives as raw bytes
+        without a pty, which is faster for large listings and has no CRLF
+        """
+        return self._run_command([self.adb_path, "exec-out", command], timeout=timeout)
+
+    def get_serial(self) -> str:
+        """Get the serial of the connected device"""
+        serial = self._run_command([self.adb_path, "get-serialno"], timeout=10).strip()
+        if not serial or serial == "unknown":
+            raise ADBError("No device connected")
+        return serial
+
+    def get_device_states(self) -> Dict[str, str]:
+        """serial -> state ("device", "unauthorized", "offline"...) of every device adb sees"""
+        output = self._run_command([self.adb_path, "devices"], timeout=10)
+        return {device["serial"]: device["state"] for device in parse_devices(output)}
+
+    def wait_for_device(self, timeout: int = 60) -> bool:
+        """Block until a device is connected again; False if it doesn't return in time"""
+        try:
+            self._run_command([self.adb_path, "wait-for-device"], timeout=timeout)
+            return True
+        except ADBError:
+            return False
+
+    def get_adb_version(self) -> Dict[str, Optional[str]]:
+        """adb and platform-tools versions of the adb binary in use"""
+        if self._adb_version is None:
+            self._adb_version = parse_adb_version(self._run_command([self.adb_path, "version"], timeout=10))
+        return self._adb_version
+
+    def get_sdk_level(self) -> Optional[int]:
+        """API level of the connected device (cached per serial)"""
+        serial = self.get_serial()
+        if serial in self._sdk_levels:
+            self.profile_stats.hit()
+        else:
+            self.profile_stats.miss()
+            self._sdk_levels[serial] = parse_sdk_level(self.shell("getprop ro.build.version.sdk", timeout=10))
+        return self._sdk_levels[serial]
+
+    def probe_device_capabilities(self) -> Dict:
+        """
+        Test which optional shell commands exist on the connected device.
+
+        Runs on connect; results are cached per serial and override the
+        API-level defaults, so modules can pick fallbacks up front.
+        """
+        serial = self.get_serial()
+        try:
+            probed = parse_probe_output(self.shell(build_probe_script(), timeout=30))
+        except DeviceDisconnectedError:
+            raise
+        except ADBError:
+            probed = {}  # Fall back to API-level defaults
+        self._probes[serial] = probed
+        return {"serial": serial, "capabilities": self.capabilities(), "probed": probed}
+
+    def profile_cache_diagnostics(self) -> Dict:
+        """get_cache_diagnostics line of the per-device API levels and probed capabilities"""
+        profile = {"sdkLevels": dict(self._sdk_levels), "probes": dict(self._probes),
+                   "deviceClasses": dict(self._device_classes), "fireOs": dict(self._fire_os),
+                   "oems": dict(self._oems), "signatures": dict(self._signatures), "overlays": dict(self._overlays),
+                   "apexModules": dict(self._apex_modules)}
+        serials = (set(self._sdk_levels) | set(self._probes) | set(self._device_classes) | set(self._fire_os)
+                   | set(self._oems) | set(self._signatures) | set(self._overlays) | set(self._apex_modules))
+        return cache_report("deviceProfile", self.profile_stats, profile, [], len(serials))
+
+    def get_device_class(self) -> Dict:
+        """{"deviceClass", "characteristics", "features"} of the connected device (cached per serial)"""
+        serial = self.get_serial()
+        if serial in self._device_classes:
+            self.profile_stats.hit()
+        else:
+            self.profile_stats.miss()
+            self._device_classes[serial] = parse_detection_output(self.shell(DETECTION_SCRIPT, timeout=15))
+        return self._device_classes[serial]
+
+    def get_fire_os_version(self) -> Optional[str]:
+        """Fire OS version of the connected device, None when it does not run Fire OS (cached per serial)"""
+        serial = self.get_serial()
+        if serial in self._fire_os:
+            self.profile_stats.hit()
+        else:
+            self.profile_stats.miss()
+            self._fire_os[serial] = fire_os.parse_fire_os_version(self.shell(fire_os.DETECTION_SCRIPT, timeout=10))
+        return self._fire_os[serial]
+
+    def get_oem(self) -> Optional[str]:
+        """OEM database file for the connected device's maker, None when there is none (cached per serial)"""
+        serial = self.get_serial()
+        if serial in self._oems:
+            self.profile_stats.hit()
+        else:
+            self.profile_stats.miss()
+            self._oems[serial] = oem_database.parse_oem(self.shell(oem_database.DETECTION_SCRIPT, timeout=10))
+        return self._oems[serial]
+
+    def get_signature_info(self) -> Dict[str, Dict]:
+        """
+        package -> {"platformSigned", "privileged", "sharedUserId",
+        "signaturePermissions"} for the connected device (cached per serial)
+        """
+        serial = self.get_serial()
+        if serial in self._signatures:
+            self.profile_stats.hit()
+        else:
+            self.profile_stats.miss()
+            # The full package dump runs to megabytes on a stock phone
+            self._signatures[serial] = signature_info(self.exec_out(SIGNATURE_SCRIPT, timeout=60))
+        return self._signatures[serial]
+
+    def get_overlays(self) -> List[Dict]:
+        """Runtime resource overlays with their target and state; refreshes the listing tags"""
+        self.require("overlay_command")
+        found = overlays.parse_overlay_list(self.shell(overlays.OVERLAY_LIST_COMMAND, timeout=15))
+        self._overlays[self.get_serial()] = overlays.overlay_targets(found)
+        for overlay in found:
+            overlay["locked"] = overlays.is_locked(overlay["id"])
+        return found
+
+    def get_overlay_targets(self) -> Dict[str, str]:
+        """Overlay package -> target of the connected device for tagging listings (cached per serial)"""
+        try:
+            serial = self.get_serial()
+            if serial in self._overlays:
+                self.profile_stats.hit()
+            else:
+                self.profile_stats.miss()
+                if not self.supports("overlay_command"):
+                    return {}
+                self.get_overlays()
+            return self._overlays[serial]
+        except ADBError:
+            return {}
+
+    def set_overlay_enabled(self, overlay_id: str, enabled: bool) -> Dict:
+        """Turn a theme overlay on or off for user 0; the APK stays installed either way"""
+        if overlays.is_locked(overlay_id):
+            return {"success": False, "locked": True, "message": overlays.LOCKED_MESSAGE.format(overlay=overlay_id)}
+        try:
+            if not is_overlay_id(overlay_id):
+                raise InvalidNameError(f"Invalid overlay id: {overlay_id!r}")
+            self.require("overlay_command")
+            action = "enable" if enabled else "disable"
+            output = self.shell(f"cmd overlay {action} --user 0 {overlay_id} 2>&1", timeout=15)
+        except DeviceDisconnectedError as e:
+            return {"success": False, "deviceLost": True, "message": str(e)}
+        except UnsupportedFeatureError as e:
+            return {"success": False, "unsupported": True, "message": str(e)}
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        if overlays.OVERLAY_ERROR_PATTERN.search(output):
+            return {"success": False, "message": f"Failed to {action} {overlay_id}: {output.strip()}"}
+        return {"success": True, "enabled": enabled,
+                "message": f"{overlay_id} {'enabled' if enabled else 'disabled'}"}
+
+    def get_apex_modules(self) -> List[Dict]:
+        """APEX modules of the connected device with their version code (cached per serial)"""
+        serial = self.get_serial()
+        if serial in self._apex_modules:
+            self.profile_stats.hit()
+        else:
+            self.profile_stats.miss()
+            self.require("apex_list")
+            self._apex_modules[serial] = apex.parse_apex_list(self.shell(apex.APEX_LIST_COMMAND, timeout=15))
+        apps = self._apex_apps.get(serial, {})
+        return [
+            {**module, "apps": sorted(app for app, owner in apps.items() if owner == module["packageName"])}
+            for module in self._apex_modules[serial]
+        ]
+
+    def get_apex_packages(self) -> Dict[str, str]:
+        """
+        package -> APEX module for the modules and the apps found inside them;
+        {} when the device can't list modules or the lookup fails
+        """
+        try:
+            if not self.supports("apex_list"):
+                return {}
+            packages = {module["packageName"]: module["packageName"] for module in self.get_apex_modules()}
+            packages.update(self._apex_apps.get(self.get_serial(), {}))
+            return packages
+        except ADBError:
+            return {}
+
+    def apex_module_for(self, package_name: str) -> Optional[str]:
+        """APEX module a package is, or ships inside, None for ordinary packages"""
+        return self.get_apex_packages().get(package_name)
+
+    def _safety_profile(self) -> Tuple[Optional[str], bool, set, Optional[str]]:
+        """(device class, runs Fire OS, platform-signed packages, OEM) of the connected device for safety levels"""
+        try:
+            device_class, is_fire_os = self.get_device_class()["deviceClass"], self.get_fire_os_version() is not None
+        except ADBError:
+            return None, False, set(), None  # Generic safety levels only
+        try:
+            platform_signed = platform_signed_packages(self.get_signature_info())
+        except ADBError:
+            platform_signed = set()
+        try:
+            oem = self.get_oem()
+        except ADBError:
+            oem = None
+        return device_class, is_fire_os, platform_signed, oem
+
+    def safety_rater(self) -> Callable[[str], str]:
+        """package -> safety level on the connected device, with its profile read once"""
+        device_class, is_fire_os, platform_signed, oem = self._safety_profile()
+        return lambda package: self._determine_safety_level(package, device_class, is_fire_os,
+                                                            package in platform_signed, oem)
+
+    def capabilities(self) -> Dict[str, bool]:
+        """Feature flags for the connected device, probing it on first use"""
+        serial = self.get_serial()
+        if serial in self._probes:
+            self.profile_stats.hit()
+        else:
+            self.profile_stats.miss()
+            self.probe_device_capabilities()
+        return capabilities_for_sdk(self.get_sdk_level(), self._probes[serial])
+
+    def supports(self, feature: str) -> bool:
+        """Whether the connected device supports a feature in FEATURE_MIN_SDK"""
+        return self.capabilities()[feature]
+
+    def require(self, feature: str):
+        """Raise UnsupportedFeatureError if the connected device lacks a feature"""
+        if not self.supports(feature):
+            raise UnsupportedFeatureError(unsupported_message(feature, self.get_sdk_level()))
+
+    def get_compatibility(self) -> Dict:
+        """Tool and device versions, feature flags and warnings"""
+        try:
+            adb_version = self.get_adb_version()
+        except ADBError:
+            adb_version = {"adbVersion": None, "platformToolsVersion": None}
+        try:
+            sdk = self.get_sdk_level()
+            capabilities = self.capabilities()
+            probed = self._probes.get(self.get_serial(), {})
+        except ADBError:
+            sdk, capabilities, probed = None, capabilities_for_sdk(None), {}
+        return {
+            **adb_version,
+            "sdkLevel": sdk,
+            "capabilities": capabilities,
+            "probed": probed,
+            "requirements": {feature: min_sdk for feature, (min_sdk, _) in FEATURE_MIN_SDK.items()},
+            "warnings": compatibility_warnings(adb_version, sdk),
+        }
+
     def _get_property(self, prop: str) -> str:
         """Get a device property"""
         try:
@@ -115,37 +652,85 @@ class ADBOperations:
             return "Unknown"
     
     def list_packages(self, package_type: str = "all") -> List[Dict]:
-        """List installed packages on device"""
+        """
+        List installed packages on device.
+
+        The "all" listing fetches the third-party list in the same adb call
+        and marks each package's isSystem, so callers need no second query.
+        """
         try:
-            # Get package list
+            # -f -i add each package's code path and installer, for its provenance
             if package_type == "system":
-                cmd = [self.adb_path, "shell", "pm", "list", "packages", "-s"]
+                script = "pm list packages -f -i -s"
             elif package_type == "user":
-                cmd = [self.adb_path, "shell", "pm", "list", "packages", "-3"]
+                script = "pm list packages -f -i -3"
             else:
-                cmd = [self.adb_path, "shell", "pm", "list", "packages"]
-            
-            output = self._run_command(cmd)
-            
-            packages = []
-            for line in output.split('\n'):
-                if line.startswith('package:'):
-                    package_name = line.replace('package:', '').strip()
-                    if package_name:
-                        packages.append({
-                            "packageName": package_name,
-                            "appName": self._get_app_name(package_name),
-                            "safetyLevel": self._determine_safety_level(package_name)
-                        })
-            
-            # Sort by package name
-            packages.sort(key=lambda p: p["packageName"])
+                script = f"pm list packages -f -i; echo {USER_PACKAGES_MARKER}; pm list packages -3"
             
-            return packages
+            output = self.exec_out(script, timeout=60)
+            if package_type == "system":
+                user_packages = set()
+            elif package_type == "user":
+                user_packages = None
+            else:
+                output, _, user_output = output.partition(USER_PACKAGES_MARKER)
+                user_packages = set(parse_package_names(user_output))
+            details = parse_package_details(output)
+            if package_type == "user":
+                user_packages = set(details)
+            return self.build_package_entries(list(details), user_packages, details)
             
         except Exception as e:
             raise ADBError(f"Failed to list packages: {str(e)}")
     
+    def build_package_entries(self, package_names: List[str], user_packages: Optional[set] = None,
+                              details: Optional[Dict] = None) -> List[Dict]:
+        """
+        Package listing entries sorted by name.
+
+        isSystem is set when the third-party set is known; installer,
+        installSource and partition when `details` (package -> (code path,
+        installer)) are. Vendor and APEX packages are rated Expert at least,
+        as `install-existing` may not bring them back.
+        """
+        device_class, is_fire_os, platform_signed, oem = self._safety_profile()
+        # package -> APEX module, from the code paths in `details`
+        apex_apps: Dict[str, str] = {}
+        packages = []
+        for package_name in sorted(set(package_names)):
+            entry = {
+                "packageName": package_name,
+                "appName": self._get_app_name(package_name),
+                "safetyLevel": self._determine_safety_level(package_name, device_class, is_fire_os,
+                                                            package_name in platform_signed, oem)
+            }
+            if package_name in platform_signed:
+                entry["platformSigned"] = True
+            if user_packages is not None:
+                entry["isSystem"] = package_name not in user_packages
+            if details is not None and package_name in details:
+                path, installer = details[package_name]
+                entry["installer"] = installer
+                entry["installSource"] = classify_install_source(package_name, path, installer, entry.get("isSystem"))
+                entry["partition"] = partition_of(path)
+                if entry["partition"] in UNRESTORABLE_PARTITIONS:
+                    entry["safetyLevel"] = stricter_safety_level(entry["safetyLevel"], "Expert")
+                module = apex.module_of_path(path)
+                if module:
+                    apex_apps[package_name] = module
+            packages.append(entry)
+        if apex_apps:
+            try:
+                self._apex_apps.setdefault(self.get_serial(), {}).update(apex_apps)
+            except ADBError:
+                pass
+        packages = apex.tag_apex(packages, self.get_apex_packages())
+        return overlays.tag_overlays(packages, self.get_overlay_targets())
+    
+    def get_package_sizes(self) -> Dict[str, Dict[str, int]]:
+        """Get app/data/cache sizes for every package (from the last diskstats run)"""
+        return parse_diskstats(self.shell("dumpsys diskstats", timeout=30))
+    
     def _guess_package_type(self, package: str) -> str:
         """Guess if package is system or user app"""
         system_prefixes = [
@@ -168,81 +753,93 @@ class ADBOperations:
         """Extract a friendly app name from package name"""
         # Remove common prefixes
         name = package_name
-        prefixes = ['com.android.', 'com.google.', 'com.', 'org.', 'net.']
-        for prefix in prefixes:
+        for prefix in APP_NAME_PREFIXES:
             if name.startswith(prefix):
                 name = name[len(prefix):]
                 break
         
-        # Split by dots and take the most relevant part
-        parts = name.split('.')
-        if parts:
-            name = parts[0]
-        
-        # Capitalize first letter
-        return name.capitalize()
+        # Take the most relevant part and capitalize its first letter
+        return name.split('.', 1)[0].capitalize()
     
-    def _determine_safety_level(self, package_name: str) -> str:
+    def _determine_safety_level(self, package_name: str, device_class: Optional[str] = None,
+                                is_fire_os: bool = False, platform_signed: bool = False,
+                                oem: Optional[str] = None) -> str:
         """Determine safety level for removing a package"""
+        # Class-specific - e.g. the launcher of a TV box
+        level = class_safety_level(device_class, package_name)
+        if level:
+            return level
+
+        # Amazon system apps on Fire tablets and Fire TV
+        level = fire_os.fire_os_safety_level(package_name) if is_fire_os else None
+        if level:
+            return level
+
         # Dangerous - Critical system apps
-        dangerous_packages = {
-            'com.android.systemui',
-            'com.android.phone',
-            'com.android.settings',
-            'com.android.launcher',
-            'com.android.launcher3',
-            'com.android.vending',  # Play Store
-        }
+        if package_name in DANGEROUS_PACKAGES:
+            return "Dangerous"
+
+        # Rated by the built-in list for the device's maker, carriers and partner preloads
+        level = self.oem_db.safety_level(oem, package_name)
+        if level:
+            return level
         
         # Expert - May break functionality
-        expert_prefixes = [
-            'com.google.android.gms',  # Google Play Services
-            'com.google.android.gsf',  # Google Services Framework
-            'com.android.bluetooth',
-            'com.android.nfc',
-        ]
+        if package_name.startswith(EXPERT_PREFIXES):
+            return "Expert"
         
         # Caution - OEM apps
-        caution_prefixes = [
-            'com.samsung.',
-            'com.xiaomi.',
-            'com.miui.',
-            'com.huawei.',
-            'com.oppo.',
-            'com.vivo.',
-            'com.realme.',
-            'com.oneplus.',
-        ]
-        
-        # Check dangerous
-        if package_name in dangerous_packages:
+        if package_name.startswith(CAUTION_PREFIXES):
+            return "Caution"
+
+        # Dangerous - signed with the platform key but in none of the tables above
+        if platform_signed:
             return "Dangerous"
         
-        # Check expert
-        for prefix in expert_prefixes:
-            if package_name.startswith(prefix):
-                return "Expert"
-        
-        # Check caution
-        for prefix in caution_prefixes:
-            if package_name.startswith(prefix):
-                return "Caution"
-        
         # Default to Safe (user apps, bloatware)
         return "Safe"
     
-    def uninstall_package(self, package_name: str) -> Dict:
-        """Uninstall a package from device"""
+    def uninstall_package(self, package_name: str, keep_data: bool = False) -> Dict:
+        """
+        Uninstall a package from device
+
+        keep_data passes `-k`, leaving the app's data and cache in place so a
+        later reinstall restores it as it was
+        """
         try:
-            # Try uninstall
+            require_package_name(package_name)
+            self.require("user_uninstall")
+            module = self.apex_module_for(package_name)
+            if module:
+                return {
+                    "success": False,
+                    "apex": True,
+                    "message": apex.REMOVAL_MESSAGE.format(package=package_name, module=module)
+                }
+            flags = ["-k"] if keep_data else []
             output = self._run_command(
-                [self.adb_path, "shell", "pm", "uninstall", "--user", "0", package_name]
+                [self.adb_path, "shell", "pm", "uninstall", *flags, "--user", "0", package_name]
             )
             
             if "Success" in output:
-                return {
+                self._own_package_changes[package_name] = time.time()
+                return self._with_fire_os_note(package_name, {
                     "success": True,
-                    "message": f"Successfully uninstalled {package_name}"
+                    "message": f"Successfully uninstalled {package_name}",
+                    "keepData": keep_data,
+                    "dataNote": KEEP_DATA_NOTE if keep_data else WIPE_DATA_NOTE
+                })
+            elif fire_os.PROTECTED_PATTERN.search(output) and self._is_fire_os():
+                return {
+                    "success": False,
+                    "protected": True,
+                    "message": fire_os.PROTECTED_MESSAGE.format(package=package_name)
+                }
+            elif any(marker in output for marker in POLICY_BLOCK_MARKERS):
+                return {
+                    "success": False,
+                    "managed": True,
+                    "message": f"{package_name} is controlled by your organization's device management and cannot be removed"
                 }
             else:
                 return {
@@ -250,33 +847,285 @@ class ADBOperations:
                     "message": f"Failed to uninstall: {output.strip()}"
                 }
                 
+        except DeviceDisconnectedError as e:
+            return {
+                "success": False,
+                "deviceLost": True,
+                "message": str(e)
+            }
+        except UnsupportedFeatureError as e:
+            return {
+                "success": False,
+                "unsupported": True,
+                "message": str(e)
+            }
         except Exception as e:
             return {
                 "success": False,
                 "message": str(e)
             }
     
-    def reinstall_package(self, package_name: str) -> Dict:
-        """Reinstall a previously removed package"""
+    def package_changes_since(self, since: float) -> List[str]:
+        """Packages the app itself installed or removed since `since`; "" stands for an APK install"""
+        return [package for package, at in list(self._own_package_changes.items()) if at >= since]
+
+    def verify_removal(self, package_names: List[str], delay: float = VERIFY_DELAY) -> Dict[str, str]:
+        """
+        Re-query the device after a batch removal
+
+        Returns package -> "removed" | "disabled" | "installed"; "installed"
+        means the uninstall reported success but the package is still there
+        (e.g. an OEM service reinstalled it)
+        """
+        if delay:
+            time.sleep(delay)
+        installed = {line[len("package:"):].strip()
+                     for line in self.shell("pm list packages", timeout=30).split('\n') if line.startswith("package:")}
+        disabled = {line[len("package:"):].strip()
+                    for line in self.shell("pm list packages -d", timeout=30).split('\n') if line.startswith("package:")}
+        statuses = {}
+        for package in package_names:
+            if package in disabled:
+                statuses[package] = "disabled"
+            elif package in installed:
+                statuses[package] = "installed"
+            else:
+                statuses[package] = "removed"
+        return statuses
+
+    def wipe_package_data(self, package_name: str) -> Dict:
+        """Delete a package's data and cache for user 0 (`pm clear`)"""
         try:
-            # Reinstall for user 0
+            require_package_name(package_name)
             output = self._run_command(
-                [self.adb_path, "shell", "cmd", "package", "install-existing", package_name]
+                [self.adb_path, "shell", "pm", "clear", "--user", "0", package_name]
             )
-            
-            if "installed" in output.lower():
+            if "Success" in output:
                 return {
                     "success": True,
-                    "message": f"Successfully reinstalled {package_name}"
+                    "message": f"Deleted app data of {package_name}"
                 }
             else:
                 return {
                     "success": False,
-                    "message": f"Failed to reinstall: {output.strip()}"
+                    "message": f"Failed to delete app data: {output.strip()}"
+                }
+        except DeviceDisconnectedError as e:
+            return {
+                "success": False,
+                "deviceLost": True,
+                "message": str(e)
+            }
+        except Exception as e:
+            return {
+                "success": False,
+                "message": str(e)
+            }
+
+    def get_apk_paths(self, package_name: str) -> List[str]:
+        """On-device paths of a package's base and split APKs (`pm path`)"""
+        output = self.shell(f"pm path {require_package_name(package_name)}", timeout=15)
+        return [line[len("package:"):].strip() for line in output.split('\n') if line.startswith("package:")]
+
+    def pull_file(self, remote_path: str, local_path: str, timeout: int = 300):
+        """Copy a file from the device"""
+        self._run_command([self.adb_path, "pull", remote_path, local_path], timeout=timeout)
+
+    def install_apks(self, apk_paths: List[str]) -> Dict:
+        """Install a package from local APK files (base plus splits)"""
+        try:
+            verb = "install-multiple" if len(apk_paths) > 1 else "install"
+            output = self._run_command([self.adb_path, verb, "-r", *apk_paths], timeout=300)
+            if "Success" in output:
+                self._own_package_changes[""] = time.time()
+                return {
+                    "success": True,
+                    "message": "Installed from backed-up APK"
+                }
+            else:
+                return {
+                    "success": False,
+                    "message": f"Failed to install APK: {output.strip()}"
+                }
+        except DeviceDisconnectedError as e:
+            return {
+                "success": False,
+                "deviceLost": True,
+                "message": str(e)
+            }
+        except Exception as e:
+            return {
+                "success": False,
+                "message": str(e)
+            }
+
+    def backup_app_data(self, package_names: List[str], backup_path: str) -> Dict:
+        """
+        Back up app data with the legacy `adb backup` (no APKs)
+
+        Blocks until the user confirms on the device. Apps that disallow
+        backup are silently skipped by Android, so an empty archive is
+        reported as a failure.
+        """
+        try:
+            for package_name in package_names:
+                require_package_name(package_name)
+            self.require("adb_backup")
+            self._run_command([self.adb_path, "backup", "-f", backup_path, "-noapk", *package_names], timeout=600)
+            size = os.path.getsize(backup_path) if os.path.exists(backup_path) else 0
+            if size < ADB_BACKUP_MIN_BYTES:
+                return {
+                    "success": False,
+                    "message": "No app data was backed up: the backup was declined on the device "
+                               "or the apps do not allow backup"
                 }
+            return {
+                "success": True,
+                "sizeBytes": size,
+                "message": f"Backed up data of {len(package_names)} app(s)"
+            }
+        except UnsupportedFeatureError as e:
+            return {
+                "success": False,
+                "unsupported": True,
+                "message": str(e)
+            }
+        except Exception as e:
+            return {
+                "success": False,
+                "message": str(e)
+            }
+
+    def restore_app_data(self, backup_path: str) -> Dict:
+        """Restore an `adb backup` archive; blocks until the user confirms on the device"""
+        try:
+            self.require("adb_backup")
+            self._run_command([self.adb_path, "restore", backup_path], timeout=600)
+            return {
+                "success": True,
+                "message": "App data restore finished"
+            }
+        except UnsupportedFeatureError as e:
+            return {
+                "success": False,
+                "unsupported": True,
+                "message": str(e)
+            }
+        except Exception as e:
+            return {
+                "success": False,
+                "message": str(e)
+            }
+
+    def set_package_enabled(self, package_name: str, enabled: bool) -> Dict:
+        """
+        Disable a package for user 0 (`pm disable-user`) or enable it again.
+
+        Unlike an uninstall the APK and its data stay in place, so enabling
+        it brings the app back exactly as it was.
+        """
+        action = "enable" if enabled else "disable-user"
+        try:
+            require_package_name(package_name)
+            output = self._run_command([self.adb_path, "shell", "pm", action, "--user", "0", package_name])
+        except DeviceDisconnectedError as e:
+            return {"success": False, "deviceLost": True, "message": str(e)}
+        except Exception as e:
+            return {"success": False, "message": str(e)}
+        if "new state" not in output:
+            if fire_os.PROTECTED_PATTERN.search(output) and self._is_fire_os():
+                return {"success": False, "protected": True,
+                        "message": fire_os.PROTECTED_MESSAGE.format(package=package_name)}
+            return {"success": False, "message": f"Failed to {action.split('-')[0]}: {output.strip()}"}
+        state = "enabled" if enabled else "disabled"
+        result = {"success": True, "message": f"{package_name} {state}"}
+        return result if enabled else self._with_fire_os_note(package_name, result)
+
+    def _is_fire_os(self) -> bool:
+        try:
+            return self.get_fire_os_version() is not None
+        except ADBError:
+            return False
+
+    def _with_fire_os_note(self, package_name: str, result: Dict) -> Dict:
+        """Warn that a removed or disabled Amazon app may come back with a Fire OS update"""
+        if package_name.startswith("com.amazon.") and self._is_fire_os():
+            result["updateNote"] = fire_os.UPDATE_NOTE
+        return result
+
+    def _data_preserved(self, package_name: str) -> Optional[bool]:
+        """Whether user 0 still has the package's data directory (None when unknown)"""
+        try:
+            inode = parse_user_data_inode(self.shell(f"dumpsys package {require_package_name(package_name)}",
+                                                     timeout=20))
+        except ADBError:
+            return None
+        return None if inode is None else inode > 0
+
+    def reinstall_package(self, package_name: str) -> Dict:
+        """
+        Reinstall a previously removed package
+
+        dataPreserved tells whether its data survived the uninstall (`-k`),
+        i.e. whether the user gets their settings back; None when unknown
+        """
+        try:
+            require_package_name(package_name)
+            self.require("install_existing")
+            # Checked before install-existing, which creates a fresh data directory
+            data_preserved = self._data_preserved(package_name)
+            # Reinstall for user 0; `cmd` only exists from Android 7
+            service = ["cmd", "package"] if self.supports("cmd_package") else ["pm"]
+            output = self._run_command(
+                [self.adb_path, "shell", *service, "install-existing", package_name]
+            )
+            
+            if "installed" in output.lower():
+                self._own_package_changes[package_name] = time.time()
+                if data_preserved is True:
+                    note = " with its previous data"
+                elif data_preserved is False:
+                    note = "; its data was deleted, so it starts fresh"
+                else:
+                    note = ""
+                return {
+                    "success": True,
+                    "message": f"Successfully reinstalled {package_name}{note}",
+                    "dataPreserved": data_preserved
+                }
+            else:
+                return self._reinstall_failure(package_name, output)
                 
+        except DeviceDisconnectedError as e:
+            return {
+                "success": False,
+                "deviceLost": True,
+                "message": str(e)
+            }
+        except UnsupportedFeatureError as e:
+            return {
+                "success": False,
+                "unsupported": True,
+                "message": str(e)
+            }
         except Exception as e:
             return {
                 "success": False,
                 "message": str(e)
             }
+
+    def _reinstall_failure(self, package_name: str, output: str) -> Dict:
+        """Failed reinstall result, with device-specific instructions when the failure is known"""
+        try:
+            sdk = self.get_sdk_level()
+        except ADBError:
+            sdk = None
+        remediation = remediation_for(output, self._get_property("ro.product.manufacturer"), sdk, package_name)
+        if remediation is None:
+            return {"success": False, "message": f"Failed to reinstall: {output.strip()}"}
+        return {
+            "success": False,
+            "message": f"Could not reinstall {package_name}: {remediation['summary']}",
+            "remediation": remediation,
+            "details": output.strip(),
+        }
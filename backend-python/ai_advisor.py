@@ -4,10 +4,16 @@ Handles AI-powered package analysis using Perplexity or OpenAI
 """
 import os
 import sys
+import copy
 import json
+import threading
 import requests
-from typing import Dict
+from typing import Any, Dict, Optional
 from dotenv import load_dotenv
+from usage_tracker import AIUsageTracker, BudgetExceededError
+from chat_context import compact_history
+import network
+import prompts
 
 
 def _get_base_dir():
@@ -23,58 +29,140 @@ load_dotenv(os.path.join(_base, '.env'))
 load_dotenv()  # also try CWD as fallback
 
 
+def local_analysis(package_name: str, safety_level: str, purpose: Optional[str] = None) -> Dict:
+    """
+    Analysis from the built-in safety rating, used instead of the AI in
+    offline mode; `purpose` is the OEM database's description when it lists the package
+    """
+    return {
+        "packageName": package_name,
+        "summary": f"Offline mode: rated {safety_level} by Debloat AI's built-in package list",
+        "purpose": purpose or "Unknown (AI analysis is disabled in offline mode)",
+        "dependencies": [],
+        "safeToRemove": safety_level == "Safe",
+        "riskCategory": safety_level,
+        "consequences": [],
+        "userReports": [],
+        "technicalDetails": "",
+        "bestCase": "",
+        "worstCase": "",
+        "offline": True,
+    }
+
+
 class AIAdvisor:
     """AI-powered package analysis"""
     
-    def __init__(self, provider: str = "perplexity"):
+    def __init__(self, provider: str = "perplexity", settings=None, context_builder=None):
         """
         Initialize AI advisor
         
         Args:
             provider: "perplexity" or "openai"
+            settings: SettingsManager supplying model and sampling parameters
+            context_builder: DeviceContextBuilder attaching live device facts to chats
         """
+        self._setup(provider, settings, context_builder)
+        self.api_key = os.getenv("PERPLEXITY_API_KEY" if provider == "perplexity" else "OPENAI_API_KEY")
+        
+        if not self.api_key:
+            print(f"[Warning] {provider.upper()}_API_KEY not found – AI features will be unavailable", file=sys.stderr)
+            self.api_key = None  # AI methods will return error gracefully
+    
+    @classmethod
+    def unconfigured(cls, settings=None, context_builder=None) -> "AIAdvisor":
+        """Perplexity advisor without an API key, for when the normal setup failed; AI methods fail gracefully"""
+        advisor = cls.__new__(cls)
+        advisor._setup("perplexity", settings, context_builder)
+        return advisor
+
+    def _setup(self, provider: str, settings, context_builder):
+        """Everything but the API key, which is left unset"""
         self.provider = provider
+        self.settings = settings
+        self.context_builder = context_builder
+        self.usage = AIUsageTracker()
+        # (model, package) -> {"done": Event, "result", "error"} of an analysis being requested
+        self._in_flight: Dict[tuple, Dict] = {}
+        self._in_flight_lock = threading.Lock()
+        self.api_key = None
         
         if provider == "perplexity":
-            self.api_key = os.getenv("PERPLEXITY_API_KEY")
             self.api_url = "https://api.perplexity.ai/chat/completions"
             self.model = "sonar"  # Updated model name for Perplexity API
         else:  # openai
-            self.api_key = os.getenv("OPENAI_API_KEY")
             self.api_url = "https://api.openai.com/v1/chat/completions"
             self.model = "gpt-4-turbo-preview"
-        
-        if not self.api_key:
-            print(f"[Warning] {provider.upper()}_API_KEY not found – AI features will be unavailable", file=sys.stderr)
-            self.api_key = None  # AI methods will return error gracefully
+
+    def _ai_setting(self, key: str, default: Any) -> Any:
+        """Read an `ai.*` setting, falling back to the built-in default"""
+        if self.settings is None:
+            return default
+        return self.settings.get("ai", key)
+    
+    def _prompt_context(self, **facts) -> prompts.PromptContext:
+        """Prompt sections to include for the configured answer style and language"""
+        locale = self.settings.get("display", "locale") if self.settings is not None else "en"
+        return prompts.PromptContext(plain_language=self._ai_setting("plainLanguage", False),
+                                     expert=self._ai_setting("expertMode", False), locale=locale, **facts)
+    
+    def _current_model(self) -> str:
+        """Model for the next request (user-selectable for Perplexity)"""
+        if self.provider == "perplexity":
+            return self._ai_setting("model", self.model)
+        return self.model
+    
+    def endpoint_url(self) -> str:
+        """Chat completions URL: the configured `ai.baseUrl` (a gateway) or the provider's API"""
+        base = (self._ai_setting("baseUrl", "") or "").rstrip("/")
+        if not base:
+            return self.api_url
+        return base if base.endswith("/chat/completions") else f"{base}/chat/completions"
+    
+    def _endpoint_model(self, model: str) -> str:
+        """Name of `model` at the endpoint, e.g. "perplexity/sonar" on OpenRouter (`ai.modelAliases`)"""
+        return (self._ai_setting("modelAliases", {}) or {}).get(model, model)
     
     def analyze_package(self, package_name: str) -> Dict:
-        """Analyze an Android package and return safety information"""
+        """
+        Analyze an Android package and return safety information
+
+        A call for a package whose analysis is already being requested (the
+        analyze button during a background revalidation) waits for that
+        request and gets the same result instead of paying for another
+        """
+        key = (self._current_model(), package_name)
+        with self._in_flight_lock:
+            request = self._in_flight.get(key)
+            owner = request is None
+            if owner:
+                request = self._in_flight[key] = {"done": threading.Event(), "result": None, "error": None}
+        if not owner:
+            request["done"].wait()
+        else:
+            try:
+                request["result"] = self._analyze_package(package_name)
+            except Exception as e:
+                request["error"] = e
+            finally:
+                with self._in_flight_lock:
+                    del self._in_flight[key]
+                request["done"].set()
+        if request["error"] is not None:
+            raise request["error"]
+        # Callers annotate their result (e.g. a conflict with the rating), so each gets its own copy
+        return copy.deepcopy(request["result"])
+
+    def _analyze_package(self, package_name: str) -> Dict:
         if not self.api_key:
             return {"error": "API key not configured. Add PERPLEXITY_API_KEY to .env file.", "safetyLevel": "unknown", "appName": package_name, "description": "AI analysis unavailable", "recommendation": "Configure API key to enable AI analysis"}
         
-        prompt = f"""You are an Android package analysis expert. Analyze package: {package_name}
-
-Return ONLY valid JSON (no markdown, no explanation):
-{{
-  "packageName": "{package_name}",
-  "summary": "Brief description in plain English",
-  "purpose": "What this package does",
-  "dependencies": ["list of packages that might depend on this"],
-  "safeToRemove": true/false,
-  "riskCategory": "Safe/Caution/Expert/Dangerous",
-  "consequences": ["what happens if removed"],
-  "userReports": ["common user experiences"],
-  "technicalDetails": "Technical information",
-  "bestCase": "Best case scenario if removed",
-  "worstCase": "Worst case scenario if removed"
-}}
-
-Risk categories:
-- Safe: Third-party apps, easily reinstallable
-- Caution: OEM apps, may affect minor features
-- Expert: May break functionality
-- Dangerous: Critical system components"""
+        try:
+            self.usage.check_budget()
+        except BudgetExceededError as e:
+            return {"error": str(e), "safetyLevel": "unknown", "appName": package_name, "description": "AI analysis unavailable", "recommendation": "Increase the monthly AI budget to continue"}
+        
+        prompt = prompts.analysis_prompt(package_name, self._prompt_context())
 
         try:
             headers = {
@@ -97,7 +185,7 @@ Risk categories:
                 messages = [
                     {
                         "role": "system",
-                        "content": "You are an Android debloating expert. Always respond with valid JSON only."
+                        "content": prompts.ANALYSIS_SYSTEM
                     },
                     {
                         "role": "user",
@@ -105,23 +193,26 @@ Risk categories:
                     }
                 ]
             
+            model = self._current_model()
             payload = {
-                "model": self.model,
+                "model": self._endpoint_model(model),
                 "messages": messages,
-                "temperature": 0.2,
-                "max_tokens": 1500
+                "temperature": self._ai_setting("analysisTemperature", 0.2),
+                "max_tokens": self._ai_setting("analysisMaxTokens", 1500)
             }
             
             # Add provider-specific parameters
             if self.provider == "perplexity":
                 payload.update({
-                    "return_citations": False,
-                    "return_images": False,
-                    "search_recency_filter": "month"
+                    "return_citations": True,
+                    "return_images": False
                 })
+                recency = self._ai_setting("searchRecency", "month")
+                if recency != "none":
+                    payload["search_recency_filter"] = recency
             
-            response = requests.post(
-                self.api_url,
+            response = network.post(
+                self.endpoint_url(),
                 headers=headers,
                 json=payload,
                 timeout=30
@@ -134,6 +225,7 @@ Risk categories:
             
             response.raise_for_status()
             data = response.json()
+            self.usage.record(self.provider, model, data.get("usage"))
             
             # Extract content
             content = data["choices"][0]["message"]["content"]
@@ -150,23 +242,58 @@ Risk categories:
             
             # Parse JSON
             analysis = json.loads(content)
+            # Sources the answer was based on (Perplexity web search)
+            citations = data.get("citations") or [r.get("url") for r in data.get("search_results") or []]
+            if isinstance(analysis, dict) and citations:
+                analysis["citations"] = [url for url in citations if isinstance(url, str)]
             return analysis
             
-        except requests.exceptions.RequestException as e:
-            raise Exception(f"API request failed: {str(e)}")
+        except (requests.exceptions.RequestException, network.OfflineModeError) as e:
+            raise Exception(f"API request failed: {network.describe_error(e)}")
         except json.JSONDecodeError as e:
             raise Exception(f"Failed to parse AI response: {str(e)}")
         except Exception as e:
             raise Exception(f"Analysis failed: {str(e)}")
     
-    def chat(self, message: str, history: list = None) -> str:
-        """Chat with AI about debloating"""
+    def chat(self, message: str, history: list = None) -> Dict:
+        """
+        Chat with AI about debloating
+        
+        Returns:
+            {"content": str, "relatedQuestions": [str]}
+        """
+        if network.is_offline():
+            return {"content": f"AI chat unavailable. {network.OFFLINE_MESSAGE}.", "relatedQuestions": []}
+        
         if not self.api_key:
-            return "AI chat unavailable. Please add your PERPLEXITY_API_KEY to the .env file next to backend.exe, then restart the app."
+            return {"content": "AI chat unavailable. Please add your PERPLEXITY_API_KEY to the .env file next to backend.exe, then restart the app.", "relatedQuestions": []}
         
         if history is None:
             history = []
         
+        try:
+            self.usage.check_budget()
+        except BudgetExceededError as e:
+            return {"content": str(e), "relatedQuestions": []}
+        
+        summarize = self._summarize_with_model if self._ai_setting("compactionMode", "local") == "model" else None
+        history = compact_history(
+            history,
+            limit=self._ai_setting("historyLimit", 20),
+            keep_recent=self._ai_setting("historyKeepRecent", 10),
+            summarize=summarize
+        )["messages"]
+        
+        resolved = None
+        if self.context_builder is not None and self._ai_setting("resolvePackageMentions", True):
+            resolved = self.context_builder.resolve_message(message)
+
+        device_context = None
+        if self.context_builder is not None and self._ai_setting("includeDeviceContext", True):
+            device_context = self.context_builder.build(history, message, include_message=resolved is None)
+
+        context = self._prompt_context(device_context=device_context, package_facts=resolved)
+        
         try:
             headers = {
                 "Authorization": f"Bearer {self.api_key}",
@@ -181,16 +308,14 @@ Risk categories:
                 messages.extend(history)
                 messages.append({
                     "role": "user",
-                    "content": message
+                    "content": prompts.chat_user_message(message, context, with_instructions=True)
                 })
             else:
                 # OpenAI supports system role
                 messages = [
                     {
                         "role": "system",
-                        "content": """You are a helpful Android debloating expert assistant. 
-Help users understand which apps are safe to remove and answer their questions about Android packages.
-Be concise and friendly."""
+                        "content": prompts.chat_system_prompt(context)
                     }
                 ]
                 # Add history
@@ -198,24 +323,29 @@ Be concise and friendly."""
                 # Add current message
                 messages.append({
                     "role": "user",
-                    "content": message
+                    "content": prompts.chat_user_message(message, context)
                 })
             
+            model = self._current_model()
             payload = {
-                "model": self.model,
+                "model": self._endpoint_model(model),
                 "messages": messages,
-                "temperature": 0.7,
-                "max_tokens": 800
+                "temperature": self._ai_setting("chatTemperature", 0.7),
+                "max_tokens": self._ai_setting("chatMaxTokens", 800)
             }
             
             if self.provider == "perplexity":
                 payload.update({
                     "return_citations": False,
-                    "return_images": False
+                    "return_images": False,
+                    "return_related_questions": True
                 })
+                recency = self._ai_setting("searchRecency", "month")
+                if recency != "none":
+                    payload["search_recency_filter"] = recency
             
-            response = requests.post(
-                self.api_url,
+            response = network.post(
+                self.endpoint_url(),
                 headers=headers,
                 json=payload,
                 timeout=30
@@ -223,11 +353,41 @@ Be concise and friendly."""
             
             response.raise_for_status()
             data = response.json()
+            self.usage.record(self.provider, model, data.get("usage"))
             
-            return data["choices"][0]["message"]["content"]
+            related = data.get("related_questions") or []
+            return {
+                "content": data["choices"][0]["message"]["content"],
+                "relatedQuestions": [q.strip() for q in related if isinstance(q, str) and q.strip()][:5]
+            }
             
         except Exception as e:
-            raise Exception(f"Chat failed: {str(e)}")
+            raise Exception(f"Chat failed: {network.describe_error(e)}")
+    
+    def _summarize_with_model(self, messages: list) -> str:
+        """Summarize older chat turns with a short, cheap model call"""
+        transcript = "\n".join(f"{m['role']}: {m['content']}" for m in messages)
+        model = "sonar" if self.provider == "perplexity" else self.model
+        payload = {
+            "model": self._endpoint_model(model),
+            "messages": [{
+                "role": "user",
+                "content": ("Summarize this Android debloating conversation in at most 8 short bullet points. "
+                            "Keep package names, device details and decisions that were made.\n\n" + transcript)
+            }],
+            "temperature": 0.2,
+            "max_tokens": 300
+        }
+        response = network.post(
+            self.endpoint_url(),
+            headers={"Authorization": f"Bearer {self.api_key}", "Content-Type": "application/json"},
+            json=payload,
+            timeout=30
+        )
+        response.raise_for_status()
+        data = response.json()
+        self.usage.record(self.provider, model, data.get("usage"))
+        return data["choices"][0]["message"]["content"]
 
 
 # Example usage
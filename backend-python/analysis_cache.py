@@ -0,0 +1,110 @@
+"""
+Analysis Cache Module
+Persists the verdict of every AI package analysis so package listings can
+show it (as `aiRisk`) without another click or API call
+"""
+import json
+import threading
+import time
+from pathlib import Path
+from typing import Dict, List, Optional
+from cache_policy import CacheStats, cache_report, is_stale
+from guardrails import analysis_conflict
+
+
+RISK_CATEGORIES = ("Safe", "Caution", "Expert", "Dangerous")
+
+# Sources kept per verdict
+MAX_CITATIONS = 5
+
+
+def _confidence(value) -> Optional[float]:
+    """The model's self-reported confidence clamped to 0..1; None when missing or not a number"""
+    if isinstance(value, bool) or not isinstance(value, (int, float)):
+        return None
+    return min(1.0, max(0.0, float(value)))
+
+
+def risk_from_analysis(analysis: Dict, model: Optional[str] = None) -> Optional[Dict]:
+    """The aiRisk entry for an analyze_package result; None for failed analyses"""
+    if not isinstance(analysis, dict) or analysis.get("error") or analysis.get("offline"):
+        return None
+    category = analysis.get("riskCategory")
+    if category not in RISK_CATEGORIES:
+        return None
+    return {
+        "riskCategory": category,
+        "safeToRemove": bool(analysis.get("safeToRemove")),
+        "summary": analysis.get("summary") or "",
+        "analyzedAt": time.time(),
+        "model": model,
+        "confidence": _confidence(analysis.get("confidence")),
+        "citations": [url for url in analysis.get("citations") or [] if isinstance(url, str)][:MAX_CITATIONS],
+    }
+
+
+class AnalysisCache:
+    """AI verdicts per package, shared by all devices"""
+
+    def __init__(self, data_dir: str = None):
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.path = Path(data_dir) / "analyses.json"
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        self._lock = threading.Lock()
+        try:
+            with open(self.path, 'r', encoding='utf-8') as f:
+                self._risks: Dict[str, Dict] = json.load(f)
+        except (OSError, json.JSONDecodeError):
+            self._risks = {}
+        self.stats = CacheStats()
+
+    def record(self, package_name: str, analysis: Dict, model: Optional[str] = None) -> Optional[Dict]:
+        """Remember the verdict of a successful analysis; returns the stored aiRisk"""
+        risk = risk_from_analysis(analysis, model)
+        if risk is None or not package_name:
+            return None
+        with self._lock:
+            self._risks[package_name] = risk
+            with open(self.path, 'w', encoding='utf-8') as f:
+                json.dump(self._risks, f, indent=2, ensure_ascii=False)
+        return {**risk, "stale": False}
+
+    def get(self, package_name: str) -> Optional[Dict]:
+        with self._lock:
+            risk = self._risks.get(package_name)
+        if risk:
+            self.stats.hit()
+        else:
+            self.stats.miss()
+        return {**risk, "stale": is_stale("analysis", risk["analyzedAt"])} if risk else None
+
+    def packages(self) -> List[str]:
+        """Every package with a stored verdict, sorted"""
+        with self._lock:
+            return sorted(self._risks)
+
+    def apply(self, packages: List[Dict]) -> List[Dict]:
+        """
+        Attach the cached aiRisk to every previously analyzed package in a
+        listing; verdicts past the analysis max age get `stale: true`
+        """
+        found = 0
+        with self._lock:
+            for pkg in packages:
+                risk = self._risks.get(pkg["packageName"])
+                if risk:
+                    found += 1
+                    pkg["aiRisk"] = {**risk, "stale": is_stale("analysis", risk["analyzedAt"])}
+                    conflict = analysis_conflict(risk, pkg.get("safetyLevel"))
+                    if conflict:
+                        pkg["aiRisk"]["conflict"] = conflict
+        self.stats.hit(found)
+        self.stats.miss(len(packages) - found)
+        return packages
+
+    def cache_diagnostics(self) -> Dict:
+        """get_cache_diagnostics line of the AI verdicts"""
+        with self._lock:
+            risks = dict(self._risks)
+        return cache_report("analysis", self.stats, risks, [r.get("analyzedAt") for r in risks.values()], len(risks))
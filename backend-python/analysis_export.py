@@ -0,0 +1,110 @@
+"""
+Analysis Export Module
+Renders AI verdicts next to the built-in safety ratings as a Markdown or CSV
+digest to post on XDA or Reddit when asking for a second opinion. Only the
+device model and Android version are included, never the serial
+"""
+import csv
+import io
+from datetime import datetime
+from typing import Callable, Dict, List, Optional
+
+
+EXPORT_FORMATS = ("markdown", "csv")
+EXPORT_EXTENSIONS = {"markdown": "md", "csv": "csv"}
+
+CSV_COLUMNS = ["packageName", "localRating", "aiRiskCategory", "safeToRemove", "confidence",
+               "summary", "citations", "analyzedAt", "model", "stale"]
+
+
+def build_rows(packages: List[str], verdict_for: Callable[[str], Optional[Dict]],
+               rating_for: Callable[[str], str]) -> List[Dict]:
+    """One row per package: built-in rating plus the stored AI verdict, if any"""
+    rows = []
+    for package in dict.fromkeys(packages):
+        verdict = verdict_for(package) or {}
+        rows.append({
+            "packageName": package,
+            "localRating": rating_for(package),
+            "aiRiskCategory": verdict.get("riskCategory"),
+            "safeToRemove": verdict.get("safeToRemove"),
+            "confidence": verdict.get("confidence"),
+            "summary": verdict.get("summary") or "",
+            "citations": verdict.get("citations") or [],
+            "analyzedAt": verdict.get("analyzedAt"),
+            "model": verdict.get("model"),
+            "stale": bool(verdict.get("stale")),
+        })
+    return rows
+
+
+def _date(timestamp: Optional[float]) -> str:
+    return datetime.fromtimestamp(timestamp).strftime("%Y-%m-%d") if timestamp else ""
+
+
+def _confidence(value: Optional[float]) -> str:
+    return f"{round(value * 100)}%" if value is not None else "n/a"
+
+
+def _cell(text: str) -> str:
+    """Table cell text: pipes escaped, newlines flattened"""
+    return " ".join(str(text).split()).replace("|", "\\|")
+
+
+def render_markdown(rows: List[Dict], device_info: Optional[Dict] = None) -> str:
+    """Forum-ready Markdown: a summary table, then each package's summary and sources"""
+    lines = ["## Debloat AI package review", ""]
+    if device_info:
+        lines.append(f"**Device:** {device_info.get('manufacturer', '')} {device_info.get('model', '')}, "
+                     f"Android {device_info.get('androidVersion')}  ")
+    lines += [
+        f"**Generated:** {datetime.now().strftime('%Y-%m-%d')}",
+        "",
+        "*Local* is the app's built-in rating; *AI* is the last AI analysis with its self-reported confidence. "
+        "Second opinions welcome.",
+        "",
+        "| Package | Local | AI | Safe to remove | Confidence |",
+        "|---|---|---|---|---|",
+    ]
+    for row in rows:
+        removable = {True: "yes", False: "no"}.get(row["safeToRemove"], "")
+        lines.append(f"| `{row['packageName']}` | {row['localRating']} | {row['aiRiskCategory'] or 'not analyzed'} "
+                     f"| {removable} | {_confidence(row['confidence']) if row['aiRiskCategory'] else ''} |")
+
+    for row in rows:
+        if not row["aiRiskCategory"]:
+            continue
+        lines += ["", f"### `{row['packageName']}`", ""]
+        if row["summary"]:
+            lines += [_cell(row["summary"]), ""]
+        analyzed = f"Analyzed {_date(row['analyzedAt'])}" + (f" with {row['model']}" if row["model"] else "")
+        lines.append(analyzed + (" (older than the refresh interval)" if row["stale"] else ""))
+        if row["citations"]:
+            lines += ["", "Sources:"] + [f"{i}. <{url}>" for i, url in enumerate(row["citations"], 1)]
+    return "\n".join(lines) + "\n"
+
+
+def render_csv(rows: List[Dict]) -> str:
+    """One line per package; citations are space-separated"""
+    out = io.StringIO()
+    writer = csv.DictWriter(out, fieldnames=CSV_COLUMNS, lineterminator="\n")
+    writer.writeheader()
+    for row in rows:
+        writer.writerow({
+            **row,
+            "safeToRemove": "" if row["safeToRemove"] is None else str(row["safeToRemove"]).lower(),
+            "confidence": "" if row["confidence"] is None else row["confidence"],
+            "citations": " ".join(row["citations"]),
+            "analyzedAt": _date(row["analyzedAt"]),
+            "model": row["model"] or "",
+            "aiRiskCategory": row["aiRiskCategory"] or "",
+            "stale": str(row["stale"]).lower(),
+        })
+    return out.getvalue()
+
+
+def render(rows: List[Dict], export_format: str, device_info: Optional[Dict] = None) -> str:
+    """The digest in one of EXPORT_FORMATS"""
+    if export_format == "csv":
+        return render_csv(rows)
+    return render_markdown(rows, device_info)
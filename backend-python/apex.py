@@ -0,0 +1,49 @@
+"""
+APEX Module
+APEX modules are the Mainline system components (time zone data, media
+codecs, networking) that Google updates through the Play Store outside of
+firmware updates. Neither the modules nor the apps shipped inside them can
+be uninstalled, so they are listed on their own and marked non-removable
+instead of failing with an internal error at removal time
+"""
+import re
+from typing import Dict, List, Optional
+
+
+# Android 10+; --show-versioncode adds " versionCode:331011000"
+APEX_LIST_COMMAND = "pm list packages --apex-only --show-versioncode"
+
+APEX_LINE_PATTERN = re.compile(r'^package:([\w.]+)(?:\s+versionCode:(\d+))?', re.MULTILINE)
+
+# "/apex/com.android.tethering/priv-app/Tethering/Tethering.apk" -> "com.android.tethering"
+APEX_PATH_PATTERN = re.compile(r'^/apex/([\w.]+?)(?:@\d+)?/')
+
+REMOVAL_MESSAGE = ("{package} is part of the {module} system module (APEX); "
+                   "system modules are updated by Google and cannot be removed")
+
+
+def parse_apex_list(output: str) -> List[Dict]:
+    """[{"packageName", "versionCode"}] from APEX_LIST_COMMAND"""
+    return [
+        {"packageName": name, "versionCode": int(version) if version else None}
+        for name, version in APEX_LINE_PATTERN.findall(output)
+    ]
+
+
+def module_of_path(path: Optional[str]) -> Optional[str]:
+    """APEX module an APK's code path lies in, None outside /apex"""
+    match = APEX_PATH_PATTERN.match(path or "")
+    return match.group(1) if match else None
+
+
+def tag_apex(packages: List[Dict], modules: Dict[str, str]) -> List[Dict]:
+    """
+    Mark APEX modules and the apps inside them (package -> module) as
+    non-removable in a listing
+    """
+    for pkg in packages:
+        module = modules.get(pkg["packageName"])
+        if module:
+            pkg["apexModule"] = module
+            pkg["removable"] = False
+    return packages
@@ -1,30 +1,242 @@
 """
 API Type Definitions for Electron IPC Communication
-Replaces Tauri invoke() calls with Electron IPC
+Single source of truth for the data shapes shared by the backend and the
+frontend; frontend/src/types.generated.ts is generated from this module by
+generate_ts_types.py
 """
-from typing import TypedDict, List, Dict, Optional, Any
+from typing import Any, Dict, List, Literal, NotRequired, Optional, TypedDict
+
+from compatibility import FEATURE_MIN_SDK
+from device_class import DEVICE_CLASSES
+from events import EVENT_KINDS
+from provenance import INSTALL_SOURCE_LABELS, PARTITIONS
+
+
+SafetyLevel = Literal["Safe", "Caution", "Expert", "Dangerous"]
+
+# Built from the backend tables so new features and event kinds reach the frontend
+DeviceFeature = Literal[tuple(FEATURE_MIN_SDK)]
+DeviceClass = Literal[DEVICE_CLASSES]
+BackendEventKind = Literal[tuple(EVENT_KINDS)]
+InstallSource = Literal[tuple(INSTALL_SOURCE_LABELS)]
+Partition = Literal[PARTITIONS]
 
-# TypeScript types for reference (not used in Python)
 
 class DeviceInfo(TypedDict):
+    name: str
     serial: str
-    model: str
-    product: str
-    manufacturer: str
-    androidVersion: str
+    nickname: NotRequired[Optional[str]]
+    model: NotRequired[str]
+    product: NotRequired[str]
+    manufacturer: NotRequired[str]
+    androidVersion: NotRequired[str]
+    batteryPercentage: NotRequired[Optional[int]]
+    storageAvailable: NotRequired[Optional[str]]
     state: str
+    capabilities: NotRequired[Dict[DeviceFeature, bool]]
+    deviceClass: NotRequired[DeviceClass]
+    # Fire OS version on Amazon devices, None elsewhere (fire_os.py)
+    fireOs: NotRequired[Optional[str]]
+
+
+class RatingConflict(TypedDict):
+    """An AI verdict contradicting the local rating (guardrails.analysis_conflict)"""
+    # aiMoreLenient: AI says removable, local Expert/Dangerous; aiStricter: AI Expert/Dangerous, local Safe
+    kind: Literal["aiMoreLenient", "aiStricter"]
+    localRating: SafetyLevel
+    aiRiskCategory: SafetyLevel
+    aiSafeToRemove: bool
+    # The rating the app applies to removal warnings; always the local one
+    enforced: Literal["local"]
+    message: str
+
+
+class AIRisk(TypedDict):
+    """Verdict of the last AI analysis of a package (analysis_cache.py)"""
+    riskCategory: SafetyLevel
+    safeToRemove: bool
+    summary: str
+    analyzedAt: float
+    model: Optional[str]
+    # Self-reported 0..1 and the sources the verdict cites; absent on verdicts stored before they were kept
+    confidence: NotRequired[Optional[float]]
+    citations: NotRequired[List[str]]
+    # Older than the analysis max age (cache_policy.py)
+    stale: NotRequired[bool]
+    # Set in listings when the verdict contradicts the package's safetyLevel
+    conflict: NotRequired[RatingConflict]
+
 
 class Package(TypedDict):
-    name: str
-    type: str
+    packageName: str
+    appName: str
+    safetyLevel: SafetyLevel
+    labelSource: NotRequired[Literal["device"]]
+    isSystem: NotRequired[bool]
+    installer: NotRequired[Optional[str]]
+    installSource: NotRequired[InstallSource]
+    partition: NotRequired[Optional[Partition]]
+    # Signed with the platform key (signatures.py); only present when true
+    platformSigned: NotRequired[bool]
+    # Runtime resource overlay: the package it restyles (overlays.py); overlays are never removable
+    overlayTarget: NotRequired[str]
+    # APEX system module the package is, or ships inside (apex.py); never removable
+    apexModule: NotRequired[str]
+    sizeBytes: NotRequired[Optional[int]]
+    lastUsed: NotRequired[Optional[float]]
+    removable: NotRequired[bool]
+    managedReason: NotRequired[str]
+    aiRisk: NotRequired[AIRisk]
+
+
+class CompactPackageList(TypedDict):
+    """Package listing in the columnar wire format of package_wire.py"""
+    format: Literal["compact"]
+    version: int
+    fields: List[str]
+    stringFields: List[str]
+    strings: List[str]
+    rows: List[List[Any]]
+
 
 class BackupInfo(TypedDict):
     name: str
+    serial: Optional[str]
+    nickname: Optional[str]
     path: str
     timestamp: str
     packageCount: int
-    deviceInfo: dict
+    deviceInfo: Any
+    full: bool
+    hasData: bool
+
+
+class ZramInfo(TypedDict):
+    origDataKb: int
+    comprDataKb: int
+    memUsedKb: int
+    diskSizeKb: Optional[int]
+    compressionRatio: Optional[float]
+
+
+class MemoryInfo(TypedDict):
+    totalKb: int
+    availableKb: int
+    usedKb: int
+    usagePercent: Optional[float]
+    swapTotalKb: int
+    swapFreeKb: int
+    swapUsedKb: int
+    swapUsagePercent: Optional[float]
+    zram: Optional[ZramInfo]
+    # Locale-formatted sizes and percents, keyed without the unit suffix
+    formatted: Dict[str, Optional[str]]
+
+
+class StorageVolume(TypedDict):
+    filesystem: str
+    mountPoint: str
+    totalKb: int
+    usedKb: int
+    availableKb: int
+    usagePercent: Optional[float]
+    # Locale-formatted sizes and percent, keyed without the unit suffix ("total": "109.9 GB")
+    formatted: Dict[str, Optional[str]]
+
+
+class StorageInfo(TypedDict):
+    internal: StorageVolume
+    adoptable: List[StorageVolume]
+    adoptableTotalKb: int
+    adoptableAvailableKb: int
+
+
+class ThermalSensor(TypedDict):
+    name: str
+    type: str
+    valueC: float
+    status: Optional[int]
+
+
+class TemperatureInfo(TypedDict):
+    temperatureC: float
+    source: str
+    sensors: List[ThermalSensor]
+
+
+class PackageServices(TypedDict):
+    packageName: str
+    count: int
+    services: List[str]
+
+
+class ServicesInfo(TypedDict):
+    count: int
+    packageCount: int
+    byPackage: List[PackageServices]
+
+
+class PackageNotifications(TypedDict):
+    packageName: str
+    # Posted since boot (usage stats) and currently shown
+    posted: int
+    active: int
+
+
+class NotificationsInfo(TypedDict):
+    posted: int
+    active: int
+    packageCount: int
+    byPackage: List[PackageNotifications]
+
+
+class PowerInfo(TypedDict):
+    charging: bool
+    # "ac" | "usb" | "wireless" | "dock"; None on battery
+    plugType: Optional[str]
+    batteryLevel: Optional[int]
+    # Partial wake locks held now
+    wakeLocks: int
+    # Since the last full charge; refreshed every few minutes
+    screenOnSeconds: Optional[int]
+
+
+class SystemHealth(TypedDict):
+    serial: str
+    timestamp: float
+    cpuUsage: Optional[float]
+    memory: Optional[MemoryInfo]
+    storage: Optional[StorageInfo]
+    temperature: Optional[TemperatureInfo]
+    services: Optional[ServicesInfo]
+    notifications: Optional[NotificationsInfo]
+    power: Optional[PowerInfo]
+    # Cached snapshot older than the health max age; a fresh one is on its way
+    stale: NotRequired[bool]
+
 
-# This file is just for reference
-# The actual API is in frontend/src/utils/api.ts for TypeScript
-# And electron/preload.js for the bridge
+# Exported to TypeScript in this order
+EXPORTED_TYPES = [
+    ("SafetyLevel", SafetyLevel),
+    ("DeviceFeature", DeviceFeature),
+    ("DeviceClass", DeviceClass),
+    ("BackendEventKind", BackendEventKind),
+    ("DeviceInfo", DeviceInfo),
+    ("RatingConflict", RatingConflict),
+    ("AIRisk", AIRisk),
+    ("Package", Package),
+    ("CompactPackageList", CompactPackageList),
+    ("BackupInfo", BackupInfo),
+    ("ZramInfo", ZramInfo),
+    ("MemoryInfo", MemoryInfo),
+    ("StorageVolume", StorageVolume),
+    ("StorageInfo", StorageInfo),
+    ("ThermalSensor", ThermalSensor),
+    ("TemperatureInfo", TemperatureInfo),
+    ("PackageServices", PackageServices),
+    ("ServicesInfo", ServicesInfo),
+    ("PackageNotifications", PackageNotifications),
+    ("NotificationsInfo", NotificationsInfo),
+    ("PowerInfo", PowerInfo),
+    ("SystemHealth", SystemHealth),
+]
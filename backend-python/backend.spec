@@ -17,6 +17,8 @@ a = Analysis(
     binaries=[],
     datas=[
         # .env.example is now in the project root, not bundled with backend
+        # Per-OEM safety ratings, read from sys._MEIPASS/oem_data (see oem_database.py)
+        (os.path.join(src, 'oem_data'), 'oem_data'),
     ],
     hiddenimports=[
         'requests',
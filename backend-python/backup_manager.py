@@ -4,16 +4,111 @@ Handles backup and restore of uninstalled packages
 """
 import json
 import os
-from datetime import datetime
-from typing import List, Dict
+import shutil
+import tempfile
+import threading
+import uuid
+import zipfile
+from datetime import datetime, timedelta
+from typing import Callable, List, Dict, Optional, Set, Tuple
 from pathlib import Path
+from adb_operations import ADBError
+import backup_mapping
+from events import EmitFn, no_emit
+from stay_awake import stay_awake_during
+from storage_guard import LOCAL_MARGIN_BYTES, StorageGuard
+import formatting
+
+
+# Per-package outcomes of restore_backup
+RESTORE_STATUSES = ("restored", "alreadyInstalled", "notAvailable", "failed")
+
+# Seconds between background retention runs
+MAINTENANCE_INTERVAL = 6 * 3600
+
+# Full backups are zips holding this manifest plus apks/<package>/<file>.apk
+ZIP_MANIFEST = "manifest.json"
+
+# Backups are JSON package lists or full backup zips
+BACKUP_SUFFIXES = (".json", ".zip")
+
+# `adb backup` app data archives, next to their JSON backup
+DATA_BACKUP_SUFFIXES = (".ab",)
+
+
+def file_in(directory: Path, name, suffixes: Tuple[str, ...]) -> Optional[Path]:
+    """directory / name for a bare file name ending in one of `suffixes`, None for anything else"""
+    if not isinstance(name, str) or not name.endswith(suffixes) or "\\" in name or Path(name).name != name:
+        return None
+    path = (directory / name).resolve()
+    return path if path.parent == directory.resolve() else None
+
+
+def parse_package_list(output: str) -> Set[str]:
+    """Package names from `pm list packages` output"""
+    return {line[len("package:"):].strip() for line in output.split('\n') if line.startswith("package:")}
+
+
+def group_backups(backups: List[Dict]) -> List[Dict]:
+    """
+    Group backup listings by device serial, most recently backed-up device first.
+
+    Backups made before serials were recorded form a group with serial None.
+    """
+    groups: Dict[Optional[str], Dict] = {}
+    for backup in backups:  # newest first, so the first entry carries the latest device info
+        group = groups.setdefault(backup["serial"], {
+            "serial": backup["serial"],
+            "nickname": backup.get("nickname"),
+            "deviceInfo": backup["deviceInfo"],
+            "latestTimestamp": backup["timestamp"],
+            "backups": [],
+        })
+        group["backups"].append(backup)
+    return list(groups.values())
+
+
+def select_expired(backups: List[Dict], keep_per_device: int, max_age_days: int,
+                   now: datetime = None) -> List[Dict]:
+    """
+    Backups a retention policy removes, each with a `reason`.
+
+    keep_per_device keeps that many newest backups per device serial and
+    max_age_days drops anything older; 0 disables either rule.
+    """
+    now = now or datetime.now()
+    expired = []
+    for group in group_backups(backups):
+        for index, backup in enumerate(group["backups"]):
+            try:
+                age_days = (now - datetime.fromisoformat(backup["timestamp"])).days
+            except (TypeError, ValueError):
+                age_days = None
+            if keep_per_device and index >= keep_per_device:
+                reason = f"More than {keep_per_device} backups for this device"
+            elif max_age_days and age_days is not None and age_days > max_age_days:
+                reason = f"Older than {max_age_days} days"
+            else:
+                continue
+            expired.append({**backup, "reason": reason})
+    return expired
 
 
 class BackupManager:
     """Manage backups of uninstalled packages"""
     
-    def __init__(self, backup_dir: str = None):
+    def __init__(self, backup_dir: str = None, adb_operations=None, emit_event: EmitFn = None,
+                 nickname_for: Callable[[Optional[str]], Optional[str]] = None, stay_awake=None):
         """Initialize backup manager"""
+        self.adb = adb_operations
+        self.storage_guard = StorageGuard(adb_operations)
+        self.stay_awake = stay_awake
+        self.emit_event = emit_event or no_emit
+        # serial -> user-chosen device name (settings devices.nicknames)
+        self.nickname_for = nickname_for or (lambda serial: None)
+        self._full_job: Optional[Dict] = None
+        self._job_lock = threading.Lock()
+        self._maintenance_stop = threading.Event()
         if backup_dir is None:
             # Use user's documents folder
             home = Path.home()
@@ -24,29 +119,77 @@ class BackupManager:
         # Create backup directory if it doesn't exist
         self.backup_dir.mkdir(parents=True, exist_ok=True)
     
-    def create_backup(self, packages: List[str], device_info: Dict = None) -> Dict:
-        """Create a backup of packages"""
+    def _serial(self) -> Optional[str]:
+        """Serial of the connected device, or None without one"""
+        if self.adb is None:
+            return None
+        try:
+            return self.adb.get_serial()
+        except ADBError:
+            return None
+    
+    def _check_space(self, packages: List[str], export_apks: bool, include_data: bool) -> Dict:
+        """Free space check before a backup (storage_guard); APKs are only exported for third-party packages"""
+        apk_packages = []
+        if export_apks:
+            try:
+                system = parse_package_list(self.adb.shell("pm list packages -s", timeout=30))
+            except ADBError as e:
+                return {"success": False, "message": str(e)}
+            apk_packages = [package for package in packages if package not in system]
+        return self.storage_guard.check_backup(self.backup_dir, packages, apk_packages, include_data)
+
+    def create_backup(self, packages: List[str], device_info: Dict = None, include_data: bool = False) -> Dict:
+        """
+        Create a backup of packages
+
+        include_data also saves their app data with `adb backup` into a
+        companion `.ab` file (Android 11 and older, confirmed on the device),
+        refused when it would not fit on this computer
+        """
+        space = None
+        if include_data:
+            space = self._check_space(packages, export_apks=False, include_data=True)
+            if not space["success"]:
+                return {"success": False, "insufficientSpace": space.get("insufficientSpace", False),
+                        "space": space, "message": space["message"]}
         try:
             timestamp = datetime.now().strftime("%Y%m%d_%H%M%S")
             backup_name = f"backup_{timestamp}.json"
             backup_path = self.backup_dir / backup_name
             
+            data_backup = None
+            if include_data:
+                data_path = backup_path.with_suffix(".ab")
+                data_backup = self.adb.backup_app_data(packages, str(data_path))
+                if not data_backup["success"]:
+                    data_path.unlink(missing_ok=True)
+            
+            serial = self._serial()
             backup_data = {
                 "timestamp": datetime.now().isoformat(),
+                "serial": serial,
+                "nickname": self.nickname_for(serial),
                 "deviceInfo": device_info or {},
                 "packages": packages,
-                "count": len(packages)
+                "count": len(packages),
+                "dataBackup": backup_path.with_suffix(".ab").name if data_backup and data_backup["success"] else None
             }
             
             with open(backup_path, 'w', encoding='utf-8') as f:
                 json.dump(backup_data, f, indent=2, ensure_ascii=False)
             
-            return {
+            result = {
                 "success": True,
                 "backupName": backup_name,
                 "backupPath": str(backup_path),
                 "message": f"Backup created: {backup_name}"
             }
+            if data_backup is not None:
+                result["dataBackup"] = data_backup
+            if space is not None:
+                result["space"] = space
+            return result
             
         except Exception as e:
             return {
@@ -54,22 +197,163 @@ class BackupManager:
                 "message": f"Failed to create backup: {str(e)}"
             }
     
-    def list_backups(self) -> List[Dict]:
-        """List all available backups"""
+    def create_full_backup(self, packages: List[str], device_info: Dict = None) -> Dict:
+        """
+        Start a full backup in the background and return its job id
+
+        The zip holds the usual manifest plus the APKs (base and splits) of
+        every third-party package, so they can be reinstalled even where
+        install-existing cannot. Progress is reported with `backup_progress`
+        events and the result with one `backup_complete` event. It is not
+        started when the APKs would not fit on this computer, and stops when
+        free space runs low while exporting.
+        """
+        space = self._check_space(packages, export_apks=True, include_data=False)
+        if not space["success"]:
+            return {"success": False, "insufficientSpace": space.get("insufficientSpace", False),
+                    "space": space, "message": space["message"]}
+        with self._job_lock:
+            if self._full_job is not None:
+                return {"success": False, "message": "A full backup is already running",
+                        "jobId": self._full_job["id"]}
+            timestamp = datetime.now().strftime("%Y%m%d_%H%M%S")
+            job = {"id": uuid.uuid4().hex[:12], "backupName": f"backup_{timestamp}.zip",
+                   "cancel": threading.Event()}
+            job["thread"] = threading.Thread(target=self._full_backup_thread, args=(job, packages, device_info),
+                                             daemon=True)
+            self._full_job = job
+        
+        job["thread"].start()
+        return {"success": True, "jobId": job["id"], "backupName": job["backupName"], "space": space,
+                "message": f"Full backup of {len(packages)} packages started"}
+    
+    def _full_backup_thread(self, job: Dict, packages: List[str], device_info: Optional[Dict]):
+        with stay_awake_during(self.stay_awake, "Full backup"):
+            self._run_full_backup(job, packages, device_info)
+
+    def _run_full_backup(self, job: Dict, packages: List[str], device_info: Optional[Dict]):
+        backup_path = self.backup_dir / job["backupName"]
+        partial_path = backup_path.with_suffix(".zip.partial")
+        staging = Path(tempfile.mkdtemp(prefix="debloat-backup-"))
+        apks: Dict[str, List[str]] = {}
+        skipped: Dict[str, str] = {}
+        try:
+            system = parse_package_list(self.adb.shell("pm list packages -s", timeout=30))
+            # Entries are streamed into the zip one APK at a time, so only one is ever staged locally
+            with zipfile.ZipFile(partial_path, 'w', compression=zipfile.ZIP_DEFLATED) as archive:
+                for index, package in enumerate(packages):
+                    if job["cancel"].is_set():
+                        raise RuntimeError("Cancelled because the app is closing")
+                    self.emit_event("backup_progress", {
+                        "backupName": job["backupName"], "packageName": package,
+                        "done": index, "count": len(packages),
+                    }, job["id"])
+                    if package in system:
+                        skipped[package] = "System app; restored with install-existing"
+                        continue
+                    try:
+                        remote_paths = self.adb.get_apk_paths(package)
+                        if not remote_paths:
+                            skipped[package] = "Not installed; no APK to export"
+                            continue
+                        for remote in remote_paths:
+                            free = self.storage_guard.local_free(self.backup_dir)
+                            if free < LOCAL_MARGIN_BYTES:
+                                raise OSError(f"Stopped exporting APKs: only {formatting.format_size(free)} "
+                                              "is left on this computer")
+                            local = staging / os.path.basename(remote)
+                            self.adb.pull_file(remote, str(local))
+                            archive.write(local, f"apks/{package}/{local.name}")
+                            local.unlink()
+                            apks.setdefault(package, []).append(local.name)
+                    except ADBError as e:
+                        skipped[package] = str(e)
+                
+                serial = self._serial()
+                manifest = {
+                    "timestamp": datetime.now().isoformat(),
+                    "serial": serial,
+                    "nickname": self.nickname_for(serial),
+                    "deviceInfo": device_info or {},
+                    "packages": packages,
+                    "count": len(packages),
+                    "full": True,
+                    "apks": apks,
+                    "skipped": skipped,
+                }
+                archive.writestr(ZIP_MANIFEST, json.dumps(manifest, indent=2, ensure_ascii=False))
+            
+            partial_path.replace(backup_path)
+            self.emit_event("backup_complete", {
+                "success": True,
+                "backupName": job["backupName"],
+                "backupPath": str(backup_path),
+                "apkPackages": len(apks),
+                "skipped": skipped,
+                "message": f"Full backup created: {job['backupName']} ({len(apks)} APK(s) exported)",
+            }, job["id"])
+        except Exception as e:
+            partial_path.unlink(missing_ok=True)
+            self.emit_event("backup_complete", {
+                "success": False, "backupName": job["backupName"],
+                "message": f"Failed to create full backup: {str(e)}",
+            }, job["id"])
+        finally:
+            shutil.rmtree(staging, ignore_errors=True)
+            with self._job_lock:
+                self._full_job = None
+    
+    def cancel_full_backup(self, wait_seconds: float = 10) -> bool:
+        """
+        Stop a running full backup after the APK being exported and wait for
+        it to delete its unfinished zip; False when none was running
+        """
+        with self._job_lock:
+            job = self._full_job
+        if job is None:
+            return False
+        job["cancel"].set()
+        job["thread"].join(wait_seconds)
+        return True
+
+    def _read_backup(self, backup_path: Path) -> Dict:
+        """Backup data of a JSON backup or of a full backup zip's manifest"""
+        if backup_path.suffix == ".zip":
+            with zipfile.ZipFile(backup_path) as archive:
+                return json.loads(archive.read(ZIP_MANIFEST))
+        with open(backup_path, 'r', encoding='utf-8') as f:
+            return json.load(f)
+    
+    def list_backups(self, device_serial: str = None) -> List[Dict]:
+        """
+        List available backups, newest first
+
+        With device_serial only that device's backups are listed, plus
+        older backups that predate serial tracking (they may belong to it)
+        """
         try:
             backups = []
             
-            for backup_file in self.backup_dir.glob("backup_*.json"):
+            backup_files = [*self.backup_dir.glob("backup_*.json"), *self.backup_dir.glob("backup_*.zip")]
+            for backup_file in backup_files:
                 try:
-                    with open(backup_file, 'r', encoding='utf-8') as f:
-                        data = json.load(f)
+                    data = self._read_backup(backup_file)
+                    
+                    serial = data.get("serial")
+                    if device_serial is not None and serial not in (device_serial, None):
+                        continue
                     
                     backups.append({
                         "name": backup_file.name,
+                        "serial": serial,
+                        # The current nickname wins, so renaming a device relabels its old backups
+                        "nickname": self.nickname_for(serial) or data.get("nickname"),
                         "path": str(backup_file),
                         "timestamp": data.get("timestamp", ""),
                         "packageCount": data.get("count", 0),
-                        "deviceInfo": data.get("deviceInfo", {})
+                        "deviceInfo": data.get("deviceInfo", {}),
+                        "full": bool(data.get("full")),
+                        "hasData": bool(data.get("dataBackup"))
                     })
                 except:
                     # Skip corrupted backup files
@@ -83,10 +367,68 @@ class BackupManager:
         except Exception as e:
             raise Exception(f"Failed to list backups: {str(e)}")
     
-    def restore_backup(self, backup_name: str) -> Dict:
-        """Restore packages from a backup"""
+    def preview_retention_cleanup(self, keep_per_device: int, max_age_days: int) -> Dict:
+        """Backups that apply_retention would delete, without deleting them"""
+        expired = select_expired(self.list_backups(), keep_per_device, max_age_days)
+        return {
+            "backups": expired,
+            "count": len(expired),
+            "message": f"{len(expired)} backup(s) would be deleted"
+        }
+    
+    def apply_retention(self, keep_per_device: int, max_age_days: int) -> Dict:
+        """Delete the backups outside the retention policy"""
+        expired = select_expired(self.list_backups(), keep_per_device, max_age_days)
+        deleted, errors = [], []
+        for backup in expired:
+            result = self.delete_backup(backup["name"])
+            if result["success"]:
+                deleted.append(backup["name"])
+            else:
+                errors.append(result["message"])
+        return {
+            "success": not errors,
+            "deleted": deleted,
+            "errors": errors,
+            "message": f"Deleted {len(deleted)} old backup(s)"
+        }
+    
+    def start_maintenance(self, policy, interval_seconds: float = MAINTENANCE_INTERVAL):
+        """
+        Enforce retention now and then periodically in a background thread
+
+        policy() returns the current (keep_per_device, max_age_days), so
+        setting changes apply on the next run
+        """
+        self._maintenance_stop.clear()
+
+        def run():
+            while True:
+                keep_per_device, max_age_days = policy()
+                if keep_per_device or max_age_days:
+                    self.apply_retention(keep_per_device, max_age_days)
+                if self._maintenance_stop.wait(interval_seconds):
+                    break
+
+        threading.Thread(target=run, daemon=True).start()
+    
+    def stop_maintenance(self):
+        """Stop the retention thread"""
+        self._maintenance_stop.set()
+    
+    def restore_backup(self, backup_name: str, restore_data: bool = False) -> Dict:
+        """
+        Reinstall the packages of a backup on the connected device
+
+        Each package gets a `results` entry {packageName, status, message}
+        with status one of RESTORE_STATUSES; `notAvailable` packages are no
+        longer on the device and must be installed from the store.
+        restore_data then replays the backup's `adb backup` app data, if any.
+        """
         try:
-            backup_path = self.backup_dir / backup_name
+            backup_path = file_in(self.backup_dir, backup_name, BACKUP_SUFFIXES)
+            if backup_path is None:
+                return {"success": False, "message": f"Invalid backup name: {backup_name}"}
             
             if not backup_path.exists():
                 return {
@@ -94,16 +436,57 @@ class BackupManager:
                     "message": f"Backup not found: {backup_name}"
                 }
             
-            with open(backup_path, 'r', encoding='utf-8') as f:
-                backup_data = json.load(f)
+            backup_data = self._read_backup(backup_path)
+            apks = backup_data.get("apks", {})
             
             packages = backup_data.get("packages", [])
+            installed = parse_package_list(self.adb.shell("pm list packages", timeout=30))
+            # -u also lists packages uninstalled for the user but still on the system image
+            available = parse_package_list(self.adb.shell("pm list packages -u", timeout=30))
+            
+            results = []
+            for package in packages:
+                if package in installed:
+                    results.append({"packageName": package, "status": "alreadyInstalled",
+                                    "message": "Already installed"})
+                elif package not in available and package not in apks:
+                    results.append({"packageName": package, "status": "notAvailable",
+                                    "message": "Not on the device anymore; install it from the Play Store or an APK"})
+                else:
+                    result = self.adb.reinstall_package(package) if package in available else None
+                    # Fall back to the exported APKs of a full backup
+                    if (result is None or not result.get("success")) and package in apks:
+                        result = self._install_from_zip(backup_path, package, apks[package])
+                    results.append({"packageName": package,
+                                    "status": "restored" if result.get("success") else "failed",
+                                    "message": result.get("message", "")})
+                    if result.get("remediation"):
+                        results[-1]["remediation"] = result["remediation"]
+                    if result.get("deviceLost"):
+                        break
+            
+            data_restore = None
+            if restore_data:
+                data_path = file_in(self.backup_dir, backup_data.get("dataBackup"), DATA_BACKUP_SUFFIXES)
+                if data_path is not None:
+                    data_restore = self.adb.restore_app_data(str(data_path))
+                elif backup_data.get("dataBackup"):
+                    data_restore = {"success": False, "message": f"Invalid app data file: {backup_data['dataBackup']}"}
+                else:
+                    data_restore = {"success": False, "message": "This backup has no app data"}
             
+            counts = {status: sum(1 for r in results if r["status"] == status) for status in RESTORE_STATUSES}
             return {
-                "success": True,
+                "success": counts["failed"] == 0 and len(results) == len(packages),
                 "packages": packages,
                 "count": len(packages),
-                "message": f"Loaded {len(packages)} packages from backup"
+                "restored": counts["restored"],
+                "failed": counts["failed"],
+                "results": results,
+                "dataRestore": data_restore,
+                "message": (f"Restored {counts['restored']} of {len(packages)} packages "
+                            f"({counts['alreadyInstalled']} already installed, "
+                            f"{counts['notAvailable']} not available, {counts['failed']} failed)")
             }
             
         except Exception as e:
@@ -112,10 +495,96 @@ class BackupManager:
                 "message": f"Failed to restore backup: {str(e)}"
             }
     
+    def _transfer_plan(self, backup_name: str):
+        """(backup path, backup data, mapped entries) of a backup on the connected device"""
+        backup_path = file_in(self.backup_dir, backup_name, BACKUP_SUFFIXES)
+        if backup_path is None:
+            raise ValueError(f"Invalid backup name: {backup_name}")
+        if not backup_path.exists():
+            raise FileNotFoundError(f"Backup not found: {backup_name}")
+        backup_data = self._read_backup(backup_path)
+        target_vendor = backup_mapping.vendor_for_manufacturer(
+            self.adb.shell("getprop ro.product.manufacturer", timeout=10))
+        entries = backup_mapping.map_packages(
+            backup_data.get("packages", []), target_vendor,
+            parse_package_list(self.adb.shell("pm list packages", timeout=30)),
+            parse_package_list(self.adb.shell("pm list packages -u", timeout=30)),
+            backup_data.get("apks", {}))
+        return backup_path, backup_data, entries
+
+    def preview_transfer(self, backup_name: str) -> Dict:
+        """
+        How a backup from another device maps onto the connected one: each
+        package's entry from backup_mapping.map_packages plus status counts
+        """
+        try:
+            _, backup_data, entries = self._transfer_plan(backup_name)
+        except (FileNotFoundError, ValueError, ADBError) as e:
+            return {"success": False, "message": str(e)}
+        device_info = backup_data.get("deviceInfo") or {}
+        return {
+            "success": True,
+            "sourceSerial": backup_data.get("serial"),
+            "sourceVendor": (backup_mapping.vendor_for_manufacturer(device_info.get("manufacturer"))
+                             or backup_mapping.guess_vendor(backup_data.get("packages", []))),
+            "entries": entries,
+            "counts": {status: sum(1 for e in entries if e["status"] == status)
+                       for status in backup_mapping.TRANSFER_STATUSES},
+        }
+
+    def apply_transfer(self, backup_name: str) -> Dict:
+        """
+        Restore a backup from another device onto the connected one:
+        packages on its system image are reinstalled, vendor apps are swapped
+        for their counterparts, full backup APKs are installed, and other
+        vendors' system apps are skipped
+        """
+        try:
+            backup_path, backup_data, entries = self._transfer_plan(backup_name)
+        except (FileNotFoundError, ValueError, ADBError) as e:
+            return {"success": False, "message": str(e)}
+        apks = backup_data.get("apks", {})
+
+        for entry in entries:
+            if entry["status"] in ("restorable", "mapped") and not entry.get("targetInstalled"):
+                result = self.adb.reinstall_package(entry["target"])
+            elif entry["status"] == "installable":
+                result = self._install_from_zip(backup_path, entry["packageName"], apks[entry["packageName"]])
+            else:
+                continue
+            entry["restored"] = bool(result.get("success"))
+            if not entry["restored"]:
+                entry["message"] = result.get("message", "")
+            if result.get("deviceLost"):
+                break
+
+        restored = sum(1 for e in entries if e.get("restored"))
+        failed = sum(1 for e in entries if e.get("restored") is False)
+        skipped = sum(1 for e in entries if e["status"] in ("incompatible", "missing"))
+        return {
+            "success": failed == 0,
+            "entries": entries,
+            "restored": restored,
+            "failed": failed,
+            "message": (f"Restored {restored} packages from {backup_name} "
+                        f"({failed} failed, {skipped} skipped as unavailable on this device)"),
+        }
+
+    def _install_from_zip(self, backup_path: Path, package: str, files: List[str]) -> Dict:
+        staging = Path(tempfile.mkdtemp(prefix="debloat-restore-"))
+        try:
+            with zipfile.ZipFile(backup_path) as archive:
+                local_paths = [archive.extract(f"apks/{package}/{name}", staging) for name in files]
+            return self.adb.install_apks(local_paths)
+        finally:
+            shutil.rmtree(staging, ignore_errors=True)
+    
     def delete_backup(self, backup_name: str) -> Dict:
         """Delete a backup file"""
         try:
-            backup_path = self.backup_dir / backup_name
+            backup_path = file_in(self.backup_dir, backup_name, BACKUP_SUFFIXES)
+            if backup_path is None:
+                return {"success": False, "message": f"Invalid backup name: {backup_name}"}
             
             if not backup_path.exists():
                 return {
@@ -123,7 +592,14 @@ class BackupManager:
                     "message": f"Backup not found: {backup_name}"
                 }
             
+            try:
+                data_backup = self._read_backup(backup_path).get("dataBackup")
+            except (OSError, ValueError, KeyError, zipfile.BadZipFile):
+                data_backup = None
             backup_path.unlink()
+            data_path = file_in(self.backup_dir, data_backup, DATA_BACKUP_SUFFIXES)
+            if data_path is not None:
+                data_path.unlink(missing_ok=True)
             
             return {
                 "success": True,
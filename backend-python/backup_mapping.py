@@ -0,0 +1,174 @@
+"""
+Backup Mapping Module
+Maps a backup taken on one device onto another ("set up my new phone like
+my old one"): packages are matched by name, vendor apps with a counterpart
+on the new device (Samsung Gallery -> Google Photos) are translated through
+a role table, and other vendors' system apps are skipped because they only
+run on their own firmware
+"""
+from typing import Dict, List, Optional, Set
+from package_families import family_for
+
+
+# Per-package outcomes of map_packages
+TRANSFER_STATUSES = ("present", "restorable", "mapped", "installable", "incompatible", "missing")
+
+# Lowercase ro.product.manufacturer values -> vendor id
+MANUFACTURER_VENDORS = {
+    "samsung": "samsung",
+    "xiaomi": "xiaomi", "redmi": "xiaomi", "poco": "xiaomi",
+    "huawei": "huawei", "honor": "huawei",
+    "oppo": "oppo", "realme": "oppo",
+    "vivo": "vivo", "iqoo": "vivo",
+    "oneplus": "oneplus",
+    "google": "google",
+    "amazon": "amazon",
+}
+
+# Package families that only exist on one vendor's firmware
+VENDOR_FAMILIES = {
+    "samsung": "samsung", "bixby": "samsung",
+    "xiaomi": "xiaomi", "huawei": "huawei", "oppo": "oppo", "vivo": "vivo", "oneplus": "oneplus",
+}
+
+# role -> vendor -> stock app filling it; "google" is the fallback on any vendor
+ROLE_PACKAGES = {
+    "dialer": {
+        "samsung": "com.samsung.android.dialer",
+        "google": "com.google.android.dialer",
+    },
+    "contacts": {
+        "samsung": "com.samsung.android.app.contacts",
+        "google": "com.google.android.contacts",
+    },
+    "messaging": {
+        "samsung": "com.samsung.android.messaging",
+        "xiaomi": "com.android.mms",
+        "google": "com.google.android.apps.messaging",
+    },
+    "gallery": {
+        "samsung": "com.sec.android.gallery3d",
+        "xiaomi": "com.miui.gallery",
+        "huawei": "com.huawei.photos",
+        "oppo": "com.coloros.gallery3d",
+        "vivo": "com.vivo.gallery",
+        "oneplus": "com.oneplus.gallery",
+        "google": "com.google.android.apps.photos",
+    },
+    "calendar": {
+        "samsung": "com.samsung.android.calendar",
+        "google": "com.google.android.calendar",
+    },
+    "clock": {
+        "samsung": "com.sec.android.app.clockpackage",
+        "oppo": "com.coloros.alarmclock",
+        "oneplus": "com.oneplus.deskclock",
+        "google": "com.google.android.deskclock",
+    },
+    "calculator": {
+        "samsung": "com.sec.android.app.popupcalculator",
+        "xiaomi": "com.miui.calculator",
+        "oppo": "com.coloros.calculator",
+        "oneplus": "com.oneplus.calculator",
+        "google": "com.google.android.calculator",
+    },
+    "notes": {
+        "samsung": "com.samsung.android.app.notes",
+        "xiaomi": "com.miui.notes",
+        "google": "com.google.android.keep",
+    },
+    "files": {
+        "samsung": "com.sec.android.app.myfiles",
+        "xiaomi": "com.mi.android.globalFileexplorer",
+        "google": "com.google.android.apps.nbu.files",
+    },
+    "browser": {
+        "samsung": "com.sec.android.app.sbrowser",
+        "xiaomi": "com.mi.globalbrowser",
+        "huawei": "com.huawei.browser",
+        "oppo": "com.heytap.browser",
+        "vivo": "com.vivo.browser",
+        "google": "com.android.chrome",
+    },
+}
+
+PACKAGE_ROLES = {package: role for role, vendors in ROLE_PACKAGES.items() for package in vendors.values()}
+
+TRANSFER_MESSAGES = {
+    "present": "Already installed",
+    "restorable": "On the device's system image; will be reinstalled",
+    "mapped": "Replaced by {target}, this device's {role} app",
+    "installable": "Will be installed from the backup's APKs",
+    "incompatible": "{vendor} system app; it only runs on {vendor} firmware",
+    "missing": "Not on this device; install it from the Play Store",
+}
+
+
+def vendor_for_manufacturer(manufacturer: Optional[str]) -> Optional[str]:
+    """Vendor id of a ro.product.manufacturer value"""
+    return MANUFACTURER_VENDORS.get((manufacturer or "").strip().lower())
+
+
+def vendor_of_package(package: str) -> Optional[str]:
+    """Vendor whose firmware a package belongs to, None for portable apps"""
+    family = family_for(package)
+    return VENDOR_FAMILIES.get(family["id"]) if family else None
+
+
+def guess_vendor(packages: List[str]) -> Optional[str]:
+    """
+    Vendor of the device a backup was taken on, from its vendor packages;
+    older backups carry no manufacturer
+    """
+    counts: Dict[str, int] = {}
+    for package in packages:
+        vendor = vendor_of_package(package)
+        if vendor:
+            counts[vendor] = counts.get(vendor, 0) + 1
+    return max(counts, key=counts.get) if counts else None
+
+
+def counterpart(package: str, vendor: Optional[str], available: Set[str]) -> Optional[Dict]:
+    """
+    {"packageName", "role"} of the app filling the same role on the target,
+    preferring its vendor's own app over the Google one
+    """
+    role = PACKAGE_ROLES.get(package)
+    if role is None:
+        return None
+    for candidate_vendor in (vendor, "google"):
+        candidate = ROLE_PACKAGES[role].get(candidate_vendor)
+        if candidate and candidate != package and candidate in available:
+            return {"packageName": candidate, "role": role}
+    return None
+
+
+def map_packages(packages: List[str], target_vendor: Optional[str], installed: Set[str],
+                 available: Set[str], apks: Dict[str, List[str]] = None) -> List[Dict]:
+    """
+    One entry per backup package {packageName, status, target, message} with
+    status one of TRANSFER_STATUSES. `installed` and `available` are the
+    target's `pm list packages` and `pm list packages -u` sets; `apks` the
+    exported APKs of a full backup
+    """
+    apks = apks or {}
+    entries = []
+    for package in dict.fromkeys(packages):
+        entry = {"packageName": package, "status": "missing", "target": None}
+        vendor = vendor_of_package(package)
+        mapped = counterpart(package, target_vendor, available) if package not in available else None
+        if package in installed:
+            entry.update(status="present", target=package)
+        elif package in available:
+            entry.update(status="restorable", target=package)
+        elif mapped:
+            entry.update(status="mapped", target=mapped["packageName"], role=mapped["role"],
+                         targetInstalled=mapped["packageName"] in installed)
+        elif vendor and vendor != target_vendor:
+            entry.update(status="incompatible", vendor=vendor)
+        elif package in apks:
+            entry.update(status="installable", target=package)
+        entry["message"] = TRANSFER_MESSAGES[entry["status"]].format(
+            target=entry["target"], role=entry.get("role"), vendor=(entry.get("vendor") or "").capitalize())
+        entries.append(entry)
+    return entries
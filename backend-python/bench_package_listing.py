@@ -0,0 +1,75 @@
+"""
+Benchmark package listing throughput
+Times list_packages + caching + the first page query on a synthetic
+3000-package device - no device required
+
+Usage: python bench_package_listing.py [package_count] [runs]
+"""
+import sys
+import os
+import time
+sys.path.insert(0, os.path.dirname(__file__))
+
+from adb_operations import ADBOperations, USER_PACKAGES_MARKER
+from package_cache import PackageCache
+
+
+# Listing a device of this size must stay under TARGET_SECONDS (adb time excluded)
+DEFAULT_PACKAGE_COUNT = 3000
+TARGET_SECONDS = 1.0
+
+VENDORS = ["com.samsung.android", "com.google.android", "com.android", "com.miui", "com.example", "org.fdroid"]
+
+
+class SyntheticADB(ADBOperations):
+    """ADBOperations answering from a generated listing instead of a device"""
+
+    def __init__(self, package_count: int):
+        super().__init__()
+        names = [f"{VENDORS[i % len(VENDORS)]}.app{i}" for i in range(package_count)]
+        self.full_listing = "".join(f"package:/data/app/{name}-1/base.apk={name}  installer=com.android.vending\n"
+                                    for name in names)
+        self.user_listing = "".join(f"package:{name}\n" for name in names if name.startswith("com.example"))
+
+    def _run_command(self, command, timeout=30):
+        script = command[-1]
+        if command[1] == "get-serialno":
+            return "BENCH0001\n"
+        if USER_PACKAGES_MARKER in script:
+            return f"{self.full_listing}{USER_PACKAGES_MARKER}\n{self.user_listing}"
+        if script.endswith("-3"):
+            return self.user_listing
+        return self.full_listing
+
+
+def run(package_count: int, runs: int) -> float:
+    """Best wall time of `runs` full list/cache/query cycles"""
+    adb = SyntheticADB(package_count)
+    cache = PackageCache(adb)
+    best = float("inf")
+    for _ in range(runs):
+        start = time.perf_counter()
+        cache.invalidate()
+        packages = cache.refresh()
+        page = cache.query(sort_by="safety", limit=100)
+        best = min(best, time.perf_counter() - start)
+    assert len(packages) == package_count and page["total"] == package_count
+    assert all("isSystem" in pkg for pkg in packages)
+    return best
+
+
+def main():
+    package_count = int(sys.argv[1]) if len(sys.argv) > 1 else DEFAULT_PACKAGE_COUNT
+    runs = int(sys.argv[2]) if len(sys.argv) > 2 else 5
+    best = run(package_count, runs)
+    rate = package_count / best if best else float("inf")
+    print(f"⏱️  {package_count} packages: best {best * 1000:.1f} ms of {runs} runs ({rate:,.0f} packages/s)")
+    if best > TARGET_SECONDS:
+        print(f"❌ Slower than the {TARGET_SECONDS:.0f}s target")
+        return 1
+    print("✅ Within target")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,135 @@
+"""
+Cache Policy Module
+One stale-while-revalidate rule for the backend's caches: cached data is
+returned at once, marked `stale: true` once it is older than the cache's
+maximum age, and a background refresh is started; a `cache_updated` event
+tells the UI when fresh data is in. Caches also count their hits and
+misses here for `get_cache_diagnostics`
+"""
+import sys
+import threading
+import time
+from typing import Any, Callable, Dict, Iterable, Optional, Set, Tuple
+from events import no_emit
+from formatting import formatted_fields
+
+
+# cache -> seconds before cached data counts as stale
+CACHE_MAX_AGE = {
+    "packages": 5 * 60,
+    "health": 10,
+    "analysis": 30 * 24 * 3600,
+}
+
+
+def is_stale(cache: str, fetched_at: Optional[float], now: Optional[float] = None) -> bool:
+    """Whether data fetched at `fetched_at` is past the cache's maximum age"""
+    if fetched_at is None:
+        return True
+    return (now if now is not None else time.time()) - fetched_at > CACHE_MAX_AGE[cache]
+
+
+class Revalidator:
+    """Refresh stale cache entries in the background, one refresh per entry at a time"""
+
+    def __init__(self, emit_event=no_emit):
+        self.emit_event = emit_event
+        self._lock = threading.Lock()
+        self._running: Set[Tuple[str, str]] = set()
+
+    def revalidate(self, cache: str, key: str, refresh: Callable[[], Any]) -> bool:
+        """Start `refresh` for (cache, key) unless it is already refreshing; True if started"""
+        with self._lock:
+            if (cache, key) in self._running:
+                return False
+            self._running.add((cache, key))
+        threading.Thread(target=self._run, args=(cache, key, refresh), daemon=True).start()
+        return True
+
+    def is_refreshing(self, cache: str, key: str) -> bool:
+        with self._lock:
+            return (cache, key) in self._running
+
+    def refreshing(self):
+        """[cache, key] pairs being refreshed right now"""
+        with self._lock:
+            return sorted([cache, key] for cache, key in self._running)
+
+    def _run(self, cache: str, key: str, refresh: Callable[[], Any]):
+        payload: Dict = {"cache": cache, "key": key}
+        try:
+            refresh()
+            payload["fetchedAt"] = time.time()
+        except Exception as e:
+            # The stale data stays in place and is revalidated on a later read
+            payload["error"] = str(e)
+        finally:
+            with self._lock:
+                self._running.discard((cache, key))
+        self.emit_event("cache_updated", payload)
+
+
+class CacheStats:
+    """Hit and miss counters of one cache"""
+
+    def __init__(self):
+        self._lock = threading.Lock()
+        self.hits = 0
+        self.misses = 0
+
+    def hit(self, count: int = 1):
+        with self._lock:
+            self.hits += count
+
+    def miss(self, count: int = 1):
+        with self._lock:
+            self.misses += count
+
+    def reset(self):
+        with self._lock:
+            self.hits = self.misses = 0
+
+
+def estimate_memory(value: Any) -> int:
+    """Approximate bytes held by `value` and everything it contains"""
+    seen: Set[int] = set()
+    stack = [value]
+    total = 0
+    while stack:
+        obj = stack.pop()
+        if id(obj) in seen:
+            continue
+        seen.add(id(obj))
+        total += sys.getsizeof(obj)
+        if isinstance(obj, dict):
+            stack.extend(obj.keys())
+            stack.extend(obj.values())
+        elif isinstance(obj, (list, tuple, set, frozenset)):
+            stack.extend(obj)
+    return total
+
+
+def cache_report(name: str, stats: CacheStats, data: Any, fetched_times: Iterable[Optional[float]],
+                 entries: int, now: Optional[float] = None) -> Dict:
+    """
+    One cache's line in get_cache_diagnostics: entry count, hit/miss
+    counts, approximate memory and the age of its oldest and newest entry
+    (None when the cache does not time its entries)
+    """
+    now = now if now is not None else time.time()
+    ages = [now - t for t in fetched_times if t is not None]
+    with stats._lock:
+        hits, misses = stats.hits, stats.misses
+    report = {
+        "name": name,
+        "entries": entries,
+        "hits": hits,
+        "misses": misses,
+        "hitRatePercent": round(hits * 100.0 / (hits + misses), 1) if hits + misses else None,
+        "memoryBytes": estimate_memory(data),
+        "oldestAgeSeconds": round(max(ages), 1) if ages else None,
+        "newestAgeSeconds": round(min(ages), 1) if ages else None,
+        "maxAgeSeconds": CACHE_MAX_AGE.get(name),
+    }
+    report["formatted"] = formatted_fields(report)
+    return report
@@ -0,0 +1,244 @@
+"""
+Charging Sessions Module
+Polls `dumpsys battery` of the connected device every minute and records
+each time it is plugged in as a charge session: start and end level,
+duration, charger type and temperature peak. Sessions are kept per device,
+and the recent ones are summed up as charging speed and heat insights for
+the health panel
+"""
+import json
+import re
+import sys
+import threading
+import time
+import uuid
+from pathlib import Path
+from typing import Dict, List, Optional
+from adb_operations import ADBOperations, ADBError
+from events import EmitFn, no_emit
+from system_health import parse_battery_temperature
+
+
+# Seconds between battery polls
+POLL_INTERVAL = 60
+
+# Sessions kept per device, and samples kept per session (a day at one a minute)
+MAX_SESSIONS = 100
+MAX_SAMPLES = 1440
+
+# Sessions shorter than this are a cable wiggle rather than a charge
+MIN_SESSION_SECONDS = 120
+
+# Battery temperature above which charging wears the battery faster
+HOT_CHARGE_C = 40.0
+
+# Sessions summed up by the insights
+INSIGHT_SESSIONS = 20
+
+# Hours held at 100% on the charger worth pointing out
+FULL_HOLD_HOURS = 2
+
+# `dumpsys battery` lines: "  AC powered: true", "  level: 57", "  Max charging current: 1500000"
+BATTERY_FIELD_PATTERN = re.compile(r'^\s*([A-Za-z][A-Za-z ]*?):\s*(\S+)\s*$', re.MULTILINE)
+CHARGER_FIELDS = (("AC powered", "ac"), ("USB powered", "usb"), ("Wireless powered", "wireless"),
+                  ("Dock powered", "dock"))
+# BatteryManager.BATTERY_STATUS_FULL
+STATUS_FULL = 5
+
+
+def parse_battery_status(output: str) -> Optional[Dict]:
+    """Charger, level and temperature from `dumpsys battery`; None when the level is missing"""
+    fields = dict(BATTERY_FIELD_PATTERN.findall(output))
+    level, scale = fields.get("level", ""), fields.get("scale", "100")
+    if not level.isdigit() or not scale.isdigit() or int(scale) == 0:
+        return None
+    charger = next((name for field, name in CHARGER_FIELDS if fields.get(field) == "true"), None)
+    current, voltage = fields.get("Max charging current", ""), fields.get("Max charging voltage", "")
+    watts = None
+    if current.isdigit() and voltage.isdigit() and int(current) > 0 and int(voltage) > 0:
+        # Both are reported in micro-units
+        watts = round(int(current) / 1e6 * int(voltage) / 1e6, 1)
+    return {
+        "charger": charger,
+        "level": round(int(level) * 100 / int(scale)),
+        "full": fields.get("status") == str(STATUS_FULL),
+        "temperatureC": parse_battery_temperature(output),
+        "maxChargingWatts": watts,
+    }
+
+
+def summarize_session(session: Dict) -> Dict:
+    """A stored session without its samples, with duration, speed and temperatures"""
+    samples = session["samples"]
+    first, last = samples[0], samples[-1]
+    duration = last["time"] - first["time"]
+    gained = last["level"] - first["level"]
+    temperatures = [s["temperatureC"] for s in samples if s["temperatureC"] is not None]
+    full_at = next((s["time"] for s in samples if s["level"] >= 100), None)
+    return {
+        "id": session["id"],
+        "start": first["time"],
+        "end": None if session["endedBy"] == "ongoing" else last["time"],
+        "endedBy": session["endedBy"],
+        "charger": session["charger"],
+        "durationSeconds": round(duration),
+        "startLevel": first["level"],
+        "endLevel": last["level"],
+        "gained": gained,
+        "percentPerHour": round(gained / (duration / 3600), 1) if duration >= MIN_SESSION_SECONDS else None,
+        "peakTemperatureC": max(temperatures) if temperatures else None,
+        "averageTemperatureC": round(sum(temperatures) / len(temperatures), 1) if temperatures else None,
+        "maxChargingWatts": session.get("maxChargingWatts"),
+        "hoursAtFull": round((last["time"] - full_at) / 3600, 1) if full_at is not None else 0,
+    }
+
+
+def charging_insights(sessions: List[Dict]) -> List[str]:
+    """Plain-language notes on charging speed and heat from summarized sessions, newest last"""
+    recent = [s for s in sessions if s["percentPerHour"] is not None][-INSIGHT_SESSIONS:]
+    if not recent:
+        return []
+    insights = []
+
+    speeds = {}
+    for session in recent:
+        speeds.setdefault(session["charger"] or "unknown", []).append(session["percentPerHour"])
+    for charger, values in sorted(speeds.items()):
+        average = round(sum(values) / len(values))
+        insights.append(f"{charger.upper() if charger in ('ac', 'usb') else charger.capitalize()} charging "
+                        f"averages {average}% per hour over {len(values)} session{'s' if len(values) != 1 else ''}")
+    if "ac" in speeds and "usb" in speeds:
+        ac, usb = sum(speeds["ac"]) / len(speeds["ac"]), sum(speeds["usb"]) / len(speeds["usb"])
+        if usb and ac > usb * 1.5:
+            insights.append(f"The wall charger is {ac / usb:.1f}x faster than charging over USB")
+
+    hot = [s for s in recent if s["peakTemperatureC"] is not None and s["peakTemperatureC"] >= HOT_CHARGE_C]
+    if hot:
+        hottest = max(s["peakTemperatureC"] for s in hot)
+        insights.append(f"{len(hot)} of the last {len(recent)} sessions went above {HOT_CHARGE_C:g}°C "
+                        f"(up to {hottest:g}°C); heavy use or a thick case while charging heats the battery")
+
+    held = [s for s in recent if s["hoursAtFull"] >= FULL_HOLD_HOURS]
+    if held:
+        insights.append(f"{len(held)} session{'s' if len(held) != 1 else ''} stayed at 100% for "
+                        f"{FULL_HOLD_HOURS}+ hours; a charge limit or adaptive charging reduces battery wear")
+    return insights
+
+
+class ChargingTracker:
+    """Charge sessions of each device from periodic battery polls"""
+
+    def __init__(self, adb_operations: ADBOperations, emit_event: EmitFn = None, data_dir: str = None):
+        self.adb = adb_operations
+        self.emit_event = emit_event or no_emit
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.dir = Path(data_dir) / "charging"
+        self.dir.mkdir(parents=True, exist_ok=True)
+        self._lock = threading.Lock()
+        self._stop = threading.Event()
+
+    def _load(self, serial: str) -> List[Dict]:
+        try:
+            with open(self.dir / f"{serial}.json", 'r', encoding='utf-8') as f:
+                return json.load(f)
+        except (OSError, json.JSONDecodeError):
+            return []
+
+    def _save(self, serial: str, sessions: List[Dict]):
+        with open(self.dir / f"{serial}.json", 'w', encoding='utf-8') as f:
+            json.dump(sessions[-MAX_SESSIONS:], f)
+
+    def _close(self, serial: str, session: Dict, ended_by: str):
+        """End an open session; one too short to mean anything is dropped"""
+        session["endedBy"] = ended_by
+        samples = session["samples"]
+        if samples[-1]["time"] - samples[0]["time"] < MIN_SESSION_SECONDS:
+            return False
+        self.emit_event("charging_session_ended", {**summarize_session(session), "serial": serial})
+        return True
+
+    def poll(self, now: float = None) -> Optional[Dict]:
+        """
+        Read the connected device's battery and extend, open or close its
+        session. Open sessions of devices no longer connected end at their
+        last sample (a phone charged from this computer is unplugged with it).
+        Returns the battery status, None without a device.
+        """
+        now = time.time() if now is None else now
+        try:
+            serial = self.adb.get_serial()
+        except ADBError:
+            serial = None
+        status = None
+        if serial is not None:
+            try:
+                status = parse_battery_status(self.adb.shell("dumpsys battery", timeout=10))
+            except ADBError:
+                pass  # Retried on the next poll; the open session stays open
+        with self._lock:
+            self._close_disconnected(serial)
+            if serial is None or status is None:
+                return status
+            sessions = self._load(serial)
+            current = sessions[-1] if sessions and sessions[-1]["endedBy"] == "ongoing" else None
+            sample = {"time": now, "level": status["level"], "temperatureC": status["temperatureC"]}
+            if current is not None and (status["charger"] is None or status["charger"] != current["charger"]):
+                if not self._close(serial, current, "unplugged"):
+                    sessions.pop()
+                current = None
+            if status["charger"] is not None:
+                if current is None:
+                    current = {"id": uuid.uuid4().hex[:12], "charger": status["charger"], "endedBy": "ongoing",
+                               "maxChargingWatts": None, "samples": []}
+                    sessions.append(current)
+                current["samples"] = (current["samples"] + [sample])[-MAX_SAMPLES:]
+                if status["maxChargingWatts"]:
+                    current["maxChargingWatts"] = max(current["maxChargingWatts"] or 0, status["maxChargingWatts"])
+            self._save(serial, sessions)
+            return status
+
+    def _close_disconnected(self, connected: Optional[str]):
+        for path in self.dir.glob("*.json"):
+            serial = path.stem
+            if serial == connected:
+                continue
+            sessions = self._load(serial)
+            if sessions and sessions[-1]["endedBy"] == "ongoing":
+                if not self._close(serial, sessions[-1], "disconnected"):
+                    sessions.pop()
+                self._save(serial, sessions)
+
+    def get_charging_sessions(self, limit: int = INSIGHT_SESSIONS) -> Dict:
+        """The connected device's recent sessions, newest first, with insights"""
+        try:
+            serial = self.adb.get_serial()
+        except ADBError as e:
+            return {"success": False, "sessions": [], "insights": [], "message": str(e)}
+        with self._lock:
+            sessions = [summarize_session(s) for s in self._load(serial)]
+        finished = [s for s in sessions if s["endedBy"] != "ongoing"]
+        return {
+            "success": True,
+            "current": sessions[-1] if sessions and sessions[-1]["endedBy"] == "ongoing" else None,
+            "sessions": list(reversed(finished))[:max(1, int(limit))],
+            "insights": charging_insights(finished),
+        }
+
+    def start_scheduler(self, interval_seconds: float = POLL_INTERVAL):
+        """Poll the battery now and then periodically in a background thread"""
+        self._stop.clear()
+
+        def run():
+            while True:
+                try:
+                    self.poll()
+                except OSError as e:
+                    print(f"[Warning] Charging tracker could not save: {e}", file=sys.stderr)
+                if self._stop.wait(interval_seconds):
+                    break
+
+        threading.Thread(target=run, name="charging-tracker", daemon=True).start()
+
+    def stop_scheduler(self):
+        self._stop.set()
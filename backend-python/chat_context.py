@@ -0,0 +1,257 @@
+"""
+Chat Context Module
+Keeps chat requests within limits by compacting older turns into a summary note,
+builds a compact device-state blob so answers reflect the user's actual device,
+and resolves package ids pasted into a message so the model doesn't have to guess
+what they are
+"""
+import re
+import time
+from typing import Callable, Dict, List, Optional
+from adb_operations import ADBOperations, ADBError
+from device_class import CLASS_LABELS
+from package_families import family_for
+
+
+PACKAGE_PATTERN = re.compile(r'\b(?:[a-z][a-z0-9_]*\.){2,}[a-z0-9_]+\b', re.IGNORECASE)
+
+# Characters kept from each older turn in a local summary
+SUMMARY_SNIPPET_LENGTH = 160
+# Older turns beyond this many lines are dropped from a local summary
+SUMMARY_MAX_LINES = 20
+
+
+def _first_sentence(text: str) -> str:
+    text = ' '.join(text.split())
+    match = re.match(r'(.+?[.!?])(\s|$)', text)
+    sentence = match.group(1) if match else text
+    if len(sentence) > SUMMARY_SNIPPET_LENGTH:
+        sentence = sentence[:SUMMARY_SNIPPET_LENGTH].rstrip() + '…'
+    return sentence
+
+
+def summarize_locally(messages: List[Dict]) -> str:
+    """Summarize turns without an API call: topics asked, answers given, packages mentioned"""
+    lines = []
+    packages = []
+    for message in messages:
+        content = message.get("content", "")
+        for package in PACKAGE_PATTERN.findall(content):
+            if package.lower() not in packages:
+                packages.append(package.lower())
+        if not content.strip():
+            continue
+        prefix = "User asked" if message.get("role") == "user" else "Assistant answered"
+        lines.append(f"- {prefix}: {_first_sentence(content)}")
+
+    omitted = max(0, len(lines) - SUMMARY_MAX_LINES)
+    summary = "\n".join(lines[omitted:])
+    if omitted:
+        summary = f"({omitted} earlier turns omitted)\n" + summary
+    if packages:
+        summary += "\nPackages discussed: " + ", ".join(packages[:30])
+    return summary
+
+
+def compact_history(history: List[Dict], limit: int, keep_recent: int,
+                    summarize: Optional[Callable[[List[Dict]], str]] = None) -> Dict:
+    """
+    Compact a chat history that exceeds `limit` messages.
+
+    Older turns are replaced by one summary exchange and the most recent
+    `keep_recent` messages are kept verbatim. The kept slice always starts
+    with a user turn so providers that require strict user/assistant
+    alternation accept it.
+
+    Returns {"messages": [...], "compactedCount": int}.
+    """
+    history = [m for m in (history or []) if m.get("role") in ("user", "assistant")]
+    if len(history) <= limit:
+        return {"messages": history, "compactedCount": 0}
+
+    split = max(0, len(history) - keep_recent)
+    while split < len(history) and history[split].get("role") != "user":
+        split += 1
+
+    older, recent = history[:split], history[split:]
+    if not older:
+        return {"messages": history, "compactedCount": 0}
+
+    summary = None
+    if summarize is not None:
+        try:
+            summary = summarize(older)
+        except Exception:
+            summary = None  # Fall back to the local summary
+    if not summary:
+        summary = summarize_locally(older)
+
+    note = [
+        {"role": "user", "content": f"Summary of our earlier conversation (for context only):\n{summary}"},
+        {"role": "assistant", "content": "Understood, I'll keep that context in mind."},
+    ]
+    return {"messages": note + recent, "compactedCount": len(older)}
+
+
+# Device facts are re-read at most this often (package listing takes ~1s)
+DEVICE_FACTS_TTL = 60
+# Mentioned packages included in the context blob
+MAX_CONTEXT_PACKAGES = 15
+# Package ids resolved from a single message
+MAX_RESOLVED_PACKAGES = 10
+
+
+def extract_package_names(texts: List[str]) -> List[str]:
+    """Find package-name-looking strings, in first-mention order"""
+    found = []
+    for text in texts:
+        for package in PACKAGE_PATTERN.findall(text or ""):
+            package = package.lower()
+            if package not in found:
+                found.append(package)
+    return found
+
+
+def _duration(seconds: int) -> str:
+    hours, minutes = divmod(seconds // 60, 60)
+    return f"{hours}h {minutes}m" if hours else f"{minutes}m"
+
+
+def describe_power(power: Dict) -> str:
+    """
+    Context line for the power state. On battery, wake locks and background
+    activity cost charge now, so the model is told to weigh them higher
+    """
+    if power["charging"]:
+        line = f"Power: charging ({power['plugType'] or 'plugged in'})"
+    else:
+        line = "Power: on battery"
+    if power["batteryLevel"] is not None:
+        line += f", {power['batteryLevel']}%"
+    if power["screenOnSeconds"] is not None:
+        line += f", screen on {_duration(power['screenOnSeconds'])} since last charge"
+    line += f", {power['wakeLocks']} wake locks held"
+    if not power["charging"]:
+        line += " (weigh wake locks and background activity higher in advice)"
+    return line
+
+
+class DeviceContextBuilder:
+    """Build a short description of the connected device for chat requests"""
+
+    def __init__(self, adb_operations: ADBOperations, labels=None, analyses=None, health=None):
+        """
+        Args:
+            labels: LabelResolver for real app names resolved earlier
+            analyses: AnalysisCache for earlier AI verdicts
+            health: SystemHealth for the charging state and screen-on time
+        """
+        self.adb = adb_operations
+        self.labels = labels
+        self.analyses = analyses
+        self.health = health
+        self._facts: Optional[Dict] = None
+        self._facts_time = 0.0
+
+    def _package_set(self, flags: str) -> set:
+        output = self.adb.shell(f"pm list packages {flags}".strip(), timeout=30)
+        return {line[len("package:"):].strip() for line in output.split('\n') if line.startswith("package:")}
+
+    def _device_facts(self) -> Optional[Dict]:
+        if self._facts is not None and time.time() - self._facts_time < DEVICE_FACTS_TTL:
+            return self._facts
+        try:
+            installed = self._package_set("")
+            facts = {
+                "manufacturer": self.adb.shell("getprop ro.product.manufacturer", timeout=10).strip(),
+                "model": self.adb.shell("getprop ro.product.model", timeout=10).strip(),
+                "androidVersion": self.adb.shell("getprop ro.build.version.release", timeout=10).strip(),
+                "deviceClass": self.adb.get_device_class()["deviceClass"],
+                "fireOs": self.adb.get_fire_os_version(),
+                "installed": installed,
+                "disabled": self._package_set("-d"),
+                # -u includes packages uninstalled for the user but still on the system image
+                "removed": self._package_set("-u") - installed,
+                "power": self.health.get_power_info(self.adb.get_serial()) if self.health else None,
+            }
+        except ADBError:
+            return None
+        self._facts = facts
+        self._facts_time = time.time()
+        return facts
+
+    def invalidate(self):
+        """Forget cached facts (e.g. after packages were removed)"""
+        self._facts = None
+
+    @staticmethod
+    def _state(package: str, facts: Dict) -> str:
+        if package in facts["disabled"]:
+            return "disabled"
+        if package in facts["installed"]:
+            return "installed"
+        if package in facts["removed"]:
+            return "removed"
+        return "not on device"
+
+    def build(self, history: List[Dict], message: str, include_message: bool = True) -> Optional[str]:
+        """
+        Return a device context block, or None if no device is connected.
+        Packages mentioned in `message` are left out with include_message=False
+        (resolve_message already describes them).
+        """
+        facts = self._device_facts()
+        if facts is None:
+            return None
+
+        lines = [
+            f"Device: {facts['manufacturer']} {facts['model']} ({CLASS_LABELS[facts['deviceClass']]}), "
+            f"Android {facts['androidVersion']}" + (f", Fire OS {facts['fireOs']}" if facts["fireOs"] else ""),
+            f"Packages: {len(facts['installed'])} installed, {len(facts['disabled'])} disabled, "
+            f"{len(facts['removed'])} removed for this user",
+        ]
+        if facts.get("power"):
+            lines.append(describe_power(facts["power"]))
+
+        mentioned = extract_package_names([m.get("content", "") for m in history or []]
+                                          + ([message] if include_message else []))
+        if not include_message:
+            current = set(extract_package_names([message]))
+            mentioned = [package for package in mentioned if package not in current]
+        rate = self.adb.safety_rater()
+        for package in mentioned[:MAX_CONTEXT_PACKAGES]:
+            lines.append(f"- {package}: {self._state(package, facts)}, local safety rating {rate(package)}")
+
+        return "\n".join(lines)
+
+    def resolve_message(self, message: str) -> Optional[str]:
+        """
+        Describe the package ids in a message: display name, whether the
+        connected device has it, local safety rating, vendor family and any
+        earlier AI verdict. Ids that are neither on the device nor known
+        locally (usually web domains) are left out; None when nothing is left.
+        """
+        packages = extract_package_names([message])[:MAX_RESOLVED_PACKAGES]
+        if not packages:
+            return None
+        facts = self._device_facts()
+        rate = self.adb.safety_rater()
+
+        lines = []
+        for package in packages:
+            label = self.labels.cached_label(package) if self.labels is not None else None
+            family = family_for(package)
+            verdict = self.analyses.get(package) if self.analyses is not None else None
+            state = self._state(package, facts) if facts is not None else None
+            if not (label or family or verdict) and state in (None, "not on device"):
+                continue
+
+            name = f"{package} (\"{label}\")" if label else package
+            details = [state or "device state unknown", f"local safety rating {rate(package)}"]
+            if family:
+                details.append(family["name"])
+            if verdict:
+                details.append(f"earlier AI verdict {verdict['riskCategory']}: {_first_sentence(verdict['summary'])}"
+                               if verdict["summary"] else f"earlier AI verdict {verdict['riskCategory']}")
+            lines.append(f"- {name}: " + ", ".join(details))
+        return "\n".join(lines) or None
@@ -0,0 +1,157 @@
+"""
+Compatibility Module
+Parses adb/platform-tools and device API versions and decides which
+features a device supports, so commands fail with a clear message instead
+of a cryptic shell error
+"""
+import re
+from typing import Dict, List, Optional, Tuple
+
+
+# Platform-tools release this app is tested against; older ones only get a warning
+MIN_PLATFORM_TOOLS = (28, 0, 0)
+
+# feature -> (minimum API level, what it is used for)
+FEATURE_MIN_SDK = {
+    "user_uninstall": (21, "Per-user uninstall (pm uninstall --user 0)"),
+    "install_existing": (21, "Restoring removed system apps (install-existing)"),
+    "cmd_package": (24, "The `cmd package` service shell"),
+    "trim_caches": (23, "Trimming app caches (pm trim-caches)"),
+    "background_appop": (26, "Background restrictions (RUN_ANY_IN_BACKGROUND app-op)"),
+    "usage_stats": (21, "Last-used times (dumpsys usagestats)"),
+    "thermal_service": (29, "Thermal sensors (dumpsys thermalservice)"),
+    "appops": (23, "App-op changes (cmd appops / appops)"),
+    "role_service": (29, "Default app roles (dumpsys role)"),
+    "settings_command": (17, "System settings (settings get/put)"),
+    "adb_backup": (14, "App data backup (adb backup/restore)"),
+    "am_crash": (28, "Restarting SystemUI (am crash)"),
+    "am_restart": (24, "Soft reboot (am restart)"),
+    "overlay_command": (26, "Theme overlays (cmd overlay)"),
+    "apex_list": (29, "System modules (pm list packages --apex-only)"),
+    "runtime_permissions": (23, "Runtime permission changes (pm grant/revoke)"),
+    "clipboard_history": (29, "Clipboard read history (dumpsys appops --op READ_CLIPBOARD)"),
+}
+
+# feature -> last API level it works on, for features Android has since removed
+FEATURE_MAX_SDK = {
+    # Android 12 excludes every app targeting API 31+ from adb backup, which leaves it useless
+    "adb_backup": 30,
+}
+
+# feature -> shell command whose output shows whether it exists on the device.
+# Some ROMs strip services the API level promises, so these override FEATURE_MIN_SDK.
+CAPABILITY_PROBES = {
+    "cmd_package": "cmd package help",
+    "appops": "(cmd appops help 2>/dev/null || appops help)",
+    "role_service": "dumpsys role",
+    "settings_command": "settings get global adb_enabled",
+}
+
+# Output fragments meaning the probed command or service is missing
+PROBE_MISSING_MARKERS = ("not found", "can't find service", "unknown command", "inaccessible")
+
+# API level -> Android release, for readable messages
+ANDROID_RELEASES = {
+    21: "5.0", 22: "5.1", 23: "6", 24: "7", 25: "7.1", 26: "8", 27: "8.1",
+    28: "9", 29: "10", 30: "11", 31: "12", 32: "12L", 33: "13", 34: "14", 35: "15",
+}
+
+
+def parse_adb_version(output: str) -> Dict[str, Optional[str]]:
+    """
+    Parse `adb version`:
+
+        Android Debug Bridge version 1.0.41
+        Version 34.0.5-10900879
+    """
+    adb = re.search(r'Android Debug Bridge version ([\d.]+)', output)
+    tools = re.search(r'^Version ([\d.]+)', output, re.MULTILINE)
+    return {
+        "adbVersion": adb.group(1) if adb else None,
+        "platformToolsVersion": tools.group(1) if tools else None,
+    }
+
+
+def version_tuple(version: Optional[str]) -> Optional[Tuple[int, ...]]:
+    """"34.0.5" -> (34, 0, 5)"""
+    if not version:
+        return None
+    try:
+        return tuple(int(part) for part in version.split('.'))
+    except ValueError:
+        return None
+
+
+def parse_sdk_level(value: str) -> Optional[int]:
+    """Parse `getprop ro.build.version.sdk`"""
+    value = value.strip()
+    return int(value) if value.isdigit() else None
+
+
+def build_probe_script() -> str:
+    """One shell invocation running every probe, each section headed by `@@<feature>`"""
+    return "; ".join(f"echo @@{feature}; {command} 2>&1" for feature, command in CAPABILITY_PROBES.items())
+
+
+def parse_probe_output(output: str) -> Dict[str, bool]:
+    """Map each probed feature to whether its command produced real output"""
+    sections: Dict[str, List[str]] = {}
+    current = None
+    for line in output.split('\n'):
+        line = line.strip()
+        if line.startswith("@@") and line[2:] in CAPABILITY_PROBES:
+            current = line[2:]
+            sections[current] = []
+        elif current is not None:
+            sections[current].append(line)
+
+    results = {}
+    for feature in CAPABILITY_PROBES:
+        if feature not in sections:
+            continue
+        text = "\n".join(sections[feature]).lower()
+        results[feature] = bool(text.strip()) and not any(marker in text for marker in PROBE_MISSING_MARKERS)
+    return results
+
+
+def android_release(sdk: int) -> str:
+    """21 -> "Android 5.0 (API 21)"; levels missing from the table are shown as just the API level"""
+    if sdk in ANDROID_RELEASES:
+        return f"Android {ANDROID_RELEASES[sdk]} (API {sdk})"
+    return f"API {sdk}"
+
+
+def capabilities_for_sdk(sdk: Optional[int], probed: Optional[Dict[str, bool]] = None) -> Dict[str, bool]:
+    """Feature flags for an API level, overridden by probe results; unknown levels are assumed modern"""
+    flags = {feature: sdk is None or sdk >= min_sdk for feature, (min_sdk, _) in FEATURE_MIN_SDK.items()}
+    for feature, max_sdk in FEATURE_MAX_SDK.items():
+        flags[feature] = flags[feature] and (sdk is None or sdk <= max_sdk)
+    flags.update(probed or {})
+    return flags
+
+
+def unsupported_message(feature: str, sdk: Optional[int]) -> str:
+    min_sdk, description = FEATURE_MIN_SDK[feature]
+    max_sdk = FEATURE_MAX_SDK.get(feature)
+    if sdk is not None and max_sdk is not None and sdk > max_sdk:
+        return f"{description} only works up to {android_release(max_sdk)}; this device runs {android_release(sdk)}"
+    if sdk is None or sdk >= min_sdk:
+        return f"{description} is not available on this device (the command is missing from its firmware)"
+    return f"{description} needs {android_release(min_sdk)} or newer; this device runs {android_release(sdk)}"
+
+
+def compatibility_warnings(adb_version: Dict[str, Optional[str]], sdk: Optional[int]) -> List[str]:
+    """Human-readable warnings about outdated tools or an old device"""
+    warnings = []
+    tools = version_tuple(adb_version.get("platformToolsVersion"))
+    if tools is None:
+        warnings.append("Could not determine the platform-tools version; update Android SDK Platform Tools if commands fail")
+    elif tools < MIN_PLATFORM_TOOLS:
+        minimum = '.'.join(str(p) for p in MIN_PLATFORM_TOOLS)
+        warnings.append(f"Platform-tools {adb_version['platformToolsVersion']} is older than {minimum}; "
+                        "update Android SDK Platform Tools for reliable results")
+    if sdk is not None:
+        for feature, (min_sdk, _) in FEATURE_MIN_SDK.items():
+            if sdk < min_sdk:
+                warnings.append(unsupported_message(feature, sdk))
+    return warnings
@@ -0,0 +1,91 @@
+"""
+Config Watcher Module
+Polls user-editable config files and directories and reloads them when they
+change, so hand edits take effect without restarting the app
+"""
+import os
+import threading
+from pathlib import Path
+from typing import Callable, Dict, Optional, Tuple
+from events import EmitFn, no_emit
+
+
+# Seconds between polls; edits are picked up within this delay
+POLL_INTERVAL = 2.0
+
+
+def snapshot_path(path: Path) -> Optional[Tuple]:
+    """
+    Cheap change fingerprint: (mtime, size) of a file, or of every JSON file in a directory.
+
+    Returns None when the path does not exist.
+    """
+    try:
+        if path.is_dir():
+            return tuple(sorted(
+                (entry.name, entry.stat().st_mtime_ns, entry.stat().st_size)
+                for entry in os.scandir(path) if entry.name.endswith(".json")
+            ))
+        stat = path.stat()
+        return (stat.st_mtime_ns, stat.st_size)
+    except OSError:
+        return None
+
+
+class ConfigWatcher:
+    """Watch registered paths and call their reload hook on change"""
+
+    def __init__(self, emit_event: EmitFn = None):
+        self.emit_event = emit_event or no_emit
+        # name -> {"path": Path, "reload": callable, "snapshot": tuple|None}
+        self._targets: Dict[str, Dict] = {}
+        self._lock = threading.Lock()
+        self._stop = threading.Event()
+        self._thread: Optional[threading.Thread] = None
+
+    def watch(self, name: str, path, reload: Callable[[], Optional[Dict]]):
+        """
+        Register a file or directory.
+
+        `reload` re-reads it and returns a payload for the `config_changed`
+        event, or None when the content did not actually change (e.g. our own save).
+        """
+        path = Path(path)
+        with self._lock:
+            self._targets[name] = {"path": path, "reload": reload, "snapshot": snapshot_path(path)}
+
+    def check(self):
+        """Poll every target once, reloading the ones that changed"""
+        with self._lock:
+            targets = list(self._targets.items())
+        for name, target in targets:
+            current = snapshot_path(target["path"])
+            if current == target["snapshot"]:
+                continue
+            target["snapshot"] = current
+            try:
+                payload = target["reload"]()
+            except Exception as e:
+                self.emit_event("config_changed", {"name": name, "path": str(target["path"]),
+                                                   "success": False, "message": str(e)})
+                continue
+            if payload is not None:
+                self.emit_event("config_changed", {"name": name, "path": str(target["path"]),
+                                                   "success": True, **payload})
+
+    def start(self, interval_seconds: float = POLL_INTERVAL):
+        """Start polling in a background thread"""
+        if self._thread and self._thread.is_alive():
+            return
+        self._stop.clear()
+
+        def run():
+            while not self._stop.wait(interval_seconds):
+                self.check()
+
+        self._thread = threading.Thread(target=run, daemon=True)
+        self._thread.start()
+
+    def stop(self):
+        """Stop polling"""
+        self._stop.set()
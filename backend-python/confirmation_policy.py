@@ -0,0 +1,215 @@
+"""
+Confirmation Policy Module
+How firmly a removal must be confirmed, by the safety level of each package
+and the role of the technician. An organization deploys
+~/DebloatAI/confirmation_policy.json to lock down what junior techs can do,
+and the backend enforces it on uninstalls, data wipes, disables, undo,
+emergency recovery and batches whatever the UI shows. Without the file nothing beyond the app's own dialogs is required
+"""
+import copy
+import json
+import sys
+from pathlib import Path
+from typing import Callable, Dict, List, Optional
+from adb_operations import SAFETY_LEVELS
+
+
+# Weakest to strongest; a level also needs everything the ones before it need
+CONFIRMATION_LEVELS = ("none", "confirm", "typedPackage", "typedPhrase", "blocked")
+
+# uninstall: uninstall_package, wipe_package_data, uninstall jobs, optimizer and chat removals and
+# undone reinstalls; disable: trials and undone enables; recover: emergency_recover, which only
+# puts packages back, so it needs just the role's `batch` level whatever its size
+OPERATIONS = ("uninstall", "disable", "recover")
+BATCH_ONLY_OPERATIONS = ("recover",)
+
+DEFAULT_PHRASE = "I understand this can break the device"
+
+# Every role maps each safety level to a confirmation level. Optional:
+# "disable" with its own map for disabling (it is reversible), and "batch",
+# the least an operation on more than one package requires
+DEFAULT_POLICY = {
+    "role": "default",
+    # When true, the role can only be changed by editing the file
+    "roleLocked": False,
+    "phrase": DEFAULT_PHRASE,
+    "roles": {"default": {level: "none" for level in SAFETY_LEVELS}},
+}
+
+POLICY_FILE = "confirmation_policy.json"
+
+
+def _level_map_error(levels, where: str) -> Optional[str]:
+    if not isinstance(levels, dict):
+        return f"{where} must map safety levels to confirmation levels"
+    for safety, level in levels.items():
+        if safety not in SAFETY_LEVELS:
+            return f"{where} has an unknown safety level: {safety}"
+        if level not in CONFIRMATION_LEVELS:
+            return f"{where}.{safety} must be one of: {', '.join(CONFIRMATION_LEVELS)}"
+    missing = [safety for safety in SAFETY_LEVELS if safety not in levels]
+    return f"{where} is missing {', '.join(missing)}" if missing else None
+
+
+def validate_policy(policy) -> Optional[str]:
+    """Why a policy file is not usable, or None"""
+    if not isinstance(policy, dict):
+        return "The policy must be a JSON object"
+    roles = policy.get("roles")
+    if not isinstance(roles, dict) or not roles:
+        return "The policy must define at least one role"
+    for name, role in roles.items():
+        if not isinstance(role, dict):
+            return f"Role {name} must be an object"
+        error = _level_map_error({k: v for k, v in role.items() if k not in ("disable", "batch")}, f"roles.{name}")
+        if error:
+            return error
+        if "disable" in role:
+            error = _level_map_error(role["disable"], f"roles.{name}.disable")
+            if error:
+                return error
+        if "batch" in role and role["batch"] not in CONFIRMATION_LEVELS:
+            return f"roles.{name}.batch must be one of: {', '.join(CONFIRMATION_LEVELS)}"
+    if policy.get("role") not in roles:
+        return f"The active role {policy.get('role')} is not defined"
+    if not isinstance(policy.get("phrase", DEFAULT_PHRASE), str) or not policy.get("phrase", DEFAULT_PHRASE).strip():
+        return "phrase must be a non-empty text"
+    return None
+
+
+def strongest(levels: List[str]) -> str:
+    return max(levels, key=CONFIRMATION_LEVELS.index, default="none")
+
+
+def is_satisfied(requirement: Dict, confirmation: Optional[Dict]) -> bool:
+    """
+    Whether `confirmation` ({"confirmed", "typedPackages", "phrase"}) meets
+    `requirement`; a blocked requirement is never met
+    """
+    level = requirement["level"]
+    if level == "blocked":
+        return False
+    if level == "none":
+        return True
+    confirmation = confirmation if isinstance(confirmation, dict) else {}
+    if confirmation.get("confirmed") is not True:
+        return False
+    typed = {str(name).strip() for name in confirmation.get("typedPackages") or []}
+    if not set(requirement["packagesToType"]) <= typed:
+        return False
+    if level == "typedPhrase" and str(confirmation.get("phrase", "")).strip() != requirement["phrase"]:
+        return False
+    return True
+
+
+class ConfirmationPolicy:
+    """Load the policy file and check removals against the active role"""
+
+    def __init__(self, data_dir: str = None):
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.path = Path(data_dir) / POLICY_FILE
+        self._policy = self._load()
+
+    def _load(self, strict: bool = False) -> Dict:
+        """The policy file, or the default policy when it is missing (or invalid unless `strict`)"""
+        try:
+            with open(self.path, 'r', encoding='utf-8') as f:
+                policy = json.load(f)
+        except FileNotFoundError:
+            return copy.deepcopy(DEFAULT_POLICY)
+        except (OSError, json.JSONDecodeError) as e:
+            error = f"could not be read: {e}"
+        else:
+            error = validate_policy(policy)
+        if error:
+            if strict:
+                raise ValueError(f"{self.path.name}: {error}")
+            print(f"[Warning] Ignoring {self.path}: {error}", file=sys.stderr)
+            return copy.deepcopy(DEFAULT_POLICY)
+        return {**copy.deepcopy(DEFAULT_POLICY), **policy}
+
+    def reload(self) -> Optional[Dict]:
+        """Re-read the file after an edit; None when nothing changed, ValueError keeps the current policy"""
+        loaded = self._load(strict=True)
+        if loaded == self._policy:
+            return None
+        self._policy = loaded
+        return {"policy": self.get_policy()}
+
+    def get_policy(self) -> Dict:
+        """The active role, what it requires and the roles to choose from"""
+        role = self._policy["roles"][self._policy["role"]]
+        return {
+            "role": self._policy["role"],
+            "roles": list(self._policy["roles"]),
+            "roleLocked": bool(self._policy["roleLocked"]),
+            "levels": {safety: role[safety] for safety in SAFETY_LEVELS},
+            "disableLevels": {safety: role.get("disable", role)[safety] for safety in SAFETY_LEVELS},
+            "batch": role.get("batch", "none"),
+            "phrase": self._policy["phrase"],
+            "path": str(self.path),
+            "custom": self.path.exists(),
+        }
+
+    def set_role(self, role: str) -> Dict:
+        """Switch the active role, unless the policy locks it"""
+        if self._policy["roleLocked"]:
+            return {"success": False, "message": "The confirmation policy locks the role; ask your administrator"}
+        if role not in self._policy["roles"]:
+            return {"success": False, "message": f"Unknown role: {role} ({', '.join(self._policy['roles'])})"}
+        self._policy["role"] = role
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        with open(self.path, 'w', encoding='utf-8') as f:
+            json.dump(self._policy, f, indent=2)
+        return {"success": True, "message": f"Role set to {role}", "policy": self.get_policy()}
+
+    def requirement(self, operation: str, packages: List[str], classify: Callable[[str], str]) -> Dict:
+        """
+        What removing `packages` takes for the active role: the strongest
+        level over the packages (raised to the role's `batch` level for
+        several), the names to type and the packages the role may not touch
+        """
+        if operation not in OPERATIONS:
+            raise ValueError(f"Unknown operation: {operation} ({', '.join(OPERATIONS)})")
+        role = self._policy["roles"][self._policy["role"]]
+        if operation in BATCH_ONLY_OPERATIONS:
+            levels = {safety: "none" for safety in SAFETY_LEVELS}
+        else:
+            levels = role.get("disable", role) if operation == "disable" else role
+        packages = list(dict.fromkeys(p for p in packages if p))
+        per_package = []
+        for package in packages:
+            safety = classify(package)
+            per_package.append({"packageName": package, "safetyLevel": safety,
+                                "level": levels.get(safety, levels["Dangerous"])})
+        is_batch = len(packages) > 1 or operation in BATCH_ONLY_OPERATIONS
+        batch = role.get("batch", "none") if is_batch else "none"
+        level = strongest([p["level"] for p in per_package] + [batch])
+        # The batch level asks the same of every package in the batch, e.g. typing each name
+        effective = {p["packageName"]: strongest([p["level"], batch]) for p in per_package}
+        return {
+            "operation": operation,
+            "role": self._policy["role"],
+            "level": level,
+            "packages": per_package,
+            "packagesToType": [name for name, needed in effective.items() if needed in ("typedPackage", "typedPhrase")],
+            "phrase": self._policy["phrase"] if level == "typedPhrase" else None,
+            "blocked": [name for name, needed in effective.items() if needed == "blocked"],
+        }
+
+    def check(self, operation: str, packages: List[str], classify: Callable[[str], str],
+              confirmation: Optional[Dict]) -> Optional[Dict]:
+        """None when the removal may go ahead, else the failure result to return instead"""
+        requirement = self.requirement(operation, packages, classify)
+        if is_satisfied(requirement, confirmation):
+            return None
+        if requirement["level"] == "blocked":
+            return {"success": False, "blocked": True, "requirement": requirement,
+                    "message": f"The {requirement['role']} role may not {operation} "
+                               f"{', '.join(requirement['blocked']) or 'this'}"}
+        needs = {"confirm": "an explicit confirmation",
+                 "typedPackage": "the package names typed out",
+                 "typedPhrase": f"the phrase \"{requirement['phrase']}\" typed out"}[requirement["level"]]
+        return {"success": False, "confirmationRequired": True, "requirement": requirement,
+                "message": f"The {requirement['role']} role needs {needs} to {operation} this"}
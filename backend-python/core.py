@@ -0,0 +1,283 @@
+"""
+Backend Core
+Builds the long-lived backend modules and their background jobs without any
+tie to how commands arrive or where events go. main.py serves them to
+Electron over stdin/stdout; a CLI, a test or another frontend builds the
+same Services with its own event sink and calls the same modules
+"""
+import sys
+from pathlib import Path
+from adb_operations import ADBOperations, ADBError
+from ai_advisor import AIAdvisor
+from backup_manager import BackupManager
+from openclaw_integration import OpenClawIntegration
+from system_health import SystemHealth
+from settings_manager import SettingsManager
+from chat_context import DeviceContextBuilder
+from guardrails import analysis_conflict
+from label_resolver import LabelResolver
+from package_cache import PackageCache
+from package_stream import PackageStreamer
+from analysis_cache import AnalysisCache
+import network
+import formatting
+from privacy_report import PrivacyAnalyzer
+from device_tweaks import DeviceTweaks
+from device_restart import DeviceRestarter
+from trial_manager import TrialManager
+from selection_rules import SelectionValidator
+from tutorial import Tutorial
+from raw_dump import RawDumpViewer
+from parser_diagnostics import ParserDiagnostics
+from prefetch import PrefetchPipeline
+from stay_awake import StayAwake
+from job_journal import JobJournal
+from weekly_digest import WeeklyDigest
+from charging_sessions import ChargingTracker
+from emergency_recovery import EmergencyRecovery
+from cache_policy import Revalidator
+from optimizer import Optimizer
+from undo_manager import UndoStack
+from config_watcher import ConfigWatcher
+from package_watcher import PackageWatcher
+from unused_apps import UnusedApps
+from events import EmitFn, EventReplayBuffer
+from device_policy import DevicePolicy
+from session_log import SessionLog
+from crash_reporter import CrashReporter
+from usb_permissions import UsbPermissions
+from screen_mirror import ScreenMirror
+from guided_actions import GuidedActions
+from remote_server import EventFeed, RemoteServer
+from confirmation_policy import ConfirmationPolicy
+from oem_database import OemDatabase, USER_DATABASE_FILE
+from profiles import ProfileStore
+from shutdown import ShutdownManager
+
+
+# Written on every shutdown, so the last session can be looked at after a crash report or a restart
+LAST_SESSION_LOG = "last_session.txt"
+
+
+class Services:
+    """Long-lived backend modules shared by every command"""
+
+    def __init__(self, **modules):
+        self.__dict__.update(modules)
+
+
+def build_services(emit_event: EmitFn, session_log: SessionLog = None, event_replay: EventReplayBuffer = None,
+                   shell_sessions: bool = True, event_feed: EventFeed = None) -> Services:
+    """
+    Initialise every backend module once. Events of all modules go to
+    `emit_event`, which should also record them in `event_replay` for
+    subscribe_with_replay, and publish them to `event_feed` for remote
+    clients; background jobs are not running until start_background
+    and `services.shutdown.run()` stops everything again
+    """
+    adb = ADBOperations(shell_sessions=shell_sessions,
+                        oem_db=OemDatabase(user_file=Path.home() / "DebloatAI" / USER_DATABASE_FILE))
+    settings = SettingsManager()
+    session_log = session_log or SessionLog()
+    network.configure(lambda key: settings.get("network", key))
+    formatting.configure(lambda: settings.get("display", "locale"))
+    stay_awake = StayAwake(adb, enabled=lambda: settings.get("devices", "stayAwakeDuringJobs"))
+    backup_mgr = BackupManager(adb_operations=adb, emit_event=emit_event, nickname_for=settings.device_nickname,
+                               stay_awake=stay_awake)
+    labels = LabelResolver(adb)
+    analyses = AnalysisCache()
+    diagnostics = ParserDiagnostics(emit_event)
+    revalidator = Revalidator(emit_event)
+    health = SystemHealth(adb, diagnostics, revalidator)
+    context_builder = DeviceContextBuilder(adb, labels, analyses, health)
+    try:
+        advisor = AIAdvisor(provider="perplexity", settings=settings, context_builder=context_builder)
+    except Exception as e:
+        print(f"[Warning] AI advisor init failed: {e}", file=sys.stderr)
+        advisor = AIAdvisor.unconfigured(settings, context_builder)
+    
+    tweaks = DeviceTweaks(adb)
+    undo = UndoStack(adb, tweaks)
+
+    policy = DevicePolicy(adb)
+    package_cache = PackageCache(adb, labels, policy, analyses, revalidator)
+
+    # Initialize OpenClaw integration
+    openclaw = OpenClawIntegration(adb, emit_event, undo, stay_awake, package_cache)
+    trials = TrialManager(adb, undo, emit_event)
+    digests = WeeklyDigest(adb, health, emit_event)
+    restarter = DeviceRestarter(adb)
+    services = Services(
+        adb=adb,
+        backup_mgr=backup_mgr,
+        advisor=advisor,
+        openclaw=openclaw,
+        health=health,
+        settings=settings,
+        labels=labels,
+        package_cache=package_cache,
+        privacy=PrivacyAnalyzer(adb),
+        tweaks=tweaks,
+        optimizer=Optimizer(adb, health, tweaks, package_cache, undo, emit_event, stay_awake),
+        undo=undo,
+        policy=policy,
+        session_log=session_log,
+        crashes=CrashReporter(lambda key: settings.get("diagnostics", key), session_log,
+                              known_serials=lambda: [*adb.get_device_states(), *settings.get("devices", "nicknames")]),
+        usb_permissions=UsbPermissions(adb),
+        screen_mirror=ScreenMirror(adb),
+        event_replay=event_replay or EventReplayBuffer(),
+        streamer=PackageStreamer(emit_event),
+        analyses=analyses,
+        restarter=restarter,
+        trials=trials,
+        selection=SelectionValidator(adb),
+        tutorial=Tutorial(adb, package_cache, undo),
+        dumps=RawDumpViewer(adb),
+        guided=GuidedActions(adb),
+        remote=RemoteServer(lambda key: settings.get("remote", key), event_feed),
+        confirmations=ConfirmationPolicy(),
+        profiles=ProfileStore(),
+        diagnostics=diagnostics,
+        revalidator=revalidator,
+        prefetch=PrefetchPipeline(adb, package_cache, health, emit_event),
+        stay_awake=stay_awake,
+        jobs=JobJournal(adb),
+        digests=digests,
+        charging=ChargingTracker(adb, emit_event),
+        recovery=EmergencyRecovery(adb, undo, backup_mgr, trials, restarter),
+        unused_apps=UnusedApps(adb, package_cache, restarter),
+        package_watcher=PackageWatcher(adb, package_cache, emit_event),
+        config_watcher=ConfigWatcher(emit_event),
+        emit_event=emit_event,
+        shutdown=ShutdownManager(),
+    )
+    register_shutdown(services, services.shutdown)
+    return services
+
+
+def start_background(services: Services):
+    """
+    Start the scheduled and watching jobs: backup retention, trials, digests,
+    charging sessions, package and config watchers
+    """
+    settings = services.settings
+
+    def reload_settings():
+        remote_before = settings.get_settings()["remote"]
+        changed = settings.reload()
+        if changed is not None and changed["settings"]["remote"] != remote_before:
+            # An edited remote section starts, moves or stops the server as update_settings does
+            services.remote.apply()
+        return changed
+
+    def reload_package_database():
+        changed = services.adb.oem_db.reload()
+        if changed is not None:
+            # Cached listings carry the old safety levels
+            services.package_cache.invalidate()
+        return changed
+
+    services.backup_mgr.start_maintenance(lambda: backup_retention(settings))
+    services.trials.start_scheduler()
+    services.digests.start_scheduler()
+    services.charging.start_scheduler()
+    services.package_watcher.start(lambda: settings.get("devices", "watchPackagesSeconds"))
+    services.config_watcher.watch("settings", settings.path, reload_settings)
+    services.config_watcher.watch("confirmation_policy", services.confirmations.path, services.confirmations.reload)
+    services.config_watcher.watch("package_database", services.adb.oem_db.user_file, reload_package_database)
+    services.config_watcher.watch("profiles", services.profiles.path, services.profiles.reload)
+    services.config_watcher.start()
+
+
+def register_shutdown(services: Services, shutdown: ShutdownManager, reports_dir: Path = None):
+    """
+    Exit steps of the backend, in order: remote clients are cut off, running jobs are cancelled before
+    the schedulers stop, the session log is written while the device may
+    still answer, and temporary device settings are put back before the
+    adb shell sessions close
+    """
+    reports_dir = reports_dir or Path.home() / "DebloatAI" / "reports"
+
+    def write_session_log():
+        reports_dir.mkdir(parents=True, exist_ok=True)
+        (reports_dir / LAST_SESSION_LOG).write_text(services.session_log.render(), encoding="utf-8")
+
+    shutdown.register("stop remote server", services.remote.stop)
+    shutdown.register("cancel full backup", services.backup_mgr.cancel_full_backup)
+    shutdown.register("cancel package streams", services.streamer.cancel_all)
+    shutdown.register("stop health monitor", services.health.stop_monitor)
+    shutdown.register("stop config watcher", services.config_watcher.stop)
+    shutdown.register("stop backup maintenance", services.backup_mgr.stop_maintenance)
+    shutdown.register("stop trial scheduler", services.trials.stop_scheduler)
+    shutdown.register("stop digest scheduler", services.digests.stop_scheduler)
+    shutdown.register("stop charging tracker", services.charging.stop_scheduler)
+    shutdown.register("stop package watcher", services.package_watcher.stop)
+    shutdown.register("close screen mirrors", services.screen_mirror.stop_all)
+    shutdown.register("write session log", write_session_log)
+    shutdown.register("restore stay-awake settings", services.stay_awake.release_all)
+    shutdown.register("close adb sessions", services.adb.close_sessions)
+
+
+def list_device_packages(services, package_type):
+    """Fresh package listing with labels and management flags; [] without a device"""
+    try:
+        if package_type == "all":
+            return services.package_cache.refresh()
+        packages = services.adb.list_packages(package_type)
+        packages = services.policy.apply_management(services.labels.apply_cached_labels(packages))
+        return services.analyses.apply(packages)
+    except ADBError:
+        return []
+
+
+def analyze_and_record(services, package_name):
+    """AI analysis of a package, remembered as its aiRisk in listings"""
+    analysis = services.advisor.analyze_package(package_name)
+    risk = services.analyses.record(package_name, analysis, services.advisor._current_model())
+    if risk:
+        services.package_cache.update_package(package_name, aiRisk=risk)
+        conflict = analysis_conflict(risk, services.adb.safety_rater()(package_name))
+        if conflict:
+            analysis["conflict"] = conflict
+    return analysis
+
+
+def revalidate_analyses(services, packages, limit=3):
+    """Re-analyze a few packages whose cached AI verdict is stale, in the background"""
+    if network.is_offline() or not services.advisor.api_key:
+        return
+    stale = [p["packageName"] for p in packages if (p.get("aiRisk") or {}).get("stale")]
+    for package_name in stale[:limit]:
+        services.revalidator.revalidate("analysis", package_name,
+                                        lambda name=package_name: analyze_and_record(services, name))
+
+
+def cache_diagnostics(services):
+    """Hit/miss counts, sizes, ages and memory of every backend cache"""
+    caches = [
+        services.package_cache.cache_diagnostics(),
+        services.health.cache_diagnostics(),
+        services.analyses.cache_diagnostics(),
+        services.labels.cache_diagnostics(),
+        services.dumps.cache_diagnostics(),
+        services.adb.profile_cache_diagnostics(),
+    ]
+    total = sum(cache["memoryBytes"] for cache in caches)
+    return {
+        "caches": caches,
+        "totalMemoryBytes": total,
+        "refreshing": services.revalidator.refreshing(),
+        "formatted": formatting.formatted_fields({"totalMemoryBytes": total}),
+    }
+
+
+def confirmation_refusal(services, operation, packages, confirmation):
+    """The failure to return when `confirmation` falls short of the policy for removing `packages`, else None"""
+    packages = [p for p in packages or [] if isinstance(p, str)]
+    return services.confirmations.check(operation, packages, services.adb.safety_rater(), confirmation)
+
+
+def backup_retention(settings):
+    """Current (keepPerDevice, maxAgeDays) backup retention policy"""
+    return settings.get("backups", "keepPerDevice"), settings.get("backups", "maxAgeDays")
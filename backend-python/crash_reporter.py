@@ -0,0 +1,190 @@
+"""
+Crash Reporter Module
+Opt-in crash reports: with `diagnostics.crashReports` on, an exception that
+escapes a command, a background thread or the backend itself is written to
+~/DebloatAI/crash_reports/ with its traceback and the end of the session
+log, and posted to `diagnostics.crashReportUrl` when one is set. Reports
+hold no device serial (serial arguments and every serial adb sees or has a
+nickname for are blanked out), and home directories are shortened to ~
+"""
+import json
+import platform
+import re
+import sys
+import threading
+import time
+import traceback
+import uuid
+from pathlib import Path
+from typing import Any, Callable, Dict, Iterable, List, Optional
+
+import network
+from session_log import SessionLog
+
+
+# Oldest reports are deleted beyond this many
+MAX_CRASH_REPORTS = 30
+
+# Lines from the end of the session log kept in a report
+RECENT_LOG_LINES = 40
+
+# Seconds allowed for posting one report
+SUBMIT_TIMEOUT = 15
+
+# Device serials passed as command arguments in the session log
+SERIAL_ARG = re.compile(r'\bserial=\S+')
+
+# Known serials shorter than this are left alone, as they would blank out ordinary words
+MIN_SERIAL_LENGTH = 4
+
+# Fields only meaningful on this computer, left out of submitted reports
+LOCAL_FIELDS = ("path", "submitted", "submitError")
+
+
+def scrub(text: str, serials: Iterable[str] = ()) -> str:
+    """`text` with the user's home directory shortened to ~ and the given device serials blanked out"""
+    home = str(Path.home())
+    text = text.replace(home, "~") if home not in ("", "/") else text
+    for serial in sorted(serials, key=len, reverse=True):
+        text = text.replace(serial, "(serial)")
+    return text
+
+
+class CrashReporter:
+    """Write, list and optionally submit crash reports"""
+
+    def __init__(self, setting: Callable[[str], Any], session_log: SessionLog, data_dir: str = None,
+                 known_serials: Callable[[], Iterable[str]] = None):
+        # Reads a `diagnostics.*` setting by key
+        self.setting = setting
+        self.session_log = session_log
+        # Serials that may appear in free text, e.g. "Mirroring R58M..." or a nickname message
+        self.known_serials = known_serials or (lambda: ())
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.dir = Path(data_dir) / "crash_reports"
+        self._lock = threading.Lock()
+
+    def enabled(self) -> bool:
+        return bool(self.setting("crashReports"))
+
+    def install(self):
+        """Report exceptions that end a background thread or the backend process"""
+        previous_thread_hook = threading.excepthook
+        previous_hook = sys.excepthook
+
+        def thread_hook(args):
+            if args.exc_value is not None and not isinstance(args.exc_value, SystemExit):
+                self.capture(args.exc_value, "thread", thread=args.thread.name if args.thread else None)
+            previous_thread_hook(args)
+
+        def process_hook(exc_type, exc_value, exc_traceback):
+            if not issubclass(exc_type, KeyboardInterrupt):
+                self.capture(exc_value, "fatal", wait=True)
+            previous_hook(exc_type, exc_value, exc_traceback)
+
+        threading.excepthook = thread_hook
+        sys.excepthook = process_hook
+
+    def capture(self, error: BaseException, kind: str, command: Optional[str] = None,
+                thread: Optional[str] = None, wait: bool = False) -> Optional[Dict]:
+        """
+        Write a report of `error` ("command", "thread", "main loop" or
+        "fatal"), None when crash reports are off. Submission runs in the
+        background unless `wait` (the process is about to exit).
+        """
+        try:
+            if not self.enabled():
+                return None
+            report = self._build(error, kind, command, thread)
+            with self._lock:
+                self._write(report)
+                self._prune()
+            url = self.setting("crashReportUrl")
+            if url:
+                if wait:
+                    self._submit(report, url)
+                else:
+                    threading.Thread(target=self._submit, args=(report, url), daemon=True).start()
+            return report
+        except Exception as e:
+            # Reporting must never add a second failure to the first
+            print(f"[Crash reporter] Could not write a report: {e}", file=sys.stderr)
+            return None
+
+    def _serials(self) -> List[str]:
+        try:
+            return [s for s in set(self.known_serials()) if isinstance(s, str) and len(s) >= MIN_SERIAL_LENGTH]
+        except Exception:
+            # No device list (adb missing or hung): serial arguments are still blanked
+            return []
+
+    def _build(self, error: BaseException, kind: str, command: Optional[str], thread: Optional[str]) -> Dict:
+        log = self.session_log.render().rstrip("\n").split("\n")
+        serials = self._serials()
+        report_id = uuid.uuid4().hex[:12]
+        return {
+            "id": report_id,
+            "time": time.time(),
+            "kind": kind,
+            "command": command,
+            "thread": thread,
+            "exceptionType": type(error).__name__,
+            "message": scrub(str(error), serials),
+            "traceback": scrub("".join(traceback.format_exception(type(error), error, error.__traceback__)),
+                               serials),
+            "recentLog": [scrub(SERIAL_ARG.sub("serial=(omitted)", line), serials)
+                          for line in log[-RECENT_LOG_LINES:]],
+            "runtime": {
+                "python": platform.python_version(),
+                "os": platform.system(),
+                "osRelease": platform.release(),
+                "packaged": bool(getattr(sys, "frozen", False)),
+            },
+            "path": str(self.dir / f"crash_{time.strftime('%Y%m%d_%H%M%S')}_{report_id}.json"),
+            "submitted": False,
+        }
+
+    def _write(self, report: Dict):
+        self.dir.mkdir(parents=True, exist_ok=True)
+        with open(report["path"], "w", encoding="utf-8") as f:
+            json.dump(report, f, indent=2)
+
+    def _prune(self):
+        reports = sorted(self.dir.glob("crash_*.json"))
+        for old in reports[:max(0, len(reports) - MAX_CRASH_REPORTS)]:
+            old.unlink(missing_ok=True)
+
+    def _submit(self, report: Dict, url: str):
+        """Post a report without its local fields; the outcome is saved in the file"""
+        try:
+            response = network.post(url, json={k: v for k, v in report.items() if k not in LOCAL_FIELDS},
+                                    timeout=SUBMIT_TIMEOUT)
+            response.raise_for_status()
+            report["submitted"] = True
+            report.pop("submitError", None)
+        except Exception as e:
+            report["submitError"] = network.describe_error(e)
+        with self._lock:
+            if Path(report["path"]).exists():
+                self._write(report)
+
+    def list_reports(self) -> List[Dict]:
+        """Saved reports without traceback and log, most recent first"""
+        reports = []
+        for path in sorted(self.dir.glob("crash_*.json"), reverse=True):
+            try:
+                with open(path, encoding="utf-8") as f:
+                    report = json.load(f)
+            except (OSError, json.JSONDecodeError):
+                continue
+            reports.append({k: v for k, v in report.items() if k not in ("traceback", "recentLog")})
+        return reports
+
+    def clear(self) -> Dict:
+        with self._lock:
+            removed = 0
+            for path in self.dir.glob("crash_*.json"):
+                path.unlink(missing_ok=True)
+                removed += 1
+        return {"success": True, "removed": removed, "message": f"Deleted {removed} crash report(s)"}
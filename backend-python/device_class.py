@@ -0,0 +1,198 @@
+"""
+Device Class Module
+Tells phones from tablets, Android TV boxes, Wear OS watches and cars, and
+holds the packages whose safety or bloat status differs by class: the TV
+launcher is what keeps a TV box usable, while its recommendation rows and
+screensavers are the usual cause of a sluggish one
+"""
+from typing import Dict, List, Optional, Set
+
+
+DEVICE_CLASSES = ("phone", "tablet", "tv", "watch", "automotive")
+
+CLASS_LABELS = {
+    "phone": "Phone",
+    "tablet": "Tablet",
+    "tv": "Android TV",
+    "watch": "Wear OS watch",
+    "automotive": "Android Automotive",
+}
+
+# One shell call: the build characteristics, then the system feature list
+DETECTION_SCRIPT = "getprop ro.build.characteristics; pm list features"
+
+# System feature -> class, checked in this order (a TV may also declare a tablet-like screen)
+CLASS_FEATURES = [
+    ("android.hardware.type.automotive", "automotive"),
+    ("android.hardware.type.watch", "watch"),
+    ("android.software.leanback", "tv"),
+    ("android.hardware.type.television", "tv"),
+]
+
+# ro.build.characteristics word -> class, for ROMs that do not declare the features
+CLASS_CHARACTERISTICS = [
+    ("automotive", "automotive"),
+    ("watch", "watch"),
+    ("tv", "tv"),
+    ("tablet", "tablet"),
+]
+
+# class -> {package: safety level}; replaces the generic level on that class only
+CLASS_SAFETY_LEVELS: Dict[str, Dict[str, str]] = {
+    "tv": {
+        "com.google.android.tvlauncher": "Dangerous",
+        "com.google.android.apps.tv.launcherx": "Dangerous",
+        "com.google.android.leanbacklauncher": "Dangerous",
+        "com.android.tv.settings": "Dangerous",
+        "com.google.android.tv.remote.service": "Dangerous",  # remote pairing
+        "com.google.android.tungsten.setupwraith": "Expert",  # setup wizard
+        "com.google.android.katniss": "Caution",  # voice search
+        "com.google.android.tvrecommendations": "Safe",
+        "com.google.android.leanbacklauncher.recommendations": "Safe",
+        "com.google.android.backdrop": "Safe",
+        "com.google.android.youtube.tv": "Safe",
+        "com.google.android.play.games": "Safe",
+        "com.google.android.videos": "Safe",
+        "com.google.android.music": "Safe",
+    },
+    "watch": {
+        "com.google.android.wearable.app": "Dangerous",  # watch face and home
+        "com.google.android.apps.wearable.settings": "Dangerous",
+        "com.google.android.wearable.ambient": "Expert",
+        "com.google.android.apps.walletnfcrel": "Caution",
+        "com.google.android.apps.wearable.retailattractloop": "Safe",
+        "com.google.android.apps.fitness": "Safe",
+        "com.google.android.apps.maps": "Safe",
+        "com.google.android.clockwork.flashlight": "Safe",
+    },
+    "automotive": {
+        "com.android.car.carlauncher": "Dangerous",
+        "com.android.car.settings": "Dangerous",
+        "com.android.car.media": "Expert",
+        "com.google.android.apps.automotive.templates.host": "Expert",
+        "com.android.car.dialer": "Caution",
+        "com.google.android.car.kitchensink": "Safe",  # test app left in some builds
+    },
+}
+
+# class -> presets: packages users of that class commonly remove together
+CLASS_PRESETS: Dict[str, List[Dict]] = {
+    "tv": [
+        {
+            "id": "tv_launcher_extras",
+            "name": "Home screen recommendations",
+            "description": "Recommendation rows and promoted content the TV launcher keeps refreshing in the background",
+            "packages": ["com.google.android.tvrecommendations", "com.google.android.leanbacklauncher.recommendations",
+                         "com.google.android.apps.tv.dreamx", "com.google.android.backdrop"],
+        },
+        {
+            "id": "tv_preloaded_apps",
+            "name": "Preloaded streaming and media apps",
+            "description": "Store, games and media apps shipped with the box; reinstallable from the Play Store",
+            "packages": ["com.google.android.play.games", "com.google.android.videos", "com.google.android.music",
+                         "com.google.android.youtube.tvmusic", "com.netflix.ninja", "com.amazon.amazonvideo.livingroom",
+                         "com.disney.disneyplus", "com.spotify.tv.android"],
+        },
+        {
+            "id": "tv_vendor_feedback",
+            "name": "Usage reporting and feedback",
+            "description": "Vendor analytics and feedback services",
+            "packages": ["com.google.android.feedback", "com.google.android.tv.bugreportsender",
+                         "com.android.providers.tv.feedback", "com.nvidia.shieldtech.feedback"],
+        },
+    ],
+    "watch": [
+        {
+            "id": "watch_retail_demo",
+            "name": "Store demo mode",
+            "description": "The attract loop shown on display units",
+            "packages": ["com.google.android.apps.wearable.retailattractloop"],
+        },
+        {
+            "id": "watch_extra_apps",
+            "name": "Optional watch apps",
+            "description": "Apps that are also on the paired phone; the watch keeps running without them",
+            "packages": ["com.google.android.apps.fitness", "com.google.android.apps.maps",
+                         "com.google.android.apps.youtube.music", "com.google.android.keep",
+                         "com.google.android.clockwork.flashlight"],
+        },
+    ],
+    "tablet": [
+        {
+            "id": "tablet_phone_features",
+            "name": "Phone features on a Wi-Fi tablet",
+            "description": "Calling, SMS and SIM services a tablet without a SIM slot cannot use",
+            "packages": ["com.android.dialer", "com.google.android.dialer", "com.android.mms",
+                         "com.android.stk", "com.android.cellbroadcastreceiver"],
+        },
+        {
+            "id": "tablet_kids_mode",
+            "name": "Kids mode",
+            "description": "OEM kids launchers and their content stores",
+            "packages": ["com.samsung.android.kidsinstaller", "com.sec.android.app.kidshome",
+                         "com.google.android.apps.kids.home"],
+        },
+    ],
+    "automotive": [
+        {
+            "id": "automotive_test_apps",
+            "name": "Developer test apps",
+            "description": "Test and demo apps some head units ship with",
+            "packages": ["com.google.android.car.kitchensink", "com.android.car.developeroptions"],
+        },
+    ],
+}
+
+
+def parse_features(output: str) -> Set[str]:
+    """Feature names from `pm list features` (`feature:android.software.leanback`)"""
+    return {line[len("feature:"):].split('=', 1)[0].strip() for line in output.split('\n')
+            if line.startswith("feature:")}
+
+
+def detect_device_class(characteristics: str, features: Set[str]) -> str:
+    """Class of a device from ro.build.characteristics and its system features; phone by default"""
+    for feature, device_class in CLASS_FEATURES:
+        if feature in features:
+            return device_class
+    words = {word.strip().lower() for word in characteristics.split(',')}
+    for word, device_class in CLASS_CHARACTERISTICS:
+        if word in words:
+            return device_class
+    return "phone"
+
+
+def parse_detection_output(output: str) -> Dict:
+    """{"deviceClass", "characteristics", "features"} from the output of DETECTION_SCRIPT"""
+    lines = output.split('\n')
+    characteristics = lines[0].strip() if lines and not lines[0].startswith("feature:") else ""
+    features = parse_features(output)
+    return {
+        "deviceClass": detect_device_class(characteristics, features),
+        "characteristics": characteristics,
+        # Only the features that decide the class, the full list runs to hundreds
+        "features": sorted(feature for feature, _ in CLASS_FEATURES if feature in features),
+    }
+
+
+def class_safety_level(device_class: Optional[str], package_name: str) -> Optional[str]:
+    """Safety level of a package on a device class, None when the generic rules apply"""
+    return CLASS_SAFETY_LEVELS.get(device_class or "", {}).get(package_name)
+
+
+def filter_presets(presets: List[Dict], installed: Optional[Set[str]] = None, **fields) -> List[Dict]:
+    """
+    Copies of `presets` with `fields` added; with `installed`, each lists
+    only the installed packages and presets with none installed are left out
+    """
+    result = []
+    for preset in presets:
+        packages = preset["packages"] if installed is None else [p for p in preset["packages"] if p in installed]
+        if packages:
+            result.append({**preset, **fields, "packages": packages})
+    return result
+
+
+def presets_for(device_class: str, installed: Optional[Set[str]] = None) -> List[Dict]:
+    """Presets of a class, limited to `installed` packages when given"""
+    return filter_presets(CLASS_PRESETS.get(device_class, []), installed, deviceClass=device_class)
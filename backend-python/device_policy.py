@@ -0,0 +1,142 @@
+"""
+Device Policy Module
+Detects device owners / work profiles (MDM) and the packages they control,
+so managed apps are shown as non-removable instead of failing on uninstall
+"""
+import re
+from typing import Dict, List, Set
+from adb_operations import ADBOperations, ADBError
+
+
+# `dpm list-owners` (Android 10+): "User 0: admin=com.mdm/.AdminReceiver,DeviceOwner,Affiliated"
+LIST_OWNERS_PATTERN = re.compile(r'User\s+(\d+):\s*admin=([\w.]+)/([\w.$]+)((?:,\w+)*)')
+# `dumpsys device_policy` sections: "Device Owner:" / "Profile Owner (User 10):" then "admin=ComponentInfo{pkg/cls}"
+DUMPSYS_OWNER_HEADER = re.compile(r'^\s*(Device Owner|Profile Owner)(?: \(User (\d+)\))?:?\s*$')
+COMPONENT_INFO = re.compile(r'ComponentInfo\{([\w.]+)/([\w.$]+)\}')
+ADMIN_ENTRY = re.compile(r'^\s+(?:ComponentInfo\{)?([\w.]+)/([\w.$]+)\}?:\s*$')
+INSTALLER_PATTERN = re.compile(r'^package:(\S+)\s+installer=(\S+)', re.MULTILINE)
+
+
+def parse_list_owners(output: str) -> List[Dict]:
+    """Parse `dpm list-owners` into [{userId, packageName, component, type}]"""
+    owners = []
+    for user, package, component, flags in LIST_OWNERS_PATTERN.findall(output):
+        flags = flags.strip(',').split(',') if flags else []
+        owner_type = "deviceOwner" if "DeviceOwner" in flags else "profileOwner"
+        owners.append({"userId": int(user), "packageName": package,
+                       "component": f"{package}/{component}", "type": owner_type})
+    return owners
+
+
+def parse_dumpsys_owners(output: str) -> List[Dict]:
+    """Fallback for Android 9 and older: owners from `dumpsys device_policy`"""
+    owners = []
+    pending = None
+    for line in output.split('\n'):
+        header = DUMPSYS_OWNER_HEADER.match(line)
+        if header:
+            kind, user = header.groups()
+            pending = {"type": "deviceOwner" if kind == "Device Owner" else "profileOwner",
+                       "userId": int(user) if user else 0}
+            continue
+        if pending is not None and "admin=" in line:
+            match = COMPONENT_INFO.search(line)
+            if match:
+                package, component = match.groups()
+                owners.append({**pending, "packageName": package, "component": f"{package}/{component}"})
+            pending = None
+    return owners
+
+
+def parse_active_admins(output: str) -> Set[str]:
+    """
+    Packages with an enabled device admin, from the `Enabled Device Admins`
+    sections of `dumpsys device_policy` (entries are `pkg/.Receiver:` or
+    `ComponentInfo{pkg/cls}:` depending on the Android version)
+    """
+    admins = set()
+    header_indent = None
+    for line in output.split('\n'):
+        indent = len(line) - len(line.lstrip())
+        if "Enabled Device Admins" in line:
+            header_indent = indent
+            continue
+        if header_indent is None or not line.strip():
+            continue
+        if indent <= header_indent:
+            header_indent = None
+            continue
+        match = ADMIN_ENTRY.match(line)
+        if match:
+            admins.add(match.group(1))
+    return admins
+
+
+def parse_installers(output: str) -> Dict[str, str]:
+    """Parse `pm list packages -i` into package -> installer"""
+    return {package: installer for package, installer in INSTALLER_PATTERN.findall(output) if installer != "null"}
+
+
+class DevicePolicy:
+    """Work out which packages are controlled by device management"""
+
+    def __init__(self, adb_operations: ADBOperations):
+        self.adb = adb_operations
+
+    def get_management_state(self) -> Dict:
+        """
+        Owners and managed packages of the connected device.
+
+        Managed packages are the owner/admin apps themselves plus every app
+        an owner installed.
+        """
+        policy_dump = ""
+        try:
+            policy_dump = self.adb.shell("dumpsys device_policy", timeout=20)
+        except ADBError:
+            pass
+
+        owners = []
+        try:
+            owners = parse_list_owners(self.adb.shell("dpm list-owners", timeout=15))
+        except ADBError:
+            pass
+        if not owners:
+            owners = parse_dumpsys_owners(policy_dump)
+
+        managed: Dict[str, str] = {}
+        owner_packages = {owner["packageName"] for owner in owners}
+        for package in owner_packages:
+            managed[package] = "Device management app"
+        for package in parse_active_admins(policy_dump) - owner_packages:
+            if owners:  # Plain device admins (e.g. Find My Device) only matter on managed devices
+                managed[package] = "Active device administrator"
+
+        if owner_packages:
+            try:
+                installers = parse_installers(self.adb.shell("pm list packages -i", timeout=30))
+            except ADBError:
+                installers = {}
+            for package, installer in installers.items():
+                if installer in owner_packages and package not in managed:
+                    managed[package] = f"Installed by device management ({installer})"
+
+        return {
+            "managed": bool(owners),
+            "deviceOwner": next((o for o in owners if o["type"] == "deviceOwner"), None),
+            "profileOwners": [o for o in owners if o["type"] == "profileOwner"],
+            "managedPackages": managed,
+        }
+
+    def apply_management(self, packages: List[Dict]) -> List[Dict]:
+        """Mark managed packages as non-removable in a listing"""
+        try:
+            managed = self.get_management_state()["managedPackages"]
+        except ADBError:
+            return packages
+        for pkg in packages:
+            reason = managed.get(pkg["packageName"])
+            if reason:
+                pkg["removable"] = False
+                pkg["managedReason"] = reason
+        return packages
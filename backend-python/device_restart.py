@@ -0,0 +1,101 @@
+"""
+Device Restart Module
+Makes changes take effect without waiting for the user to reboot: restart
+SystemUI or the launcher after disabling their components, restart the
+Android framework (soft reboot) or reboot the whole device
+"""
+from typing import Dict, Optional
+from adb_operations import ADBOperations, ADBError, UnsupportedFeatureError
+
+
+SYSTEM_UI_PACKAGE = "com.android.systemui"
+
+HOME_INTENT = "-a android.intent.action.MAIN -c android.intent.category.HOME"
+
+# mode -> `adb reboot` argument
+REBOOT_MODES = {"system": None, "recovery": "recovery", "bootloader": "bootloader"}
+
+SOFT_REBOOT_WARNING = "Restarting the Android framework closes every app, like a reboot without the boot screen."
+REBOOT_WARNING = "Rebooting disconnects the device until it has started again."
+
+
+def parse_home_activity(output: str) -> Optional[str]:
+    """
+    Package of the default launcher from `cmd package resolve-activity
+    --brief` with the HOME intent; its last line is "package/activity"
+    """
+    lines = [line.strip() for line in output.strip().split('\n') if line.strip()]
+    if not lines or '/' not in lines[-1]:
+        return None
+    return lines[-1].split('/', 1)[0]
+
+
+def confirmation_needed(message: str) -> Dict:
+    return {"success": False, "confirmationRequired": True, "message": message}
+
+
+class DeviceRestarter:
+    """Restart device processes or the device itself"""
+
+    def __init__(self, adb_operations: ADBOperations):
+        self.adb = adb_operations
+
+    def restart_system_ui(self) -> Dict:
+        """Restart SystemUI (status bar, quick settings, navigation)"""
+        try:
+            self.adb.require("am_crash")
+            # SystemUI is persistent: force-stop leaves it running, a crash restarts it at once
+            output = self.adb.shell(f"am crash {SYSTEM_UI_PACKAGE}", timeout=15)
+        except UnsupportedFeatureError as e:
+            return {"success": False, "unsupported": True, "message": str(e)}
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        if "error" in output.lower():
+            return {"success": False, "message": output.strip()}
+        return {"success": True, "message": "SystemUI restarted"}
+
+    def get_launcher_package(self) -> Optional[str]:
+        """Package of the default home app, None if the device cannot tell"""
+        if not self.adb.supports("cmd_package"):
+            return None
+        return parse_home_activity(self.adb.shell(f"cmd package resolve-activity --brief {HOME_INTENT}", timeout=15))
+
+    def restart_launcher(self) -> Dict:
+        """Stop the home app and bring it back by going home"""
+        try:
+            launcher = self.get_launcher_package()
+            if launcher is None:
+                return {"success": False, "message": "Could not find the default launcher"}
+            self.adb.shell(f"am force-stop {launcher}", timeout=15)
+            self.adb.shell(f"am start {HOME_INTENT}", timeout=15)
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        return {"success": True, "message": f"Launcher {launcher} restarted", "packageName": launcher}
+
+    def soft_reboot(self, confirmed: bool = False) -> Dict:
+        """Restart the Android framework without rebooting the kernel (`am restart`)"""
+        if not confirmed:
+            return confirmation_needed(SOFT_REBOOT_WARNING)
+        try:
+            self.adb.require("am_restart")
+            output = self.adb.shell("am restart", timeout=15)
+        except UnsupportedFeatureError as e:
+            return {"success": False, "unsupported": True, "message": str(e)}
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        if "error" in output.lower():
+            return {"success": False, "message": output.strip()}
+        return {"success": True, "message": "Android framework restarting"}
+
+    def reboot_device(self, mode: str = "system", confirmed: bool = False) -> Dict:
+        """Reboot the device, or into recovery or the bootloader"""
+        if mode not in REBOOT_MODES:
+            return {"success": False, "message": f"Mode must be one of: {', '.join(REBOOT_MODES)}"}
+        if not confirmed:
+            return confirmation_needed(REBOOT_WARNING)
+        target = REBOOT_MODES[mode]
+        try:
+            self.adb._run_command([self.adb.adb_path, "reboot", *([target] if target else [])], timeout=30)
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        return {"success": True, "message": "Device rebooting" if target is None else f"Device rebooting into {mode}"}
@@ -0,0 +1,192 @@
+"""
+Device Tweaks Module
+Small reversible device-wide adjustments: animation scales, cache trimming
+background execution limits, notification muting and runtime permissions
+"""
+import re
+from typing import Dict, Optional
+from adb_operations import ADBOperations, ADBError, UnsupportedFeatureError, require_package_name
+from validation import is_permission_name
+
+
+ANIMATION_SETTINGS = ["window_animation_scale", "transition_animation_scale", "animator_duration_scale"]
+ANIMATION_SCALES = [0, 0.5, 1, 1.5, 2, 5, 10]
+APPOP_MODES = ["allow", "ignore", "deny", "default"]
+
+
+def parse_scale(value: str) -> Optional[float]:
+    """Parse a `settings get` value; unset ("null") means the 1x default"""
+    value = value.strip()
+    if value in ("", "null"):
+        return 1.0
+    try:
+        return float(value)
+    except ValueError:
+        return None
+
+
+def parse_appop_mode(output: str, op: str) -> str:
+    """Parse `cmd appops get <pkg> <op>`; a package without an override reports no operations"""
+    match = re.search(rf'{op}: (\w+)', output)
+    return match.group(1) if match else "default"
+
+
+def permission_name(permission: str) -> Optional[str]:
+    """Full name of a permission given as "RECORD_AUDIO" or "android.permission.RECORD_AUDIO"; None when malformed"""
+    permission = (permission or "").strip()
+    if not is_permission_name(permission):
+        return None
+    return permission if "." in permission else f"android.permission.{permission}"
+
+
+def parse_permission_state(output: str, permission: str) -> Optional[bool]:
+    """Whether `dumpsys package <pkg>` shows a permission granted; None when the package does not request it"""
+    match = re.search(rf'^\s*{re.escape(permission)}: granted=(true|false)', output, re.MULTILINE)
+    return match.group(1) == "true" if match else None
+
+
+class DeviceTweaks:
+    """Read and apply device-wide performance tweaks"""
+
+    def __init__(self, adb_operations: ADBOperations):
+        self.adb = adb_operations
+
+    def get_animation_scales(self) -> Dict[str, Optional[float]]:
+        """Get the three developer-option animation scales (None where unreadable)"""
+        if not self.adb.supports("settings_command"):
+            return {name: None for name in ANIMATION_SETTINGS}
+        return {
+            name: parse_scale(self.adb.shell(f"settings get global {name}", timeout=10))
+            for name in ANIMATION_SETTINGS
+        }
+
+    def set_animation_scale(self, scale: float) -> Dict:
+        """Set all animation scales to one value (0.5 makes the UI feel faster)"""
+        if scale not in ANIMATION_SCALES:
+            return {"success": False, "message": f"Scale must be one of: {', '.join(str(s) for s in ANIMATION_SCALES)}"}
+        try:
+            self.adb.require("settings_command")
+            previous = self.get_animation_scales()
+            for name in ANIMATION_SETTINGS:
+                self.adb.shell(f"settings put global {name} {scale:g}", timeout=10)
+        except UnsupportedFeatureError as e:
+            return {"success": False, "unsupported": True, "message": str(e)}
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        return {"success": True, "message": f"Animation scale set to {scale:g}x", "previous": previous}
+
+    def trim_caches(self) -> Dict:
+        """Ask the package manager to free all app cache space"""
+        try:
+            self.adb.require("trim_caches")
+            # Requesting more space than exists makes pm clear every cache it can
+            output = self.adb.shell("pm trim-caches 999G", timeout=120)
+        except UnsupportedFeatureError as e:
+            return {"success": False, "unsupported": True, "message": str(e)}
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        if output.strip() and "error" in output.lower():
+            return {"success": False, "message": output.strip()}
+        return {"success": True, "message": "App caches trimmed"}
+
+    def restore_animation_scales(self, scales: Dict[str, Optional[float]]) -> Dict:
+        """Put back individual animation scales, e.g. those returned as `previous`"""
+        try:
+            for name, value in scales.items():
+                if name in ANIMATION_SETTINGS and value is not None:
+                    self.adb.shell(f"settings put global {name} {value:g}", timeout=10)
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        return {"success": True, "message": "Animation scales restored"}
+
+    def _appops(self) -> str:
+        # Firmware without the `cmd` binary still ships the standalone appops tool
+        return "cmd appops" if self.adb.supports("cmd_package") else "appops"
+
+    def _get_appop_mode(self, package_name: str, op: str) -> str:
+        output = self.adb.shell(f"{self._appops()} get {require_package_name(package_name)} {op}", timeout=15)
+        return parse_appop_mode(output, op)
+
+    def _set_appop_mode(self, package_name: str, op: str, mode: str, feature: Optional[str] = None) -> Dict:
+        """Set an app-op, returning the mode it replaced as `previous`"""
+        if mode not in APPOP_MODES:
+            return {"success": False, "message": f"Mode must be one of: {', '.join(APPOP_MODES)}"}
+        try:
+            if feature:
+                self.adb.require(feature)
+            self.adb.require("appops")
+            previous = self._get_appop_mode(package_name, op)
+            output = self.adb.shell(f"{self._appops()} set {package_name} {op} {mode}", timeout=15)
+        except UnsupportedFeatureError as e:
+            return {"success": False, "unsupported": True, "message": str(e)}
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        if output.strip():
+            return {"success": False, "message": output.strip()}
+        return {"success": True, "message": f"{op} for {package_name} set to {mode}", "previous": previous}
+
+    def get_background_mode(self, package_name: str) -> str:
+        """Current RUN_ANY_IN_BACKGROUND mode of a package"""
+        return self._get_appop_mode(package_name, "RUN_ANY_IN_BACKGROUND")
+
+    def set_background_mode(self, package_name: str, mode: str) -> Dict:
+        """Set the RUN_ANY_IN_BACKGROUND app-op, returning the mode it replaced as `previous`"""
+        result = self._set_appop_mode(package_name, "RUN_ANY_IN_BACKGROUND", mode, "background_appop")
+        if result["success"]:
+            result["message"] = f"Background mode for {package_name} set to {mode}"
+        return result
+
+    def set_notification_mode(self, package_name: str, mode: str) -> Dict:
+        """Set the POST_NOTIFICATION app-op, returning the mode it replaced as `previous`"""
+        return self._set_appop_mode(package_name, "POST_NOTIFICATION", mode)
+
+    def restrict_background(self, package_name: str, restricted: bool = True) -> Dict:
+        """Allow or deny a package running in the background (RUN_ANY_IN_BACKGROUND app-op)"""
+        result = self.set_background_mode(package_name, "ignore" if restricted else "allow")
+        if result["success"]:
+            state = "restricted" if restricted else "allowed"
+            result["message"] = f"Background activity {state} for {package_name}"
+        return result
+
+    def mute_notifications(self, package_name: str, muted: bool = True) -> Dict:
+        """Drop or allow a package's notifications (POST_NOTIFICATION app-op) without touching its permission"""
+        result = self.set_notification_mode(package_name, "ignore" if muted else "allow")
+        if result["success"]:
+            result["message"] = f"Notifications {'muted' if muted else 'allowed'} for {package_name}"
+        return result
+
+    def set_permission(self, package_name: str, permission: str, granted: bool) -> Dict:
+        """
+        Grant or revoke a runtime permission with `pm grant` / `pm revoke`,
+        returning whether it was granted before as `previous`. Android stops
+        the app when one of its permissions is revoked; install-time and
+        system-fixed permissions cannot be changed and are reported as failures
+        """
+        name = permission_name(permission)
+        if name is None:
+            return {"success": False, "message": f"Not a permission name: {permission}"}
+        try:
+            require_package_name(package_name)
+            self.adb.require("runtime_permissions")
+            previous = parse_permission_state(self.adb.shell(f"dumpsys package {package_name}", timeout=30), name)
+            if previous is None:
+                return {"success": False, "message": f"{package_name} does not request {name}"}
+            verb = "grant" if granted else "revoke"
+            output = self.adb.shell(f"pm {verb} {package_name} {name}", timeout=15)
+        except UnsupportedFeatureError as e:
+            return {"success": False, "unsupported": True, "message": str(e)}
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        if output.strip():
+            # e.g. "SecurityException: Permission ... is not a changeable permission type"
+            return {"success": False, "message": output.strip().splitlines()[-1]}
+        state = f"granted to {package_name}" if granted else f"revoked from {package_name}"
+        return {"success": True, "message": f"{name} {state}", "permission": name, "previous": previous}
+
+    def revoke_permission(self, package_name: str, permission: str) -> Dict:
+        """Take a runtime permission (location, microphone, ...) away from a package"""
+        return self.set_permission(package_name, permission, False)
+
+    def grant_permission(self, package_name: str, permission: str) -> Dict:
+        """Give a package a runtime permission it requests"""
+        return self.set_permission(package_name, permission, True)
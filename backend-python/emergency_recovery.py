@@ -0,0 +1,125 @@
+"""
+Emergency Recovery Module
+One guided command for a device that became unstable after a removal:
+re-enable what was disabled recently (including running trials), restore
+the newest backup, then reboot into safe mode so third-party apps stay off
+while the user checks the device
+"""
+import time
+from typing import Callable, Dict, List, Optional
+from adb_operations import ADBOperations, ADBError
+from backup_manager import parse_package_list
+
+
+# Disables newer than this are undone by default
+DEFAULT_RECOVERY_HOURS = 24
+
+# Read once at boot and cleared again by the system; settable from the shell on many builds
+SAFE_MODE_PROPERTY = "persist.sys.safemode"
+POWER_MENU_COMMAND = "input keyevent --longpress KEYCODE_POWER"
+
+RECOVERY_WARNING = ("Emergency recovery re-enables recently disabled packages, restores the newest backup "
+                    "and reboots the device into safe mode.")
+POWER_MENU_MESSAGE = ("The power menu is open on the device: touch and hold Power off, "
+                      "then confirm to reboot into safe mode")
+MANUAL_SAFE_MODE_MESSAGE = ("Turn the device off, then turn it on and hold Volume Down while it starts "
+                            "until \"Safe mode\" appears in the corner")
+
+
+class EmergencyRecovery:
+    """Undo recent damage in one step and boot the device into safe mode"""
+
+    def __init__(self, adb_operations: ADBOperations, undo, backup_mgr, trials, restarter):
+        self.adb = adb_operations
+        self.undo = undo
+        self.backup_mgr = backup_mgr
+        self.trials = trials
+        self.restarter = restarter
+
+    def _recently_disabled(self, since: float) -> List[str]:
+        """Packages disabled through the app since `since` that are still disabled"""
+        disabled = parse_package_list(self.adb.shell("pm list packages -d", timeout=30))
+        return [package for package in self.undo.packages_since("disable", since) if package in disabled]
+
+    def _newest_backup(self, serial: str) -> Optional[Dict]:
+        backups = [b for b in self.backup_mgr.list_backups(serial) if b.get("packageCount")]
+        return backups[0] if backups else None
+
+    def plan(self, hours: float = DEFAULT_RECOVERY_HOURS) -> Dict:
+        """What emergency_recover would do on the connected device"""
+        serial = self.adb.get_serial()
+        backup = self._newest_backup(serial)
+        return {
+            "reenable": self._recently_disabled(time.time() - hours * 3600),
+            "trials": [trial["id"] for trial in self.trials.list_trials()],
+            "backup": backup["name"] if backup else None,
+        }
+
+    def reboot_to_safe_mode(self) -> Dict:
+        """
+        Reboot into safe mode via the safe mode property; where the build
+        refuses it, open the power menu (long-press Power on Power off offers
+        safe mode) or explain the button combination
+        """
+        try:
+            self.adb.shell(f"setprop {SAFE_MODE_PROPERTY} 1 2>&1", timeout=10)
+            if self.adb.shell(f"getprop {SAFE_MODE_PROPERTY}", timeout=10).strip() == "1":
+                reboot = self.restarter.reboot_device("system", confirmed=True)
+                if reboot["success"]:
+                    return {"success": True, "method": "property", "message": "Device rebooting into safe mode"}
+            output = self.adb.shell(POWER_MENU_COMMAND, timeout=10)
+            if not output.strip():
+                return {"success": True, "method": "powerMenu", "message": POWER_MENU_MESSAGE}
+        except ADBError:
+            pass
+        return {"success": False, "method": "manual", "message": MANUAL_SAFE_MODE_MESSAGE}
+
+    def recover(self, confirmed: bool = False, hours: float = DEFAULT_RECOVERY_HOURS,
+                restore_backup: bool = True, safe_mode: bool = True,
+                confirm: Callable[[List[str]], Optional[Dict]] = None) -> Dict:
+        """
+        Run the recovery steps in order; each step's outcome is reported and a
+        failing step does not stop the next. Without `confirmed` only the plan
+        is returned. `confirm` gets the packages to re-enable and returns the
+        confirmation policy's failure to give instead, before any step runs.
+        """
+        try:
+            plan = self.plan(hours)
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        if not confirmed:
+            return {"success": False, "confirmationRequired": True, "plan": plan, "message": RECOVERY_WARNING}
+        refusal = confirm(plan["reenable"]) if confirm is not None else None
+        if refusal:
+            return {**refusal, "plan": plan}
+
+        steps = []
+        for trial_id in plan["trials"]:
+            result = self.trials.revert(trial_id, reason="recovery")
+            steps.append({"step": "revertTrial", "target": trial_id, "success": result["success"],
+                          "message": result["message"]})
+        for package in plan["reenable"]:
+            result = self.adb.set_package_enabled(package, True)
+            self.undo.record("enable", package, result)
+            steps.append({"step": "reenable", "target": package, "success": result["success"],
+                          "message": result["message"]})
+        if restore_backup and plan["backup"]:
+            result = self.backup_mgr.restore_backup(plan["backup"])
+            for item in result.get("results", []):
+                if item["status"] == "restored":
+                    self.undo.record("reinstall", item["packageName"], {"success": True})
+            steps.append({"step": "restoreBackup", "target": plan["backup"], "success": result["success"],
+                          "message": result["message"]})
+        if safe_mode:
+            result = self.reboot_to_safe_mode()
+            steps.append({"step": "safeMode", "target": result["method"], "success": result["success"],
+                          "message": result["message"]})
+
+        failed = [step for step in steps if not step["success"]]
+        return {
+            "success": not failed,
+            "plan": plan,
+            "steps": steps,
+            "message": (f"Recovery finished: {len(steps) - len(failed)} of {len(steps)} steps succeeded"
+                        if steps else "Nothing to recover"),
+        }
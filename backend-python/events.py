@@ -0,0 +1,89 @@
+"""
+Backend Events Module
+Single versioned envelope for every unsolicited event written to Electron
+"""
+import itertools
+import threading
+from typing import Any, Callable, Dict, List, NotRequired, Optional, TypedDict
+
+
+# Bump when an envelope field or an existing payload shape changes incompatibly.
+# Frontends compare it with the version they were built for.
+EVENT_VERSION = 1
+
+# kind -> what the payload carries
+EVENT_KINDS = {
+    "health_update": "SystemHealth snapshot from the health monitor",
+    "device_lost": "The device disconnected during a long-running operation",
+    "device_restored": "The device reconnected; the operation resumes",
+    "chat_uninstall_complete": "Result of a chat uninstall that paused for a disconnected device",
+    "optimization_progress": "Stage progress of a run_optimization job",
+    "optimization_complete": "Final before/after summary of a run_optimization job",
+    "config_changed": "A watched config file was reloaded after an edit",
+    "backup_progress": "Per-package progress of a create_full_backup job",
+    "backup_complete": "Result of a create_full_backup job",
+    "package_chunk": "One compact chunk of a stream_packages listing, acknowledged with ack_package_chunk",
+    "trial_reverted": "A trial disable expired and its packages were re-enabled",
+    "external_package_change": "Packages were installed or removed on the device outside the app",
+    "weekly_digest_ready": "A new weekly digest of the connected device (get_weekly_digest)",
+    "prefetch_progress": "A stage of the on-connect prefetch finished (done, failed or skipped)",
+    "prefetch_complete": "All stages of the on-connect prefetch have run",
+    "cache_updated": "A stale cache entry (packages, health or analysis) was refreshed in the background",
+    "parser_failed": "A metric parser could not read the device's output (first failure per parser)",
+    "charging_session_ended": "The connected device was unplugged (or disconnected) after a charge session",
+}
+
+# Kinds never replayed: chunks belong to one stream and are acknowledged one by one
+NOT_REPLAYED_KINDS = {"package_chunk"}
+
+# Job id of the background health monitor (there is at most one)
+HEALTH_MONITOR_JOB = "health-monitor"
+
+
+class EventEnvelope(TypedDict):
+    event_version: int
+    job_id: Optional[str]
+    kind: str
+    payload: Any
+    # Sent again by subscribe_with_replay after it was first emitted
+    replayed: NotRequired[bool]
+
+
+# emit(kind, payload, job_id=None)
+EmitFn = Callable[..., None]
+
+
+def make_event(kind: str, payload: Any, job_id: Optional[str] = None) -> EventEnvelope:
+    """Wrap a payload in the versioned event envelope"""
+    if kind not in EVENT_KINDS:
+        raise ValueError(f"Unknown event kind: {kind}")
+    return {"event_version": EVENT_VERSION, "job_id": job_id, "kind": kind, "payload": payload}
+
+
+def no_emit(kind: str, payload: Dict, job_id: Optional[str] = None):
+    """Default emitter for modules used without an event channel (tests, scripts)"""
+
+
+class EventReplayBuffer:
+    """
+    The last event of each kind, so a frontend that finishes loading after
+    early events (health updates, prefetch progress) can ask for them again
+    """
+
+    def __init__(self):
+        # kind -> (sequence, envelope)
+        self._last: Dict[str, tuple] = {}
+        self._sequence = itertools.count()
+        self._lock = threading.Lock()
+
+    def record(self, event: EventEnvelope):
+        if event["kind"] in NOT_REPLAYED_KINDS:
+            return
+        with self._lock:
+            self._last[event["kind"]] = (next(self._sequence), event)
+
+    def replay(self, kinds: Optional[List[str]] = None) -> List[EventEnvelope]:
+        """Copies of the last event of each of `kinds` (every kind by default), in emit order, marked `replayed`"""
+        with self._lock:
+            entries = [entry for kind, entry in self._last.items() if kinds is None or kind in kinds]
+        return [{**event, "replayed": True} for _, event in sorted(entries, key=lambda entry: entry[0])]
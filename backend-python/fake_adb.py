@@ -0,0 +1,71 @@
+"""
+Fake device shared by the tests
+ADBOperations answering from canned output instead of adb, so the real
+parsing and bookkeeping run against a known device - no ADB required
+"""
+from typing import Callable, Dict, List, Optional, Union
+from adb_operations import ADBOperations, DeviceDisconnectedError
+
+
+# Output of a command, or a function of the command giving it
+Answer = Union[str, Callable[[str], str]]
+
+
+class FakeADB(ADBOperations):
+    """
+    A device connected as SERIAL1. `shell_output` and `exec_output` map whole
+    commands to their output and anything else answers `default`; tests with
+    output that depends on state subclass this and override `answer`.
+    Commands are kept in `commands`, direct adb invocations (installs,
+    uninstalls, pulls) in `runs`, and `connected = False` fails every call
+    like an unplugged device.
+    """
+
+    def __init__(self, shell_output: Optional[Dict[str, Answer]] = None,
+                 exec_output: Optional[Dict[str, Answer]] = None, default: str = "",
+                 run_output: str = "Success", sdk: Optional[int] = None, serial: str = "SERIAL1",
+                 shell_sessions: bool = False):
+        super().__init__(shell_sessions=shell_sessions)
+        self.shell_output = dict(shell_output or {})
+        self.exec_output = dict(exec_output or {})
+        self.default = default
+        self.run_output = run_output
+        self.sdk = sdk
+        self.serial = serial
+        self.connected = True
+        self.commands: List[str] = []
+        self.runs: List[List[str]] = []
+
+    def _check_connected(self):
+        if not self.connected:
+            raise DeviceDisconnectedError("No device connected")
+
+    def answer(self, command: str) -> str:
+        """Output of a shell command"""
+        output = self.shell_output.get(command, self.default)
+        return output(command) if callable(output) else output
+
+    def get_serial(self):
+        self._check_connected()
+        return self.serial
+
+    def get_sdk_level(self):
+        return self.sdk if self.sdk is not None else super().get_sdk_level()
+
+    def shell(self, command, timeout=30):
+        if self.shell_sessions:
+            return super().shell(command, timeout)
+        self._check_connected()
+        self.commands.append(command)
+        return self.answer(command)
+
+    def exec_out(self, command, timeout=30):
+        self._check_connected()
+        self.commands.append(command)
+        output = self.exec_output.get(command, "")
+        return output(command) if callable(output) else output
+
+    def _run_command(self, command, timeout=30):
+        self._check_connected()
+        self.runs.append(command)
+        return self.run_output
@@ -0,0 +1,104 @@
+"""
+Fire OS Module
+Recognises Amazon Fire tablets and Fire TV devices and rates Amazon's own
+packages, which the generic rules treat as ordinary apps: on Fire OS the
+launcher, Appstore and account services are what the device cannot do
+without, and Amazon apps removed over adb can come back with the next
+Fire OS update
+"""
+import re
+from typing import Dict, List, Optional, Set
+
+from device_class import filter_presets
+
+
+# Manufacturer, marketing version (Fire OS 5+), then the build's version name
+DETECTION_SCRIPT = "getprop ro.product.manufacturer; getprop ro.build.mktg.fireos; getprop ro.build.version.name"
+
+# "Fire OS 7.3.2.9 (PS7329/4620)" -> "7.3.2.9"
+VERSION_NAME_PATTERN = re.compile(r'Fire\s*OS\s+([\d.]+)', re.IGNORECASE)
+
+UPDATE_NOTE = ("Fire OS updates can put removed Amazon apps back; "
+               "check the list again after an update")
+
+# Newer Fire OS releases refuse adb changes to some Amazon apps (the launcher first)
+PROTECTED_PATTERN = re.compile(r'SecurityException|Cannot (disable|uninstall) a protected package', re.IGNORECASE)
+PROTECTED_MESSAGE = "Fire OS does not allow {package} to be changed over adb"
+
+# package -> (safety level, what it is). Unlisted com.amazon.* system packages are rated Caution.
+FIRE_OS_PACKAGES: Dict[str, tuple] = {
+    "com.amazon.firelauncher": ("Dangerous", "Fire tablet home screen"),
+    "com.amazon.tv.launcher": ("Dangerous", "Fire TV home screen"),
+    "com.amazon.tv.settings.v2": ("Dangerous", "Fire TV settings"),
+    "com.amazon.identity.auth.device.authorization": ("Dangerous", "Amazon account sign-in"),
+    "com.amazon.webview.chromium": ("Dangerous", "Fire OS WebView"),
+    "com.amazon.venezia": ("Expert", "Amazon Appstore, the only store for app updates"),
+    "com.amazon.tv.oobe": ("Expert", "Fire TV setup"),
+    "com.amazon.tv.ime": ("Expert", "Fire TV keyboard"),
+    "com.amazon.geo.mapsv2.services": ("Expert", "Maps services other apps rely on"),
+    "com.amazon.device.messaging": ("Caution", "Push notifications for Amazon apps"),
+    "com.amazon.device.software.ota": ("Caution", "System updates (security fixes stop too)"),
+    "com.amazon.device.software.ota.override": ("Caution", "System update scheduling"),
+    "com.amazon.device.sync": ("Caution", "Whispersync (reading position, game saves)"),
+    "com.amazon.parentalcontrols": ("Caution", "Parental controls and Amazon Kids"),
+    "com.amazon.cloud9": ("Caution", "Silk browser, often the only browser"),
+    "com.amazon.kindle.unifiedSearch": ("Caution", "Device search"),
+    "com.amazon.kindle.kso": ("Safe", "Lock screen ads (Special Offers)"),
+    "com.amazon.avod.thirdpartyclient": ("Safe", "Prime Video"),
+    "com.amazon.mp3": ("Safe", "Amazon Music"),
+    "com.amazon.kindle": ("Safe", "Kindle"),
+    "com.audible.application.kindle": ("Safe", "Audible"),
+    "com.amazon.weather": ("Safe", "Weather"),
+    "com.amazon.photos": ("Safe", "Amazon Photos"),
+    "com.amazon.windowshop": ("Safe", "Amazon Shopping"),
+    "com.amazon.dee.app": ("Safe", "Alexa app"),
+    "com.amazon.zico": ("Safe", "Amazon Docs"),
+    "com.amazon.csapp": ("Safe", "Help and customer service"),
+}
+
+FIRE_OS_PRESETS: List[Dict] = [
+    {
+        "id": "fire_os_ads",
+        "name": "Amazon lock screen ads",
+        "description": "Special Offers on the lock screen; Amazon may ask to pay to remove them, removing the app hides them",
+        "packages": ["com.amazon.kindle.kso"],
+    },
+    {
+        "id": "fire_os_amazon_apps",
+        "name": "Amazon media and shopping apps",
+        "description": "Preloaded Amazon apps, all reinstallable from the Amazon Appstore",
+        "packages": ["com.amazon.avod.thirdpartyclient", "com.amazon.mp3", "com.amazon.kindle",
+                     "com.audible.application.kindle", "com.amazon.weather", "com.amazon.photos",
+                     "com.amazon.windowshop", "com.amazon.dee.app", "com.amazon.zico", "com.amazon.csapp"],
+    },
+]
+
+
+def parse_fire_os_version(output: str) -> Optional[str]:
+    """
+    Fire OS version from the output of DETECTION_SCRIPT, None on other
+    devices. Amazon devices without either version property (Fire OS 4 and
+    older) report "unknown".
+    """
+    lines = [line.strip() for line in output.split('\n')] + ["", "", ""]
+    manufacturer, marketing, version_name = lines[:3]
+    match = VERSION_NAME_PATTERN.search(version_name)
+    if match:
+        return match.group(1)
+    if manufacturer.lower() != "amazon":
+        return None
+    return marketing or "unknown"
+
+
+def fire_os_safety_level(package_name: str) -> Optional[str]:
+    """Safety level of a package on Fire OS, None when the generic rules apply"""
+    if package_name in FIRE_OS_PACKAGES:
+        return FIRE_OS_PACKAGES[package_name][0]
+    if package_name.startswith("com.amazon."):
+        return "Caution"  # Unlisted Amazon system component
+    return None
+
+
+def fire_os_presets(installed: Optional[Set[str]] = None) -> List[Dict]:
+    """Fire OS presets, limited to `installed` packages like device class presets"""
+    return filter_presets(FIRE_OS_PRESETS, installed, platform="fireOs")
@@ -0,0 +1,95 @@
+"""
+Formatting Module
+Shared number, size and percent formatting, so every size shown to the
+user uses the same binary units and the separators of the user's language.
+Results carry the raw numbers next to the formatted strings
+"""
+from typing import Callable, Dict, Optional
+
+
+# language -> (decimal separator, thousands separator, text between number and %)
+LOCALE_FORMATS = {
+    "en": (".", ",", ""),
+    "es": (",", ".", " "),
+    "fr": (",", " ", " "),
+    "de": (",", ".", " "),
+}
+LOCALES = list(LOCALE_FORMATS)
+DEFAULT_LOCALE = "en"
+
+SIZE_UNITS = ["B", "KB", "MB", "GB", "TB"]
+
+# Set by main.py to read the `display.locale` setting; modules used on their own get English
+_locale: Callable[[], str] = lambda: DEFAULT_LOCALE
+
+
+def configure(locale: Callable[[], str]):
+    """Install the function returning the user's language"""
+    global _locale
+    _locale = locale
+
+
+def _separators(locale: Optional[str]):
+    return LOCALE_FORMATS.get(locale or _locale(), LOCALE_FORMATS[DEFAULT_LOCALE])
+
+
+def percent(part: float, whole: float) -> Optional[float]:
+    """part / whole in percent rounded to one decimal, None when whole is 0"""
+    return round(part * 100.0 / whole, 1) if whole else None
+
+
+def format_number(value: float, decimals: int = 0, locale: Optional[str] = None) -> str:
+    """Number with the locale's separators; trailing zero decimals are dropped"""
+    decimal_sep, group_sep, _ = _separators(locale)
+    text = f"{value:,.{decimals}f}"
+    if decimals:
+        text = text.rstrip("0").rstrip(".")
+    return text.replace(",", "\0").replace(".", decimal_sep).replace("\0", group_sep)
+
+
+def format_size(num_bytes: Optional[int], locale: Optional[str] = None) -> Optional[str]:
+    """
+    Size in binary units: 1536 -> "1.5 KB". One decimal below 100 of a
+    unit, none above; None stays None.
+    """
+    if num_bytes is None:
+        return None
+    value = float(num_bytes)
+    unit = 0
+    while abs(value) >= 1024 and unit < len(SIZE_UNITS) - 1:
+        value /= 1024
+        unit += 1
+    decimals = 1 if unit and abs(value) < 100 else 0
+    return f"{format_number(value, decimals, locale)} {SIZE_UNITS[unit]}"
+
+
+def format_size_kb(kilobytes: Optional[int], locale: Optional[str] = None) -> Optional[str]:
+    """Size given in KB (df, /proc/meminfo)"""
+    return None if kilobytes is None else format_size(kilobytes * 1024, locale)
+
+
+def format_percent(value: Optional[float], locale: Optional[str] = None) -> Optional[str]:
+    """76.4 -> "76.4%" ("76,4 %" in French)"""
+    if value is None:
+        return None
+    _, _, spacing = _separators(locale)
+    return f"{format_number(value, 1, locale)}{spacing}%"
+
+
+def formatted_fields(values: Dict, locale: Optional[str] = None) -> Dict[str, Optional[str]]:
+    """
+    Formatted strings for the raw fields of a result: `*Kb` and `*Bytes`
+    fields become sizes and `*Percent` fields percents, keyed without the
+    unit suffix ({"totalKb": 2048} -> {"total": "2 MB"})
+    """
+    formatted = {}
+    for key, value in values.items():
+        if isinstance(value, bool) or not (value is None or isinstance(value, (int, float))):
+            continue
+        if key.endswith("Kb"):
+            formatted[key[:-2]] = format_size_kb(value, locale)
+        elif key.endswith("Bytes"):
+            formatted[key[:-5]] = format_size(value, locale)
+        elif key.endswith("Percent"):
+            formatted[key[:-7]] = format_percent(value, locale)
+    return formatted
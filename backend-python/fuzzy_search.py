@@ -0,0 +1,82 @@
+"""
+Fuzzy Search Module
+Typo-tolerant matching of queries against package ids and display names
+"""
+import re
+from typing import Dict, List
+
+
+# A query token counts as matched when its best similarity reaches this
+TOKEN_MATCH_THRESHOLD = 0.5
+DEFAULT_MIN_SCORE = 0.3
+
+# Common package-name filler that should not anchor a match
+IGNORED_PARTS = {"com", "android", "app", "apps", "org", "net"}
+
+
+def _bigrams(text: str) -> List[str]:
+    padded = f" {text} "
+    return [padded[i:i + 2] for i in range(len(padded) - 1)]
+
+
+def similarity(a: str, b: str) -> float:
+    """Dice coefficient over character bigrams, with a bonus for prefixes"""
+    if not a or not b:
+        return 0.0
+    if a == b:
+        return 1.0
+    if b.startswith(a) and len(a) >= 3:
+        return 0.9
+    first, second = _bigrams(a), _bigrams(b)
+    remaining = list(second)
+    common = 0
+    for gram in first:
+        if gram in remaining:
+            remaining.remove(gram)
+            common += 1
+    return 2.0 * common / (len(first) + len(second))
+
+
+def tokenize(text: str) -> List[str]:
+    """Split package ids and labels into lowercase words (dots, underscores, camelCase)"""
+    text = re.sub(r'([a-z])([A-Z])', r'\1 \2', text or "")
+    return [t for t in re.split(r'[^a-z0-9]+', text.lower()) if t]
+
+
+def score_candidate(query_tokens: List[str], candidate_tokens: List[str]) -> float:
+    """
+    Score 0..1: the average best similarity of each query token.
+
+    Tokens without any reasonable match (e.g. "bloatware") contribute 0
+    instead of disqualifying the candidate.
+    """
+    if not query_tokens or not candidate_tokens:
+        return 0.0
+    total = 0.0
+    for token in query_tokens:
+        best = max(similarity(token, candidate) for candidate in candidate_tokens)
+        if best >= TOKEN_MATCH_THRESHOLD:
+            total += best
+    return round(total / len(query_tokens), 3)
+
+
+def search_packages(packages: List[Dict], query: str, limit: int = 50,
+                    min_score: float = DEFAULT_MIN_SCORE) -> List[Dict]:
+    """Rank packages by fuzzy match against package name and display name"""
+    query_tokens = tokenize(query)
+    if not query_tokens:
+        return []
+
+    results = []
+    for pkg in packages:
+        name_tokens = [t for t in tokenize(pkg["packageName"]) if t not in IGNORED_PARTS]
+        candidate_tokens = name_tokens + tokenize(pkg.get("appName", ""))
+        score = score_candidate(query_tokens, candidate_tokens)
+        # A pasted (partial) package id always wins
+        if "." in query and query.strip().lower() in pkg["packageName"].lower():
+            score = 1.0
+        if score >= min_score:
+            results.append({**pkg, "matchScore": score})
+
+    results.sort(key=lambda p: (-p["matchScore"], p["packageName"]))
+    return results[:limit]
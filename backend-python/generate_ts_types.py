@@ -0,0 +1,93 @@
+"""
+TypeScript Type Generator
+Writes frontend/src/types.generated.ts from api_types.py so the frontend
+and backend share one definition of every DTO
+
+Usage: python generate_ts_types.py [--check]
+"""
+import os
+import sys
+import typing
+from typing import Any, Dict, List, Literal, Union, get_args, get_origin, is_typeddict
+
+from api_types import EXPORTED_TYPES
+
+
+OUTPUT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), '..', 'frontend', 'src', 'types.generated.ts'))
+
+HEADER = """// Generated by backend-python/generate_ts_types.py from api_types.py - do not edit.
+// Run `python backend-python/generate_ts_types.py` after changing api_types.py.
+"""
+
+PRIMITIVES = {str: "string", int: "number", float: "number", bool: "boolean", type(None): "null", Any: "any"}
+
+
+def ts_type(annotation, names: Dict[Any, str]) -> str:
+    """TypeScript spelling of a type annotation"""
+    if annotation in names:
+        return names[annotation]
+    if annotation in PRIMITIVES:
+        return PRIMITIVES[annotation]
+    origin, args = get_origin(annotation), get_args(annotation)
+    if origin is Literal:
+        return " | ".join(f"'{value}'" for value in args)
+    if origin is Union:
+        return " | ".join(ts_type(arg, names) for arg in args)
+    if origin in (list, List):
+        inner = ts_type(args[0], names)
+        return f"({inner})[]" if " " in inner else f"{inner}[]"
+    if origin in (dict, Dict):
+        return f"Record<{ts_type(args[0], names)}, {ts_type(args[1], names)}>"
+    raise TypeError(f"No TypeScript mapping for {annotation!r}")
+
+
+def render_interface(name: str, cls, names: Dict[Any, str]) -> str:
+    hints = typing.get_type_hints(cls)
+    lines = [f"export interface {name} {{"]
+    for field, annotation in hints.items():
+        optional = "?" if field in cls.__optional_keys__ else ""
+        lines.append(f"  {field}{optional}: {ts_type(annotation, names)};")
+    lines.append("}")
+    return "\n".join(lines)
+
+
+def render_alias(name: str, annotation, names: Dict[Any, str]) -> str:
+    args = get_args(annotation)
+    if get_origin(annotation) is Literal and len(args) > 3:
+        return f"export type {name} =\n" + "\n".join(f"  | '{value}'" for value in args) + ";"
+    return f"export type {name} = {ts_type(annotation, names)};"
+
+
+def generate() -> str:
+    """Full contents of types.generated.ts"""
+    names: Dict[Any, str] = {}
+    blocks = []
+    for name, definition in EXPORTED_TYPES:
+        if is_typeddict(definition):
+            blocks.append(render_interface(name, definition, names))
+        else:
+            blocks.append(render_alias(name, definition, names))
+        names[definition] = name
+    return HEADER + "\n" + "\n\n".join(blocks) + "\n"
+
+
+def main() -> int:
+    content = generate()
+    if "--check" in sys.argv:
+        try:
+            with open(OUTPUT_PATH, 'r', encoding='utf-8') as f:
+                current = f.read()
+        except OSError:
+            current = None
+        if current != content:
+            print(f"{OUTPUT_PATH} is out of date; run python backend-python/generate_ts_types.py")
+            return 1
+        return 0
+    with open(OUTPUT_PATH, 'w', encoding='utf-8', newline='\n') as f:
+        f.write(content)
+    print(f"Wrote {OUTPUT_PATH}")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
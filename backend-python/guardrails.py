@@ -0,0 +1,113 @@
+"""
+Guardrails Module
+Cross-checks AI advice against local safety classifications so the model can
+never talk a user into removing a critical system component
+"""
+import re
+from typing import Callable, Dict, List, Optional
+from chat_context import PACKAGE_PATTERN
+
+
+# Common names the model uses instead of package ids for critical components
+DANGEROUS_ALIASES = {
+    r'\bsystem\s?ui\b': 'com.android.systemui',
+    r'\bphone (?:app|service)s?\b': 'com.android.phone',
+    r'\bsettings app\b': 'com.android.settings',
+    r'\b(?:google )?play store\b': 'com.android.vending',
+}
+
+REMOVAL_PATTERN = re.compile(r'\b(remove|removing|uninstall|uninstalling|disable|disabling|debloat|delete|get rid of|safe to remove)\b', re.IGNORECASE)
+NEGATION_PATTERN = re.compile(r"\b(don'?t|do not|never|avoid|shouldn'?t|should not|not safe|must not|cannot)\b", re.IGNORECASE)
+
+# Local ratings an AI "safe to remove" contradicts, and AI categories that contradict a local Safe
+PROTECTED_RATINGS = ("Expert", "Dangerous")
+
+WARNING_REASONS = {
+    "Dangerous": "critical system component – removing it can cause a bootloop or leave the device unusable",
+}
+
+
+def _sentences(text: str) -> List[str]:
+    # Keep package ids intact: only split on terminators followed by whitespace
+    return [s for s in re.split(r'(?<=[.!?])\s+|\n+', text) if s.strip()]
+
+
+def find_contradicting_advice(text: str, classify: Callable[[str], str]) -> List[Dict]:
+    """
+    Find packages the text recommends removing that are classified Dangerous locally.
+
+    Sentences that warn against removal ("don't remove SystemUI") are ignored.
+    """
+    flagged: Dict[str, Dict] = {}
+    for sentence in _sentences(text):
+        if not REMOVAL_PATTERN.search(sentence) or NEGATION_PATTERN.search(sentence):
+            continue
+
+        candidates = [p.lower() for p in PACKAGE_PATTERN.findall(sentence)]
+        for pattern, package in DANGEROUS_ALIASES.items():
+            if re.search(pattern, sentence, re.IGNORECASE):
+                candidates.append(package)
+
+        for package in candidates:
+            level = classify(package)
+            if level in WARNING_REASONS and package not in flagged:
+                flagged[package] = {
+                    "packageName": package,
+                    "safetyLevel": level,
+                    "reason": WARNING_REASONS[level],
+                    "sentence": sentence.strip(),
+                }
+    return list(flagged.values())
+
+
+def analysis_conflict(ai: Dict, local_rating: Optional[str]) -> Optional[Dict]:
+    """
+    The `conflict` of an AI verdict (analysis or aiRisk) with the local
+    rating, None when they agree. The app always enforces the local rating:
+    it drives the removal warnings and the chat guardrails, so the conflict
+    says so instead of leaving two panels to disagree silently
+    """
+    category = ai.get("riskCategory")
+    ai_removable = bool(ai.get("safeToRemove")) or category == "Safe"
+    if ai_removable and local_rating in PROTECTED_RATINGS:
+        kind = "aiMoreLenient"
+        message = (f"The AI says this is safe to remove, but Debloat AI rates it {local_rating}. "
+                   f"The {local_rating} rating applies: removal is warned about as {local_rating}.")
+    elif not ai.get("safeToRemove") and category in PROTECTED_RATINGS and local_rating == "Safe":
+        kind = "aiStricter"
+        message = (f"The AI rates this {category} and advises keeping it, but Debloat AI rates it Safe. "
+                   f"The Safe rating applies, so removal is not warned about; keep it if you rely on what the AI describes.")
+    else:
+        return None
+    return {
+        "kind": kind,
+        "localRating": local_rating,
+        "aiRiskCategory": category,
+        "aiSafeToRemove": bool(ai.get("safeToRemove")),
+        "enforced": "local",
+        "message": message,
+    }
+
+
+def apply_guardrails(text: str, classify: Callable[[str], str]) -> Dict:
+    """
+    Annotate AI output that contradicts local Dangerous classifications.
+
+    Returns {"content": annotated text, "warnings": [...]}; content is unchanged
+    when nothing was flagged.
+    """
+    warnings = find_contradicting_advice(text, classify)
+    if not warnings:
+        return {"content": text, "warnings": []}
+
+    lines = [
+        "",
+        "---",
+        "⚠️ **Safety warning from Debloat AI**",
+        "The answer above suggests removing packages that Debloat AI classifies as **Dangerous**. "
+        "Do not remove these, whatever the AI says:",
+    ]
+    for warning in warnings:
+        lines.append(f"- `{warning['packageName']}` – {warning['reason']}")
+
+    return {"content": text.rstrip() + "\n" + "\n".join(lines), "warnings": warnings}
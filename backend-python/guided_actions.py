@@ -0,0 +1,214 @@
+"""
+Guided Actions Module
+The only ways guided flows may drive the device: open allowlisted Settings
+pages and store listings through `am start` intents, press navigation keys
+and tap named Settings entries. Nothing here can accept a prompt on the user's behalf - keys
+that confirm (Enter, D-pad center) are not offered, there are no taps at
+raw coordinates, and taps are refused while a permission, USB debugging,
+install or lock screen dialog has focus
+"""
+import html
+import re
+from typing import Dict, Optional, Tuple
+from adb_operations import ADBOperations, ADBError
+from validation import is_package_name
+
+
+# page -> (what it shows, `am start` arguments with `{package}` for per-package pages)
+SETTINGS_PAGES = {
+    "developerOptions": ("Developer options", "-a android.settings.APPLICATION_DEVELOPMENT_SETTINGS"),
+    "aboutPhone": ("About phone, where tapping Build number unlocks Developer options",
+                   "-a android.settings.DEVICE_INFO_SETTINGS"),
+    "appInfo": ("App info of a package", "-a android.settings.APPLICATION_DETAILS_SETTINGS -d package:{package}"),
+    "appNotifications": ("Notification settings of a package",
+                         "-a android.settings.APP_NOTIFICATION_SETTINGS --es android.provider.extra.APP_PACKAGE {package}"),
+    "allApps": ("All installed apps", "-a android.settings.MANAGE_APPLICATIONS_SETTINGS"),
+    "defaultApps": ("Default apps", "-a android.settings.MANAGE_DEFAULT_APPS_SETTINGS"),
+    "usageAccess": ("Usage access", "-a android.settings.USAGE_ACCESS_SETTINGS"),
+    "batteryOptimization": ("Battery optimization", "-a android.settings.IGNORE_BATTERY_OPTIMIZATION_SETTINGS"),
+    "storage": ("Storage", "-a android.settings.INTERNAL_STORAGE_SETTINGS"),
+}
+
+PLAY_STORE_PACKAGE = "com.android.vending"
+# Opened in a browser when no store app takes the market:// link
+PLAY_STORE_WEB_URL = "https://play.google.com/store/apps/details?id={package}"
+
+# key -> keycode; navigation only, so a focused "Allow" button can never be pressed
+GUIDED_KEYS = {
+    "wake": "KEYCODE_WAKEUP",
+    "home": "KEYCODE_HOME",
+    "back": "KEYCODE_BACK",
+    "recents": "KEYCODE_APP_SWITCH",
+    "scrollUp": "KEYCODE_PAGE_UP",
+    "scrollDown": "KEYCODE_PAGE_DOWN",
+}
+
+# Taps land only in these apps, the ones the settings pages open
+TAP_PACKAGES = ("com.android.settings",)
+
+# target -> (what it is, page it is on, labels it shows). A tap finds the entry by its
+# label in the UI hierarchy and hits its center, so it can never land on a grant switch
+TAP_TARGETS = {
+    "buildNumber": ("Build number, tapped seven times to unlock Developer options", "aboutPhone",
+                    ("Build number",)),
+    "appStorage": ("Storage & cache entry of App info", "appInfo", ("Storage & cache", "Storage")),
+}
+
+# Prints the hierarchy of the focused screen, followed by "UI hierchary dumped to: /dev/tty"
+UI_DUMP_COMMAND = "uiautomator dump /dev/tty"
+
+# Focused windows a tap must never reach, even inside Settings: runtime permission and
+# special access grants, device admin activation, screen lock and the USB debugging prompt
+PROTECTED_WINDOW = re.compile(r'permissioncontroller|packageinstaller|Permission|Grant|Consent|DeviceAdminAdd|'
+                              r'Lock|Password|UsbDebugging|Confirm', re.IGNORECASE)
+
+# "  mCurrentFocus=Window{5d1b0c3 u0 com.android.settings/com.android.settings.SubSettings}"
+FOCUS_PATTERN = re.compile(r'mCurrentFocus=Window\{\S+ \S+ ([^}\s]+)\}')
+# <node index="0" text="Build number" ... package="com.android.settings" ... bounds="[0,1032][1080,1221]">
+UI_NODE_PATTERN = re.compile(r'<node\b[^>]*>')
+UI_ATTRIBUTE_PATTERN = re.compile(r'([\w-]+)="([^"]*)"')
+BOUNDS_PATTERN = re.compile(r'^\[(\d+),(\d+)\]\[(\d+),(\d+)\]$')
+
+
+def page_command(page: str, package: Optional[str] = None) -> str:
+    """`am start` for an allowlisted page; ValueError for anything else"""
+    if page not in SETTINGS_PAGES:
+        raise ValueError(f"'{page}' is not an allowed settings page ({', '.join(SETTINGS_PAGES)})")
+    _, arguments = SETTINGS_PAGES[page]
+    if "{package}" in arguments:
+        if not is_package_name(package):
+            raise ValueError(f"Invalid package name: {package}")
+        arguments = arguments.format(package=package)
+    return f"am start {arguments}"
+
+
+def view_command(uri: str, package: Optional[str] = None) -> str:
+    """`am start` showing `uri`, in the app `package` when given; the URI is quoted for `?` and `&`"""
+    return f"am start -a android.intent.action.VIEW -d '{uri}'" + (f" -p {package}" if package else "")
+
+
+def parse_focus(output: str) -> Optional[str]:
+    """Focused window ("package/activity") from `dumpsys window`; None when nothing has focus"""
+    match = FOCUS_PATTERN.search(output)
+    return match.group(1) if match else None
+
+
+def find_label(dump: str, labels: Tuple[str, ...]) -> Optional[Tuple[int, int]]:
+    """Center of the first Settings element showing one of `labels` in a UI dump; None when none does"""
+    for node in UI_NODE_PATTERN.findall(dump):
+        attributes = {name: html.unescape(value) for name, value in UI_ATTRIBUTE_PATTERN.findall(node)}
+        bounds = BOUNDS_PATTERN.match(attributes.get("bounds", ""))
+        if attributes.get("package") in TAP_PACKAGES and attributes.get("text") in labels and bounds:
+            left, top, right, bottom = (int(value) for value in bounds.groups())
+            return (left + right) // 2, (top + bottom) // 2
+    return None
+
+
+def tap_refusal(focus: Optional[str]) -> Optional[str]:
+    """Why a tap on `focus` is not allowed; None when it is"""
+    if focus is None:
+        return "Nothing on the device has focus; unlock it and open the page first"
+    package = focus.split('/', 1)[0]
+    if package not in TAP_PACKAGES:
+        return f"Taps are only allowed inside Settings, not in {package}"
+    if PROTECTED_WINDOW.search(focus):
+        return "A permission, lock or confirmation screen is open; it must be answered on the device"
+    return None
+
+
+class GuidedActions:
+    """Allowlisted intents, keys and tap targets for guided flows"""
+
+    def __init__(self, adb_operations: ADBOperations):
+        self.adb = adb_operations
+
+    def list_actions(self) -> Dict:
+        """Pages that can be opened, keys that can be pressed and entries that can be tapped"""
+        return {
+            "pages": {page: {"description": description, "perPackage": "{package}" in arguments}
+                      for page, (description, arguments) in SETTINGS_PAGES.items()},
+            "keys": list(GUIDED_KEYS),
+            "tapTargets": {target: {"description": description, "page": page}
+                           for target, (description, page, _) in TAP_TARGETS.items()},
+        }
+
+    def open_settings_page(self, page: str, package: str = None) -> Dict:
+        """Wake the screen and open an allowlisted Settings page"""
+        try:
+            command = page_command(page, package)
+        except ValueError as e:
+            return {"success": False, "message": str(e)}
+        try:
+            self.adb.shell(f"input keyevent {GUIDED_KEYS['wake']}", timeout=10)
+            output = self.adb.shell(command, timeout=15)
+        except ADBError as e:
+            return {"success": False, "page": page, "message": str(e)}
+        # "Error: Activity not started, unable to resolve Intent" when the build has no such page
+        if "Error" in output:
+            return {"success": False, "page": page, "unsupported": True,
+                    "message": f"This device has no {SETTINGS_PAGES[page][0]} page"}
+        return {"success": True, "page": page, "packageName": package,
+                "message": f"Opened {SETTINGS_PAGES[page][0]} on the device"}
+
+    def open_app_info(self, package: str) -> Dict:
+        """Open the App info page of `package`, e.g. to uninstall its updates by hand"""
+        return self.open_settings_page("appInfo", package)
+
+    def open_in_play_store(self, package: str) -> Dict:
+        """
+        Show the store listing of `package`: in the Play Store when it is
+        installed, else in whatever store takes market:// links, else in a browser
+        """
+        if not is_package_name(package):
+            return {"success": False, "message": f"Invalid package name: {package}"}
+        market_uri = f"market://details?id={package}"
+        try:
+            store = PLAY_STORE_PACKAGE if self.adb.get_apk_paths(PLAY_STORE_PACKAGE) else None
+            self.adb.shell(f"input keyevent {GUIDED_KEYS['wake']}", timeout=10)
+            attempts = [(view_command(market_uri, store), "Play Store" if store else "app store"),
+                        (view_command(PLAY_STORE_WEB_URL.format(package=package)), "browser")]
+            if store:
+                # A disabled Play Store still has its APK; any other store may take the link
+                attempts.insert(1, (view_command(market_uri), "app store"))
+            for command, opened_in in attempts:
+                if "Error" not in self.adb.shell(command, timeout=15):
+                    return {"success": True, "packageName": package, "openedIn": opened_in,
+                            "message": f"Opened the store page of {package} in the {opened_in} on the device"}
+        except ADBError as e:
+            return {"success": False, "packageName": package, "message": str(e)}
+        return {"success": False, "packageName": package, "unsupported": True,
+                "message": "No app store or browser on the device can open the store page"}
+
+    def press_key(self, key: str) -> Dict:
+        """Press an allowlisted navigation key"""
+        if key not in GUIDED_KEYS:
+            return {"success": False, "message": f"'{key}' is not an allowed key ({', '.join(GUIDED_KEYS)})"}
+        try:
+            self.adb.shell(f"input keyevent {GUIDED_KEYS[key]}", timeout=10)
+        except ADBError as e:
+            return {"success": False, "key": key, "message": str(e)}
+        return {"success": True, "key": key, "message": f"Pressed {key}"}
+
+    def tap(self, target: str) -> Dict:
+        """
+        Tap an allowlisted Settings entry, found by its label on the focused
+        screen, only when Settings has focus and no protected screen is
+        showing; the focus is checked right before the tap
+        """
+        if target not in TAP_TARGETS:
+            return {"success": False,
+                    "message": f"'{target}' is not an allowed tap target ({', '.join(TAP_TARGETS)})"}
+        _, page, labels = TAP_TARGETS[target]
+        try:
+            focus = parse_focus(self.adb.shell("dumpsys window | grep mCurrentFocus", timeout=10))
+            refusal = tap_refusal(focus)
+            if refusal:
+                return {"success": False, "refused": True, "target": target, "focus": focus, "message": refusal}
+            point = find_label(self.adb.shell(UI_DUMP_COMMAND, timeout=20), labels)
+            if point is None:
+                return {"success": False, "refused": True, "target": target, "focus": focus,
+                        "message": f"{labels[0]} is not on the screen; open the {page} page first"}
+            self.adb.shell(f"input tap {point[0]} {point[1]}", timeout=10)
+        except ADBError as e:
+            return {"success": False, "target": target, "message": str(e)}
+        return {"success": True, "target": target, "focus": focus, "message": f"Tapped {labels[0]}"}
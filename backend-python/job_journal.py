@@ -0,0 +1,161 @@
+"""
+Job Journal Module
+Keeps the state of batch jobs (the selection, the items done and the items
+left) on disk, rewritten after every item, so a batch removal cut short by
+a crash, a closed window or an unplugged device can be resumed from where
+it stopped instead of starting over
+"""
+import copy
+import json
+import os
+import threading
+import time
+import uuid
+from pathlib import Path
+from typing import Dict, List, Optional
+from adb_operations import ADBError
+
+
+# Job kinds the UI knows how to resume
+JOB_KINDS = ("uninstall",)
+
+
+class JobJournal:
+    """Persist in-flight batch jobs and list the ones left unfinished"""
+
+    def __init__(self, adb_operations=None, data_dir: str = None):
+        self.adb = adb_operations
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.path = Path(data_dir) / "job_journal.json"
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        self._lock = threading.Lock()
+        try:
+            with open(self.path, 'r', encoding='utf-8') as f:
+                self._jobs: List[Dict] = json.load(f)
+        except (OSError, json.JSONDecodeError):
+            self._jobs = []
+        # Ids of jobs being worked on by this process; every other journaled job is unfinished
+        self._running = set()
+
+    def _save(self):
+        # Written to a temporary file and swapped in, so a crash mid-write keeps the previous state
+        partial = self.path.with_suffix(".json.partial")
+        with open(partial, 'w', encoding='utf-8') as f:
+            json.dump(self._jobs, f, indent=2)
+        os.replace(partial, self.path)
+
+    def _serial(self) -> Optional[str]:
+        if self.adb is None:
+            return None
+        try:
+            return self.adb.get_serial()
+        except ADBError:
+            return None
+
+    def _find(self, job_id: Optional[str]) -> Optional[Dict]:
+        return next((job for job in self._jobs if job["id"] == job_id), None)
+
+    def start(self, kind: str, packages: List[str], options: Optional[Dict] = None) -> Dict:
+        """Journal a new job over `packages`; returns its id"""
+        if kind not in JOB_KINDS:
+            return {"success": False, "message": f"Unknown job kind: {kind}"}
+        packages = list(dict.fromkeys(packages or []))
+        if not packages:
+            return {"success": False, "message": "No packages selected"}
+        now = time.time()
+        job = {
+            "id": uuid.uuid4().hex[:12],
+            "kind": kind,
+            "serial": self._serial(),
+            "options": dict(options or {}),
+            "selection": packages,
+            "completed": [],
+            "pending": list(packages),
+            "startedAt": now,
+            "updatedAt": now,
+        }
+        with self._lock:
+            self._jobs.append(job)
+            self._running.add(job["id"])
+            self._save()
+        return {"success": True, "jobId": job["id"]}
+
+    def record(self, job_id: Optional[str], package: str, result: Dict) -> bool:
+        """
+        Mark one item done with its result. Items that failed because the
+        device went away stay pending, so a resume tries them again.
+        """
+        if not job_id or result.get("deviceLost"):
+            return False
+        with self._lock:
+            job = self._find(job_id)
+            if job is None or package not in job["pending"]:
+                return False
+            job["pending"].remove(package)
+            job["completed"].append({
+                "packageName": package,
+                "success": bool(result.get("success")),
+                "message": result.get("message", ""),
+            })
+            job["updatedAt"] = time.time()
+            self._save()
+        return True
+
+    def includes(self, job_id: Optional[str], package: str) -> bool:
+        """Whether `package` is pending in a job this process is working on"""
+        with self._lock:
+            job = self._find(job_id) if job_id in self._running else None
+            return job is not None and package in job["pending"]
+
+    def finish(self, job_id: Optional[str]) -> Dict:
+        """
+        End this process's work on a job: it is dropped once nothing is
+        pending, otherwise kept to be offered for resuming
+        """
+        with self._lock:
+            job = self._find(job_id)
+            if job is None:
+                return {"success": False, "message": "No such job"}
+            self._running.discard(job_id)
+            if not job["pending"]:
+                self._jobs.remove(job)
+                self._save()
+                return {"success": True, "finished": True, "remaining": 0, "message": "Job finished"}
+            remaining = len(job["pending"])
+        return {"success": True, "finished": False, "remaining": remaining,
+                "message": f"{remaining} item(s) left; the job can be resumed"}
+
+    def get_incomplete(self) -> List[Dict]:
+        """Unfinished jobs not being worked on, newest first, marked with whether their device is connected"""
+        serial = self._serial()
+        with self._lock:
+            jobs = [copy.deepcopy(job) for job in self._jobs if job["id"] not in self._running]
+        for job in jobs:
+            job["deviceConnected"] = job["serial"] is None or job["serial"] == serial
+        return sorted(jobs, key=lambda job: job["updatedAt"], reverse=True)
+
+    def resume(self, job_id: Optional[str]) -> Dict:
+        """Take an unfinished job back up; returns it so the caller can work through `pending`"""
+        serial = self._serial()
+        with self._lock:
+            job = self._find(job_id)
+            if job is None:
+                return {"success": False, "message": "No such job"}
+            if job_id in self._running:
+                return {"success": False, "message": "This job is already running"}
+            if job["serial"] is not None and job["serial"] != serial:
+                return {"success": False, "message": f"Connect {job['serial']} to resume this job"}
+            self._running.add(job_id)
+            return {"success": True, "job": copy.deepcopy(job)}
+
+    def discard(self, job_id: Optional[str]) -> Dict:
+        """Forget an unfinished job without running the rest"""
+        with self._lock:
+            job = self._find(job_id)
+            if job is None:
+                return {"success": False, "message": "No such job"}
+            self._jobs.remove(job)
+            self._running.discard(job_id)
+            self._save()
+        return {"success": True, "message": f"Discarded {len(job['pending'])} pending item(s)"}
@@ -0,0 +1,172 @@
+"""
+Label Resolver Module
+Resolves real application labels ("Samsung Free" instead of "Spage") from the device
+"""
+import glob
+import json
+import os
+import re
+import shutil
+import subprocess
+import tempfile
+import threading
+from pathlib import Path
+from typing import Dict, List, Optional
+from adb_operations import ADBOperations, ADBError, require_package_name
+from cache_policy import CacheStats, cache_report
+
+
+# Packages resolved per call - each one means pulling an APK
+MAX_LABELS_PER_CALL = 25
+
+LABEL_PATTERN = re.compile(r"^application-label(?:-en(?:-US)?)?:'(.*)'$", re.MULTILINE)
+
+
+def find_aapt() -> Optional[str]:
+    """Locate aapt2/aapt from PATH or the Android SDK build-tools"""
+    for name in ("aapt2", "aapt"):
+        path = shutil.which(name)
+        if path:
+            return path
+    for env in ("ANDROID_HOME", "ANDROID_SDK_ROOT"):
+        sdk = os.environ.get(env)
+        if not sdk:
+            continue
+        for name in ("aapt2", "aapt2.exe", "aapt", "aapt.exe"):
+            matches = sorted(glob.glob(os.path.join(sdk, "build-tools", "*", name)))
+            if matches:
+                return matches[-1]  # newest build-tools version
+    return None
+
+
+def parse_badging_label(output: str) -> Optional[str]:
+    """Extract the application label from `aapt dump badging` output"""
+    match = LABEL_PATTERN.search(output)
+    if match and match.group(1).strip():
+        return match.group(1).strip()
+    return None
+
+
+class LabelResolver:
+    """Resolve and cache application labels per device"""
+
+    def __init__(self, adb_operations: ADBOperations, data_dir: str = None):
+        self.adb = adb_operations
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.cache_dir = Path(data_dir) / "labels"
+        self.cache_dir.mkdir(parents=True, exist_ok=True)
+        self.aapt_path = find_aapt()
+        self._caches: Dict[str, Dict[str, str]] = {}
+        self._lock = threading.Lock()
+        self.stats = CacheStats()
+
+    def _cache_for(self, serial: str) -> Dict[str, str]:
+        if serial not in self._caches:
+            path = self.cache_dir / f"{serial}.json"
+            try:
+                with open(path, 'r', encoding='utf-8') as f:
+                    self._caches[serial] = json.load(f)
+            except (OSError, json.JSONDecodeError):
+                self._caches[serial] = {}
+        return self._caches[serial]
+
+    def _save(self, serial: str):
+        with open(self.cache_dir / f"{serial}.json", 'w', encoding='utf-8') as f:
+            json.dump(self._caches[serial], f, indent=2, ensure_ascii=False)
+
+    def apply_cached_labels(self, packages: List[Dict]) -> List[Dict]:
+        """Replace guessed appName values with cached real labels where known"""
+        try:
+            serial = self.adb.get_serial()
+        except ADBError:
+            return packages
+        cache = self._cache_for(serial)
+        found = 0
+        for pkg in packages:
+            label = cache.get(pkg["packageName"])
+            if label:
+                found += 1
+                pkg["appName"] = label
+                pkg["labelSource"] = "device"
+        self.stats.hit(found)
+        self.stats.miss(len(packages) - found)
+        return packages
+
+    def cached_label(self, package: str) -> Optional[str]:
+        """Real label of a package on the connected device, if resolved before"""
+        try:
+            serial = self.adb.get_serial()
+        except ADBError:
+            return None
+        with self._lock:
+            return self._cache_for(serial).get(package)
+
+    def cache_diagnostics(self) -> Dict:
+        """get_cache_diagnostics line of the loaded label caches (labels are not timed)"""
+        caches = dict(self._caches)
+        return cache_report("labels", self.stats, caches, [], sum(len(labels) for labels in caches.values()))
+
+    def _resolve_one(self, package: str, workdir: str) -> Optional[str]:
+        paths = self.adb.shell(f"pm path {require_package_name(package)}", timeout=15)
+        apks = [line[len("package:"):].strip() for line in paths.split('\n') if line.startswith("package:")]
+        base = next((p for p in apks if p.endswith("/base.apk")), apks[0] if apks else None)
+        if not base:
+            return None
+
+        local_apk = os.path.join(workdir, f"{package}.apk")
+        try:
+            self.adb._run_command([self.adb.adb_path, "pull", base, local_apk], timeout=120)
+            result = subprocess.run(
+                [self.aapt_path, "dump", "badging", local_apk],
+                capture_output=True, text=True, timeout=60, encoding='utf-8', errors='replace'
+            )
+            return parse_badging_label(result.stdout)
+        finally:
+            if os.path.exists(local_apk):
+                os.remove(local_apk)
+
+    def resolve_labels(self, packages: List[str]) -> Dict:
+        """
+        Resolve labels for packages not yet in the cache.
+
+        At most MAX_LABELS_PER_CALL APKs are pulled per call; callers can
+        call again with the `pending` list to continue.
+        """
+        serial = self.adb.get_serial()
+        with self._lock:
+            cache = self._cache_for(serial)
+            labels = {p: cache[p] for p in packages if p in cache}
+
+            if self.aapt_path is None:
+                return {
+                    "available": False,
+                    "labels": labels,
+                    "unresolved": [p for p in packages if p not in labels],
+                    "pending": [],
+                    "message": "Install Android SDK build-tools (aapt2) to resolve real app names"
+                }
+
+            todo = [p for p in packages if p not in cache]
+            batch, pending = todo[:MAX_LABELS_PER_CALL], todo[MAX_LABELS_PER_CALL:]
+            unresolved = []
+            with tempfile.TemporaryDirectory(prefix="debloat-labels-") as workdir:
+                for package in batch:
+                    try:
+                        label = self._resolve_one(package, workdir)
+                    except (ADBError, OSError, subprocess.TimeoutExpired):
+                        label = None
+                    if label:
+                        cache[package] = label
+                        labels[package] = label
+                    else:
+                        unresolved.append(package)
+            self._save(serial)
+
+        return {
+            "available": True,
+            "labels": labels,
+            "unresolved": unresolved,
+            "pending": pending,
+            "message": f"Resolved {len(labels)} label(s)"
+        }
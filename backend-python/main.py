@@ -5,42 +5,480 @@ Stays alive for the lifetime of the Electron app (no per-call spawn overhead).
 """
 import sys
 import json
+import signal
+import threading
+import time
 import traceback
-from adb_operations import ADBOperations, ADBError
-from ai_advisor import AIAdvisor
-from backup_manager import BackupManager
-from openclaw_integration import OpenClawIntegration
+from pathlib import Path
+from adb_operations import ADBError, UnsupportedFeatureError
+from ai_advisor import local_analysis
+from backup_manager import group_backups
+from guardrails import apply_guardrails
+from package_families import group_packages
+from package_wire import compact_packages
+from analysis_export import EXPORT_EXTENSIONS, EXPORT_FORMATS, build_rows, render as render_analyses
+import network
+from fuzzy_search import search_packages
+from trial_manager import DEFAULT_TRIAL_HOURS
+from device_class import CLASS_LABELS, presets_for
+import fire_os
+from signatures import describe_signature
+from stay_awake import stay_awake_during
+from emergency_recovery import DEFAULT_RECOVERY_HOURS
+from performance_report import build_performance_report
+from unused_apps import DEFAULT_UNUSED_DAYS
+from weekly_digest import DEFAULT_TREND_DAYS
+from charging_sessions import INSIGHT_SESSIONS as CHARGING_SESSION_LIMIT
+from events import EVENT_KINDS, EventReplayBuffer, make_event
+from remote_server import EventFeed, serve_address
+from session_log import SessionLog
+from core import (analyze_and_record, backup_retention, build_services, cache_diagnostics, confirmation_refusal,
+                  list_device_packages, revalidate_analyses, start_background)
 
+# Responses and background events share stdout, so writes must not interleave
+_stdout_lock = threading.Lock()
 
-def handle_command(command_data, adb, backup_mgr, advisor, openclaw):
+
+def write_message(message):
+    """Write one JSON line to Electron"""
+    with _stdout_lock:
+        sys.stdout.write(json.dumps(message, ensure_ascii=False) + "\n")
+        sys.stdout.flush()
+
+
+# Commands and events of this process, for export_session_report
+SESSION_LOG = SessionLog()
+
+
+# Last event of each kind, for a window that subscribes after it was emitted
+EVENT_REPLAY = EventReplayBuffer()
+
+
+# Numbered events for clients of the remote HTTP API, which cannot read stdout
+EVENT_FEED = EventFeed()
+
+# Commands from stdin and the remote API run one at a time, as they did with stdin alone
+_command_lock = threading.Lock()
+
+
+def emit_event(kind, payload, job_id=None):
+    """Push an unsolicited event (no request id) to Electron in the versioned envelope"""
+    event = make_event(kind, payload, job_id)
+    write_message(event)
+    EVENT_REPLAY.record(event)
+    EVENT_FEED.publish(event)
+    SESSION_LOG.record_event(kind, payload, job_id)
+
+
+def handle_command(command_data, services):
     """Route a single command and return the result."""
     command = command_data.get("command")
     args = command_data.get("args", {})
 
+    adb = services.adb
+    backup_mgr = services.backup_mgr
+    advisor = services.advisor
+    openclaw = services.openclaw
+    health = services.health
+    settings = services.settings
+    labels = services.labels
+    package_cache = services.package_cache
+    privacy = services.privacy
+    tweaks = services.tweaks
+    optimizer = services.optimizer
+    undo = services.undo
+    policy = services.policy
+    session_log = services.session_log
+    streamer = services.streamer
+    restarter = services.restarter
+    trials = services.trials
+    selection = services.selection
+    tutorial = services.tutorial
+    dumps = services.dumps
+    diagnostics = services.diagnostics
+    prefetch = services.prefetch
+    stay_awake = services.stay_awake
+    jobs = services.jobs
+    digests = services.digests
+    recovery = services.recovery
+    unused_apps = services.unused_apps
+
     if command == "get_device_info":
         try:
-            return adb.get_device_info()
+            info = adb.get_device_info()
         except ADBError:
             return None
+        info["nickname"] = settings.device_nickname(info.get("serial"))
+        try:
+            # Probe once on connect so later commands pick fallbacks up front
+            info["capabilities"] = adb.capabilities()
+            info["deviceClass"] = adb.get_device_class()["deviceClass"]
+            info["fireOs"] = adb.get_fire_os_version()
+        except ADBError:
+            pass
+        # Put back a stay-awake setting left changed by a job that never finished
+        stay_awake.restore_pending(info["serial"])
+        # Warm the caches in the background the first time a device is seen
+        prefetch.on_device_seen(info["serial"])
+        return info
+
+    elif command == "subscribe_with_replay":
+        # A window that just finished loading asks for the events it missed
+        kinds = args.get("kinds")
+        unknown = [kind for kind in kinds or [] if kind not in EVENT_KINDS]
+        if unknown:
+            return {"success": False, "message": f"Unknown event kind: {', '.join(unknown)}"}
+        replayed = services.event_replay.replay(kinds)
+        for event in replayed:
+            write_message(event)
+        return {"success": True, "replayed": [event["kind"] for event in replayed]}
+
+    elif command == "start_prefetch":
+        return prefetch.start()
+
+    elif command == "get_prefetch_status":
+        return prefetch.get_status()
+
+    elif command == "hold_stay_awake":
+        # The UI holds the device awake around the batches it runs itself
+        hold_id = stay_awake.acquire(args.get("reason") or "Batch operation")
+        return {"success": hold_id is not None, "holdId": hold_id}
+
+    elif command == "release_stay_awake":
+        return stay_awake.release(args.get("holdId"))
+
+    elif command == "get_stay_awake_status":
+        return stay_awake.get_status()
+
+    elif command == "set_device_nickname":
+        return settings.set_device_nickname(args.get("serial"), args.get("name"))
+
+    elif command == "get_usb_permission_help":
+        return services.usb_permissions.check()
+
+    elif command == "install_udev_rule":
+        return services.usb_permissions.install_rule(args.get("vendorId"))
+
+    elif command == "launch_screen_mirror":
+        return services.screen_mirror.launch(args.get("serial"), bool(args.get("download", False)))
+
+    elif command == "get_screen_mirror_status":
+        return services.screen_mirror.status()
+
+    elif command == "stop_screen_mirror":
+        return services.screen_mirror.stop(args.get("serial"))
+
+    elif command == "probe_device_capabilities":
+        try:
+            return adb.probe_device_capabilities()
+        except ADBError as e:
+            return {"serial": None, "capabilities": {}, "probed": {}, "message": str(e)}
+
+    elif command == "get_device_class":
+        try:
+            detected = adb.get_device_class()
+            fire_os_version = adb.get_fire_os_version()
+        except ADBError as e:
+            return {"deviceClass": None, "message": str(e)}
+        result = {**detected, "label": CLASS_LABELS[detected["deviceClass"]], "fireOs": fire_os_version}
+        if fire_os_version is not None:
+            result["updateNote"] = fire_os.UPDATE_NOTE
+        return result
+
+    elif command == "get_class_presets":
+        try:
+            device_class = adb.get_device_class()["deviceClass"]
+            is_fire_os = adb.get_fire_os_version() is not None
+            installed = {pkg["packageName"] for pkg in package_cache.packages()}
+        except ADBError as e:
+            return {"deviceClass": None, "presets": [], "message": str(e)}
+        presets = presets_for(device_class, installed)
+        if is_fire_os:
+            presets += fire_os.fire_os_presets(installed)
+        return {"deviceClass": device_class, "presets": presets}
+
+    elif command == "get_oem_database":
+        try:
+            return adb.oem_db.summary(adb.get_oem())
+        except ADBError as e:
+            return {**adb.oem_db.summary(None), "message": str(e)}
+
+    elif command == "get_package_signature":
+        package_name = args.get("packageName")
+        try:
+            entry = adb.get_signature_info().get(package_name)
+        except ADBError as e:
+            return {"success": False, "packageName": package_name, "message": str(e)}
+        if entry is None:
+            return {"success": False, "packageName": package_name, "message": f"{package_name} is not in the package dump"}
+        return {"success": True, "packageName": package_name, **entry, "notes": describe_signature(entry)}
+
+    elif command == "get_compatibility":
+        return adb.get_compatibility()
+
+    elif command == "get_device_management":
+        try:
+            return policy.get_management_state()
+        except ADBError:
+            return {"managed": False, "deviceOwner": None, "profileOwners": [], "managedPackages": {}}
 
     elif command == "list_packages":
+        packages = list_device_packages(services, args.get("type", "all"))
+        return compact_packages(packages) if args.get("compact") else packages
+
+    elif command == "stream_packages":
+        return streamer.start(list_device_packages(services, args.get("type", "all")))
+
+    elif command == "ack_package_chunk":
+        return streamer.ack(args.get("jobId"), args.get("index"))
+
+    elif command == "cancel_package_stream":
+        return streamer.cancel(args.get("jobId"))
+
+    elif command == "get_cached_packages":
+        try:
+            page = package_cache.query(
+                sort_by=args.get("sortBy", "name"),
+                direction=args.get("direction", "asc"),
+                offset=args.get("offset", 0),
+                limit=args.get("limit", 100),
+                filters=args.get("filters")
+            )
+        except ADBError:
+            page = {"total": 0, "offset": 0, "limit": 0, "items": [], "cachedAt": None, "stale": False}
+        revalidate_analyses(services, page["items"])
+        if args.get("compact"):
+            page["items"] = compact_packages(page["items"])
+        return page
+
+    elif command == "search_packages":
+        try:
+            packages = package_cache.packages()
+        except ADBError:
+            return []
+        return search_packages(packages, args.get("query", ""), limit=args.get("limit", 50))
+
+    elif command == "list_package_families":
         try:
-            return adb.list_packages(args.get("type", "all"))
+            packages = adb.list_packages(args.get("type", "all"))
         except ADBError:
             return []
+        sizes = {}
+        if args.get("includeSizes", True):
+            try:
+                sizes = adb.get_package_sizes()
+            except ADBError:
+                pass  # Families are still useful without sizes
+        return group_packages(packages, sizes)
+
+    elif command == "get_special_access":
+        try:
+            return privacy.get_special_access(package_cache.packages())
+        except ADBError as e:
+            return {"packages": [], "unchecked": [], "message": str(e)}
+
+    elif command == "get_service_audit":
+        try:
+            return privacy.get_service_audit(package_cache.packages())
+        except ADBError as e:
+            return {"services": [], "unchecked": [], "message": str(e)}
+
+    elif command == "revoke_special_access":
+        return privacy.revoke_special_access(args.get("packageName"), args.get("access"))
+
+    elif command == "get_clipboard_audit":
+        try:
+            return privacy.get_clipboard_audit(package_cache.packages())
+        except ADBError as e:
+            return {"available": False, "packages": [], "message": str(e)}
+
+    elif command == "get_privacy_report":
+        try:
+            return privacy.get_privacy_report(package_cache.packages())
+        except ADBError as e:
+            return {"score": None, "factors": [], "suggestedRemovals": [], "reviewItems": [], "message": str(e)}
+
+    elif command == "get_unused_app_suggestions":
+        return unused_apps.get_suggestions(args.get("days", DEFAULT_UNUSED_DAYS))
+
+    elif command == "resolve_app_labels":
+        try:
+            return labels.resolve_labels(args.get("packages", []))
+        except ADBError as e:
+            return {"available": False, "labels": {}, "unresolved": args.get("packages", []), "pending": [], "message": str(e)}
+
+    elif command == "get_tutorial_state":
+        return tutorial.get_tutorial_state()
+
+    elif command == "advance_tutorial":
+        return tutorial.advance_tutorial(bool(args.get("skip", False)))
+
+    elif command == "dismiss_tutorial":
+        return tutorial.dismiss(args.get("dismissed", True))
+
+    elif command == "validate_selection":
+        return selection.validate_selection(args.get("packages", []))
+
+    elif command == "list_overlays":
+        try:
+            return {"success": True, "overlays": adb.get_overlays()}
+        except UnsupportedFeatureError as e:
+            return {"success": False, "unsupported": True, "overlays": [], "message": str(e)}
+        except ADBError as e:
+            return {"success": False, "overlays": [], "message": str(e)}
+
+    elif command == "list_apex_modules":
+        try:
+            return {"success": True, "modules": adb.get_apex_modules()}
+        except UnsupportedFeatureError as e:
+            return {"success": False, "unsupported": True, "modules": [], "message": str(e)}
+        except ADBError as e:
+            return {"success": False, "modules": [], "message": str(e)}
+
+    elif command == "set_overlay_enabled":
+        enabled = bool(args.get("enabled", True))
+        result = adb.set_overlay_enabled(args.get("overlayId"), enabled)
+        undo.record("overlay_enable" if enabled else "overlay_disable", args.get("overlayId"), result)
+        return result
 
     elif command == "uninstall_package":
-        return adb.uninstall_package(args.get("packageName"))
+        # Packages of a running job were confirmed for the whole batch in start_job
+        if not jobs.includes(args.get("jobId"), args.get("packageName")):
+            refusal = confirmation_refusal(services, "uninstall", [args.get("packageName")], args.get("confirmation"))
+            if refusal:
+                return refusal
+        package_cache.invalidate()
+        result = adb.uninstall_package(args.get("packageName"), bool(args.get("keepData", False)))
+        undo.record("uninstall", args.get("packageName"), result)
+        # Part of a journaled batch: saved before the UI moves on to the next package
+        jobs.record(args.get("jobId"), args.get("packageName"), result)
+        return result
+
+    elif command == "get_weekly_digest":
+        return digests.get_weekly_digest()
+
+    elif command == "get_charging_sessions":
+        return services.charging.get_charging_sessions(args.get("limit", CHARGING_SESSION_LIMIT))
+
+    elif command == "get_battery_trend":
+        package_name = args.get("packageName")
+        days = args.get("days", DEFAULT_TREND_DAYS)
+        return {**digests.get_battery_trend(package_name, days, undo.last_change(package_name)),
+                "packageName": package_name}
+
+    elif command == "start_job":
+        refusal = confirmation_refusal(services, "uninstall", args.get("packages", []), args.get("confirmation"))
+        if refusal:
+            return refusal
+        return jobs.start(args.get("kind"), args.get("packages", []), args.get("options"))
+
+    elif command == "finish_job":
+        return jobs.finish(args.get("jobId"))
+
+    elif command == "get_incomplete_jobs":
+        return jobs.get_incomplete()
+
+    elif command == "resume_job":
+        return jobs.resume(args.get("jobId"))
+
+    elif command == "discard_job":
+        return jobs.discard(args.get("jobId"))
+
+    elif command == "start_trial":
+        refusal = confirmation_refusal(services, "disable", args.get("packages", []), args.get("confirmation"))
+        if refusal:
+            return refusal
+        package_cache.invalidate()
+        return trials.start_trial(args.get("packages", []), args.get("durationHours", DEFAULT_TRIAL_HOURS))
+
+    elif command == "list_trials":
+        return trials.list_trials(bool(args.get("includeEnded", False)))
+
+    elif command == "keep_trial":
+        return trials.keep(args.get("trialId"))
+
+    elif command == "revert_trial":
+        package_cache.invalidate()
+        return trials.revert(args.get("trialId"))
+
+    elif command == "wipe_package_data":
+        refusal = confirmation_refusal(services, "uninstall", [args.get("packageName")], args.get("confirmation"))
+        if refusal:
+            return refusal
+        package_cache.invalidate()
+        result = adb.wipe_package_data(args.get("packageName"))
+        undo.record("wipe_data", args.get("packageName"), result)
+        return result
 
     elif command == "reinstall_package":
-        return adb.reinstall_package(args.get("packageName"))
+        package_cache.invalidate()
+        result = adb.reinstall_package(args.get("packageName"))
+        undo.record("reinstall", args.get("packageName"), result)
+        return result
 
     elif command == "analyze_package":
-        return advisor.analyze_package(args.get("packageName"))
+        package_name = args.get("packageName")
+        if network.is_offline():
+            try:
+                oem = adb.get_oem()
+            except ADBError:
+                oem = None  # Carrier and partner preloads are listed for every maker
+            listed = adb.oem_db.lookup(oem, package_name)
+            return local_analysis(package_name, adb.safety_rater()(package_name),
+                                  listed["description"] if listed else None)
+        return analyze_and_record(services, package_name)
 
     elif command == "chat_message":
-        response = advisor.chat(args.get("message", ""), args.get("history", []))
-        return {"response": response}
+        reply = advisor.chat(args.get("message", ""), args.get("history", []))
+        checked = apply_guardrails(reply["content"], adb.safety_rater())
+        return {
+            "response": checked["content"],
+            "relatedQuestions": reply["relatedQuestions"],
+            "safetyWarnings": checked["warnings"]
+        }
+
+    elif command == "get_settings":
+        return settings.get_settings()
+
+    elif command == "update_settings":
+        result = settings.update_settings(args.get("settings", {}))
+        if result["success"] and "remote" in (args.get("settings") or {}):
+            result["remote"] = services.remote.apply()
+        return result
+
+    elif command == "get_remote_status":
+        return services.remote.status()
+
+    elif command == "regenerate_remote_token":
+        return services.remote.regenerate_token()
+
+    elif command == "list_profiles":
+        return services.profiles.list_profiles()
+
+    elif command == "get_confirmation_policy":
+        return services.confirmations.get_policy()
+
+    elif command == "set_confirmation_role":
+        return services.confirmations.set_role(args.get("role"))
+
+    elif command == "get_confirmation_requirement":
+        try:
+            return services.confirmations.requirement(args.get("operation", "uninstall"), args.get("packages", []),
+                                                      adb.safety_rater())
+        except ValueError as e:
+            return {"success": False, "message": str(e)}
+
+    elif command == "reset_settings":
+        return settings.reset_settings()
+
+    elif command == "test_connection":
+        return network.test_connection(advisor.endpoint_url())
+
+    elif command == "get_ai_usage_stats":
+        return advisor.usage.get_stats()
+
+    elif command == "set_ai_budget":
+        return advisor.usage.set_budget(args.get("monthlyBudgetUsd"))
     
     # OpenClaw Integration Commands
     elif command == "parse_chat_command":
@@ -52,16 +490,68 @@ def handle_command(command_data, adb, backup_mgr, advisor, openclaw):
         # Execute confirmed action
         execution_result = args.get("executionResult", {})
         confirmed = args.get("confirmed", False)
+        if confirmed and execution_result.get("action") == "uninstall":
+            packages = [p.get("packageName") for p in (execution_result.get("data") or {}).get("packages", [])]
+            refusal = confirmation_refusal(services, "uninstall", packages, args.get("confirmation"))
+            if refusal:
+                return refusal
+        package_cache.invalidate()
         return openclaw.execute_confirmed_action(execution_result, confirmed)
 
     elif command == "create_backup":
-        return backup_mgr.create_backup(args.get("packages", []), args.get("deviceInfo"))
+        result = backup_mgr.create_backup(args.get("packages", []), args.get("deviceInfo"),
+                                          bool(args.get("includeData", False)))
+        if result["success"]:
+            keep_per_device, max_age_days = backup_retention(settings)
+            if keep_per_device or max_age_days:
+                result["cleanup"] = backup_mgr.apply_retention(keep_per_device, max_age_days)
+        return result
+
+    elif command == "create_full_backup":
+        return backup_mgr.create_full_backup(args.get("packages", []), args.get("deviceInfo"))
 
     elif command == "list_backups":
-        return backup_mgr.list_backups()
+        # Default to the connected device's restore points
+        serial = args.get("serial")
+        if serial is None and not args.get("allDevices"):
+            try:
+                serial = adb.get_serial()
+            except ADBError:
+                pass
+        return backup_mgr.list_backups(serial)
+
+    elif command == "list_backup_groups":
+        return group_backups(backup_mgr.list_backups())
+
+    elif command == "preview_retention_cleanup":
+        # Optional overrides let the UI preview a policy before saving it
+        keep_per_device, max_age_days = backup_retention(settings)
+        return backup_mgr.preview_retention_cleanup(args.get("keepPerDevice", keep_per_device),
+                                                    args.get("maxAgeDays", max_age_days))
+
+    elif command == "run_retention_cleanup":
+        return backup_mgr.apply_retention(*backup_retention(settings))
 
     elif command == "restore_backup":
-        return backup_mgr.restore_backup(args.get("backupName"))
+        package_cache.invalidate()
+        with stay_awake_during(stay_awake, "Restore"):
+            result = backup_mgr.restore_backup(args.get("backupName"), bool(args.get("restoreData", False)))
+        for item in result.get("results", []):
+            if item["status"] == "restored":
+                undo.record("reinstall", item["packageName"], {"success": True})
+        return result
+
+    elif command == "preview_backup_transfer":
+        return backup_mgr.preview_transfer(args.get("backupName"))
+
+    elif command == "apply_backup_transfer":
+        package_cache.invalidate()
+        with stay_awake_during(stay_awake, "Restore"):
+            result = backup_mgr.apply_transfer(args.get("backupName"))
+        for entry in result.get("entries", []):
+            if entry.get("restored"):
+                undo.record("reinstall", entry["target"], {"success": True})
+        return result
 
     elif command == "delete_backup":
         return backup_mgr.delete_backup(args.get("backupName"))
@@ -69,62 +559,254 @@ def handle_command(command_data, adb, backup_mgr, advisor, openclaw):
     elif command == "get_backup_path":
         return {"path": backup_mgr.get_backup_path()}
 
+    # System Health Commands
+    elif command == "get_system_health":
+        try:
+            return health.get_cached_health()
+        except ADBError:
+            return None
+
+    elif command == "start_health_monitor":
+        return health.start_monitor(services.emit_event, args.get("intervalSeconds", 5))
+
+    elif command == "stop_health_monitor":
+        return health.stop_monitor()
+
+    elif command == "get_cache_diagnostics":
+        return cache_diagnostics(services)
+
+    elif command == "clear_health_cache":
+        return health.clear_health_cache(args.get("serial"))
+
+    elif command == "list_raw_dump_services":
+        return dumps.list_services()
+
+    elif command == "get_raw_dump":
+        return dumps.get_raw_dump(
+            args.get("service", ""),
+            args.get("packageName"),
+            args.get("page", 0),
+            args.get("pageLines", 500),
+            bool(args.get("refresh", False))
+        )
+
+    elif command == "list_guided_actions":
+        return services.guided.list_actions()
+
+    elif command == "open_settings_page":
+        return services.guided.open_settings_page(args.get("page", ""), args.get("packageName"))
+
+    elif command == "open_app_info":
+        return services.guided.open_app_info(args.get("packageName", ""))
+
+    elif command == "open_in_play_store":
+        return services.guided.open_in_play_store(args.get("packageName", ""))
+
+    elif command == "press_guided_key":
+        return services.guided.press_key(args.get("key", ""))
+
+    elif command == "guided_tap":
+        return services.guided.tap(args.get("target"))
+
+    elif command == "get_performance_report":
+        try:
+            return build_performance_report(health.get_system_health(), tweaks.get_animation_scales())
+        except ADBError as e:
+            return {"score": None, "factors": [], "recommendations": [], "message": str(e)}
+
+    elif command == "set_animation_scale":
+        result = tweaks.set_animation_scale(args.get("scale", 1))
+        undo.record("animation_scale", None, result)
+        return result
+
+    elif command == "trim_caches":
+        package_cache.invalidate()
+        result = tweaks.trim_caches()
+        undo.record("trim_caches", None, result)
+        return result
+
+    elif command == "restrict_background":
+        result = tweaks.restrict_background(args.get("packageName"), args.get("restricted", True))
+        undo.record("background", args.get("packageName"), result)
+        return result
+
+    elif command == "mute_notifications":
+        result = tweaks.mute_notifications(args.get("packageName"), args.get("muted", True))
+        undo.record("notifications", args.get("packageName"), result)
+        return result
+
+    elif command == "revoke_permission":
+        result = tweaks.revoke_permission(args.get("packageName"), args.get("permission"))
+        undo.record("permission", args.get("packageName"), result)
+        return result
+
+    elif command == "grant_permission":
+        result = tweaks.grant_permission(args.get("packageName"), args.get("permission"))
+        undo.record("permission", args.get("packageName"), result)
+        return result
+
+    elif command == "restart_system_ui":
+        return restarter.restart_system_ui()
+
+    elif command == "restart_launcher":
+        return restarter.restart_launcher()
+
+    elif command == "soft_reboot":
+        return restarter.soft_reboot(bool(args.get("confirmed", False)))
+
+    elif command == "reboot_device":
+        return restarter.reboot_device(args.get("mode", "system"), bool(args.get("confirmed", False)))
+
+    elif command == "emergency_recover":
+        package_cache.invalidate()
+        return recovery.recover(bool(args.get("confirmed", False)), float(args.get("hours", DEFAULT_RECOVERY_HOURS)),
+                                bool(args.get("restoreBackup", True)), bool(args.get("safeMode", True)),
+                                lambda packages: confirmation_refusal(services, "recover", packages,
+                                                                      args.get("confirmation")))
+
+    elif command == "run_optimization":
+        plan = args.get("plan") or {}
+        refusal = confirmation_refusal(services, "uninstall", plan.get("removePackages", []), args.get("confirmation"))
+        if refusal:
+            return refusal
+        return optimizer.run_optimization(args.get("plan"))
+
+    elif command == "export_session_report":
+        try:
+            device_info = adb.get_device_info()
+        except ADBError:
+            device_info = None
+        report = session_log.render(device_info, adb.get_compatibility() if device_info else None)
+        reports_dir = Path.home() / "DebloatAI" / "reports"
+        reports_dir.mkdir(parents=True, exist_ok=True)
+        path = reports_dir / f"session_{time.strftime('%Y%m%d_%H%M%S')}.txt"
+        path.write_text(report, encoding="utf-8")
+        return {"success": True, "report": report, "path": str(path), "message": f"Session report saved to {path}"}
+
+    elif command == "export_analyses":
+        export_format = args.get("format", "markdown")
+        if export_format not in EXPORT_FORMATS:
+            return {"success": False, "message": f"Unknown format {export_format}; use one of {', '.join(EXPORT_FORMATS)}"}
+        packages = args.get("packages") or services.analyses.packages()
+        if not packages:
+            return {"success": False, "message": "No packages have been analyzed yet"}
+        try:
+            device_info = adb.get_device_info()
+        except ADBError:
+            device_info = None
+        rows = build_rows(packages, services.analyses.get, adb.safety_rater())
+        report = render_analyses(rows, export_format, device_info)
+        reports_dir = Path.home() / "DebloatAI" / "reports"
+        reports_dir.mkdir(parents=True, exist_ok=True)
+        path = reports_dir / f"analyses_{time.strftime('%Y%m%d_%H%M%S')}.{EXPORT_EXTENSIONS[export_format]}"
+        path.write_text(report, encoding="utf-8")
+        return {"success": True, "report": report, "path": str(path), "count": len(rows),
+                "message": f"{len(rows)} packages exported to {path}"}
+
+    elif command == "list_parser_failures":
+        return diagnostics.list_failures()
+
+    elif command == "export_diagnostics":
+        try:
+            device_info = adb.get_device_info()
+        except ADBError:
+            device_info = None
+        return diagnostics.export_diagnostics(
+            bool(args.get("includeOutput", False)),
+            device_info,
+            adb.get_compatibility() if device_info else None
+        )
+
+    elif command == "clear_parser_failures":
+        return diagnostics.clear()
+
+    elif command == "list_crash_reports":
+        return services.crashes.list_reports()
+
+    elif command == "clear_crash_reports":
+        return services.crashes.clear()
+
+    elif command == "get_undo_stack":
+        return undo.get_undo_stack()
+
+    elif command == "undo":
+        package_cache.invalidate()
+        return undo.undo(args.get("count", 1),
+                         lambda operation, packages: confirmation_refusal(services, operation, packages,
+                                                                          args.get("confirmation")))
+
     else:
         return {"success": False, "error": f"Unknown command: {command}"}
 
 
+def run_command(request, services):
+    """Run one {id, command, args} request from stdin or the remote API; returns {id, result} or {id, error}"""
+    request_id = request.get("id")
+    with _command_lock:
+        try:
+            result = handle_command(request, services)
+            SESSION_LOG.record_command(request.get("command"), request.get("args"), result)
+            return {"id": request_id, "result": result}
+        except Exception as exc:
+            SESSION_LOG.record_command(request.get("command"), request.get("args"), error=str(exc))
+            services.crashes.capture(exc, "command", command=request.get("command"))
+            return {"id": request_id, "error": str(exc)}
+
+
 def main():
     """
     Persistent process: initialise modules once, then loop over stdin lines.
     Each line is a JSON object with {id, command, args}.
     Each response is a JSON object with {id, result} or {id, error}.
+
+    With --serve (and optionally --host/--port) there is no stdin loop: the
+    backend runs headless behind the remote HTTP API until it is stopped.
     """
-    # Initialise heavy modules once
-    adb = ADBOperations()
-    backup_mgr = BackupManager()
-    try:
-        advisor = AIAdvisor(provider="perplexity")
-    except Exception as e:
-        print(f"[Warning] AI advisor init failed: {e}", file=sys.stderr)
-        advisor = AIAdvisor.__new__(AIAdvisor)
-        advisor.api_key = None
-        advisor.provider = "perplexity"
-        advisor.api_url = "https://api.perplexity.ai/chat/completions"
-        advisor.model = "sonar"
-    
-    # Initialize OpenClaw integration
-    openclaw = OpenClawIntegration(adb)
+    serve = "--serve" in sys.argv
+    host, port = serve_address(sys.argv) if serve else (None, None)
+    services = build_services(emit_event, SESSION_LOG, EVENT_REPLAY, event_feed=EVENT_FEED)
+    services.crashes.install()
+    start_background(services)
+    services.remote.start(lambda request: run_command(request, services), force=serve, host=host, port=port)
+
+    def terminate(signum, frame):
+        services.shutdown.run(f"signal {signum}")
+        sys.exit(0)
+
+    # Electron falls back to killing the process when stdin closing is not enough
+    signal.signal(signal.SIGTERM, terminate)
+    signal.signal(signal.SIGINT, terminate)
+
+    if serve:
+        status = services.remote.status()
+        if not status["running"]:
+            print(status["error"], file=sys.stderr)
+            services.shutdown.run("remote server failed")
+            sys.exit(1)
+        print(f"Serving {status['url']} (token in {services.remote.token_path})", file=sys.stderr)
+        while True:
+            time.sleep(3600)  # Until SIGTERM or Ctrl+C; sleep is interrupted by signals on every platform
 
     # Signal that we are ready
-    sys.stdout.write(json.dumps({"status": "ready"}) + "\n")
-    sys.stdout.flush()
+    write_message({"status": "ready"})
 
     while True:
         try:
             line = sys.stdin.readline()
             if not line:
+                services.shutdown.run("stdin closed")
                 break  # EOF – Electron closed our stdin
             line = line.strip()
             if not line:
                 continue
 
-            request = json.loads(line)
-            request_id = request.get("id")
-
-            try:
-                result = handle_command(request, adb, backup_mgr, advisor, openclaw)
-                response = {"id": request_id, "result": result}
-            except Exception as exc:
-                response = {"id": request_id, "error": str(exc)}
-
-            sys.stdout.write(json.dumps(response, ensure_ascii=False) + "\n")
-            sys.stdout.flush()
+            write_message(run_command(json.loads(line), services))
 
         except json.JSONDecodeError as exc:
-            sys.stdout.write(json.dumps({"id": None, "error": f"JSON parse error: {exc}"}) + "\n")
-            sys.stdout.flush()
-        except Exception:
+            write_message({"id": None, "error": f"JSON parse error: {exc}"})
+        except Exception as exc:
             traceback.print_exc(file=sys.stderr)
+            services.crashes.capture(exc, "main loop")
 
 
 if __name__ == "__main__":
@@ -0,0 +1,99 @@
+"""
+Network Module
+Single chokepoint for every outbound HTTP request of the backend, so the
+network settings apply everywhere: offline mode refuses all requests, and
+the proxy and custom CA bundle are used by all of them
+"""
+import time
+from typing import Any, Callable, Dict
+from urllib.parse import urlsplit
+import requests
+
+
+class OfflineModeError(Exception):
+    """An outbound request was refused because offline mode is on"""
+    pass
+
+
+OFFLINE_MESSAGE = "Offline mode is on: network features are disabled (turn it off in Settings)"
+
+# Seconds allowed for test_connection
+TEST_TIMEOUT = 10
+
+NETWORK_DEFAULTS = {"offline": False, "proxy": "", "caBundle": ""}
+
+# Set by main.py to read the `network.*` settings; modules used on their own get the defaults
+_setting: Callable[[str], Any] = NETWORK_DEFAULTS.get
+
+
+def configure(setting: Callable[[str], Any]):
+    """Install the function reading a `network.*` setting by key"""
+    global _setting
+    _setting = setting
+
+
+def is_offline() -> bool:
+    return bool(_setting("offline"))
+
+
+def request_options() -> Dict:
+    """requests keyword arguments for the configured proxy and CA bundle"""
+    options = {}
+    proxy = _setting("proxy")
+    if proxy:
+        options["proxies"] = {"http": proxy, "https": proxy}
+    ca_bundle = _setting("caBundle")
+    if ca_bundle:
+        options["verify"] = ca_bundle
+    return options
+
+
+def request(method: str, url: str, **kwargs) -> requests.Response:
+    """requests.request through the configured proxy/CA, refused with OfflineModeError in offline mode"""
+    if is_offline():
+        raise OfflineModeError(OFFLINE_MESSAGE)
+    return requests.request(method, url, **{**request_options(), **kwargs})
+
+
+def post(url: str, **kwargs) -> requests.Response:
+    return request("POST", url, **kwargs)
+
+
+def describe_error(error: Exception) -> str:
+    """What a failed request says about the network path, in user terms"""
+    if isinstance(error, OfflineModeError):
+        return str(error)
+    if isinstance(error, requests.exceptions.ProxyError):
+        return f"The proxy refused or could not forward the connection: {error}"
+    if isinstance(error, requests.exceptions.SSLError):
+        hint = "the custom CA bundle" if _setting("caBundle") else "a custom CA bundle (e.g. your company's root certificate)"
+        return f"TLS certificate check failed; the network may intercept HTTPS, try {hint}: {error}"
+    if isinstance(error, requests.exceptions.Timeout):
+        return "The connection timed out; a firewall may block it, or a proxy may be required"
+    if isinstance(error, requests.exceptions.ConnectionError):
+        return f"Could not connect; check the internet connection or proxy settings: {error}"
+    return str(error)
+
+
+def test_connection(url: str) -> Dict:
+    """
+    Check that `url` can be reached with the current network settings.
+
+    Any HTTP response, even an error status, proves the path through the
+    proxy and TLS works; no API call is made.
+    """
+    base = "{0.scheme}://{0.netloc}/".format(urlsplit(url))
+    details = {"url": base, "proxy": _setting("proxy") or None, "caBundle": _setting("caBundle") or None}
+    started = time.monotonic()
+    try:
+        response = request("GET", base, timeout=TEST_TIMEOUT)
+    except (OfflineModeError, requests.exceptions.RequestException, OSError) as e:
+        return {"success": False, "message": describe_error(e), **details}
+    elapsed_ms = round((time.monotonic() - started) * 1000)
+    return {
+        "success": True,
+        "message": f"Reached {urlsplit(url).netloc} in {elapsed_ms} ms (HTTP {response.status_code})",
+        "statusCode": response.status_code,
+        "elapsedMs": elapsed_ms,
+        **details,
+    }
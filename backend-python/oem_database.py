@@ -0,0 +1,176 @@
+"""
+OEM Database Module
+Offline safety ratings for the packages phone makers, carriers and their
+partners preload. Each OEM has one JSON file in oem_data/ listing package ->
+[safety level, what it is]; only the connected device's file, the files it
+includes (OnePlus and realme build on ColorOS) and the packs every device
+gets (carrier apps, partner preloads) are read, on first use, so the
+backend does not hold every OEM's list in memory. A user database in the
+same format (~/DebloatAI/package_database.json) is merged over all of them
+"""
+import json
+import sys
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+from validation import is_package_name
+
+
+# PyInstaller unpacks bundled data next to the modules in sys._MEIPASS
+DATA_DIR = Path(getattr(sys, "_MEIPASS", Path(__file__).parent)) / "oem_data"
+
+# Packs loaded on every device, after the OEM's own (which wins on overlap)
+SHARED_PACKS = ("carriers", "partners")
+
+# Hand-maintained ratings of the user, merged last so they win over every built-in pack
+USER_DATABASE_FILE = "package_database.json"
+USER_PACK = "user"
+
+# Lower-cased ro.product.manufacturer or ro.product.brand -> OEM file
+OEM_MANUFACTURERS = {
+    "samsung": "samsung",
+    "xiaomi": "xiaomi",
+    "redmi": "xiaomi",
+    "poco": "xiaomi",
+    "oppo": "oppo",
+    "vivo": "vivo",
+    "iqoo": "vivo",
+    "oneplus": "oneplus",
+    "motorola": "motorola",
+    "realme": "realme",
+    "huawei": "huawei",
+    "google": "google",
+    "hmd global": "nokia",
+    "nokia": "nokia",
+}
+
+# Manufacturer then brand, e.g. "Xiaomi" / "POCO"
+DETECTION_SCRIPT = "getprop ro.product.manufacturer; getprop ro.product.brand"
+
+def parse_oem(output: str) -> Optional[str]:
+    """OEM file for the output of DETECTION_SCRIPT, None for makers without one"""
+    for value in output.split('\n')[:2]:
+        oem = OEM_MANUFACTURERS.get(value.strip().lower())
+        if oem:
+            return oem
+    return None
+
+
+def validate_pack(pack: Dict) -> List[str]:
+    """Problems with one OEM file: unknown levels, malformed names, missing descriptions"""
+    from adb_operations import SAFETY_LEVELS  # adb_operations imports this module
+    problems = []
+    for package, entry in pack.get("packages", {}).items():
+        if not is_package_name(package):
+            problems.append(f"{package}: not a package name")
+        if not isinstance(entry, list) or len(entry) != 2:
+            problems.append(f"{package}: expected [level, description]")
+            continue
+        level, description = entry
+        if level not in SAFETY_LEVELS:
+            problems.append(f"{package}: unknown safety level {level!r}")
+        if not description:
+            problems.append(f"{package}: missing description")
+    return problems
+
+
+class OemDatabase:
+    """Built-in per-OEM package ratings, read lazily from oem_data/"""
+
+    def __init__(self, data_dir: Path = DATA_DIR, user_file: Optional[Path] = None):
+        self.data_dir = Path(data_dir)
+        self.user_file = Path(user_file) if user_file else None
+        # pack name -> parsed file
+        self._packs: Dict[str, Dict] = {}
+        # OEM -> merged package -> (level, description, pack)
+        self._merged: Dict[str, Dict[str, Tuple[str, str, str]]] = {}
+        self._user_pack = self._load_user_pack()
+
+    def _load_user_pack(self, strict: bool = False) -> Dict:
+        """The user database; missing counts as empty, a broken one too unless `strict`"""
+        empty = {"name": "User database", "packages": {}}
+        if not self.user_file or not self.user_file.exists():
+            return empty
+        try:
+            with open(self.user_file, encoding="utf-8") as f:
+                pack = json.load(f)
+            if not isinstance(pack, dict) or not isinstance(pack.get("packages", {}), dict):
+                raise ValueError("expected {\"packages\": {package: [level, description]}}")
+            problems = validate_pack(pack)
+            if problems:
+                raise ValueError("; ".join(problems[:3]))
+        except (OSError, ValueError) as e:
+            if strict:
+                raise ValueError(f"{self.user_file.name}: {e}")
+            print(f"[Warning] Ignoring {self.user_file}: {e}", file=sys.stderr)
+            return empty
+        return pack
+
+    def reload(self) -> Optional[Dict]:
+        """Re-read the user database after an edit; None when nothing changed, ValueError keeps the current one"""
+        loaded = self._load_user_pack(strict=True)
+        if loaded == self._user_pack:
+            return None
+        self._user_pack = loaded
+        self._merged.clear()
+        return {"packageCount": len(loaded.get("packages", {}))}
+
+    def available(self) -> List[str]:
+        """OEM files shipped with the app (read from the directory listing only)"""
+        return sorted(p.stem for p in self.data_dir.glob("*.json") if p.stem not in SHARED_PACKS)
+
+    def pack(self, name: str) -> Dict:
+        """One parsed file; a missing or unreadable file counts as empty"""
+        if name not in self._packs:
+            try:
+                with open(self.data_dir / f"{name}.json", encoding="utf-8") as f:
+                    self._packs[name] = json.load(f)
+            except (OSError, json.JSONDecodeError) as e:
+                print(f"[OEM database] {name}.json not loaded: {e}", file=sys.stderr)
+                self._packs[name] = {"name": name, "packages": {}}
+        return self._packs[name]
+
+    def packages(self, oem: Optional[str]) -> Dict[str, Tuple[str, str, str]]:
+        """package -> (level, description, pack) for a device of `oem` (None: shared packs only)"""
+        key = oem or ""
+        if key not in self._merged:
+            order = list(SHARED_PACKS)
+            if oem:
+                included = self.pack(oem).get("includes", [])
+                order += [name for name in included if name not in order] + [oem]
+            merged: Dict[str, Tuple[str, str, str]] = {}
+            # Later packs override earlier ones: the OEM's own file has the last word
+            for name in order:
+                for package, (level, description) in self.pack(name).get("packages", {}).items():
+                    merged[package] = (level, description, name)
+            for package, (level, description) in self._user_pack.get("packages", {}).items():
+                merged[package] = (level, description, USER_PACK)
+            self._merged[key] = merged
+        return self._merged[key]
+
+    def lookup(self, oem: Optional[str], package_name: str) -> Optional[Dict]:
+        """{"safetyLevel", "description", "source"} of a listed package, None otherwise"""
+        entry = self.packages(oem).get(package_name)
+        if entry is None:
+            return None
+        level, description, source = entry
+        return {"safetyLevel": level, "description": description, "source": source}
+
+    def safety_level(self, oem: Optional[str], package_name: str) -> Optional[str]:
+        entry = self.packages(oem).get(package_name)
+        return entry[0] if entry else None
+
+    def summary(self, oem: Optional[str]) -> Dict:
+        """What the database knows for a device of `oem`, for get_oem_database"""
+        packages = self.packages(oem)
+        counts: Dict[str, int] = {}
+        for _, _, source in packages.values():
+            counts[source] = counts.get(source, 0) + 1
+        return {
+            "oem": oem,
+            "name": self.pack(oem).get("name", oem) if oem else None,
+            "packageCount": len(packages),
+            "packs": counts,
+            "loaded": sorted(self._packs),
+            "available": self.available(),
+            "userDatabase": str(self.user_file) if self.user_file else None,
+        }
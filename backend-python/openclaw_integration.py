@@ -4,8 +4,15 @@ Enables chatbot to execute actions via command parsing
 """
 import json
 import re
+import threading
+import uuid
 from typing import Dict, List, Optional, Tuple
-from adb_operations import ADBOperations
+from adb_operations import ADBOperations, ADBError
+from events import EmitFn, no_emit
+from stay_awake import stay_awake_during
+
+# How long a batch waits for a lost device to come back before giving up
+DEVICE_RECONNECT_TIMEOUT = 120
 
 
 class CommandParser:
@@ -107,8 +114,13 @@ class CommandParser:
 class ActionExecutor:
     """Execute actions parsed from commands"""
     
-    def __init__(self, adb_operations: ADBOperations):
+    def __init__(self, adb_operations: ADBOperations, emit_event: EmitFn = None, undo=None, stay_awake=None,
+                 package_cache=None):
         self.adb = adb_operations
+        self.emit_event = emit_event or no_emit
+        self.undo = undo
+        self.stay_awake = stay_awake
+        self.package_cache = package_cache
     
     def execute(self, parsed_command: Dict) -> Dict:
         """
@@ -315,7 +327,8 @@ class ActionExecutor:
         data = action_result.get('data', {})
         
         if action == 'uninstall':
-            return self._execute_uninstall(data.get('packages', []))
+            with stay_awake_during(self.stay_awake, "Chat uninstall"):
+                return self._execute_uninstall(data.get('packages', []))
         elif action == 'backup':
             return self._execute_backup()
         elif action == 'restore':
@@ -327,26 +340,113 @@ class ActionExecutor:
         }
     
     def _execute_uninstall(self, packages: List[Dict]) -> Dict:
-        """Execute actual uninstall"""
-        results = []
-        success_count = 0
-        
-        for pkg in packages:
+        """
+        Execute actual uninstall. If the device disconnects, the rest is left
+        to a background job that waits for it, so commands are not held up
+        meanwhile; it reports with a `chat_uninstall_complete` event
+        """
+        job = {'id': uuid.uuid4().hex[:12], 'total': len(packages), 'pending': list(packages), 'results': []}
+        if self._uninstall_pending(job):
+            return self._uninstall_result(job)
+
+        self._device_lost(job)
+        threading.Thread(target=self._resume_uninstall, args=(job,), name="chat-uninstall", daemon=True).start()
+        done = sum(1 for r in job['results'] if r['success'])
+        return {
+            'success': done > 0,
+            'paused': True,
+            'jobId': job['id'],
+            'message': (f"Device disconnected after removing {done}/{job['total']} packages. "
+                        f"The other {len(job['pending'])} will be removed when it reconnects."),
+            'details': job['results'],
+            'pending': [p['packageName'] for p in job['pending']]
+        }
+
+    def _uninstall_pending(self, job: Dict) -> bool:
+        """Uninstall the job's pending packages in order; False when the device disconnects"""
+        while job['pending']:
+            pkg = job['pending'][0]
             result = self.adb.uninstall_package(pkg['packageName'])
-            results.append({
+            if result.get('deviceLost'):
+                return False  # retry the same package after reconnecting
+
+            job['pending'].pop(0)
+            if self.undo is not None:
+                self.undo.record('uninstall', pkg['packageName'], result)
+            job['results'].append({
                 'package': pkg['packageName'],
                 'success': result.get('success', False),
                 'message': result.get('message', '')
             })
-            if result.get('success'):
-                success_count += 1
-        
+        return True
+
+    def _device_lost(self, job: Dict):
+        self.emit_event('device_lost', {
+            'operation': 'uninstall',
+            'completed': job['results'],
+            'pending': [p['packageName'] for p in job['pending']]
+        }, job['id'])
+
+    def _resume_uninstall(self, job: Dict):
+        """Wait for the device to come back and finish the removal, however many times it drops"""
+        with stay_awake_during(self.stay_awake, "Chat uninstall"):
+            while True:
+                if not self.adb.wait_for_device(DEVICE_RECONNECT_TIMEOUT):
+                    done = sum(1 for r in job['results'] if r['success'])
+                    result = {
+                        'success': done > 0,
+                        'interrupted': True,
+                        'message': (f"Device disconnected after removing {done}/{job['total']} packages. "
+                                    f"{len(job['pending'])} package(s) were not processed."),
+                        'details': job['results'],
+                        'pending': [p['packageName'] for p in job['pending']]
+                    }
+                    break
+                self.emit_event('device_restored', {'operation': 'uninstall', 'remaining': len(job['pending'])},
+                                job['id'])
+                if self._uninstall_pending(job):
+                    result = self._uninstall_result(job)
+                    break
+                self._device_lost(job)
+        if self.package_cache is not None:
+            self.package_cache.invalidate()
+        self.emit_event('chat_uninstall_complete', result, job['id'])
+
+    def _uninstall_result(self, job: Dict) -> Dict:
+        results = job['results']
+        discrepancies = self._verify_removal(results)
+        success_count = sum(1 for r in results if r['success'])
+        message = f"Successfully removed {success_count}/{job['total']} packages"
+        if discrepancies:
+            message += f" ({len(discrepancies)} reported success but are still installed: {', '.join(discrepancies)})"
         return {
             'success': success_count > 0,
-            'message': f"Successfully removed {success_count}/{len(packages)} packages",
-            'details': results
+            'message': message,
+            'details': results,
+            'discrepancies': discrepancies
         }
     
+    def _verify_removal(self, results: List[Dict]) -> List[str]:
+        """Check reported removals against the device; returns packages that are still installed"""
+        removed = [r['package'] for r in results if r['success']]
+        if not removed:
+            return []
+        try:
+            statuses = self.adb.verify_removal(removed)
+        except ADBError:
+            return []  # Keep the reported results when the device cannot be queried
+        discrepancies = []
+        for r in results:
+            status = statuses.get(r['package'])
+            if status is None:
+                continue
+            r['verified'] = status
+            if status == 'installed':
+                r['success'] = False
+                r['message'] = 'Uninstall reported success, but the package is still installed (possibly reinstalled by the system)'
+                discrepancies.append(r['package'])
+        return discrepancies
+    
     def _execute_backup(self) -> Dict:
         """Execute backup creation"""
         # This would call backup_manager
@@ -361,6 +461,8 @@ class ActionExecutor:
             return {'success': False, 'message': 'No package specified'}
         
         result = self.adb.reinstall_package(package)
+        if self.undo is not None:
+            self.undo.record('reinstall', package, result)
         return {
             'success': result.get('success', False),
             'message': result.get('message', 'Restore failed')
@@ -370,9 +472,10 @@ class ActionExecutor:
 class OpenClawIntegration:
     """Main integration class for OpenClaw-powered chatbot"""
     
-    def __init__(self, adb_operations: ADBOperations):
+    def __init__(self, adb_operations: ADBOperations, emit_event: EmitFn = None, undo=None, stay_awake=None,
+                 package_cache=None):
         self.parser = CommandParser()
-        self.executor = ActionExecutor(adb_operations)
+        self.executor = ActionExecutor(adb_operations, emit_event, undo, stay_awake, package_cache)
     
     def process_message(self, message: str) -> Dict:
         """
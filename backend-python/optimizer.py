@@ -0,0 +1,230 @@
+"""
+Optimizer Module
+Runs a selected optimization plan (remove bloat, restrict background apps,
+trim caches, apply tweaks) as one background job with progress events and
+a before/after impact summary
+"""
+import threading
+import time
+import uuid
+from typing import Dict, List, Optional
+from adb_operations import ADBOperations, ADBError
+from events import EmitFn, no_emit
+from stay_awake import stay_awake_during
+
+
+# Stage ids in execution order
+STAGE_LABELS = {
+    "remove": "Removing bloatware",
+    "restrict": "Restricting background apps",
+    "trim": "Trimming app caches",
+    "tweaks": "Applying tweaks",
+}
+
+# Removal through the optimizer is limited to these safety levels
+REMOVABLE_LEVELS = ("Safe", "Caution")
+
+
+def impact_metrics(snapshot: Dict, package_count: Optional[int]) -> Dict:
+    """Pick the figures shown in the before/after summary from a health snapshot"""
+    memory = snapshot.get("memory") or {}
+    storage = (snapshot.get("storage") or {}).get("internal") or {}
+    services = snapshot.get("services") or {}
+    return {
+        "memoryAvailableKb": memory.get("availableKb"),
+        "storageAvailableKb": storage.get("availableKb"),
+        "runningServices": services.get("count"),
+        "packageCount": package_count,
+    }
+
+
+def diff_metrics(before: Dict, after: Dict) -> Dict:
+    """After minus before for every metric known on both sides"""
+    return {
+        key: (after[key] - before[key]) if before.get(key) is not None and after.get(key) is not None else None
+        for key in before
+    }
+
+
+class Optimizer:
+    """Run optimization plans as a single background job"""
+
+    def __init__(self, adb_operations: ADBOperations, health, tweaks, package_cache, undo,
+                 emit_event: EmitFn = None, stay_awake=None):
+        self.adb = adb_operations
+        self.health = health
+        self.tweaks = tweaks
+        self.package_cache = package_cache
+        self.undo = undo
+        self.emit_event = emit_event or no_emit
+        self.stay_awake = stay_awake
+        self._job: Optional[Dict] = None
+        self._lock = threading.Lock()
+
+    def _package_count(self) -> Optional[int]:
+        try:
+            output = self.adb.shell("pm list packages", timeout=30)
+        except ADBError:
+            return None
+        return sum(1 for line in output.split('\n') if line.startswith("package:"))
+
+    def _measure(self) -> Dict:
+        try:
+            snapshot = self.health.get_system_health()
+        except ADBError:
+            snapshot = {}
+        return impact_metrics(snapshot, self._package_count())
+
+    def _plan_stages(self, plan: Dict) -> List[str]:
+        stages = []
+        if plan.get("removePackages"):
+            stages.append("remove")
+        if plan.get("restrictBackground"):
+            stages.append("restrict")
+        if plan.get("trimCaches"):
+            stages.append("trim")
+        if plan.get("animationScale") is not None:
+            stages.append("tweaks")
+        return stages
+
+    def run_optimization(self, plan: Dict) -> Dict:
+        """
+        Start a plan in the background and return its job id.
+
+        plan: {"removePackages": [...], "restrictBackground": [...],
+               "trimCaches": bool, "animationScale": float}
+        Progress is reported with `optimization_progress` events and the
+        result with one `optimization_complete` event.
+        """
+        plan = plan or {}
+        stages = self._plan_stages(plan)
+        if not stages:
+            return {"success": False, "message": "Nothing selected to optimize"}
+
+        with self._lock:
+            if self._job is not None:
+                return {"success": False, "message": "An optimization is already running", "jobId": self._job["id"]}
+            job = {"id": uuid.uuid4().hex[:12], "stages": stages, "started": time.time()}
+            self._job = job
+
+        threading.Thread(target=self._run, args=(job, plan), daemon=True).start()
+        return {"success": True, "message": f"Optimization started ({len(stages)} stage(s))",
+                "jobId": job["id"], "stages": stages}
+
+    def _progress(self, job: Dict, stage: str, status: str, **extra):
+        self.emit_event("optimization_progress", {
+            "stage": stage,
+            "label": STAGE_LABELS[stage],
+            "index": job["stages"].index(stage),
+            "total": len(job["stages"]),
+            "status": status,
+            **extra,
+        }, job["id"])
+
+    def _run(self, job: Dict, plan: Dict):
+        # The device is kept awake until the plan ends, however it ends
+        with stay_awake_during(self.stay_awake, "Optimization"):
+            self._run_plan(job, plan)
+
+    def _run_plan(self, job: Dict, plan: Dict):
+        results = []
+        interrupted = False
+        try:
+            before = self._measure()
+            for stage in job["stages"]:
+                if interrupted:
+                    results.append({"stage": stage, "status": "skipped", "details": []})
+                    continue
+                self._progress(job, stage, "running")
+                details = self._run_stage(job, stage, plan)
+                interrupted = any(d.get("deviceLost") for d in details)
+                ok = sum(1 for d in details if d.get("success"))
+                status = "interrupted" if interrupted else "done"
+                results.append({"stage": stage, "status": status, "succeeded": ok, "details": details})
+                self._progress(job, stage, status, succeeded=ok, attempted=len(details))
+
+            if "remove" in job["stages"] or "trim" in job["stages"]:
+                self.package_cache.invalidate()
+            after = self._measure()
+            self.emit_event("optimization_complete", {
+                "success": not interrupted,
+                "interrupted": interrupted,
+                "stages": results,
+                "before": before,
+                "after": after,
+                "impact": diff_metrics(before, after),
+                "durationSeconds": round(time.time() - job["started"], 1),
+            }, job["id"])
+        except Exception as e:
+            self.emit_event("optimization_complete", {
+                "success": False, "interrupted": False, "stages": results, "message": str(e),
+            }, job["id"])
+        finally:
+            with self._lock:
+                self._job = None
+
+    def _verify_removal(self, details: List[Dict]):
+        """Mark reported removals that the device still lists as failed"""
+        removed = [d["packageName"] for d in details if d.get("success")]
+        if not removed:
+            return
+        try:
+            statuses = self.adb.verify_removal(removed)
+        except ADBError:
+            return
+        for detail in details:
+            status = statuses.get(detail["packageName"])
+            if status is None:
+                continue
+            detail["verified"] = status
+            if status == "installed":
+                detail["success"] = False
+                detail["discrepancy"] = True
+                detail["message"] = "Uninstall reported success, but the package is still installed"
+
+    def _run_stage(self, job: Dict, stage: str, plan: Dict) -> List[Dict]:
+        if stage == "remove":
+            details = []
+            packages = plan["removePackages"]
+            overlay_targets = self.adb.get_overlay_targets()
+            apex_packages = self.adb.get_apex_packages()
+            rate = self.adb.safety_rater()
+            for i, package in enumerate(packages):
+                if package in overlay_targets:
+                    details.append({"packageName": package, "success": False,
+                                    "message": "Skipped: theme overlay, not an app"})
+                    continue
+                if package in apex_packages:
+                    details.append({"packageName": package, "success": False,
+                                    "message": f"Skipped: part of the {apex_packages[package]} system module"})
+                    continue
+                level = rate(package)
+                if level not in REMOVABLE_LEVELS:
+                    details.append({"packageName": package, "success": False,
+                                    "message": f"Skipped: classified {level}"})
+                    continue
+                result = self.adb.uninstall_package(package)
+                self.undo.record("uninstall", package, result)
+                details.append({"packageName": package, **result})
+                self._progress(job, stage, "running", current=package, done=i + 1, count=len(packages))
+                if result.get("deviceLost"):
+                    break
+            self._verify_removal(details)
+            return details
+
+        if stage == "restrict":
+            details = []
+            for package in plan["restrictBackground"]:
+                result = self.tweaks.restrict_background(package, True)
+                self.undo.record("background", package, result)
+                details.append({"packageName": package, **result})
+            return details
+
+        if stage == "trim":
+            result = self.tweaks.trim_caches()
+            self.undo.record("trim_caches", None, result)
+            return [result]
+
+        result = self.tweaks.set_animation_scale(plan["animationScale"])
+        self.undo.record("animation_scale", None, result)
+        return [result]
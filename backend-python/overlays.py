@@ -0,0 +1,77 @@
+"""
+Overlays Module
+Runtime resource overlays (RROs) are packages with no code that restyle
+another package: icon shapes, accent colours, display cutouts. They show up
+in package lists as `com.*.overlay.*` entries nobody recognises, so they are
+tagged with the package they restyle, kept out of removal, and turned on or
+off with `cmd overlay` instead, which leaves the APK in place
+"""
+import re
+from typing import Dict, List, Optional
+
+
+OVERLAY_LIST_COMMAND = "cmd overlay list"
+
+# "[x] com.android.theme.icon_pack.filled.android", "[ ] ...", "--- ..." (target missing or not ready)
+OVERLAY_LINE_PATTERN = re.compile(r'^(\[[xX ]\]|---)\s+(\S+)')
+OVERLAY_STATES = {"[x]": "enabled", "[X]": "enabled", "[ ]": "disabled", "---": "unavailable"}
+
+# Overlays that decide how the device is used rather than how it looks; the
+# system switches them itself (Settings > Navigation mode), so they are not toggled here
+LOCKED_OVERLAY_PATTERNS = (
+    re.compile(r'\.navbar\.'),  # gesture / 2-button / 3-button navigation
+)
+LOCKED_MESSAGE = "{overlay} sets the navigation mode; change it in the device's Settings instead"
+
+# `cmd overlay` failures: unknown overlay, immutable (static) overlay, missing target
+OVERLAY_ERROR_PATTERN = re.compile(r'Error|Exception|not mutable|Unknown overlay', re.IGNORECASE)
+
+REMOVAL_MESSAGE = "Theme overlay for {target}: turn it off under Theme overlays instead of removing it"
+
+
+def parse_overlay_list(output: str) -> List[Dict]:
+    """
+    [{"id", "packageName", "target", "state"}] from `cmd overlay list`.
+    Android 12+ lists fabricated overlays as "package:name"; their
+    packageName is the part before the colon.
+    """
+    overlays = []
+    target: Optional[str] = None
+    for line in output.split('\n'):
+        stripped = line.strip()
+        if not stripped:
+            continue
+        match = OVERLAY_LINE_PATTERN.match(stripped)
+        if match:
+            if target is None:
+                continue
+            overlay_id = match.group(2)
+            overlays.append({
+                "id": overlay_id,
+                "packageName": overlay_id.split(':', 1)[0],
+                "target": target,
+                "state": OVERLAY_STATES[match.group(1)],
+            })
+        elif ' ' not in stripped:
+            target = stripped
+    return overlays
+
+
+def is_locked(overlay_id: str) -> bool:
+    """Whether an overlay is left to the device's own settings"""
+    return any(pattern.search(overlay_id) for pattern in LOCKED_OVERLAY_PATTERNS)
+
+
+def overlay_targets(overlays: List[Dict]) -> Dict[str, str]:
+    """overlay package -> package it restyles, for tagging listings"""
+    return {overlay["packageName"]: overlay["target"] for overlay in overlays}
+
+
+def tag_overlays(packages: List[Dict], targets: Dict[str, str]) -> List[Dict]:
+    """Mark overlay packages in a listing with their target and keep them out of removal"""
+    for pkg in packages:
+        target = targets.get(pkg["packageName"])
+        if target:
+            pkg["overlayTarget"] = target
+            pkg["removable"] = False
+    return packages
@@ -0,0 +1,199 @@
+"""
+Package Cache Module
+Keeps the last package listing per device so large lists (1500+ packages)
+can be sorted, filtered and paged without re-querying the device
+"""
+import re
+import threading
+import time
+from datetime import datetime
+from typing import Dict, List, Optional
+from adb_operations import ADBOperations, ADBError, parse_package_names
+from cache_policy import CacheStats, cache_report, is_stale
+
+
+SORT_KEYS = ["name", "appName", "size", "safety", "lastUsed"]
+SAFETY_ORDER = {"Safe": 0, "Caution": 1, "Expert": 2, "Dangerous": 3}
+MAX_PAGE_SIZE = 500
+
+LAST_USED_PATTERN = re.compile(r'package=(\S+).*?lastTimeUsed="([^"]+)"')
+
+
+def parse_last_used(output: str) -> Dict[str, float]:
+    """Parse the most recent lastTimeUsed per package from `dumpsys usagestats`"""
+    last_used: Dict[str, float] = {}
+    for match in LAST_USED_PATTERN.finditer(output):
+        package, stamp = match.groups()
+        try:
+            when = datetime.strptime(stamp, "%Y-%m-%d %H:%M:%S").timestamp()
+        except ValueError:
+            continue
+        if when > last_used.get(package, 0):
+            last_used[package] = when
+    return last_used
+
+
+class PackageCache:
+    """Per-device package listing cache with sort/filter/pagination"""
+
+    def __init__(self, adb_operations: ADBOperations, label_resolver=None, device_policy=None,
+                 analysis_cache=None, revalidator=None):
+        self.adb = adb_operations
+        self.labels = label_resolver
+        self.policy = device_policy
+        self.analyses = analysis_cache
+        # Revalidator re-listing stale listings in the background
+        self.revalidator = revalidator
+        # serial -> {"packages": [...], "time": float, "sizes": {...}|None, "lastUsed": {...}|None}
+        self._entries: Dict[str, Dict] = {}
+        self._lock = threading.Lock()
+        self.stats = CacheStats()
+
+    def store(self, serial: str, packages: List[Dict]):
+        """Cache a full listing, marking which packages are system apps"""
+        if not all("isSystem" in pkg for pkg in packages):
+            try:
+                user_packages = set(parse_package_names(self.adb.shell("pm list packages -3", timeout=30)))
+            except ADBError:
+                user_packages = None
+            for pkg in packages:
+                if user_packages is not None:
+                    pkg["isSystem"] = pkg["packageName"] not in user_packages
+        with self._lock:
+            self._entries[serial] = {"packages": packages, "time": time.time(), "sizes": None, "lastUsed": None}
+
+    def refresh(self) -> List[Dict]:
+        """Re-list all packages from the device into the cache"""
+        serial = self.adb.get_serial()
+        packages = self.adb.list_packages("all")
+        if self.labels is not None:
+            packages = self.labels.apply_cached_labels(packages)
+        if self.policy is not None:
+            packages = self.policy.apply_management(packages)
+        if self.analyses is not None:
+            packages = self.analyses.apply(packages)
+        self.store(serial, packages)
+        return packages
+
+    def has_listing(self, serial: str) -> bool:
+        """Whether a listing of the device is cached"""
+        with self._lock:
+            return serial in self._entries
+
+    def update_package(self, package_name: str, **fields):
+        """Set fields of one package in every cached listing (e.g. a new aiRisk)"""
+        with self._lock:
+            for entry in self._entries.values():
+                for pkg in entry["packages"]:
+                    if pkg["packageName"] == package_name:
+                        pkg.update(fields)
+
+    def invalidate(self, serial: str = None):
+        """Drop the cache for one device, or all devices"""
+        with self._lock:
+            if serial:
+                self._entries.pop(serial, None)
+            else:
+                self._entries.clear()
+
+    def cache_diagnostics(self) -> Dict:
+        """get_cache_diagnostics line of the package listings (sizes and last-used included)"""
+        with self._lock:
+            entries = dict(self._entries)
+        return cache_report("packages", self.stats, entries, [e["time"] for e in entries.values()], len(entries))
+
+    def _ensure_sizes(self, entry: Dict):
+        if entry["sizes"] is None:
+            try:
+                entry["sizes"] = self.adb.get_package_sizes()
+            except ADBError:
+                entry["sizes"] = {}
+            for pkg in entry["packages"]:
+                size = entry["sizes"].get(pkg["packageName"])
+                pkg["sizeBytes"] = size["totalBytes"] if size else None
+
+    def _ensure_last_used(self, entry: Dict):
+        if entry["lastUsed"] is None:
+            try:
+                entry["lastUsed"] = parse_last_used(self.adb.shell("dumpsys usagestats", timeout=60))
+            except ADBError:
+                entry["lastUsed"] = {}
+            for pkg in entry["packages"]:
+                pkg["lastUsed"] = entry["lastUsed"].get(pkg["packageName"])
+
+    def _current_entry(self) -> Dict:
+        """Cached entry of the connected device; a stale one is returned and re-listed in the background"""
+        serial = self.adb.get_serial()
+        if serial not in self._entries:
+            self.stats.miss()
+            self.refresh()
+        else:
+            self.stats.hit()
+        entry = self._entries[serial]
+        if self.revalidator is not None and is_stale("packages", entry["time"]):
+            self.revalidator.revalidate("packages", serial, self.refresh)
+        return entry
+
+    def prefetch_sizes(self):
+        """Measure app sizes of the connected device's listing ahead of a size sort"""
+        self._ensure_sizes(self._current_entry())
+
+    def prefetch_last_used(self):
+        """Read last-used times of the connected device's listing ahead of a last-used sort"""
+        self._ensure_last_used(self._current_entry())
+
+    def packages(self) -> List[Dict]:
+        """All cached packages for the connected device, listing them first if needed"""
+        return self._current_entry()["packages"]
+
+    def query(self, sort_by: str = "name", direction: str = "asc", offset: int = 0,
+              limit: int = 100, filters: Optional[Dict] = None) -> Dict:
+        """
+        Return one page of cached packages.
+
+        filters: {"search": str, "safetyLevels": [...], "type": "all"|"system"|"user"}
+        Null sizes/last-used values always sort last, whatever the direction.
+        """
+        if sort_by not in SORT_KEYS:
+            raise ValueError(f"Unknown sort key '{sort_by}'. Use one of: {', '.join(SORT_KEYS)}")
+        filters = filters or {}
+        entry = self._current_entry()
+        if sort_by == "size":
+            self._ensure_sizes(entry)
+        elif sort_by == "lastUsed":
+            self._ensure_last_used(entry)
+
+        items = entry["packages"]
+        search = (filters.get("search") or "").lower().strip()
+        if search:
+            items = [p for p in items if search in p["packageName"].lower() or search in p.get("appName", "").lower()]
+        levels = filters.get("safetyLevels")
+        if levels:
+            items = [p for p in items if p.get("safetyLevel") in levels]
+        package_type = filters.get("type", "all")
+        if package_type in ("system", "user"):
+            want_system = package_type == "system"
+            items = [p for p in items if p.get("isSystem") is want_system]
+
+        reverse = direction == "desc"
+        if sort_by == "name":
+            items = sorted(items, key=lambda p: p["packageName"], reverse=reverse)
+        elif sort_by == "appName":
+            items = sorted(items, key=lambda p: p.get("appName", "").lower(), reverse=reverse)
+        elif sort_by == "safety":
+            items = sorted(items, key=lambda p: (SAFETY_ORDER.get(p.get("safetyLevel"), 0), p["packageName"]), reverse=reverse)
+        else:
+            field = "sizeBytes" if sort_by == "size" else "lastUsed"
+            known = sorted((p for p in items if p.get(field) is not None), key=lambda p: p[field], reverse=reverse)
+            items = known + [p for p in items if p.get(field) is None]
+
+        offset = max(0, int(offset))
+        limit = max(1, min(MAX_PAGE_SIZE, int(limit)))
+        return {
+            "total": len(items),
+            "offset": offset,
+            "limit": limit,
+            "items": items[offset:offset + limit],
+            "cachedAt": entry["time"],
+            "stale": is_stale("packages", entry["time"]),
+        }
@@ -0,0 +1,94 @@
+"""
+Package Families Module
+Groups vendor suites (all Facebook, Google apps, Bixby...) so they can be
+reviewed and removed as one unit
+"""
+from typing import Dict, List, Optional
+
+
+# (id, display name, package prefixes); first match wins, so list specific families first
+KNOWN_FAMILIES = [
+    ("facebook", "Facebook / Meta services", ["com.facebook.", "com.instagram.", "com.meta."]),
+    ("bixby", "Samsung Bixby", ["com.samsung.android.bixby", "com.samsung.android.visionintelligence",
+                                "com.samsung.systemui.bixby", "com.samsung.android.app.spage"]),
+    ("google_apps", "Google apps", ["com.google.android.apps."]),
+    ("microsoft", "Microsoft apps", ["com.microsoft.", "com.skype."]),
+    ("amazon", "Amazon apps", ["com.amazon."]),
+    ("carrier", "Carrier apps", ["com.verizon.", "com.vzw.", "com.att.", "com.tmobile.", "com.sprint.",
+                                 "com.vodafone.", "com.orange."]),
+    ("samsung", "Samsung apps", ["com.samsung.", "com.sec.android.app."]),
+    ("xiaomi", "Xiaomi / MIUI apps", ["com.miui.", "com.xiaomi.", "com.mi."]),
+    ("huawei", "Huawei apps", ["com.huawei."]),
+    ("oppo", "OPPO / ColorOS / Realme apps", ["com.coloros.", "com.oppo.", "com.heytap.", "com.realme."]),
+    ("vivo", "vivo apps", ["com.vivo.", "com.bbk.", "com.iqoo."]),
+    ("oneplus", "OnePlus apps", ["com.oneplus.", "net.oneplus."]),
+]
+
+# Vendors without a known family are grouped automatically once they ship this many packages
+AUTO_FAMILY_MIN_SIZE = 3
+
+SAFETY_ORDER = ["Safe", "Caution", "Expert", "Dangerous"]
+
+
+def family_for(package_name: str) -> Optional[Dict]:
+    """Return {"id", "name"} of the known family a package belongs to"""
+    for family_id, name, prefixes in KNOWN_FAMILIES:
+        if any(package_name.startswith(prefix) for prefix in prefixes):
+            return {"id": family_id, "name": name}
+    return None
+
+
+def _vendor_prefix(package_name: str) -> Optional[str]:
+    parts = package_name.split('.')
+    if len(parts) < 3:
+        return None
+    return '.'.join(parts[:2])
+
+
+def group_packages(packages: List[Dict], sizes: Dict[str, Dict] = None) -> List[Dict]:
+    """
+    Group listed packages into families with aggregate size and safety info.
+
+    `packages` are list_packages entries; `sizes` comes from get_package_sizes.
+    """
+    sizes = sizes or {}
+    groups: Dict[str, Dict] = {}
+    leftovers: Dict[str, List[Dict]] = {}
+
+    for pkg in packages:
+        family = family_for(pkg["packageName"])
+        if family:
+            groups.setdefault(family["id"], {**family, "packages": []})["packages"].append(pkg)
+            continue
+        vendor = _vendor_prefix(pkg["packageName"])
+        if vendor and not vendor.startswith("com.android") and vendor != "com.google":
+            leftovers.setdefault(vendor, []).append(pkg)
+
+    for vendor, members in leftovers.items():
+        if len(members) >= AUTO_FAMILY_MIN_SIZE:
+            groups[vendor] = {"id": vendor, "name": f"{vendor}.*", "packages": members}
+
+    families = []
+    for group in groups.values():
+        members = group["packages"]
+        breakdown = {level: 0 for level in SAFETY_ORDER}
+        for pkg in members:
+            level = pkg.get("safetyLevel", "Safe")
+            breakdown[level] = breakdown.get(level, 0) + 1
+        worst = max((pkg.get("safetyLevel", "Safe") for pkg in members),
+                    key=lambda level: SAFETY_ORDER.index(level) if level in SAFETY_ORDER else 0)
+        known_sizes = [sizes[p["packageName"]]["totalBytes"] for p in members if p["packageName"] in sizes]
+        families.append({
+            "id": group["id"],
+            "name": group["name"],
+            "packageCount": len(members),
+            "packages": [p["packageName"] for p in members],
+            "safetyBreakdown": breakdown,
+            "worstSafetyLevel": worst,
+            # Safe and Caution members can go in one toggle; Expert/Dangerous need individual review
+            "removablePackages": [p["packageName"] for p in members if p.get("safetyLevel") in ("Safe", "Caution")],
+            "totalSizeBytes": sum(known_sizes) if known_sizes else None,
+        })
+
+    families.sort(key=lambda f: (-f["packageCount"], f["name"]))
+    return families
@@ -0,0 +1,112 @@
+"""
+Package Stream Module
+Sends a package listing to the frontend in acknowledged chunks. The chunk
+size follows how quickly the frontend acknowledges each one: it grows while
+acks come back fast and shrinks when the renderer falls behind, so slow
+webviews are not flooded and fast ones are not kept waiting
+"""
+import threading
+import time
+import uuid
+from typing import Dict, List
+from events import EmitFn, no_emit
+from package_wire import compact_packages
+
+
+INITIAL_CHUNK_SIZE = 100
+MIN_CHUNK_SIZE = 25
+MAX_CHUNK_SIZE = 1000
+
+# Ack round trips (seconds) below FAST_ACK double the chunk size, above SLOW_ACK halve it
+FAST_ACK = 0.05
+SLOW_ACK = 0.25
+
+# A stream whose chunk is not acknowledged in this many seconds is abandoned
+ACK_TIMEOUT = 30.0
+
+
+def next_chunk_size(size: int, ack_seconds: float) -> int:
+    """Chunk size to use after a chunk of `size` was acknowledged in `ack_seconds`"""
+    if ack_seconds < FAST_ACK:
+        return min(size * 2, MAX_CHUNK_SIZE)
+    if ack_seconds > SLOW_ACK:
+        return max(size // 2, MIN_CHUNK_SIZE)
+    return size
+
+
+class PackageStreamer:
+    """Stream package listings as package_chunk events, paced by frontend acks"""
+
+    def __init__(self, emit_event: EmitFn = None, ack_timeout: float = ACK_TIMEOUT):
+        self.emit_event = emit_event or no_emit
+        self.ack_timeout = ack_timeout
+        # job id -> {"acked": threading.Event, "index": int}
+        self._streams: Dict[str, Dict] = {}
+        self._lock = threading.Lock()
+
+    def start(self, packages: List[Dict]) -> Dict:
+        """Start streaming in the background; returns {jobId, total}"""
+        job_id = uuid.uuid4().hex[:12]
+        with self._lock:
+            self._streams[job_id] = {"acked": threading.Event(), "index": -1}
+        threading.Thread(target=self._run, args=(job_id, packages), daemon=True).start()
+        return {"jobId": job_id, "total": len(packages)}
+
+    def ack(self, job_id: str, index: int) -> Dict:
+        """The frontend has rendered chunk `index` of a stream"""
+        with self._lock:
+            stream = self._streams.get(job_id)
+        if stream is None or index != stream["index"]:
+            return {"success": False, "message": "No such chunk is waiting for an acknowledgement"}
+        stream["acked"].set()
+        return {"success": True}
+
+    def cancel(self, job_id: str) -> Dict:
+        """Stop a stream, e.g. when the list is closed or refreshed"""
+        with self._lock:
+            stream = self._streams.pop(job_id, None)
+        if stream is None:
+            return {"success": False, "message": "No such stream"}
+        stream["acked"].set()
+        return {"success": True}
+
+    def cancel_all(self):
+        """Stop every stream (the backend is shutting down)"""
+        with self._lock:
+            job_ids = list(self._streams)
+        for job_id in job_ids:
+            self.cancel(job_id)
+
+    def _run(self, job_id: str, packages: List[Dict]):
+        size = INITIAL_CHUNK_SIZE
+        sent = 0
+        index = 0
+        try:
+            while True:
+                with self._lock:
+                    stream = self._streams.get(job_id)
+                if stream is None:
+                    return  # Cancelled
+                chunk = packages[sent:sent + size]
+                sent += len(chunk)
+                done = sent >= len(packages)
+                stream["acked"].clear()
+                stream["index"] = index
+                started = time.monotonic()
+                self.emit_event("package_chunk", {
+                    "index": index,
+                    "packages": compact_packages(chunk),
+                    "sent": sent,
+                    "total": len(packages),
+                    "done": done,
+                }, job_id)
+                if done:
+                    return
+                if not stream["acked"].wait(self.ack_timeout):
+                    return  # The frontend went away
+                size = next_chunk_size(size, time.monotonic() - started)
+                index += 1
+        finally:
+            with self._lock:
+                self._streams.pop(job_id, None)
+
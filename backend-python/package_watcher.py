@@ -0,0 +1,90 @@
+"""
+Package Watcher Module
+Polls the connected device's package list so installs and uninstalls made
+on the phone itself (Play Store, Settings, another tool) show up in the app
+without a manual refresh. Changes the app made itself are recorded by
+ADBOperations and not reported
+"""
+import threading
+import time
+from typing import Callable, Dict, List, Optional, Set
+from adb_operations import ADBOperations, ADBError
+from backup_manager import parse_package_list
+from events import EmitFn, no_emit
+
+
+# Default seconds between polls (devices.watchPackagesSeconds; 0 turns the watcher off)
+DEFAULT_WATCH_SECONDS = 10
+# Poll interval while the watcher is turned off, to notice it being turned back on
+IDLE_SECONDS = 30
+
+
+def diff_packages(before: Set[str], after: Set[str], own: Set[str]) -> Optional[Dict[str, List[str]]]:
+    """
+    {"installed", "removed"} between two listings, leaving out `own` changes
+    ("" in `own` covers every install); None when nothing else changed
+    """
+    installed = sorted(after - before - own) if "" not in own else []
+    removed = sorted(before - after - own)
+    if not installed and not removed:
+        return None
+    return {"installed": installed, "removed": removed}
+
+
+class PackageWatcher:
+    """Reports packages installed or removed outside the app as `external_package_change` events"""
+
+    def __init__(self, adb_operations: ADBOperations, package_cache, emit_event: EmitFn = None):
+        self.adb = adb_operations
+        self.package_cache = package_cache
+        self.emit_event = emit_event or no_emit
+        # Listing of the last poll; reset when another device is connected
+        self._serial: Optional[str] = None
+        self._packages: Optional[Set[str]] = None
+        self._polled_at = 0.0
+        self._stop = threading.Event()
+
+    def poll(self) -> Optional[Dict]:
+        """
+        Compare the device's package list with the last poll. Returns and
+        emits the change made outside the app, if any; the first poll of a
+        device only records its listing
+        """
+        serial = self.adb.get_serial()
+        started = time.time()
+        packages = parse_package_list(self.adb.shell("pm list packages", timeout=30))
+        before, since = self._packages, self._polled_at
+        same_device = serial == self._serial
+        self._serial, self._packages, self._polled_at = serial, packages, started
+        if before is None or not same_device:
+            return None
+
+        change = diff_packages(before, packages, set(self.adb.package_changes_since(since)))
+        if change is None:
+            return None
+        self.package_cache.invalidate(serial)
+        change["serial"] = serial
+        self.emit_event("external_package_change", change)
+        return change
+
+    def start(self, interval_for: Callable[[], float] = lambda: DEFAULT_WATCH_SECONDS):
+        """Poll in a background thread; `interval_for` is re-read every round so settings apply live"""
+        self._stop.clear()
+
+        def run():
+            while True:
+                interval = interval_for()
+                if interval:
+                    try:
+                        self.poll()
+                    except ADBError:
+                        self._packages = None  # No device; the next one starts a fresh listing
+                else:
+                    self._packages = None  # Turned off; changes made meanwhile are not reported later
+                if self._stop.wait(interval or IDLE_SECONDS):
+                    break
+
+        threading.Thread(target=run, name="package-watcher", daemon=True).start()
+
+    def stop(self):
+        self._stop.set()
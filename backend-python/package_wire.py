@@ -0,0 +1,66 @@
+"""
+Package Wire Format
+Compact columnar encoding of package listings for IPC: field names are sent
+once and repeated strings (safety levels, labels, vendor names) become
+indices into a shared string table, which cuts the JSON size of listings
+with thousands of packages
+"""
+from typing import Any, Dict, List
+
+from api_types import Package
+
+
+WIRE_FORMAT = "compact"
+WIRE_VERSION = 1
+
+# Column order on the wire; every Package field, so new fields are carried automatically
+PACKAGE_FIELDS = list(Package.__annotations__)
+
+
+def compact_packages(packages: List[Dict]) -> Dict:
+    """
+    Encode a package listing as {format, version, fields, strings, rows}.
+
+    Each row holds one value per field: strings are indices into `strings`,
+    other values are sent as-is and missing fields are null.
+    """
+    strings: List[str] = []
+    index: Dict[str, int] = {}
+
+    def intern(value: Any) -> Any:
+        if not isinstance(value, str):
+            return value
+        if value not in index:
+            index[value] = len(strings)
+            strings.append(value)
+        return index[value]
+
+    fields = [field for field in PACKAGE_FIELDS if any(field in pkg for pkg in packages)]
+    string_fields = {field for field in fields if any(isinstance(pkg.get(field), str) for pkg in packages)}
+    rows = [
+        [intern(pkg.get(field)) if field in string_fields else pkg.get(field) for field in fields]
+        for pkg in packages
+    ]
+    return {
+        "format": WIRE_FORMAT,
+        "version": WIRE_VERSION,
+        "fields": fields,
+        "stringFields": [field for field in fields if field in string_fields],
+        "strings": strings,
+        "rows": rows,
+    }
+
+
+def expand_packages(payload: Dict) -> List[Dict]:
+    """Decode compact_packages output back into package dicts (null cells are left out)"""
+    fields, strings = payload["fields"], payload["strings"]
+    string_fields = set(payload["stringFields"])
+    packages = []
+    for row in payload["rows"]:
+        pkg = {}
+        for field, value in zip(fields, row):
+            if value is None:
+                continue
+            pkg[field] = strings[value] if field in string_fields else value
+        packages.append(pkg)
+    return packages
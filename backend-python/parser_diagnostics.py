@@ -0,0 +1,102 @@
+"""
+Parser Diagnostics Module
+Remembers device output the metric parsers could not read (storage,
+thermal, battery, memory) and exports it as a diagnostics bundle, so
+unsupported OEM formats can be reported and added. Raw output only goes
+into the bundle when the user agrees to include it
+"""
+import json
+import threading
+import time
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+from events import no_emit
+
+
+# Raw output kept per failure, and most failures kept per session
+MAX_SNIPPET_CHARS = 2000
+MAX_FAILURES = 50
+
+# adb output is decoded as UTF-8 with U+FFFD for bytes that are not, as some
+# localized OEM shells print in a legacy code page
+REPLACEMENT_CHAR = "\ufffd"
+
+
+def failure_reason(output: str) -> str:
+    """Why output went unread: "encoding" when it was not UTF-8, "format" for a layout the parser does not know"""
+    return "encoding" if REPLACEMENT_CHAR in output else "format"
+
+
+class ParserDiagnostics:
+    """Collect parser failures of this session and export them"""
+
+    def __init__(self, emit_event=no_emit, data_dir: str = None):
+        self.emit_event = emit_event
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.reports_dir = Path(data_dir) / "reports"
+        self._lock = threading.Lock()
+        # (parser, command) -> failure; kept in memory only
+        self._failures: Dict[Tuple[str, str], Dict] = {}
+
+    def record_failure(self, parser: str, command: str, output: str):
+        """
+        Note that `parser` could not read the output of `command`. The first
+        failure of each parser emits a `parser_failed` event.
+        """
+        now = time.time()
+        reason = failure_reason(output)
+        with self._lock:
+            first_for_parser = not any(p == parser for p, _ in self._failures)
+            failure = self._failures.get((parser, command))
+            if failure is None:
+                if len(self._failures) >= MAX_FAILURES:
+                    return
+                failure = {"parser": parser, "command": command, "firstSeen": now, "count": 0}
+                self._failures[(parser, command)] = failure
+            failure["lastSeen"] = now
+            failure["count"] += 1
+            failure["outputChars"] = len(output)
+            failure["reason"] = reason
+            failure["snippet"] = output[:MAX_SNIPPET_CHARS]
+        if first_for_parser:
+            self.emit_event("parser_failed", {"parser": parser, "command": command, "reason": reason})
+
+    def list_failures(self) -> List[Dict]:
+        """Failures of this session without their raw output, most recent first"""
+        with self._lock:
+            failures = [{k: v for k, v in f.items() if k != "snippet"} for f in self._failures.values()]
+        return sorted(failures, key=lambda f: -f["lastSeen"])
+
+    def clear(self) -> Dict:
+        with self._lock:
+            self._failures.clear()
+        return {"success": True, "message": "Parser failures cleared"}
+
+    def export_diagnostics(self, include_output: bool, device_info: Optional[Dict] = None,
+                           compatibility: Optional[Dict] = None) -> Dict:
+        """
+        Write the failures to reports/diagnostics_<time>.json. Raw output
+        snippets are only included when `include_output` is True.
+        """
+        with self._lock:
+            failures = []
+            for failure in self._failures.values():
+                entry = dict(failure)
+                if not include_output:
+                    entry.pop("snippet", None)
+                failures.append(entry)
+        if not failures:
+            return {"success": False, "message": "No parser failures to export"}
+        bundle = {
+            "createdAt": time.time(),
+            "device": device_info,
+            "compatibility": compatibility,
+            "includesOutput": bool(include_output),
+            "failures": failures,
+        }
+        self.reports_dir.mkdir(parents=True, exist_ok=True)
+        path = self.reports_dir / f"diagnostics_{time.strftime('%Y%m%d_%H%M%S')}.json"
+        path.write_text(json.dumps(bundle, indent=2), encoding="utf-8")
+        return {"success": True, "path": str(path), "failures": len(failures),
+                "message": f"Diagnostics saved to {path}"}
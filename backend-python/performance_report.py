@@ -0,0 +1,156 @@
+"""
+Performance Report Module
+Turns a health snapshot into a 0-100 performance score with actionable
+recommendations, each tied to a backend command the UI can run
+"""
+from typing import Dict, List, Optional
+from formatting import format_percent
+
+
+# Recommendation severities, most urgent first
+SEVERITY_ORDER = {"high": 0, "medium": 1, "low": 2}
+
+# Thermal status at or above which the device is throttling (android.os.Temperature)
+THROTTLING_STATUS = 2
+# Running app services above which background load is considered heavy
+SERVICES_BUSY = 60
+# Packages with at least this many running services are named in recommendations
+SERVICE_HEAVY_PACKAGE = 3
+# On battery, background services are penalised this many services earlier
+BATTERY_SERVICES_SHIFT = 20
+# Partial wake locks held on battery start costing points above this count
+WAKE_LOCKS_BUSY = 2
+# Packages that posted at least this many notifications since boot are offered muting, at most NOISY_PACKAGES of them
+NOISY_NOTIFICATIONS = 100
+NOISY_PACKAGES = 3
+
+
+def _scaled_penalty(value: float, start: float, end: float, max_penalty: int) -> int:
+    """0 at `start`, rising linearly to `max_penalty` at `end`"""
+    if value is None or value <= start:
+        return 0
+    return round(min(1.0, (value - start) / (end - start)) * max_penalty)
+
+
+def _action(command: str, label: str, args: Optional[Dict] = None) -> Dict:
+    return {"command": command, "args": args or {}, "label": label}
+
+
+def build_performance_report(health: Dict, animation_scales: Dict[str, Optional[float]]) -> Dict:
+    """
+    Score a health snapshot (get_system_health) plus current animation scales.
+
+    Each factor is {id, label, value, penalty}; each recommendation carries an
+    `action` {command, args, label} for the UI to send back to the backend.
+    """
+    factors: List[Dict] = []
+    recommendations: List[Dict] = []
+    # Unknown power state (older snapshots, unreadable `dumpsys power`) is scored like charging
+    on_battery = (health.get("power") or {}).get("charging") is False
+
+    memory = health.get("memory") or {}
+    ram_usage = memory.get("usagePercent")
+    ram_penalty = _scaled_penalty(ram_usage, 70, 95, 25)
+    factors.append({"id": "memory", "label": "RAM pressure", "value": ram_usage, "penalty": ram_penalty})
+    if ram_penalty:
+        recommendations.append({
+            "id": "memory",
+            "severity": "high" if ram_usage >= 90 else "medium",
+            "message": f"RAM is {format_percent(ram_usage)} used. Removing unused preinstalled apps frees memory they hold in the background.",
+            "action": _action("get_cached_packages", "Review removable apps",
+                              {"sortBy": "safety", "filters": {"safetyLevels": ["Safe"], "type": "system"}}),
+        })
+
+    storage = (health.get("storage") or {}).get("internal") or {}
+    storage_usage = storage.get("usagePercent")
+    storage_penalty = _scaled_penalty(storage_usage, 80, 98, 25)
+    factors.append({"id": "storage", "label": "Storage fullness", "value": storage_usage, "penalty": storage_penalty})
+    if storage_penalty:
+        recommendations.append({
+            "id": "storage_cache",
+            "severity": "high" if storage_usage >= 95 else "medium",
+            "message": f"Internal storage is {format_percent(storage_usage)} full; nearly full flash slows down writes. Clear app caches first.",
+            "action": _action("trim_caches", "Trim app caches"),
+        })
+        recommendations.append({
+            "id": "storage_apps",
+            "severity": "low",
+            "message": "The largest apps are listed first so you can decide what to remove.",
+            "action": _action("get_cached_packages", "Show largest apps", {"sortBy": "size", "direction": "desc"}),
+        })
+
+    services = health.get("services") or {}
+    service_count = services.get("count")
+    service_start = SERVICES_BUSY - 20 - (BATTERY_SERVICES_SHIFT if on_battery else 0)
+    service_penalty = _scaled_penalty(service_count, service_start, SERVICES_BUSY + 60, 20)
+    factors.append({"id": "services", "label": "Background services", "value": service_count, "penalty": service_penalty})
+    if service_penalty:
+        for entry in services.get("byPackage", []):
+            if entry["count"] < SERVICE_HEAVY_PACKAGE:
+                break
+            recommendations.append({
+                "id": f"services:{entry['packageName']}",
+                "severity": "medium",
+                "message": f"{entry['packageName']} runs {entry['count']} background services.",
+                "action": _action("restrict_background", "Restrict background activity",
+                                  {"packageName": entry["packageName"], "restricted": True}),
+            })
+
+    # Wake locks keep the CPU from sleeping, which only costs anything when the device is not charging
+    wake_locks = (health.get("power") or {}).get("wakeLocks")
+    wake_lock_penalty = _scaled_penalty(wake_locks, WAKE_LOCKS_BUSY, WAKE_LOCKS_BUSY + 10, 15) if on_battery else 0
+    factors.append({"id": "wakelocks", "label": "Wake locks on battery", "value": wake_locks, "penalty": wake_lock_penalty})
+    if wake_lock_penalty:
+        recommendations.append({
+            "id": "wakelocks",
+            "severity": "high" if wake_lock_penalty >= 10 else "medium",
+            "message": f"{wake_locks} wake locks are keeping the CPU awake while on battery. "
+                       "Apps running background services are the usual holders.",
+            "action": _action("get_system_health", "Show running services"),
+        })
+
+    # Notifications wake the screen and the CPU but are not scored; the noisiest apps are offered muting
+    notifications = health.get("notifications") or {}
+    for entry in notifications.get("byPackage", [])[:NOISY_PACKAGES]:
+        if entry["posted"] < NOISY_NOTIFICATIONS:
+            break
+        recommendations.append({
+            "id": f"notifications:{entry['packageName']}",
+            "severity": "low",
+            "message": f"{entry['packageName']} posted {entry['posted']} notifications since the last reboot.",
+            "action": _action("mute_notifications", "Mute notifications",
+                              {"packageName": entry["packageName"], "muted": True}),
+        })
+
+    temperature = health.get("temperature") or {}
+    statuses = [s["status"] for s in temperature.get("sensors", []) if s.get("status") is not None]
+    thermal_status = max(statuses) if statuses else None
+    thermal_penalty = min(20, (thermal_status - 1) * 7) if thermal_status and thermal_status >= THROTTLING_STATUS else 0
+    factors.append({"id": "thermal", "label": "Thermal throttling", "value": thermal_status, "penalty": thermal_penalty})
+    if thermal_penalty:
+        recommendations.append({
+            "id": "thermal",
+            "severity": "high",
+            "message": f"The device is thermally throttling ({temperature.get('temperatureC')}°C). "
+                       "Background apps add heat; check which ones keep services running.",
+            "action": _action("get_system_health", "Show running services"),
+        })
+
+    scales = [s for s in animation_scales.values() if s is not None]
+    slowest = max(scales) if scales else None
+    animation_penalty = 10 if slowest is not None and slowest > 1 else 0
+    factors.append({"id": "animations", "label": "Animation scale", "value": slowest, "penalty": animation_penalty})
+    if slowest is not None and slowest >= 1:
+        recommendations.append({
+            "id": "animations",
+            "severity": "medium" if animation_penalty else "low",
+            "message": f"Animations run at {slowest:g}x. Setting 0.5x makes the UI feel noticeably faster.",
+            "action": _action("set_animation_scale", "Set animations to 0.5x", {"scale": 0.5}),
+        })
+
+    recommendations.sort(key=lambda r: SEVERITY_ORDER[r["severity"]])
+    return {
+        "score": max(0, 100 - sum(f["penalty"] for f in factors)),
+        "factors": factors,
+        "recommendations": recommendations,
+    }
@@ -0,0 +1,128 @@
+"""
+Prefetch Module
+Warms the caches in the background when a device connects: device
+profile, then the package list, sizes, usage stats and a health snapshot,
+in the order the screens need them. Progress is pushed as events
+"""
+import threading
+import time
+import uuid
+from typing import Dict, List, Optional
+from adb_operations import ADBOperations, ADBError, DeviceDisconnectedError
+from events import no_emit
+
+
+# Stage ids in run order, with labels for the progress events
+PREFETCH_STAGES = ["deviceProfile", "packages", "sizes", "usageStats", "health"]
+STAGE_LABELS = {
+    "deviceProfile": "Reading device profile",
+    "packages": "Listing packages",
+    "sizes": "Measuring app sizes",
+    "usageStats": "Reading last-used times",
+    "health": "Taking a health snapshot",
+}
+
+
+class PrefetchPipeline:
+    """Run PREFETCH_STAGES once per newly connected device"""
+
+    def __init__(self, adb_operations: ADBOperations, package_cache, health, emit_event=no_emit):
+        self.adb = adb_operations
+        self.package_cache = package_cache
+        self.health = health
+        self.emit_event = emit_event
+        self._lock = threading.Lock()
+        self._job: Optional[Dict] = None
+        # Serial the pipeline last ran for; it only runs again after another device
+        self._last_serial: Optional[str] = None
+
+    def on_device_seen(self, serial: str) -> bool:
+        """Start prefetching if `serial` is a device the pipeline has not run for"""
+        with self._lock:
+            if serial == self._last_serial:
+                return False
+        return self.start(serial)["success"]
+
+    def start(self, serial: Optional[str] = None) -> Dict:
+        """Prefetch for the connected device (again), unless a run is in progress"""
+        if serial is None:
+            try:
+                serial = self.adb.get_serial()
+            except ADBError as e:
+                return {"success": False, "message": str(e)}
+        with self._lock:
+            if self._job is not None and not self._job["finished"]:
+                return {"success": False, "message": "Prefetch already running", "jobId": self._job["id"]}
+            job = {
+                "id": uuid.uuid4().hex[:12],
+                "serial": serial,
+                "started": time.time(),
+                "finished": False,
+                "stages": [{"stage": s, "label": STAGE_LABELS[s], "status": "pending"} for s in PREFETCH_STAGES],
+            }
+            self._job = job
+            self._last_serial = serial
+        threading.Thread(target=self._run, args=(job,), daemon=True).start()
+        return {"success": True, "message": "Prefetch started", "jobId": job["id"]}
+
+    def get_status(self) -> Optional[Dict]:
+        """Stages of the current or last prefetch run, None before the first one"""
+        with self._lock:
+            if self._job is None:
+                return None
+            return {**self._job, "stages": [dict(s) for s in self._job["stages"]]}
+
+    def _run_stage(self, stage: str):
+        if stage == "deviceProfile":
+            self.adb.get_device_info()
+            self.adb.capabilities()
+            self.adb.get_device_class()
+        elif stage == "packages":
+            self.package_cache.refresh()
+        elif stage == "sizes":
+            self.package_cache.prefetch_sizes()
+        elif stage == "usageStats":
+            if self.adb.supports("usage_stats"):
+                self.package_cache.prefetch_last_used()
+        elif stage == "health":
+            self.health.get_system_health()
+
+    def _progress(self, job: Dict, index: int, entry: Dict):
+        self.emit_event("prefetch_progress", {
+            **entry, "index": index, "total": len(job["stages"]), "serial": job["serial"],
+        }, job["id"])
+
+    def _run(self, job: Dict):
+        stages: List[Dict] = job["stages"]
+        lost = False
+        for index, entry in enumerate(stages):
+            if lost:
+                entry["status"] = "skipped"
+                self._progress(job, index, entry)
+                continue
+            entry["status"] = "running"
+            started = time.time()
+            try:
+                self._run_stage(entry["stage"])
+                entry["status"] = "done"
+            except DeviceDisconnectedError as e:
+                # Without the device the remaining stages cannot succeed either;
+                # run again when it comes back
+                entry["status"] = "failed"
+                entry["message"] = str(e)
+                lost = True
+                with self._lock:
+                    if self._last_serial == job["serial"]:
+                        self._last_serial = None
+            except Exception as e:
+                entry["status"] = "failed"
+                entry["message"] = str(e)
+            entry["durationSeconds"] = round(time.time() - started, 2)
+            self._progress(job, index, entry)
+        job["finished"] = True
+        self.emit_event("prefetch_complete", {
+            "serial": job["serial"],
+            "success": all(s["status"] == "done" for s in stages),
+            "stages": [dict(s) for s in stages],
+            "durationSeconds": round(time.time() - job["started"], 1),
+        }, job["id"])
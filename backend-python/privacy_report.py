@@ -0,0 +1,446 @@
+"""
+Privacy Report Module
+Scores a device's privacy exposure from preinstalled telemetry/ads packages,
+tracker SDKs embedded in apps, risky runtime permission combinations and
+preinstalled apps drawing over other apps, and lists the apps holding
+notification-listener, usage-access or overlay special access, those
+running accessibility, autofill or VPN services and those that read the
+clipboard since boot
+"""
+import re
+from typing import Dict, List, Optional, Set
+from adb_operations import ADBOperations, ADBError, UnsupportedFeatureError, require_package_name
+from system_health import parse_duration_seconds
+
+
+# Preinstalled packages whose main purpose is telemetry, analytics or ads
+TELEMETRY_PACKAGES = {
+    "com.facebook.appmanager": ("telemetry", "Facebook App Manager – background updater and usage reporting"),
+    "com.facebook.services": ("telemetry", "Facebook Services – preinstalled data collection"),
+    "com.facebook.system": ("telemetry", "Facebook App Installer – preinstalled data collection"),
+    "com.miui.analytics": ("analytics", "MIUI Analytics – Xiaomi usage telemetry"),
+    "com.miui.msa.global": ("ads", "MIUI System Ads"),
+    "com.miui.daemon": ("telemetry", "MIUI daemon – device statistics collection"),
+    "com.samsung.android.mobileservice": ("telemetry", "Samsung Experience Service – account and usage sync"),
+    "com.samsung.android.rubin.app": ("analytics", "Samsung Customization Service – behaviour profiling"),
+    "com.samsung.android.app.spage": ("ads", "Samsung Free – sponsored content feed"),
+    "com.sec.android.diagmonagent": ("telemetry", "Samsung diagnostic monitoring agent"),
+    "com.huawei.hiview": ("telemetry", "Huawei HiView – log and usage collection"),
+    "com.heytap.mcs": ("ads", "HeyTap push and promotions"),
+    "com.coloros.bootreg": ("telemetry", "ColorOS registration telemetry"),
+    "com.oneplus.opbugreportlite": ("telemetry", "OnePlus bug report uploader"),
+    "com.google.android.feedback": ("telemetry", "Google feedback and crash reports"),
+    "com.google.mainline.telemetry": ("telemetry", "Google Mainline telemetry module"),
+}
+
+# Class-name prefixes of common tracker SDKs, matched against declared components
+TRACKER_SDKS = {
+    "com.google.firebase.analytics": "Firebase Analytics",
+    "com.google.android.gms.measurement": "Google Analytics for Firebase",
+    "com.google.android.gms.ads": "Google AdMob",
+    "com.facebook.ads": "Facebook Audience Network",
+    "com.facebook.appevents": "Facebook App Events",
+    "com.appsflyer": "AppsFlyer",
+    "com.adjust.sdk": "Adjust",
+    "io.branch": "Branch",
+    "com.crashlytics": "Crashlytics",
+    "com.mixpanel": "Mixpanel",
+    "com.amplitude": "Amplitude",
+    "com.onesignal": "OneSignal",
+    "com.unity3d.ads": "Unity Ads",
+    "com.applovin": "AppLovin",
+    "com.ironsource": "ironSource",
+    "com.moengage": "MoEngage",
+}
+
+# Granted runtime permission combinations that allow covert tracking or surveillance
+RISKY_PERMISSION_COMBOS = [
+    ({"ACCESS_BACKGROUND_LOCATION"}, "Location access while not in use"),
+    ({"ACCESS_FINE_LOCATION", "RECORD_AUDIO"}, "Precise location together with microphone"),
+    ({"CAMERA", "RECORD_AUDIO", "ACCESS_FINE_LOCATION"}, "Camera, microphone and precise location"),
+    ({"READ_CONTACTS", "READ_SMS"}, "Contacts together with SMS messages"),
+    ({"READ_CONTACTS", "READ_CALL_LOG"}, "Contacts together with call history"),
+]
+
+# Special access granted outside runtime permissions -> what it exposes
+SPECIAL_ACCESS = {
+    "notificationListener": "Reads every notification, including message contents and one-time codes",
+    "usageAccess": "Sees which apps are used, when and for how long",
+    "overlay": "Draws over other apps, e.g. full-screen ads or fake dialogs on top of them",
+}
+
+# Special access kinds granted through an app-op -> the op
+APPOP_ACCESS = {
+    "usageAccess": "GET_USAGE_STATS",
+    "overlay": "SYSTEM_ALERT_WINDOW",
+}
+
+ACCESS_LABELS = {
+    "notificationListener": "Notification access",
+    "usageAccess": "Usage access",
+    "overlay": "Drawing over other apps",
+}
+
+# Privileged services -> what the owning app can do with them
+AUDITED_SERVICES = {
+    "accessibility": "Sees and controls everything on screen, including typed passwords",
+    "autofill": "Receives the contents of login and payment forms",
+    "vpn": "Routes and can inspect all network traffic",
+}
+
+# Output of an appops command the device's appops tool does not have
+APPOPS_UNSUPPORTED_MARKERS = ("unknown command", "error", "usage:")
+
+# (penalty per item, maximum penalty) for each score factor
+FACTOR_WEIGHTS = {
+    "telemetry": (6, 36),
+    "trackers": (2, 30),
+    "permissions": (4, 24),
+    "overlays": (3, 15),
+}
+
+# `dumpsys appops --op READ_CLIPBOARD`: "Package com.example:" then per op
+# "Access: [top-s] 2024-05-01 10:11:12.345 (-2h3m4s567ms)" or, when denied, "Reject: ..."
+APPOPS_PACKAGE = re.compile(r'^\s*Package ([\w.]+):')
+APPOPS_OP = re.compile(r'^\s*([A-Z_]+) \(')
+APPOPS_ENTRY = re.compile(r'^\s*(Access|Reject): \[?([a-z]+)[^\s\]]*\]?.*\(-((?:\d+(?:ms|d|h|m|s))+)\)')
+# Process states of an app on screen or a persistent system process; reads in any other state came from the background
+FOREGROUND_STATES = {"top", "pers"}
+
+PACKAGE_HEADER = re.compile(r'^\s*Package \[([\w.]+)\]')
+GRANTED_PERMISSION = re.compile(r'^\s*android\.permission\.(\w+): granted=true')
+COMPONENT = re.compile(r'\b([a-zA-Z][\w]*(?:\.[\w]+)+)/([\w.$]+)')
+
+
+def parse_granted_permissions(output: str) -> Dict[str, Set[str]]:
+    """Map package -> granted runtime permissions from full `dumpsys package` output"""
+    granted: Dict[str, Set[str]] = {}
+    current = None
+    for line in output.split('\n'):
+        header = PACKAGE_HEADER.match(line)
+        if header:
+            current = header.group(1)
+            continue
+        match = GRANTED_PERMISSION.match(line)
+        if match and current:
+            granted.setdefault(current, set()).add(match.group(1))
+    return granted
+
+
+def find_tracker_sdks(output: str) -> Dict[str, List[str]]:
+    """Map package -> tracker SDK names, from components listed in `dumpsys package` resolver tables"""
+    found: Dict[str, List[str]] = {}
+    for package, class_name in COMPONENT.findall(output):
+        if class_name.startswith('.'):
+            class_name = package + class_name
+        for prefix, sdk in TRACKER_SDKS.items():
+            if class_name.startswith(prefix):
+                sdks = found.setdefault(package, [])
+                if sdk not in sdks:
+                    sdks.append(sdk)
+    return found
+
+
+def parse_listener_components(output: str) -> Dict[str, List[str]]:
+    """Map package -> enabled listener components from `cmd notification allowed_listeners`"""
+    listeners: Dict[str, List[str]] = {}
+    for package, class_name in COMPONENT.findall(output):
+        listeners.setdefault(package, []).append(f"{package}/{class_name}")
+    return listeners
+
+
+def parse_op_packages(output: str) -> List[str]:
+    """Package names from `appops query-op <op> allow`, one per line"""
+    return re.findall(r'^\s*([a-zA-Z][\w]*(?:\.[\w]+)+)\s*$', output, re.MULTILINE)
+
+
+def parse_component_setting(value: str) -> List[str]:
+    """Components from a secure setting such as enabled_accessibility_services ("pkg/cls:pkg/cls")"""
+    value = value.strip()
+    if value in ("", "null"):
+        return []
+    return [f"{package}/{class_name}" for package, class_name in COMPONENT.findall(value.replace(':', ' '))]
+
+
+def parse_clipboard_reads(output: str) -> List[Dict]:
+    """
+    Clipboard reads per package from `dumpsys appops --op READ_CLIPBOARD`,
+    most recent first: when it last read (`lastReadSecondsAgo`), whether a
+    read came from the background and whether one was rejected
+    """
+    reads: Dict[str, Dict] = {}
+    current = op = None
+    for line in output.split('\n'):
+        header = APPOPS_PACKAGE.match(line)
+        if header:
+            current, op = header.group(1), None
+            continue
+        op_header = APPOPS_OP.match(line)
+        if op_header:
+            op = op_header.group(1)
+            continue
+        entry = APPOPS_ENTRY.match(line)
+        # Older appops ignore --op and dump every op
+        if not entry or not current or op != "READ_CLIPBOARD":
+            continue
+        kind, state, ago = entry.groups()
+        seconds = parse_duration_seconds(ago)
+        read = reads.setdefault(current, {"packageName": current, "lastReadSecondsAgo": None,
+                                          "background": False, "rejected": False})
+        if kind == "Reject":
+            read["rejected"] = True
+            continue
+        if read["lastReadSecondsAgo"] is None or seconds < read["lastReadSecondsAgo"]:
+            read["lastReadSecondsAgo"] = seconds
+        if state not in FOREGROUND_STATES:
+            read["background"] = True
+    return sorted(reads.values(), key=lambda r: (r["lastReadSecondsAgo"] is None, r["lastReadSecondsAgo"] or 0))
+
+
+def find_risky_combos(granted: Dict[str, Set[str]]) -> Dict[str, List[str]]:
+    """Map package -> descriptions of risky permission combinations it holds"""
+    risky: Dict[str, List[str]] = {}
+    for package, permissions in granted.items():
+        for combo, description in RISKY_PERMISSION_COMBOS:
+            if combo <= permissions:
+                risky.setdefault(package, []).append(description)
+    return risky
+
+
+def factor_penalty(factor_id: str, count: int) -> int:
+    """Score points lost for `count` findings of one factor"""
+    per_item, cap = FACTOR_WEIGHTS[factor_id]
+    return min(cap, per_item * count)
+
+
+class PrivacyAnalyzer:
+    """Build a privacy report for the connected device"""
+
+    def __init__(self, adb_operations: ADBOperations):
+        self.adb = adb_operations
+
+    def _appops(self) -> str:
+        return "cmd appops" if self.adb.supports("cmd_package") else "appops"
+
+    def _notification_listeners(self) -> Dict[str, List[str]]:
+        return parse_listener_components(self.adb.shell("cmd notification allowed_listeners", timeout=20))
+
+    def _op_holders(self, op: str) -> Optional[List[str]]:
+        """Packages allowed an app-op; None when the device's appops cannot be queried"""
+        output = self.adb.shell(f"{self._appops()} query-op {op} allow", timeout=20) \
+            if self.adb.supports("appops") else "unknown command"
+        op_packages = parse_op_packages(output)
+        if not op_packages and any(marker in output.lower() for marker in APPOPS_UNSUPPORTED_MARKERS):
+            return None
+        return op_packages
+
+    def get_special_access(self, packages: List[Dict]) -> Dict:
+        """
+        Apps holding notification-listener, usage or overlay access,
+        preinstalled ones first.
+
+        Kinds the device cannot be asked about (no `cmd`, or an appops
+        without `query-op`) are listed in `unchecked`.
+        """
+        installed = {pkg["packageName"]: pkg for pkg in packages}
+        holders: Dict[str, List[str]] = {}
+        unchecked = []
+
+        if self.adb.supports("cmd_package"):
+            for package in self._notification_listeners():
+                holders.setdefault(package, []).append("notificationListener")
+        else:
+            unchecked.append("notificationListener")
+
+        for access, op in APPOP_ACCESS.items():
+            op_packages = self._op_holders(op)
+            if op_packages is None:
+                unchecked.append(access)
+                continue
+            for package in op_packages:
+                holders.setdefault(package, []).append(access)
+
+        entries = []
+        for package, access in holders.items():
+            pkg = installed.get(package, {})
+            entries.append({
+                "packageName": package,
+                "appName": pkg.get("appName", package),
+                "safetyLevel": pkg.get("safetyLevel"),
+                "isSystem": pkg.get("isSystem"),
+                "access": access,
+                "detail": "; ".join(SPECIAL_ACCESS[a] for a in access),
+            })
+        entries.sort(key=lambda e: (not e["isSystem"], e["packageName"]))
+        return {"packages": entries, "unchecked": unchecked}
+
+    def revoke_special_access(self, package_name: str, access: str) -> Dict:
+        """Take notification-listener, usage or overlay access away from a package"""
+        if access not in SPECIAL_ACCESS:
+            return {"success": False, "message": f"Access must be one of: {', '.join(SPECIAL_ACCESS)}"}
+        try:
+            if access == "notificationListener":
+                self.adb.require("cmd_package")
+                components = self._notification_listeners().get(package_name, [])
+                if not components:
+                    return {"success": False, "message": f"{package_name} is not a notification listener"}
+                for component in components:
+                    self.adb.shell(f"cmd notification disallow_listener {component}", timeout=20)
+            else:
+                self.adb.require("appops")
+                require_package_name(package_name)
+                output = self.adb.shell(f"{self._appops()} set {package_name} {APPOP_ACCESS[access]} ignore",
+                                        timeout=20)
+                if output.strip():
+                    return {"success": False, "message": output.strip()}
+        except UnsupportedFeatureError as e:
+            return {"success": False, "unsupported": True, "message": str(e)}
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        return {"success": True, "message": f"{ACCESS_LABELS[access]} revoked for {package_name}"}
+
+    def get_clipboard_audit(self, packages: List[Dict]) -> Dict:
+        """
+        Apps that read the clipboard since the device booted (Android 10+).
+        `available` is False with a `message` when the device keeps no
+        history or its appops cannot filter by op.
+        """
+        if not self.adb.supports("clipboard_history"):
+            return {"available": False, "packages": [],
+                    "message": "Clipboard access history needs Android 10 or newer"}
+        output = self.adb.shell("dumpsys appops --op READ_CLIPBOARD", timeout=30)
+        # An op name the device does not know is reported before the state header
+        if "AppOps Service state" not in output:
+            return {"available": False, "packages": [],
+                    "message": "This device does not report clipboard access history"}
+
+        installed = {pkg["packageName"]: pkg for pkg in packages}
+        reads = parse_clipboard_reads(output)
+        for read in reads:
+            pkg = installed.get(read["packageName"], {})
+            read.update(appName=pkg.get("appName", read["packageName"]), isSystem=pkg.get("isSystem"))
+        return {"available": True, "packages": reads}
+
+    def get_service_audit(self, packages: List[Dict]) -> Dict:
+        """
+        Enabled accessibility services, the autofill provider and VPN apps,
+        with their owning packages. Preinstalled owners get `priority` "high":
+        unlike a service the user set up, nobody chose to trust them.
+        """
+        installed = {pkg["packageName"]: pkg for pkg in packages}
+        found = []  # (kind, component, active)
+        unchecked = []
+
+        for component in parse_component_setting(
+                self.adb.shell("settings get secure enabled_accessibility_services", timeout=15)):
+            found.append(("accessibility", component, True))
+        for component in parse_component_setting(self.adb.shell("settings get secure autofill_service", timeout=15)):
+            found.append(("autofill", component, True))
+
+        if self.adb.supports("cmd_package"):
+            always_on = self.adb.shell("settings get secure always_on_vpn_app", timeout=15).strip()
+            vpn_services = self.adb.shell("cmd package query-services --brief -a android.net.VpnService", timeout=20)
+            for package, class_name in COMPONENT.findall(vpn_services):
+                found.append(("vpn", f"{package}/{class_name}", package == always_on))
+        else:
+            unchecked.append("vpn")
+
+        services = []
+        for kind, component, active in found:
+            package = component.split('/', 1)[0]
+            pkg = installed.get(package, {})
+            services.append({
+                "kind": kind,
+                "packageName": package,
+                "appName": pkg.get("appName", package),
+                "component": component,
+                "active": active,
+                "isSystem": pkg.get("isSystem"),
+                "priority": "high" if pkg.get("isSystem") else "normal",
+                "detail": AUDITED_SERVICES[kind],
+            })
+        services.sort(key=lambda s: (s["priority"] != "high", not s["active"], s["kind"], s["packageName"]))
+        return {"services": services, "unchecked": unchecked}
+
+    def get_privacy_report(self, packages: List[Dict]) -> Dict:
+        """
+        Score the device and list contributing factors and suggested removals.
+
+        `packages` are the installed packages (list_packages entries).
+        """
+        installed = {pkg["packageName"]: pkg for pkg in packages}
+        dumpsys = self.adb.shell("dumpsys package", timeout=120)
+
+        telemetry = [name for name in TELEMETRY_PACKAGES if name in installed]
+        trackers = {p: sdks for p, sdks in find_tracker_sdks(dumpsys).items() if p in installed}
+        risky = {p: combos for p, combos in find_risky_combos(parse_granted_permissions(dumpsys)).items()
+                 if p in installed}
+        # Preinstalled cleaner and ads apps use the overlay for ads on top of other apps
+        overlays = sorted(p for p in self._op_holders("SYSTEM_ALERT_WINDOW") or []
+                          if installed.get(p, {}).get("isSystem"))
+
+        factors = [
+            {
+                "id": "telemetry",
+                "label": "Telemetry, analytics and ads packages",
+                "count": len(telemetry),
+                "packages": [{"packageName": p, "category": TELEMETRY_PACKAGES[p][0],
+                              "detail": TELEMETRY_PACKAGES[p][1]} for p in telemetry],
+            },
+            {
+                "id": "trackers",
+                "label": "Apps with embedded tracker SDKs",
+                "count": len(trackers),
+                "packages": [{"packageName": p, "detail": ", ".join(sdks)} for p, sdks in sorted(trackers.items())],
+            },
+            {
+                "id": "permissions",
+                "label": "Apps holding risky permission combinations",
+                "count": len(risky),
+                "packages": [{"packageName": p, "detail": "; ".join(combos)} for p, combos in sorted(risky.items())],
+            },
+            {
+                "id": "overlays",
+                "label": "Preinstalled apps drawing over other apps",
+                "count": len(overlays),
+                "packages": [{"packageName": p, "detail": SPECIAL_ACCESS["overlay"],
+                              "action": {"command": "revoke_special_access",
+                                         "args": {"packageName": p, "access": "overlay"},
+                                         "label": "Stop drawing over other apps"}} for p in overlays],
+            },
+        ]
+        for factor in factors:
+            factor["penalty"] = factor_penalty(factor["id"], factor["count"])
+
+        suggestions = []
+        for package in telemetry:
+            level = installed[package].get("safetyLevel", "Safe")
+            if level in ("Safe", "Caution"):
+                suggestions.append({"packageName": package, "safetyLevel": level,
+                                    "reason": TELEMETRY_PACKAGES[package][1]})
+        # Trackers alone in an ordinary app are reported, not suggested for removal
+        for package, sdks in sorted(trackers.items()):
+            level = installed[package].get("safetyLevel", "Safe")
+            if package in risky and package not in TELEMETRY_PACKAGES and level in ("Safe", "Caution"):
+                suggestions.append({"packageName": package, "safetyLevel": level,
+                                    "reason": f"Contains {', '.join(sdks)} and holds {risky[package][0].lower()}"})
+
+        # Preinstalled apps running privileged services: reviewed, not scored
+        review_items = [
+            {"packageName": s["packageName"], "kind": s["kind"], "detail": s["detail"], "active": s["active"]}
+            for s in self.get_service_audit(packages)["services"] if s["priority"] == "high"
+        ]
+
+        # Reading the clipboard is mostly pasting, so reads are listed for review and not scored
+        try:
+            clipboard = self.get_clipboard_audit(packages)
+        except ADBError as e:
+            clipboard = {"available": False, "packages": [], "message": str(e)}
+
+        return {
+            "score": max(0, 100 - sum(f["penalty"] for f in factors)),
+            "factors": factors,
+            "suggestedRemovals": suggestions,
+            "reviewItems": review_items,
+            "clipboard": clipboard,
+        }
@@ -0,0 +1,76 @@
+"""
+Profiles Module
+Named package selections kept as hand-editable JSON files in
+~/DebloatAI/profiles/, e.g. a shop's "Retail Samsung" list, one file per
+profile: {"name": ..., "description": ..., "packages": [...]}
+"""
+import json
+import sys
+from pathlib import Path
+from typing import Dict, List, Optional
+from validation import is_package_name
+
+
+PROFILE_DIR = "profiles"
+
+
+def validate_profile(profile) -> Optional[str]:
+    """Why a parsed profile file is unusable, None when it is fine"""
+    if not isinstance(profile, dict):
+        return "expected an object"
+    if not isinstance(profile.get("name"), str) or not profile["name"].strip():
+        return "name must be a non-empty string"
+    if not isinstance(profile.get("description", ""), str):
+        return "description must be a string"
+    packages = profile.get("packages")
+    if not isinstance(packages, list) or not packages:
+        return "packages must be a non-empty list"
+    bad = [p for p in packages if not is_package_name(p)]
+    if bad:
+        return f"not a package name: {bad[0]!r}"
+    return None
+
+
+class ProfileStore:
+    """Profiles read from the profiles directory, keyed by file name"""
+
+    def __init__(self, data_dir: str = None):
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.path = Path(data_dir) / PROFILE_DIR
+        self._profiles, self._skipped = self._load()
+
+    def _load(self):
+        """(id -> profile, files skipped with the reason); broken files never hide the others"""
+        profiles: Dict[str, Dict] = {}
+        skipped: List[Dict] = []
+        for file in sorted(self.path.glob("*.json")):
+            try:
+                with open(file, 'r', encoding='utf-8') as f:
+                    profile = json.load(f)
+                error = validate_profile(profile)
+            except (OSError, json.JSONDecodeError) as e:
+                error = str(e)
+            if error:
+                print(f"[Warning] Ignoring profile {file}: {error}", file=sys.stderr)
+                skipped.append({"file": file.name, "message": error})
+                continue
+            profiles[file.stem] = {"id": file.stem, "name": profile["name"].strip(),
+                                   "description": profile.get("description", ""),
+                                   "packages": list(dict.fromkeys(profile["packages"]))}
+        return profiles, skipped
+
+    def reload(self) -> Optional[Dict]:
+        """Re-read the directory after an edit; None when no profile changed"""
+        profiles, skipped = self._load()
+        if profiles == self._profiles and skipped == self._skipped:
+            return None
+        self._profiles, self._skipped = profiles, skipped
+        return self.list_profiles()
+
+    def list_profiles(self) -> Dict:
+        """Every usable profile, and the files that were skipped"""
+        return {"profiles": list(self._profiles.values()), "skipped": list(self._skipped), "path": str(self.path)}
+
+    def get_profile(self, profile_id: str) -> Optional[Dict]:
+        return self._profiles.get(profile_id)
@@ -0,0 +1,152 @@
+"""
+Prompts Module
+The AI prompts as ordered sections instead of one inline string. Each
+section renders from a PromptContext and is left out when it does not
+apply (no device attached, default answer style, English), so changing one
+part of a prompt does not mean editing the others. Fixed text is kept
+within PROMPT_TOKEN_BUDGETS, checked by test_prompts.py
+"""
+import math
+from dataclasses import dataclass
+from typing import Callable, List, Optional
+
+
+@dataclass
+class PromptContext:
+    """What a prompt is built for"""
+    # Live device facts (chat_context.DeviceContextBuilder.build); None without a device
+    device_context: Optional[str] = None
+    # Local facts about package ids in the message (DeviceContextBuilder.resolve_message)
+    package_facts: Optional[str] = None
+    # ai.plainLanguage / ai.expertMode; plain language wins when both are on
+    plain_language: bool = False
+    expert: bool = False
+    # display.locale; answers are written in this language
+    locale: str = "en"
+
+
+# A section returns its text, or None to be left out
+Section = Callable[[PromptContext], Optional[str]]
+
+LANGUAGES = {"en": "English", "es": "Spanish", "fr": "French", "de": "German"}
+
+# Rough tokens of fixed prompt text (device facts and the user's message come on top)
+PROMPT_TOKEN_BUDGETS = {"chat_system": 200, "analysis": 450}
+
+CHAT_ROLE = """You are a helpful Android debloating expert assistant.
+Help users understand which apps are safe to remove and answer their questions about Android packages.
+Be concise and friendly."""
+
+ANALYSIS_SYSTEM = "You are an Android debloating expert. Always respond with valid JSON only."
+
+ANALYSIS_TEMPLATE = """You are an Android package analysis expert. Analyze package: {package_name}
+
+Return ONLY valid JSON (no markdown, no explanation):
+{{
+  "packageName": "{package_name}",
+  "summary": "Brief description in plain words",
+  "purpose": "What this package does",
+  "dependencies": ["list of packages that might depend on this"],
+  "safeToRemove": true/false,
+  "riskCategory": "Safe/Caution/Expert/Dangerous",
+  "consequences": ["what happens if removed"],
+  "userReports": ["common user experiences"],
+  "technicalDetails": "Technical information",
+  "bestCase": "Best case scenario if removed",
+  "worstCase": "Worst case scenario if removed",
+  "confidence": 0.0-1.0 (how sure you are of riskCategory)
+}}
+
+Risk categories:
+- Safe: Third-party apps, easily reinstallable
+- Caution: OEM apps, may affect minor features
+- Expert: May break functionality
+- Dangerous: Critical system components"""
+
+# Added when `ai.plainLanguage` is on
+PLAIN_LANGUAGE_STYLE = """Write for someone who is not technical:
+- Use short sentences and everyday words
+- Avoid jargon; if a technical term is unavoidable, explain it in a few words
+- Say what the user will notice on their phone, not how Android works inside"""
+
+# Added when `ai.expertMode` is on
+EXPERT_STYLE = """The user is experienced with adb:
+- Name exact package and component ids
+- Include the adb shell commands to check or undo a change
+- Skip basic explanations"""
+
+
+def estimate_tokens(text: str) -> int:
+    """Rough token count (about four characters per token), enough for budget checks"""
+    return math.ceil(len(text) / 4)
+
+
+def compose(sections: List[Section], context: PromptContext) -> str:
+    """Render the sections in order, leaving out those that do not apply"""
+    return "\n\n".join(text for text in (section(context) for section in sections) if text)
+
+
+# ===== Sections =====
+
+def device_state(context: PromptContext) -> Optional[str]:
+    return f"[Current device state]\n{context.device_context}" if context.device_context else None
+
+
+def package_facts(context: PromptContext) -> Optional[str]:
+    return f"[Packages in this message]\n{context.package_facts}" if context.package_facts else None
+
+
+def answer_style(context: PromptContext) -> Optional[str]:
+    if context.plain_language:
+        return PLAIN_LANGUAGE_STYLE
+    return EXPERT_STYLE if context.expert else None
+
+
+def answer_language(context: PromptContext) -> Optional[str]:
+    language = LANGUAGES.get(context.locale)
+    if not language or context.locale == "en":
+        return None
+    return f"Answer in {language}."
+
+
+def analysis_style(context: PromptContext) -> Optional[str]:
+    style = answer_style(context)
+    if style is None:
+        return None
+    return f"{style}\nThis applies to every text field of the JSON; keep technicalDetails short as well."
+
+
+def analysis_language(context: PromptContext) -> Optional[str]:
+    language = answer_language(context)
+    if language is None:
+        return None
+    return f"Write the text fields in {LANGUAGES[context.locale]}; keep the JSON keys and riskCategory values in English."
+
+
+# Instructions following the role in a system prompt, or the user's message without a system role
+CHAT_INSTRUCTIONS: List[Section] = [answer_style, answer_language]
+CHAT_SYSTEM: List[Section] = [lambda context: CHAT_ROLE, *CHAT_INSTRUCTIONS]
+ANALYSIS_INSTRUCTIONS: List[Section] = [analysis_style, analysis_language]
+
+
+# ===== Prompts =====
+
+def chat_system_prompt(context: PromptContext) -> str:
+    return compose(CHAT_SYSTEM, context)
+
+
+def chat_user_message(message: str, context: PromptContext, with_instructions: bool = False) -> str:
+    """
+    The user's turn: device state, the message and the facts about its
+    packages; with_instructions adds the answer style and language for
+    providers without a system role
+    """
+    sections = [device_state, lambda c: message, package_facts]
+    if with_instructions:
+        sections += CHAT_INSTRUCTIONS
+    return compose(sections, context)
+
+
+def analysis_prompt(package_name: str, context: PromptContext) -> str:
+    return compose([lambda c: ANALYSIS_TEMPLATE.format(package_name=package_name), *ANALYSIS_INSTRUCTIONS],
+                   context)
@@ -0,0 +1,111 @@
+"""
+Provenance Module
+Works out where each package came from (Google Play, another store, an
+OEM or carrier preload, or a sideload) and which partition it lives on
+from its installer and code path, since removal safety and the way to get
+an app back differ by origin
+"""
+import re
+from typing import Dict, Optional, Tuple
+
+
+# `pm list packages -f -i`: "package:/data/app/~~a1/com.foo-b2/base.apk=com.foo  installer=com.android.vending"
+PACKAGE_DETAIL_PATTERN = re.compile(r'^package:(\S+)=([\w.]+)(?:\s+installer=(\S+))?', re.MULTILINE)
+
+PLAY_STORE_INSTALLERS = {"com.android.vending"}
+
+# Installers that only mean "installed from an APK file or over adb"
+SIDELOAD_INSTALLERS = {
+    "null",
+    "adb",
+    "com.android.packageinstaller",
+    "com.google.android.packageinstaller",
+    "com.android.shell",
+}
+
+# Package prefixes of carrier apps and of the preload services carriers use
+CARRIER_PREFIXES = (
+    "com.verizon.",
+    "com.vzw.",
+    "com.att.",
+    "com.tmobile.",
+    "com.sprint.",
+    "com.mobitv.",
+    "com.dti.",  # Digital Turbine (carrier app preloads)
+    "com.motricity.",
+    "com.vodafone.",
+    "com.orange.",
+)
+
+# Partition reported for each code path prefix; anything else (/data/app) is "data"
+PARTITION_PREFIXES = {
+    "/system/": "system",
+    "/system_ext/": "system_ext",
+    "/product/": "product",
+    "/vendor/": "vendor",
+    "/odm/": "odm",
+    "/oem/": "oem",
+    "/apex/": "apex",
+}
+PARTITIONS = (*PARTITION_PREFIXES.values(), "data")
+
+# Read-only partitions apps are preloaded on
+PRELOAD_PARTITIONS = tuple(PARTITION_PREFIXES)
+
+# Packages on these partitions may not come back with `install-existing`:
+# vendor apps are tied to the vendor image and APEX-backed ones to their module
+UNRESTORABLE_PARTITIONS = {"vendor", "apex"}
+
+# installSource -> label shown to the user
+INSTALL_SOURCE_LABELS = {
+    "googlePlay": "Google Play",
+    "otherStore": "Other app store",
+    "oemPreload": "OEM preload",
+    "carrier": "Carrier",
+    "sideloaded": "Sideloaded",
+}
+
+
+def parse_package_details(output: str) -> Dict[str, Tuple[str, Optional[str]]]:
+    """Parse `pm list packages -f -i` into package -> (code path, installer or None)"""
+    details = {}
+    for path, package, installer in PACKAGE_DETAIL_PATTERN.findall(output):
+        details[package] = (path, None if installer in ("", "null") else installer)
+    return details
+
+
+def is_preload_path(path: Optional[str]) -> bool:
+    return bool(path) and path.startswith(PRELOAD_PARTITIONS)
+
+
+def partition_of(path: Optional[str]) -> Optional[str]:
+    """
+    Partition holding a package's code path; None when the path is unknown.
+
+    Updated system apps run from /data/app, so they report "data".
+    """
+    if not path:
+        return None
+    for prefix, partition in PARTITION_PREFIXES.items():
+        if path.startswith(prefix):
+            return partition
+    return "data"
+
+
+def classify_install_source(package: str, path: Optional[str], installer: Optional[str],
+                            is_system: Optional[bool] = None) -> str:
+    """
+    One of INSTALL_SOURCE_LABELS for a package.
+
+    Updated preloads live under /data/app like user apps, so `is_system`
+    (not in `pm list packages -3`) marks them as preloads too.
+    """
+    if package.startswith(CARRIER_PREFIXES) or (installer or "").startswith(CARRIER_PREFIXES):
+        return "carrier"
+    if is_preload_path(path) or is_system:
+        return "oemPreload"
+    if installer in PLAY_STORE_INSTALLERS:
+        return "googlePlay"
+    if installer is None or installer in SIDELOAD_INSTALLERS:
+        return "sideloaded"
+    return "otherStore"
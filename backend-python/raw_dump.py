@@ -0,0 +1,133 @@
+"""
+Raw Dump Module
+Shows advanced users the raw `dumpsys` text the parsers work from, so a
+metric that looks wrong can be checked against what the device reported.
+Only allowlisted services can be dumped, output is capped and served in
+pages of lines
+"""
+import threading
+import time
+from typing import Dict, Optional, Tuple
+from adb_operations import ADBOperations, ADBError, UnsupportedFeatureError
+from cache_policy import CacheStats, cache_report
+from validation import is_package_name
+
+
+# service -> (what the app reads from it, command with `{package}`, or None when
+# the service has no per-package form, feature it needs or None)
+RAW_DUMP_SERVICES = {
+    "package": ("Package details, permissions and components", "dumpsys package {package}", None),
+    "activity": ("Running app services", "dumpsys activity services {package}", None),
+    "meminfo": ("Memory use", "dumpsys meminfo {package}", None),
+    "batterystats": ("Battery use", "dumpsys batterystats {package}", None),
+    "usagestats": ("Last-used times", None, "usage_stats"),
+    "diskstats": ("App and cache sizes", None, None),
+    "battery": ("Battery state and temperature", None, None),
+    "thermalservice": ("Thermal sensors", None, "thermal_service"),
+    "device_policy": ("Device and profile owners", None, None),
+    "role": ("Default app roles", None, "role_service"),
+    "webviewupdate": ("WebView providers", None, None),
+}
+
+# Output beyond this many characters is cut off (full `dumpsys package` can run to tens of MB)
+MAX_DUMP_CHARS = 4 * 1024 * 1024
+
+DEFAULT_PAGE_LINES = 500
+MAX_PAGE_LINES = 5000
+
+# Dumps are kept this long so paging does not re-run dumpsys
+CACHE_SECONDS = 120
+
+def dump_command(service: str, package: Optional[str] = None) -> str:
+    """Shell command for an allowlisted service; ValueError for anything else"""
+    if service not in RAW_DUMP_SERVICES:
+        raise ValueError(f"'{service}' is not an allowed service ({', '.join(RAW_DUMP_SERVICES)})")
+    _, package_command, _ = RAW_DUMP_SERVICES[service]
+    if not package:
+        return package_command.replace(" {package}", "") if package_command else f"dumpsys {service}"
+    if package_command is None:
+        raise ValueError(f"dumpsys {service} cannot be filtered by package")
+    if not is_package_name(package):
+        raise ValueError(f"Invalid package name: {package}")
+    return package_command.format(package=package)
+
+
+def paginate(text: str, page: int, page_lines: int) -> Dict:
+    """One page of `text` by lines; pages are numbered from 0 and clamped to the last page"""
+    lines = text.split('\n')
+    page_lines = max(1, min(int(page_lines), MAX_PAGE_LINES))
+    total_pages = max(1, -(-len(lines) // page_lines))
+    page = max(0, min(int(page), total_pages - 1))
+    start = page * page_lines
+    return {
+        "text": '\n'.join(lines[start:start + page_lines]),
+        "page": page,
+        "pageLines": page_lines,
+        "totalPages": total_pages,
+        "totalLines": len(lines),
+        "firstLine": start + 1,
+    }
+
+
+class RawDumpViewer:
+    """Fetch, cap and page raw dumpsys output"""
+
+    def __init__(self, adb_operations: ADBOperations):
+        self.adb = adb_operations
+        self._lock = threading.Lock()
+        # command -> (fetched at, text, truncated)
+        self._cache: Dict[str, Tuple[float, str, bool]] = {}
+        self.stats = CacheStats()
+
+    def list_services(self) -> Dict[str, Dict]:
+        """Allowlisted services with what they are used for"""
+        return {
+            service: {"usedFor": used_for, "perPackage": package_command is not None}
+            for service, (used_for, package_command, _) in RAW_DUMP_SERVICES.items()
+        }
+
+    def _fetch(self, command: str, refresh: bool) -> Tuple[float, str, bool]:
+        with self._lock:
+            cached = self._cache.get(command)
+            if cached and not refresh and time.time() - cached[0] < CACHE_SECONDS:
+                self.stats.hit()
+                return cached
+        self.stats.miss()
+        output = self.adb.shell(command, timeout=120)
+        truncated = len(output) > MAX_DUMP_CHARS
+        entry = (time.time(), output[:MAX_DUMP_CHARS], truncated)
+        with self._lock:
+            # Only the latest dump is kept; they can be several MB each
+            self._cache = {command: entry}
+        return entry
+
+    def cache_diagnostics(self) -> Dict:
+        """get_cache_diagnostics line of the kept dump"""
+        with self._lock:
+            cache = dict(self._cache)
+        return cache_report("rawDump", self.stats, cache, [entry[0] for entry in cache.values()], len(cache))
+
+    def get_raw_dump(self, service: str, package: Optional[str] = None, page: int = 0,
+                     page_lines: int = DEFAULT_PAGE_LINES, refresh: bool = False) -> Dict:
+        """
+        A page of `dumpsys <service> [package]`.
+
+        `truncated` is True when the output was longer than MAX_DUMP_CHARS;
+        `fetchedAt` tells how old the (cached) dump is.
+        """
+        try:
+            command = dump_command(service, package)
+            feature = RAW_DUMP_SERVICES[service][2]
+            if feature:
+                self.adb.require(feature)
+            fetched_at, text, truncated = self._fetch(command, refresh)
+        except ValueError as e:
+            return {"success": False, "message": str(e)}
+        except UnsupportedFeatureError as e:
+            return {"success": False, "unsupported": True, "message": str(e)}
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        result = {"success": True, "service": service, "package": package or None, "command": command,
+                  "fetchedAt": fetched_at, "truncated": truncated}
+        result.update(paginate(text, page, page_lines))
+        return result
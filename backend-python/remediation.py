@@ -0,0 +1,175 @@
+"""
+Remediation Module
+Turns a failed `install-existing` into instructions the user can follow,
+chosen from a knowledge table by failure kind, manufacturer and Android
+version instead of showing raw package manager output
+"""
+import re
+from typing import Dict, List, Optional
+
+
+# failure kind -> pattern of the package manager output; first match wins
+REINSTALL_FAILURES = [
+    ("restricted", re.compile(r'SecurityException|Permission Denial|INSTALL_FAILED_USER_RESTRICTED', re.IGNORECASE)),
+    ("unknownCommand", re.compile(r'Unknown command|unknown option', re.IGNORECASE)),
+    ("notInstalledForUser", re.compile(r'not installed for (user )?\d+|isn.t installed for', re.IGNORECASE)),
+    ("notOnDevice", re.compile(r'doesn.t exist|Unknown package|NameNotFoundException|not found', re.IGNORECASE)),
+]
+
+# Most specific entries first: the first one whose failure, manufacturer and
+# API range all match is used. manufacturers are lowercase
+# ro.product.manufacturer values, None for any.
+REMEDIATIONS = [
+    {
+        "failure": "restricted",
+        "manufacturers": ("xiaomi", "redmi", "poco"),
+        "summary": "MIUI/HyperOS blocks app installs over USB until it is allowed in Developer options",
+        "steps": [
+            "Turn on Developer options → \"Install via USB\" and \"USB debugging (Security settings)\"",
+            "These need a Mi account and a SIM card; reconnect the device after changing them",
+            "Retry the reinstall",
+        ],
+    },
+    {
+        "failure": "restricted",
+        "manufacturers": ("oppo", "realme", "oneplus"),
+        "summary": "ColorOS blocks package changes over USB while permission monitoring is on",
+        "steps": [
+            "Turn off Developer options → \"Disable permission monitoring\" (it must be switched on)",
+            "Reconnect the device and retry the reinstall",
+        ],
+    },
+    {
+        "failure": "restricted",
+        "manufacturers": ("amazon",),
+        "summary": "Fire OS refused to reinstall this Amazon app over adb",
+        "steps": [
+            "Install it again from the Amazon Appstore if it is listed there",
+            "Otherwise wait for the next Fire OS update, which puts preloaded Amazon apps back",
+            "Settings → Device Options → Reset to Factory Defaults restores it now, but erases all data",
+        ],
+    },
+    {
+        "failure": "restricted",
+        "manufacturers": None,
+        "summary": "The device refused the reinstall",
+        "steps": [
+            "Unlock the device and accept any prompt shown on its screen",
+            "Check that no work profile or device management policy blocks app installs",
+            "Retry the reinstall",
+        ],
+    },
+    {
+        "failure": "unknownCommand",
+        "manufacturers": None,
+        "summary": "This Android version does not understand the reinstall command",
+        "steps": [
+            "Run `adb shell pm install-existing --user 0 <package>` (the `pm` form instead of `cmd package`)",
+            "If the app was disabled rather than uninstalled, run `adb shell pm enable <package>`",
+        ],
+    },
+    {
+        "failure": "notInstalledForUser",
+        "manufacturers": ("samsung",),
+        "summary": "The app is not installed for the main user; Secure Folder and Dual Messenger add extra users",
+        "steps": [
+            "Run `adb shell pm install-existing --user 0 <package>` to target the main user explicitly",
+            "If it was removed from Secure Folder only, reinstall it from inside Secure Folder",
+        ],
+    },
+    {
+        "failure": "notInstalledForUser",
+        "manufacturers": None,
+        "maxSdk": 25,
+        "summary": "Android 7 and older can only restore apps removed for the main user with the `pm` syntax",
+        "steps": [
+            "Run `adb shell pm install-existing --user 0 <package>`",
+            "If that fails, the app can only come back with a factory reset (erases all data) or its APK",
+        ],
+    },
+    {
+        "failure": "notInstalledForUser",
+        "manufacturers": None,
+        "summary": "The app is not installed for the main user",
+        "steps": [
+            "Run `adb shell pm install-existing --user 0 <package>` to target the main user explicitly",
+            "Check whether the app lives in a work profile or another user instead",
+        ],
+    },
+    {
+        "failure": "notOnDevice",
+        "manufacturers": ("samsung",),
+        "summary": "The app is no longer on the device; Samsung apps can be installed again from the Galaxy Store",
+        "steps": [
+            "Search for the app in the Galaxy Store (or the Play Store)",
+            "A factory reset restores every preloaded app, but erases all data",
+        ],
+    },
+    {
+        "failure": "notOnDevice",
+        "manufacturers": ("xiaomi", "redmi", "poco"),
+        "summary": "The app is no longer on the device; Xiaomi apps can be installed again from GetApps",
+        "steps": [
+            "Search for the app in GetApps (or the Play Store)",
+            "A factory reset restores every preloaded app, but erases all data",
+        ],
+    },
+    {
+        "failure": "notOnDevice",
+        "manufacturers": ("amazon",),
+        "summary": "The app is no longer on the device; Amazon apps can be installed again from the Amazon Appstore",
+        "steps": [
+            "Search for the app in the Amazon Appstore (Fire devices have no Play Store)",
+            "Fire OS updates also put preloaded Amazon apps back",
+            "A factory reset restores every preloaded app, but erases all data",
+        ],
+    },
+    {
+        "failure": "notOnDevice",
+        "manufacturers": ("huawei", "honor"),
+        "summary": "The app is no longer on the device; Huawei apps can be installed again from AppGallery",
+        "steps": [
+            "Search for the app in AppGallery",
+            "A factory reset restores every preloaded app, but erases all data",
+        ],
+    },
+    {
+        "failure": "notOnDevice",
+        "manufacturers": None,
+        "summary": "The app is no longer on the device, so it cannot be restored from the system image",
+        "steps": [
+            "Install it from the Play Store, or restore a full backup that contains its APK",
+            "A factory reset restores every preloaded app, but erases all data",
+        ],
+    },
+]
+
+
+def classify_reinstall_failure(output: str) -> Optional[str]:
+    """Failure kind of `install-existing` output, None if unrecognised"""
+    for failure, pattern in REINSTALL_FAILURES:
+        if pattern.search(output):
+            return failure
+    return None
+
+
+def remediation_for(output: str, manufacturer: Optional[str] = None, sdk: Optional[int] = None,
+                    package_name: str = "<package>") -> Optional[Dict]:
+    """
+    {failure, summary, steps} for a failed reinstall, None if the failure
+    is not recognised. `<package>` in the steps is replaced by `package_name`.
+    """
+    failure = classify_reinstall_failure(output)
+    if failure is None:
+        return None
+    manufacturer = (manufacturer or "").strip().lower()
+    for entry in REMEDIATIONS:
+        if entry["failure"] != failure:
+            continue
+        if entry["manufacturers"] and manufacturer not in entry["manufacturers"]:
+            continue
+        if "maxSdk" in entry and (sdk is None or sdk > entry["maxSdk"]):
+            continue
+        steps: List[str] = [step.replace("<package>", package_name) for step in entry["steps"]]
+        return {"failure": failure, "summary": entry["summary"], "steps": steps}
+    return None
@@ -0,0 +1,318 @@
+"""
+Remote Server Module
+Optional HTTP API with the same commands as the Electron channel, so a
+browser-based or remote UI can drive a bench machine running the backend
+without a window (`python main.py --serve`). It listens on loopback unless
+another address is configured, and every request needs the bearer token
+kept in ~/DebloatAI/remote_token
+"""
+import hmac
+import ipaddress
+import json
+import os
+import secrets
+import sys
+import threading
+from collections import deque
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional, Tuple
+from urllib.parse import parse_qs, urlsplit
+from events import EventEnvelope
+
+
+REMOTE_DEFAULTS = {"enabled": False, "host": "127.0.0.1", "port": 8765, "allowedOrigin": ""}
+
+# Events kept for clients polling /api/events
+EVENT_FEED_SIZE = 500
+
+# Longest wait of one /api/events poll, in seconds
+MAX_EVENT_WAIT = 30
+
+# Largest request body accepted (command arguments are small JSON)
+MAX_BODY_BYTES = 10 * 1024 * 1024
+
+COMMAND_PREFIX = "/api/commands/"
+
+# Commands a remote client may run. Left out: changing settings, the access token, the
+# confirmation role and the AI budget, reading settings (API keys) or the token itself,
+# installing udev rules with root, windows opened on this computer (scrcpy) and the
+# stdout event replay (remote clients poll /api/events instead)
+REMOTE_COMMANDS = frozenset({
+    # Device
+    "get_device_info", "set_device_nickname", "get_usb_permission_help", "probe_device_capabilities",
+    "get_device_class", "get_class_presets", "get_oem_database", "get_package_signature", "get_compatibility",
+    "get_device_management", "start_prefetch", "get_prefetch_status", "hold_stay_awake", "release_stay_awake",
+    "get_stay_awake_status", "get_screen_mirror_status",
+    # Packages
+    "list_packages", "stream_packages", "ack_package_chunk", "cancel_package_stream", "get_cached_packages",
+    "search_packages", "list_package_families", "resolve_app_labels", "validate_selection", "list_profiles",
+    "list_overlays", "list_apex_modules", "set_overlay_enabled", "uninstall_package", "wipe_package_data",
+    "reinstall_package", "start_job", "finish_job", "get_incomplete_jobs", "resume_job", "discard_job",
+    "start_trial", "list_trials", "keep_trial", "revert_trial", "get_undo_stack", "undo",
+    "get_confirmation_policy", "get_confirmation_requirement",
+    # Privacy, usage and battery
+    "get_special_access", "get_service_audit", "revoke_special_access", "get_clipboard_audit",
+    "get_privacy_report", "get_unused_app_suggestions", "get_weekly_digest", "get_charging_sessions",
+    "get_battery_trend",
+    # Tutorial
+    "get_tutorial_state", "advance_tutorial", "dismiss_tutorial",
+    # AI
+    "analyze_package", "chat_message", "test_connection", "get_ai_usage_stats", "parse_chat_command",
+    "execute_action", "export_analyses",
+    # Backups
+    "create_backup", "create_full_backup", "list_backups", "list_backup_groups", "preview_retention_cleanup",
+    "run_retention_cleanup", "restore_backup", "preview_backup_transfer", "apply_backup_transfer",
+    "delete_backup", "get_backup_path",
+    # Health and tweaks
+    "get_system_health", "start_health_monitor", "stop_health_monitor", "get_cache_diagnostics",
+    "clear_health_cache", "list_raw_dump_services", "get_raw_dump", "get_performance_report",
+    "set_animation_scale", "trim_caches", "restrict_background", "mute_notifications", "revoke_permission",
+    "grant_permission", "run_optimization",
+    # Guided flows and restarts
+    "list_guided_actions", "open_settings_page", "open_app_info", "open_in_play_store", "press_guided_key",
+    "guided_tap", "restart_system_ui", "restart_launcher", "soft_reboot", "reboot_device", "emergency_recover",
+    # Diagnostics
+    "export_session_report", "list_parser_failures", "export_diagnostics", "clear_parser_failures",
+    "list_crash_reports", "clear_crash_reports",
+})
+
+
+class EventFeed:
+    """Numbered recent events, for clients that poll instead of reading stdout"""
+
+    def __init__(self, size: int = EVENT_FEED_SIZE):
+        self._events: deque = deque(maxlen=size)
+        self._last = 0
+        self._changed = threading.Condition()
+
+    def publish(self, event: EventEnvelope):
+        with self._changed:
+            self._last += 1
+            self._events.append((self._last, event))
+            self._changed.notify_all()
+
+    def since(self, after: int, wait: float = 0) -> Dict:
+        """
+        Events numbered above `after`, waiting up to `wait` seconds for one.
+        `missed` is set when some of them were already dropped from the feed.
+        """
+        with self._changed:
+            self._changed.wait_for(lambda: self._last > after, timeout=max(0.0, min(wait, MAX_EVENT_WAIT)))
+            events = [{**event, "sequence": number} for number, event in self._events if number > after]
+            first = self._events[0][0] if self._events else self._last + 1
+            return {"events": events, "last": self._last, "missed": after + 1 < first and after < self._last}
+
+    @property
+    def last(self) -> int:
+        return self._last
+
+
+def is_loopback(host: str) -> bool:
+    if host == "localhost":
+        return True
+    try:
+        return ipaddress.ip_address(host).is_loopback
+    except ValueError:
+        return False
+
+
+class RemoteServer:
+    """Serve backend commands and events over HTTP with token auth"""
+
+    def __init__(self, setting: Callable[[str], Any] = REMOTE_DEFAULTS.get, event_feed: EventFeed = None,
+                 data_dir: str = None):
+        self.setting = setting
+        self.events = event_feed or EventFeed()
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.token_path = Path(data_dir) / "remote_token"
+        self._token: Optional[str] = None
+        self._dispatch: Optional[Callable[[Dict], Dict]] = None
+        self._server: Optional[ThreadingHTTPServer] = None
+        self._forced = False
+        self._address: Tuple[Optional[str], Optional[int]] = (None, None)
+        self._error: Optional[str] = None
+        self._lock = threading.Lock()
+
+    def token(self) -> str:
+        """The access token, created on first use and readable only by this user"""
+        if self._token is None:
+            try:
+                self._token = self.token_path.read_text(encoding="utf-8").strip() or None
+            except OSError:
+                pass
+        return self._token or self.regenerate_token()["token"]
+
+    def regenerate_token(self) -> Dict:
+        """Replace the token; clients using the old one are refused from now on"""
+        token = secrets.token_urlsafe(32)
+        self.token_path.parent.mkdir(parents=True, exist_ok=True)
+        fd = os.open(self.token_path, os.O_WRONLY | os.O_CREAT | os.O_TRUNC, 0o600)
+        with os.fdopen(fd, 'w', encoding="utf-8") as f:
+            f.write(token)
+        self._token = token
+        return {"success": True, "token": token, "message": "New remote access token created"}
+
+    def start(self, dispatch: Callable[[Dict], Dict], force: bool = False, host: str = None, port: int = None):
+        """
+        Serve `dispatch` ({command, args} -> {result} or {error}) when the
+        `remote.enabled` setting is on, or always with `force` (--serve);
+        `host` and `port` override the settings
+        """
+        self._dispatch = dispatch
+        self._forced = force
+        self._address = (host, port)
+        self.apply()
+
+    def address(self) -> Tuple[str, int]:
+        host, port = self._address
+        return host or self.setting("host"), int(port if port is not None else self.setting("port"))
+
+    def apply(self) -> Dict:
+        """Start, restart or stop the server to match the current settings"""
+        with self._lock:
+            self._shutdown()
+            self._error = None
+            if self._dispatch is not None and (self._forced or self.setting("enabled")):
+                host, port = self.address()
+                try:
+                    self._server = ThreadingHTTPServer((host, port), self._handler())
+                except OSError as e:
+                    self._error = f"Could not listen on {host}:{port}: {e}"
+                    print(f"[Warning] {self._error}", file=sys.stderr)
+                else:
+                    self._server.daemon_threads = True
+                    threading.Thread(target=self._server.serve_forever, name="remote-server", daemon=True).start()
+                    if not is_loopback(host):
+                        print(f"[Warning] Remote control is reachable from the network on {host}:{port}",
+                              file=sys.stderr)
+        return self.status()
+
+    def _shutdown(self):
+        if self._server is not None:
+            self._server.shutdown()
+            self._server.server_close()
+            self._server = None
+
+    def stop(self):
+        with self._lock:
+            self._shutdown()
+
+    def status(self) -> Dict:
+        """Whether the server runs, where, and the token to give to clients"""
+        server = self._server
+        host, port = self.address()
+        if server is not None:
+            port = server.server_address[1]  # The port picked by the system when 0
+        return {
+            "running": server is not None,
+            "enabled": bool(self.setting("enabled")) or self._forced,
+            "url": f"http://{host}:{port}/api/" if server is not None else None,
+            "loopbackOnly": is_loopback(host),
+            "token": self.token(),
+            "error": self._error,
+        }
+
+    def _handler(self):
+        remote = self
+
+        class Handler(BaseHTTPRequestHandler):
+            server_version = "DebloatAI"
+
+            def log_message(self, format, *args):
+                pass  # stdout is the Electron channel, and stderr would fill with every poll
+
+            def _cors(self):
+                origin = remote.setting("allowedOrigin")
+                if origin and self.headers.get("Origin") == origin:
+                    self.send_header("Access-Control-Allow-Origin", origin)
+                    self.send_header("Access-Control-Allow-Headers", "Authorization, Content-Type")
+                    self.send_header("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
+
+            def _reply(self, status: int, body: Dict):
+                data = json.dumps(body, ensure_ascii=False).encode("utf-8")
+                self.send_response(status)
+                self._cors()
+                self.send_header("Content-Type", "application/json; charset=utf-8")
+                self.send_header("Content-Length", str(len(data)))
+                self.end_headers()
+                self.wfile.write(data)
+
+            def _authorized(self) -> bool:
+                scheme, _, given = (self.headers.get("Authorization") or "").partition(" ")
+                if scheme == "Bearer" and hmac.compare_digest(given.strip().encode(), remote.token().encode()):
+                    return True
+                self._reply(401, {"error": "Missing or wrong access token"})
+                return False
+
+            def do_OPTIONS(self):
+                self.send_response(204)
+                self._cors()
+                self.end_headers()
+
+            def do_GET(self):
+                if not self._authorized():
+                    return
+                url = urlsplit(self.path)
+                if url.path == "/api/status":
+                    self._reply(200, {"ready": True, "lastEvent": remote.events.last})
+                elif url.path == "/api/events":
+                    query = parse_qs(url.query)
+                    try:
+                        after = int(query.get("after", ["0"])[0])
+                        wait = float(query.get("wait", ["0"])[0])
+                    except ValueError:
+                        self._reply(400, {"error": "after and wait must be numbers"})
+                        return
+                    self._reply(200, remote.events.since(after, wait))
+                else:
+                    self._reply(404, {"error": f"No such endpoint: {url.path}"})
+
+            def do_POST(self):
+                if not self._authorized():
+                    return
+                path = urlsplit(self.path).path
+                if not path.startswith(COMMAND_PREFIX) or len(path) == len(COMMAND_PREFIX):
+                    self._reply(404, {"error": f"No such endpoint: {path}"})
+                    return
+                command = path[len(COMMAND_PREFIX):]
+                if command not in REMOTE_COMMANDS:
+                    self._reply(403, {"error": f"{command} cannot be run remotely"})
+                    return
+                args, error = self._read_args()
+                if error:
+                    self._reply(400, {"error": error})
+                    return
+                response = remote._dispatch({"command": command, "args": args})
+                self._reply(200, response)
+
+            def _read_args(self) -> Tuple[Dict, Optional[str]]:
+                try:
+                    length = int(self.headers.get("Content-Length") or 0)
+                except ValueError:
+                    return {}, "Bad Content-Length"
+                if length < 0:
+                    return {}, "Bad Content-Length"
+                if length > MAX_BODY_BYTES:
+                    return {}, f"Request body over {MAX_BODY_BYTES // (1024 * 1024)} MB"
+                if length == 0:
+                    return {}, None
+                try:
+                    args = json.loads(self.rfile.read(length))
+                except (json.JSONDecodeError, UnicodeDecodeError) as e:
+                    return {}, f"JSON parse error: {e}"
+                if not isinstance(args, dict):
+                    return {}, "The body must be a JSON object of command arguments"
+                return args, None
+
+        return Handler
+
+
+def serve_address(argv: List[str]) -> Tuple[Optional[str], Optional[int]]:
+    """`--host` and `--port` given with --serve, None where not given"""
+    host = argv[argv.index("--host") + 1] if "--host" in argv[:-1] else None
+    port = argv[argv.index("--port") + 1] if "--port" in argv[:-1] else None
+    if port is not None and not port.isdigit():
+        raise ValueError(f"--port must be a number, not {port}")
+    return host, int(port) if port is not None else None
@@ -0,0 +1,251 @@
+"""
+Screen Mirror Module
+Starts scrcpy for the connected device, so its screen can be watched and
+used from the desktop while authorization dialogs are confirmed and apps
+are tested after debloating. scrcpy is taken from PATH, from next to the
+app, or downloaded once from its GitHub releases into ~/DebloatAI/tools
+"""
+import glob
+import hashlib
+import os
+import platform as platform_module
+import re
+import shutil
+import subprocess
+import sys
+import tarfile
+import threading
+import time
+import zipfile
+from pathlib import Path
+from typing import Callable, Dict, Optional, Tuple
+import network
+from adb_operations import ADBOperations, ADBError
+from compatibility import version_tuple
+
+
+SCRCPY_RELEASE_URL = "https://api.github.com/repos/Genymobile/scrcpy/releases/latest"
+
+# Seconds allowed for the release lookup and the archive download
+RELEASE_TIMEOUT = 15
+DOWNLOAD_TIMEOUT = 300
+
+# Seconds to watch a new scrcpy for an early exit (no device, no display...)
+STARTUP_WAIT = 1.5
+
+# Lines of scrcpy's output kept to explain an early exit
+ERROR_TAIL_LINES = 5
+
+
+def scrcpy_name(platform: str) -> str:
+    return "scrcpy.exe" if platform == "win32" else "scrcpy"
+
+
+def asset_pattern(platform: str, machine: str) -> Optional[re.Pattern]:
+    """Release asset name of the prebuilt scrcpy for this system; None without one"""
+    machine = machine.lower()
+    if platform == "win32":
+        return re.compile(r'^scrcpy-win64-v[\d.]+\.zip$')
+    if platform.startswith("linux") and machine in ("x86_64", "amd64"):
+        return re.compile(r'^scrcpy-linux-x86_64-v[\d.]+\.tar\.gz$')
+    if platform == "darwin":
+        arch = "aarch64" if machine in ("arm64", "aarch64") else "x86_64"
+        return re.compile(rf'^scrcpy-macos-{arch}-v[\d.]+\.tar\.gz$')
+    return None
+
+
+def release_version(folder: str) -> Tuple[int, ...]:
+    """Version of a download folder, "scrcpy-linux-x86_64-v3.1" -> (3, 1); () when it has none"""
+    match = re.search(r'-v([\d.]+)$', folder)
+    return (version_tuple(match.group(1).strip('.')) or ()) if match else ()
+
+
+def pick_asset(release: Dict, platform: str, machine: str) -> Optional[Dict]:
+    pattern = asset_pattern(platform, machine)
+    if pattern is None:
+        return None
+    return next((a for a in release.get("assets", []) if pattern.match(a.get("name", ""))), None)
+
+
+def extract_archive(archive: Path, target: Path):
+    """Unpack a release zip or tar.gz, refusing members that would land outside `target`"""
+    target.mkdir(parents=True, exist_ok=True)
+    root = target.resolve()
+    if archive.name.endswith(".zip"):
+        with zipfile.ZipFile(archive) as zf:
+            names = zf.namelist()
+            if any(not (root / name).resolve().is_relative_to(root) for name in names):
+                raise ValueError("The archive contains paths outside its folder")
+            zf.extractall(target)
+        return
+    with tarfile.open(archive, "r:gz") as tf:
+        members = tf.getmembers()
+        if any(m.issym() or m.islnk() or not (root / m.name).resolve().is_relative_to(root) for m in members):
+            raise ValueError("The archive contains links or paths outside its folder")
+        tf.extractall(target, members=members)
+
+
+def drain(stream):
+    for _ in stream:
+        pass
+
+
+class ScreenMirror:
+    """Finds, downloads and runs scrcpy mirroring sessions"""
+
+    def __init__(self, adb_operations: ADBOperations, data_dir: str = None, platform: str = None,
+                 machine: str = None, popen: Callable = subprocess.Popen):
+        self.adb = adb_operations
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.tools_dir = Path(data_dir) / "tools" / "scrcpy"
+        self.platform = platform or sys.platform
+        self.machine = machine or platform_module.machine()
+        self.popen = popen
+        self._sessions: Dict[str, subprocess.Popen] = {}
+        self._lock = threading.Lock()
+
+    def locate(self) -> Optional[str]:
+        """
+        scrcpy to run, in order: on PATH, bundled in a `scrcpy` folder next
+        to the exe (or this script), then a copy downloaded earlier
+        """
+        name = scrcpy_name(self.platform)
+        found = shutil.which("scrcpy")
+        if found:
+            return found
+        base_dir = os.path.dirname(sys.executable if getattr(sys, 'frozen', False) else os.path.abspath(__file__))
+        bundled = os.path.join(base_dir, "scrcpy", name)
+        if os.path.exists(bundled):
+            return bundled
+        # Newest download last: release folders are named after the version, and v3.10 is newer than v3.9
+        downloaded = sorted(glob.glob(str(self.tools_dir / "*" / name)),
+                            key=lambda path: (release_version(Path(path).parent.name), path))
+        return downloaded[-1] if downloaded else None
+
+    def download(self) -> Dict:
+        """Download and unpack the latest prebuilt scrcpy release for this system"""
+        try:
+            response = network.request("GET", SCRCPY_RELEASE_URL, timeout=RELEASE_TIMEOUT)
+            response.raise_for_status()
+            release = response.json()
+        except (network.OfflineModeError, network.requests.exceptions.RequestException, ValueError) as e:
+            return {"success": False, "message": f"Could not look up the scrcpy release: {network.describe_error(e)}"}
+
+        asset = pick_asset(release, self.platform, self.machine)
+        if asset is None:
+            return {"success": False,
+                    "message": f"scrcpy has no prebuilt release for {self.platform} {self.machine}; "
+                               "install it with your package manager (e.g. `apt install scrcpy`)"}
+
+        folder = self.tools_dir / re.sub(r'(\.zip|\.tar\.gz)$', '', asset["name"])
+        archive = self.tools_dir / asset["name"]
+        self.tools_dir.mkdir(parents=True, exist_ok=True)
+        try:
+            response = network.request("GET", asset["browser_download_url"], timeout=DOWNLOAD_TIMEOUT, stream=True)
+            response.raise_for_status()
+            digest = hashlib.sha256()
+            with open(archive, 'wb') as f:
+                for chunk in response.iter_content(chunk_size=1 << 16):
+                    digest.update(chunk)
+                    f.write(chunk)
+            # GitHub lists "sha256:<hex>" for assets uploaded since mid-2025
+            expected = (asset.get("digest") or "").partition("sha256:")[2]
+            if expected and expected.lower() != digest.hexdigest():
+                return {"success": False, "message": f"{asset['name']} failed its checksum check; try again"}
+            shutil.rmtree(folder, ignore_errors=True)
+            extract_archive(archive, self.tools_dir)
+        except (network.OfflineModeError, network.requests.exceptions.RequestException) as e:
+            return {"success": False, "message": f"Could not download scrcpy: {network.describe_error(e)}"}
+        except (OSError, ValueError, zipfile.BadZipFile, tarfile.TarError) as e:
+            return {"success": False, "message": f"Could not unpack {asset['name']}: {e}"}
+        finally:
+            archive.unlink(missing_ok=True)
+
+        path = self.locate()
+        if path is None:
+            return {"success": False, "message": f"{asset['name']} did not contain {scrcpy_name(self.platform)}"}
+        return {"success": True, "path": path, "version": release.get("tag_name"),
+                "message": f"Downloaded scrcpy {release.get('tag_name', '')}".strip()}
+
+    def launch(self, serial: str = None, download: bool = False) -> Dict:
+        """
+        Mirror `serial` (default: the connected device) in a scrcpy window.
+        Without scrcpy, `needsDownload` is returned unless `download` allows
+        fetching it first.
+        """
+        if serial is None:
+            try:
+                serial = self.adb.get_serial()
+            except ADBError as e:
+                return {"success": False, "message": str(e)}
+
+        with self._lock:
+            running = self._sessions.get(serial)
+            if running is not None and running.poll() is None:
+                return {"success": True, "serial": serial, "alreadyRunning": True,
+                        "message": f"The screen of {serial} is already being mirrored"}
+
+        path = self.locate()
+        downloaded = None
+        if path is None:
+            if not download:
+                return {"success": False, "needsDownload": True, "serial": serial,
+                        "message": "scrcpy is not installed; download it to mirror the screen"}
+            downloaded = self.download()
+            if not downloaded["success"]:
+                return {**downloaded, "serial": serial}
+            path = downloaded["path"]
+
+        # scrcpy starts its own adb otherwise, which can differ from ours and restart the server
+        env = {**os.environ, "ADB": self.adb.adb_path}
+        try:
+            process = self.popen([path, "--serial", serial, "--window-title", f"DebloatAI - {serial}"],
+                                 env=env, stdin=subprocess.DEVNULL, stdout=subprocess.PIPE,
+                                 stderr=subprocess.STDOUT, text=True, errors="replace")
+        except OSError as e:
+            return {"success": False, "serial": serial, "message": f"Could not start scrcpy: {e}"}
+
+        deadline = time.monotonic() + STARTUP_WAIT
+        while process.poll() is None and time.monotonic() < deadline:
+            time.sleep(0.1)
+        if process.poll() is not None:
+            output = (process.stdout.read() if process.stdout else "") or ""
+            tail = "\n".join(output.strip().splitlines()[-ERROR_TAIL_LINES:])
+            return {"success": False, "serial": serial,
+                    "message": f"scrcpy exited right away (code {process.returncode})" + (f": {tail}" if tail else "")}
+
+        # Nobody reads the output once running; drop it so a full pipe can't block scrcpy
+        if process.stdout:
+            threading.Thread(target=drain, args=(process.stdout,), name="scrcpy-output", daemon=True).start()
+        with self._lock:
+            self._sessions[serial] = process
+        message = f"Mirroring {serial}" + (f" ({downloaded['message']})" if downloaded else "")
+        return {"success": True, "serial": serial, "path": path, "message": message}
+
+    def status(self) -> Dict:
+        """Where scrcpy was found and the devices being mirrored"""
+        with self._lock:
+            self._sessions = {s: p for s, p in self._sessions.items() if p.poll() is None}
+            running = sorted(self._sessions)
+        path = self.locate()
+        return {"installed": path is not None, "path": path, "running": running,
+                "canDownload": asset_pattern(self.platform, self.machine) is not None}
+
+    def stop(self, serial: str = None) -> Dict:
+        """Close the mirror of `serial`, or all of them"""
+        with self._lock:
+            serials = [serial] if serial is not None else list(self._sessions)
+            processes = [(s, self._sessions.pop(s)) for s in serials if s in self._sessions]
+        for _, process in processes:
+            if process.poll() is None:
+                process.terminate()
+                try:
+                    process.wait(timeout=5)
+                except subprocess.TimeoutExpired:
+                    process.kill()
+        return {"success": True, "stopped": [s for s, _ in processes],
+                "message": f"Stopped {len(processes)} mirroring session(s)"}
+
+    def stop_all(self):
+        self.stop()
@@ -0,0 +1,125 @@
+"""
+Selection Rules Module
+Checks a whole removal selection against combination rules: packages that
+are each fine to remove can together leave the device without any keyboard,
+home screen or WebView, which locks the user out or breaks most apps
+"""
+import re
+from typing import Dict, List, Set
+from adb_operations import ADBOperations, ADBError
+
+
+# id -> rule. The device is queried for every package providing the role;
+# a selection covering all of them violates the rule. `feature` is the
+# capability the query needs, if any.
+SELECTION_RULES = {
+    "keyboard": {
+        "severity": "critical",
+        "query": "ime list -a -s",
+        "feature": None,
+        "message": "No keyboard would be left: the lock screen PIN or password could not be typed",
+    },
+    "launcher": {
+        "severity": "critical",
+        "query": "cmd package query-activities --brief -a android.intent.action.MAIN -c android.intent.category.HOME",
+        "feature": "cmd_package",
+        "message": "No home screen app would be left: the device can get stuck after boot",
+    },
+    "webview": {
+        "severity": "critical",
+        "query": "dumpsys webviewupdate",
+        "feature": None,
+        "message": "No WebView provider would be left: many apps and the setup screens crash",
+    },
+    "dialer": {
+        "severity": "warning",
+        "query": "cmd package query-activities --brief -a android.intent.action.DIAL",
+        "feature": "cmd_package",
+        "message": "No phone app would be left to place calls, including emergency calls",
+    },
+}
+
+SEVERITIES = ("warning", "critical")
+
+# Reported for theme overlays in a removal selection; they are switched off, not removed (overlays.py)
+OVERLAY_MESSAGE = "Theme overlays are not apps: turn them off under Theme overlays instead of removing them"
+
+# Reported for APEX modules and the apps inside them, which cannot be uninstalled (apex.py)
+APEX_MESSAGE = "System modules (APEX) are updated by Google and cannot be removed"
+
+# Providers that never serve the role after boot (Settings only shows its
+# fallback home screen while the device starts)
+IGNORED_PROVIDERS = {"launcher": {"com.android.settings"}}
+
+COMPONENT_PATTERN = re.compile(r'^\s*([A-Za-z][\w.]*)/[\w.$]+\s*$', re.MULTILINE)
+WEBVIEW_PATTERN = re.compile(r'Valid package ([\w.]+)')
+
+
+def parse_providers(rule_id: str, output: str) -> Set[str]:
+    """Packages providing a rule's role, from the output of its query"""
+    if rule_id == "webview":
+        providers = set(WEBVIEW_PATTERN.findall(output))
+    else:
+        providers = set(COMPONENT_PATTERN.findall(output))
+    return providers - IGNORED_PROVIDERS.get(rule_id, set())
+
+
+def find_violations(selection: Set[str], providers: Dict[str, Set[str]]) -> List[Dict]:
+    """Rules whose every provider is in the selection, most severe first"""
+    violations = []
+    for rule_id, found in providers.items():
+        if found and found <= selection:
+            rule = SELECTION_RULES[rule_id]
+            violations.append({
+                "rule": rule_id,
+                "severity": rule["severity"],
+                "message": rule["message"],
+                "packages": sorted(found),
+            })
+    violations.sort(key=lambda v: -SEVERITIES.index(v["severity"]))
+    return violations
+
+
+class SelectionValidator:
+    """Validate removal selections against SELECTION_RULES on the connected device"""
+
+    def __init__(self, adb_operations: ADBOperations):
+        self.adb = adb_operations
+
+    def get_providers(self) -> Dict[str, Set[str]]:
+        """rule id -> packages providing its role; rules the device cannot answer are left out"""
+        providers = {}
+        for rule_id, rule in SELECTION_RULES.items():
+            if rule["feature"] and not self.adb.supports(rule["feature"]):
+                continue
+            try:
+                providers[rule_id] = parse_providers(rule_id, self.adb.shell(rule["query"], timeout=20))
+            except ADBError:
+                continue
+        return providers
+
+    def validate_selection(self, package_names: List[str]) -> Dict:
+        """
+        Check a selection as a whole before removing it.
+
+        `valid` is False when a critical rule is violated; `unchecked` lists
+        rules the device could not be queried for.
+        """
+        try:
+            providers = self.get_providers()
+        except ADBError as e:
+            return {"valid": True, "violations": [], "unchecked": list(SELECTION_RULES), "message": str(e)}
+        violations = find_violations(set(package_names), providers)
+        selected_overlays = sorted(set(package_names) & set(self.adb.get_overlay_targets()))
+        if selected_overlays:
+            violations.insert(0, {"rule": "overlay", "severity": "critical", "message": OVERLAY_MESSAGE,
+                                  "packages": selected_overlays})
+        selected_apex = sorted(set(package_names) & set(self.adb.get_apex_packages()))
+        if selected_apex:
+            violations.insert(0, {"rule": "apex", "severity": "critical", "message": APEX_MESSAGE,
+                                  "packages": selected_apex})
+        return {
+            "valid": not any(v["severity"] == "critical" for v in violations),
+            "violations": violations,
+            "unchecked": [rule_id for rule_id in SELECTION_RULES if rule_id not in providers],
+        }
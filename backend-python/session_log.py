@@ -0,0 +1,141 @@
+"""
+Session Log Module
+Keeps a chronological record of the commands and notable events of the
+current backend session and renders it as a plain-text report for support
+"""
+import threading
+import time
+from collections import deque
+from datetime import datetime
+from typing import Any, Dict, List, Optional
+
+
+# Oldest entries are dropped beyond this many
+MAX_SESSION_ENTRIES = 1000
+
+# Events too frequent to be worth a line in the report
+QUIET_EVENTS = {"health_update", "optimization_progress", "backup_progress", "package_chunk"}
+
+# Commands too frequent to be worth a line in the report
+QUIET_COMMANDS = {"ack_package_chunk"}
+
+# Commands that only read state; repeated successful calls collapse into one line
+READ_ONLY_PREFIXES = ("get_", "list_", "search_", "preview_")
+
+# Argument and message strings are cut to this length in the report
+MAX_VALUE_LENGTH = 160
+
+# Arguments left out of the report because they hold the user's private chat text
+REDACTED_ARGS = {"message", "history"}
+
+
+def summarize_value(value: Any) -> str:
+    """Short single-line rendering of an argument or result value"""
+    if isinstance(value, (list, tuple)):
+        if len(value) <= 3:
+            return "[" + ", ".join(summarize_value(v) for v in value) + "]"
+        return f"[{len(value)} items]"
+    if isinstance(value, dict):
+        return f"{{{len(value)} fields}}"
+    text = " ".join(str(value).split())
+    return text if len(text) <= MAX_VALUE_LENGTH else text[:MAX_VALUE_LENGTH - 1] + "…"
+
+
+def summarize_result(result: Any) -> str:
+    """OK/FAILED plus message of a command result"""
+    if isinstance(result, dict):
+        failed = result.get("success") is False or "error" in result
+        message = result.get("message") or result.get("error") or ""
+        status = "FAILED" if failed else "OK"
+        return f"{status}: {summarize_value(message)}" if message else status
+    if isinstance(result, list):
+        return f"OK: {len(result)} item(s)"
+    return "OK"
+
+
+class SessionLog:
+    """Record commands and events of this session"""
+
+    def __init__(self):
+        self.started = time.time()
+        self._entries = deque(maxlen=MAX_SESSION_ENTRIES)
+        self._lock = threading.Lock()
+
+    def record_command(self, command: str, args: Optional[Dict], result: Any = None, error: str = None):
+        """Record a handled command with its result or the exception it raised"""
+        if command in QUIET_COMMANDS:
+            return
+        entry = {
+            "time": time.time(),
+            "type": "command",
+            "command": command,
+            "args": args or {},
+            "outcome": f"ERROR: {summarize_value(error)}" if error is not None else summarize_result(result),
+            "count": 1,
+        }
+        with self._lock:
+            last = self._entries[-1] if self._entries else None
+            if (last and last["type"] == "command" and last["command"] == command
+                    and command.startswith(READ_ONLY_PREFIXES)
+                    and last["args"] == entry["args"] and last["outcome"] == entry["outcome"]):
+                last["count"] += 1
+                last["lastTime"] = entry["time"]
+                return
+            self._entries.append(entry)
+
+    def record_event(self, kind: str, payload: Any, job_id: Optional[str] = None):
+        """Record a backend event, skipping high-frequency progress kinds"""
+        if kind in QUIET_EVENTS:
+            return
+        has_outcome = isinstance(payload, dict) and ("success" in payload or "message" in payload)
+        with self._lock:
+            self._entries.append({
+                "time": time.time(),
+                "type": "event",
+                "kind": kind,
+                "jobId": job_id,
+                "outcome": summarize_result(payload) if has_outcome else None,
+            })
+
+    def entries(self) -> List[Dict]:
+        with self._lock:
+            return [dict(entry) for entry in self._entries]
+
+    def render(self, device_info: Optional[Dict] = None, compatibility: Optional[Dict] = None) -> str:
+        """Human-readable report of the session, oldest first"""
+        def clock(timestamp):
+            return datetime.fromtimestamp(timestamp).strftime("%H:%M:%S")
+
+        lines = [
+            "Debloat AI session report",
+            f"Generated: {datetime.now().strftime('%Y-%m-%d %H:%M:%S')}",
+            f"Session started: {datetime.fromtimestamp(self.started).strftime('%Y-%m-%d %H:%M:%S')}",
+        ]
+        if device_info:
+            lines.append(f"Device: {device_info.get('manufacturer', '')} {device_info.get('model', '')} "
+                         f"(serial {device_info.get('serial')}), Android {device_info.get('androidVersion')}")
+        else:
+            lines.append("Device: not connected")
+        if compatibility:
+            lines.append(f"adb: {compatibility.get('adbVersion')}, "
+                         f"platform-tools {compatibility.get('platformToolsVersion')}, "
+                         f"API level {compatibility.get('sdkLevel')}")
+        lines.append("")
+
+        entries = self.entries()
+        if len(entries) == MAX_SESSION_ENTRIES:
+            lines.append(f"(only the last {MAX_SESSION_ENTRIES} entries are kept)")
+        for entry in entries:
+            if entry["type"] == "event":
+                job = f" [job {entry['jobId']}]" if entry["jobId"] else ""
+                lines.append(f"{clock(entry['time'])}  event {entry['kind']}{job}")
+            else:
+                args = " ".join(f"{key}={'(omitted)' if key in REDACTED_ARGS else summarize_value(value)}"
+                                for key, value in entry["args"].items())
+                repeat = f" (x{entry['count']}, last at {clock(entry['lastTime'])})" if entry["count"] > 1 else ""
+                lines.append(f"{clock(entry['time'])}  {entry['command']} {args}".rstrip() + repeat)
+            if entry["outcome"]:
+                lines.append(f"          -> {entry['outcome']}")
+        if not entries:
+            lines.append("No commands were run in this session.")
+        return "\n".join(lines) + "\n"
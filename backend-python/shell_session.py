@@ -0,0 +1,116 @@
+"""
+Shell Session Module
+Keeps one long-lived `adb shell` per device and runs quick commands over
+it, reading each command's output up to a sentinel line. This saves the
+100-300 ms of starting an adb process per metric; commands that need a
+clean environment or run long still use one-shot invocations
+"""
+import queue
+import re
+import subprocess
+import threading
+import time
+import uuid
+from typing import List, Optional, Tuple
+
+
+# Longest timeout a command may have and still go over the session; slower
+# commands would hold the session and block the quick ones behind them
+MAX_SESSION_TIMEOUT = 20
+
+# Commands that rely on a fresh shell (working directory, environment,
+# privileges) or take the device down with the session
+ONE_SHOT_PATTERN = re.compile(
+    r'(^|[;&|(]\s*)(exit|cd|export|unset|exec|set|ulimit|umask|alias|su|reboot)\b|am restart|<<'
+)
+
+
+class ShellSessionError(Exception):
+    """The session could not run the command; it is closed and one-shot adb should be used"""
+    pass
+
+
+class ShellSessionTimeout(ShellSessionError):
+    """The command did not finish in time; the session was killed"""
+    pass
+
+
+def can_use_session(command: str, timeout: float) -> bool:
+    """Whether `command` may run over a shared session instead of its own adb process"""
+    return timeout <= MAX_SESSION_TIMEOUT and not ONE_SHOT_PATTERN.search(command)
+
+
+class ShellSession:
+    """One persistent shell process, running one command at a time"""
+
+    def __init__(self, argv: List[str]):
+        self.process = subprocess.Popen(
+            argv, stdin=subprocess.PIPE, stdout=subprocess.PIPE, stderr=subprocess.DEVNULL
+        )
+        self._lines: "queue.Queue[Optional[str]]" = queue.Queue()
+        self._lock = threading.Lock()
+        self._reader = threading.Thread(target=self._read, daemon=True)
+        self._reader.start()
+
+    def _read(self):
+        for raw in iter(self.process.stdout.readline, b''):
+            self._lines.put(raw.decode('utf-8', errors='replace'))
+        self._lines.put(None)  # EOF: the shell exited or the device went away
+
+    @property
+    def alive(self) -> bool:
+        return self.process.poll() is None
+
+    def run(self, command: str, timeout: float) -> Optional[Tuple[str, int]]:
+        """
+        (output, exit status) of `command`, or None when another command is
+        using the session (the caller should not wait for it). stderr is
+        discarded, like one-shot output on success.
+        """
+        if not self._lock.acquire(blocking=False):
+            return None
+        try:
+            if not self.alive:
+                raise ShellSessionError("Shell session has ended")
+            marker = f"__DEBLOAT_END_{uuid.uuid4().hex}__"
+            # The newline before ")" ends a trailing comment in `command`
+            script = f"( {command}\n) 2>/dev/null </dev/null; echo \"{marker} $?\"\n"
+            try:
+                self.process.stdin.write(script.encode('utf-8'))
+                self.process.stdin.flush()
+            except OSError as e:
+                self.close()
+                raise ShellSessionError(f"Shell session closed: {e}")
+            return self._collect(marker, time.monotonic() + timeout)
+        finally:
+            self._lock.release()
+
+    def _collect(self, marker: str, deadline: float) -> Tuple[str, int]:
+        output = []
+        while True:
+            remaining = deadline - time.monotonic()
+            try:
+                line = self._lines.get(timeout=max(0.0, remaining))
+            except queue.Empty:
+                # The command may still be running; the session cannot be reused
+                self.close()
+                raise ShellSessionTimeout("Command timed out")
+            if line is None:
+                self.close()
+                raise ShellSessionError("Shell session ended during the command")
+            before, found, status = line.partition(marker)
+            if found:
+                output.append(before)  # output without a trailing newline
+                try:
+                    return ''.join(output), int(status.strip())
+                except ValueError:
+                    return ''.join(output), 1
+            output.append(line)
+
+    def close(self):
+        if self.alive:
+            self.process.kill()
+        try:
+            self.process.wait(timeout=5)
+        except subprocess.TimeoutExpired:
+            pass
@@ -0,0 +1,50 @@
+"""
+Shutdown Module
+Runs the backend's exit steps once, in the order they were registered, when
+Electron closes stdin or the process is asked to terminate: cancel running
+jobs, stop the schedulers, write the session log, put back temporary device
+settings and close the adb child processes. A failing step is reported and
+does not keep the later ones from running
+"""
+import sys
+import threading
+import time
+from typing import Callable, Dict, List
+
+
+class ShutdownManager:
+    """Ordered exit steps, run at most once"""
+
+    def __init__(self):
+        self._hooks: List[tuple] = []
+        self._lock = threading.Lock()
+        self._done = False
+
+    def register(self, name: str, hook: Callable[[], object]):
+        """Add a step; steps run in registration order"""
+        self._hooks.append((name, hook))
+
+    @property
+    def done(self) -> bool:
+        return self._done
+
+    def run(self, reason: str = "exit") -> List[Dict]:
+        """Run every step, returning {name, success, seconds, error?} per step; later calls do nothing"""
+        with self._lock:
+            if self._done:
+                return []
+            self._done = True
+
+        print(f"[Shutdown] {reason}: running {len(self._hooks)} step(s)", file=sys.stderr)
+        results = []
+        for name, hook in self._hooks:
+            started = time.time()
+            entry = {"name": name, "success": True}
+            try:
+                hook()
+            except Exception as e:
+                entry.update(success=False, error=str(e))
+                print(f"[Shutdown] {name} failed: {e}", file=sys.stderr)
+            entry["seconds"] = round(time.time() - started, 3)
+            results.append(entry)
+        return results
@@ -0,0 +1,145 @@
+"""
+Signatures Module
+Finds the packages signed with the platform key (the key the firmware
+itself is signed with) and the signature-level permissions each package
+holds, from `dumpsys package`. Platform-signed packages run with system
+privileges; one that no safety table knows about is rated Dangerous
+rather than Safe
+"""
+import re
+from typing import Dict, List, Optional, Set
+
+
+# One call for the package and permission sections; both are needed to rate a package
+PERMISSIONS_MARKER = "@@permissions"
+SIGNATURE_SCRIPT = f"dumpsys package packages; echo {PERMISSIONS_MARKER}; dumpsys package permissions"
+
+# The framework package, whose signer is the platform key
+PLATFORM_PACKAGE = "android"
+
+PACKAGE_HEADER_PATTERN = re.compile(r'^\s*Package \[([\w.]+)\]')
+PERMISSION_HEADER_PATTERN = re.compile(r'^\s*Permission \[([\w.]+)\]')
+# Android 9+: "signatures=PackageSignatures{7d1e6a3 version:3, signatures:[2a1f1b5d], past signatures:[]}"
+SIGNATURES_PATTERN = re.compile(r'signatures=PackageSignatures\{.*?signatures:\[([0-9a-f, ]*)\]')
+SHARED_USER_PATTERN = re.compile(r'sharedUser=SharedUserSetting\{\S+ ([\w.]+)/(\d+)\}')
+GRANTED_PERMISSION_PATTERN = re.compile(r'^\s*([\w.]+): granted=true')
+# "uid=1000 gids=[] type=0 prot=signature|privileged"
+PROTECTION_PATTERN = re.compile(r'\bprot=(\w+)')
+
+# Shared user ids only platform-signed packages can join; used where the
+# signature hashes are not printed (Android 8 and older)
+PLATFORM_SHARED_USERS = {
+    "android.uid.system",
+    "android.uid.phone",
+    "android.uid.bluetooth",
+    "android.uid.nfc",
+    "android.uid.se",
+    "android.uid.networkstack",
+    "android.uid.shell",
+}
+
+# Base protection levels granted only to apps signed with the declaring package's key
+SIGNATURE_PROTECTIONS = {"signature", "signatureOrSystem"}
+
+
+def parse_packages_section(output: str) -> Dict[str, Dict]:
+    """
+    package -> {"signatures", "sharedUser", "privileged", "permissions"}
+    from `dumpsys package packages`; permissions are the granted install
+    permissions, where signature permissions are listed
+    """
+    packages: Dict[str, Dict] = {}
+    current: Optional[Dict] = None
+    # Indent of the "install permissions:" line while inside that list, else None
+    permissions_indent: Optional[int] = None
+    for line in output.split('\n'):
+        header = PACKAGE_HEADER_PATTERN.match(line)
+        if header:
+            current = packages.setdefault(header.group(1), {
+                "signatures": [], "sharedUser": None, "privileged": False, "permissions": [],
+            })
+            permissions_indent = None
+            continue
+        if current is None:
+            continue
+        stripped = line.strip()
+        indent = len(line) - len(line.lstrip())
+        if permissions_indent is not None:
+            if stripped and indent > permissions_indent:
+                granted = GRANTED_PERMISSION_PATTERN.match(line)
+                if granted:
+                    current["permissions"].append(granted.group(1))
+                continue
+            permissions_indent = None
+        match = SIGNATURES_PATTERN.search(line)
+        if match:
+            current["signatures"] = [s.strip() for s in match.group(1).split(',') if s.strip()]
+        elif stripped.startswith("sharedUser="):
+            shared = SHARED_USER_PATTERN.search(stripped)
+            current["sharedUser"] = shared.group(1) if shared else None
+        elif stripped.startswith("privateFlags="):
+            current["privileged"] = "PRIVILEGED" in stripped
+        elif stripped == "install permissions:":
+            permissions_indent = indent
+    return packages
+
+
+def parse_permission_protections(output: str) -> Dict[str, str]:
+    """permission -> base protection level ("signature", "normal"...) from `dumpsys package permissions`"""
+    protections: Dict[str, str] = {}
+    current: Optional[str] = None
+    for line in output.split('\n'):
+        header = PERMISSION_HEADER_PATTERN.match(line)
+        if header:
+            current = header.group(1)
+            continue
+        protection = PROTECTION_PATTERN.search(line) if current else None
+        if protection:
+            protections[current] = protection.group(1)
+            current = None
+    return protections
+
+
+def signature_info(output: str) -> Dict[str, Dict]:
+    """
+    package -> {"platformSigned", "privileged", "sharedUserId",
+    "signaturePermissions"} from the output of SIGNATURE_SCRIPT
+    """
+    packages_output, _, permissions_output = output.partition(PERMISSIONS_MARKER)
+    packages = parse_packages_section(packages_output)
+    protections = parse_permission_protections(permissions_output)
+    platform_signatures = set(packages.get(PLATFORM_PACKAGE, {}).get("signatures", []))
+
+    info = {}
+    for name, package in packages.items():
+        platform_signed = (
+            bool(platform_signatures) and bool(set(package["signatures"]) & platform_signatures)
+        ) or package["sharedUser"] in PLATFORM_SHARED_USERS
+        info[name] = {
+            "platformSigned": platform_signed,
+            "privileged": package["privileged"],
+            "sharedUserId": package["sharedUser"],
+            "signaturePermissions": sorted(
+                p for p in package["permissions"] if protections.get(p) in SIGNATURE_PROTECTIONS
+            ),
+        }
+    return info
+
+
+def platform_signed_packages(info: Dict[str, Dict]) -> Set[str]:
+    """Names of the platform-signed packages in signature_info output"""
+    return {name for name, entry in info.items() if entry["platformSigned"]}
+
+
+def describe_signature(entry: Dict) -> List[str]:
+    """Plain-language notes on a package's signature_info entry, for the package details"""
+    notes = []
+    if entry["platformSigned"]:
+        notes.append("Signed with the platform key: runs with system privileges")
+    if entry["privileged"]:
+        notes.append("Privileged system app (priv-app)")
+    if entry["sharedUserId"]:
+        notes.append(f"Shares its user id with other packages ({entry['sharedUserId']})")
+    if entry["signaturePermissions"]:
+        notes.append(f"Holds {len(entry['signaturePermissions'])} signature-level permission(s)")
+    return notes
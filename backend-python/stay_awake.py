@@ -0,0 +1,155 @@
+"""
+Stay Awake Module
+Keeps the device awake while it is plugged into USB for the length of long
+jobs (batch removals, optimizations, full backups and restores): a device
+that sleeps mid-batch can drop the adb connection. The original
+`stay_on_while_plugged_in` value is saved to disk before it is changed and
+put back when the last job ends, or when the device next connects if the
+backend was closed mid-job
+"""
+import json
+import threading
+import uuid
+from contextlib import contextmanager, nullcontext
+from pathlib import Path
+from typing import Callable, Dict, Iterator, List, Optional
+from adb_operations import ADBOperations, ADBError
+
+
+STAY_ON_SETTING = "stay_on_while_plugged_in"
+
+# BatteryManager.BATTERY_PLUGGED_USB: the bit of the setting for USB power
+USB_BIT = 2
+
+
+def parse_stay_on(output: str) -> Optional[int]:
+    """Value of `settings get global stay_on_while_plugged_in`; "null" (never set) is 0"""
+    value = output.strip()
+    if value == "null":
+        return 0
+    try:
+        return int(value)
+    except ValueError:
+        return None
+
+
+class StayAwake:
+    """Reference-counted stay-awake holds per device"""
+
+    def __init__(self, adb_operations: ADBOperations, data_dir: str = None,
+                 enabled: Callable[[], bool] = lambda: True):
+        self.adb = adb_operations
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.path = Path(data_dir) / "stay_awake.json"
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        # Reads the devices.stayAwakeDuringJobs setting
+        self.enabled = enabled
+        self._lock = threading.Lock()
+        # hold id -> {"serial", "reason"}
+        self._holds: Dict[str, Dict] = {}
+        # serial -> value to put back; persisted until it is restored
+        self._originals: Dict[str, int] = self._load()
+
+    def _load(self) -> Dict[str, int]:
+        try:
+            with open(self.path, 'r', encoding='utf-8') as f:
+                return {serial: int(value) for serial, value in json.load(f).items()}
+        except (OSError, ValueError, AttributeError):
+            return {}
+
+    def _save(self):
+        with open(self.path, 'w', encoding='utf-8') as f:
+            json.dump(self._originals, f, indent=2)
+
+    def _write_setting(self, value: int):
+        if self.adb.supports("settings_command"):
+            self.adb.shell(f"settings put global {STAY_ON_SETTING} {value}", timeout=10)
+        else:
+            # `svc power stayon` only knows usb/ac/wireless/true/false
+            self.adb.shell(f"svc power stayon {'usb' if value & USB_BIT else 'false'}", timeout=10)
+
+    def acquire(self, reason: str) -> Optional[str]:
+        """
+        Keep the connected device awake on USB until the returned hold is
+        released; None when the setting is off or the device could not be set
+        """
+        if not self.enabled():
+            return None
+        with self._lock:
+            try:
+                serial = self.adb.get_serial()
+                if serial not in self._originals and not any(h["serial"] == serial for h in self._holds.values()):
+                    if self.adb.supports("settings_command"):
+                        original = parse_stay_on(self.adb.shell(f"settings get global {STAY_ON_SETTING}", timeout=10))
+                    else:
+                        original = 0  # Unreadable; stock devices ship with it off
+                    if original is None:
+                        return None
+                    if not original & USB_BIT:
+                        # Saved first, so a crash before the restore still leaves a record
+                        self._originals[serial] = original
+                        self._save()
+                        self._write_setting(original | USB_BIT)
+            except ADBError:
+                return None
+            hold_id = uuid.uuid4().hex[:12]
+            self._holds[hold_id] = {"serial": serial, "reason": reason}
+            return hold_id
+
+    def release(self, hold_id: Optional[str]) -> Dict:
+        """End a hold; the last hold on a device puts its original setting back"""
+        with self._lock:
+            hold = self._holds.pop(hold_id, None) if hold_id else None
+            if hold is None:
+                return {"success": False, "message": "No such stay-awake hold"}
+            restored = self._restore(hold["serial"])
+        return {"success": True, "restored": restored, "message": "Stay-awake released"}
+
+    def _restore(self, serial: str) -> bool:
+        """Put back the saved value when no job holds the device; it must be the connected one"""
+        if serial not in self._originals or any(h["serial"] == serial for h in self._holds.values()):
+            return False
+        try:
+            if self.adb.get_serial() != serial:
+                return False  # Another device is connected; restored when this one returns
+            self._write_setting(self._originals[serial])
+        except ADBError:
+            return False
+        del self._originals[serial]
+        self._save()
+        return True
+
+    def restore_pending(self, serial: str) -> bool:
+        """Restore a setting left changed by a job that never ended (backend closed, device unplugged)"""
+        with self._lock:
+            return self._restore(serial)
+
+    def release_all(self):
+        """End every hold, e.g. on shutdown"""
+        with self._lock:
+            serials = {hold["serial"] for hold in self._holds.values()}
+            self._holds.clear()
+            for serial in serials:
+                self._restore(serial)
+
+    @contextmanager
+    def hold(self, reason: str) -> Iterator[Optional[str]]:
+        """Stay awake for the `with` block, releasing even when it raises or is cancelled"""
+        hold_id = self.acquire(reason)
+        try:
+            yield hold_id
+        finally:
+            if hold_id:
+                self.release(hold_id)
+
+    def get_status(self) -> Dict:
+        """Running holds and devices whose setting still has to be restored"""
+        with self._lock:
+            holds: List[Dict] = [{"holdId": hold_id, **hold} for hold_id, hold in self._holds.items()]
+            return {"enabled": self.enabled(), "holds": holds, "pendingRestore": sorted(self._originals)}
+
+
+def stay_awake_during(stay_awake: Optional[StayAwake], reason: str):
+    """`stay_awake.hold(reason)`, or a no-op for modules used without one"""
+    return stay_awake.hold(reason) if stay_awake is not None else nullcontext()
@@ -0,0 +1,97 @@
+"""
+Storage Guard Module
+Checks for room before a backup starts, so a full disk does not leave a
+half-written zip behind. The size is estimated from `dumpsys diskstats`:
+APK exports need the app sizes of third-party packages (system apps are
+restored with install-existing and not exported), data backups their data
+sizes. Backups that would not fit on this computer are refused; tight ones
+and data backups from a nearly full device get a warning
+"""
+import shutil
+from pathlib import Path
+from typing import Dict, List, Optional
+from adb_operations import ADBOperations, ADBError
+import formatting
+from system_health import build_storage_info, c_locale, parse_df
+
+
+MB = 1024 * 1024
+# Kept free on this computer on top of the estimate; a running export stops below it
+LOCAL_MARGIN_BYTES = 200 * MB
+# Free space under this multiple of the estimate gets a warning (diskstats sizes can be hours old)
+TIGHT_RATIO = 1.5
+# Backup agents write their data on the device before it is streamed out
+DEVICE_MIN_FREE_BYTES = 500 * MB
+
+
+def estimate_backup_bytes(sizes: Dict[str, Dict[str, int]], packages: List[str], apk_packages: List[str],
+                          include_data: bool) -> int:
+    """Bytes a backup writes: app sizes of `apk_packages` plus, with include_data, data sizes of all `packages`"""
+    total = sum(sizes.get(package, {}).get("appBytes", 0) for package in apk_packages)
+    if include_data:
+        total += sum(sizes.get(package, {}).get("dataBytes", 0) for package in packages)
+    return total
+
+
+def check_space(required: int, available: int, device_available: Optional[int] = None) -> Dict:
+    """
+    Compare an estimate with the free space. `success` is False when the
+    backup would not fit with LOCAL_MARGIN_BYTES to spare; `warnings` lists
+    tight local space and a device below DEVICE_MIN_FREE_BYTES (None when
+    the device was not checked)
+    """
+    fits = available - LOCAL_MARGIN_BYTES >= required
+    warnings = []
+    if fits and available < required * TIGHT_RATIO:
+        warnings.append(f"Free space is tight: the backup needs about {formatting.format_size(required)} "
+                        f"and {formatting.format_size(available)} is free on this computer")
+    if device_available is not None and device_available < DEVICE_MIN_FREE_BYTES:
+        warnings.append(f"The device has only {formatting.format_size(device_available)} free; "
+                        "apps may fail to write their backup data")
+
+    result = {
+        "success": fits,
+        "requiredBytes": required,
+        "availableBytes": available,
+        "deviceAvailableBytes": device_available,
+        "formatted": formatting.formatted_fields({
+            "requiredBytes": required, "availableBytes": available, "deviceAvailableBytes": device_available,
+        }),
+        "warnings": warnings,
+    }
+    if fits:
+        result["message"] = warnings[0] if warnings else "Enough free space for the backup"
+    else:
+        result["insufficientSpace"] = True
+        result["message"] = (f"Not enough free space for the backup: it needs about "
+                             f"{formatting.format_size(required + LOCAL_MARGIN_BYTES)} and "
+                             f"{formatting.format_size(available)} is free on this computer")
+    return result
+
+
+class StorageGuard:
+    """Free space checks for backups of the connected device"""
+
+    def __init__(self, adb_operations: ADBOperations):
+        self.adb = adb_operations
+
+    @staticmethod
+    def local_free(directory: Path) -> int:
+        return shutil.disk_usage(directory).free
+
+    def device_free(self) -> Optional[int]:
+        """Free bytes on the device's /data, None when unreadable"""
+        try:
+            storage = build_storage_info(parse_df(self.adb.shell(c_locale("df -k /data"), timeout=15)))
+        except ADBError:
+            return None
+        return storage["internal"]["availableKb"] * 1024 if storage else None
+
+    def check_backup(self, directory: Path, packages: List[str], apk_packages: List[str], include_data: bool) -> Dict:
+        """Check a backup of `packages` into `directory`, exporting the APKs of `apk_packages`, before it starts"""
+        try:
+            sizes = self.adb.get_package_sizes()
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        required = estimate_backup_bytes(sizes, packages, apk_packages, include_data)
+        return check_space(required, self.local_free(directory), self.device_free() if include_data else None)
@@ -0,0 +1,769 @@
+"""
+System Health Module
+Collects live device metrics (CPU, memory, storage, temperature, notifications,
+power) over ADB
+"""
+import re
+import sys
+import threading
+import time
+from typing import Dict, List, Optional
+from adb_operations import ADBOperations, ADBError, DeviceDisconnectedError
+from events import EmitFn, HEALTH_MONITOR_JOB
+from formatting import percent, formatted_fields
+from cache_policy import CacheStats, cache_report, is_stale
+
+
+# Seconds between the two /proc/stat reads when no previous sample is cached
+CPU_SAMPLE_INTERVAL = 0.5
+# Seconds a cached /proc/stat sample is used as the start of the next delta;
+# an older one would average the CPU over minutes instead of showing it now
+CPU_SAMPLE_MAX_AGE = 10
+# Seconds a device's screen-on time is reused; batterystats output is large and slow to produce
+SCREEN_ON_REFRESH = 300
+
+
+def parse_proc_stat(output: str) -> Optional[List[int]]:
+    """Parse the aggregate 'cpu' line of /proc/stat into a list of counters"""
+    for line in output.split('\n'):
+        parts = line.split()
+        if parts and parts[0] == 'cpu':
+            try:
+                return [int(value) for value in parts[1:]]
+            except ValueError:
+                return None
+    return None
+
+
+def compute_cpu_usage(previous: List[int], current: List[int]) -> Optional[float]:
+    """
+    Compute CPU usage percent between two /proc/stat samples.
+
+    Idle time is idle + iowait (columns 4 and 5); everything else counts as busy.
+    """
+    if not previous or not current or len(previous) < 4 or len(current) < 4:
+        return None
+
+    def idle_of(sample: List[int]) -> int:
+        return sample[3] + (sample[4] if len(sample) > 4 else 0)
+
+    total_delta = sum(current) - sum(previous)
+    idle_delta = idle_of(current) - idle_of(previous)
+
+    # Counters went backwards (device rebooted) or no time elapsed
+    if total_delta <= 0 or idle_delta < 0:
+        return None
+
+    usage = (total_delta - idle_delta) * 100.0 / total_delta
+    return round(max(0.0, min(100.0, usage)), 1)
+
+
+def parse_top_cpu(output: str) -> Optional[float]:
+    """Parse overall CPU usage from toybox `top` header (e.g. '800%cpu 12%user ... 700%idle')"""
+    for line in output.split('\n'):
+        if '%cpu' in line and '%idle' in line:
+            fields = {}
+            for token in line.split():
+                if '%' in token:
+                    value, _, name = token.partition('%')
+                    try:
+                        fields[name] = float(value)
+                    except ValueError:
+                        continue
+            total = fields.get('cpu')
+            idle = fields.get('idle')
+            if total and idle is not None:
+                return round((total - idle) * 100.0 / total, 1)
+    return None
+
+
+def parse_meminfo(output: str) -> Dict[str, int]:
+    """
+    Parse /proc/meminfo into a dict of field -> kB.
+
+    Lines without the kB unit are skipped: page counts such as
+    HugePages_Total, and a last line cut off in the middle of its number.
+    """
+    values = {}
+    for line in output.split('\n'):
+        key, sep, rest = line.partition(':')
+        if not sep:
+            continue
+        parts = rest.split()
+        if len(parts) == 2 and parts[1] == "kB" and parts[0].isdigit():
+            values[key.strip()] = int(parts[0])
+    return values
+
+
+def parse_zram_mm_stat(output: str) -> Optional[Dict[str, int]]:
+    """
+    Parse /sys/block/zram0/mm_stat.
+
+    Columns are byte counts: orig_data_size compr_data_size mem_used_total ...
+    """
+    parts = output.split()
+    if len(parts) < 3:
+        return None
+    try:
+        orig, compr, used = (int(value) for value in parts[:3])
+    except ValueError:
+        return None
+    return {
+        "origDataKb": orig // 1024,
+        "comprDataKb": compr // 1024,
+        "memUsedKb": used // 1024,
+    }
+
+
+def build_memory_info(meminfo: Dict[str, int], zram: Optional[Dict[str, int]] = None,
+                      zram_disk_size: Optional[int] = None) -> Dict:
+    """Combine /proc/meminfo and zram stats into the MemoryInfo shape"""
+    total = meminfo.get("MemTotal", 0)
+    available = meminfo.get("MemAvailable", meminfo.get("MemFree", 0))
+    used = max(0, total - available)
+    swap_total = meminfo.get("SwapTotal", 0)
+    swap_free = meminfo.get("SwapFree", 0)
+    swap_used = max(0, swap_total - swap_free)
+
+    zram_info = None
+    if zram is not None:
+        ratio = None
+        if zram["comprDataKb"] > 0:
+            ratio = round(zram["origDataKb"] / zram["comprDataKb"], 2)
+        zram_info = {
+            **zram,
+            "diskSizeKb": (zram_disk_size // 1024) if zram_disk_size else None,
+            "compressionRatio": ratio,
+        }
+
+    info = {
+        "totalKb": total,
+        "availableKb": available,
+        "usedKb": used,
+        "usagePercent": percent(used, total),
+        "swapTotalKb": swap_total,
+        "swapFreeKb": swap_free,
+        "swapUsedKb": swap_used,
+        "swapUsagePercent": percent(swap_used, swap_total),
+        "zram": zram_info,
+    }
+    info["formatted"] = formatted_fields(info)
+    return info
+
+
+SERVICE_RECORD_PATTERN = re.compile(r'\*\s*ServiceRecord\{[0-9a-f]+\s+u\d+\s+([\w.]+)/([\w.$]+)')
+
+
+def parse_running_services(output: str) -> Dict:
+    """
+    Parse `dumpsys activity services` into running app services grouped by package.
+
+    Each running service appears as `* ServiceRecord{<hash> u<user> <package>/<class>}`.
+    """
+    by_package: Dict[str, List[str]] = {}
+    for line in output.split('\n'):
+        match = SERVICE_RECORD_PATTERN.search(line)
+        if not match:
+            continue
+        package, service = match.groups()
+        if service.startswith('.'):
+            service = package + service
+        services = by_package.setdefault(package, [])
+        if service not in services:
+            services.append(service)
+
+    breakdown = [
+        {"packageName": package, "count": len(services), "services": services}
+        for package, services in by_package.items()
+    ]
+    breakdown.sort(key=lambda entry: (-entry["count"], entry["packageName"]))
+
+    return {
+        "count": sum(entry["count"] for entry in breakdown),
+        "packageCount": len(breakdown),
+        "byPackage": breakdown,
+    }
+
+
+# `dumpsys notification --noredact`: posted notifications as `NotificationRecord(0x...: pkg=<package> ...`,
+# and per-package usage stats since boot as an `AggregatedStats{` block with `key='<package>'` and `numPostedByApp=<n>`
+NOTIFICATION_RECORD_PATTERN = re.compile(r'NotificationRecord\(0x[0-9a-f]+: pkg=([\w.]+)')
+NOTIFICATION_STATS_KEY_PATTERN = re.compile(r"key='([\w.]+)'")
+NOTIFICATION_POSTED_PATTERN = re.compile(r'numPostedByApp=(\d+)')
+
+
+def parse_notification_counts(output: str) -> Dict:
+    """
+    Parse `dumpsys notification --noredact` into notifications per package,
+    noisiest first: `posted` since boot from the usage stats and `active`
+    currently in the shade. Firmware without the stats ranks by `active`.
+    """
+    posted: Dict[str, int] = {}
+    active: Dict[str, int] = {}
+    stats_package = None
+    for line in output.split('\n'):
+        record = NOTIFICATION_RECORD_PATTERN.search(line)
+        if record:
+            active[record.group(1)] = active.get(record.group(1), 0) + 1
+            continue
+        key = NOTIFICATION_STATS_KEY_PATTERN.search(line)
+        if key:
+            # Global aggregates use keys such as '__global'
+            stats_package = None if key.group(1).startswith('_') else key.group(1)
+        count = NOTIFICATION_POSTED_PATTERN.search(line)
+        if count and stats_package:
+            posted[stats_package] = posted.get(stats_package, 0) + int(count.group(1))
+            stats_package = None
+
+    breakdown = [
+        {"packageName": package, "posted": posted.get(package, 0), "active": active.get(package, 0)}
+        for package in set(posted) | set(active)
+    ]
+    breakdown.sort(key=lambda entry: (-entry["posted"], -entry["active"], entry["packageName"]))
+
+    return {
+        "posted": sum(entry["posted"] for entry in breakdown),
+        "active": sum(entry["active"] for entry in breakdown),
+        "packageCount": len(breakdown),
+        "byPackage": breakdown,
+    }
+
+
+def c_locale(command: str) -> str:
+    """
+    `command` run with LC_ALL=C, so busybox and toybox builds that honour
+    the locale print English headers and plain digits
+    """
+    return f"LC_ALL=C {command}"
+
+
+# df header aliases across toybox, busybox and legacy toolbox
+DF_COLUMN_ALIASES = {
+    "total": ("1k-blocks", "1024-blocks", "size", "blocks"),
+    "used": ("used",),
+    "available": ("available", "avail", "free"),
+    "mount": ("mounted",),
+}
+
+# Column positions of toybox `df -k` ("Filesystem 1K-blocks Used Available Use% Mounted on")
+TOYBOX_DF_COLUMNS = {"total": 1, "used": 2, "available": 3, "mount": 5}
+TOYBOX_DF_WIDTH = 6
+
+SIZE_SUFFIXES = {"K": 1, "M": 1024, "G": 1024 ** 2, "T": 1024 ** 3}
+DF_SIZE_PATTERN = re.compile(r'(\d+(?:\.\d+)?)([KMGTkmgt]?)')
+
+
+def _parse_df_size_kb(value: str) -> Optional[int]:
+    """Parse a df size cell in kB; legacy toolbox prints human sizes like '5.9G'"""
+    match = DF_SIZE_PATTERN.fullmatch(value.strip())
+    if not match:
+        return None  # "-", "5,9G" and other cells that are not plain sizes
+    number, suffix = match.groups()
+    if suffix:
+        return int(float(number) * SIZE_SUFFIXES[suffix.upper()])
+    return int(number) if number.isdigit() else None
+
+
+def _is_toybox_df_row(parts: List[str]) -> bool:
+    """Whether a df row has the toybox shape: name, three kB counts adding up, a percentage, a path"""
+    if len(parts) != TOYBOX_DF_WIDTH or not all(cell.isdigit() for cell in parts[1:4]):
+        return False
+    total, used, available = (int(cell) for cell in parts[1:4])
+    return parts[4].endswith('%') and parts[5].startswith('/') and used + available <= total
+
+
+def parse_df(output: str) -> List[Dict]:
+    """
+    Parse `df -k` output by header columns.
+
+    Handles filesystem names wrapped onto their own line and headers that
+    differ between df implementations. Rows with more or fewer cells than
+    the header (cut off, or sizes printed with digit-group spaces) are
+    skipped rather than read from the wrong columns. A translated header is
+    read by the toybox column positions, but only when every row has
+    exactly that shape; otherwise nothing is returned.
+    """
+    lines = [line for line in output.split('\n') if line.strip()]
+    if not lines:
+        return []
+
+    header = re.sub(r'mounted\s+on', "mounted", lines[0].lower()).split()
+    columns = {}
+    for field, aliases in DF_COLUMN_ALIASES.items():
+        for index, name in enumerate(header):
+            if name in aliases:
+                columns[field] = index
+                break
+    width = len(header)
+
+    # Re-join rows where a long filesystem name was wrapped onto its own line
+    rows = []
+    pending = None
+    for line in lines[1:]:
+        parts = line.split()
+        if pending is not None:
+            parts = [pending] + parts
+            pending = None
+        if len(parts) == 1:
+            pending = parts[0]
+            continue
+        rows.append(parts)
+
+    if "total" not in columns or "available" not in columns:
+        if not rows or not all(_is_toybox_df_row(parts) for parts in rows):
+            return []
+        columns, width = TOYBOX_DF_COLUMNS, TOYBOX_DF_WIDTH
+
+    entries = []
+    for parts in rows:
+        if len(parts) != width:
+            continue
+        total = _parse_df_size_kb(parts[columns["total"]])
+        available = _parse_df_size_kb(parts[columns["available"]])
+        if total is None or available is None:
+            continue
+        if "used" in columns:
+            used = _parse_df_size_kb(parts[columns["used"]])
+            if used is None:
+                continue
+        else:
+            used = max(0, total - available)
+        # Legacy toolbox puts the mount point first and has no "Mounted on" column
+        mount = parts[columns["mount"]] if "mount" in columns and len(parts) > columns["mount"] else parts[0]
+        entry = {
+            "filesystem": parts[0],
+            "mountPoint": mount,
+            "totalKb": total,
+            "usedKb": used,
+            "availableKb": available,
+            "usagePercent": percent(used, total),
+        }
+        entry["formatted"] = formatted_fields(entry)
+        entries.append(entry)
+    return entries
+
+
+def parse_adopted_volumes(output: str) -> List[str]:
+    """
+    Mount points of adopted SD cards from `sm list-volumes private`.
+
+    Lines are "<id> <state> <fsUuid>"; the built-in "private" volume is
+    /data, and a mounted "private:<disk>" one is at /mnt/expand/<fsUuid>.
+    """
+    mounts = []
+    for line in output.split('\n'):
+        parts = line.split()
+        if len(parts) == 3 and parts[0].startswith("private:") and parts[1] == "mounted" \
+                and re.fullmatch(r'[0-9A-Za-z-]+', parts[2]) and parts[2] != "null":
+            mounts.append(f"/mnt/expand/{parts[2]}")
+    return mounts
+
+
+def build_storage_info(entries: List[Dict]) -> Optional[Dict]:
+    """Split parsed df entries into internal /data and adoptable SD storage"""
+    internal = next((e for e in entries if e["mountPoint"] == "/data"), None)
+    if internal is None and entries:
+        internal = entries[0]
+    if internal is None:
+        return None
+
+    adoptable = [e for e in entries if e["mountPoint"].startswith("/mnt/expand/")]
+    return {
+        "internal": internal,
+        "adoptable": adoptable,
+        "adoptableTotalKb": sum(e["totalKb"] for e in adoptable),
+        "adoptableAvailableKb": sum(e["availableKb"] for e in adoptable),
+    }
+
+
+# android.os.Temperature types
+THERMAL_TYPE_NAMES = {
+    0: "cpu", 1: "gpu", 2: "battery", 3: "skin", 4: "usb_port",
+    5: "power_amplifier", 6: "bcl_voltage", 7: "bcl_current", 8: "bcl_percentage", 9: "npu",
+}
+
+TEMPERATURE_PATTERN = re.compile(r'Temperature\{([^}]*)\}')
+
+
+def normalize_celsius(value: float) -> Optional[float]:
+    """
+    Convert a raw sensor reading to degrees Celsius.
+
+    OEM HALs report degrees, deci-degrees (352 = 35.2C) or milli-degrees
+    (35200 = 35.2C); no real device sensor reads above 150C.
+    """
+    if value >= 1000:
+        value = value / 1000.0
+    elif value > 150:
+        value = value / 10.0
+    if value <= -40 or value > 150:
+        return None
+    return round(value, 1)
+
+
+def parse_thermal_sensors(output: str) -> List[Dict]:
+    """Parse `Temperature{mValue=..., mType=..., mName=..., mStatus=...}` entries"""
+    sensors = {}
+    for match in TEMPERATURE_PATTERN.finditer(output):
+        fields = {}
+        for pair in match.group(1).split(','):
+            key, sep, value = pair.partition('=')
+            if sep:
+                fields[key.strip()] = value.strip()
+        try:
+            raw = float(fields.get("mValue", ""))
+        except ValueError:
+            continue
+        value = normalize_celsius(raw)
+        if value is None:
+            continue
+        try:
+            sensor_type = int(fields.get("mType", -1))
+        except ValueError:
+            sensor_type = -1
+        name = fields.get("mName", THERMAL_TYPE_NAMES.get(sensor_type, "unknown"))
+        # The HAL section comes first; later "cached" copies of a sensor are ignored
+        if name in sensors:
+            continue
+        sensors[name] = {
+            "name": name,
+            "type": THERMAL_TYPE_NAMES.get(sensor_type, "unknown"),
+            "valueC": value,
+            "status": int(fields["mStatus"]) if fields.get("mStatus", "").isdigit() else None,
+        }
+    return list(sensors.values())
+
+
+def parse_battery_temperature(output: str) -> Optional[float]:
+    """Parse `temperature: 320` (tenths of a degree) from `dumpsys battery`"""
+    match = re.search(r'^\s*temperature:\s*(-?\d+)', output, re.MULTILINE)
+    if not match:
+        return None
+    return normalize_celsius(int(match.group(1)) / 10.0)
+
+
+# `dumpsys power` plug types (BatteryManager.BATTERY_PLUGGED_*)
+PLUG_TYPES = {1: "ac", 2: "usb", 4: "wireless", 8: "dock"}
+POWER_FIELD_PATTERN = re.compile(r'\b(mIsPowered|mPlugType|mBatteryLevel)=(\w+)')
+PARTIAL_WAKE_LOCK_PATTERN = re.compile(r"^\s*PARTIAL_WAKE_LOCK\s+'", re.MULTILINE)
+# `dumpsys batterystats --charged`: "Screen on: 2h 3m 4s 567ms (13.5%) 45x, Interactive: ..."
+SCREEN_ON_PATTERN = re.compile(r'^\s*Screen on: ((?:\d+(?:ms|d|h|m|s) ?)+)', re.MULTILINE)
+DURATION_PART_PATTERN = re.compile(r'(\d+)(ms|d|h|m|s)')
+DURATION_SECONDS = {"d": 86400, "h": 3600, "m": 60, "s": 1, "ms": 0.001}
+
+
+def parse_power_state(output: str) -> Optional[Dict]:
+    """
+    Parse `dumpsys power` into whether the device is charging, from what,
+    its battery level and the partial wake locks held right now
+    """
+    fields = dict(POWER_FIELD_PATTERN.findall(output))
+    if "mIsPowered" not in fields:
+        return None
+    plug_type = int(fields["mPlugType"]) if fields.get("mPlugType", "").isdigit() else 0
+    return {
+        "charging": fields["mIsPowered"] == "true",
+        "plugType": PLUG_TYPES.get(plug_type),
+        "batteryLevel": int(fields["mBatteryLevel"]) if fields.get("mBatteryLevel", "").isdigit() else None,
+        "wakeLocks": len(PARTIAL_WAKE_LOCK_PATTERN.findall(output)),
+    }
+
+
+def parse_duration_seconds(text: str) -> int:
+    """Whole seconds of a dumpsys duration, e.g. 7384 for "2h 3m 4s 567ms" or "2h3m4s567ms" alike"""
+    return int(sum(int(value) * DURATION_SECONDS[unit] for value, unit in DURATION_PART_PATTERN.findall(text)))
+
+
+def parse_screen_on_seconds(output: str) -> Optional[int]:
+    """Screen-on time since the last full charge from `dumpsys batterystats --charged`"""
+    match = SCREEN_ON_PATTERN.search(output)
+    if not match:
+        return None
+    return parse_duration_seconds(match.group(1))
+
+
+def select_device_temperature(sensors: List[Dict]) -> Optional[Dict]:
+    """Pick the most representative sensor: skin, then battery, then hottest CPU"""
+    for sensor_type in ("skin", "battery"):
+        candidates = [s for s in sensors if s["type"] == sensor_type]
+        if candidates:
+            return max(candidates, key=lambda s: s["valueC"])
+    cpus = [s for s in sensors if s["type"] == "cpu"]
+    if cpus:
+        return max(cpus, key=lambda s: s["valueC"])
+    return sensors[0] if sensors else None
+
+
+class SystemHealth:
+    """Collect and cache device health metrics"""
+
+    def __init__(self, adb_operations: ADBOperations, diagnostics=None, revalidator=None):
+        self.adb = adb_operations
+        # Revalidator refreshing stale snapshots for get_cached_health
+        self.revalidator = revalidator
+        # ParserDiagnostics noting output the parsers could not read
+        self.diagnostics = diagnostics
+        # Per-device cache: serial -> {"cpu_sample": [...], "cpu_sample_time": float}
+        self._cache: Dict[str, Dict] = {}
+        self.stats = CacheStats()
+        # Serialises snapshots between IPC calls and the monitor thread
+        self._lock = threading.Lock()
+        self._monitor_thread: Optional[threading.Thread] = None
+        self._monitor_stop = threading.Event()
+
+    def _device_cache(self, serial: str) -> Dict:
+        return self._cache.setdefault(serial, {})
+
+    def _parser_failed(self, parser: str, command: str, output: str):
+        # Empty output means the command itself gave nothing, not a format we cannot read
+        if self.diagnostics is not None and output.strip():
+            self.diagnostics.record_failure(parser, command, output)
+
+    def _read_proc_stat(self) -> Optional[List[int]]:
+        try:
+            return parse_proc_stat(self.adb.shell("cat /proc/stat", timeout=10))
+        except ADBError:
+            return None
+
+    def get_cpu_usage(self, serial: str) -> Optional[float]:
+        """
+        Get current CPU usage percent.
+
+        Uses the delta between the cached /proc/stat sample from the previous
+        call and a fresh one. On the first call for a device, or when the
+        cached sample is older than CPU_SAMPLE_MAX_AGE, two samples are
+        taken CPU_SAMPLE_INTERVAL apart. Falls back to `top` only when
+        /proc/stat cannot be read.
+        """
+        cache = self._device_cache(serial)
+
+        current = self._read_proc_stat()
+        if current is None:
+            return self._cpu_usage_from_top()
+
+        previous = cache.get("cpu_sample")
+        if previous is None or time.time() - cache.get("cpu_sample_time", 0) > CPU_SAMPLE_MAX_AGE:
+            time.sleep(CPU_SAMPLE_INTERVAL)
+            previous, current = current, self._read_proc_stat()
+            if current is None:
+                return self._cpu_usage_from_top()
+
+        usage = compute_cpu_usage(previous, current)
+        cache["cpu_sample"] = current
+        cache["cpu_sample_time"] = time.time()
+
+        if usage is None:
+            # Counter reset - keep the new sample and try again next call
+            return self._cpu_usage_from_top()
+        return usage
+
+    def _cpu_usage_from_top(self) -> Optional[float]:
+        try:
+            return parse_top_cpu(self.adb.shell(c_locale("top -n 1 -b -m 1"), timeout=15))
+        except ADBError:
+            return None
+
+    def get_memory_info(self) -> Optional[Dict]:
+        """Get RAM, swap and zram usage"""
+        try:
+            output = self.adb.shell("cat /proc/meminfo", timeout=10)
+        except ADBError:
+            return None
+        meminfo = parse_meminfo(output)
+        # Without a free figure every kB would count as used
+        if "MemTotal" not in meminfo or not ({"MemAvailable", "MemFree"} & meminfo.keys()):
+            self._parser_failed("memory", "cat /proc/meminfo", output)
+            return None
+
+        zram = None
+        zram_disk_size = None
+        try:
+            output = self.adb.shell(
+                "cat /sys/block/zram0/mm_stat 2>/dev/null; echo '|'; cat /sys/block/zram0/disksize 2>/dev/null",
+                timeout=10
+            )
+            mm_stat, _, disksize = output.partition('|')
+            zram = parse_zram_mm_stat(mm_stat)
+            if disksize.strip().isdigit():
+                zram_disk_size = int(disksize.strip())
+        except ADBError:
+            pass  # zram stats are optional (not exposed on every kernel)
+
+        return build_memory_info(meminfo, zram, zram_disk_size)
+
+    def get_storage_info(self) -> Optional[Dict]:
+        """Get internal /data usage plus any adopted SD card volumes"""
+        try:
+            # The shell user cannot list /mnt/expand, so ask the storage manager
+            volumes = parse_adopted_volumes(self.adb.shell("sm list-volumes private 2>/dev/null", timeout=15))
+            command = c_locale(" ".join(["df -k /data", *volumes]))
+            output = self.adb.shell(command, timeout=15)
+        except ADBError:
+            return None
+        storage = build_storage_info(parse_df(output))
+        if storage is None:
+            self._parser_failed("storage", command, output)
+        return storage
+
+    def get_temperature_info(self) -> Optional[Dict]:
+        """Get device temperature, preferring the skin sensor when available"""
+        sensors = []
+        try:
+            if self.adb.supports("thermal_service"):
+                output = self.adb.shell("dumpsys thermalservice", timeout=15)
+                sensors = parse_thermal_sensors(output)
+                if not sensors:
+                    self._parser_failed("thermal", "dumpsys thermalservice", output)
+        except ADBError:
+            pass
+
+        selected = select_device_temperature(sensors)
+        if selected is None:
+            # Pre-Android 10 devices have no thermalservice; use the battery sensor
+            try:
+                output = self.adb.shell("dumpsys battery", timeout=10)
+                value = parse_battery_temperature(output)
+                if value is None:
+                    self._parser_failed("battery", "dumpsys battery", output)
+            except ADBError:
+                value = None
+            if value is None:
+                return None
+            selected = {"name": "battery", "type": "battery", "valueC": value, "status": None}
+            sensors = [selected]
+
+        return {
+            "temperatureC": selected["valueC"],
+            "source": selected["name"],
+            "sensors": sensors,
+        }
+
+    def get_running_services(self) -> Optional[Dict]:
+        """Get running app services, counted and grouped by owning package"""
+        try:
+            return parse_running_services(self.adb.shell("dumpsys activity services", timeout=20))
+        except ADBError:
+            return None
+
+    def get_notification_counts(self) -> Optional[Dict]:
+        """Get notifications per package, noisiest first"""
+        try:
+            return parse_notification_counts(self.adb.shell("dumpsys notification --noredact", timeout=20))
+        except ADBError:
+            return None
+
+    def get_power_info(self, serial: str) -> Optional[Dict]:
+        """Get the charging state and wake locks, with screen-on time since the last charge"""
+        try:
+            output = self.adb.shell("dumpsys power", timeout=15)
+        except ADBError:
+            return None
+        power = parse_power_state(output)
+        if power is None:
+            self._parser_failed("power", "dumpsys power", output)
+            return None
+
+        cache = self._device_cache(serial)
+        if time.time() - cache.get("screen_on_time", 0) >= SCREEN_ON_REFRESH:
+            try:
+                cache["screen_on_seconds"] = parse_screen_on_seconds(
+                    self.adb.shell("dumpsys batterystats --charged", timeout=60))
+            except ADBError:
+                cache["screen_on_seconds"] = None
+            cache["screen_on_time"] = time.time()
+        power["screenOnSeconds"] = cache["screen_on_seconds"]
+        return power
+
+    def get_system_health(self) -> Dict:
+        """Collect a health snapshot for the connected device"""
+        with self._lock:
+            serial = self.adb.get_serial()
+            snapshot = {
+                "serial": serial,
+                "timestamp": time.time(),
+                "cpuUsage": self.get_cpu_usage(serial),
+                "memory": self.get_memory_info(),
+                "storage": self.get_storage_info(),
+                "temperature": self.get_temperature_info(),
+                "services": self.get_running_services(),
+                "notifications": self.get_notification_counts(),
+                "power": self.get_power_info(serial),
+            }
+            self._device_cache(serial)["snapshot"] = snapshot
+            return snapshot
+
+    def get_cached_health(self) -> Dict:
+        """
+        The last snapshot of the connected device at once, with `stale: true`
+        and a background refresh once it is older than the health max age.
+        Only the first call for a device waits for a snapshot.
+        """
+        serial = self.adb.get_serial()
+        snapshot = self._device_cache(serial).get("snapshot")
+        if snapshot is None:
+            self.stats.miss()
+            return {**self.get_system_health(), "stale": False}
+        self.stats.hit()
+        stale = is_stale("health", snapshot["timestamp"])
+        if stale and self.revalidator is not None:
+            self.revalidator.revalidate("health", serial, self.get_system_health)
+        return {**snapshot, "stale": stale}
+
+    def cache_diagnostics(self) -> Dict:
+        """get_cache_diagnostics line of the health snapshots and CPU samples"""
+        with self._lock:
+            cache = {serial: dict(entry) for serial, entry in self._cache.items()}
+        times = [entry["snapshot"]["timestamp"] for entry in cache.values() if "snapshot" in entry]
+        return cache_report("health", self.stats, cache, times, len(cache))
+
+    def start_monitor(self, emit: EmitFn, interval_seconds: float = 5) -> Dict:
+        """Start polling health in the background, emitting `health_update` events"""
+        if self._monitor_thread and self._monitor_thread.is_alive():
+            return {"success": True, "message": "Health monitor already running"}
+
+        interval = max(1.0, float(interval_seconds))
+        # Each thread gets its own event: a thread told to stop may still be
+        # finishing a poll when the next one starts, and must not be revived
+        stop = threading.Event()
+        self._monitor_stop = stop
+
+        def run():
+            device_lost = False
+            while not stop.is_set():
+                try:
+                    snapshot = self.get_system_health()
+                    if stop.is_set():
+                        break
+                    if device_lost:
+                        device_lost = False
+                        emit("device_restored", {"operation": "health_monitor", "serial": snapshot["serial"]},
+                             HEALTH_MONITOR_JOB)
+                    emit("health_update", snapshot, HEALTH_MONITOR_JOB)
+                except DeviceDisconnectedError as e:
+                    if not device_lost:
+                        device_lost = True
+                        emit("device_lost", {"operation": "health_monitor", "message": str(e)}, HEALTH_MONITOR_JOB)
+                except ADBError:
+                    pass  # Transient failure - keep polling
+                except Exception as e:
+                    print(f"[Warning] Health monitor error: {e}", file=sys.stderr)
+                stop.wait(interval)
+
+        self._monitor_thread = threading.Thread(target=run, name="health-monitor", daemon=True)
+        self._monitor_thread.start()
+        return {"success": True, "message": f"Health monitor started ({interval:g}s interval)"}
+
+    def stop_monitor(self) -> Dict:
+        """Stop the background health monitor"""
+        self._monitor_stop.set()
+        self._monitor_thread = None
+        return {"success": True, "message": "Health monitor stopped"}
+
+    def clear_health_cache(self, serial: str = None) -> Dict:
+        """Drop cached samples for one device, or for all devices"""
+        with self._lock:
+            if serial:
+                self._cache.pop(serial, None)
+            else:
+                self._cache.clear()
+        return {"success": True, "message": "Health cache cleared"}
@@ -0,0 +1,135 @@
+"""
+Test package analysis requests of the AI advisor and its keyless fallback
+Runs against a fake API endpoint - no network or API key required
+"""
+import sys
+import os
+import json
+import tempfile
+import threading
+import time
+sys.path.insert(0, os.path.dirname(__file__))
+
+import network
+from ai_advisor import AIAdvisor
+from settings_manager import SettingsManager
+from usage_tracker import AIUsageTracker
+
+
+ANALYSIS = {"packageName": "com.facebook.katana", "summary": "Facebook social network app",
+            "safeToRemove": True, "riskCategory": "Safe"}
+
+
+class AnalysisResponse:
+    status_code = 200
+    text = ""
+
+    def raise_for_status(self):
+        pass
+
+    def json(self):
+        return {"choices": [{"message": {"content": json.dumps(ANALYSIS)}}], "usage": {}}
+
+
+class SlowEndpoint:
+    """Answers once `release` is set, counting the requests that reached it"""
+
+    def __init__(self, fail=False):
+        self.requests = 0
+        self.fail = fail
+        self.started = threading.Event()
+        self.release = threading.Event()
+
+    def post(self, url, **kwargs):
+        self.requests += 1
+        self.started.set()
+        self.release.wait(5)
+        if self.fail:
+            raise network.OfflineModeError("Offline mode is on")
+        return AnalysisResponse()
+
+
+def analyze_twice(endpoint, data_dir):
+    """Two concurrent analyses of the same package; returns their results (or exceptions)"""
+    advisor = AIAdvisor()
+    advisor.api_key = "test-key"
+    advisor.usage = AIUsageTracker(data_dir)
+    results = [None, None]
+
+    def analyze(index):
+        try:
+            results[index] = advisor.analyze_package("com.facebook.katana")
+        except Exception as e:
+            results[index] = e
+
+    first = threading.Thread(target=analyze, args=(0,))
+    first.start()
+    assert endpoint.started.wait(5), "the first analysis never reached the API"
+    second = threading.Thread(target=analyze, args=(1,))
+    second.start()
+    time.sleep(0.1)  # let the second call find the first in flight
+    endpoint.release.set()
+    first.join(5)
+    second.join(5)
+    assert advisor._in_flight == {}, "finished analyses are not kept in flight"
+    return advisor, results
+
+
+def test_coalesced_analysis():
+    """Test that concurrent analyses of one package share a request, its result and its failure"""
+    print("\n🔗 Testing coalesced analyses...")
+    original_post = network.post
+    try:
+        with tempfile.TemporaryDirectory() as tmp:
+            endpoint = SlowEndpoint()
+            network.post = endpoint.post
+            advisor, results = analyze_twice(endpoint, tmp)
+            assert endpoint.requests == 1
+            assert results[0] == results[1] == ANALYSIS
+            results[0]["conflict"] = "annotated by one caller"
+            assert "conflict" not in results[1], "each caller gets its own copy"
+
+            # Once finished, a new call asks again
+            endpoint.release.set()
+            advisor.analyze_package("com.facebook.katana")
+            assert endpoint.requests == 2
+
+            failing = SlowEndpoint(fail=True)
+            network.post = failing.post
+            _, errors = analyze_twice(failing, tmp)
+            assert failing.requests == 1
+            assert all(isinstance(e, Exception) and "API request failed" in str(e) for e in errors), errors
+    finally:
+        network.post = original_post
+    print("  └─ ✅ Coalesced analyses OK")
+    return True
+
+
+def test_unconfigured():
+    """Test the keyless advisor the backend falls back to when setting one up fails"""
+    print("\n🔑 Testing the unconfigured advisor...")
+    with tempfile.TemporaryDirectory() as tmp:
+        settings = SettingsManager(tmp)
+        advisor = AIAdvisor.unconfigured(settings)
+        assert advisor.api_key is None and advisor.settings is settings and advisor.provider == "perplexity"
+        assert advisor.analyze_package("com.facebook.katana")["error"].startswith("API key not configured")
+        assert advisor.chat("What is com.facebook.katana?")["content"].startswith("AI chat unavailable")
+    print("  └─ ✅ Unconfigured advisor OK")
+    return True
+
+
+def main():
+    """Run all AI advisor tests"""
+    tests = [test_coalesced_analysis, test_unconfigured]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,93 @@
+"""
+Test the persisted AI analysis cache
+Runs against a temporary data directory - no device or API key required
+"""
+import sys
+import os
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from analysis_cache import AnalysisCache, risk_from_analysis
+from guardrails import analysis_conflict
+
+
+ANALYSIS = {
+    "packageName": "com.facebook.katana",
+    "summary": "Facebook social network app",
+    "safeToRemove": True,
+    "riskCategory": "Safe",
+}
+
+
+def test_risk_extraction():
+    """Test that only successful analyses produce a verdict"""
+    print("\n🧠 Testing verdict extraction...")
+    risk = risk_from_analysis(ANALYSIS, "sonar")
+    assert risk["riskCategory"] == "Safe" and risk["safeToRemove"] is True and risk["model"] == "sonar"
+    assert risk_from_analysis({"error": "API key not configured", "safetyLevel": "unknown"}) is None
+    assert risk_from_analysis({**ANALYSIS, "riskCategory": "Maybe"}) is None
+    risk = risk_from_analysis({**ANALYSIS, "confidence": 1.4, "citations": [f"https://x.example/{i}" for i in range(8)]})
+    assert risk["confidence"] == 1.0 and len(risk["citations"]) == 5
+    assert risk_from_analysis({**ANALYSIS, "confidence": "high"})["confidence"] is None
+    print("  └─ ✅ Verdict extraction OK")
+    return True
+
+
+def test_persist_and_apply():
+    """Test that verdicts survive a restart and are joined into listings"""
+    print("\n💾 Testing persistence...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        AnalysisCache(data_dir).record("com.facebook.katana", ANALYSIS, "sonar")
+        cache = AnalysisCache(data_dir)
+        packages = cache.apply([
+            {"packageName": "com.facebook.katana", "appName": "Facebook", "safetyLevel": "Safe"},
+            {"packageName": "com.android.systemui", "appName": "Systemui", "safetyLevel": "Dangerous"},
+        ])
+        assert packages[0]["aiRisk"]["summary"] == "Facebook social network app"
+        assert "aiRisk" not in packages[1]
+        assert cache.record("com.android.systemui", {"error": "Budget exceeded"}) is None
+        assert cache.get("com.android.systemui") is None
+    print("  └─ ✅ Persistence OK")
+    return True
+
+
+def test_rating_conflicts():
+    """Test that verdicts contradicting the local rating carry an explicit conflict"""
+    print("\n⚖️  Testing rating conflicts...")
+    assert analysis_conflict(ANALYSIS, "Safe") is None
+    assert analysis_conflict(ANALYSIS, "Caution") is None
+    lenient = analysis_conflict(ANALYSIS, "Dangerous")
+    assert lenient["kind"] == "aiMoreLenient" and lenient["enforced"] == "local"
+    assert "rates it Dangerous" in lenient["message"]
+    strict = analysis_conflict({"riskCategory": "Expert", "safeToRemove": False}, "Safe")
+    assert strict["kind"] == "aiStricter" and strict["aiRiskCategory"] == "Expert"
+    assert analysis_conflict({"riskCategory": "Caution", "safeToRemove": False}, "Safe") is None
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        cache = AnalysisCache(data_dir)
+        cache.record("com.samsung.android.app.spage", ANALYSIS)
+        packages = cache.apply([
+            {"packageName": "com.facebook.katana", "safetyLevel": "Safe"},
+            {"packageName": "com.samsung.android.app.spage", "safetyLevel": "Expert"},
+        ])
+        assert packages[1]["aiRisk"]["conflict"]["localRating"] == "Expert"
+        assert cache.get("com.samsung.android.app.spage").get("conflict") is None
+    print("  └─ ✅ Rating conflicts OK")
+    return True
+
+
+def main():
+    """Run all analysis cache tests"""
+    tests = [test_risk_extraction, test_persist_and_apply, test_rating_conflicts]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,73 @@
+"""
+Test the Markdown/CSV digest of AI verdicts and local ratings
+Runs against canned verdicts - no device or API key required
+"""
+import sys
+import os
+import csv
+import io
+sys.path.insert(0, os.path.dirname(__file__))
+
+from analysis_export import CSV_COLUMNS, build_rows, render
+
+
+VERDICTS = {
+    "com.facebook.katana": {
+        "riskCategory": "Safe", "safeToRemove": True, "summary": "Facebook app | social network",
+        "analyzedAt": 1760000000.0, "model": "sonar", "confidence": 0.85,
+        "citations": ["https://xdaforums.com/t/facebook-bloat", "https://reddit.com/r/android/x"], "stale": True,
+    },
+}
+RATINGS = {"com.facebook.katana": "Safe", "com.samsung.android.bixby.agent": "Caution"}
+DEVICE = {"manufacturer": "samsung", "model": "SM-G991B", "androidVersion": "14", "serial": "R58N12345"}
+
+
+def rows():
+    return build_rows(["com.facebook.katana", "com.samsung.android.bixby.agent", "com.facebook.katana"],
+                      VERDICTS.get, RATINGS.get)
+
+
+def test_markdown():
+    """Test the forum post: table, per-package sources, no serial"""
+    print("\n📝 Testing the Markdown digest...")
+    report = render(rows(), "markdown", DEVICE)
+    assert "**Device:** samsung SM-G991B, Android 14" in report
+    assert "R58N12345" not in report
+    assert "| `com.facebook.katana` | Safe | Safe | yes | 85% |" in report
+    assert "| `com.samsung.android.bixby.agent` | Caution | not analyzed |  |  |" in report
+    assert "Facebook app \\| social network" in report
+    assert "1. <https://xdaforums.com/t/facebook-bloat>" in report
+    assert "(older than the refresh interval)" in report
+    assert "### `com.samsung.android.bixby.agent`" not in report  # Nothing to say beyond the table
+    print("  └─ ✅ Markdown digest OK")
+    return True
+
+
+def test_csv():
+    """Test the spreadsheet export"""
+    print("\n📊 Testing the CSV digest...")
+    parsed = list(csv.DictReader(io.StringIO(render(rows(), "csv"))))
+    assert len(parsed) == 2  # Duplicates collapsed
+    assert list(parsed[0]) == CSV_COLUMNS
+    assert parsed[0]["confidence"] == "0.85" and parsed[0]["safeToRemove"] == "true"
+    assert parsed[0]["citations"] == "https://xdaforums.com/t/facebook-bloat https://reddit.com/r/android/x"
+    assert parsed[1]["aiRiskCategory"] == "" and parsed[1]["localRating"] == "Caution"
+    print("  └─ ✅ CSV digest OK")
+    return True
+
+
+def main():
+    """Run all analysis export tests"""
+    tests = [test_markdown, test_csv]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
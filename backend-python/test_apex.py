@@ -0,0 +1,105 @@
+"""
+Test APEX module listing, listing tags and the uninstall refusal
+Runs against canned device output - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from apex import APEX_LIST_COMMAND, module_of_path, parse_apex_list
+
+
+APEX_LIST = """package:com.android.tzdata versionCode:331011000
+package:com.android.tethering versionCode:331311000
+package:com.android.media.swcodec
+"""
+
+DETAILS = {
+    "com.android.networkstack.tethering": ("/apex/com.android.tethering/priv-app/Tethering/Tethering.apk", None),
+    "com.facebook.katana": ("/data/app/~~abc==/com.facebook.katana-1/base.apk", "com.android.vending"),
+}
+
+
+def make_adb(sdk=30):
+    """A device answering the APEX listing"""
+    return FakeADB({APEX_LIST_COMMAND: APEX_LIST}, sdk=sdk)
+
+
+def uninstalls(adb):
+    return [run[-1] for run in adb.runs]
+
+
+def test_parse():
+    """Test parsing the module list and APEX code paths"""
+    print("\n🧩 Testing APEX parsing...")
+    assert parse_apex_list(APEX_LIST) == [
+        {"packageName": "com.android.tzdata", "versionCode": 331011000},
+        {"packageName": "com.android.tethering", "versionCode": 331311000},
+        {"packageName": "com.android.media.swcodec", "versionCode": None},
+    ]
+    assert module_of_path("/apex/com.android.tethering/priv-app/Tethering/Tethering.apk") == "com.android.tethering"
+    assert module_of_path("/apex/com.android.permission@311/priv-app/PermissionController.apk") == "com.android.permission"
+    assert module_of_path("/system/app/Chrome/Chrome.apk") is None
+    assert module_of_path(None) is None
+    print("  └─ ✅ APEX parsing OK")
+    return True
+
+
+def test_listing_tags():
+    """Test that modules and the apps inside them are tagged and kept out of removal"""
+    print("\n🏷️  Testing listing tags...")
+    adb = make_adb()
+    entries = {p["packageName"]: p for p in adb.build_package_entries(
+        ["com.android.tethering", "com.android.networkstack.tethering", "com.facebook.katana"], details=DETAILS)}
+    assert entries["com.android.tethering"]["apexModule"] == "com.android.tethering"
+    app = entries["com.android.networkstack.tethering"]
+    assert app["apexModule"] == "com.android.tethering" and app["removable"] is False
+    assert "apexModule" not in entries["com.facebook.katana"]
+    assert "removable" not in entries["com.facebook.katana"]
+
+    modules = {m["packageName"]: m for m in adb.get_apex_modules()}
+    assert modules["com.android.tethering"]["apps"] == ["com.android.networkstack.tethering"]
+    assert modules["com.android.tzdata"]["apps"] == []
+    assert adb.commands.count(APEX_LIST_COMMAND) == 1  # Listed once per device
+
+    # Android 9 has no APEX; nothing is listed or tagged
+    old = make_adb(sdk=28)
+    assert "apexModule" not in old.build_package_entries(["com.android.tethering"])[0]
+    assert APEX_LIST_COMMAND not in old.commands
+    print("  └─ ✅ Listing tags OK")
+    return True
+
+
+def test_uninstall_refused():
+    """Test that uninstalling a module or an app inside one is refused before reaching pm"""
+    print("\n🚫 Testing the uninstall refusal...")
+    adb = make_adb()
+    adb.build_package_entries(["com.android.networkstack.tethering"], details=DETAILS)
+    for package in ("com.android.tzdata", "com.android.networkstack.tethering"):
+        result = adb.uninstall_package(package)
+        assert not result["success"] and result["apex"]
+        assert "system module" in result["message"]
+    assert uninstalls(adb) == []
+
+    assert adb.uninstall_package("com.facebook.katana")["success"]
+    assert uninstalls(adb) == ["com.facebook.katana"]
+    print("  └─ ✅ Uninstall refusal OK")
+    return True
+
+
+def main():
+    """Run all APEX tests"""
+    tests = [test_parse, test_listing_tags, test_uninstall_refused]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
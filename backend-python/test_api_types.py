@@ -0,0 +1,32 @@
+"""
+Smoke test - the generated TypeScript DTOs match api_types.py
+Fails when api_types.py changed without rerunning generate_ts_types.py
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from generate_ts_types import OUTPUT_PATH, generate
+
+
+def test_generated_types_current():
+    """frontend/src/types.generated.ts is what the generator produces now"""
+    print("\n🧬 Testing generated TypeScript types...")
+    with open(OUTPUT_PATH, 'r', encoding='utf-8') as f:
+        current = f.read()
+    assert current == generate(), "types.generated.ts is stale; run python backend-python/generate_ts_types.py"
+    print("  └─ ✅ Generated types are up to date")
+    return True
+
+
+def main():
+    try:
+        test_generated_types_current()
+    except AssertionError as e:
+        print(f"  └─ ❌ {e}")
+        return 1
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
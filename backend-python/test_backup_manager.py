@@ -0,0 +1,316 @@
+"""
+Test creating, listing and restoring backups
+Runs against a fake device and a temporary backup directory - no ADB required
+"""
+import sys
+import os
+import json
+import tempfile
+import threading
+import zipfile
+from datetime import datetime, timedelta
+sys.path.insert(0, os.path.dirname(__file__))
+
+from backup_manager import ZIP_MANIFEST, BackupManager, group_backups, select_expired
+from fake_adb import FakeADB
+
+
+def listing(packages):
+    return "".join(f"package:{p}\n" for p in sorted(packages))
+
+
+class RestoreDevice(FakeADB):
+    """Facebook is installed, Netflix and Bixby removed (Bixby's reinstall fails), Spotify gone"""
+
+    def __init__(self):
+        super().__init__({
+            "pm list packages": listing({"com.facebook.katana"}),
+            "pm list packages -u": listing({"com.facebook.katana", "com.netflix.mediaclient",
+                                            "com.samsung.android.bixby.agent"}),
+        })
+
+    def reinstall_package(self, package_name):
+        if package_name == "com.samsung.android.bixby.agent":
+            return {"success": False, "message": "Failed to reinstall: Package not found"}
+        return {"success": True, "message": f"Successfully reinstalled {package_name}"}
+
+
+class UnpluggingDevice(FakeADB):
+    """Facebook is installed, the others removed; the cable comes out after the first reinstall"""
+
+    def __init__(self):
+        super().__init__({
+            "pm list packages": listing({"com.facebook.katana"}),
+            "pm list packages -u": listing({"com.facebook.katana", "com.netflix.mediaclient", "com.spotify.music",
+                                            "com.samsung.android.bixby.agent"}),
+        }, run_output="Package installed for user: 0", sdk=30)
+
+    def _run_command(self, command, timeout=30):
+        output = super()._run_command(command, timeout)
+        self.connected = False
+        return output
+
+
+class ExportingDevice(FakeADB):
+    """Netflix has a base and a split APK, Bixby is a system app; installs are recorded"""
+
+    APKS = {"com.netflix.mediaclient": ["/data/app/netflix/base.apk", "/data/app/netflix/split_config.arm64_v8a.apk"]}
+
+    def __init__(self):
+        super().__init__({"pm list packages -s": listing({"com.samsung.android.bixby.agent"})})
+        self.installed_apks = []
+
+    def get_package_sizes(self):
+        return {}
+
+    def get_apk_paths(self, package_name):
+        return self.APKS.get(package_name, [])
+
+    def pull_file(self, remote_path, local_path, timeout=300):
+        with open(local_path, 'w', encoding='utf-8') as f:
+            f.write(f"apk of {remote_path}")
+
+    def install_apks(self, apk_paths):
+        self.installed_apks.append(sorted(os.path.basename(p) for p in apk_paths))
+        return {"success": True, "message": "Installed from APK"}
+
+
+class DataDevice(FakeADB):
+    """`adb backup` writes `archive_bytes` to its -f file, as if confirmed on the device"""
+
+    def __init__(self, sdk, archive_bytes=4096):
+        super().__init__({"pm list packages": listing({"com.facebook.katana"}),
+                          "pm list packages -u": listing({"com.facebook.katana"})}, sdk=sdk)
+        self.archive_bytes = archive_bytes
+
+    def get_package_sizes(self):
+        return {}
+
+    def _run_command(self, command, timeout=30):
+        if command[1:3] == ["backup", "-f"]:
+            with open(command[3], 'wb') as f:
+                f.write(b"\0" * self.archive_bytes)
+        return super()._run_command(command, timeout)
+
+
+def write_backup(backup_dir, name, packages, **fields):
+    with open(os.path.join(backup_dir, name), 'w', encoding='utf-8') as f:
+        json.dump({"timestamp": "2026-10-01T08:00:00", "packages": packages, "count": len(packages), **fields}, f)
+
+
+def test_restore_results():
+    """Test the per-package status of a restore and the counts summarising it"""
+    print("\n📋 Testing restore results...")
+    with tempfile.TemporaryDirectory() as tmp:
+        packages = ["com.facebook.katana", "com.netflix.mediaclient", "com.samsung.android.bixby.agent",
+                    "com.spotify.music"]
+        write_backup(tmp, "backup_20261001_080000.json", packages)
+        result = BackupManager(tmp, RestoreDevice()).restore_backup("backup_20261001_080000.json")
+
+        assert [(r["packageName"], r["status"]) for r in result["results"]] == [
+            ("com.facebook.katana", "alreadyInstalled"), ("com.netflix.mediaclient", "restored"),
+            ("com.samsung.android.bixby.agent", "failed"), ("com.spotify.music", "notAvailable")]
+        assert result["results"][2]["message"] == "Failed to reinstall: Package not found"
+        assert "Play Store" in result["results"][3]["message"]
+        assert result["success"] is False and result["restored"] == 1 and result["failed"] == 1
+        assert result["message"] == "Restored 1 of 4 packages (1 already installed, 1 not available, 1 failed)"
+
+        missing = BackupManager(tmp, RestoreDevice()).restore_backup("backup_missing.json")
+        assert missing == {"success": False, "message": "Backup not found: backup_missing.json"}
+
+        # Unplugged mid-restore: the rest of the backup is not attempted
+        unplugged = BackupManager(tmp, UnpluggingDevice()).restore_backup("backup_20261001_080000.json")
+        assert [(r["packageName"], r["status"]) for r in unplugged["results"]] == [
+            ("com.facebook.katana", "alreadyInstalled"), ("com.netflix.mediaclient", "restored"),
+            ("com.samsung.android.bixby.agent", "failed")]
+        assert unplugged["results"][2]["message"] == "No device connected" and unplugged["success"] is False
+    print("  └─ ✅ Restore results OK")
+    return True
+
+
+def test_backup_names():
+    """Test that backup and app data names reaching outside the backup directory are refused"""
+    print("\n🚧 Testing backup names...")
+    with tempfile.TemporaryDirectory() as parent:
+        manager = BackupManager(os.path.join(parent, "backups"), RestoreDevice())
+        write_backup(parent, "backup_outside.json", ["com.netflix.mediaclient"])
+        for name in ["../backup_outside.json", os.path.join(parent, "backup_outside.json"), "..\\backup_outside.json",
+                     "old/../backup_20261001_080000.json", "settings.txt", None]:
+            refused = {"success": False, "message": f"Invalid backup name: {name}"}
+            assert manager.restore_backup(name) == refused, name
+            assert manager.preview_transfer(name) == refused, name
+            assert manager.delete_backup(name) == refused, name
+        assert os.path.exists(os.path.join(parent, "backup_outside.json"))
+
+        # A hand-edited backup pointing its app data elsewhere
+        write_backup(manager.backup_dir, "backup_edited.json", ["com.facebook.katana"], dataBackup="../data.ab")
+        open(os.path.join(parent, "data.ab"), 'wb').close()
+        restored = manager.restore_backup("backup_edited.json", restore_data=True)
+        assert restored["dataRestore"] == {"success": False, "message": "Invalid app data file: ../data.ab"}
+        assert manager.delete_backup("backup_edited.json")["success"]
+        assert os.path.exists(os.path.join(parent, "data.ab"))
+    print("  └─ ✅ Backup names OK")
+    return True
+
+
+def test_device_filtering():
+    """Test that backups record the device, list per device and group by serial"""
+    print("\n📱 Testing backups per device...")
+    with tempfile.TemporaryDirectory() as tmp:
+        nicknames = {"R58M123": "Work phone"}
+        manager = BackupManager(tmp, FakeADB(serial="R58M123"), nickname_for=nicknames.get)
+        created = manager.create_backup(["com.facebook.katana"], {"model": "SM-G991B"})
+        with open(created["backupPath"], encoding="utf-8") as f:
+            saved = json.load(f)
+        assert saved["serial"] == "R58M123" and saved["nickname"] == "Work phone"
+
+        write_backup(tmp, "backup_20260901_080000.json", ["com.netflix.mediaclient"], serial="PIXEL7",
+                     timestamp="2026-09-01T08:00:00")
+        write_backup(tmp, "backup_20250101_080000.json", ["com.spotify.music"], timestamp="2025-01-01T08:00:00")
+
+        assert len(manager.list_backups()) == 3
+        # Backups made before serials were recorded may belong to any device
+        mine = manager.list_backups("R58M123")
+        assert [b["name"] for b in mine] == [created["backupName"], "backup_20250101_080000.json"]
+        assert mine[0]["nickname"] == "Work phone" and mine[0]["deviceInfo"] == {"model": "SM-G991B"}
+
+        nicknames["R58M123"] = "Old phone"
+        groups = group_backups(manager.list_backups())
+        assert [g["serial"] for g in groups] == ["R58M123", "PIXEL7", None]
+        assert groups[0]["nickname"] == "Old phone", "a rename relabels the old backups"
+        assert groups[1]["backups"][0]["packageCount"] == 1
+    print("  └─ ✅ Backups per device OK")
+    return True
+
+
+def test_retention():
+    """Test which backups each retention rule expires, the preview and the cleanup with its app data"""
+    print("\n🧹 Testing backup retention...")
+    with tempfile.TemporaryDirectory() as tmp:
+        now = datetime.now()
+        for days in (1, 5, 40):
+            stamp = now - timedelta(days=days)
+            name = f"backup_{stamp:%Y%m%d_%H%M%S}"
+            write_backup(tmp, f"{name}.json", ["com.facebook.katana"], serial="R58M123",
+                         timestamp=stamp.isoformat(), dataBackup=f"{name}.ab" if days == 40 else None)
+        old = now - timedelta(days=90)
+        write_backup(tmp, f"backup_{old:%Y%m%d_%H%M%S}.json", ["com.spotify.music"], serial="PIXEL7",
+                     timestamp=old.isoformat())
+        data_file = os.path.join(tmp, f"backup_{now - timedelta(days=40):%Y%m%d_%H%M%S}.ab")
+        open(data_file, 'wb').close()
+        manager = BackupManager(tmp, FakeADB())
+
+        by_count = select_expired(manager.list_backups(), keep_per_device=2, max_age_days=0, now=now)
+        assert [b["serial"] for b in by_count] == ["R58M123"]
+        assert by_count[0]["reason"] == "More than 2 backups for this device"
+        by_age = select_expired(manager.list_backups(), keep_per_device=0, max_age_days=30, now=now)
+        assert [b["reason"] for b in by_age] == ["Older than 30 days"] * 2
+        assert select_expired(manager.list_backups(), 0, 0, now=now) == []
+
+        preview = manager.preview_retention_cleanup(keep_per_device=1, max_age_days=60)
+        assert preview["count"] == 3 and len(manager.list_backups()) == 4, "a preview deletes nothing"
+
+        applied = manager.apply_retention(keep_per_device=1, max_age_days=60)
+        assert applied["success"] and sorted(applied["deleted"]) == sorted(b["name"] for b in preview["backups"])
+        remaining = manager.list_backups()
+        assert [(b["serial"], b["timestamp"][:10]) for b in remaining] == [
+            ("R58M123", (now - timedelta(days=1)).isoformat()[:10])]
+        assert not os.path.exists(data_file), "the app data of a deleted backup goes with it"
+    print("  └─ ✅ Backup retention OK")
+    return True
+
+
+def test_full_backup():
+    """Test the zip of a full backup, its progress events and restoring from its APKs"""
+    print("\n🗜️  Testing full backup...")
+    with tempfile.TemporaryDirectory() as tmp:
+        events, done = [], threading.Event()
+
+        def emit(kind, payload, job_id=None):
+            events.append((kind, payload, job_id))
+            if kind == "backup_complete":
+                done.set()
+
+        adb = ExportingDevice()
+        manager = BackupManager(tmp, adb, emit)
+        packages = ["com.netflix.mediaclient", "com.samsung.android.bixby.agent", "com.spotify.music"]
+        started = manager.create_full_backup(packages, {"model": "SM-G991B"})
+        assert started["success"] and done.wait(5), started
+
+        progress = [payload["packageName"] for kind, payload, _ in events if kind == "backup_progress"]
+        assert progress == packages and {job_id for _, _, job_id in events} == {started["jobId"]}
+        complete = events[-1][1]
+        assert complete["success"] and complete["apkPackages"] == 1
+        assert complete["skipped"] == {"com.samsung.android.bixby.agent": "System app; restored with install-existing",
+                                       "com.spotify.music": "Not installed; no APK to export"}
+
+        with zipfile.ZipFile(complete["backupPath"]) as archive:
+            assert sorted(archive.namelist()) == [
+                "apks/com.netflix.mediaclient/base.apk", "apks/com.netflix.mediaclient/split_config.arm64_v8a.apk",
+                ZIP_MANIFEST]
+            manifest = json.loads(archive.read(ZIP_MANIFEST))
+        assert manifest["full"] and manifest["packages"] == packages
+        assert not any(name.endswith(".partial") for name in os.listdir(tmp))
+        assert manager.list_backups()[0]["full"] is True
+
+        # Netflix is gone from the device, so only the exported APKs can bring it back
+        adb.shell_output.update({"pm list packages": "", "pm list packages -u": ""})
+        restored = manager.restore_backup(started["backupName"])
+        assert restored["results"][0] == {"packageName": "com.netflix.mediaclient", "status": "restored",
+                                          "message": "Installed from APK"}
+        assert adb.installed_apks == [["base.apk", "split_config.arm64_v8a.apk"]]
+        assert [r["status"] for r in restored["results"][1:]] == ["notAvailable", "notAvailable"]
+    print("  └─ ✅ Full backup OK")
+    return True
+
+
+def test_app_data_backup():
+    """Test backing up and restoring app data with adb backup, and refusing it where Android dropped it"""
+    print("\n💾 Testing app data backup...")
+    with tempfile.TemporaryDirectory() as tmp:
+        adb = DataDevice(sdk=30)
+        manager = BackupManager(tmp, adb)
+        created = manager.create_backup(["com.facebook.katana"], include_data=True)
+        assert created["success"] and created["dataBackup"]["sizeBytes"] == 4096
+        archive = created["backupPath"].replace(".json", ".ab")
+        assert adb.runs[-1] == [adb.adb_path, "backup", "-f", archive, "-noapk", "com.facebook.katana"]
+        assert manager.list_backups()[0]["hasData"] is True
+
+        restored = manager.restore_backup(created["backupName"], restore_data=True)
+        assert restored["dataRestore"] == {"success": True, "message": "App data restore finished"}
+        assert adb.runs[-1] == [adb.adb_path, "restore", archive]
+
+        # A declined backup leaves an empty archive, which is not kept
+        adb.archive_bytes = 0
+        declined = manager.create_backup(["com.netflix.mediaclient"], include_data=True)
+        assert declined["success"] and declined["dataBackup"]["success"] is False
+        assert "declined on the device" in declined["dataBackup"]["message"]
+        assert not os.path.exists(declined["backupPath"].replace(".json", ".ab"))
+        no_data = manager.restore_backup(declined["backupName"], restore_data=True)["dataRestore"]
+        assert no_data == {"success": False, "message": "This backup has no app data"}
+
+        android12 = DataDevice(sdk=31)
+        unsupported = BackupManager(tmp, android12).create_backup(["com.facebook.katana"], include_data=True)
+        assert unsupported["dataBackup"]["unsupported"]
+        assert "only works up to Android 11 (API 30)" in unsupported["dataBackup"]["message"]
+        assert not any(run[1] == "backup" for run in android12.runs)
+    print("  └─ ✅ App data backup OK")
+    return True
+
+
+def main():
+    """Run all backup manager tests"""
+    tests = [test_restore_results, test_backup_names, test_device_filtering, test_retention, test_full_backup,
+             test_app_data_backup]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,116 @@
+"""
+Test restoring another device's backup: name matching, OEM role mapping and skipped vendor apps
+Runs against fakes - no ADB required
+"""
+import sys
+import os
+import json
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from backup_manager import BackupManager
+from backup_mapping import counterpart, guess_vendor, map_packages, vendor_for_manufacturer
+
+
+# A Samsung phone's backup restored onto a Pixel
+BACKUP_PACKAGES = [
+    "com.spotify.music",                # Installed on the Pixel
+    "com.android.egg",                  # Uninstalled for the user, still on the system image
+    "com.sec.android.gallery3d",        # Samsung Gallery -> Google Photos
+    "com.sec.android.app.clockpackage", # Samsung Clock -> Google Clock (already installed)
+    "com.samsung.android.bixby.agent",  # Samsung-only
+    "org.example.sideloaded",           # Only in the full backup's APKs
+    "com.example.store",                # Nowhere
+]
+PIXEL_INSTALLED = {"com.spotify.music", "com.google.android.deskclock", "com.android.chrome"}
+PIXEL_AVAILABLE = PIXEL_INSTALLED | {"com.android.egg", "com.google.android.apps.photos"}
+
+
+class PixelDevice(FakeADB):
+    """A Pixel recording the reinstalls and APK installs a mapped restore makes"""
+
+    def __init__(self):
+        super().__init__()
+        self.reinstalled = []
+        self.installed_apks = []
+
+    def answer(self, command):
+        if command == "getprop ro.product.manufacturer":
+            return "Google\n"
+        packages = PIXEL_AVAILABLE if command == "pm list packages -u" else PIXEL_INSTALLED
+        return "".join(f"package:{p}\n" for p in sorted(packages))
+
+    def reinstall_package(self, package):
+        self.reinstalled.append(package)
+        return {"success": True, "message": f"Reinstalled {package}"}
+
+    def install_apks(self, paths):
+        self.installed_apks.extend(os.path.basename(p) for p in paths)
+        return {"success": True, "message": "Installed"}
+
+
+def test_map_packages():
+    """Test each transfer status and the vendor detection"""
+    print("\n🗺️  Testing package mapping...")
+    assert vendor_for_manufacturer("Xiaomi\n") == "xiaomi" and vendor_for_manufacturer("POCO") == "xiaomi"
+    assert vendor_for_manufacturer("Fairphone") is None
+    assert guess_vendor(BACKUP_PACKAGES) == "samsung" and guess_vendor(["com.spotify.music"]) is None
+
+    entries = map_packages(BACKUP_PACKAGES, "google", PIXEL_INSTALLED, PIXEL_AVAILABLE,
+                           {"org.example.sideloaded": ["base.apk"]})
+    by_name = {e["packageName"]: e for e in entries}
+    assert [by_name[p]["status"] for p in BACKUP_PACKAGES] == [
+        "present", "restorable", "mapped", "mapped", "incompatible", "installable", "missing"]
+    gallery = by_name["com.sec.android.gallery3d"]
+    assert gallery["target"] == "com.google.android.apps.photos" and gallery["role"] == "gallery"
+    assert not gallery["targetInstalled"] and by_name["com.sec.android.app.clockpackage"]["targetInstalled"]
+    assert "Samsung firmware" in by_name["com.samsung.android.bixby.agent"]["message"]
+
+    assert counterpart("com.spotify.music", "xiaomi", PIXEL_AVAILABLE) is None
+    # The vendor's own app wins over the Google fallback
+    assert counterpart("com.sec.android.gallery3d", "xiaomi",
+                       {"com.miui.gallery", "com.google.android.apps.photos"})["packageName"] == "com.miui.gallery"
+    # Same-vendor packages are never incompatible
+    assert map_packages(["com.samsung.android.bixby.agent"], "samsung", set(), set())[0]["status"] == "missing"
+    print("  └─ ✅ Package mapping OK")
+    return True
+
+
+def test_apply_transfer():
+    """Test that only restorable, mapped and installable packages are touched"""
+    print("\n📲 Testing backup transfer...")
+    with tempfile.TemporaryDirectory() as backup_dir:
+        with open(os.path.join(backup_dir, "backup_20250101_120000.json"), 'w') as f:
+            json.dump({"serial": "SAMSUNG1", "packages": BACKUP_PACKAGES[:5], "count": 5}, f)
+        adb = PixelDevice()
+        manager = BackupManager(backup_dir, adb_operations=adb)
+
+        preview = manager.preview_transfer("backup_20250101_120000.json")
+        assert preview["success"] and preview["sourceVendor"] == "samsung"
+        assert preview["counts"]["mapped"] == 2 and preview["counts"]["incompatible"] == 1
+        assert adb.reinstalled == []
+
+        result = manager.apply_transfer("backup_20250101_120000.json")
+        assert result["success"] and result["restored"] == 2
+        assert adb.reinstalled == ["com.android.egg", "com.google.android.apps.photos"]
+        assert not manager.apply_transfer("backup_missing.json")["success"]
+    print("  └─ ✅ Backup transfer OK")
+    return True
+
+
+def main():
+    """Run all backup mapping tests"""
+    tests = [test_map_packages, test_apply_transfer]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
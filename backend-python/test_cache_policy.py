@@ -0,0 +1,146 @@
+"""
+Test stale-while-revalidate behaviour of the caches
+Runs against fake devices - no ADB required
+"""
+import sys
+import os
+import tempfile
+import threading
+import time
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from cache_policy import CacheStats, Revalidator, cache_report, estimate_memory, is_stale, CACHE_MAX_AGE
+from analysis_cache import AnalysisCache
+from package_cache import PackageCache
+from system_health import SystemHealth
+
+
+class Recorder:
+    def __init__(self):
+        self.events = []
+        self.done = threading.Event()
+
+    def __call__(self, kind, payload, job_id=None):
+        self.events.append((kind, payload))
+        self.done.set()
+
+
+class ListingDevice(FakeADB):
+    """Counts package listings"""
+
+    def __init__(self):
+        super().__init__()
+        self.listings = 0
+
+    def list_packages(self, package_type):
+        self.listings += 1
+        return [{"packageName": "com.facebook.katana", "isSystem": False}]
+
+    def supports(self, feature):
+        return False
+
+
+def test_staleness():
+    """Test the max-age rule"""
+    print("\n⏳ Testing staleness rule...")
+    now = time.time()
+    assert not is_stale("packages", now - 10, now)
+    assert is_stale("packages", now - CACHE_MAX_AGE["packages"] - 1, now)
+    assert is_stale("health", None)
+    print("  └─ ✅ Staleness rule OK")
+    return True
+
+
+def test_package_cache_revalidates():
+    """Test that a stale listing is returned at once and re-listed in the background"""
+    print("\n📦 Testing package cache revalidation...")
+    recorder = Recorder()
+    adb = ListingDevice()
+    cache = PackageCache(adb, revalidator=Revalidator(recorder))
+    page = cache.query()
+    assert not page["stale"] and adb.listings == 1
+
+    cache._entries["SERIAL1"]["time"] -= CACHE_MAX_AGE["packages"] + 1
+    page = cache.query()
+    assert page["stale"] and page["total"] == 1, "stale data is served immediately"
+    assert recorder.done.wait(5)
+    assert recorder.events == [("cache_updated", {"cache": "packages", "key": "SERIAL1",
+                                                  "fetchedAt": recorder.events[0][1]["fetchedAt"]})]
+    assert adb.listings == 2 and not cache.query()["stale"]
+    print("  └─ ✅ Package cache revalidation OK")
+    return True
+
+
+def test_health_and_analysis():
+    """Test the cached health snapshot and stale AI verdicts"""
+    print("\n🩺 Testing health and analysis staleness...")
+    recorder = Recorder()
+    health = SystemHealth(ListingDevice(), revalidator=Revalidator(recorder))
+    first = health.get_cached_health()
+    assert first["stale"] is False
+    health._cache["SERIAL1"]["snapshot"]["timestamp"] -= CACHE_MAX_AGE["health"] + 1
+    assert health.get_cached_health()["stale"]
+    assert recorder.done.wait(5) and recorder.events[0][1]["cache"] == "health"
+    assert not health.get_cached_health()["stale"]
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        analyses = AnalysisCache(data_dir)
+        analysis = {"riskCategory": "Safe", "safeToRemove": True, "summary": "Social app"}
+        assert analyses.record("com.facebook.katana", analysis)["stale"] is False
+        analyses._risks["com.facebook.katana"]["analyzedAt"] -= CACHE_MAX_AGE["analysis"] + 1
+        packages = analyses.apply([{"packageName": "com.facebook.katana"}])
+        assert packages[0]["aiRisk"]["stale"]
+        assert "stale" not in analyses._risks["com.facebook.katana"], "the flag is not persisted"
+    print("  └─ ✅ Health and analysis staleness OK")
+    return True
+
+
+def test_cache_diagnostics():
+    """Test hit/miss counting and the diagnostics report"""
+    print("\n📊 Testing cache diagnostics...")
+    adb = ListingDevice()
+    cache = PackageCache(adb)
+    cache.query()
+    cache.query()
+    cache.packages()
+    report = cache.cache_diagnostics()
+    assert report["name"] == "packages" and report["entries"] == 1
+    assert (report["hits"], report["misses"]) == (2, 1)
+    assert report["hitRatePercent"] == 66.7 and report["formatted"]["hitRate"] == "66.7%"
+    assert report["memoryBytes"] > 0 and report["formatted"]["memory"].endswith("KB")
+    assert 0 <= report["newestAgeSeconds"] <= report["oldestAgeSeconds"] < 5
+    assert report["maxAgeSeconds"] == CACHE_MAX_AGE["packages"]
+
+    empty = cache_report("labels", CacheStats(), {}, [], 0)
+    assert empty["hitRatePercent"] is None and empty["oldestAgeSeconds"] is None
+    assert empty["maxAgeSeconds"] is None
+
+    shared = ["x" * 1000]
+    assert estimate_memory([shared, shared]) < 2 * estimate_memory(shared), "shared objects count once"
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        analyses = AnalysisCache(data_dir)
+        analyses.record("com.facebook.katana", {"riskCategory": "Safe", "summary": "Social app"})
+        analyses.apply([{"packageName": "com.facebook.katana"}, {"packageName": "com.android.chrome"}])
+        report = analyses.cache_diagnostics()
+        assert (report["entries"], report["hits"], report["misses"]) == (1, 1, 1)
+    print("  └─ ✅ Cache diagnostics OK")
+    return True
+
+
+def main():
+    """Run all cache policy tests"""
+    tests = [test_staleness, test_package_cache_revalidates, test_health_and_analysis, test_cache_diagnostics]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
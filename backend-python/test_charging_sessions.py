@@ -0,0 +1,139 @@
+"""
+Test charge session tracking: battery parsing, opening and closing sessions
+from polls, unplugging with the computer, and the charging insights
+Runs against canned `dumpsys battery` output - no ADB required
+"""
+import sys
+import os
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from charging_sessions import ChargingTracker, charging_insights, parse_battery_status
+
+
+def battery_output(level, charger=None, temperature=300, status=2):
+    return (
+        "Current Battery Service state:\n"
+        f"  AC powered: {'true' if charger == 'ac' else 'false'}\n"
+        f"  USB powered: {'true' if charger == 'usb' else 'false'}\n"
+        "  Wireless powered: false\n"
+        "  Max charging current: 3000000\n"
+        "  Max charging voltage: 9000000\n"
+        f"  status: {status}\n"
+        "  health: 2\n"
+        "  present: true\n"
+        f"  level: {level}\n"
+        "  scale: 100\n"
+        "  voltage: 4213\n"
+        f"  temperature: {temperature}\n"
+        "  technology: Li-ion\n"
+    )
+
+
+class BatteryDevice(FakeADB):
+    """Answers dumpsys battery with `output`"""
+
+    def __init__(self):
+        super().__init__()
+        self.output = battery_output(50)
+
+    def answer(self, command):
+        assert command == "dumpsys battery"
+        return self.output
+
+
+def test_parse():
+    """Test reading the charger, level, temperature and charger power"""
+    print("\n🔌 Testing battery status parsing...")
+    status = parse_battery_status(battery_output(57, "ac", 352))
+    assert status == {"charger": "ac", "level": 57, "full": False, "temperatureC": 35.2, "maxChargingWatts": 27.0}
+    assert parse_battery_status(battery_output(100, status=5))["full"] is True
+    assert parse_battery_status(battery_output(80))["charger"] is None
+    assert parse_battery_status("Can't find service: battery\n") is None
+    print("  └─ ✅ Parsing OK")
+    return True
+
+
+def test_sessions():
+    """Test a session from plug-in to unplug, a short one dropped and one ended by disconnecting"""
+    print("\n🔋 Testing charge sessions...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        adb, events = BatteryDevice(), []
+        tracker = ChargingTracker(adb, lambda kind, payload, job_id=None: events.append((kind, payload)), data_dir)
+        t = 1_000_000.0
+        tracker.poll(t)
+        assert tracker.get_charging_sessions()["current"] is None
+
+        for minute, (level, temperature) in enumerate([(20, 300), (35, 380), (50, 415), (60, 400)]):
+            adb.output = battery_output(level, "ac", temperature)
+            tracker.poll(t + 60 + minute * 1200)
+        current = tracker.get_charging_sessions()["current"]
+        assert current["endedBy"] == "ongoing" and current["gained"] == 40 and current["end"] is None
+
+        adb.output = battery_output(60)
+        tracker.poll(t + 7200)
+        result = tracker.get_charging_sessions()
+        [session] = result["sessions"]
+        assert result["current"] is None
+        assert session["endedBy"] == "unplugged" and session["charger"] == "ac"
+        assert session["startLevel"] == 20 and session["endLevel"] == 60 and session["durationSeconds"] == 3600
+        assert session["percentPerHour"] == 40.0 and session["peakTemperatureC"] == 41.5
+        assert session["maxChargingWatts"] == 27.0
+        assert events == [("charging_session_ended", {**session, "serial": "SERIAL1"})]
+
+        # Plugged in for one poll only: a cable wiggle, not a session
+        adb.output = battery_output(60, "usb")
+        tracker.poll(t + 7260)
+        adb.output = battery_output(60)
+        tracker.poll(t + 7320)
+        assert len(tracker.get_charging_sessions()["sessions"]) == 1 and len(events) == 1
+
+        # Charging from this computer: unplugging disconnects the device too
+        adb.output = battery_output(30, "usb")
+        tracker.poll(t + 9000)
+        adb.output = battery_output(45, "usb")
+        tracker.poll(t + 12600)
+        adb.connected = False
+        tracker.poll(t + 12660)
+        adb.connected = True
+        latest = tracker.get_charging_sessions()["sessions"][0]
+        assert latest["endedBy"] == "disconnected" and latest["end"] == t + 12600 and latest["gained"] == 15
+    print("  └─ ✅ Sessions OK")
+    return True
+
+
+def test_insights():
+    """Test speed, heat and time-at-full insights"""
+    print("\n💡 Testing charging insights...")
+
+    def session(charger, speed, peak=35.0, hours_at_full=0):
+        return {"charger": charger, "percentPerHour": speed, "peakTemperatureC": peak, "hoursAtFull": hours_at_full}
+
+    insights = charging_insights([session("usb", 20.0), session("ac", 60.0, 42.5), session("ac", 50.0, 38.0, 3.5),
+                                  session("ac", None)])
+    assert insights[0] == "AC charging averages 55% per hour over 2 sessions"
+    assert insights[1] == "USB charging averages 20% per hour over 1 session"
+    assert insights[2] == "The wall charger is 2.8x faster than charging over USB"
+    assert insights[3].startswith("1 of the last 3 sessions went above 40°C (up to 42.5°C)")
+    assert insights[4].startswith("1 session stayed at 100% for 2+ hours")
+    assert charging_insights([]) == []
+    print("  └─ ✅ Insights OK")
+    return True
+
+
+def main():
+    """Run all charging session tests"""
+    tests = [test_parse, test_sessions, test_insights]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
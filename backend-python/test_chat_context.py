@@ -0,0 +1,167 @@
+"""
+Test chat history compaction, the device context of chats, resolving
+package ids pasted into chat messages and the device power line
+Runs against canned device output - no ADB required
+"""
+import sys
+import os
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from analysis_cache import AnalysisCache
+from chat_context import DeviceContextBuilder, compact_history, describe_power
+
+
+PACKAGES = {
+    "": "package:com.sec.android.daemonapp\npackage:com.facebook.katana\n",
+    "-d": "package:com.facebook.katana\n",
+    "-u": "package:com.sec.android.daemonapp\npackage:com.facebook.katana\npackage:com.samsung.android.bixby.agent\n",
+}
+
+
+def make_adb(connected=True):
+    """A Samsung phone answering package lists; connected=False fails like a missing device"""
+    adb = FakeADB({f"pm list packages {flag}".strip(): output for flag, output in PACKAGES.items()})
+    adb.shell_output["getprop ro.product.manufacturer"] = "samsung"
+    adb.connected = connected
+    return adb
+
+
+class FakeHealth:
+    def get_power_info(self, serial):
+        return {"charging": False, "plugType": None, "batteryLevel": 41, "wakeLocks": 3, "screenOnSeconds": 11100}
+
+
+class FakeLabels:
+    def cached_label(self, package):
+        return {"com.sec.android.daemonapp": "Weather"}.get(package)
+
+
+def chat(turns):
+    """Alternating user/assistant messages, each naming one package"""
+    return [{"role": "user" if i % 2 == 0 else "assistant",
+             "content": f"Turn {i} is about com.example.app{i}. More detail follows here."} for i in range(turns)]
+
+
+def test_compact_history():
+    """Test that older turns become one summary exchange and recent turns are kept"""
+    print("\n🗜️  Testing history compaction...")
+    history = chat(24)
+    assert compact_history(history[:20], limit=20, keep_recent=10) == {"messages": history[:20], "compactedCount": 0}
+    # Roles the providers do not take in the history are dropped
+    assert compact_history([{"role": "system", "content": "x"}] + history[:2], 20, 10)["messages"] == history[:2]
+
+    result = compact_history(history, limit=20, keep_recent=10)
+    assert result["compactedCount"] == 14 and result["messages"][2:] == history[14:]
+    summary = result["messages"][0]["content"]
+    assert [m["role"] for m in result["messages"][:2]] == ["user", "assistant"]
+    assert "- User asked: Turn 0 is about com.example.app0." in summary
+    assert "- Assistant answered: Turn 13 is about com.example.app13." in summary
+    assert summary.endswith("Packages discussed: " + ", ".join(f"com.example.app{i}" for i in range(14)))
+    assert "com.example.app14" not in summary
+
+    # The kept slice starts with a user turn, so an odd keep_recent keeps one less
+    odd = compact_history(history, limit=20, keep_recent=9)
+    assert odd["compactedCount"] == 16 and odd["messages"][2]["role"] == "user"
+
+    # A model summary is used when it works, the local one when it fails
+    assert compact_history(history, 20, 10, summarize=lambda older: "They asked about apps.")["messages"][0][
+        "content"].endswith("They asked about apps.")
+    failing = compact_history(history, 20, 10, summarize=lambda older: 1 / 0)
+    assert failing["messages"][0]["content"] == summary
+    print("  └─ ✅ History compaction OK")
+    return True
+
+
+def test_device_context():
+    """Test the device facts attached to chats, their caching and a missing device"""
+    print("\n📱 Testing device context...")
+    with tempfile.TemporaryDirectory() as tmp:
+        adb = make_adb()
+        builder = DeviceContextBuilder(adb, None, AnalysisCache(tmp))
+        context = builder.build([{"role": "user", "content": "Is com.samsung.android.bixby.agent safe?"}],
+                                "and com.facebook.katana?").split("\n")
+        assert context[0].startswith("Device: samsung")
+        assert context[1] == "Packages: 2 installed, 1 disabled, 1 removed for this user"
+        assert context[2].startswith("- com.samsung.android.bixby.agent: removed, local safety rating")
+        assert context[3].startswith("- com.facebook.katana: disabled, local safety rating")
+
+        # Facts are read once per DEVICE_FACTS_TTL, and again after an invalidate
+        listings = adb.commands.count("pm list packages")
+        builder.build([], "hi")
+        assert adb.commands.count("pm list packages") == listings
+        builder.invalidate()
+        builder.build([], "hi")
+        assert adb.commands.count("pm list packages") == listings + 1
+
+        assert DeviceContextBuilder(make_adb(connected=False)).build([], "hi") is None
+    print("  └─ ✅ Device context OK")
+    return True
+
+
+def test_resolve_message():
+    """Test that pasted ids are described with name, state, rating and earlier verdict"""
+    print("\n🔎 Testing package id resolution...")
+    with tempfile.TemporaryDirectory() as tmp:
+        analyses = AnalysisCache(tmp)
+        analyses.record("com.facebook.katana", {"riskCategory": "Safe", "safeToRemove": True,
+                                                "summary": "Facebook app. Removable without side effects."})
+        builder = DeviceContextBuilder(make_adb(), FakeLabels(), analyses)
+        resolved = builder.resolve_message(
+            "What are com.sec.android.daemonapp, com.facebook.katana and com.samsung.android.bixby.agent? "
+            "See support.example.com")
+        lines = resolved.split("\n")
+        assert lines[0].startswith('- com.sec.android.daemonapp ("Weather"): installed, local safety rating')
+        assert lines[1].startswith("- com.facebook.katana: disabled")
+        assert lines[1].endswith("earlier AI verdict Safe: Facebook app.")
+        assert lines[2].startswith("- com.samsung.android.bixby.agent: removed")
+        assert lines[2].endswith("Samsung Bixby")
+        assert len(lines) == 3  # The web domain matches nothing
+
+        assert builder.resolve_message("How do I free up storage?") is None
+
+        # The device context leaves the resolved ids to the resolution block
+        context = builder.build([{"role": "user", "content": "is com.google.android.youtube safe?"}],
+                                "and com.facebook.katana?", include_message=False)
+        assert "com.google.android.youtube" in context and "com.facebook.katana" not in context
+
+        # Without a device, ids known locally are still described
+        offline = DeviceContextBuilder(make_adb(connected=False), None, analyses)
+        resolved = offline.resolve_message("com.facebook.katana or com.unknown.thing.app?")
+        assert resolved.startswith("- com.facebook.katana: device state unknown")
+        assert "com.unknown.thing.app" not in resolved
+    print("  └─ ✅ Package id resolution OK")
+    return True
+
+
+def test_power_context():
+    """Test that the device context says whether the device runs on battery"""
+    print("\n🔋 Testing power context...")
+    with tempfile.TemporaryDirectory() as tmp:
+        analyses = AnalysisCache(tmp)
+        context = DeviceContextBuilder(make_adb(), None, analyses, FakeHealth()).build([], "hi")
+    assert context.split("\n")[2] == ("Power: on battery, 41%, screen on 3h 5m since last charge, 3 wake locks held "
+                                      "(weigh wake locks and background activity higher in advice)")
+    charging = describe_power({"charging": True, "plugType": "ac", "batteryLevel": None, "wakeLocks": 0,
+                               "screenOnSeconds": 300})
+    assert charging == "Power: charging (ac), screen on 5m since last charge, 0 wake locks held"
+    print("  └─ ✅ Power context OK")
+    return True
+
+
+def main():
+    """Run all chat context tests"""
+    tests = [test_compact_history, test_device_context, test_resolve_message, test_power_context]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,128 @@
+"""
+Test adb and Android version checks, capability probing and the features
+gated on them
+Runs against a fake device - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from adb_operations import ADBError
+from compatibility import (build_probe_script, compatibility_warnings, parse_adb_version, parse_probe_output,
+                           version_tuple)
+from device_tweaks import DeviceTweaks
+from fake_adb import FakeADB
+
+
+ADB_VERSION = (
+    "Android Debug Bridge version 1.0.39\n"
+    "Version 0.0.1-4500957\n"
+    "Installed as /usr/bin/adb\n"
+)
+
+# Every probed command answers, so only the API level decides
+ALL_PRESENT = ("@@cmd_package\nPackage manager (package) commands:\n@@appops\nAppOps service (appops) commands:\n"
+               "@@role_service\nRoles:\n@@settings_command\n1\n")
+
+# An Android 11 ROM without the `cmd` binary, whose appops tool still works
+STRIPPED_ROM = ("@@cmd_package\n/system/bin/sh: cmd: not found\n@@appops\nAppOps service (appops) commands:\n"
+                "@@role_service\nCan't find service: role\n@@settings_command\n1\n")
+
+
+def make_adb(sdk):
+    return FakeADB({build_probe_script(): ALL_PRESENT}, sdk=sdk, run_output=ADB_VERSION)
+
+
+def test_versions():
+    """Test parsing adb version output and the warnings for old tools and devices"""
+    print("\n🔢 Testing version parsing...")
+    versions = parse_adb_version(ADB_VERSION)
+    assert versions == {"adbVersion": "1.0.39", "platformToolsVersion": "0.0.1"}
+    assert version_tuple("34.0.5") == (34, 0, 5) and version_tuple("34.rc") is None
+
+    warnings = compatibility_warnings(versions, 22)
+    assert "Platform-tools 0.0.1 is older than 28.0.0" in warnings[0]
+    assert any("needs Android 6 (API 23) or newer; this device runs Android 5.1 (API 22)" in w for w in warnings)
+    assert compatibility_warnings({"platformToolsVersion": "35.0.2"}, 34) == []
+    assert "Could not determine" in compatibility_warnings({"platformToolsVersion": None}, None)[0]
+    print("  └─ ✅ Version parsing OK")
+    return True
+
+
+def test_feature_gating():
+    """Test that features the device's API level lacks are refused with a readable message"""
+    print("\n🚧 Testing feature gating...")
+    lollipop = make_adb(22)
+    report = lollipop.get_compatibility()
+    assert report["platformToolsVersion"] == "0.0.1" and report["sdkLevel"] == 22
+    assert report["capabilities"]["user_uninstall"] and not report["capabilities"]["trim_caches"]
+
+    trimmed = DeviceTweaks(lollipop).trim_caches()
+    assert trimmed["success"] is False and trimmed["unsupported"]
+    assert trimmed["message"] == ("Trimming app caches (pm trim-caches) needs Android 6 (API 23) or newer; "
+                                  "this device runs Android 5.1 (API 22)")
+    assert not any("trim-caches" in c for c in lollipop.commands), "nothing reaches the shell"
+
+    # adb backup stops working for modern apps after Android 11
+    android12 = make_adb(31)
+    assert android12.supports("trim_caches") and not android12.supports("adb_backup")
+    assert DeviceTweaks(android12).trim_caches()["success"]
+    print("  └─ ✅ Feature gating OK")
+    return True
+
+
+class StrippedDevice(FakeADB):
+    """Answers the probe with STRIPPED_ROM, or fails it while `probe_fails`"""
+
+    def __init__(self):
+        super().__init__({build_probe_script(): STRIPPED_ROM}, sdk=30)
+        self.probe_fails = False
+
+    def shell(self, command, timeout=30):
+        if command == build_probe_script() and self.probe_fails:
+            raise ADBError("Command timed out")
+        return super().shell(command, timeout)
+
+
+def test_capability_probing():
+    """Test that probe results override the API level and pick the fallback commands"""
+    print("\n🔍 Testing capability probing...")
+    assert parse_probe_output(STRIPPED_ROM) == {"cmd_package": False, "appops": True, "role_service": False,
+                                                "settings_command": True}
+
+    adb = StrippedDevice()
+    probe = adb.probe_device_capabilities()
+    assert probe["serial"] == "SERIAL1" and probe["probed"]["cmd_package"] is False
+    assert probe["capabilities"]["role_service"] is False and probe["capabilities"]["thermal_service"]
+
+    result = DeviceTweaks(adb).restrict_background("com.facebook.katana")
+    assert result["success"], result
+    assert "appops set com.facebook.katana RUN_ANY_IN_BACKGROUND ignore" in adb.commands
+    assert not any(c.startswith("cmd appops") for c in adb.commands), "cmd is missing on this ROM"
+
+    # The probe runs once per device, and a failed one falls back to the API level
+    probes = adb.commands.count(build_probe_script())
+    adb.capabilities()
+    assert adb.commands.count(build_probe_script()) == probes == 1
+    adb.probe_fails = True
+    assert adb.probe_device_capabilities()["probed"] == {}
+    assert adb.supports("cmd_package") and adb.supports("role_service")
+    print("  └─ ✅ Capability probing OK")
+    return True
+
+
+def main():
+    """Run all compatibility tests"""
+    tests = [test_versions, test_feature_gating, test_capability_probing]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,124 @@
+"""
+Test reloading hand-edited config files and directories
+Runs against a temporary data directory - no ADB required
+"""
+import sys
+import os
+import json
+import tempfile
+from pathlib import Path
+sys.path.insert(0, os.path.dirname(__file__))
+
+from config_watcher import ConfigWatcher
+from oem_database import OemDatabase
+from profiles import ProfileStore
+from settings_manager import SettingsManager
+
+
+def write(path, data, tick):
+    """Write JSON with a distinct mtime, so coarse filesystem clocks still see the edit"""
+    path.write_text(json.dumps(data), encoding="utf-8")
+    os.utime(path, (tick, tick))
+
+
+def make_watcher():
+    events = []
+    watcher = ConfigWatcher(lambda kind, payload, job_id=None: events.append((kind, payload)))
+    return watcher, events
+
+
+def test_settings_reload():
+    """Test that an edited settings file is reloaded and announced, and a broken one is refused"""
+    print("\n⚙️  Testing settings reload...")
+    with tempfile.TemporaryDirectory() as tmp:
+        settings = SettingsManager(tmp)
+        settings.update_settings({"ai": {"chatMaxTokens": 400}})
+        watcher, events = make_watcher()
+        watcher.watch("settings", settings.path, settings.reload)
+        watcher.check()
+        assert events == [], "nothing changed yet"
+
+        data = json.loads(settings.path.read_text(encoding="utf-8"))
+        data["ai"]["chatMaxTokens"] = 900
+        write(settings.path, data, 1000)
+        watcher.check()
+        kind, payload = events[-1]
+        assert kind == "config_changed" and payload["name"] == "settings" and payload["success"]
+        assert payload["settings"]["ai"]["chatMaxTokens"] == 900 and settings.get("ai", "chatMaxTokens") == 900
+
+        settings.path.write_text("{not json", encoding="utf-8")
+        watcher.check()
+        assert events[-1][1]["success"] is False
+        assert settings.get("ai", "chatMaxTokens") == 900, "a broken edit keeps the current settings"
+    print("  └─ ✅ Settings reload OK")
+    return True
+
+
+def test_package_database_reload():
+    """Test that the user package database overrides bundled ratings once edited"""
+    print("\n🗂️  Testing package database reload...")
+    with tempfile.TemporaryDirectory() as tmp:
+        user_file = Path(tmp) / "package_database.json"
+        db = OemDatabase(user_file=user_file)
+        assert db.safety_level("samsung", "com.samsung.android.bixby.agent") != "Dangerous"
+        watcher, events = make_watcher()
+        watcher.watch("package_database", user_file, db.reload)
+
+        write(user_file, {"name": "Shop", "packages": {
+            "com.samsung.android.bixby.agent": ["Dangerous", "Needed by our kiosk setup"]}}, 1000)
+        watcher.check()
+        assert events[-1][1] == {"name": "package_database", "path": str(user_file), "success": True,
+                                 "packageCount": 1}
+        assert db.lookup("samsung", "com.samsung.android.bixby.agent")["source"] == "user"
+
+        write(user_file, {"packages": {"com.example.app": ["Harmless", "typo"]}}, 2000)
+        watcher.check()
+        assert events[-1][1]["success"] is False and "unknown safety level" in events[-1][1]["message"]
+        assert db.safety_level("samsung", "com.samsung.android.bixby.agent") == "Dangerous"
+    print("  └─ ✅ Package database reload OK")
+    return True
+
+
+def test_profiles_reload():
+    """Test that added, edited and broken profile files are picked up from the directory"""
+    print("\n📁 Testing profiles reload...")
+    with tempfile.TemporaryDirectory() as tmp:
+        profiles = ProfileStore(tmp)
+        profiles.path.mkdir()
+        watcher, events = make_watcher()
+        watcher.watch("profiles", profiles.path, profiles.reload)
+
+        write(profiles.path / "retail.json", {"name": "Retail", "packages": ["com.facebook.katana"]}, 1000)
+        watcher.check()
+        payload = events[-1][1]
+        assert payload["name"] == "profiles" and payload["success"]
+        assert payload["profiles"] == [{"id": "retail", "name": "Retail", "description": "",
+                                        "packages": ["com.facebook.katana"]}]
+
+        write(profiles.path / "retail.json", {"name": "Retail", "packages": ["com.facebook.katana",
+                                                                            "com.facebook.services"]}, 2000)
+        write(profiles.path / "kids.json", {"name": "Kids", "packages": ["not a package"]}, 2000)
+        watcher.check()
+        listed = profiles.list_profiles()
+        assert listed["profiles"][0]["packages"] == ["com.facebook.katana", "com.facebook.services"]
+        assert listed["skipped"] == [{"file": "kids.json", "message": "not a package name: 'not a package'"}]
+        assert events[-1][1]["skipped"] == listed["skipped"]
+    print("  └─ ✅ Profiles reload OK")
+    return True
+
+
+def main():
+    """Run all config watcher tests"""
+    tests = [test_settings_reload, test_package_database_reload, test_profiles_reload]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
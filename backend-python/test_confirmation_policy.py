@@ -0,0 +1,230 @@
+"""
+Test role-based confirmation policies: loading and validating the policy
+file, what each role requires, typed confirmations, locked roles and the
+commands that enforce it
+Runs against a temporary policy file and a fake device - no ADB required
+"""
+import sys
+import os
+import json
+import tempfile
+from types import SimpleNamespace
+sys.path.insert(0, os.path.dirname(__file__))
+
+from confirmation_policy import ConfirmationPolicy, is_satisfied, validate_policy
+from emergency_recovery import EmergencyRecovery
+from fake_adb import FakeADB
+from undo_manager import UndoStack
+
+
+SAFETY = {
+    "com.facebook.katana": "Safe",
+    "com.google.android.youtube": "Caution",
+    "com.android.chrome": "Expert",
+    "com.android.systemui": "Dangerous",
+}
+
+POLICY = {
+    "role": "junior",
+    "roleLocked": False,
+    "phrase": "I have a backup",
+    "roles": {
+        "junior": {"Safe": "confirm", "Caution": "typedPackage", "Expert": "typedPhrase", "Dangerous": "blocked",
+                   "disable": {"Safe": "none", "Caution": "confirm", "Expert": "typedPackage", "Dangerous": "blocked"},
+                   "batch": "typedPhrase"},
+        "senior": {"Safe": "none", "Caution": "none", "Expert": "confirm", "Dangerous": "typedPackage"},
+    },
+}
+
+
+def write_policy(data_dir, policy):
+    with open(os.path.join(data_dir, "confirmation_policy.json"), 'w', encoding='utf-8') as f:
+        json.dump(policy, f)
+
+
+def test_default_policy():
+    """Test that without a policy file nothing extra is required"""
+    print("\n📄 Testing the default policy...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        policy = ConfirmationPolicy(data_dir)
+        assert policy.get_policy()["custom"] is False
+        assert policy.check("uninstall", list(SAFETY), SAFETY.get, None) is None
+        assert policy.check("disable", ["com.android.systemui"], SAFETY.get, None) is None
+    print("  └─ ✅ Default policy OK")
+    return True
+
+
+def test_requirements():
+    """Test the level per package, the batch minimum and the disable overrides"""
+    print("\n📏 Testing requirements...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        write_policy(data_dir, POLICY)
+        policy = ConfirmationPolicy(data_dir)
+        requirement = policy.requirement("uninstall", ["com.facebook.katana"], SAFETY.get)
+        assert requirement["level"] == "confirm" and requirement["packagesToType"] == []
+        assert requirement["phrase"] is None
+
+        requirement = policy.requirement("uninstall", ["com.google.android.youtube"], SAFETY.get)
+        assert requirement["level"] == "typedPackage"
+        assert requirement["packagesToType"] == ["com.google.android.youtube"]
+
+        # A batch is raised to the batch level, and a typed batch level asks for every name
+        requirement = policy.requirement("uninstall", ["com.facebook.katana", "com.google.android.youtube"], SAFETY.get)
+        assert requirement["level"] == "typedPhrase" and requirement["phrase"] == "I have a backup"
+        assert requirement["packagesToType"] == ["com.facebook.katana", "com.google.android.youtube"]
+        assert [p["level"] for p in requirement["packages"]] == ["confirm", "typedPackage"], "levels stay per package"
+
+        # Recovery only puts packages back: the batch level applies whatever its size
+        requirement = policy.requirement("recover", ["com.android.systemui"], SAFETY.get)
+        assert requirement["level"] == "typedPhrase" and requirement["blocked"] == []
+        assert requirement["packagesToType"] == ["com.android.systemui"]
+        assert policy.requirement("recover", [], SAFETY.get)["level"] == "typedPhrase"
+
+        requirement = policy.requirement("disable", ["com.google.android.youtube"], SAFETY.get)
+        assert requirement["level"] == "confirm", "disabling has its own, lighter map"
+        requirement = policy.requirement("uninstall", ["com.android.systemui", "com.facebook.katana"], SAFETY.get)
+        assert requirement["level"] == "blocked" and requirement["blocked"] == ["com.android.systemui"]
+        try:
+            policy.requirement("wipe", ["com.facebook.katana"], SAFETY.get)
+            assert False, "unknown operations are rejected"
+        except ValueError:
+            pass
+    print("  └─ ✅ Requirements OK")
+    return True
+
+
+def test_check():
+    """Test which confirmations satisfy a requirement"""
+    print("\n✍️ Testing confirmations...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        write_policy(data_dir, POLICY)
+        policy = ConfirmationPolicy(data_dir)
+        packages = ["com.google.android.youtube", "com.android.chrome"]
+        refusal = policy.check("uninstall", packages, SAFETY.get, None)
+        assert refusal["confirmationRequired"] is True and refusal["success"] is False
+        assert "I have a backup" in refusal["message"]
+
+        full = {"confirmed": True, "typedPackages": packages, "phrase": " I have a backup "}
+        assert policy.check("uninstall", packages, SAFETY.get, full) is None
+        assert policy.check("uninstall", packages, SAFETY.get, {**full, "phrase": "i have a backup"}) is not None
+        assert policy.check("uninstall", packages, SAFETY.get, {**full, "typedPackages": packages[:1]}) is not None
+        assert policy.check("uninstall", packages, SAFETY.get, {**full, "confirmed": False}) is not None
+
+        refusal = policy.check("uninstall", ["com.android.systemui"], SAFETY.get,
+                               {"confirmed": True, "typedPackages": ["com.android.systemui"], "phrase": "I have a backup"})
+        assert refusal["blocked"] is True and "junior" in refusal["message"]
+        assert not is_satisfied({"level": "blocked"}, full)
+        assert is_satisfied({"level": "none"}, None)
+    print("  └─ ✅ Confirmations OK")
+    return True
+
+
+def test_enforced_commands():
+    """Test that data wipes, undo and emergency recovery are refused without the confirmation the role needs"""
+    print("\n🔐 Testing enforcement on wipes, undo and recovery...")
+    from core import build_services
+    from main import handle_command
+
+    with tempfile.TemporaryDirectory() as tmp:
+        home = os.environ.get("HOME")
+        os.environ["HOME"] = tmp
+        try:
+            services = build_services(lambda kind, payload, job_id=None: None, shell_sessions=False)
+            write_policy(os.path.join(tmp, "DebloatAI"), {"role": "tech", "roles": {"tech": {
+                "Safe": "confirm", "Caution": "confirm", "Expert": "confirm", "Dangerous": "confirm",
+                "batch": "typedPackage"}}})
+            services.confirmations.reload()
+            adb = FakeADB({"pm list packages -d": "package:com.facebook.katana\n"}, sdk=33)
+            services.adb = adb
+            services.undo = UndoStack(adb, None, tmp)
+            services.recovery = EmergencyRecovery(adb, services.undo, SimpleNamespace(list_backups=lambda serial: []),
+                                                  SimpleNamespace(list_trials=lambda: []), None)
+            confirmed = {"confirmed": True}
+
+            wipe = {"command": "wipe_package_data", "args": {"packageName": "com.facebook.katana"}}
+            refusal = handle_command(wipe, services)
+            assert refusal["confirmationRequired"] and refusal["requirement"]["operation"] == "uninstall"
+            assert adb.runs == [], "nothing reached the device"
+            wipe["args"]["confirmation"] = confirmed
+            assert handle_command(wipe, services)["success"] and adb.runs[-1][-1] == "com.facebook.katana"
+
+            # Undoing a reinstall uninstalls; undoing an enable disables
+            services.undo.record("reinstall", "com.facebook.katana", {"success": True})
+            services.undo.record("enable", "com.google.android.youtube", {"success": True})
+            runs, entries = len(adb.runs), len(services.undo.get_undo_stack())
+            refusal = handle_command({"command": "undo", "args": {"count": 2}}, services)
+            assert refusal["confirmationRequired"] and refusal["results"] == []
+            assert refusal["requirement"]["operation"] == "uninstall"
+            assert len(adb.runs) == runs and len(services.undo.get_undo_stack()) == entries, "nothing was undone"
+            adb.run_output = "Success\nPackage com.google.android.youtube new state: disabled-user"
+            result = handle_command({"command": "undo", "args": {"count": 2, "confirmation": confirmed}}, services)
+            assert result["success"] and [r["success"] for r in result["results"]] == [True, True], result
+            assert adb.runs[-1][-1] == "com.facebook.katana" and "uninstall" in adb.runs[-1]
+
+            services.undo.record("disable", "com.facebook.katana", {"success": True})
+            recover = {"command": "emergency_recover", "args": {"confirmed": True, "safeMode": False}}
+            refusal = handle_command(recover, services)
+            assert refusal["success"] is False and refusal["requirement"]["operation"] == "recover"
+            assert refusal["plan"]["reenable"] == ["com.facebook.katana"] and "steps" not in refusal
+            recover["args"]["confirmation"] = {"confirmed": True, "typedPackages": ["com.facebook.katana"]}
+            assert handle_command(recover, services)["steps"][0]["step"] == "reenable"
+        finally:
+            if home is None:
+                del os.environ["HOME"]
+            else:
+                os.environ["HOME"] = home
+    print("  └─ ✅ Enforcement OK")
+    return True
+
+
+def test_roles_and_reload():
+    """Test switching and locking roles, and re-reading an edited file"""
+    print("\n👥 Testing roles and reloading...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        write_policy(data_dir, POLICY)
+        policy = ConfirmationPolicy(data_dir)
+        assert policy.set_role("admin")["success"] is False
+        result = policy.set_role("senior")
+        assert result["success"] and result["policy"]["levels"]["Expert"] == "confirm"
+        assert ConfirmationPolicy(data_dir).get_policy()["role"] == "senior", "the choice is saved"
+        assert policy.reload() is None, "our own save is not a change"
+
+        write_policy(data_dir, {**POLICY, "roleLocked": True})
+        assert policy.reload()["policy"]["role"] == "junior"
+        result = policy.set_role("senior")
+        assert result["success"] is False and "locks" in result["message"]
+
+        # A broken edit keeps the policy in force
+        write_policy(data_dir, {**POLICY, "role": "intern"})
+        try:
+            policy.reload()
+            assert False, "an undefined role is rejected"
+        except ValueError:
+            pass
+        assert policy.get_policy()["roleLocked"] is True
+        # ...but a fresh start with a broken file must not lock anyone out of the app
+        assert ConfirmationPolicy(data_dir).get_policy()["role"] == "default"
+
+    assert validate_policy(POLICY) is None
+    assert "missing" in validate_policy({"role": "a", "roles": {"a": {"Safe": "none"}}})
+    assert "must be one of" in validate_policy({"role": "a", "roles": {"a": {
+        "Safe": "none", "Caution": "none", "Expert": "none", "Dangerous": "maybe"}}})
+    print("  └─ ✅ Roles and reloading OK")
+    return True
+
+
+def main():
+    """Run all confirmation policy tests"""
+    tests = [test_default_policy, test_requirements, test_check, test_enforced_commands, test_roles_and_reload]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,72 @@
+"""
+Test building the backend without Electron: services with their own event
+sink, commands routed through handle_command, background jobs started and shut down
+Runs with a temporary home folder - no ADB or device required
+"""
+import sys
+import os
+import json
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+
+def test_headless_services():
+    """Test that the whole backend runs from a plain script"""
+    print("\n🧱 Testing headless services...")
+    with tempfile.TemporaryDirectory() as tmp:
+        home = os.environ.get("HOME")
+        os.environ["HOME"] = tmp
+        try:
+            from core import build_services, start_background
+            from main import handle_command
+
+            events = []
+            services = build_services(lambda kind, payload, job_id=None: events.append(kind), shell_sessions=False)
+            assert services.emit_event is not None and services.session_log is not None
+            assert str(services.backup_mgr.get_backup_path()).startswith(tmp)
+
+            settings = handle_command({"command": "get_settings"}, services)
+            assert "ai" in settings and "devices" in settings
+            assert handle_command({"command": "no_such_command"}, services)["error"] == "Unknown command: no_such_command"
+
+            start_background(services)
+
+            # Turning remote control on by editing settings.json starts the server without a restart
+            services.remote.start(lambda request: handle_command(request, services))
+            assert services.remote.status()["running"] is False
+            edited = services.settings.get_settings()
+            edited["remote"].update({"enabled": True, "port": 0})
+            with open(services.settings.path, "w", encoding="utf-8") as f:
+                json.dump(edited, f)
+            services.config_watcher.check()
+            assert services.remote.status()["running"] is True
+
+            steps = services.shutdown.run("test")
+            assert [step["name"] for step in steps][-1] == "close adb sessions"
+            assert all(step["success"] for step in steps), steps
+            assert os.path.isfile(os.path.join(tmp, "DebloatAI", "reports", "last_session.txt"))
+            assert services.shutdown.run("again") == []
+        finally:
+            if home is None:
+                del os.environ["HOME"]
+            else:
+                os.environ["HOME"] = home
+    print("  └─ ✅ Headless services OK")
+    return True
+
+
+def main():
+    """Run all core tests"""
+    tests = [test_headless_services]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,210 @@
+"""
+Test the opt-in crash reporter: nothing is written until it is turned on,
+reports keep the traceback and recent log without serials or home paths,
+old reports are pruned and failed submissions are recorded
+Runs in a temporary directory with a fake endpoint - no network required
+"""
+import sys
+import os
+import json
+import tempfile
+import threading
+from pathlib import Path
+sys.path.insert(0, os.path.dirname(__file__))
+
+import crash_reporter
+import network
+from crash_reporter import CrashReporter
+from session_log import SessionLog
+from settings_manager import DEFAULT_SETTINGS
+
+
+class FakeResponse:
+    def __init__(self, status):
+        self.status = status
+
+    def raise_for_status(self):
+        if self.status >= 400:
+            raise network.requests.HTTPError(f"{self.status} Server Error")
+
+
+def raise_in_command():
+    """An exception with a real traceback through a file under the home directory"""
+    try:
+        raise ValueError(f"could not open {Path.home()}/DebloatAI/backups/index.json")
+    except ValueError as e:
+        return e
+
+
+def make_reporter(data_dir, **settings):
+    log = SessionLog()
+    log.record_command("set_device_nickname", {"serial": "R58M12345AB", "name": "Work"}, {"success": True})
+    log.record_command("uninstall_package", {"packageName": "com.facebook.katana"}, error="boom")
+    values = {**DEFAULT_SETTINGS["diagnostics"], **settings}
+    return CrashReporter(values.get, log, data_dir)
+
+
+def test_off_by_default():
+    """Test that no report is written until crash reports are turned on"""
+    print("\n🔕 Testing crash reports off by default...")
+    assert DEFAULT_SETTINGS["diagnostics"] == {"crashReports": False, "crashReportUrl": ""}
+    with tempfile.TemporaryDirectory() as tmp:
+        reporter = make_reporter(tmp)
+        assert reporter.capture(raise_in_command(), "command", command="uninstall_package") is None
+        assert not (Path(tmp) / "crash_reports").exists()
+        assert reporter.list_reports() == []
+    print("  └─ ✅ Nothing written")
+    return True
+
+
+def test_report_contents():
+    """Test the saved report: traceback, recent log, no serial and no home path"""
+    print("\n📝 Testing report contents...")
+    with tempfile.TemporaryDirectory() as tmp:
+        reporter = make_reporter(tmp, crashReports=True)
+        report = reporter.capture(raise_in_command(), "command", command="uninstall_package")
+        with open(report["path"], encoding="utf-8") as f:
+            saved = json.load(f)
+        assert saved["kind"] == "command" and saved["command"] == "uninstall_package"
+        assert saved["exceptionType"] == "ValueError" and "raise_in_command" in saved["traceback"]
+        assert saved["message"] == "could not open ~/DebloatAI/backups/index.json"
+        assert any("uninstall_package" in line for line in saved["recentLog"])
+        text = json.dumps(saved)
+        assert "R58M12345AB" not in text and "serial=(omitted)" in text
+        assert str(Path.home()) + "/DebloatAI" not in text
+        assert saved["submitted"] is False and "submitError" not in saved
+
+        listed = reporter.list_reports()
+        assert [r["id"] for r in listed] == [report["id"]]
+        assert "traceback" not in listed[0] and "recentLog" not in listed[0]
+    print("  └─ ✅ Report contents OK")
+    return True
+
+
+def test_known_serials():
+    """Test that serials adb sees or has a nickname for are blanked out of free-text log lines and messages"""
+    print("\n🙈 Testing known serial scrubbing...")
+    with tempfile.TemporaryDirectory() as tmp:
+        reporter = make_reporter(tmp, crashReports=True)
+        reporter.known_serials = lambda: ["R58M12345AB", "emulator-5554", "R58M12345AB-2", "ab"]
+        log = reporter.session_log
+        log.record_command("set_device_nickname", {"serial": "R58M12345AB", "name": "Work"},
+                           {"success": True, "message": "R58M12345AB is now called Work"})
+        log.record_command("start_screen_mirror", {}, {"success": True, "message": "Mirroring emulator-5554"})
+        log.record_command("get_device_info", {}, {"success": True, "message": "Found R58M12345AB-2"})
+        report = reporter.capture(ValueError("device 'emulator-5554' not found"), "command")
+        text = json.dumps(report)
+        assert "R58M12345AB" not in text and "emulator-5554" not in text
+        assert "(serial) is now called Work" in text and "Mirroring (serial)" in text
+        assert "Found (serial)" in text, "the longer serial is blanked whole"
+        assert report["message"] == "device '(serial)' not found"
+        assert "uninstall_package" in text, "short serials do not blank out ordinary words"
+
+        # A failing device listing still leaves serial arguments blanked
+        reporter.known_serials = lambda: 1 / 0
+        report = reporter.capture(raise_in_command(), "command")
+        assert report is not None and "serial=(omitted)" in json.dumps(report["recentLog"])
+    print("  └─ ✅ Known serials scrubbed")
+    return True
+
+
+def test_thread_hook():
+    """Test that an exception ending a background thread is reported"""
+    print("\n🧵 Testing background thread crashes...")
+    with tempfile.TemporaryDirectory() as tmp:
+        reporter = make_reporter(tmp, crashReports=True)
+        saved_thread_hook, saved_hook = threading.excepthook, sys.excepthook
+        threading.excepthook = lambda args: None  # keep the test output clean
+        try:
+            reporter.install()
+            worker = threading.Thread(target=lambda: 1 / 0, name="prefetch")
+            worker.start()
+            worker.join()
+        finally:
+            threading.excepthook, sys.excepthook = saved_thread_hook, saved_hook
+        [report] = reporter.list_reports()
+        assert report["kind"] == "thread" and report["thread"] == "prefetch"
+        assert report["exceptionType"] == "ZeroDivisionError"
+    print("  └─ ✅ Thread crash reported")
+    return True
+
+
+def test_pruning_and_clear():
+    """Test that old reports are pruned and clear removes the rest"""
+    print("\n🧹 Testing pruning...")
+    with tempfile.TemporaryDirectory() as tmp:
+        reporter = make_reporter(tmp, crashReports=True)
+        folder = Path(tmp) / "crash_reports"
+        folder.mkdir()
+        for i in range(crash_reporter.MAX_CRASH_REPORTS):
+            (folder / f"crash_20200101_0000{i:02d}_old{i}.json").write_text("{}")
+        report = reporter.capture(raise_in_command(), "main loop")
+        files = sorted(folder.glob("crash_*.json"))
+        assert len(files) == crash_reporter.MAX_CRASH_REPORTS
+        assert files[0].name == "crash_20200101_000001_old1.json"
+        assert Path(report["path"]) in files
+
+        result = reporter.clear()
+        assert result["success"] and result["removed"] == crash_reporter.MAX_CRASH_REPORTS
+        assert reporter.list_reports() == []
+    print("  └─ ✅ Pruning OK")
+    return True
+
+
+def test_submission():
+    """Test posting to the endpoint without local fields, and a failed post"""
+    print("\n📤 Testing submission...")
+    posted = []
+    original_post = network.post
+
+    def fake_post(url, **kwargs):
+        posted.append((url, kwargs["json"]))
+        return FakeResponse(500 if "broken" in url else 200)
+
+    network.post = fake_post
+    try:
+        with tempfile.TemporaryDirectory() as tmp:
+            reporter = make_reporter(tmp, crashReports=True, crashReportUrl="https://crash.example.com/report")
+            report = reporter.capture(raise_in_command(), "fatal", wait=True)
+            url, body = posted[-1]
+            assert url == "https://crash.example.com/report"
+            assert body["id"] == report["id"] and "path" not in body and "submitted" not in body
+            assert reporter.list_reports()[0]["submitted"] is True
+
+            reporter = make_reporter(tmp, crashReports=True, crashReportUrl="https://broken.example.com/")
+            reporter.capture(raise_in_command(), "fatal", wait=True)
+            latest = [r for r in reporter.list_reports() if not r["submitted"]]
+            assert len(latest) == 1 and latest[0]["submitError"]
+    finally:
+        network.post = original_post
+
+    # Offline mode refuses the post and the report stays local
+    network.configure({**network.NETWORK_DEFAULTS, "offline": True}.get)
+    try:
+        with tempfile.TemporaryDirectory() as tmp:
+            reporter = make_reporter(tmp, crashReports=True, crashReportUrl="https://crash.example.com/report")
+            reporter.capture(raise_in_command(), "fatal", wait=True)
+            [report] = reporter.list_reports()
+            assert report["submitted"] is False and "ffline" in report["submitError"]
+    finally:
+        network.configure(network.NETWORK_DEFAULTS.get)
+    print("  └─ ✅ Submission OK")
+    return True
+
+
+def main():
+    """Run all crash reporter tests"""
+    tests = [test_off_by_default, test_report_contents, test_known_serials, test_thread_hook, test_pruning_and_clear,
+             test_submission]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,119 @@
+"""
+Test device class detection and class-specific safety levels and presets
+Runs against canned getprop / pm output - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from device_class import (CLASS_PRESETS, CLASS_SAFETY_LEVELS, DETECTION_SCRIPT, DEVICE_CLASSES, parse_detection_output,
+                          presets_for)
+
+
+TV_OUTPUT = """tv
+feature:android.hardware.wifi
+feature:android.software.leanback
+feature:android.software.leanback_only
+feature:android.hardware.type.television
+feature:reqGlEsVersion=0x30002
+"""
+
+WATCH_OUTPUT = """nosdcard,watch
+feature:android.hardware.type.watch
+feature:android.hardware.sensor.heartrate
+"""
+
+TABLET_OUTPUT = """tablet
+feature:android.hardware.touchscreen
+feature:android.hardware.wifi
+"""
+
+PHONE_OUTPUT = """default
+feature:android.hardware.telephony
+feature:android.hardware.touchscreen
+"""
+
+
+class ClassDevice(FakeADB):
+    """Answers the detection script with canned output; API level and overlay reads get nothing"""
+
+    def __init__(self, detection_output):
+        super().__init__({DETECTION_SCRIPT: detection_output})
+
+    def answer(self, command):
+        if "ro.build.mktg.fireos" in command:
+            return "Google\n\n\n"
+        return super().answer(command)
+
+
+def test_detection():
+    """Test each class from characteristics and features"""
+    print("\n📺 Testing device class detection...")
+    tv = parse_detection_output(TV_OUTPUT)
+    assert tv["deviceClass"] == "tv" and tv["characteristics"] == "tv"
+    assert tv["features"] == ["android.hardware.type.television", "android.software.leanback"]
+    assert parse_detection_output(WATCH_OUTPUT)["deviceClass"] == "watch"
+    assert parse_detection_output(TABLET_OUTPUT)["deviceClass"] == "tablet"
+    assert parse_detection_output(PHONE_OUTPUT)["deviceClass"] == "phone"
+    # A TV box ROM that only declares the leanback feature
+    assert parse_detection_output("default\nfeature:android.software.leanback\n")["deviceClass"] == "tv"
+    assert parse_detection_output("automotive\n")["deviceClass"] == "automotive"
+    assert parse_detection_output("")["deviceClass"] == "phone"
+    print("  └─ ✅ Detection OK")
+    return True
+
+
+def test_class_safety_levels():
+    """Test that listings use the class levels of the connected device"""
+    print("\n🛡️  Testing class-specific safety levels...")
+    tv = ClassDevice(TV_OUTPUT)
+    packages = {p["packageName"]: p for p in tv.build_package_entries(
+        ["com.google.android.tvlauncher", "com.google.android.tvrecommendations", "com.android.systemui"])}
+    assert packages["com.google.android.tvlauncher"]["safetyLevel"] == "Dangerous"
+    assert packages["com.google.android.tvrecommendations"]["safetyLevel"] == "Safe"
+    assert packages["com.android.systemui"]["safetyLevel"] == "Dangerous"
+    tv.build_package_entries(["com.example.app"])
+    assert tv.commands.count(DETECTION_SCRIPT) == 1, "the class is detected once per device"
+
+    phone = ClassDevice(PHONE_OUTPUT)
+    assert phone.build_package_entries(["com.google.android.tvlauncher"])[0]["safetyLevel"] == "Safe"
+    for levels in CLASS_SAFETY_LEVELS.values():
+        assert set(levels.values()) <= {"Safe", "Caution", "Expert", "Dangerous"}
+    print("  └─ ✅ Safety levels OK")
+    return True
+
+
+def test_presets():
+    """Test preset filtering against installed packages"""
+    print("\n🎛️  Testing class presets...")
+    assert set(CLASS_PRESETS) <= set(DEVICE_CLASSES)
+    installed = {"com.google.android.tvrecommendations", "com.netflix.ninja", "com.google.android.tvlauncher"}
+    presets = {p["id"]: p for p in presets_for("tv", installed)}
+    assert set(presets) == {"tv_launcher_extras", "tv_preloaded_apps"}, "presets with nothing installed are left out"
+    assert presets["tv_preloaded_apps"]["packages"] == ["com.netflix.ninja"]
+    assert presets["tv_launcher_extras"]["deviceClass"] == "tv"
+    assert presets_for("phone", installed) == []
+    for preset in presets_for("tv"):
+        for package in preset["packages"]:
+            level = CLASS_SAFETY_LEVELS["tv"].get(package, "Safe")
+            assert level in ("Safe", "Caution"), f"{package} is {level} but in preset {preset['id']}"
+    print("  └─ ✅ Presets OK")
+    return True
+
+
+def main():
+    """Run all device class tests"""
+    tests = [test_detection, test_class_safety_levels, test_presets]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,92 @@
+"""
+Test device owner / MDM detection
+Runs against captured dpm/dumpsys output - no device required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from device_policy import DevicePolicy, parse_list_owners, parse_dumpsys_owners, parse_active_admins
+
+
+LIST_OWNERS = """2 owners:
+User 0: admin=com.airwatch.androidagent/com.airwatch.agent.DeviceAdministratorReceiver,DeviceOwner,Affiliated
+User 10: admin=com.google.android.apps.work.clouddpc/.receivers.CloudDeviceAdminReceiver,ProfileOwner
+"""
+
+DUMPSYS_POLICY = """Current Device Policy Manager state:
+  Device Owner:
+    admin=ComponentInfo{com.airwatch.androidagent/com.airwatch.agent.DeviceAdministratorReceiver}
+    name=
+  Enabled Device Admins (User 0, provisioningState: 3):
+    com.airwatch.androidagent/com.airwatch.agent.DeviceAdministratorReceiver:
+      uid=10123
+    com.google.android.gms/com.google.android.gms.mdm.receivers.MdmDeviceAdminReceiver:
+      uid=10045
+  Encryption Status: encrypted
+"""
+
+INSTALLERS = """package:com.airwatch.androidagent installer=com.android.vending
+package:com.vmware.boxer installer=com.airwatch.androidagent
+package:com.whatsapp installer=com.android.vending
+package:com.android.chrome installer=null
+"""
+
+
+class PolicyDevice(FakeADB):
+    """A managed device: owners, admins and installers"""
+
+    def answer(self, command):
+        if command.startswith("dpm"):
+            return LIST_OWNERS
+        if command.startswith("dumpsys"):
+            return DUMPSYS_POLICY
+        return INSTALLERS
+
+
+def test_parsers():
+    """Test parsing owners and device admins"""
+    print("\n🏢 Testing device policy parsers...")
+    owners = parse_list_owners(LIST_OWNERS)
+    assert [o["type"] for o in owners] == ["deviceOwner", "profileOwner"]
+    assert owners[1]["userId"] == 10
+    assert parse_dumpsys_owners(DUMPSYS_POLICY)[0]["packageName"] == "com.airwatch.androidagent"
+    assert parse_active_admins(DUMPSYS_POLICY) == {"com.airwatch.androidagent", "com.google.android.gms"}
+    print("  └─ ✅ Parsers OK")
+    return True
+
+
+def test_managed_packages():
+    """Test marking owner, admin and owner-installed apps non-removable"""
+    print("\n🔒 Testing managed package marking...")
+    policy = DevicePolicy(PolicyDevice())
+    state = policy.get_management_state()
+    assert state["managed"]
+    assert state["deviceOwner"]["packageName"] == "com.airwatch.androidagent"
+    assert set(state["managedPackages"]) == {
+        "com.airwatch.androidagent", "com.google.android.apps.work.clouddpc",
+        "com.google.android.gms", "com.vmware.boxer",
+    }
+    packages = policy.apply_management([{"packageName": "com.vmware.boxer"}, {"packageName": "com.whatsapp"}])
+    assert packages[0]["removable"] is False and "airwatch" in packages[0]["managedReason"]
+    assert "removable" not in packages[1]
+    print("  └─ ✅ Managed packages OK")
+    return True
+
+
+def main():
+    """Run all device policy tests"""
+    tests = [test_parsers, test_managed_packages]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
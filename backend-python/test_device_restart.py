@@ -0,0 +1,85 @@
+"""
+Test the restart and reboot helpers
+Runs against a fake adb that records commands - no device required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from device_restart import DeviceRestarter, parse_home_activity
+
+
+RESOLVE_HOME = """priority=0 preferredOrder=0 match=0x108000 specificIndex=-1 isDefault=true
+com.google.android.apps.nexuslauncher/.NexusLauncherActivity
+"""
+
+
+class RestartDevice(FakeADB):
+    """A Pixel logging adb invocations alongside its shell commands"""
+
+    def __init__(self, supported=True):
+        super().__init__()
+        self.adb_path = "adb"
+        self.supported = supported
+
+    def supports(self, feature):
+        return self.supported
+
+    def require(self, feature):
+        pass
+
+    def answer(self, command):
+        return RESOLVE_HOME if "resolve-activity" in command else ""
+
+    def _run_command(self, command, timeout=30):
+        self.commands.append(" ".join(command))
+        return ""
+
+
+def test_launcher():
+    """Test that the default launcher is found, stopped and started again"""
+    print("\n🏠 Testing launcher restart...")
+    assert parse_home_activity(RESOLVE_HOME) == "com.google.android.apps.nexuslauncher"
+    assert parse_home_activity("No activity found\n") is None
+    adb = RestartDevice()
+    result = DeviceRestarter(adb).restart_launcher()
+    assert result["success"] and result["packageName"] == "com.google.android.apps.nexuslauncher"
+    assert "am force-stop com.google.android.apps.nexuslauncher" in adb.commands
+    assert adb.commands[-1].startswith("am start")
+    assert not DeviceRestarter(RestartDevice(supported=False)).restart_launcher()["success"]
+    print("  └─ ✅ Launcher restart OK")
+    return True
+
+
+def test_confirmation():
+    """Test that soft reboot and reboot do nothing until confirmed"""
+    print("\n🔌 Testing reboot confirmation...")
+    adb = RestartDevice()
+    restarter = DeviceRestarter(adb)
+    assert restarter.soft_reboot()["confirmationRequired"]
+    assert restarter.reboot_device("recovery")["confirmationRequired"]
+    assert adb.commands == []
+    assert not restarter.reboot_device("download", confirmed=True)["success"]
+    assert restarter.reboot_device("recovery", confirmed=True)["success"]
+    assert restarter.soft_reboot(confirmed=True)["success"]
+    assert adb.commands == ["adb reboot recovery", "am restart"]
+    print("  └─ ✅ Confirmation OK")
+    return True
+
+
+def main():
+    """Run all restart helper tests"""
+    tests = [test_launcher, test_confirmation]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,144 @@
+"""
+Test the one-step emergency recovery: plan, re-enabling, backup restore and safe mode
+Runs against fakes - no ADB required
+"""
+import sys
+import os
+import tempfile
+import time
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from emergency_recovery import MANUAL_SAFE_MODE_MESSAGE, POWER_MENU_COMMAND, EmergencyRecovery
+from undo_manager import UndoStack
+
+
+class RecoveryDevice(FakeADB):
+    """Tracks disabled packages and the safe mode property"""
+
+    def __init__(self, settable=True, power_menu=True):
+        super().__init__()
+        self.disabled = {"com.samsung.bloat", "com.old.disabled"}
+        self.settable = settable
+        self.power_menu = power_menu
+        self.safemode = ""
+
+    def answer(self, command):
+        if command == "pm list packages -d":
+            return "".join(f"package:{p}\n" for p in sorted(self.disabled))
+        if command.startswith("setprop") and self.settable:
+            self.safemode = "1"
+        if command.startswith("getprop"):
+            return self.safemode + "\n"
+        if command == POWER_MENU_COMMAND and not self.power_menu:
+            return "Error: Unknown option: --longpress"
+        return ""
+
+    def set_package_enabled(self, package, enabled):
+        self.disabled.discard(package)
+        return {"success": True, "message": f"Enabled {package}"}
+
+
+class FakeBackups:
+    def __init__(self):
+        self.restored = []
+
+    def list_backups(self, serial=None):
+        return [{"name": "backup_new.json", "packageCount": 2}, {"name": "backup_old.json", "packageCount": 5}]
+
+    def restore_backup(self, name, restore_data=False):
+        self.restored.append(name)
+        return {"success": True, "message": "Restored 1 of 2 packages",
+                "results": [{"packageName": "com.sec.android.app.launcher", "status": "restored"}]}
+
+
+class FakeTrials:
+    def __init__(self):
+        self.reverted = []
+
+    def list_trials(self):
+        return [{"id": "trial1"}]
+
+    def revert(self, trial_id, reason="problem"):
+        self.reverted.append((trial_id, reason))
+        return {"success": True, "message": "Re-enabled 1 of 1 package(s)"}
+
+
+class FakeRestarter:
+    def __init__(self):
+        self.reboots = 0
+
+    def reboot_device(self, mode="system", confirmed=False):
+        self.reboots += 1
+        return {"success": True, "message": "Device rebooting"}
+
+
+def make(data_dir, adb):
+    undo = UndoStack(adb, None, data_dir)
+    undo._push("disable", "Disabled com.old.disabled", {"action": "enable", "packageName": "com.old.disabled"})
+    undo._entries[-1]["timestamp"] = time.time() - 3 * 24 * 3600  # Outside the window
+    undo.record("disable", "com.samsung.bloat", {"success": True})
+    undo.record("disable", "com.reenabled.since", {"success": True})  # Already enabled again
+    return EmergencyRecovery(adb, undo, FakeBackups(), FakeTrials(), FakeRestarter()), undo
+
+
+def test_plan_needs_confirmation():
+    """Test that nothing runs without confirmation and the plan lists what would"""
+    print("\n🧭 Testing the recovery plan...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        adb = RecoveryDevice()
+        recovery, _ = make(data_dir, adb)
+        result = recovery.recover()
+        assert result["confirmationRequired"] and not result["success"]
+        assert result["plan"] == {"reenable": ["com.samsung.bloat"], "trials": ["trial1"], "backup": "backup_new.json"}
+        assert "com.samsung.bloat" in adb.disabled and recovery.restarter.reboots == 0
+    print("  └─ ✅ Recovery plan OK")
+    return True
+
+
+def test_recover():
+    """Test the steps in order, undo records, and the safe mode fallbacks"""
+    print("\n🛟 Testing recovery...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        adb = RecoveryDevice()
+        recovery, undo = make(data_dir, adb)
+        result = recovery.recover(confirmed=True)
+        assert result["success"]
+        assert [s["step"] for s in result["steps"]] == ["revertTrial", "reenable", "restoreBackup", "safeMode"]
+        assert recovery.trials.reverted == [("trial1", "recovery")]
+        assert "com.samsung.bloat" not in adb.disabled and "com.old.disabled" in adb.disabled
+        assert recovery.backup_mgr.restored == ["backup_new.json"]
+        assert result["steps"][-1]["target"] == "property" and recovery.restarter.reboots == 1
+        assert [e["kind"] for e in undo.get_undo_stack()[:2]] == ["reinstall", "enable"]
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        recovery, _ = make(data_dir, RecoveryDevice(settable=False))
+        result = recovery.recover(confirmed=True, restore_backup=False)
+        assert recovery.backup_mgr.restored == []
+        assert result["steps"][-1]["target"] == "powerMenu" and result["success"]
+        assert recovery.restarter.reboots == 0
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        recovery, _ = make(data_dir, RecoveryDevice(settable=False, power_menu=False))
+        result = recovery.recover(confirmed=True)
+        assert not result["success"] and result["steps"][-1]["message"] == MANUAL_SAFE_MODE_MESSAGE
+        assert result["steps"][-1]["target"] == "manual"
+    print("  └─ ✅ Recovery OK")
+    return True
+
+
+def main():
+    """Run all emergency recovery tests"""
+    tests = [test_plan_needs_confirmation, test_recover]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
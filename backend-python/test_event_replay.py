@@ -0,0 +1,48 @@
+"""
+Test the event replay buffer: last event per kind, emit order, filtering and
+the kinds that are never replayed
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from events import EventReplayBuffer, make_event
+
+
+def test_replay():
+    """Test that the last event of each kind is replayed in emit order"""
+    print("\n⏪ Testing event replay...")
+    buffer = EventReplayBuffer()
+    buffer.record(make_event("health_update", {"cpuUsage": 10.0}, "health-monitor"))
+    buffer.record(make_event("prefetch_progress", {"stage": "packages"}))
+    buffer.record(make_event("package_chunk", {"items": []}, "stream-1"))
+    buffer.record(make_event("health_update", {"cpuUsage": 42.0}, "health-monitor"))
+
+    replayed = buffer.replay()
+    assert [e["kind"] for e in replayed] == ["prefetch_progress", "health_update"]
+    assert replayed[1]["payload"] == {"cpuUsage": 42.0} and replayed[1]["job_id"] == "health-monitor"
+    assert all(e["replayed"] for e in replayed)
+
+    assert [e["kind"] for e in buffer.replay(["health_update", "weekly_digest_ready"])] == ["health_update"]
+    assert buffer.replay([]) == []
+    # Replays are copies; the stored envelope is not marked
+    assert "replayed" not in buffer._last["health_update"][1]
+    print("  └─ ✅ Event replay OK")
+    return True
+
+
+def main():
+    """Run all event replay tests"""
+    tests = [test_replay]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
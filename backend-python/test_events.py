@@ -0,0 +1,58 @@
+"""
+Test the versioned event envelope around every backend event
+Runs against main.py's emitter - no ADB required
+"""
+import sys
+import os
+import io
+import json
+sys.path.insert(0, os.path.dirname(__file__))
+
+from events import EVENT_VERSION, make_event
+
+
+def test_envelope():
+    """Test that main.py writes every event as one versioned JSON envelope line"""
+    print("\n✉️  Testing event envelope...")
+    assert make_event("optimization_progress", {"stage": "trim"}, "opt-1") == {
+        "event_version": EVENT_VERSION, "job_id": "opt-1", "kind": "optimization_progress",
+        "payload": {"stage": "trim"}}
+    try:
+        make_event("health_updated", {})
+        assert False, "unknown kind accepted"
+    except ValueError as e:
+        assert "health_updated" in str(e)
+
+    import main
+    stdout, sys.stdout = sys.stdout, io.StringIO()
+    try:
+        main.emit_event("optimization_progress", {"stage": "remove", "status": "running"}, "opt-1")
+        main.emit_event("health_update", {"cpuUsage": 12.5})
+        lines = sys.stdout.getvalue().splitlines()
+    finally:
+        sys.stdout = stdout
+    events = [json.loads(line) for line in lines]
+    assert [set(e) for e in events] == [{"event_version", "job_id", "kind", "payload"}] * 2
+    assert events[0]["job_id"] == "opt-1" and events[0]["payload"]["stage"] == "remove"
+    assert events[1]["job_id"] is None and events[1]["event_version"] == EVENT_VERSION
+    # Nothing outside the envelope: no request id, so Electron cannot mistake it for a response
+    assert "id" not in events[0]
+    print("  └─ ✅ Event envelope OK")
+    return True
+
+
+def main():
+    """Run all event tests"""
+    tests = [test_envelope]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
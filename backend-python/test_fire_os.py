@@ -0,0 +1,106 @@
+"""
+Test Fire OS detection, Amazon package ratings, presets and removal notes
+Runs against canned device output - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from fire_os import FIRE_OS_PACKAGES, fire_os_presets, parse_fire_os_version
+from remediation import remediation_for
+
+
+FIRE_HD_PROPS = "Amazon\n7.3.2.9\nFire OS 7.3.2.9 (PS7329/4620)\n"
+FIRE_OS_5_PROPS = "Amazon\n5.6.9.0\n\n"
+PIXEL_PROPS = "Google\n\nAP2A.240805.005\n"
+
+
+class FireOSDevice(FakeADB):
+    """Answers Fire OS property reads; anything else looks like a phone-like tablet build"""
+
+    def __init__(self, props, pm_output="Success"):
+        super().__init__(default="default\n", run_output=pm_output)
+        self.props = props
+
+    def answer(self, command):
+        return self.props if "ro.build.mktg.fireos" in command else self.default
+
+    def require(self, feature):
+        pass
+
+
+def test_detection():
+    """Test Fire OS version detection"""
+    print("\n🔥 Testing Fire OS detection...")
+    assert parse_fire_os_version(FIRE_HD_PROPS) == "7.3.2.9"
+    assert parse_fire_os_version(FIRE_OS_5_PROPS) == "5.6.9.0"
+    assert parse_fire_os_version("Amazon\n\n\n") == "unknown"
+    assert parse_fire_os_version(PIXEL_PROPS) is None
+    assert parse_fire_os_version("") is None
+    print("  └─ ✅ Detection OK")
+    return True
+
+
+def test_amazon_safety_levels():
+    """Test that Amazon packages are rated by the Fire OS table only on Fire OS"""
+    print("\n🛡️  Testing Amazon package ratings...")
+    names = ["com.amazon.firelauncher", "com.amazon.kindle.kso", "com.amazon.venezia", "com.amazon.unlisted.service"]
+    fire = {p["packageName"]: p["safetyLevel"] for p in FireOSDevice(FIRE_HD_PROPS).build_package_entries(names)}
+    assert fire == {"com.amazon.firelauncher": "Dangerous", "com.amazon.kindle.kso": "Safe",
+                    "com.amazon.venezia": "Expert", "com.amazon.unlisted.service": "Caution"}
+    pixel = {p["packageName"]: p["safetyLevel"] for p in FireOSDevice(PIXEL_PROPS).build_package_entries(names)}
+    assert set(pixel.values()) == {"Safe"}, "Amazon apps on other devices are ordinary apps"
+    assert all(level in ("Safe", "Caution", "Expert", "Dangerous") for level, _ in FIRE_OS_PACKAGES.values())
+    print("  └─ ✅ Ratings OK")
+    return True
+
+
+def test_presets():
+    """Test that presets only hold Safe, installed packages"""
+    print("\n🎛️  Testing Fire OS presets...")
+    for preset in fire_os_presets():
+        assert preset["platform"] == "fireOs"
+        for package in preset["packages"]:
+            assert FIRE_OS_PACKAGES[package][0] == "Safe", f"{package} is in preset {preset['id']}"
+    presets = fire_os_presets({"com.amazon.kindle.kso", "com.amazon.firelauncher"})
+    assert [p["id"] for p in presets] == ["fire_os_ads"]
+    print("  └─ ✅ Presets OK")
+    return True
+
+
+def test_removal_notes():
+    """Test update notes, protected packages and reinstall remediation"""
+    print("\n📝 Testing Fire OS removal notes...")
+    result = FireOSDevice(FIRE_HD_PROPS).uninstall_package("com.amazon.kindle.kso")
+    assert result["success"] and "update" in result["updateNote"]
+    assert "updateNote" not in FireOSDevice(FIRE_HD_PROPS).uninstall_package("com.spotify.music")
+    assert "updateNote" not in FireOSDevice(PIXEL_PROPS).uninstall_package("com.amazon.kindle.kso")
+
+    denied = "Exception occurred while executing:\njava.lang.SecurityException: Cannot disable a protected package"
+    result = FireOSDevice(FIRE_HD_PROPS, denied).set_package_enabled("com.amazon.firelauncher", False)
+    assert not result["success"] and result["protected"]
+    result = FireOSDevice(PIXEL_PROPS, denied).set_package_enabled("com.google.android.apps.nexuslauncher", False)
+    assert "protected" not in result
+
+    remediation = remediation_for("Package com.amazon.mp3 doesn't exist", "Amazon", 28, "com.amazon.mp3")
+    assert "Amazon Appstore" in remediation["summary"]
+    print("  └─ ✅ Removal notes OK")
+    return True
+
+
+def main():
+    """Run all Fire OS tests"""
+    tests = [test_detection, test_amazon_safety_levels, test_presets, test_removal_notes]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,84 @@
+"""
+Test the shared number, size and percent formatting
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+import formatting
+from formatting import format_number, format_size, format_size_kb, format_percent, formatted_fields, percent
+from system_health import parse_df
+
+
+def plain(text):
+    """Formatted text with non-breaking spaces shown as plain spaces"""
+    return text.replace("\u00a0", " ").replace("\u202f", " ")
+
+
+def test_sizes():
+    """Test binary size units and rounding"""
+    print("\n📏 Testing size formatting...")
+    assert plain(format_size(0)) == "0 B"
+    assert plain(format_size(1023)) == "1,023 B"
+    assert plain(format_size(1536)) == "1.5 KB"
+    assert plain(format_size(2 * 1024 ** 2)) == "2 MB"
+    assert plain(format_size(int(118.3 * 1024 ** 3))) == "118 GB"
+    assert plain(format_size(int(5.94 * 1024 ** 3))) == "5.9 GB"
+    assert plain(format_size(3 * 1024 ** 5)) == "3,072 TB"
+    assert plain(format_size_kb(115249236)) == "110 GB"
+    assert format_size(None) is None
+    assert format_size(1536) == "1.5\u00a0KB", "number and unit must not be split across lines"
+    print("  └─ ✅ Sizes OK")
+    return True
+
+
+def test_locales():
+    """Test separators and percent spacing per language"""
+    print("\n🌍 Testing locale formatting...")
+    assert plain(format_number(1234567.25, 2)) == "1,234,567.25"
+    assert plain(format_number(1234567.25, 2, "de")) == "1.234.567,25"
+    assert plain(format_number(1234567.25, 2, "fr")) == "1 234 567,25"
+    assert plain(format_size(1536, "es")) == "1,5 KB"
+    assert plain(format_percent(76.4)) == "76.4%"
+    assert plain(format_percent(76.4, "fr")) == "76,4 %"
+    assert plain(format_percent(50.0)) == "50%"
+    assert percent(1, 3) == 33.3 and percent(1, 0) is None
+
+    formatting.configure(lambda: "de")
+    try:
+        assert plain(format_size(1536)) == "1,5 KB"
+    finally:
+        formatting.configure(lambda: formatting.DEFAULT_LOCALE)
+    print("  └─ ✅ Locales OK")
+    return True
+
+
+def test_formatted_fields():
+    """Test that results carry formatted strings next to the raw numbers"""
+    print("\n🏷️  Testing formatted result fields...")
+    fields = formatted_fields({"totalKb": 2048, "sizeBytes": 1536, "usagePercent": 12.5, "mountPoint": "/data", "ok": True})
+    assert {k: plain(v) for k, v in fields.items()} == {"total": "2 MB", "size": "1.5 KB", "usage": "12.5%"}
+
+    volume = parse_df("Filesystem 1K-blocks Used Available Use% Mounted on\n"
+                      "/dev/block/dm-5 115249236 60123456 55125780 53% /data\n")[0]
+    assert volume["totalKb"] == 115249236 and volume["usagePercent"] == 52.2
+    assert {k: plain(v) for k, v in volume["formatted"].items()} == {"total": "110 GB", "used": "57.3 GB", "available": "52.6 GB", "usage": "52.2%"}
+    print("  └─ ✅ Formatted fields OK")
+    return True
+
+
+def main():
+    """Run all formatting tests"""
+    tests = [test_sizes, test_locales, test_formatted_fields]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
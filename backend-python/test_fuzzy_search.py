@@ -0,0 +1,61 @@
+"""
+Test fuzzy package search
+Runs against a fixed package list - no device required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fuzzy_search import similarity, tokenize, search_packages
+
+
+PACKAGES = [
+    {"packageName": "com.samsung.android.app.spage", "appName": "Samsung Free"},
+    {"packageName": "com.samsung.android.bixby.agent", "appName": "Bixby Voice"},
+    {"packageName": "com.facebook.katana", "appName": "Facebook"},
+    {"packageName": "com.freeware.game", "appName": "Freeware"},
+]
+
+
+def test_tokenize():
+    """Test splitting package ids and labels"""
+    print("\n🔤 Testing tokenization...")
+    assert tokenize("com.samsung.android_app") == ["com", "samsung", "android", "app"]
+    assert tokenize("SamsungFree") == ["samsung", "free"]
+    assert similarity("smsung", "samsung") > 0.5
+    assert similarity("bixby", "facebook") < 0.5
+    print("  └─ ✅ Tokenization OK")
+    return True
+
+
+def test_typo_tolerant_ranking():
+    """Test that misspelled multi-word queries still rank the right package first"""
+    print("\n🔎 Testing fuzzy ranking...")
+    results = search_packages(PACKAGES, "blotware smsung free")
+    assert results[0]["packageName"] == "com.samsung.android.app.spage"
+    assert results[0]["matchScore"] > results[1]["matchScore"]
+
+    assert search_packages(PACKAGES, "facebok")[0]["packageName"] == "com.facebook.katana"
+    assert search_packages(PACKAGES, "com.facebook.katana")[0]["matchScore"] == 1.0
+    # "android" alone is package-id filler and should not match everything
+    assert search_packages(PACKAGES, "android") == []
+    assert search_packages(PACKAGES, "   ") == []
+    print("  └─ ✅ Fuzzy ranking OK")
+    return True
+
+
+def main():
+    """Run all search tests"""
+    tests = [test_tokenize, test_typo_tolerant_ranking]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
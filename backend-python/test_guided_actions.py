@@ -0,0 +1,193 @@
+"""
+Test guided flow actions: the settings page allowlist, navigation keys,
+named tap targets refused outside Settings, off their page or on permission
+and USB debugging prompts, App info and store pages
+Runs against canned shell output - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from guided_actions import UI_DUMP_COMMAND, GuidedActions, find_label, page_command, parse_focus, tap_refusal
+
+
+SETTINGS_FOCUS = "  mCurrentFocus=Window{5d1b0c3 u0 com.android.settings/com.android.settings.SubSettings}\n"
+PERMISSION_FOCUS = ("  mCurrentFocus=Window{a17f2e1 u0 com.google.android.permissioncontroller/"
+                    "com.android.permissioncontroller.permission.ui.GrantPermissionsActivity}\n")
+USB_DEBUGGING_FOCUS = "  mCurrentFocus=Window{77e0a9d u0 com.android.systemui/com.android.systemui.usb.UsbDebuggingActivity}\n"
+
+
+def ui_dump(*nodes):
+    """`uiautomator dump /dev/tty` output with one node per (text, class, bounds)"""
+    lines = [f'<node index="{i}" text="{text}" resource-id="" class="{cls}" package="com.android.settings" '
+             f'content-desc="" checkable="false" clickable="false" bounds="{bounds}" />'
+             for i, (text, cls, bounds) in enumerate(nodes)]
+    return ("<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation=\"0\">"
+            + "".join(lines) + "</hierarchy>UI hierchary dumped to: /dev/tty\n")
+
+
+ABOUT_PHONE_DUMP = ui_dump(("About phone", "android.widget.TextView", "[42,96][500,180]"),
+                           ("Build number", "android.widget.TextView", "[42,1032][678,1100]"))
+# Usage access lists apps with grant switches, the kind of screen a raw tap could have changed
+USAGE_ACCESS_DUMP = ui_dump(("Usage access", "android.widget.TextView", "[42,96][500,180]"),
+                            ("Facebook", "android.widget.TextView", "[150,300][500,360]"),
+                            ("", "android.widget.Switch", "[560,300][680,360]"))
+
+
+class GuidedDevice(FakeADB):
+    """A phone showing `focus`, with a Play Store unless `play_store` is off"""
+
+    def __init__(self):
+        super().__init__()
+        self.focus = SETTINGS_FOCUS
+        self.start_output = "Starting: Intent { act=android.settings.APPLICATION_DEVELOPMENT_SETTINGS }\n"
+        self.play_store = True
+        self.rejected = ()
+        self.screen = ABOUT_PHONE_DUMP
+
+    def get_apk_paths(self, package_name):
+        assert package_name == "com.android.vending"
+        return ["/product/priv-app/Phonesky/Phonesky.apk"] if self.play_store else []
+
+    def answer(self, command):
+        if command == "wm size":
+            return "Physical size: 1080x2400\nOverride size: 720x1600\n"
+        if command.startswith("dumpsys window"):
+            return self.focus
+        if command == UI_DUMP_COMMAND:
+            return self.screen
+        if any(uri in command for uri in self.rejected):
+            return "Error: Activity not started, unable to resolve Intent { act=android.intent.action.VIEW }\n"
+        if command.startswith("am start"):
+            return self.start_output
+        return ""
+
+
+def test_allowlist():
+    """Test building intents for allowed pages and rejecting everything else"""
+    print("\n📋 Testing the settings page allowlist...")
+    assert page_command("developerOptions") == "am start -a android.settings.APPLICATION_DEVELOPMENT_SETTINGS"
+    assert page_command("appInfo", "com.facebook.katana") == (
+        "am start -a android.settings.APPLICATION_DETAILS_SETTINGS -d package:com.facebook.katana")
+    for page, package in [("appInfo", None), ("appInfo", "x; reboot"), ("android.settings.WIFI_SETTINGS", None)]:
+        try:
+            page_command(page, package)
+            assert False, f"{page} {package} was allowed"
+        except ValueError:
+            pass
+    print("  └─ ✅ Allowlist OK")
+    return True
+
+
+def test_parsing():
+    """Test reading the focused window and finding labels in a UI dump"""
+    print("\n🔍 Testing focus and UI dump parsing...")
+    assert parse_focus(SETTINGS_FOCUS) == "com.android.settings/com.android.settings.SubSettings"
+    assert parse_focus("  mCurrentFocus=null\n") is None
+    assert find_label(ABOUT_PHONE_DUMP, ("Build number",)) == (360, 1066)
+    assert find_label(ui_dump(("Storage &amp; cache", "android.widget.TextView", "[0,0][100,50]")),
+                      ("Storage & cache",)) == (50, 25)
+    assert find_label(ABOUT_PHONE_DUMP.replace("com.android.settings", "com.evil.overlay"), ("Build number",)) is None
+    assert find_label(USAGE_ACCESS_DUMP, ("Build number",)) is None
+    assert tap_refusal(parse_focus(SETTINGS_FOCUS)) is None
+    assert tap_refusal(parse_focus(PERMISSION_FOCUS)).startswith("Taps are only allowed inside Settings")
+    assert tap_refusal("com.android.settings/com.android.settings.DeviceAdminAdd") is not None
+    assert tap_refusal("com.android.settings/com.android.settings.password.ConfirmLockPassword") is not None
+    print("  └─ ✅ Parsing OK")
+    return True
+
+
+def test_actions():
+    """Test opening pages, pressing keys and tapping against a fake device"""
+    print("\n👆 Testing guided actions...")
+    adb = GuidedDevice()
+    guided = GuidedActions(adb)
+    assert set(guided.list_actions()["keys"]) >= {"home", "back"}
+    assert "KEYCODE_ENTER" not in str(guided.list_actions()) and "DPAD_CENTER" not in str(guided.list_actions())
+    assert guided.list_actions()["tapTargets"]["buildNumber"]["page"] == "aboutPhone"
+
+    result = guided.open_settings_page("developerOptions")
+    assert result["success"], result
+    assert adb.commands == ["input keyevent KEYCODE_WAKEUP",
+                            "am start -a android.settings.APPLICATION_DEVELOPMENT_SETTINGS"]
+    adb.start_output = "Error: Activity not started, unable to resolve Intent { act=android.settings.X }\n"
+    result = guided.open_settings_page("usageAccess")
+    assert result["success"] is False and result["unsupported"] is True
+
+    assert guided.press_key("back")["success"] and adb.commands[-1] == "input keyevent KEYCODE_BACK"
+    assert guided.press_key("enter")["success"] is False
+
+    result = guided.tap("buildNumber")
+    assert result["success"] and adb.commands[-1] == "input tap 360 1066", result
+    count = len(adb.commands)
+    assert guided.tap("allow")["success"] is False and len(adb.commands) == count, "unknown targets are refused"
+
+    # Settings has focus, but the usage access page shows no allowed target: nothing is tapped
+    adb.screen = USAGE_ACCESS_DUMP
+    result = guided.tap("buildNumber")
+    assert result["success"] is False and result["refused"] is True
+    assert result["message"] == "Build number is not on the screen; open the aboutPhone page first"
+    assert not any(c.startswith("input tap") for c in adb.commands[count:])
+
+    adb.screen = ABOUT_PHONE_DUMP
+    for focus in (PERMISSION_FOCUS, USB_DEBUGGING_FOCUS, "  mCurrentFocus=null\n"):
+        adb.focus = focus
+        count = len(adb.commands)
+        result = guided.tap("buildNumber")
+        assert result["success"] is False and result["refused"] is True, focus
+        assert not any(c.startswith("input tap") for c in adb.commands[count:])
+
+    adb.connected = False
+    assert guided.press_key("home")["message"] == "No device connected"
+    print("  └─ ✅ Actions OK")
+    return True
+
+
+def test_store_and_app_info():
+    """Test the App info page and the store listing with and without the Play Store"""
+    print("\n🛒 Testing App info and store pages...")
+    adb = GuidedDevice()
+    guided = GuidedActions(adb)
+    result = guided.open_app_info("com.facebook.katana")
+    assert result["success"] and result["page"] == "appInfo"
+    assert adb.commands[-1] == "am start -a android.settings.APPLICATION_DETAILS_SETTINGS -d package:com.facebook.katana"
+
+    result = guided.open_in_play_store("com.facebook.katana")
+    assert result["success"] and result["openedIn"] == "Play Store"
+    assert adb.commands[-1] == ("am start -a android.intent.action.VIEW "
+                                "-d 'market://details?id=com.facebook.katana' -p com.android.vending")
+
+    # Play Store disabled: another store, then the browser
+    adb.rejected = ("-p com.android.vending",)
+    assert guided.open_in_play_store("com.facebook.katana")["openedIn"] == "app store"
+    adb.rejected = ("market://",)
+    result = guided.open_in_play_store("com.facebook.katana")
+    assert result["openedIn"] == "browser"
+    assert "'https://play.google.com/store/apps/details?id=com.facebook.katana'" in adb.commands[-1]
+
+    adb.play_store, adb.rejected = False, ()
+    guided.open_in_play_store("com.facebook.katana")
+    assert adb.commands[-1].endswith("'market://details?id=com.facebook.katana'")
+    adb.rejected = ("market://", "https://")
+    assert guided.open_in_play_store("com.facebook.katana")["unsupported"] is True
+    assert guided.open_in_play_store("x' && reboot '")["success"] is False
+    print("  └─ ✅ App info and store pages OK")
+    return True
+
+
+def main():
+    """Run all guided action tests"""
+    tests = [test_allowlist, test_parsing, test_actions, test_store_and_app_info]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
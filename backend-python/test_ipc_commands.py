@@ -0,0 +1,49 @@
+"""
+Smoke test - every backend command is reachable from the frontend
+Checks main.py's router against electron/main.js and electron/preload.js
+"""
+import sys
+import os
+import re
+
+ROOT = os.path.abspath(os.path.join(os.path.dirname(__file__), '..'))
+
+
+def _read(*parts):
+    with open(os.path.join(ROOT, *parts), 'r', encoding='utf-8') as f:
+        return f.read()
+
+
+def test_commands_registered():
+    """Every routed command has an IPC handler, and every handler has a route"""
+    print("\n🔌 Testing IPC command registration...")
+
+    routed = set(re.findall(r'command == "(\w+)"', _read('backend-python', 'main.py')))
+    electron_main = _read('electron', 'main.js')
+    called = set(re.findall(r"callPython\('(\w+)'", electron_main))
+    handled = set(re.findall(r"ipcMain\.handle\('([\w-]+)'", electron_main))
+    invoked = set(re.findall(r"ipcRenderer\.invoke\('([\w-]+)'", _read('electron', 'preload.js')))
+
+    missing_handler = routed - called
+    missing_route = called - routed
+    missing_bridge = handled ^ invoked
+
+    assert not missing_handler, f"Commands without an Electron handler: {sorted(missing_handler)}"
+    assert not missing_route, f"Electron calls unknown commands: {sorted(missing_route)}"
+    assert not missing_bridge, f"ipcMain/preload channel mismatch: {sorted(missing_bridge)}"
+
+    print(f"  └─ ✅ {len(routed)} commands registered end to end")
+    return True
+
+
+def main():
+    try:
+        test_commands_registered()
+    except AssertionError as e:
+        print(f"  └─ ❌ {e}")
+        return 1
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
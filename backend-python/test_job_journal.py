@@ -0,0 +1,129 @@
+"""
+Test journaling batch jobs and resuming them after an interruption
+Runs against a fake device - no ADB required
+"""
+import sys
+import os
+import json
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from job_journal import JobJournal
+
+
+PACKAGES = ["com.facebook.katana", "com.facebook.services", "com.netflix.mediaclient"]
+
+
+def test_record_on_every_item():
+    """Test that each result is on disk as soon as it is recorded"""
+    print("\n💾 Testing per-item persistence...")
+    with tempfile.TemporaryDirectory() as tmp:
+        journal = JobJournal(FakeADB(), tmp)
+        job_id = journal.start("uninstall", PACKAGES + [PACKAGES[0]], {"keepData": True})["jobId"]
+        assert journal.record(job_id, PACKAGES[0], {"success": True, "message": "Removed"})
+        with open(os.path.join(tmp, "job_journal.json"), encoding="utf-8") as f:
+            saved = json.load(f)[0]
+        assert saved["selection"] == PACKAGES and saved["options"] == {"keepData": True}
+        assert saved["pending"] == PACKAGES[1:]
+        assert saved["completed"] == [{"packageName": PACKAGES[0], "success": True, "message": "Removed"}]
+
+        # Failures are done too; a lost device leaves the package pending
+        assert journal.record(job_id, PACKAGES[1], {"success": False, "message": "Not installed"})
+        assert not journal.record(job_id, PACKAGES[2], {"success": False, "deviceLost": True})
+        assert not journal.record(job_id, PACKAGES[0], {"success": True})
+        assert not journal.record(None, PACKAGES[2], {"success": True})
+        assert journal.get_incomplete() == []  # Still running in this process
+    print("  └─ ✅ Per-item persistence OK")
+    return True
+
+
+def test_finish():
+    """Test that finished jobs are dropped and interrupted ones kept"""
+    print("\n🏁 Testing finishing jobs...")
+    with tempfile.TemporaryDirectory() as tmp:
+        journal = JobJournal(FakeADB(), tmp)
+        job_id = journal.start("uninstall", PACKAGES[:1])["jobId"]
+        journal.record(job_id, PACKAGES[0], {"success": True})
+        assert journal.finish(job_id)["finished"]
+        assert journal.get_incomplete() == []
+
+        job_id = journal.start("uninstall", PACKAGES)["jobId"]
+        journal.record(job_id, PACKAGES[0], {"success": True})
+        result = journal.finish(job_id)
+        assert not result["finished"] and result["remaining"] == 2
+        assert [job["id"] for job in journal.get_incomplete()] == [job_id]
+
+        assert not journal.start("uninstall", [])["success"]
+        assert not journal.start("optimize", PACKAGES)["success"]
+    print("  └─ ✅ Finishing jobs OK")
+    return True
+
+
+def test_resume_after_restart():
+    """Test that a job cut short by a closed app is offered and resumed on the next start"""
+    print("\n🔁 Testing resume after restart...")
+    with tempfile.TemporaryDirectory() as tmp:
+        adb = FakeADB()
+        journal = JobJournal(adb, tmp)
+        job_id = journal.start("uninstall", PACKAGES, {"keepData": False})["jobId"]
+        journal.record(job_id, PACKAGES[0], {"success": True})
+        # The app closes here without finishing the job
+
+        reopened = JobJournal(adb, tmp)
+        assert not reopened.includes(job_id, PACKAGES[1]), "not running until resumed"
+        incomplete = reopened.get_incomplete()
+        assert len(incomplete) == 1 and incomplete[0]["deviceConnected"]
+        assert incomplete[0]["pending"] == PACKAGES[1:]
+
+        adb.serial = "SERIAL2"
+        assert not reopened.get_incomplete()[0]["deviceConnected"]
+        assert not reopened.resume(job_id)["success"]
+        adb.serial = "SERIAL1"
+
+        resumed = reopened.resume(job_id)
+        assert resumed["success"] and resumed["job"]["pending"] == PACKAGES[1:]
+        assert not reopened.resume(job_id)["success"]  # Already running
+        assert reopened.includes(job_id, PACKAGES[1]) and not reopened.includes(job_id, PACKAGES[0])
+        for package in resumed["job"]["pending"]:
+            reopened.record(job_id, package, {"success": True})
+        assert reopened.finish(job_id)["finished"]
+        assert JobJournal(adb, tmp).get_incomplete() == []
+    print("  └─ ✅ Resume after restart OK")
+    return True
+
+
+def test_discard():
+    """Test forgetting an interrupted job"""
+    print("\n🗑️  Testing discarding jobs...")
+    with tempfile.TemporaryDirectory() as tmp:
+        journal = JobJournal(FakeADB(), tmp)
+        job_id = journal.start("uninstall", PACKAGES)["jobId"]
+        journal.finish(job_id)
+        assert journal.discard(job_id)["success"]
+        assert journal.get_incomplete() == []
+        assert not journal.discard(job_id)["success"]
+
+        # A corrupt journal starts empty instead of failing the backend
+        with open(os.path.join(tmp, "job_journal.json"), "w", encoding="utf-8") as f:
+            f.write("{not json")
+        assert JobJournal(FakeADB(), tmp).get_incomplete() == []
+    print("  └─ ✅ Discarding jobs OK")
+    return True
+
+
+def main():
+    """Run all job journal tests"""
+    tests = [test_record_on_every_item, test_finish, test_resume_after_restart, test_discard]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
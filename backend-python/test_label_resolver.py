@@ -0,0 +1,99 @@
+"""
+Test resolving real app labels from pulled APKs
+Runs against a fake device and a fake aapt - no ADB or SDK required
+"""
+import sys
+import os
+import subprocess
+import tempfile
+from types import SimpleNamespace
+sys.path.insert(0, os.path.dirname(__file__))
+
+import label_resolver
+from fake_adb import FakeADB
+from label_resolver import LabelResolver, MAX_LABELS_PER_CALL, parse_badging_label
+
+
+BADGING = (
+    "package: name='com.samsung.android.app.spage' versionCode='330400000'\n"
+    "application-label:'Samsung Free'\n"
+    "application-label-de:'Samsung Free'\n"
+)
+
+
+def make_adb():
+    """Spage has a split APK; com.missing.app is not installed"""
+    return FakeADB({"pm path com.samsung.android.app.spage": ("package:/data/app/spage/split_config.xxhdpi.apk\n"
+                                                              "package:/data/app/spage/base.apk\n")})
+
+
+def fake_aapt(command, **kwargs):
+    """aapt dump badging answering BADGING for the spage APK"""
+    return SimpleNamespace(stdout=BADGING if command[-1].endswith("com.samsung.android.app.spage.apk") else "")
+
+
+def test_badging():
+    """Test reading the label from aapt output"""
+    print("\n🏷️  Testing badging parsing...")
+    assert parse_badging_label(BADGING) == "Samsung Free"
+    assert parse_badging_label("application-label-en-US:'Bixby'\n") == "Bixby"
+    assert parse_badging_label("application-label:''\n") is None
+    assert parse_badging_label("package: name='x'\n") is None
+    print("  └─ ✅ Badging parsing OK")
+    return True
+
+
+def test_resolve_labels():
+    """Test resolving, caching per device and applying labels to a package list"""
+    print("\n📛 Testing label resolution...")
+    label_resolver.subprocess = SimpleNamespace(run=fake_aapt, TimeoutExpired=subprocess.TimeoutExpired)
+    try:
+        with tempfile.TemporaryDirectory() as tmp:
+            adb = make_adb()
+            resolver = LabelResolver(adb, tmp)
+            resolver.aapt_path = "aapt2"
+            result = resolver.resolve_labels(["com.samsung.android.app.spage", "com.missing.app"])
+            assert result["available"] and result["labels"] == {"com.samsung.android.app.spage": "Samsung Free"}
+            assert result["unresolved"] == ["com.missing.app"] and result["pending"] == []
+            # The base APK is pulled, not a split
+            assert adb.runs[0][-2] == "/data/app/spage/base.apk"
+
+            # Labels survive a restart and replace the guessed names
+            cached = LabelResolver(make_adb(), tmp)
+            packages = cached.apply_cached_labels([{"packageName": "com.samsung.android.app.spage", "appName": "Spage"},
+                                                   {"packageName": "com.facebook.katana", "appName": "Katana"}])
+            assert packages[0] == {"packageName": "com.samsung.android.app.spage", "appName": "Samsung Free",
+                                   "labelSource": "device"}
+            assert packages[1]["appName"] == "Katana"
+            assert cached.cached_label("com.samsung.android.app.spage") == "Samsung Free"
+
+            # Large requests are resolved in batches
+            many = [f"com.example.app{i}" for i in range(MAX_LABELS_PER_CALL + 3)]
+            assert resolver.resolve_labels(many)["pending"] == many[MAX_LABELS_PER_CALL:]
+
+            # Without aapt, only cached labels come back
+            cached.aapt_path = None
+            missing = cached.resolve_labels(["com.samsung.android.app.spage", "com.facebook.katana"])
+            assert missing["available"] is False and missing["unresolved"] == ["com.facebook.katana"]
+            assert missing["labels"] == {"com.samsung.android.app.spage": "Samsung Free"}
+    finally:
+        label_resolver.subprocess = subprocess
+    print("  └─ ✅ Label resolution OK")
+    return True
+
+
+def main():
+    """Run all label resolver tests"""
+    tests = [test_badging, test_resolve_labels]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,81 @@
+"""
+Test the network chokepoint (offline mode, proxy, CA bundle)
+No network, device or API key required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+import network
+from ai_advisor import AIAdvisor, local_analysis
+from analysis_cache import risk_from_analysis
+
+
+def test_offline_refuses_requests():
+    """Test that offline mode refuses requests before they leave the machine"""
+    print("\n📴 Testing offline mode...")
+    network.configure({**network.NETWORK_DEFAULTS, "offline": True}.get)
+    try:
+        try:
+            network.post("https://api.perplexity.ai/chat/completions", json={}, timeout=1)
+            assert False, "request was not refused"
+        except network.OfflineModeError:
+            pass
+
+        advisor = AIAdvisor()
+        advisor.api_key = "test-key"
+        reply = advisor.chat("Is com.facebook.katana safe to remove?")
+        assert "Offline mode" in reply["content"]
+    finally:
+        network.configure(network.NETWORK_DEFAULTS.get)
+    print("  └─ ✅ Requests refused")
+    return True
+
+
+def test_local_analysis():
+    """Test the offline fallback analysis and that it is never cached as an AI verdict"""
+    print("\n📋 Testing local analysis...")
+    analysis = local_analysis("com.android.systemui", "Dangerous")
+    assert analysis["riskCategory"] == "Dangerous" and analysis["safeToRemove"] is False
+    assert analysis["offline"] is True
+    assert risk_from_analysis(analysis) is None
+    print("  └─ ✅ Local analysis OK")
+    return True
+
+
+def test_proxy_and_ca_bundle():
+    """Test that the proxy and CA bundle reach every request and a dead proxy is reported"""
+    print("\n🌐 Testing proxy settings...")
+    settings = {**network.NETWORK_DEFAULTS, "proxy": "http://127.0.0.1:9", "caBundle": "/etc/corp-root.pem"}
+    network.configure(settings.get)
+    try:
+        options = network.request_options()
+        assert options["proxies"] == {"http": "http://127.0.0.1:9", "https": "http://127.0.0.1:9"}
+        assert options["verify"] == "/etc/corp-root.pem"
+
+        settings["caBundle"] = ""
+        result = network.test_connection("https://api.perplexity.ai/chat/completions")
+        assert result["success"] is False and result["url"] == "https://api.perplexity.ai/"
+        assert "proxy" in result["message"].lower() and result["proxy"] == "http://127.0.0.1:9"
+    finally:
+        network.configure(network.NETWORK_DEFAULTS.get)
+    assert network.request_options() == {}
+    print("  └─ ✅ Proxy settings OK")
+    return True
+
+
+def main():
+    """Run all network tests"""
+    tests = [test_offline_refuses_requests, test_local_analysis, test_proxy_and_ca_bundle]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
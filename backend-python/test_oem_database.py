@@ -0,0 +1,142 @@
+"""
+Test the bundled per-OEM safety ratings: file coverage, critical packages,
+lazy loading and how the ratings feed package listings
+Runs against the shipped oem_data/ files and canned device output - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from oem_database import OEM_MANUFACTURERS, SHARED_PACKS, OemDatabase, parse_oem, validate_pack
+
+
+# Smallest list each bundled file must keep
+MIN_PACKAGES = {
+    "samsung": 120, "xiaomi": 90, "oppo": 80, "vivo": 45, "oneplus": 30, "motorola": 20,
+    "realme": 3, "huawei": 35, "google": 30, "nokia": 15, "carriers": 25, "partners": 20,
+}
+
+# Removing these leaves the phone without a home screen or a way to place calls
+CRITICAL = {
+    "samsung": ["com.sec.android.app.launcher", "com.samsung.android.dialer", "com.samsung.android.incallui"],
+    "xiaomi": ["com.miui.home", "com.miui.securitycenter", "com.miui.core"],
+    "oppo": ["com.oppo.launcher"],
+    "vivo": ["com.bbk.launcher2"],
+    "oneplus": ["net.oneplus.launcher", "com.oneplus.dialer"],
+    "motorola": ["com.motorola.launcher3"],
+    "huawei": ["com.huawei.android.launcher"],
+    "google": ["com.google.android.apps.nexuslauncher", "com.google.android.dialer"],
+}
+
+# Well-known bloat every list should let users remove
+KNOWN_BLOAT = {
+    "samsung": ["com.samsung.android.bixby.agent", "com.samsung.android.app.spage", "com.sec.android.diagmonagent"],
+    "xiaomi": ["com.miui.analytics", "com.miui.msa.global"],
+    "oppo": ["com.heytap.mcs", "com.nearme.statistics.rom"],
+    "vivo": ["com.vivo.hiboard"],
+    "oneplus": ["net.oneplus.odm"],
+    "motorola": ["com.motorola.ccc.notification"],
+    "carriers": ["com.tmobile.pr.adapt", "com.vzw.hss.myverizon"],
+    "partners": ["com.facebook.appmanager", "com.facebook.services"],
+}
+
+
+class MakerDevice(FakeADB):
+    """Answers maker property reads; anything else looks like a phone"""
+
+    def __init__(self, props):
+        super().__init__(default="default\n")
+        self.props = props
+
+    def answer(self, command):
+        return self.props if "ro.product.manufacturer" in command else self.default
+
+    def supports(self, feature):
+        return False
+
+
+def test_files():
+    """Test that every bundled file is well-formed and keeps its coverage"""
+    print("\n📚 Testing bundled OEM files...")
+    db = OemDatabase()
+    assert set(OEM_MANUFACTURERS.values()) == set(db.available()), "every detected maker needs a file"
+    for name in db.available() + list(SHARED_PACKS):
+        pack = db.pack(name)
+        assert not validate_pack(pack), f"{name}: {validate_pack(pack)}"
+        assert len(pack["packages"]) >= MIN_PACKAGES[name], f"{name} lists {len(pack['packages'])} packages"
+        for included in pack.get("includes", []):
+            assert included in db.available(), f"{name} includes unknown {included}"
+    assert validate_pack({"packages": {"bad name": ["Unsafe", ""]}}) == [
+        "bad name: not a package name", "bad name: unknown safety level 'Unsafe'", "bad name: missing description"]
+    print("  └─ ✅ Files OK")
+    return True
+
+
+def test_ratings():
+    """Test critical and bloat packages per OEM, and the includes chain"""
+    print("\n🛡️  Testing OEM ratings...")
+    db = OemDatabase()
+    for oem, packages in CRITICAL.items():
+        for package in packages:
+            assert db.safety_level(oem, package) == "Dangerous", f"{oem}: {package}"
+    for oem, packages in KNOWN_BLOAT.items():
+        for package in packages:
+            assert db.safety_level(None if oem in SHARED_PACKS else oem, package) == "Safe", f"{oem}: {package}"
+    # OnePlus builds on ColorOS, and carrier apps turn up on every maker's phones
+    assert db.lookup("oneplus", "com.heytap.mcs")["source"] == "oppo"
+    assert db.lookup("samsung", "com.vzw.hss.myverizon")["source"] == "carriers"
+    assert db.lookup("samsung", "com.miui.analytics") is None
+    print("  └─ ✅ Ratings OK")
+    return True
+
+
+def test_detection_and_lazy_loading():
+    """Test maker detection and that only the detected OEM's files are read"""
+    print("\n🔎 Testing detection and lazy loading...")
+    assert parse_oem("samsung\nsamsung\n") == "samsung"
+    assert parse_oem("Xiaomi\nPOCO\n") == "xiaomi"
+    assert parse_oem("unknown\nRedmi\n") == "xiaomi"
+    assert parse_oem("HMD Global\nNokia\n") == "nokia"
+    assert parse_oem("Fairphone\nFairphone\n") is None
+    assert parse_oem("") is None
+
+    db = OemDatabase()
+    summary = db.summary("oneplus")
+    assert summary["loaded"] == ["carriers", "oneplus", "oppo", "partners"]
+    assert summary["packs"]["oneplus"] == len(db.pack("oneplus")["packages"])
+    assert summary["packageCount"] == len(db.packages("oneplus"))
+    assert db.summary(None)["oem"] is None
+    print("  └─ ✅ Detection and lazy loading OK")
+    return True
+
+
+def test_package_listing():
+    """Test that listings rate OEM packages from the detected maker's file"""
+    print("\n📋 Testing listing ratings...")
+    names = ["com.samsung.android.app.spage", "com.sec.android.app.launcher", "com.samsung.android.unlisted"]
+    samsung = {p["packageName"]: p["safetyLevel"] for p in MakerDevice("samsung\nsamsung\n").build_package_entries(names)}
+    assert samsung == {"com.samsung.android.app.spage": "Safe", "com.sec.android.app.launcher": "Dangerous",
+                       "com.samsung.android.unlisted": "Caution"}
+    other = MakerDevice("Fairphone\nFairphone\n")
+    assert other.get_oem() is None
+    assert other._determine_safety_level("com.samsung.android.app.spage") == "Caution", "generic rules elsewhere"
+    print("  └─ ✅ Listing ratings OK")
+    return True
+
+
+def main():
+    """Run all OEM database tests"""
+    tests = [test_files, test_ratings, test_detection_and_lazy_loading, test_package_listing]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -4,8 +4,10 @@ Run this to verify the command parser works correctly
 """
 import sys
 import os
+import threading
 sys.path.insert(0, os.path.dirname(__file__))
 
+from fake_adb import FakeADB
 from openclaw_integration import CommandParser, ActionExecutor, OpenClawIntegration
 from adb_operations import ADBOperations
 
@@ -112,6 +114,92 @@ def test_full_integration():
         print(f"⚠️  ADB not available: {str(e)}")
 
 
+class FakeReinstallingADB(FakeADB):
+    """Reports every uninstall as successful, but an OEM service puts one package back"""
+
+    def uninstall_package(self, package_name):
+        return {'success': True, 'message': f"Successfully uninstalled {package_name}"}
+
+    def verify_removal(self, package_names):
+        return {p: 'installed' if p == 'com.oem.store' else 'removed' for p in package_names}
+
+
+def test_uninstall_verification():
+    """Test that removals still listed by the device are reported as failures"""
+    print("\n" + "=" * 60)
+    print("TESTING UNINSTALL VERIFICATION")
+    print("=" * 60)
+    
+    executor = ActionExecutor(FakeReinstallingADB())
+    result = executor._execute_uninstall([{'packageName': 'com.facebook.katana'}, {'packageName': 'com.oem.store'}])
+    assert result['discrepancies'] == ['com.oem.store']
+    assert [d['success'] for d in result['details']] == [True, False]
+    assert result['details'][1]['verified'] == 'installed'
+    assert result['message'].startswith("Successfully removed 1/2 packages")
+    print(f"   {result['message']}")
+
+
+class DroppingADB(FakeADB):
+    """Unplugged during the second uninstall; `wait_for_device` blocks until the test plugs it back"""
+
+    def __init__(self):
+        super().__init__()
+        self.removed = []
+        self.waiting = threading.Event()
+        self.plugged = threading.Event()
+        self.returns = True
+
+    def uninstall_package(self, package_name):
+        if len(self.removed) == 1 and not self.plugged.is_set():
+            return {'success': False, 'deviceLost': True, 'message': "Device disconnected"}
+        self.removed.append(package_name)
+        return {'success': True, 'message': f"Successfully uninstalled {package_name}"}
+
+    def wait_for_device(self, timeout=60):
+        self.waiting.set()
+        return self.plugged.wait(timeout) and self.returns
+
+    def verify_removal(self, package_names):
+        return {p: 'removed' for p in package_names}
+
+
+def test_uninstall_reconnect():
+    """Test that a dropped device is waited for in the background, not in the command"""
+    print("\n" + "=" * 60)
+    print("TESTING UNINSTALL AFTER RECONNECT")
+    print("=" * 60)
+
+    packages = [{'packageName': p} for p in ('com.facebook.katana', 'com.facebook.services', 'com.netflix.mediaclient')]
+    adb, events, complete = DroppingADB(), [], threading.Event()
+
+    def emit(kind, payload, job_id=None):
+        events.append((kind, payload, job_id))
+        if kind == 'chat_uninstall_complete':
+            complete.set()
+
+    result = ActionExecutor(adb, emit)._execute_uninstall(packages)
+    # The command returns while the device is still gone
+    assert result['paused'] and result['pending'] == ['com.facebook.services', 'com.netflix.mediaclient']
+    assert adb.waiting.wait(5) and [e[0] for e in events] == ['device_lost']
+
+    adb.plugged.set()
+    assert complete.wait(5)
+    assert [e[0] for e in events] == ['device_lost', 'device_restored', 'chat_uninstall_complete']
+    assert {e[2] for e in events} == {result['jobId']}
+    final = events[-1][1]
+    assert final['success'] and final['message'].startswith("Successfully removed 3/3 packages")
+    assert adb.removed == [p['packageName'] for p in packages]
+
+    # The device never comes back: the job reports what was left
+    adb, events, complete = DroppingADB(), [], threading.Event()
+    adb.returns = False
+    ActionExecutor(adb, emit)._execute_uninstall(packages)
+    adb.plugged.set()
+    assert complete.wait(5)
+    assert events[-1][1]['interrupted'] and len(events[-1][1]['pending']) == 2
+    print(f"   {final['message']}")
+
+
 if __name__ == "__main__":
     print("\n")
     print("╔════════════════════════════════════════════════════════╗")
@@ -124,6 +212,8 @@ if __name__ == "__main__":
     test_command_parser()
     test_action_execution()
     test_full_integration()
+    test_uninstall_verification()
+    test_uninstall_reconnect()
     
     print("\n" + "=" * 60)
     print("✅ TESTS COMPLETE")
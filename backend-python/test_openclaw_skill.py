@@ -0,0 +1,87 @@
+"""
+Test that the OpenClaw skill drives the backend's own ADB operations
+Runs the skill from openclaw-skill/ against a fake device - no ADB or OpenClaw required
+"""
+import sys
+import os
+import importlib.util
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from backup_manager import BackupManager
+from fake_adb import FakeADB
+
+
+SKILL_PATH = os.path.join(os.path.dirname(__file__), "..", "openclaw-skill", "debloat-controller.py")
+
+PACKAGES = [
+    {"packageName": "com.facebook.katana", "appName": "Facebook", "safetyLevel": "Safe"},
+    {"packageName": "com.android.systemui", "appName": "System UI", "safetyLevel": "Dangerous"},
+    {"packageName": "com.samsung.android.bixby.agent", "appName": "Bixby", "safetyLevel": "Caution"},
+]
+
+
+def load_skill():
+    spec = importlib.util.spec_from_file_location("debloat_controller", SKILL_PATH)
+    module = importlib.util.module_from_spec(spec)
+    spec.loader.exec_module(module)
+    return module
+
+
+class SkillDevice(FakeADB):
+    """Lists PACKAGES; the real uninstall_package runs against the fake shell"""
+
+    def __init__(self):
+        super().__init__(sdk=34)
+
+    def list_packages(self, package_type="all"):
+        return [dict(p) for p in PACKAGES]
+
+    def get_device_info(self):
+        return {"model": "SM-G991B", "manufacturer": "samsung", "androidVersion": "14", "serial": "SERIAL1"}
+
+
+def test_skill_uses_backend():
+    """Test scanning, confirmed removal and backups through the shared backend modules"""
+    print("\n🦞 Testing OpenClaw skill...")
+    skill = load_skill()
+    assert skill.ADBOperations is sys.modules["adb_operations"].ADBOperations, "one ADB implementation"
+    with tempfile.TemporaryDirectory() as tmp:
+        adb = SkillDevice()
+        controller = skill.DebloatController(adb, BackupManager(tmp, adb))
+
+        scan = controller.scan_bloatware()
+        assert "Found 2 potential bloatware packages" in scan and "com.android.systemui" not in scan
+
+        prompt = controller.remove_package("facebook")
+        assert "com.facebook.katana (Facebook)" in prompt and controller.pending_removal == "com.facebook.katana"
+        assert adb.runs == [], "nothing is removed before the confirmation"
+        assert controller.confirm_action("remove facebook", True) == "✅ Successfully removed com.facebook.katana"
+        assert adb.runs[-1][-5:] == ["pm", "uninstall", "--user", "0", "com.facebook.katana"]
+        assert controller.confirm_action("remove facebook", True) == "❓ Nothing to confirm"
+
+        assert "rated DANGEROUS" in controller.remove_package("com.android.systemui")
+        assert controller.confirm_action("remove", False) == "❌ Action cancelled" and len(adb.runs) == 1
+        assert controller.remove_package("whatsapp zzz").startswith("❓ No installed package matches")
+
+        assert "All 3 current packages have been saved" in controller.create_backup()
+        assert BackupManager(tmp).list_backups()[0]["packageCount"] == 3
+    print("  └─ ✅ OpenClaw skill OK")
+    return True
+
+
+def main():
+    """Run all OpenClaw skill tests"""
+    tests = [test_skill_uses_backend]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,192 @@
+"""
+Test the one-click optimization pipeline
+Runs against a fake device and fake tweaks - no ADB required
+"""
+import sys
+import os
+import threading
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from oem_database import DETECTION_SCRIPT as OEM_SCRIPT
+from optimizer import Optimizer, diff_metrics
+
+
+LEVELS = {"com.facebook.katana": "Safe", "com.facebook.services": "Caution", "com.android.systemui": "Dangerous"}
+
+
+class OptimizerDevice(FakeADB):
+    """Removes packages until `lose_after` removals, when it drops off"""
+
+    def __init__(self, lose_after=None):
+        super().__init__()
+        self.installed = set(LEVELS) | {"com.netflix.mediaclient"}
+        self.lose_after = lose_after
+        self.removals = 0
+
+    def answer(self, command):
+        if command == "pm list packages":
+            return "".join(f"package:{p}\n" for p in sorted(self.installed))
+        return super().answer(command)
+
+    def get_overlay_targets(self):
+        return {}
+
+    def get_apex_packages(self):
+        return {}
+
+    def _determine_safety_level(self, package_name, *profile):
+        return LEVELS.get(package_name) or super()._determine_safety_level(package_name, *profile)
+
+    def uninstall_package(self, package_name, keep_data=False):
+        if self.lose_after is not None and self.removals >= self.lose_after:
+            return {"success": False, "deviceLost": True, "message": "Device disconnected"}
+        self.removals += 1
+        self.installed.discard(package_name)
+        return {"success": True, "message": f"Successfully uninstalled {package_name}"}
+
+    def verify_removal(self, package_names):
+        return {p: "installed" if p in self.installed else "removed" for p in package_names}
+
+
+class FakeHealth:
+    def __init__(self):
+        self.available = [4000000, 4500000]
+
+    def get_system_health(self):
+        return {"memory": {"availableKb": self.available.pop(0)}, "storage": {"internal": {"availableKb": 100}},
+                "services": {"count": 80}}
+
+
+class FakeTweaks:
+    def __init__(self):
+        self.calls = []
+
+    def restrict_background(self, package, restricted):
+        self.calls.append(("restrict", package))
+        return {"success": True, "message": f"Restricted {package}"}
+
+    def trim_caches(self):
+        self.calls.append(("trim", None))
+        return {"success": True, "message": "Trimmed caches"}
+
+    def set_animation_scale(self, scale):
+        self.calls.append(("animation", scale))
+        return {"success": True, "message": f"Animation scale {scale}"}
+
+
+class FakeCache:
+    def __init__(self):
+        self.invalidated = 0
+
+    def invalidate(self, serial=None):
+        self.invalidated += 1
+
+
+class FakeUndo:
+    def __init__(self):
+        self.records = []
+
+    def record(self, kind, package, result):
+        self.records.append((kind, package))
+
+
+def run(adb, plan):
+    """Run a plan to completion; returns (start result, events, optimizer parts)"""
+    events, done = [], threading.Event()
+
+    def emit(kind, payload, job_id=None):
+        events.append((kind, payload, job_id))
+        if kind == "optimization_complete":
+            done.set()
+
+    tweaks, cache, undo = FakeTweaks(), FakeCache(), FakeUndo()
+    optimizer = Optimizer(adb, FakeHealth(), tweaks, cache, undo, emit)
+    started = optimizer.run_optimization(plan)
+    assert done.wait(5), "the job did not finish"
+    return started, events, tweaks, cache, undo
+
+
+def test_full_plan():
+    """Test the stages, removal limits, progress events and impact summary of a plan"""
+    print("\n🚀 Testing optimization plan...")
+    plan = {"removePackages": ["com.facebook.katana", "com.facebook.services", "com.android.systemui"],
+            "restrictBackground": ["com.netflix.mediaclient"], "trimCaches": True, "animationScale": 0.5}
+    adb = OptimizerDevice()
+    started, events, tweaks, cache, undo = run(adb, plan)
+    assert started["success"] and started["stages"] == ["remove", "restrict", "trim", "tweaks"]
+    assert {job_id for _, _, job_id in events} == {started["jobId"]}
+
+    progress = [payload for kind, payload, _ in events if kind == "optimization_progress"]
+    assert [(p["stage"], p["status"]) for p in progress if p["status"] != "running"] == [
+        ("remove", "done"), ("restrict", "done"), ("trim", "done"), ("tweaks", "done")]
+    complete = events[-1][1]
+    assert complete["success"] and not complete["interrupted"]
+    removal = complete["stages"][0]
+    assert removal["succeeded"] == 2
+    assert removal["details"][2]["message"] == "Skipped: classified Dangerous"
+    assert "com.android.systemui" in adb.installed
+    assert complete["impact"]["memoryAvailableKb"] == 500000
+    assert tweaks.calls == [("restrict", "com.netflix.mediaclient"), ("trim", None), ("animation", 0.5)]
+    assert ("uninstall", "com.facebook.katana") in undo.records and cache.invalidated == 1
+    print("  └─ ✅ Optimization plan OK")
+    return True
+
+
+def test_interrupted_plan():
+    """Test that a lost device ends the remove stage and skips the rest"""
+    print("\n🔌 Testing interrupted optimization...")
+    plan = {"removePackages": ["com.facebook.katana", "com.facebook.services"], "trimCaches": True}
+    started, events, tweaks, _, _ = run(OptimizerDevice(lose_after=1), plan)
+    complete = events[-1][1]
+    assert complete["interrupted"] and not complete["success"]
+    assert [s["status"] for s in complete["stages"]] == ["interrupted", "skipped"]
+    assert tweaks.calls == []
+    print("  └─ ✅ Interrupted optimization OK")
+    return True
+
+
+def test_oem_ratings():
+    """Test that removals follow the maker's list of the connected device"""
+    print("\n🏷️  Testing OEM ratings in optimization...")
+    launcher = "com.sec.android.app.launcher"
+    assert OptimizerDevice().safety_rater()(launcher) == "Safe", "generic rules without a maker"
+    adb = OptimizerDevice()
+    adb.installed.add(launcher)
+    adb.shell_output[OEM_SCRIPT] = "samsung\nsamsung\n"
+    _, events, _, _, _ = run(adb, {"removePackages": [launcher]})
+    removal = events[-1][1]["stages"][0]
+    assert removal["details"][0]["message"] == "Skipped: classified Dangerous", "One UI Home on a Samsung"
+    assert launcher in adb.installed
+    print("  └─ ✅ OEM ratings OK")
+    return True
+
+
+def test_rejected_plans():
+    """Test an empty plan, a second plan while one runs and the metric diff"""
+    print("\n🚫 Testing rejected plans...")
+    optimizer = Optimizer(OptimizerDevice(), FakeHealth(), FakeTweaks(), FakeCache(), FakeUndo())
+    assert optimizer.run_optimization({})["message"] == "Nothing selected to optimize"
+    optimizer._job = {"id": "running"}
+    busy = optimizer.run_optimization({"trimCaches": True})
+    assert busy["success"] is False and busy["jobId"] == "running"
+    assert diff_metrics({"a": 1, "b": None}, {"a": 3, "b": 2}) == {"a": 2, "b": None}
+    print("  └─ ✅ Rejected plans OK")
+    return True
+
+
+def main():
+    """Run all optimizer tests"""
+    tests = [test_full_plan, test_interrupted_plan, test_oem_ratings, test_rejected_plans]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
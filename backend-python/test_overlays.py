@@ -0,0 +1,119 @@
+"""
+Test runtime resource overlay detection, listing tags and toggling
+Runs against canned device output - no ADB required
+"""
+import sys
+import os
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from overlays import is_locked, parse_overlay_list
+from undo_manager import UndoStack
+
+
+OVERLAY_LIST = """com.android.systemui
+[x] com.android.theme.icon_pack.filled.systemui
+[ ] com.android.theme.icon_pack.rounded.systemui
+
+android
+[x] com.android.internal.display.cutout.emulation.corner
+[ ] com.android.internal.systemui.navbar.threebutton
+[x] com.android.internal.systemui.navbar.gestural
+--- com.google.android.overlay.modules.ext.services
+[x] com.android.shell:com.android.systemui.fabricated
+"""
+
+
+class OverlayDevice(FakeADB):
+    """Answers `cmd overlay` with canned output"""
+
+    def __init__(self, sdk=30, toggle_output=""):
+        super().__init__({"cmd overlay list": OVERLAY_LIST}, sdk=sdk)
+        self.toggle_output = toggle_output
+
+    def answer(self, command):
+        if command.startswith("cmd overlay") and command != "cmd overlay list":
+            return self.toggle_output
+        return super().answer(command)
+
+
+def test_parse():
+    """Test parsing `cmd overlay list`"""
+    print("\n🎨 Testing overlay parsing...")
+    overlays = {o["id"]: o for o in parse_overlay_list(OVERLAY_LIST)}
+    assert overlays["com.android.theme.icon_pack.filled.systemui"] == {
+        "id": "com.android.theme.icon_pack.filled.systemui",
+        "packageName": "com.android.theme.icon_pack.filled.systemui",
+        "target": "com.android.systemui",
+        "state": "enabled",
+    }
+    assert overlays["com.android.theme.icon_pack.rounded.systemui"]["state"] == "disabled"
+    assert overlays["com.google.android.overlay.modules.ext.services"]["state"] == "unavailable"
+    assert overlays["com.android.shell:com.android.systemui.fabricated"]["packageName"] == "com.android.shell"
+    assert is_locked("com.android.internal.systemui.navbar.gestural")
+    assert not is_locked("com.android.internal.display.cutout.emulation.corner")
+    assert parse_overlay_list("") == []
+    print("  └─ ✅ Overlay parsing OK")
+    return True
+
+
+def test_listing_tags():
+    """Test that overlays are tagged with their target and kept out of removal"""
+    print("\n🏷️  Testing listing tags...")
+    adb = OverlayDevice()
+    entries = {p["packageName"]: p for p in adb.build_package_entries(
+        ["com.android.theme.icon_pack.filled.systemui", "com.facebook.katana"])}
+    overlay = entries["com.android.theme.icon_pack.filled.systemui"]
+    assert overlay["overlayTarget"] == "com.android.systemui" and overlay["removable"] is False
+    assert "overlayTarget" not in entries["com.facebook.katana"]
+    assert "removable" not in entries["com.facebook.katana"]
+
+    # Android 7 has no `cmd overlay`; listings are left untagged
+    old = OverlayDevice(sdk=24)
+    assert "overlayTarget" not in old.build_package_entries(["com.android.theme.icon_pack.filled.systemui"])[0]
+    assert "cmd overlay list" not in old.commands
+    print("  └─ ✅ Listing tags OK")
+    return True
+
+
+def test_toggle():
+    """Test turning overlays on and off, and undoing it"""
+    print("\n🔀 Testing overlay toggles...")
+    adb = OverlayDevice()
+    result = adb.set_overlay_enabled("com.android.theme.icon_pack.rounded.systemui", True)
+    assert result["success"] and adb.commands[-1] == (
+        "cmd overlay enable --user 0 com.android.theme.icon_pack.rounded.systemui 2>&1")
+
+    result = adb.set_overlay_enabled("com.android.internal.systemui.navbar.gestural", False)
+    assert not result["success"] and result["locked"]
+
+    failing = OverlayDevice(toggle_output="Error: com.android.overlay.static is not mutable")
+    assert not failing.set_overlay_enabled("com.android.overlay.static", False)["success"]
+
+    assert OverlayDevice(sdk=24).set_overlay_enabled("com.android.theme.icon_pack.rounded.systemui", True)["unsupported"]
+
+    with tempfile.TemporaryDirectory() as tmp:
+        undo = UndoStack(adb, None, tmp)
+        undo.record("overlay_enable", "com.android.theme.icon_pack.rounded.systemui", result={"success": True})
+        assert undo.undo()["success"]
+        assert adb.commands[-1] == "cmd overlay disable --user 0 com.android.theme.icon_pack.rounded.systemui 2>&1"
+    print("  └─ ✅ Overlay toggles OK")
+    return True
+
+
+def main():
+    """Run all overlay tests"""
+    tests = [test_parse, test_listing_tags, test_toggle]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,118 @@
+"""
+Test sorting, filtering and paging the cached package listing
+Runs against a fake device - no ADB required
+"""
+import sys
+import os
+from datetime import datetime
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from package_cache import PackageCache, MAX_PAGE_SIZE, parse_last_used
+
+
+PACKAGES = [
+    {"packageName": "com.facebook.katana", "appName": "Facebook", "safetyLevel": "Safe", "isSystem": False},
+    {"packageName": "com.android.systemui", "appName": "System UI", "safetyLevel": "Dangerous", "isSystem": True},
+    {"packageName": "com.samsung.android.bixby.agent", "appName": "bixby", "safetyLevel": "Caution", "isSystem": True},
+    {"packageName": "com.spotify.music", "appName": "Spotify", "safetyLevel": "Safe", "isSystem": False},
+]
+
+USAGESTATS = (
+    '    package=com.spotify.music lastTimeUsed="2026-10-01 08:00:00" totalTime="1:00"\n'
+    '    package=com.facebook.katana lastTimeUsed="2026-09-01 08:00:00" totalTime="0:10"\n'
+    '    package=com.spotify.music lastTimeUsed="2026-09-15 08:00:00" totalTime="2:00"\n'
+)
+
+
+class CachedDevice(FakeADB):
+    """Lists PACKAGES; Bixby has no measured size"""
+
+    def __init__(self):
+        super().__init__({"dumpsys usagestats": USAGESTATS})
+        self.listings = 0
+
+    def list_packages(self, package_type):
+        self.listings += 1
+        return [dict(pkg) for pkg in PACKAGES]
+
+    def get_package_sizes(self):
+        return {"com.facebook.katana": {"totalBytes": 300}, "com.android.systemui": {"totalBytes": 50},
+                "com.spotify.music": {"totalBytes": 120}}
+
+
+def names(page):
+    return [p["packageName"] for p in page["items"]]
+
+
+def test_sorting():
+    """Test every sort key, with unknown sizes and last-used times last in both directions"""
+    print("\n🔃 Testing package sorting...")
+    cache = PackageCache(CachedDevice())
+    assert names(cache.query())[0] == "com.android.systemui"
+    assert names(cache.query("appName"))[:2] == ["com.samsung.android.bixby.agent", "com.facebook.katana"]
+    assert names(cache.query("safety", "desc"))[:2] == ["com.android.systemui", "com.samsung.android.bixby.agent"]
+    assert names(cache.query("size", "desc")) == ["com.facebook.katana", "com.spotify.music", "com.android.systemui",
+                                                  "com.samsung.android.bixby.agent"]
+    assert names(cache.query("size"))[-1] == "com.samsung.android.bixby.agent"
+    assert names(cache.query("lastUsed", "desc"))[:2] == ["com.spotify.music", "com.facebook.katana"]
+    assert cache.packages()[3]["lastUsed"] == datetime(2026, 10, 1, 8).timestamp()
+    try:
+        cache.query("installDate")
+        assert False, "unknown sort key accepted"
+    except ValueError as e:
+        assert "lastUsed" in str(e)
+    print("  └─ ✅ Package sorting OK")
+    return True
+
+
+def test_filters_and_pages():
+    """Test search, safety and type filters and the page window"""
+    print("\n📄 Testing package filters and pages...")
+    adb = CachedDevice()
+    cache = PackageCache(adb)
+    assert names(cache.query(filters={"search": "SPOT"})) == ["com.spotify.music"]
+    assert names(cache.query(filters={"search": "bixby"})) == ["com.samsung.android.bixby.agent"]
+    assert names(cache.query(filters={"safetyLevels": ["Safe"], "type": "user"})) == ["com.facebook.katana",
+                                                                                     "com.spotify.music"]
+    assert cache.query(filters={"type": "system"})["total"] == 2
+
+    page = cache.query(offset=1, limit=2)
+    assert page["total"] == 4 and page["offset"] == 1 and names(page) == ["com.facebook.katana",
+                                                                          "com.samsung.android.bixby.agent"]
+    assert cache.query(offset=-5, limit=0)["offset"] == 0 and cache.query(limit=0)["limit"] == 1
+    assert cache.query(limit=10 ** 6)["limit"] == MAX_PAGE_SIZE
+    assert adb.listings == 1, "every page comes from the one cached listing"
+
+    cache.invalidate()
+    cache.query()
+    assert adb.listings == 2
+    print("  └─ ✅ Package filters and pages OK")
+    return True
+
+
+def test_last_used_parsing():
+    """Test that the latest of several usagestats entries wins"""
+    print("\n🕒 Testing last-used parsing...")
+    last_used = parse_last_used(USAGESTATS + '    package=com.bad lastTimeUsed="yesterday"\n')
+    assert last_used == {"com.spotify.music": datetime(2026, 10, 1, 8).timestamp(),
+                         "com.facebook.katana": datetime(2026, 9, 1, 8).timestamp()}
+    print("  └─ ✅ Last-used parsing OK")
+    return True
+
+
+def main():
+    """Run all package cache tests"""
+    tests = [test_sorting, test_filters_and_pages, test_last_used_parsing]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,69 @@
+"""
+Test grouping vendor suites into package families
+Runs against package lists - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from package_families import family_for, group_packages
+
+
+def pkg(name, level="Safe"):
+    return {"packageName": name, "safetyLevel": level}
+
+
+def test_family_for():
+    """Test that specific families win over the vendor-wide ones"""
+    print("\n👪 Testing family lookup...")
+    assert family_for("com.instagram.android")["id"] == "facebook"
+    assert family_for("com.samsung.android.bixby.agent")["id"] == "bixby"
+    assert family_for("com.samsung.android.app.notes")["id"] == "samsung"
+    assert family_for("com.google.android.apps.maps")["id"] == "google_apps"
+    assert family_for("com.google.android.gms") is None
+    print("  └─ ✅ Family lookup OK")
+    return True
+
+
+def test_group_packages():
+    """Test aggregate safety and size, removable members and automatic vendor groups"""
+    print("\n📦 Testing package grouping...")
+    packages = [
+        pkg("com.facebook.katana"), pkg("com.facebook.services", "Caution"), pkg("com.facebook.appmanager", "Expert"),
+        pkg("com.acme.launcher"), pkg("com.acme.store"), pkg("com.acme.weather", "Dangerous"),
+        pkg("com.tiny.one"), pkg("com.tiny.two"),
+        pkg("com.android.one"), pkg("com.android.two"), pkg("com.android.three"),
+    ]
+    sizes = {"com.facebook.katana": {"totalBytes": 300}, "com.facebook.services": {"totalBytes": 20}}
+    families = {f["id"]: f for f in group_packages(packages, sizes)}
+
+    # Two members are too few for a vendor group, and AOSP is never grouped
+    assert set(families) == {"facebook", "com.acme"}
+    facebook = families["facebook"]
+    assert facebook["packageCount"] == 3 and facebook["worstSafetyLevel"] == "Expert"
+    assert facebook["safetyBreakdown"] == {"Safe": 1, "Caution": 1, "Expert": 1, "Dangerous": 0}
+    assert facebook["removablePackages"] == ["com.facebook.katana", "com.facebook.services"]
+    assert facebook["totalSizeBytes"] == 320
+
+    acme = families["com.acme"]
+    assert acme["name"] == "com.acme.*" and acme["worstSafetyLevel"] == "Dangerous"
+    assert acme["totalSizeBytes"] is None
+    print("  └─ ✅ Package grouping OK")
+    return True
+
+
+def main():
+    """Run all package family tests"""
+    tests = [test_family_for, test_group_packages]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
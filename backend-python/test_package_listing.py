@@ -0,0 +1,66 @@
+"""
+Test that a package listing is one adb call and stays within the benchmark target
+Runs against the synthetic device of bench_package_listing.py - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from bench_package_listing import DEFAULT_PACKAGE_COUNT, TARGET_SECONDS, SyntheticADB, run
+
+
+class CountingADB(SyntheticADB):
+    """SyntheticADB keeping the adb invocations it answered"""
+
+    def __init__(self, package_count):
+        super().__init__(package_count)
+        self.calls = []
+
+    def _run_command(self, command, timeout=30):
+        self.calls.append(command)
+        return super()._run_command(command, timeout)
+
+
+def test_single_call_listing():
+    """Test that the full listing and its third-party flags come from one exec-out call"""
+    print("\n📦 Testing single-call listing...")
+    adb = CountingADB(60)
+    adb.list_packages("all")
+    # Once the device profile (class, OEM, signatures, APEX modules) is cached, a listing is one adb call
+    adb.calls.clear()
+    packages = adb.list_packages("all")
+    calls = [c for c in adb.calls if c[1] != "get-serialno"]
+    assert calls == [[adb.adb_path, "exec-out", "pm list packages -f -i; echo @@user; pm list packages -3"]], calls
+    assert len(packages) == 60
+    assert [p["packageName"] for p in packages] == sorted(p["packageName"] for p in packages)
+    user = {p["packageName"] for p in packages if not p["isSystem"]}
+    assert user == {p["packageName"] for p in packages if p["packageName"].startswith("com.example")}
+    assert all(p["installer"] == "com.android.vending" for p in packages)
+    print("  └─ ✅ Single-call listing OK")
+    return True
+
+
+def test_listing_throughput():
+    """Test that listing, caching and paging a 3000-package device stays under the target"""
+    print("\n⏱️  Testing listing throughput...")
+    best = run(DEFAULT_PACKAGE_COUNT, 3)
+    assert best < TARGET_SECONDS, f"{DEFAULT_PACKAGE_COUNT} packages took {best:.2f}s"
+    print(f"  └─ ✅ Listing throughput OK ({best * 1000:.0f} ms)")
+    return True
+
+
+def main():
+    """Run all package listing tests"""
+    tests = [test_single_call_listing, test_listing_throughput]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
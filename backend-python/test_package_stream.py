@@ -0,0 +1,95 @@
+"""
+Test acknowledged package streaming
+Runs against a generated package list - no device required
+"""
+import sys
+import os
+import threading
+sys.path.insert(0, os.path.dirname(__file__))
+
+from package_stream import (
+    PackageStreamer, next_chunk_size, INITIAL_CHUNK_SIZE, MIN_CHUNK_SIZE, MAX_CHUNK_SIZE,
+)
+from package_wire import expand_packages
+
+
+PACKAGES = [{"packageName": f"com.example.app{i:04d}", "appName": f"App{i}", "safetyLevel": "Safe"}
+            for i in range(1000)]
+
+
+def test_chunk_sizing():
+    """Test that fast acks grow chunks and slow acks shrink them within bounds"""
+    print("\n📏 Testing chunk sizing...")
+    assert next_chunk_size(100, 0.01) == 200
+    assert next_chunk_size(100, 0.1) == 100
+    assert next_chunk_size(100, 1.0) == 50
+    assert next_chunk_size(MAX_CHUNK_SIZE, 0.0) == MAX_CHUNK_SIZE
+    assert next_chunk_size(MIN_CHUNK_SIZE, 5.0) == MIN_CHUNK_SIZE
+    print("  └─ ✅ Chunk sizing OK")
+    return True
+
+
+def test_stream_with_acks():
+    """Test that an acknowledging consumer receives every package in growing chunks"""
+    print("\n📡 Testing acknowledged stream...")
+    chunks = []
+    finished = threading.Event()
+
+    def emit(kind, payload, job_id=None):
+        assert kind == "package_chunk"
+        chunks.append(payload)
+        if payload["done"]:
+            finished.set()
+        else:
+            threading.Thread(target=streamer.ack, args=(job_id, payload["index"])).start()
+
+    streamer = PackageStreamer(emit)
+    start = streamer.start(PACKAGES)
+    assert start["total"] == len(PACKAGES)
+    assert finished.wait(5), "stream did not finish"
+
+    received = [pkg for chunk in chunks for pkg in expand_packages(chunk["packages"])]
+    assert received == PACKAGES
+    sizes = [len(chunk["packages"]["rows"]) for chunk in chunks]
+    assert sizes[0] == INITIAL_CHUNK_SIZE and sizes[1] > sizes[0]
+    assert [chunk["index"] for chunk in chunks] == list(range(len(chunks)))
+    assert streamer.ack(start["jobId"], 0)["success"] is False  # Finished streams are forgotten
+    print(f"  └─ ✅ {len(chunks)} chunks: {sizes}")
+    return True
+
+
+def test_stream_stops_without_ack():
+    """Test that a stream waits for its ack and can be cancelled"""
+    print("\n⏸️  Testing unacknowledged stream...")
+    chunks = []
+    first = threading.Event()
+
+    def emit(kind, payload, job_id=None):
+        chunks.append(payload)
+        first.set()
+
+    streamer = PackageStreamer(emit, ack_timeout=5)
+    job_id = streamer.start(PACKAGES)["jobId"]
+    assert first.wait(5)
+    assert streamer.ack(job_id, 3)["success"] is False  # Not the chunk being waited for
+    assert streamer.cancel(job_id)["success"] is True
+    assert len(chunks) == 1 and not chunks[0]["done"]
+    print("  └─ ✅ Stream paused and cancelled")
+    return True
+
+
+def main():
+    """Run all streaming tests"""
+    tests = [test_chunk_sizing, test_stream_with_acks, test_stream_stops_without_ack]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
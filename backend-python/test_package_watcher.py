@@ -0,0 +1,88 @@
+"""
+Test detection of packages installed or removed outside the app
+Runs against fakes - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from package_watcher import PackageWatcher, diff_packages
+
+
+class WatchedDevice(FakeADB):
+    """A device whose package list the test edits between polls"""
+
+    def __init__(self):
+        super().__init__()
+        self.packages = {"com.android.chrome", "com.facebook.katana", "com.spotify.music"}
+        self.own = []
+
+    def answer(self, command):
+        return "".join(f"package:{p}\n" for p in sorted(self.packages))
+
+    def package_changes_since(self, since):
+        return self.own
+
+
+class FakeCache:
+    def __init__(self):
+        self.invalidated = []
+
+    def invalidate(self, serial=None):
+        self.invalidated.append(serial)
+
+
+def test_diff_packages():
+    """Test that the app's own changes are left out"""
+    print("\n🔍 Testing package diffs...")
+    before, after = {"a", "b", "c"}, {"b", "c", "d", "e"}
+    assert diff_packages(before, after, set()) == {"installed": ["d", "e"], "removed": ["a"]}
+    assert diff_packages(before, after, {"a", "d"}) == {"installed": ["e"], "removed": []}
+    # An APK install of an unknown package hides every install of the round
+    assert diff_packages(before, after, {""}) == {"installed": [], "removed": ["a"]}
+    assert diff_packages(before, after, {"", "a"}) is None
+    print("  └─ ✅ Package diffs OK")
+    return True
+
+
+def test_poll():
+    """Test events for outside changes, silence for own changes and a new device"""
+    print("\n👀 Testing the package watcher...")
+    adb, cache, events = WatchedDevice(), FakeCache(), []
+    watcher = PackageWatcher(adb, cache, lambda kind, payload, job_id=None: events.append((kind, payload)))
+
+    assert watcher.poll() is None  # First listing
+    adb.packages.add("com.whatsapp")
+    adb.packages.discard("com.facebook.katana")
+    change = watcher.poll()
+    assert change == {"installed": ["com.whatsapp"], "removed": ["com.facebook.katana"], "serial": "SERIAL1"}
+    assert events == [("external_package_change", change)] and cache.invalidated == ["SERIAL1"]
+
+    # Removed through the app
+    adb.packages.discard("com.spotify.music")
+    adb.own = ["com.spotify.music"]
+    assert watcher.poll() is None and len(events) == 1
+
+    # Another device: its listing is new, not a change
+    adb.serial, adb.packages, adb.own = "SERIAL2", {"com.android.chrome"}, []
+    assert watcher.poll() is None and len(events) == 1
+    print("  └─ ✅ Package watcher OK")
+    return True
+
+
+def main():
+    """Run all package watcher tests"""
+    tests = [test_diff_packages, test_poll]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
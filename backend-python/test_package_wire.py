@@ -0,0 +1,70 @@
+"""
+Test the compact package wire format
+Runs against generated package lists - no device required
+"""
+import sys
+import os
+import json
+sys.path.insert(0, os.path.dirname(__file__))
+
+from package_wire import compact_packages, expand_packages, PACKAGE_FIELDS
+
+
+PACKAGES = [
+    {"packageName": "com.facebook.katana", "appName": "Facebook", "safetyLevel": "Safe",
+     "labelSource": "device", "isSystem": False, "sizeBytes": 123456, "lastUsed": None},
+    {"packageName": "com.android.systemui", "appName": "Systemui", "safetyLevel": "Dangerous",
+     "isSystem": True, "sizeBytes": None},
+    {"packageName": "com.mdm.agent", "appName": "Agent", "safetyLevel": "Safe",
+     "removable": False, "managedReason": "Device management app"},
+]
+
+
+def test_round_trip():
+    """Test that expanding a compact listing gives the packages back"""
+    print("\n🔁 Testing round trip...")
+    payload = compact_packages(PACKAGES)
+    assert payload["format"] == "compact" and payload["version"] == 1
+    assert payload["fields"] == [f for f in PACKAGE_FIELDS if any(f in p for p in PACKAGES)]
+    assert payload["strings"].count("Safe") == 1
+    assert payload["rows"][2][payload["fields"].index("removable")] is False
+
+    expected = [{k: v for k, v in p.items() if v is not None} for p in PACKAGES]
+    assert expand_packages(payload) == expected
+    assert expand_packages(compact_packages([])) == []
+    print("  └─ ✅ Round trip OK")
+    return True
+
+
+def test_size_reduction():
+    """Test that a large listing shrinks on the wire"""
+    print("\n📦 Testing payload size...")
+    vendors = ["com.samsung.android", "com.google.android", "com.android", "com.example"]
+    packages = [
+        {"packageName": f"{vendors[i % 4]}.app{i}", "appName": vendors[i % 4].split('.')[1].capitalize(),
+         "safetyLevel": ["Safe", "Caution", "Expert"][i % 3], "isSystem": i % 4 != 3}
+        for i in range(3000)
+    ]
+    plain = len(json.dumps(packages))
+    compact = len(json.dumps(compact_packages(packages)))
+    print(f"  ├─ {plain} -> {compact} bytes")
+    assert compact < plain * 0.6
+    print("  └─ ✅ Payload size OK")
+    return True
+
+
+def main():
+    """Run all wire format tests"""
+    tests = [test_round_trip, test_size_reduction]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,101 @@
+"""
+Test parser failure capture and the diagnostics bundle
+Runs against a fake device - no ADB required
+"""
+import sys
+import os
+import json
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from parser_diagnostics import ParserDiagnostics, MAX_SNIPPET_CHARS
+from system_health import SystemHealth
+
+
+class UnknownFormatDevice(FakeADB):
+    """Device whose df and thermalservice output uses an unknown format"""
+
+    def __init__(self, outputs):
+        super().__init__()
+        self.outputs = outputs
+
+    def answer(self, command):
+        for prefix, output in self.outputs.items():
+            if command.startswith(prefix):
+                return output
+        return ""
+
+    def supports(self, feature):
+        return True
+
+
+def test_health_records_failures():
+    """Test that unreadable output is recorded once per parser with an event"""
+    print("\n🩺 Testing parser failure capture...")
+    events = []
+    diagnostics = ParserDiagnostics(lambda kind, payload, job_id=None: events.append((kind, payload["parser"])))
+    health = SystemHealth(UnknownFormatDevice({
+        "LC_ALL=C df": "Speicher  Gesamt  Belegt\n/data  ??  ??\n",
+        "dumpsys thermalservice": "Thermal HAL unavailable on this vendor build\n",
+        "dumpsys battery": "",
+    }), diagnostics)
+    assert health.get_storage_info() is None
+    assert health.get_storage_info() is None
+    assert health.get_temperature_info() is None
+
+    failures = {f["parser"]: f for f in diagnostics.list_failures()}
+    assert set(failures) == {"storage", "thermal"}, "empty battery output is not a parser failure"
+    assert failures["storage"]["count"] == 2 and "snippet" not in failures["storage"]
+    assert failures["storage"]["reason"] == "format"
+    assert events == [("parser_failed", "storage"), ("parser_failed", "thermal")]
+
+    # A shell printing in a legacy code page: the undecodable bytes became U+FFFD
+    diagnostics.clear()
+    health = SystemHealth(UnknownFormatDevice({"dumpsys thermalservice": "\ufffd\ufffd\ufffd: 38\ufffd\n"}), diagnostics)
+    assert health.get_temperature_info() is None
+    assert [f["reason"] for f in diagnostics.list_failures()] == ["encoding"]
+    print("  └─ ✅ Failure capture OK")
+    return True
+
+
+def test_export_consent():
+    """Test that raw output is only exported when the user includes it"""
+    print("\n📦 Testing diagnostics export...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        diagnostics = ParserDiagnostics(data_dir=data_dir)
+        assert not diagnostics.export_diagnostics(True)["success"]
+        diagnostics.record_failure("storage", "df -k /data", "x" * (MAX_SNIPPET_CHARS + 500))
+
+        without = diagnostics.export_diagnostics(False, {"model": "Pixel 8"})
+        with open(without["path"], encoding="utf-8") as f:
+            bundle = json.load(f)
+        assert not bundle["includesOutput"] and "snippet" not in bundle["failures"][0]
+        assert bundle["device"] == {"model": "Pixel 8"}
+
+        with_output = diagnostics.export_diagnostics(True)
+        with open(with_output["path"], encoding="utf-8") as f:
+            failure = json.load(f)["failures"][0]
+        assert len(failure["snippet"]) == MAX_SNIPPET_CHARS and failure["outputChars"] == MAX_SNIPPET_CHARS + 500
+
+        diagnostics.clear()
+        assert diagnostics.list_failures() == []
+    print("  └─ ✅ Diagnostics export OK")
+    return True
+
+
+def main():
+    """Run all parser diagnostics tests"""
+    tests = [test_health_records_failures, test_export_consent]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
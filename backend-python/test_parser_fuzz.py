@@ -0,0 +1,198 @@
+"""
+Property tests for the shell output parsers (df, /proc/meminfo, adb devices,
+dumpsys diskstats): randomized whitespace, localized numbers and cut-off
+output must give the same values or none at all, never zeros or wrong numbers
+Runs against generated output - no ADB required
+"""
+import sys
+import os
+import random
+sys.path.insert(0, os.path.dirname(__file__))
+
+from adb_operations import parse_devices, parse_diskstats
+from fake_adb import FakeADB
+from parser_diagnostics import ParserDiagnostics
+from system_health import SystemHealth, parse_df, parse_meminfo
+
+
+SEED = 984
+RUNS = 300
+
+DF = (
+    "Filesystem            1K-blocks     Used Available Use% Mounted on\n"
+    "/dev/block/dm-8        115249236 60123456  55125780  53% /data\n"
+    "/dev/block/dm-12        62000000  2000000  60000000   4% /mnt/expand/1234-abcd\n"
+    "tmpfs                    1905688     1234   1904454   1% /dev\n"
+)
+LEGACY_DF = (
+    "Filesystem             Size   Used   Free   Blksize\n"
+    "/data                  5.9G   2.0G   3.9G   4096\n"
+    "/system                1.5G   1.2G 300.0M   4096\n"
+)
+MEMINFO = (
+    "MemTotal:        7812345 kB\n"
+    "MemFree:          123456 kB\n"
+    "MemAvailable:    2812345 kB\n"
+    "Buffers:          104448 kB\n"
+    "Cached:          2398208 kB\n"
+    "SwapTotal:       4194300 kB\n"
+    "SwapFree:        1048575 kB\n"
+    "HugePages_Total:       0\n"
+)
+DEVICES = (
+    "* daemon not running; starting now at tcp:5037\n"
+    "* daemon started successfully\n"
+    "List of devices attached\n"
+    "R58M12345AB\tdevice product:beyond1lte model:SM_G973F device:beyond1 transport_id:1\n"
+    "emulator-5554\toffline\n"
+    "192.168.1.20:5555\tunauthorized\n"
+    "\n"
+)
+DISKSTATS = (
+    "Latency: 2ms [512B Data Write]\n"
+    "Data-Free: 55125780K / 115249236K total = 47% free\n"
+    'Package Names: ["com.facebook.katana","com.spotify.music","com.android.chrome"]\n'
+    "App Sizes: [251658240,104857600,314572800]\n"
+    "App Data Sizes: [524288000,2147483648,73400320]\n"
+    "Cache Sizes: [209715200,1048576000,52428800]\n"
+)
+
+
+def reflow(text: str, rng: random.Random) -> str:
+    """Same output with other field separators, line endings and blank lines"""
+    lines = []
+    for line in text.split('\n'):
+        fields = line.split(' ')
+        line = fields[0]
+        for field in fields[1:]:
+            line += rng.choice([" ", "  ", "\t", " \t "]) + field
+        lines.append(line + rng.choice(["", " ", "\t"]) if line else line)
+        if rng.random() < 0.1:
+            lines.append(rng.choice(["", "   "]))
+    return rng.choice(["\n", "\r\n"]).join(lines)
+
+
+def localize(text: str, rng: random.Random) -> str:
+    """Digit groups and decimal commas as a localized shell would print them"""
+    def group(match_text: str) -> str:
+        separator = rng.choice([",", ".", " ", " "])
+        digits = match_text
+        groups = []
+        while len(digits) > 3:
+            groups.insert(0, digits[-3:])
+            digits = digits[:-3]
+        return separator.join([digits] + groups)
+
+    words = []
+    for word in text.split(' '):
+        if word.isdigit() and len(word) > 3 and rng.random() < 0.5:
+            word = group(word)
+        elif "." in word and word[-1:] in "GM" and rng.random() < 0.5:
+            word = word.replace(".", ",")
+        words.append(word)
+    return ' '.join(words)
+
+
+def truncate(text: str, rng: random.Random) -> str:
+    """Output cut off at a random point, as when a command times out"""
+    return text[:rng.randrange(len(text))]
+
+
+def by_filesystem(entries):
+    return {e["filesystem"]: (e["totalKb"], e["usedKb"], e["availableKb"]) for e in entries}
+
+
+def assert_subset(found: dict, expected: dict, label: str, seed: int):
+    """Every value the parser still reports is the one in the intact output"""
+    for key, value in found.items():
+        assert key in expected, f"{label} (run {seed}): invented {key!r}"
+        assert value == expected[key], f"{label} (run {seed}): {key!r} read as {value!r}, not {expected[key]!r}"
+
+
+def test_df():
+    """Test df parsing under reflowed, localized and cut-off output"""
+    print("\n💽 Fuzzing df parsing...")
+    for sample in (DF, LEGACY_DF):
+        expected = by_filesystem(parse_df(sample))
+        assert len(expected) == sample.count("\n") - 1
+        for run in range(RUNS):
+            rng = random.Random(SEED + run)
+            assert by_filesystem(parse_df(reflow(sample, rng))) == expected, f"reflow (run {run})"
+            assert_subset(by_filesystem(parse_df(localize(sample, rng))), expected, "localized", run)
+            cut = by_filesystem(parse_df(truncate(sample, rng)))
+            # The mount point is the only cell after the sizes in toybox df
+            assert_subset(cut, expected, "truncated", run)
+    # A row whose sizes are grouped or use a decimal comma drops out instead of shifting columns
+    assert "/dev/block/dm-8" not in by_filesystem(parse_df(DF.replace("115249236", "115 249 236")))
+    assert "/dev/block/dm-8" not in by_filesystem(parse_df(DF.replace("60123456", "60.123.456")))
+    assert "/data" not in by_filesystem(parse_df(LEGACY_DF.replace("3.9G", "3,9G")))
+    print("  └─ ✅ df OK")
+    return True
+
+
+def test_meminfo():
+    """Test /proc/meminfo parsing and that missing free figures are a parser failure"""
+    print("\n🧠 Fuzzing meminfo parsing...")
+    expected = parse_meminfo(MEMINFO)
+    assert "HugePages_Total" not in expected and expected["MemTotal"] == 7812345
+    for run in range(RUNS):
+        rng = random.Random(SEED + run)
+        assert parse_meminfo(reflow(MEMINFO, rng)) == expected, f"reflow (run {run})"
+        assert_subset(parse_meminfo(localize(MEMINFO, rng)), expected, "localized", run)
+        assert_subset(parse_meminfo(truncate(MEMINFO, rng)), expected, "truncated", run)
+
+    diagnostics = ParserDiagnostics(lambda kind, payload, job_id=None: None)
+    assert SystemHealth(FakeADB({"cat /proc/meminfo": MEMINFO.split("MemFree")[0]}), diagnostics).get_memory_info() is None
+    assert [f["parser"] for f in diagnostics.list_failures()] == ["memory"]
+    assert SystemHealth(FakeADB({"cat /proc/meminfo": MEMINFO}), diagnostics).get_memory_info()["usedKb"] == 5000000
+    print("  └─ ✅ meminfo OK")
+    return True
+
+
+def test_devices():
+    """Test adb devices parsing, daemon notices included"""
+    print("\n📱 Fuzzing adb devices parsing...")
+    expected = {d["serial"]: d["state"] for d in parse_devices(DEVICES)}
+    assert expected == {"R58M12345AB": "device", "emulator-5554": "offline", "192.168.1.20:5555": "unauthorized"}
+    assert parse_devices("adb server version (40) doesn't match this client (41); killing...\n") == []
+    for run in range(RUNS):
+        rng = random.Random(SEED + run)
+        reflowed = {d["serial"]: d["state"] for d in parse_devices(reflow(DEVICES, rng))}
+        assert reflowed == expected, f"reflow (run {run})"
+        cut = {d["serial"]: d["state"] for d in parse_devices(truncate(DEVICES, rng))}
+        assert_subset(cut, expected, "truncated", run)
+    print("  └─ ✅ adb devices OK")
+    return True
+
+
+def test_diskstats():
+    """Test dumpsys diskstats parsing: cut-off or unreadable sizes leave the package out"""
+    print("\n📦 Fuzzing diskstats parsing...")
+    expected = parse_diskstats(DISKSTATS)
+    assert expected["com.spotify.music"]["totalBytes"] == 3300917248
+    for run in range(RUNS):
+        rng = random.Random(SEED + run)
+        assert parse_diskstats(reflow(DISKSTATS, rng)) == expected, f"reflow (run {run})"
+        assert_subset(parse_diskstats(truncate(DISKSTATS, rng)), expected, "truncated", run)
+    floats = DISKSTATS.replace("104857600", "104.857600")
+    assert "com.spotify.music" not in parse_diskstats(floats)
+    assert set(parse_diskstats(DISKSTATS.split("Cache Sizes")[0])) == set()
+    print("  └─ ✅ diskstats OK")
+    return True
+
+
+def main():
+    """Run all parser property tests"""
+    tests = [test_df, test_meminfo, test_devices, test_diskstats]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,103 @@
+"""
+Test granting and revoking runtime permissions: name handling, the state
+before the change, refused changes and undo
+Runs against a fake device - no ADB required
+"""
+import sys
+import os
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from device_tweaks import DeviceTweaks, parse_permission_state, permission_name
+from undo_manager import UndoStack
+
+
+DUMPSYS = """
+Packages:
+  Package [com.samsung.android.bixby.agent] (5e2a1c0):
+    install permissions:
+      android.permission.INTERNET: granted=true
+    User 0: ceDataInode=1234 installed=true hidden=false
+      runtime permissions:
+        android.permission.ACCESS_FINE_LOCATION: granted=false, flags=[ USER_SET ]
+        android.permission.RECORD_AUDIO: granted=true, flags=[ GRANTED_BY_DEFAULT ]
+"""
+
+
+class PermissionDevice(FakeADB):
+    """Answers the package dump and refuses to revoke INTERNET like Android does"""
+
+    def answer(self, command):
+        if command.startswith("dumpsys package"):
+            return DUMPSYS
+        if command.startswith("pm list packages -s"):
+            return "package:com.samsung.android.bixby.agent\n"
+        if command.endswith("android.permission.INTERNET"):
+            return ("Exception occurred while executing 'revoke':\n"
+                    "java.lang.SecurityException: Permission android.permission.INTERNET "
+                    "requested by com.samsung.android.bixby.agent is not a changeable permission type\n")
+        return ""
+
+    def require(self, feature):
+        pass
+
+
+def test_permission_parsing():
+    """Test permission name completion and reading the granted state"""
+    print("\n🔑 Testing permission parsing...")
+    assert permission_name("RECORD_AUDIO") == "android.permission.RECORD_AUDIO"
+    assert permission_name("com.example.permission.C2D") == "com.example.permission.C2D"
+    assert permission_name("RECORD_AUDIO; reboot") is None and permission_name("") is None
+    assert parse_permission_state(DUMPSYS, "android.permission.RECORD_AUDIO") is True
+    assert parse_permission_state(DUMPSYS, "android.permission.ACCESS_FINE_LOCATION") is False
+    assert parse_permission_state(DUMPSYS, "android.permission.CAMERA") is None
+    print("  └─ ✅ Permission parsing OK")
+    return True
+
+
+def test_revoke_and_undo():
+    """Test that a revoke runs pm revoke, reports the old state and is undone with pm grant"""
+    print("\n🎙️  Testing permission revoke and undo...")
+    adb = PermissionDevice()
+    tweaks = DeviceTweaks(adb)
+    package = "com.samsung.android.bixby.agent"
+
+    result = tweaks.revoke_permission(package, "RECORD_AUDIO")
+    assert result["success"] and result["previous"] is True
+    assert result["message"] == f"android.permission.RECORD_AUDIO revoked from {package}"
+    assert adb.commands[-1] == f"pm revoke {package} android.permission.RECORD_AUDIO"
+
+    missing = tweaks.revoke_permission(package, "CAMERA")
+    assert not missing["success"] and missing["message"] == f"{package} does not request android.permission.CAMERA"
+    fixed = tweaks.revoke_permission(package, "INTERNET")
+    assert not fixed["success"] and "not a changeable permission type" in fixed["message"]
+    assert not tweaks.grant_permission(package, "RECORD_AUDIO && reboot")["success"]
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        stack = UndoStack(adb, tweaks, data_dir)
+        stack.record("permission", package, result)
+        stack.record("permission", package, fixed)
+        entries = stack.get_undo_stack()
+        assert [e["description"] for e in entries] == [f"Changed android.permission.RECORD_AUDIO of {package}"]
+        assert stack.undo()["success"]
+        assert adb.commands[-1] == f"pm grant {package} android.permission.RECORD_AUDIO"
+    print("  └─ ✅ Permission revoke and undo OK")
+    return True
+
+
+def main():
+    """Run all permission tests"""
+    tests = [test_permission_parsing, test_revoke_and_undo]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
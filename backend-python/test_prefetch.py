@@ -0,0 +1,115 @@
+"""
+Test the on-connect prefetch pipeline
+Runs against fake caches - no ADB required
+"""
+import sys
+import os
+import threading
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from adb_operations import DeviceDisconnectedError
+from prefetch import PrefetchPipeline, PREFETCH_STAGES
+
+
+class PrefetchDevice(FakeADB):
+    """A phone with usage stats, recording device info reads"""
+
+    def __init__(self):
+        super().__init__()
+        self.calls = []
+
+    def get_device_info(self):
+        self.calls.append("info")
+        return {"serial": "SERIAL1"}
+
+    def capabilities(self):
+        return {"usage_stats": True}
+
+    def get_device_class(self):
+        return {"deviceClass": "phone", "characteristics": "default", "features": []}
+
+    def supports(self, feature):
+        return True
+
+
+class FakeCache:
+    def __init__(self, fail_sizes=False):
+        self.calls = []
+        self.fail_sizes = fail_sizes
+
+    def refresh(self):
+        self.calls.append("refresh")
+
+    def prefetch_sizes(self):
+        if self.fail_sizes:
+            raise DeviceDisconnectedError("No Android device connected. Please connect via USB.")
+        self.calls.append("sizes")
+
+    def prefetch_last_used(self):
+        self.calls.append("lastUsed")
+
+
+class FakeHealth:
+    def get_system_health(self):
+        return {"serial": "SERIAL1"}
+
+
+class Recorder:
+    def __init__(self):
+        self.events = []
+        self.done = threading.Event()
+
+    def __call__(self, kind, payload, job_id=None):
+        self.events.append((kind, payload))
+        if kind == "prefetch_complete":
+            self.done.set()
+
+
+def test_stages_in_order():
+    """Test that every stage runs once per device, in order, with events"""
+    print("\n🚀 Testing prefetch stages...")
+    recorder, cache = Recorder(), FakeCache()
+    pipeline = PrefetchPipeline(PrefetchDevice(), cache, FakeHealth(), recorder)
+    assert pipeline.on_device_seen("SERIAL1")
+    assert recorder.done.wait(5)
+    assert cache.calls == ["refresh", "sizes", "lastUsed"]
+    progress = [p["stage"] for kind, p in recorder.events if kind == "prefetch_progress"]
+    assert progress == PREFETCH_STAGES
+    assert recorder.events[-1][1]["success"]
+    assert not pipeline.on_device_seen("SERIAL1"), "the same device is only prefetched once"
+    assert pipeline.get_status()["finished"]
+    print("  └─ ✅ Prefetch stages OK")
+    return True
+
+
+def test_device_lost():
+    """Test that losing the device skips the rest and allows a new run"""
+    print("\n🔌 Testing prefetch on disconnect...")
+    recorder = Recorder()
+    pipeline = PrefetchPipeline(PrefetchDevice(), FakeCache(fail_sizes=True), FakeHealth(), recorder)
+    pipeline.start()
+    assert recorder.done.wait(5)
+    statuses = {s["stage"]: s["status"] for s in recorder.events[-1][1]["stages"]}
+    assert statuses == {"deviceProfile": "done", "packages": "done", "sizes": "failed",
+                        "usageStats": "skipped", "health": "skipped"}
+    assert pipeline.on_device_seen("SERIAL1"), "a device lost mid-run is prefetched again"
+    print("  └─ ✅ Disconnect handling OK")
+    return True
+
+
+def main():
+    """Run all prefetch tests"""
+    tests = [test_stages_in_order, test_device_lost]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,271 @@
+"""
+Test the privacy report parsers and scoring
+Runs against captured dumpsys output - no device required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from privacy_report import (
+    parse_granted_permissions, find_tracker_sdks, find_risky_combos, PrivacyAnalyzer,
+    parse_listener_components, parse_op_packages, parse_component_setting, parse_clipboard_reads,
+)
+
+
+DUMPSYS_PACKAGE = """\
+Receiver Resolver Table:
+  Non-Data Actions:
+      com.android.vending.INSTALL_REFERRER:
+        5c1a2b0 com.example.game/com.appsflyer.SingleInstallBroadcastReceiver filter 9d3e4f1
+      com.google.android.c2dm.intent.RECEIVE:
+        7a8b9c0 com.example.game/com.google.firebase.iid.FirebaseInstanceIdReceiver filter 1f2e3d4
+
+Service Resolver Table:
+  Non-Data Actions:
+      com.google.android.gms.measurement.UPLOAD:
+        3e4f5a6 com.example.game/com.google.android.gms.measurement.AppMeasurementService filter 0a1b2c3
+        4f5a6b7 com.example.notes/.sync.SyncService filter 1b2c3d4
+
+Packages:
+  Package [com.example.game] (8c2d1e0):
+    userId=10231
+    User 0: ceDataInode=12345 installed=true hidden=false
+      runtime permissions:
+        android.permission.ACCESS_FINE_LOCATION: granted=true, flags=[ USER_SET ]
+        android.permission.RECORD_AUDIO: granted=true, flags=[ USER_SET ]
+        android.permission.CAMERA: granted=false, flags=[ USER_SET ]
+  Package [com.example.notes] (1a2b3c4):
+    userId=10232
+    User 0: ceDataInode=12346 installed=true hidden=false
+      runtime permissions:
+        android.permission.READ_CONTACTS: granted=true, flags=[ USER_SET ]
+"""
+
+ALLOWED_LISTENERS = """ComponentInfo{com.facebook.services/com.facebook.services.NotificationListener}
+ComponentInfo{com.google.android.projection.gearhead/com.google.android.gearhead.notifications.SharedNotificationListenerManager}
+"""
+
+USAGE_OPS = """com.example.game
+com.samsung.android.errorreporter
+"""
+
+OVERLAY_OPS = """com.facebook.services
+com.example.game
+"""
+
+APPOPS_CLIPBOARD = """Current AppOps Service state:
+  Settings:
+    top_state_settle_time=+30s0ms
+  Uid 10245:
+    state=cch
+    Package com.example.keyboard:
+      READ_CLIPBOARD (allow):
+        null=[
+          Access: [pers-s] 2024-05-01 10:11:12.345 (-2h3m4s567ms)
+        ]
+  Uid 10311:
+    Package com.example.game:
+      READ_CLIPBOARD (allow):
+        Access: [top-s] 2024-05-01 08:00:00.000 (-1d2h3m4s567ms)
+        Access: [fgsvc-s] 2024-05-02 10:06:00.000 (-5m4s567ms)
+        Reject: [cch-s] 2024-05-02 10:08:00.000 (-3m)
+      COARSE_LOCATION (allow):
+        Access: [top-s] 2024-05-02 10:10:00.000 (-1m)
+"""
+
+SECURE_SETTINGS = {
+    "enabled_accessibility_services":
+        "com.samsung.accessibility/.universalswitch.UniversalSwitchService:com.example.game/.TapHelperService",
+    "autofill_service": "com.google.android.gms/com.google.android.gms.autofill.service.AutofillService",
+    "always_on_vpn_app": "null",
+}
+
+VPN_SERVICES = """priority=0 preferredOrder=0 match=0x108000 specificIndex=-1 isDefault=false
+com.example.vpn/.TunnelService
+"""
+
+
+class PrivacyDevice(FakeADB):
+    """Answers the listener, appops, settings and package dumps of the report"""
+
+    def supports(self, feature):
+        return True
+
+    def require(self, feature):
+        pass
+
+    def answer(self, command):
+        if "allowed_listeners" in command:
+            return ALLOWED_LISTENERS
+        if "query-op SYSTEM_ALERT_WINDOW" in command:
+            return OVERLAY_OPS
+        if "query-op" in command:
+            return USAGE_OPS
+        if command.startswith("cmd") and " set " in command:
+            return ""
+        if command.startswith("settings get secure"):
+            return SECURE_SETTINGS[command.split()[-1]] + "\n"
+        if command == "dumpsys appops --op READ_CLIPBOARD":
+            return APPOPS_CLIPBOARD
+        if "VpnService" in command:
+            return VPN_SERVICES
+        return DUMPSYS_PACKAGE
+
+
+def test_parsers():
+    """Test permission, tracker and combo extraction"""
+    print("\n🔏 Testing privacy parsers...")
+    granted = parse_granted_permissions(DUMPSYS_PACKAGE)
+    assert granted["com.example.game"] == {"ACCESS_FINE_LOCATION", "RECORD_AUDIO"}
+    assert granted["com.example.notes"] == {"READ_CONTACTS"}
+
+    trackers = find_tracker_sdks(DUMPSYS_PACKAGE)
+    assert trackers == {"com.example.game": ["AppsFlyer", "Google Analytics for Firebase"]}
+
+    risky = find_risky_combos(granted)
+    assert risky == {"com.example.game": ["Precise location together with microphone"]}
+    print("  └─ ✅ Privacy parsers OK")
+    return True
+
+
+def test_report():
+    """Test scoring and removal suggestions"""
+    print("\n📊 Testing privacy report...")
+    packages = [
+        {"packageName": "com.example.game", "safetyLevel": "Safe"},
+        {"packageName": "com.example.notes", "safetyLevel": "Safe"},
+        {"packageName": "com.facebook.services", "safetyLevel": "Safe", "isSystem": True},
+    ]
+    report = PrivacyAnalyzer(PrivacyDevice()).get_privacy_report(packages)
+    penalties = {f["id"]: f["penalty"] for f in report["factors"]}
+    assert penalties == {"telemetry": 6, "trackers": 2, "permissions": 4, "overlays": 3}
+    assert report["score"] == 85
+    # Only the preinstalled overlay holder counts, with a one-click revocation
+    overlays = report["factors"][-1]["packages"]
+    assert [o["packageName"] for o in overlays] == ["com.facebook.services"]
+    assert overlays[0]["action"] == {"command": "revoke_special_access", "label": "Stop drawing over other apps",
+                                     "args": {"packageName": "com.facebook.services", "access": "overlay"}}
+    assert report["reviewItems"] == []  # No preinstalled package runs a privileged service
+    suggested = [s["packageName"] for s in report["suggestedRemovals"]]
+    assert suggested == ["com.facebook.services", "com.example.game"]
+    print("  └─ ✅ Privacy report OK")
+    return True
+
+
+def test_special_access():
+    """Test the notification listener, usage access and overlay inventory"""
+    print("\n🔔 Testing special access inventory...")
+    assert parse_listener_components(ALLOWED_LISTENERS)["com.facebook.services"] == [
+        "com.facebook.services/com.facebook.services.NotificationListener"]
+    assert parse_op_packages(USAGE_OPS) == ["com.example.game", "com.samsung.android.errorreporter"]
+
+    packages = [
+        {"packageName": "com.example.game", "appName": "Game", "safetyLevel": "Safe", "isSystem": False},
+        {"packageName": "com.facebook.services", "appName": "Services", "safetyLevel": "Safe", "isSystem": True},
+    ]
+    adb = PrivacyDevice()
+    analyzer = PrivacyAnalyzer(adb)
+    inventory = analyzer.get_special_access(packages)
+    holders = {e["packageName"]: e for e in inventory["packages"]}
+    assert inventory["unchecked"] == []
+    assert inventory["packages"][0]["packageName"] == "com.facebook.services"  # Preinstalled first
+    assert holders["com.facebook.services"]["access"] == ["notificationListener", "overlay"]
+    assert holders["com.example.game"]["access"] == ["usageAccess", "overlay"]
+
+    assert analyzer.revoke_special_access("com.facebook.services", "notificationListener")["success"]
+    assert adb.commands[-1] == \
+        "cmd notification disallow_listener com.facebook.services/com.facebook.services.NotificationListener"
+    assert not analyzer.revoke_special_access("com.example.game", "notificationListener")["success"]
+    assert analyzer.revoke_special_access("com.example.game", "usageAccess")["success"]
+    assert adb.commands[-1] == "cmd appops set com.example.game GET_USAGE_STATS ignore"
+    revoked = analyzer.revoke_special_access("com.facebook.services", "overlay")
+    assert revoked["message"] == "Drawing over other apps revoked for com.facebook.services"
+    assert adb.commands[-1] == "cmd appops set com.facebook.services SYSTEM_ALERT_WINDOW ignore"
+    print("  └─ ✅ Special access OK")
+    return True
+
+
+def test_service_audit():
+    """Test the accessibility, autofill and VPN audit"""
+    print("\n🛡️  Testing service audit...")
+    assert parse_component_setting("null\n") == []
+    assert parse_component_setting(SECURE_SETTINGS["enabled_accessibility_services"]) == [
+        "com.samsung.accessibility/.universalswitch.UniversalSwitchService", "com.example.game/.TapHelperService"]
+
+    packages = [
+        {"packageName": "com.example.game", "appName": "Game", "safetyLevel": "Safe", "isSystem": False},
+        {"packageName": "com.samsung.accessibility", "appName": "Accessibility", "safetyLevel": "Caution",
+         "isSystem": True},
+        {"packageName": "com.google.android.gms", "appName": "Gms", "safetyLevel": "Dangerous", "isSystem": True},
+        {"packageName": "com.example.vpn", "appName": "Vpn", "safetyLevel": "Safe", "isSystem": False},
+    ]
+    analyzer = PrivacyAnalyzer(PrivacyDevice())
+    services = analyzer.get_service_audit(packages)["services"]
+    assert [(s["kind"], s["packageName"], s["priority"]) for s in services] == [
+        ("accessibility", "com.samsung.accessibility", "high"),
+        ("autofill", "com.google.android.gms", "high"),
+        ("accessibility", "com.example.game", "normal"),
+        ("vpn", "com.example.vpn", "normal"),
+    ]
+    assert services[-1]["active"] is False  # Installed, not the always-on VPN
+
+    review = analyzer.get_privacy_report(packages)["reviewItems"]
+    assert [r["packageName"] for r in review] == ["com.samsung.accessibility", "com.google.android.gms"]
+    print("  └─ ✅ Service audit OK")
+    return True
+
+
+def test_clipboard_audit():
+    """Test clipboard reads, background and rejected reads, and devices without the history"""
+    print("\n📋 Testing clipboard audit...")
+    reads = parse_clipboard_reads(APPOPS_CLIPBOARD)
+    # The location access below the clipboard op is not a clipboard read
+    assert reads == [
+        {"packageName": "com.example.game", "lastReadSecondsAgo": 304, "background": True, "rejected": True},
+        {"packageName": "com.example.keyboard", "lastReadSecondsAgo": 7384, "background": False, "rejected": False},
+    ]
+    assert parse_clipboard_reads("Current AppOps Service state:\n") == []
+
+    packages = [{"packageName": "com.example.game", "appName": "Game", "safetyLevel": "Safe", "isSystem": False}]
+    audit = PrivacyAnalyzer(PrivacyDevice()).get_clipboard_audit(packages)
+    assert audit["available"] and audit["packages"][0]["appName"] == "Game"
+    assert audit["packages"][1]["appName"] == "com.example.keyboard" and audit["packages"][1]["isSystem"] is None
+
+    report = PrivacyAnalyzer(PrivacyDevice()).get_privacy_report(packages)
+    assert report["clipboard"]["available"] and len(report["clipboard"]["packages"]) == 2
+
+    class OldADB(PrivacyDevice):
+        def supports(self, feature):
+            return feature != "clipboard_history"
+
+    class UnknownOpADB(PrivacyDevice):
+        def answer(self, command):
+            if command.startswith("dumpsys appops"):
+                return "Unknown operation string: READ_CLIPBOARD\n"
+            return super().answer(command)
+
+    old = PrivacyAnalyzer(OldADB()).get_clipboard_audit(packages)
+    assert not old["available"] and old["message"] == "Clipboard access history needs Android 10 or newer"
+    unknown = PrivacyAnalyzer(UnknownOpADB()).get_privacy_report(packages)
+    assert unknown["clipboard"] == {"available": False, "packages": [],
+                                    "message": "This device does not report clipboard access history"}
+    print("  └─ ✅ Clipboard audit OK")
+    return True
+
+
+def main():
+    """Run all privacy tests"""
+    tests = [test_parsers, test_report, test_special_access, test_service_audit, test_clipboard_audit]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
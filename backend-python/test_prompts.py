@@ -0,0 +1,66 @@
+"""
+Test the prompt sections: which apply in which context, and the token budgets of the fixed text
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from prompts import (CHAT_ROLE, EXPERT_STYLE, PLAIN_LANGUAGE_STYLE, PROMPT_TOKEN_BUDGETS, PromptContext,
+                     analysis_prompt, chat_system_prompt, chat_user_message, estimate_tokens)
+
+
+def test_sections():
+    """Test that sections are included only when their context applies"""
+    print("\n🧩 Testing prompt sections...")
+    default = PromptContext()
+    assert chat_system_prompt(default) == CHAT_ROLE
+    assert chat_user_message("hi", default) == "hi"
+    assert chat_user_message("hi", default, with_instructions=True) == "hi"
+
+    device = PromptContext(device_context="Device: Samsung SM-G991B", package_facts="- com.facebook.katana: Safe")
+    assert chat_user_message("hi", device) == ("[Current device state]\nDevice: Samsung SM-G991B\n\nhi\n\n"
+                                               "[Packages in this message]\n- com.facebook.katana: Safe")
+
+    assert PLAIN_LANGUAGE_STYLE in chat_system_prompt(PromptContext(plain_language=True))
+    assert EXPERT_STYLE in chat_system_prompt(PromptContext(expert=True))
+    both = chat_system_prompt(PromptContext(plain_language=True, expert=True))
+    assert PLAIN_LANGUAGE_STYLE in both and EXPERT_STYLE not in both
+
+    german = PromptContext(locale="de")
+    assert chat_user_message("hi", german, with_instructions=True).endswith("Answer in German.")
+    assert "riskCategory values in English" in analysis_prompt("com.example", german)
+    assert "German" not in analysis_prompt("com.example", default)
+    assert '"packageName": "com.example"' in analysis_prompt("com.example", default)
+    print("  └─ ✅ Prompt sections OK")
+    return True
+
+
+def test_token_budgets():
+    """Test that the fixed text stays within budget with every optional section on"""
+    print("\n📏 Testing prompt token budgets...")
+    widest = PromptContext(plain_language=True, locale="de")
+    expert = PromptContext(expert=True, locale="fr")
+    for context in (widest, expert):
+        assert estimate_tokens(chat_system_prompt(context)) <= PROMPT_TOKEN_BUDGETS["chat_system"]
+        assert estimate_tokens(analysis_prompt("com.samsung.android.app.spage", context)) <= PROMPT_TOKEN_BUDGETS["analysis"]
+    assert estimate_tokens("abcd") == 1 and estimate_tokens("abcde") == 2
+    print(f"  └─ ✅ Chat system {estimate_tokens(chat_system_prompt(widest))} tokens, "
+          f"analysis {estimate_tokens(analysis_prompt('com.samsung.android.app.spage', widest))} tokens")
+    return True
+
+
+def main():
+    """Run all prompt tests"""
+    tests = [test_sections, test_token_budgets]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,126 @@
+"""
+Test install source classification of packages
+Runs on canned `pm list packages -f -i` output - no device required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from provenance import parse_package_details, classify_install_source, partition_of
+from adb_operations import USER_PACKAGES_MARKER
+
+
+LISTING = """package:/data/app/~~Xy1==/com.spotify.music-Ab2==/base.apk=com.spotify.music  installer=com.android.vending
+package:/system/priv-app/Settings/Settings.apk=com.android.settings  installer=null
+package:/product/app/YouTube/YouTube.apk=com.google.android.youtube  installer=null
+package:/data/app/com.google.android.gm-1/base.apk=com.google.android.gm  installer=com.android.vending
+package:/data/app/org.fdroid.fdroid-1/base.apk=org.fdroid.fdroid  installer=null
+package:/data/app/org.videolan.vlc-1/base.apk=org.videolan.vlc  installer=org.fdroid.fdroid
+package:/data/app/com.termux-1/base.apk=com.termux  installer=com.google.android.packageinstaller
+package:/system/app/MyVerizon/MyVerizon.apk=com.vzw.hss.myverizon  installer=null
+package:/data/app/com.candycrush-1/base.apk=com.king.candycrushsaga  installer=com.dti.att
+package:/vendor/app/ims/ims.apk=com.shannon.imsservice  installer=null
+package:/apex/com.android.permission/priv-app/PermissionController/PermissionController.apk=com.google.android.permissioncontroller  installer=null
+"""
+
+USER_LISTING = """package:com.spotify.music
+package:org.fdroid.fdroid
+package:org.videolan.vlc
+package:com.termux
+package:com.king.candycrushsaga
+"""
+
+
+class ListingDevice(FakeADB):
+    """Lists LISTING, then USER_LISTING as its user packages"""
+
+    def exec_out(self, command, timeout=30):
+        return f"{LISTING}{USER_PACKAGES_MARKER}\n{USER_LISTING}"
+
+
+def test_parse_details():
+    """Test parsing of code paths and installers"""
+    print("\n📄 Testing pm listing parsing...")
+    details = parse_package_details(LISTING)
+    assert len(details) == 11
+    assert details["com.spotify.music"] == (
+        "/data/app/~~Xy1==/com.spotify.music-Ab2==/base.apk", "com.android.vending")
+    assert details["com.android.settings"] == ("/system/priv-app/Settings/Settings.apk", None)
+    print("  └─ ✅ Parsing OK")
+    return True
+
+
+def test_classification():
+    """Test each install source"""
+    print("\n🏷️  Testing install source classification...")
+    details = parse_package_details(LISTING)
+    user = set(USER_LISTING.replace("package:", "").split())
+
+    def source(package):
+        path, installer = details[package]
+        return classify_install_source(package, path, installer, package not in user)
+
+    assert source("com.spotify.music") == "googlePlay"
+    assert source("com.android.settings") == "oemPreload"
+    assert source("com.google.android.youtube") == "oemPreload"
+    # Updated system app: /data/app path and a Play installer, but not third-party
+    assert source("com.google.android.gm") == "oemPreload"
+    assert source("org.fdroid.fdroid") == "sideloaded"
+    assert source("org.videolan.vlc") == "otherStore"
+    assert source("com.termux") == "sideloaded"
+    assert source("com.vzw.hss.myverizon") == "carrier"
+    assert source("com.king.candycrushsaga") == "carrier"
+    print("  └─ ✅ Classification OK")
+    return True
+
+
+def test_listing_fields():
+    """Test that list_packages tags every package"""
+    print("\n📦 Testing listing fields...")
+    packages = {pkg["packageName"]: pkg for pkg in ListingDevice().list_packages("all")}
+    assert len(packages) == 11
+    assert packages["com.spotify.music"]["installer"] == "com.android.vending"
+    assert packages["com.spotify.music"]["installSource"] == "googlePlay"
+    assert packages["com.spotify.music"]["isSystem"] is False
+    assert packages["com.android.settings"]["installer"] is None
+    assert packages["com.android.settings"]["installSource"] == "oemPreload"
+    print("  └─ ✅ Listing fields OK")
+    return True
+
+
+def test_partitions():
+    """Test partition detection and the stricter rating of unrestorable packages"""
+    print("\n💽 Testing partitions...")
+    assert partition_of("/system_ext/priv-app/Foo/Foo.apk") == "system_ext"
+    assert partition_of("/product/app/YouTube/YouTube.apk") == "product"
+    assert partition_of("/data/app/~~Xy1==/com.foo-Ab2==/base.apk") == "data"
+    assert partition_of(None) is None
+    packages = {pkg["packageName"]: pkg for pkg in ListingDevice().list_packages("all")}
+    assert packages["com.android.settings"]["partition"] == "system"
+    assert packages["com.shannon.imsservice"]["partition"] == "vendor"
+    assert packages["com.shannon.imsservice"]["safetyLevel"] == "Expert"
+    assert packages["com.google.android.permissioncontroller"]["partition"] == "apex"
+    assert packages["com.google.android.permissioncontroller"]["safetyLevel"] == "Expert"
+    # Already stricter ratings are kept, other partitions are not raised
+    assert packages["com.android.settings"]["safetyLevel"] == "Dangerous"
+    assert packages["com.spotify.music"]["safetyLevel"] == "Safe"
+    print("  └─ ✅ Partitions OK")
+    return True
+
+
+def main():
+    """Run all provenance tests"""
+    tests = [test_parse_details, test_classification, test_listing_fields, test_partitions]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,85 @@
+"""
+Test the raw dumpsys viewer
+Runs against a fake device - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from adb_operations import UnsupportedFeatureError
+from raw_dump import RawDumpViewer, dump_command, paginate, MAX_DUMP_CHARS
+
+
+class DumpDevice(FakeADB):
+    """Answers every dump with `output`; thermal sensors need Android 10"""
+
+    def __init__(self, output="", sdk=34):
+        super().__init__(default=output, sdk=sdk)
+
+    def require(self, feature):
+        if feature == "thermal_service" and self.sdk < 29:
+            raise UnsupportedFeatureError("Thermal sensors need Android 10 or newer")
+
+
+def test_allowlist():
+    """Test that only allowlisted services and valid package names are dumped"""
+    print("\n🔒 Testing dump allowlist...")
+    assert dump_command("package", "com.facebook.katana") == "dumpsys package com.facebook.katana"
+    assert dump_command("activity") == "dumpsys activity services"
+    assert dump_command("battery") == "dumpsys battery"
+    for service, package in [("window", None), ("package", "com.x; reboot"), ("battery", "com.android.shell")]:
+        try:
+            dump_command(service, package)
+            assert False, f"{service} {package} should be refused"
+        except ValueError:
+            pass
+
+    adb = DumpDevice()
+    result = RawDumpViewer(adb).get_raw_dump("package", "$(reboot)")
+    assert not result["success"] and adb.commands == []
+    result = RawDumpViewer(DumpDevice(sdk=28)).get_raw_dump("thermalservice")
+    assert not result["success"] and result["unsupported"]
+    print("  └─ ✅ Allowlist OK")
+    return True
+
+
+def test_pagination_and_limit():
+    """Test paging, clamping, truncation and the dump cache"""
+    print("\n📄 Testing dump pagination...")
+    text = "\n".join(f"line {i}" for i in range(1, 1201))
+    page = paginate(text, 2, 500)
+    assert page["totalPages"] == 3 and page["firstLine"] == 1001
+    assert page["text"].split("\n")[0] == "line 1001" and len(page["text"].split("\n")) == 200
+    assert paginate(text, 99, 500)["page"] == 2
+
+    adb = DumpDevice(text)
+    viewer = RawDumpViewer(adb)
+    first = viewer.get_raw_dump("diskstats", page=0, page_lines=100)
+    second = viewer.get_raw_dump("diskstats", page=1, page_lines=100)
+    assert first["success"] and not first["truncated"] and second["text"].startswith("line 101")
+    assert len(adb.commands) == 1, "paging should reuse the cached dump"
+    viewer.get_raw_dump("diskstats", refresh=True)
+    assert len(adb.commands) == 2
+
+    huge = RawDumpViewer(DumpDevice("x" * (MAX_DUMP_CHARS + 10))).get_raw_dump("package")
+    assert huge["truncated"] and len(huge["text"]) == MAX_DUMP_CHARS
+    print("  └─ ✅ Pagination OK")
+    return True
+
+
+def main():
+    """Run all raw dump tests"""
+    tests = [test_allowlist, test_pagination_and_limit]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,90 @@
+"""
+Test remediation text for failed reinstalls
+Runs on captured package manager output - no device required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from remediation import classify_reinstall_failure, remediation_for
+
+
+class FailingDevice(FakeADB):
+    """A device whose reinstalls fail with `output`"""
+
+    def __init__(self, output, manufacturer):
+        super().__init__(run_output=output, sdk=33)
+        self.manufacturer = manufacturer
+
+    def supports(self, feature):
+        return True
+
+    def require(self, feature):
+        pass
+
+    def _data_preserved(self, package_name):
+        return None
+
+    def _get_property(self, prop):
+        return self.manufacturer
+
+
+def test_classification():
+    """Test that package manager output maps to failure kinds"""
+    print("\n🔎 Testing failure classification...")
+    assert classify_reinstall_failure("Package com.foo doesn't exist") == "notOnDevice"
+    assert classify_reinstall_failure(
+        "Failure [INSTALL_FAILED_USER_RESTRICTED: Install canceled by user]") == "restricted"
+    assert classify_reinstall_failure("Package com.foo not installed for user 0") == "notInstalledForUser"
+    assert classify_reinstall_failure("Unknown command: install-existing") == "unknownCommand"
+    assert classify_reinstall_failure("Something else went wrong") is None
+    print("  └─ ✅ Classification OK")
+    return True
+
+
+def test_remediation_choice():
+    """Test that OEM and Android version specific entries win over generic ones"""
+    print("\n🧭 Testing remediation choice...")
+    restricted = "Failure [INSTALL_FAILED_USER_RESTRICTED: Install canceled by user]"
+    assert "MIUI" in remediation_for(restricted, "Xiaomi", 33)["summary"]
+    assert "ColorOS" in remediation_for(restricted, "OnePlus", 33)["summary"]
+    assert remediation_for(restricted, "Google", 33)["summary"] == "The device refused the reinstall"
+
+    missing_user = "Package com.foo not installed for user 0"
+    assert "Android 7" in remediation_for(missing_user, "Motorola", 24)["summary"]
+    generic = remediation_for(missing_user, "Motorola", 30, "com.foo")
+    assert generic["steps"][0] == "Run `adb shell pm install-existing --user 0 com.foo` to target the main user explicitly"
+    assert "Galaxy Store" in remediation_for("Package com.foo doesn't exist", "samsung", 34)["summary"]
+    print("  └─ ✅ Remediation choice OK")
+    return True
+
+
+def test_reinstall_result():
+    """Test that reinstall_package returns the remediation instead of raw output"""
+    print("\n📦 Testing reinstall result...")
+    result = FailingDevice("Package com.miui.notes doesn't exist", "Xiaomi").reinstall_package("com.miui.notes")
+    assert not result["success"] and "GetApps" in result["message"]
+    assert result["remediation"]["failure"] == "notOnDevice"
+    assert result["details"] == "Package com.miui.notes doesn't exist"
+    result = FailingDevice("Error: something odd", "Xiaomi").reinstall_package("com.miui.notes")
+    assert result["message"] == "Failed to reinstall: Error: something odd" and "remediation" not in result
+    print("  └─ ✅ Reinstall result OK")
+    return True
+
+
+def main():
+    """Run all remediation tests"""
+    tests = [test_classification, test_remediation_choice, test_reinstall_result]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
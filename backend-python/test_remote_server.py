@@ -0,0 +1,195 @@
+"""
+Test the remote HTTP API: token auth, commands, the event feed, browser
+origins and turning the server on and off from the settings
+Runs a real server on a free loopback port with a fake command dispatcher - no ADB required
+"""
+import sys
+import os
+import re
+import json
+import http.client
+import tempfile
+import threading
+import time
+import urllib.error
+import urllib.parse
+import urllib.request
+sys.path.insert(0, os.path.dirname(__file__))
+
+from events import make_event
+from remote_server import REMOTE_COMMANDS, EventFeed, RemoteServer, is_loopback, serve_address
+
+
+def call(url, token=None, body=None, origin=None):
+    """(status, JSON body, headers) of a GET, or a POST when `body` is given"""
+    headers = {"Content-Type": "application/json"}
+    if token:
+        headers["Authorization"] = f"Bearer {token}"
+    if origin:
+        headers["Origin"] = origin
+    data = body if isinstance(body, bytes) or body is None else json.dumps(body).encode()
+    request = urllib.request.Request(url, data=data, headers=headers, method="POST" if body is not None else "GET")
+    try:
+        with urllib.request.urlopen(request, timeout=10) as response:
+            return response.status, json.loads(response.read()), response.headers
+    except urllib.error.HTTPError as e:
+        return e.code, json.loads(e.read()), e.headers
+
+
+def make_server(data_dir, settings):
+    dispatched = []
+
+    def dispatch(request):
+        dispatched.append(request)
+        if request["command"] == "reboot_device":
+            return {"id": None, "error": "boom"}
+        return {"id": None, "result": {"success": True, "echo": request["args"]}}
+
+    remote = RemoteServer(settings.get, EventFeed(size=3), data_dir)
+    remote.start(dispatch)
+    return remote, dispatched
+
+
+def test_event_feed():
+    """Test numbering, waiting for new events and reporting dropped ones"""
+    print("\n📰 Testing the event feed...")
+    feed = EventFeed(size=3)
+    assert feed.since(0) == {"events": [], "last": 0, "missed": False}
+    for index in range(5):
+        feed.publish(make_event("health_update", {"index": index}))
+    result = feed.since(0)
+    assert [e["sequence"] for e in result["events"]] == [3, 4, 5] and result["missed"] is True
+    assert feed.since(3)["missed"] is False and len(feed.since(3)["events"]) == 2
+
+    threading.Timer(0.2, lambda: feed.publish(make_event("health_update", {"index": 5}))).start()
+    started = time.monotonic()
+    result = feed.since(5, wait=5)
+    assert result["last"] == 6 and result["events"][0]["payload"] == {"index": 5}
+    assert time.monotonic() - started < 4, "the wait ends as soon as an event arrives"
+    print("  └─ ✅ Event feed OK")
+    return True
+
+
+def test_commands_and_auth():
+    """Test the token check, running commands, errors and events over HTTP"""
+    print("\n🌐 Testing the remote API...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        settings = {"enabled": True, "host": "127.0.0.1", "port": 0, "allowedOrigin": ""}
+        remote, dispatched = make_server(data_dir, settings)
+        try:
+            status = remote.status()
+            assert status["running"] and status["loopbackOnly"], status
+            base, token = status["url"], status["token"]
+            assert oct(os.stat(remote.token_path).st_mode & 0o777) == "0o600" or sys.platform == "win32"
+
+            assert call(base + "status")[0] == 401
+            assert call(base + "status", "wrong-token")[0] == 401
+            assert call(base + "commands/get_settings", body={})[0] == 401 and dispatched == []
+            code, body, _ = call(base + "status", token)
+            assert code == 200 and body["ready"] is True
+
+            code, body, _ = call(base + "commands/get_battery_trend", token, {"packageName": "com.facebook.katana"})
+            assert code == 200 and body["result"]["echo"] == {"packageName": "com.facebook.katana"}
+            assert dispatched[-1] == {"command": "get_battery_trend", "args": {"packageName": "com.facebook.katana"}}
+            assert call(base + "commands/reboot_device", token, {})[1]["error"] == "boom"
+            assert call(base + "commands/list_backups", token, b"{not json")[0] == 400
+            assert call(base + "commands/list_backups", token, [1, 2])[0] == 400
+            # A negative length would otherwise read the connection until the client gives up
+            url = urllib.parse.urlsplit(base)
+            connection = http.client.HTTPConnection(url.hostname, url.port, timeout=10)
+            connection.putrequest("POST", url.path + "commands/list_backups")
+            connection.putheader("Authorization", f"Bearer {token}")
+            connection.putheader("Content-Length", "-1")
+            connection.endheaders()
+            response = connection.getresponse()
+            assert response.status == 400 and json.loads(response.read())["error"] == "Bad Content-Length"
+            connection.close()
+            assert call(base + "nothing", token)[0] == 404
+
+            remote.events.publish(make_event("health_update", {"cpu": 12}))
+            code, body, _ = call(base + "events?after=0&wait=1", token)
+            assert code == 200 and body["last"] == 1 and body["events"][0]["kind"] == "health_update"
+
+            # A new token locks out the old one at once
+            new_token = remote.regenerate_token()["token"]
+            assert call(base + "status", token)[0] == 401 and call(base + "status", new_token)[0] == 200
+        finally:
+            remote.stop()
+    print("  └─ ✅ Remote API OK")
+    return True
+
+
+def test_command_allowlist():
+    """Test that only allowlisted commands run remotely, and that the list names real commands"""
+    print("\n🚫 Testing the remote command allowlist...")
+    with open(os.path.join(os.path.dirname(__file__), "main.py"), encoding="utf-8") as f:
+        routed = set(re.findall(r'command == "(\w+)"', f.read()))
+    assert REMOTE_COMMANDS <= routed, sorted(REMOTE_COMMANDS - routed)
+    local_only = {"regenerate_remote_token", "get_remote_status", "get_settings", "update_settings",
+                  "reset_settings", "set_confirmation_role", "set_ai_budget", "install_udev_rule",
+                  "launch_screen_mirror"}
+    assert not REMOTE_COMMANDS & local_only, sorted(REMOTE_COMMANDS & local_only)
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        settings = {"enabled": True, "host": "127.0.0.1", "port": 0, "allowedOrigin": ""}
+        remote, dispatched = make_server(data_dir, settings)
+        try:
+            base, token = remote.status()["url"], remote.token()
+            for command in sorted(local_only) + ["no_such_command"]:
+                code, body, _ = call(base + f"commands/{command}", token, {})
+                assert code == 403 and body["error"] == f"{command} cannot be run remotely", (command, body)
+            assert dispatched == [], "refused commands never reach the backend"
+        finally:
+            remote.stop()
+    print("  └─ ✅ Remote command allowlist OK")
+    return True
+
+
+def test_origin_and_settings():
+    """Test browser origins, and the server following the enabled setting"""
+    print("\n🔧 Testing origins and settings...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        settings = {"enabled": True, "host": "127.0.0.1", "port": 0, "allowedOrigin": "http://bench-ui:3000"}
+        remote, _ = make_server(data_dir, settings)
+        try:
+            base, token = remote.status()["url"], remote.token()
+            _, _, headers = call(base + "status", token, origin="http://bench-ui:3000")
+            assert headers.get("Access-Control-Allow-Origin") == "http://bench-ui:3000"
+            _, _, headers = call(base + "status", token, origin="http://evil.example")
+            assert headers.get("Access-Control-Allow-Origin") is None
+
+            settings["enabled"] = False
+            assert remote.apply()["running"] is False
+            try:
+                call(base + "status", token)
+                assert False, "the server still answers after being turned off"
+            except urllib.error.URLError:
+                pass
+            remote.start(remote._dispatch, force=True)
+            assert remote.status()["running"] is True, "--serve runs it regardless of the setting"
+        finally:
+            remote.stop()
+
+    assert is_loopback("127.0.0.1") and is_loopback("::1") and is_loopback("localhost")
+    assert not is_loopback("0.0.0.0") and not is_loopback("192.168.1.20")
+    assert serve_address(["main.py", "--serve", "--host", "0.0.0.0", "--port", "9000"]) == ("0.0.0.0", 9000)
+    assert serve_address(["main.py", "--serve"]) == (None, None)
+    print("  └─ ✅ Origins and settings OK")
+    return True
+
+
+def main():
+    """Run all remote server tests"""
+    tests = [test_event_feed, test_commands_and_auth, test_command_allowlist, test_origin_and_settings]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
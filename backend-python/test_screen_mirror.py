@@ -0,0 +1,233 @@
+"""
+Test scrcpy screen mirroring: picking the release asset, downloading and
+unpacking it, launching, an early exit and stopping sessions
+Runs against a fake release API and a fake scrcpy process - no ADB, network or scrcpy required
+"""
+import sys
+import os
+import hashlib
+import io
+import tarfile
+import tempfile
+from pathlib import Path
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+import network
+import screen_mirror
+from screen_mirror import ScreenMirror, extract_archive, pick_asset, release_version
+
+
+RELEASE = {
+    "tag_name": "v3.1",
+    "assets": [
+        {"name": "scrcpy-server-v3.1", "browser_download_url": "https://example.test/server"},
+        {"name": "scrcpy-win64-v3.1.zip", "browser_download_url": "https://example.test/win64.zip"},
+        {"name": "scrcpy-linux-x86_64-v3.1.tar.gz", "browser_download_url": "https://example.test/linux.tar.gz"},
+        {"name": "scrcpy-macos-aarch64-v3.1.tar.gz", "browser_download_url": "https://example.test/mac.tar.gz"},
+    ],
+}
+
+
+def release_archive(members):
+    """A tar.gz in memory with the given name -> content members"""
+    buffer = io.BytesIO()
+    with tarfile.open(fileobj=buffer, mode="w:gz") as tf:
+        for name, content in members.items():
+            info = tarfile.TarInfo(name)
+            info.size = len(content)
+            info.mode = 0o755
+            tf.addfile(info, io.BytesIO(content))
+    return buffer.getvalue()
+
+
+class FakeResponse:
+    def __init__(self, payload=None, content=b""):
+        self.payload, self.content = payload, content
+
+    def raise_for_status(self):
+        pass
+
+    def json(self):
+        return self.payload
+
+    def iter_content(self, chunk_size):
+        for i in range(0, len(self.content), chunk_size):
+            yield self.content[i:i + chunk_size]
+
+
+class MirrorDevice(FakeADB):
+    """A device with adb from an unpacked platform-tools"""
+
+    def __init__(self):
+        super().__init__()
+        self.adb_path = "/opt/platform-tools/adb"
+
+
+class FakeProcess:
+    def __init__(self, returncode=None, output=""):
+        self.returncode = returncode
+        self.stdout = io.StringIO(output)
+        self.terminated = False
+
+    def poll(self):
+        return self.returncode
+
+    def terminate(self):
+        self.terminated = True
+        self.returncode = 0
+
+    def wait(self, timeout=None):
+        return self.returncode
+
+
+def test_assets():
+    """Test choosing the prebuilt release for each system"""
+    print("\n📦 Testing release asset choice...")
+    assert pick_asset(RELEASE, "win32", "AMD64")["name"] == "scrcpy-win64-v3.1.zip"
+    assert pick_asset(RELEASE, "linux", "x86_64")["name"] == "scrcpy-linux-x86_64-v3.1.tar.gz"
+    assert pick_asset(RELEASE, "darwin", "arm64")["name"] == "scrcpy-macos-aarch64-v3.1.tar.gz"
+    assert pick_asset(RELEASE, "darwin", "x86_64") is None, "not in this release"
+    assert pick_asset(RELEASE, "linux", "aarch64") is None, "no prebuilt Linux ARM scrcpy"
+    print("  └─ ✅ Asset choice OK")
+    return True
+
+
+def test_newest_download():
+    """Test that the newest downloaded release is run, comparing versions by number rather than by text"""
+    print("\n🔢 Testing downloaded release order...")
+    assert release_version("scrcpy-linux-x86_64-v3.10") == (3, 10) and release_version("scrcpy-old") == ()
+    original_which = screen_mirror.shutil.which
+    screen_mirror.shutil.which = lambda name: None
+    try:
+        with tempfile.TemporaryDirectory() as data_dir:
+            for version in ["v2.4", "v3.10", "v3.9", "v3.1.1"]:
+                folder = Path(data_dir) / "tools/scrcpy" / f"scrcpy-linux-x86_64-{version}"
+                folder.mkdir(parents=True)
+                (folder / "scrcpy").write_bytes(b"#!/bin/sh\n")
+            mirror = ScreenMirror(MirrorDevice(), data_dir, platform="linux", machine="x86_64")
+            assert mirror.locate() == str(Path(data_dir) / "tools/scrcpy/scrcpy-linux-x86_64-v3.10/scrcpy")
+    finally:
+        screen_mirror.shutil.which = original_which
+    print("  └─ ✅ Downloaded release order OK")
+    return True
+
+
+def test_download():
+    """Test downloading and unpacking, a bad checksum, offline mode and an unsafe archive"""
+    print("\n⬇️ Testing scrcpy download...")
+    archive = release_archive({"scrcpy-linux-x86_64-v3.1/scrcpy": b"#!/bin/sh\n",
+                               "scrcpy-linux-x86_64-v3.1/scrcpy-server": b"server"})
+    release = {**RELEASE, "assets": [{**a} for a in RELEASE["assets"]]}
+    release["assets"][2]["digest"] = "sha256:" + hashlib.sha256(archive).hexdigest()
+    requested = []
+
+    def fake_request(method, url, **kwargs):
+        requested.append(url)
+        if url == screen_mirror.SCRCPY_RELEASE_URL:
+            return FakeResponse(release)
+        return FakeResponse(content=archive)
+
+    original_request, original_which = network.request, screen_mirror.shutil.which
+    network.request = fake_request
+    screen_mirror.shutil.which = lambda name: None
+    try:
+        with tempfile.TemporaryDirectory() as data_dir:
+            mirror = ScreenMirror(MirrorDevice(), data_dir, platform="linux", machine="x86_64")
+            assert mirror.locate() is None
+            result = mirror.download()
+            assert result["success"] and result["version"] == "v3.1", result
+            assert result["path"] == str(Path(data_dir) / "tools/scrcpy/scrcpy-linux-x86_64-v3.1/scrcpy")
+            assert requested[-1] == "https://example.test/linux.tar.gz"
+            assert mirror.locate() == result["path"]
+            assert not list(Path(data_dir, "tools/scrcpy").glob("*.tar.gz")), "the archive is removed"
+
+            release["assets"][2]["digest"] = "sha256:" + "0" * 64
+            result = mirror.download()
+            assert result["success"] is False and "checksum" in result["message"]
+
+            mac = ScreenMirror(MirrorDevice(), data_dir, platform="darwin", machine="x86_64").download()
+            assert mac["success"] is False and "no prebuilt release" in mac["message"]
+
+        network.request = original_request
+        network.configure({"offline": True}.get)
+        with tempfile.TemporaryDirectory() as data_dir:
+            result = ScreenMirror(MirrorDevice(), data_dir, platform="linux", machine="x86_64").download()
+            assert result["success"] is False and network.OFFLINE_MESSAGE in result["message"]
+
+            unsafe = Path(data_dir) / "unsafe.tar.gz"
+            unsafe.write_bytes(release_archive({"../escaped": b"x"}))
+            try:
+                extract_archive(unsafe, Path(data_dir) / "out")
+                assert False, "a path outside the folder was unpacked"
+            except ValueError:
+                pass
+            assert not (Path(data_dir) / "escaped").exists()
+    finally:
+        network.request, screen_mirror.shutil.which = original_request, original_which
+        network.configure(network.NETWORK_DEFAULTS.get)
+    print("  └─ ✅ Download OK")
+    return True
+
+
+def test_launch():
+    """Test launching for the connected device, a second launch, an early exit and stopping"""
+    print("\n📱 Testing mirroring sessions...")
+    launched = []
+    processes = []
+
+    def fake_popen(command, **kwargs):
+        launched.append((command, kwargs["env"]["ADB"]))
+        return processes.pop(0)
+
+    original_which, original_wait = screen_mirror.shutil.which, screen_mirror.STARTUP_WAIT
+    screen_mirror.STARTUP_WAIT = 0
+    try:
+        with tempfile.TemporaryDirectory() as data_dir:
+            adb = MirrorDevice()
+            mirror = ScreenMirror(adb, data_dir, platform="linux", machine="x86_64", popen=fake_popen)
+            screen_mirror.shutil.which = lambda name: None
+            result = mirror.launch()
+            assert result["success"] is False and result["needsDownload"] is True and not launched
+
+            screen_mirror.shutil.which = lambda name: "/usr/bin/scrcpy"
+            running = FakeProcess()
+            processes.append(running)
+            result = mirror.launch()
+            assert result["success"] and result["serial"] == "SERIAL1", result
+            command, adb_path = launched[-1]
+            assert command[:3] == ["/usr/bin/scrcpy", "--serial", "SERIAL1"] and adb_path == adb.adb_path
+            assert mirror.launch()["alreadyRunning"] is True and len(launched) == 1
+            assert mirror.status() == {"installed": True, "path": "/usr/bin/scrcpy", "running": ["SERIAL1"],
+                                       "canDownload": True}
+
+            processes.append(FakeProcess(1, "INFO: scrcpy 3.1\nERROR: Could not find any ADB device\n"))
+            result = mirror.launch("OTHER")
+            assert result["success"] is False and "Could not find any ADB device" in result["message"]
+
+            assert mirror.stop()["stopped"] == ["SERIAL1"] and running.terminated
+            assert mirror.status()["running"] == []
+
+            adb.connected = False
+            assert mirror.launch()["message"] == "No device connected"
+    finally:
+        screen_mirror.shutil.which, screen_mirror.STARTUP_WAIT = original_which, original_wait
+    print("  └─ ✅ Sessions OK")
+    return True
+
+
+def main():
+    """Run all screen mirror tests"""
+    tests = [test_assets, test_newest_download, test_download, test_launch]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,110 @@
+"""
+Test selection-wide combination rules
+Runs against captured ime/pm/dumpsys output - no device required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from selection_rules import SelectionValidator, parse_providers
+
+
+IME_LIST = """com.google.android.inputmethod.latin/com.android.inputmethod.latin.LatinIME
+com.samsung.android.honeyboard/.service.HoneyBoardService
+"""
+
+HOME_ACTIVITIES = """priority=0 preferredOrder=0 match=0x108000 specificIndex=-1 isDefault=false
+com.sec.android.app.launcher/com.android.launcher3.uioverrides.QuickstepLauncher
+priority=-1000 preferredOrder=0 match=0x108000 specificIndex=-1 isDefault=false
+com.android.settings/.FallbackHome
+"""
+
+WEBVIEW = """Current WebView package (name, version): (com.google.android.webview, 120.0.6099.144)
+Valid package com.google.android.webview (versionName: 120.0.6099.144, versionCode: 609914433, targetSdkVersion: 34) is installed/enabled for all users
+Invalid package com.android.chrome (versionName: 120.0.6099.144), reason: No WebView-library manifest flag
+"""
+
+
+class RulesDevice(FakeADB):
+    """A Samsung with its keyboards, WebView providers and launchers"""
+
+    def __init__(self, cmd_package=True):
+        super().__init__()
+        self.cmd_package = cmd_package
+
+    def supports(self, feature):
+        return self.cmd_package
+
+    def get_overlay_targets(self):
+        return {"com.android.theme.icon_pack.filled.android": "android"}
+
+    def get_apex_packages(self):
+        return {"com.android.tethering": "com.android.tethering"}
+
+    def answer(self, command):
+        if command.startswith("ime"):
+            return IME_LIST
+        if command.startswith("dumpsys webviewupdate"):
+            return WEBVIEW
+        if "HOME" in command:
+            return HOME_ACTIVITIES
+        return ""
+
+
+def test_parsers():
+    """Test provider parsing for each query"""
+    print("\n📄 Testing provider parsing...")
+    assert parse_providers("keyboard", IME_LIST) == {
+        "com.google.android.inputmethod.latin", "com.samsung.android.honeyboard"}
+    # Settings' boot-time fallback home does not count as a launcher
+    assert parse_providers("launcher", HOME_ACTIVITIES) == {"com.sec.android.app.launcher"}
+    assert parse_providers("webview", WEBVIEW) == {"com.google.android.webview"}
+    print("  └─ ✅ Parsing OK")
+    return True
+
+
+def test_validation():
+    """Test that only selections covering every provider of a role are flagged"""
+    print("\n🧩 Testing selection validation...")
+    validator = SelectionValidator(RulesDevice())
+    result = validator.validate_selection(["com.samsung.android.honeyboard", "com.facebook.katana"])
+    assert result["valid"] and result["violations"] == []
+
+    result = validator.validate_selection([
+        "com.samsung.android.honeyboard", "com.google.android.inputmethod.latin", "com.sec.android.app.launcher"])
+    assert not result["valid"]
+    assert [v["rule"] for v in result["violations"]] == ["keyboard", "launcher"]
+    # No dialer was found, so nothing can be concluded about it
+    assert "dialer" not in [v["rule"] for v in result["violations"]]
+
+    # Theme overlays are never part of a removal
+    result = validator.validate_selection(["com.android.theme.icon_pack.filled.android", "com.facebook.katana"])
+    assert not result["valid"] and result["violations"][0]["rule"] == "overlay"
+    assert result["violations"][0]["packages"] == ["com.android.theme.icon_pack.filled.android"]
+
+    # Neither are system modules
+    result = validator.validate_selection(["com.android.tethering"])
+    assert not result["valid"] and result["violations"][0]["rule"] == "apex"
+
+    result = SelectionValidator(RulesDevice(cmd_package=False)).validate_selection(["com.sec.android.app.launcher"])
+    assert result["valid"] and set(result["unchecked"]) == {"launcher", "dialer"}
+    print("  └─ ✅ Validation OK")
+    return True
+
+
+def main():
+    """Run all selection rule tests"""
+    tests = [test_parsers, test_validation]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,86 @@
+"""
+Test the session log behind export_session_report
+Runs against recorded commands and events - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from session_log import MAX_SESSION_ENTRIES, SessionLog
+
+
+DEVICE = {"manufacturer": "samsung", "model": "SM-G991B", "serial": "SERIAL1", "androidVersion": "14"}
+
+
+def body(report):
+    """Report lines after the header, without their clock times"""
+    lines = report.split("\n\n", 1)[1].splitlines()
+    return [line[10:] if line[:2].isdigit() else line.strip() for line in lines]
+
+
+def test_session_report():
+    """Test the order, outcomes, collapsed reads, quiet entries and redacted chat text of a report"""
+    print("\n🧾 Testing session report...")
+    log = SessionLog()
+    log.record_command("list_packages", {"packageType": "all"}, [{"packageName": "com.facebook.katana"}])
+    log.record_command("list_packages", {"packageType": "all"}, [{"packageName": "com.facebook.katana"}])
+    log.record_command("ack_package_chunk", {"streamId": "s1"}, {"success": True})
+    log.record_command("uninstall_package", {"packageName": "com.facebook.katana"},
+                       {"success": False, "message": "Failed to uninstall: DELETE_FAILED_INTERNAL_ERROR"})
+    log.record_command("chat_message", {"message": "my bank app keeps crashing", "history": []},
+                       error="Timed out")
+    log.record_event("health_update", {"cpuUsage": 10})
+    log.record_event("backup_complete", {"success": True, "message": "Backup created"}, "job-1")
+
+    report = log.render(DEVICE, {"adbVersion": "1.0.41", "platformToolsVersion": "35.0.2", "sdkLevel": 34})
+    assert "Device: samsung SM-G991B (serial SERIAL1), Android 14" in report
+    assert "adb: 1.0.41, platform-tools 35.0.2, API level 34" in report
+    lines = body(report)
+    assert lines[0].startswith("list_packages packageType=all (x2, last at ")
+    assert lines[1:] == [
+        "-> OK: 1 item(s)",
+        "uninstall_package packageName=com.facebook.katana",
+        "-> FAILED: Failed to uninstall: DELETE_FAILED_INTERNAL_ERROR",
+        "chat_message message=(omitted) history=(omitted)",
+        "-> ERROR: Timed out",
+        "event backup_complete [job job-1]",
+        "-> OK: Backup created",
+    ]
+    assert "bank" not in report
+    print("  └─ ✅ Session report OK")
+    return True
+
+
+def test_report_limits():
+    """Test the empty report, long values and the entry cap"""
+    print("\n📏 Testing session report limits...")
+    log = SessionLog()
+    empty = log.render()
+    assert "Device: not connected" in empty and "No commands were run in this session." in empty
+
+    log.record_command("search_packages", {"query": "x" * 500}, {"success": True})
+    assert body(log.render())[0].endswith("x…") and len(body(log.render())[0]) < 200
+    for i in range(MAX_SESSION_ENTRIES + 5):
+        log.record_command("disable_package", {"packageName": f"com.example.app{i}"}, {"success": True})
+    entries = log.entries()
+    assert len(entries) == MAX_SESSION_ENTRIES and entries[0]["args"]["packageName"] == "com.example.app5"
+    assert f"(only the last {MAX_SESSION_ENTRIES} entries are kept)" in log.render()
+    print("  └─ ✅ Session report limits OK")
+    return True
+
+
+def main():
+    """Run all session log tests"""
+    tests = [test_session_report, test_report_limits]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,188 @@
+"""
+Test the backend settings and the AI requests built from them
+Runs against a temporary data directory - no network or API key required
+"""
+import sys
+import os
+import json
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+import network
+from ai_advisor import AIAdvisor
+from prompts import EXPERT_STYLE, PLAIN_LANGUAGE_STYLE
+from settings_manager import MAX_NICKNAME_LENGTH, SettingsManager
+from usage_tracker import AIUsageTracker
+
+
+class FakeResponse:
+    status_code = 200
+    text = ""
+
+    def raise_for_status(self):
+        pass
+
+    def json(self):
+        return {"choices": [{"message": {"content": "Yes, it is safe to remove."}}], "usage": {}}
+
+
+class AnalysisResponse(FakeResponse):
+    def json(self):
+        return {"choices": [{"message": {"content": '{"riskCategory": "Safe", "safeToRemove": true}'}}],
+                "usage": {}}
+
+
+def make_advisor(settings, data_dir):
+    advisor = AIAdvisor(settings=settings)
+    advisor.api_key = "test-key"
+    advisor.usage = AIUsageTracker(data_dir)
+    return advisor
+
+
+def capture_posts(posted, response=FakeResponse):
+    def fake_post(url, **kwargs):
+        posted.append((url, kwargs["json"]))
+        return response()
+    return fake_post
+
+
+def test_ai_parameters():
+    """Test that the model and sampling parameters are validated and sent with chats"""
+    print("\n🎛️  Testing AI model settings...")
+    posted, original_post = [], network.post
+    network.post = capture_posts(posted)
+    try:
+        with tempfile.TemporaryDirectory() as tmp:
+            settings = SettingsManager(tmp)
+            rejected = settings.update_settings({"ai": {"model": "gpt-5", "chatTemperature": 0.3}})
+            assert rejected["success"] is False and "ai.model" in rejected["message"]
+            assert settings.get("ai", "chatTemperature") == 0.7, "nothing is saved when a value is invalid"
+            assert settings.update_settings({"ai": {"chatMaxTokens": 10.5}})["success"] is False
+
+            changes = {"model": "sonar-pro", "chatTemperature": 0.3, "chatMaxTokens": 400, "searchRecency": "none"}
+            assert settings.update_settings({"ai": changes})["success"]
+            with open(os.path.join(tmp, "settings.json"), encoding="utf-8") as f:
+                assert json.load(f)["ai"]["model"] == "sonar-pro"
+
+            make_advisor(SettingsManager(tmp), tmp).chat("Is com.facebook.katana safe to remove?")
+            payload = posted[-1][1]
+            assert payload["model"] == "sonar-pro"
+            assert payload["temperature"] == 0.3 and payload["max_tokens"] == 400
+            assert "search_recency_filter" not in payload
+
+            settings.update_settings({"ai": {"searchRecency": "week"}})
+            make_advisor(settings, tmp).chat("And com.facebook.services?")
+            assert posted[-1][1]["search_recency_filter"] == "week"
+    finally:
+        network.post = original_post
+    print("  └─ ✅ AI model settings OK")
+    return True
+
+
+def test_device_nicknames():
+    """Test naming, renaming and unnaming devices, and that names are validated and persisted"""
+    print("\n🏷️  Testing device nicknames...")
+    with tempfile.TemporaryDirectory() as tmp:
+        settings = SettingsManager(tmp)
+        named = settings.set_device_nickname("R58M123ABC", "  My S21 ")
+        assert named["success"] and named["message"] == "R58M123ABC is now called My S21"
+        settings.set_device_nickname("emulator-5554", "Work phone")
+        assert SettingsManager(tmp).device_nickname("R58M123ABC") == "My S21", "names survive a restart"
+
+        too_long = settings.set_device_nickname("R58M123ABC", "x" * (MAX_NICKNAME_LENGTH + 1))
+        assert too_long["success"] is False and "devices.nicknames" in too_long["message"]
+        assert settings.device_nickname("R58M123ABC") == "My S21"
+        assert settings.set_device_nickname("", "Tablet")["message"] == "No device serial given"
+
+        removed = settings.set_device_nickname("R58M123ABC", "")
+        assert removed["message"] == "Removed the nickname of R58M123ABC"
+        assert settings.get("devices", "nicknames") == {"emulator-5554": "Work phone"}
+        assert settings.device_nickname(None) is None
+    print("  └─ ✅ Device nicknames OK")
+    return True
+
+
+def test_endpoint_override():
+    """Test that analyses and chats go to a configured gateway with its model names"""
+    print("\n🌐 Testing endpoint override...")
+    posted, original_post = [], network.post
+    network.post = capture_posts(posted)
+    try:
+        with tempfile.TemporaryDirectory() as tmp:
+            settings = SettingsManager(tmp)
+            advisor = make_advisor(settings, tmp)
+            assert advisor.endpoint_url() == "https://api.perplexity.ai/chat/completions"
+
+            for bad in ("openrouter.ai/api/v1", "ftp://gateway.local/v1", "https://my gateway/v1", 42):
+                rejected = settings.update_settings({"ai": {"baseUrl": bad}})
+                assert rejected["success"] is False and "ai.baseUrl" in rejected["message"], bad
+            assert settings.update_settings({"ai": {"modelAliases": {"sonar": " "}}})["success"] is False
+
+            assert settings.update_settings({"ai": {"baseUrl": "https://openrouter.ai/api/v1/",
+                                                    "modelAliases": {"sonar": "perplexity/sonar"}}})["success"]
+            assert advisor.endpoint_url() == "https://openrouter.ai/api/v1/chat/completions"
+            advisor.chat("Is com.facebook.katana safe to remove?")
+            url, payload = posted[-1]
+            assert url == "https://openrouter.ai/api/v1/chat/completions" and payload["model"] == "perplexity/sonar"
+
+            # A full completions URL (LiteLLM) is used as given; models without an alias keep their name
+            settings.update_settings({"ai": {"baseUrl": "http://localhost:4000/chat/completions",
+                                             "model": "sonar-pro"}})
+            advisor.chat("And com.facebook.services?")
+            assert posted[-1][0] == "http://localhost:4000/chat/completions" and posted[-1][1]["model"] == "sonar-pro"
+
+            settings.update_settings({"ai": {"baseUrl": ""}})
+            assert advisor.endpoint_url() == "https://api.perplexity.ai/chat/completions"
+    finally:
+        network.post = original_post
+    print("  └─ ✅ Endpoint override OK")
+    return True
+
+
+def test_plain_language():
+    """Test that plain-language mode reaches both the analysis and the chat prompts"""
+    print("\n🗣️  Testing plain-language mode...")
+    posted, original_post = [], network.post
+    network.post = capture_posts(posted, AnalysisResponse)
+
+    def prompts_sent(advisor):
+        del posted[:]
+        advisor.analyze_package("com.samsung.android.app.spage")
+        advisor.chat("What does Samsung Free do?")
+        return ["\n".join(m["content"] for m in payload["messages"]) for _, payload in posted]
+
+    try:
+        with tempfile.TemporaryDirectory() as tmp:
+            settings = SettingsManager(tmp)
+            assert settings.update_settings({"ai": {"plainLanguage": "yes"}})["success"] is False
+            for provider in ("perplexity", "openai"):
+                advisor = make_advisor(settings, tmp)
+                advisor.provider = provider
+                settings.update_settings({"ai": {"plainLanguage": False, "expertMode": False}})
+                assert not any(PLAIN_LANGUAGE_STYLE in text for text in prompts_sent(advisor))
+
+                settings.update_settings({"ai": {"plainLanguage": True, "expertMode": True}})
+                analysis, chat = prompts_sent(advisor)
+                assert PLAIN_LANGUAGE_STYLE in analysis and PLAIN_LANGUAGE_STYLE in chat, provider
+                assert EXPERT_STYLE not in analysis + chat, "plain language wins over expert mode"
+    finally:
+        network.post = original_post
+    print("  └─ ✅ Plain-language mode OK")
+    return True
+
+
+def main():
+    """Run all settings tests"""
+    tests = [test_ai_parameters, test_device_nicknames, test_endpoint_override, test_plain_language]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
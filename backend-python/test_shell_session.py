@@ -0,0 +1,101 @@
+"""
+Test the persistent shell session
+Runs against a local `sh` standing in for `adb shell` - no ADB required
+"""
+import sys
+import os
+import threading
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from shell_session import ShellSession, ShellSessionTimeout, can_use_session
+
+
+def test_session_protocol():
+    """Test output, exit status and output without a trailing newline"""
+    print("\n🐚 Testing shell session protocol...")
+    session = ShellSession(["sh"])
+    try:
+        assert session.run("echo one; echo two", 5) == ("one\ntwo\n", 0)
+        assert session.run("printf 'no newline'", 5) == ("no newline", 0)
+        assert session.run("echo hidden >&2; false", 5) == ("", 1)
+        # State from one command does not leak into the next one
+        session.run("FOO=bar", 5)
+        assert session.run("echo \"[$FOO]\"", 5) == ("[]\n", 0)
+        assert session.run("echo done # comment", 5) == ("done\n", 0)
+    finally:
+        session.close()
+    print("  └─ ✅ Session protocol OK")
+    return True
+
+
+def test_timeout_and_busy():
+    """Test that a hung command kills the session and a busy session is skipped"""
+    print("\n⏱️  Testing session timeout...")
+    session = ShellSession(["sh"])
+    try:
+        session.run("sleep 5", 0.3)
+        assert False, "should time out"
+    except ShellSessionTimeout:
+        pass
+    assert not session.alive
+
+    session = ShellSession(["sh"])
+    started = threading.Event()
+    worker = threading.Thread(target=lambda: (started.set(), session.run("sleep 0.5", 5)))
+    worker.start()
+    started.wait()
+    threading.Event().wait(0.1)
+    assert session.run("echo quick", 5) is None, "a busy session should not make callers wait"
+    worker.join()
+    session.close()
+    print("  └─ ✅ Session timeout OK")
+    return True
+
+
+def test_adb_fallback():
+    """Test which commands use the session and the one-shot fallback"""
+    print("\n🔀 Testing one-shot fallback...")
+    assert can_use_session("cat /proc/meminfo", 10)
+    assert not can_use_session("dumpsys package", 120)
+    assert not can_use_session("cd /sdcard && ls", 10)
+    assert not can_use_session("am restart", 10)
+
+    class SessionADB(FakeADB):
+        """A fake device whose sessions are local shells"""
+        def __init__(self):
+            super().__init__(run_output="one-shot\n", shell_sessions=True)
+
+        def _current_session(self):
+            if "SERIAL1" not in self._sessions:
+                self._sessions["SERIAL1"] = ShellSession(["sh"])
+            return self._sessions["SERIAL1"]
+
+    adb = SessionADB()
+    try:
+        assert adb.shell("echo pooled", timeout=10) == "pooled\n"
+        assert adb.shell("exit 3", timeout=10) == "one-shot\n"
+        assert adb.shell("false", timeout=10) == "one-shot\n", "failures are re-run for adb's error"
+        assert adb.shell("echo slow", timeout=60) == "one-shot\n"
+        assert [run[-1] for run in adb.runs] == ["exit 3", "false", "echo slow"]
+    finally:
+        adb.close_sessions()
+    print("  └─ ✅ Fallback OK")
+    return True
+
+
+def main():
+    """Run all shell session tests"""
+    tests = [test_session_protocol, test_timeout_and_busy, test_adb_fallback]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,106 @@
+"""
+Test the exit steps: registration order, a failing step not stopping the
+others, running once, and a full backup cancelled before it finishes
+Runs against fakes - no ADB required
+"""
+import sys
+import os
+import tempfile
+import threading
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from backup_manager import BackupManager
+from shutdown import ShutdownManager
+
+
+class PullingDevice(FakeADB):
+    """Blocks the first APK pull until the test lets it go"""
+
+    def __init__(self):
+        super().__init__()
+        self.pulling = threading.Event()
+        self.release = threading.Event()
+        self.pulled = []
+
+    def get_package_sizes(self):
+        return {}
+
+    def get_apk_paths(self, package):
+        return [f"/data/app/{package}/base.apk"]
+
+    def pull_file(self, remote, local):
+        self.pulling.set()
+        self.release.wait(5)
+        self.pulled.append(remote)
+        with open(local, "wb") as f:
+            f.write(b"apk")
+
+
+def test_steps():
+    """Test that steps run in order, failures are isolated and a second run does nothing"""
+    print("\n🚪 Testing shutdown steps...")
+    calls = []
+    shutdown = ShutdownManager()
+    shutdown.register("first", lambda: calls.append("first"))
+
+    def broken():
+        raise RuntimeError("device gone")
+
+    shutdown.register("broken", broken)
+    shutdown.register("last", lambda: calls.append("last"))
+
+    results = shutdown.run("test")
+    assert calls == ["first", "last"]
+    assert [r["name"] for r in results] == ["first", "broken", "last"]
+    assert not results[1]["success"] and results[1]["error"] == "device gone"
+    assert results[0]["success"] and "error" not in results[0]
+    assert shutdown.done and shutdown.run("again") == [] and calls == ["first", "last"]
+    print("  └─ ✅ Shutdown steps OK")
+    return True
+
+
+def test_cancel_full_backup():
+    """Test that a running full backup stops after the current APK and leaves no zip"""
+    print("\n🛑 Testing full backup cancellation...")
+    with tempfile.TemporaryDirectory() as tmp:
+        adb = PullingDevice()
+        events = []
+        manager = BackupManager(tmp, adb_operations=adb, emit_event=lambda kind, payload, job_id=None:
+                                events.append((kind, payload)))
+        manager.storage_guard.local_free = lambda directory: 50_000 * 1024 * 1024
+        assert not manager.cancel_full_backup()
+
+        started = manager.create_full_backup(["com.one", "com.two", "com.three"])
+        assert started["success"]
+        assert adb.pulling.wait(5)
+        threading.Timer(0.2, adb.release.set).start()
+        assert manager.cancel_full_backup(wait_seconds=5)
+
+        assert adb.pulled == ["/data/app/com.one/base.apk"]
+        complete = [payload for kind, payload in events if kind == "backup_complete"]
+        assert len(complete) == 1 and not complete[0]["success"]
+        assert complete[0]["message"] == "Failed to create full backup: Cancelled because the app is closing"
+        assert os.listdir(tmp) == []
+        # The job slot is free again
+        assert manager.create_full_backup(["com.one"])["success"]
+        manager.cancel_full_backup(wait_seconds=5)
+    print("  └─ ✅ Full backup cancelled")
+    return True
+
+
+def main():
+    """Run all shutdown tests"""
+    tests = [test_steps, test_cancel_full_backup]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
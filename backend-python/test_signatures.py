@@ -0,0 +1,118 @@
+"""
+Test platform-key and signature permission detection from `dumpsys package`
+Runs against canned device output - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from signatures import (PERMISSIONS_MARKER, SIGNATURE_SCRIPT, describe_signature, parse_permission_protections,
+                        platform_signed_packages, signature_info)
+
+
+PACKAGES_DUMP = """Packages:
+  Package [android] (5b1c2d3):
+    userId=1000
+    sharedUser=SharedUserSetting{8e9f0a1 android.uid.system/1000}
+    signatures=PackageSignatures{7d1e6a3 version:3, signatures:[2a1f1b5d], past signatures:[]}
+    pkgFlags=[ SYSTEM HAS_CODE PERSISTENT ]
+  Package [com.example.oemservice] (6c2d3e4):
+    userId=10087
+    signatures=PackageSignatures{1a2b3c4 version:3, signatures:[2a1f1b5d], past signatures:[]}
+    pkgFlags=[ SYSTEM HAS_CODE ]
+    privateFlags=[ PRIVATE_FLAG_PRIVILEGED PRIVATE_FLAG_PRODUCT ]
+    requested permissions:
+      android.permission.INTERACT_ACROSS_USERS
+      android.permission.INTERNET
+    install permissions:
+      android.permission.INTERACT_ACROSS_USERS: granted=true
+      android.permission.INTERNET: granted=true
+    User 0: ceDataInode=1234 installed=true hidden=false
+      runtime permissions:
+        android.permission.READ_CONTACTS: granted=true
+  Package [com.android.phone] (7d3e4f5):
+    userId=1001
+    sharedUser=SharedUserSetting{9f0a1b2 android.uid.phone/1001}
+    signatures=PackageSignatures{2b3c4d5 [4f5e6d7c]}
+  Package [com.facebook.katana] (8e4f5a6):
+    userId=10123
+    signatures=PackageSignatures{3c4d5e6 version:2, signatures:[9a8b7c6d], past signatures:[]}
+    pkgFlags=[ HAS_CODE ALLOW_CLEAR_USER_DATA ]
+    install permissions:
+      android.permission.INTERNET: granted=true
+"""
+
+PERMISSIONS_DUMP = """Permissions:
+  Permission [android.permission.INTERACT_ACROSS_USERS] (a1b2c3d):
+    sourcePackage=android
+    uid=1000 gids=[] type=0 prot=signature|privileged|development
+  Permission [android.permission.INTERNET] (b2c3d4e):
+    sourcePackage=android
+    uid=1000 gids=[3003] type=0 prot=normal
+"""
+
+DUMP = PACKAGES_DUMP + PERMISSIONS_MARKER + "\n" + PERMISSIONS_DUMP
+
+
+def make_adb(dump=DUMP):
+    """A plain phone answering the package dump"""
+    return FakeADB(exec_output={SIGNATURE_SCRIPT: dump})
+
+
+def test_parse():
+    """Test platform key, privilege and signature permission parsing"""
+    print("\n🔑 Testing signature parsing...")
+    assert parse_permission_protections(PERMISSIONS_DUMP) == {
+        "android.permission.INTERACT_ACROSS_USERS": "signature", "android.permission.INTERNET": "normal",
+    }
+    info = signature_info(DUMP)
+    oem = info["com.example.oemservice"]
+    assert oem["platformSigned"] and oem["privileged"] and oem["sharedUserId"] is None
+    assert oem["signaturePermissions"] == ["android.permission.INTERACT_ACROSS_USERS"]
+    # Old dump format: no hashes, recognised by its platform shared user id
+    assert info["com.android.phone"]["platformSigned"]
+    facebook = info["com.facebook.katana"]
+    assert not facebook["platformSigned"] and facebook["signaturePermissions"] == []
+    assert platform_signed_packages(info) == {"android", "com.example.oemservice", "com.android.phone"}
+    assert describe_signature(facebook) == []
+    assert describe_signature(oem)[0].startswith("Signed with the platform key")
+    assert signature_info("") == {}
+    print("  └─ ✅ Signature parsing OK")
+    return True
+
+
+def test_dangerous_signal():
+    """Test that unlisted platform-signed packages are rated Dangerous and listed ones keep their level"""
+    print("\n🛡️  Testing the platform key safety signal...")
+    adb = make_adb()
+    entries = {p["packageName"]: p for p in adb.build_package_entries(
+        ["com.example.oemservice", "com.facebook.katana", "com.android.phone", "com.samsung.android.app.tips"])}
+    assert entries["com.example.oemservice"]["safetyLevel"] == "Dangerous"
+    assert entries["com.example.oemservice"]["platformSigned"] is True
+    assert entries["com.facebook.katana"]["safetyLevel"] == "Safe"
+    assert "platformSigned" not in entries["com.facebook.katana"]
+    assert entries["com.android.phone"]["safetyLevel"] == "Dangerous"
+    assert entries["com.samsung.android.app.tips"]["safetyLevel"] == "Caution"
+
+    adb.build_package_entries(["com.facebook.katana"])
+    assert adb.commands.count(SIGNATURE_SCRIPT) == 1  # Dumped once per device
+    print("  └─ ✅ Platform key safety signal OK")
+    return True
+
+
+def main():
+    """Run all signature tests"""
+    tests = [test_parse, test_dangerous_signal]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
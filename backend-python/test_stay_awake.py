@@ -0,0 +1,173 @@
+"""
+Test keeping the device awake during long jobs and restoring its setting
+Runs against a fake device - no ADB required
+"""
+import sys
+import os
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from stay_awake import StayAwake, parse_stay_on
+
+
+class SettingsDevice(FakeADB):
+    """Keeps stay_on_while_plugged_in in memory and records every write"""
+
+    def __init__(self, value="0", settings_command=True):
+        super().__init__()
+        self.value = value
+        self.settings_command = settings_command
+        self.writes = []
+
+    def supports(self, feature):
+        return self.settings_command
+
+    def answer(self, command):
+        if command.startswith("settings get"):
+            return self.value + "\n"
+        self.writes.append(command)
+        if command.startswith("settings put"):
+            self.value = command.split()[-1]
+        return ""
+
+
+def test_parse():
+    """Test reading the setting value"""
+    print("\n🔢 Testing setting parsing...")
+    assert parse_stay_on("3\n") == 3
+    assert parse_stay_on("null\n") == 0
+    assert parse_stay_on("error") is None
+    print("  └─ ✅ Parsing OK")
+    return True
+
+
+def test_hold_and_restore():
+    """Test that the USB bit is set during a hold and the original value put back"""
+    print("\n☕ Testing hold and restore...")
+    with tempfile.TemporaryDirectory() as tmp:
+        adb = SettingsDevice(value="1")  # AC only
+        stay = StayAwake(adb, tmp)
+        with stay.hold("Full backup") as hold_id:
+            assert hold_id and adb.value == "3"
+            assert stay.get_status()["holds"][0]["reason"] == "Full backup"
+        assert adb.value == "1"
+        assert stay.get_status() == {"enabled": True, "holds": [], "pendingRestore": []}
+
+        # Released even when the job raises
+        try:
+            with stay.hold("Optimization"):
+                raise RuntimeError("cancelled")
+        except RuntimeError:
+            pass
+        assert adb.value == "1"
+    print("  └─ ✅ Hold and restore OK")
+    return True
+
+
+def test_overlapping_holds():
+    """Test that only the last of overlapping holds restores the setting"""
+    print("\n🔁 Testing overlapping holds...")
+    with tempfile.TemporaryDirectory() as tmp:
+        adb = SettingsDevice()
+        stay = StayAwake(adb, tmp)
+        first = stay.acquire("Batch uninstall")
+        second = stay.acquire("Restore")
+        assert adb.writes == ["settings put global stay_on_while_plugged_in 2"]
+        assert stay.release(first)["restored"] is False and adb.value == "2"
+        assert stay.release(second)["restored"] is True and adb.value == "0"
+        assert not stay.release(second)["success"]
+    print("  └─ ✅ Overlapping holds OK")
+    return True
+
+
+def test_untouched_devices():
+    """Test that nothing is written when already awake on USB or when disabled"""
+    print("\n🚫 Testing untouched devices...")
+    with tempfile.TemporaryDirectory() as tmp:
+        adb = SettingsDevice(value="7")
+        stay = StayAwake(adb, tmp)
+        stay.release(stay.acquire("Batch uninstall"))
+        assert adb.writes == []
+
+        adb = SettingsDevice()
+        stay = StayAwake(adb, tmp, enabled=lambda: False)
+        assert stay.acquire("Batch uninstall") is None
+        assert adb.writes == []
+
+        adb = SettingsDevice()
+        adb.connected = False
+        assert StayAwake(adb, tmp).acquire("Batch uninstall") is None
+    print("  └─ ✅ Untouched devices OK")
+    return True
+
+
+def test_svc_fallback():
+    """Test `svc power stayon` on devices without the settings command"""
+    print("\n🧰 Testing svc fallback...")
+    with tempfile.TemporaryDirectory() as tmp:
+        adb = SettingsDevice(settings_command=False)
+        stay = StayAwake(adb, tmp)
+        stay.release(stay.acquire("Batch uninstall"))
+        assert adb.writes == ["svc power stayon usb", "svc power stayon false"]
+    print("  └─ ✅ svc fallback OK")
+    return True
+
+
+def test_pending_restore():
+    """Test that a device unplugged mid-job, or a closed backend, is restored on reconnect"""
+    print("\n🔌 Testing restore after disconnect...")
+    with tempfile.TemporaryDirectory() as tmp:
+        adb = SettingsDevice()
+        stay = StayAwake(adb, tmp)
+        hold_id = stay.acquire("Batch uninstall")
+        adb.connected = False
+        assert stay.release(hold_id)["restored"] is False
+        assert stay.get_status()["pendingRestore"] == ["SERIAL1"]
+
+        # Another device connected first: left for SERIAL1 to come back
+        adb.connected, adb.serial = True, "SERIAL2"
+        assert not stay.restore_pending("SERIAL1")
+        adb.serial = "SERIAL1"
+        assert stay.restore_pending("SERIAL1") and adb.value == "0"
+        assert not stay.restore_pending("SERIAL1")
+
+        # The original survives a restart of the backend
+        stay.acquire("Optimization")
+        reopened = StayAwake(adb, tmp)
+        assert reopened.get_status()["pendingRestore"] == ["SERIAL1"]
+        assert reopened.restore_pending("SERIAL1") and adb.value == "0"
+    print("  └─ ✅ Restore after disconnect OK")
+    return True
+
+
+def test_release_all():
+    """Test that shutdown ends every hold"""
+    print("\n🛑 Testing release on shutdown...")
+    with tempfile.TemporaryDirectory() as tmp:
+        adb = SettingsDevice()
+        stay = StayAwake(adb, tmp)
+        stay.acquire("Batch uninstall")
+        stay.acquire("Full backup")
+        stay.release_all()
+        assert adb.value == "0" and stay.get_status()["holds"] == []
+    print("  └─ ✅ Release on shutdown OK")
+    return True
+
+
+def main():
+    """Run all stay-awake tests"""
+    tests = [test_parse, test_hold_and_restore, test_overlapping_holds, test_untouched_devices,
+             test_svc_fallback, test_pending_restore, test_release_all]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
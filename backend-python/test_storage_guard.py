@@ -0,0 +1,94 @@
+"""
+Test the free space checks before backups: estimates, refusal, warnings and
+a full backup that is not started
+Runs against fakes - no ADB required
+"""
+import sys
+import os
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from backup_manager import BackupManager
+from formatting import format_size
+from storage_guard import LOCAL_MARGIN_BYTES, MB, check_space, estimate_backup_bytes
+
+
+SIZES = {
+    "com.spotify.music": {"appBytes": 150 * MB, "dataBytes": 900 * MB},
+    "com.samsung.bloat": {"appBytes": 80 * MB, "dataBytes": 5 * MB},
+}
+
+
+def make_adb():
+    """A phone with its /data partition almost full"""
+    adb = FakeADB({"pm list packages -s": "package:com.samsung.bloat\n",
+                   "LC_ALL=C df -k /data": ("Filesystem 1K-blocks Used Available Use% Mounted on\n"
+                                            "/dev/block/dm-8 115249236 115000000 249236 99% /data\n")})
+    adb.get_package_sizes = lambda: SIZES
+    return adb
+
+
+def test_estimate():
+    """Test that only exported APKs and, with data, every package's data count"""
+    print("\n📐 Testing backup size estimate...")
+    packages = ["com.spotify.music", "com.samsung.bloat", "com.unknown"]
+    assert estimate_backup_bytes(SIZES, packages, ["com.spotify.music"], False) == 150 * MB
+    assert estimate_backup_bytes(SIZES, packages, [], True) == 905 * MB
+    print("  └─ ✅ Estimate OK")
+    return True
+
+
+def test_check_space():
+    """Test refusal below the margin and warnings for tight space and a full device"""
+    print("\n💾 Testing free space check...")
+    refused = check_space(1000 * MB, 1100 * MB)
+    assert not refused["success"] and refused["insufficientSpace"]
+    assert refused["message"] == (f"Not enough free space for the backup: it needs about {format_size(1200 * MB)} "
+                                  f"and {format_size(1100 * MB)} is free on this computer")
+
+    tight = check_space(1000 * MB, 1000 * MB + LOCAL_MARGIN_BYTES)
+    assert tight["success"] and tight["message"].startswith("Free space is tight")
+
+    roomy = check_space(100 * MB, 50_000 * MB, device_available=100 * MB)
+    assert roomy["success"] and roomy["warnings"] == [
+        f"The device has only {format_size(100 * MB)} free; apps may fail to write their backup data"]
+    assert roomy["formatted"]["required"] == format_size(100 * MB)
+    assert check_space(100 * MB, 50_000 * MB)["warnings"] == []
+    print(f"  └─ ✅ {refused['message']}")
+    return True
+
+
+def test_full_backup_refused():
+    """Test that a full backup is not started when its APKs would not fit"""
+    print("\n🛑 Testing full backup refusal...")
+    with tempfile.TemporaryDirectory() as tmp:
+        manager = BackupManager(tmp, adb_operations=make_adb())
+        manager.storage_guard.local_free = lambda directory: 300 * MB
+        result = manager.create_full_backup(["com.spotify.music", "com.samsung.bloat"])
+        # 150 MB of third-party APKs plus the 200 MB margin do not fit in 300 MB
+        assert not result["success"] and result["insufficientSpace"] and "jobId" not in result
+        assert result["space"]["requiredBytes"] == 150 * MB
+        assert os.listdir(tmp) == []
+
+        data = manager.create_backup(["com.spotify.music"], include_data=True)
+        assert not data["success"] and data["space"]["deviceAvailableBytes"] == 249236 * 1024
+    print("  └─ ✅ Full backup refused")
+    return True
+
+
+def main():
+    """Run all storage guard tests"""
+    tests = [test_estimate, test_check_space, test_full_backup_refused]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,374 @@
+"""
+Test the System Health parsers
+Runs against captured shell output and a fake device - no device required
+"""
+import sys
+import os
+import threading
+sys.path.insert(0, os.path.dirname(__file__))
+
+import system_health
+from fake_adb import FakeADB
+from system_health import (
+    parse_proc_stat, compute_cpu_usage, parse_top_cpu,
+    parse_meminfo, parse_zram_mm_stat, build_memory_info,
+    parse_running_services, parse_notification_counts, parse_df, build_storage_info,
+    parse_thermal_sensors, parse_battery_temperature, select_device_temperature,
+    parse_power_state, parse_screen_on_seconds, parse_adopted_volumes, SystemHealth, CPU_SAMPLE_MAX_AGE,
+)
+from performance_report import build_performance_report
+
+
+def test_cpu_parsing():
+    """Test /proc/stat delta sampling and the top fallback"""
+    print("\n🧮 Testing CPU parsing...")
+
+    first = parse_proc_stat(
+        "cpu  1000 0 500 8000 500 0 0 0 0 0\n"
+        "cpu0 250 0 125 2000 125 0 0 0 0 0\n"
+    )
+    second = parse_proc_stat("cpu  1600 0 900 8400 600 0 0 0 0 0\n")
+
+    assert first == [1000, 0, 500, 8000, 500, 0, 0, 0, 0, 0]
+    # busy delta 1000, idle delta 500 -> 66.7%
+    assert compute_cpu_usage(first, second) == 66.7
+    # Counters reset after reboot
+    assert compute_cpu_usage(second, first) is None
+    assert parse_proc_stat("garbage") is None
+
+    top = "800%cpu  14%user   0%nice  26%sys 760%idle   0%iow   0%irq   0%sirq   0%host"
+    assert parse_top_cpu(top) == 5.0
+
+    print("  └─ ✅ CPU parsing OK")
+    return True
+
+
+def test_cpu_sampling():
+    """Test that a cached /proc/stat sample is reused only while it is recent"""
+    print("\n⏱️  Testing CPU sampling...")
+    samples = iter([
+        "cpu  1000 0 500 8000 500 0 0 0 0 0\n",
+        "cpu  1600 0 900 8400 600 0 0 0 0 0\n",
+        "cpu  1600 0 900 9400 600 0 0 0 0 0\n",
+        "cpu  1600 0 900 99400 600 0 0 0 0 0\n",
+        "cpu  1700 0 900 99400 600 0 0 0 0 0\n",
+    ])
+    adb = FakeADB({"cat /proc/stat": lambda command: next(samples)})
+    health = SystemHealth(adb)
+    interval, system_health.CPU_SAMPLE_INTERVAL = system_health.CPU_SAMPLE_INTERVAL, 0
+    try:
+        # No sample yet: two reads
+        assert health.get_cpu_usage("SERIAL1") == 66.7 and len(adb.commands) == 2
+        # A recent sample is the start of the next delta
+        assert health.get_cpu_usage("SERIAL1") == 0.0 and len(adb.commands) == 3
+        # An old one is discarded instead of averaging over the idle gap
+        health._cache["SERIAL1"]["cpu_sample_time"] -= CPU_SAMPLE_MAX_AGE + 1
+        assert health.get_cpu_usage("SERIAL1") == 100.0 and len(adb.commands) == 5
+    finally:
+        system_health.CPU_SAMPLE_INTERVAL = interval
+    print("  └─ ✅ CPU sampling OK")
+    return True
+
+
+def test_monitor_restart():
+    """Test that the monitor restarts right after a stop while the old thread is mid-poll"""
+    print("\n🔁 Testing health monitor restart...")
+    health = SystemHealth(FakeADB())
+    polling, release = threading.Event(), threading.Event()
+
+    def poll():
+        polling.set()
+        release.wait(5)
+        return {"serial": "SERIAL1"}
+
+    health.get_system_health = poll
+    senders = []
+    emit = lambda kind, payload, job_id=None: senders.append(threading.current_thread())
+
+    health.start_monitor(emit)
+    assert polling.wait(5)
+    old = health._monitor_thread
+    health.stop_monitor()
+    assert health.start_monitor(emit)["message"].startswith("Health monitor started")
+    new = health._monitor_thread
+    release.set()
+    old.join(5)
+    assert not old.is_alive() and new.is_alive()
+    health.stop_monitor()
+    new.join(5)
+    assert old not in senders, "a stopped thread must not report its last poll"
+    print("  └─ ✅ Health monitor restart OK")
+    return True
+
+
+def test_memory_parsing():
+    """Test /proc/meminfo and zram parsing"""
+    print("\n🧠 Testing memory parsing...")
+
+    meminfo = parse_meminfo(
+        "MemTotal:        7812345 kB\n"
+        "MemFree:          123456 kB\n"
+        "MemAvailable:    2812345 kB\n"
+        "SwapTotal:       4194300 kB\n"
+        "SwapFree:        1048575 kB\n"
+    )
+    zram = parse_zram_mm_stat("3221225472 1073741824 1111490560 0 1200000000 12 345 0")
+    memory = build_memory_info(meminfo, zram, 4294967296)
+
+    assert memory["usedKb"] == 5000000
+    assert memory["usagePercent"] == 64.0
+    assert memory["swapUsedKb"] == 3145725
+    assert memory["zram"]["compressionRatio"] == 3.0
+    assert memory["zram"]["diskSizeKb"] == 4194304
+    assert parse_zram_mm_stat("") is None
+    # No swap configured
+    assert build_memory_info({"MemTotal": 100, "MemAvailable": 50})["swapUsagePercent"] is None
+
+    print("  └─ ✅ Memory parsing OK")
+    return True
+
+
+def test_services_parsing():
+    """Test grouping of running services by package"""
+    print("\n⚙️  Testing services parsing...")
+
+    services = parse_running_services(
+        "ACTIVITY MANAGER SERVICES (dumpsys activity services)\n"
+        "  User 0 active services:\n"
+        "  * ServiceRecord{a1b2c3 u0 com.google.android.gms/.chimera.PersistentIntentOperationService}\n"
+        "    intent={cmp=com.google.android.gms/.chimera.PersistentIntentOperationService}\n"
+        "  * ServiceRecord{d4e5f6 u0 com.google.android.gms/com.google.android.location.LocationService}\n"
+        "  * ServiceRecord{0789ab u0 com.facebook.services/com.facebook.oxygen.Service$Inner}\n"
+    )
+
+    assert services["count"] == 3
+    assert services["packageCount"] == 2
+    top = services["byPackage"][0]
+    assert top["packageName"] == "com.google.android.gms"
+    assert "com.google.android.gms.chimera.PersistentIntentOperationService" in top["services"]
+    assert parse_running_services("")["count"] == 0
+
+    print("  └─ ✅ Services parsing OK")
+    return True
+
+
+def test_notification_parsing():
+    """Test ranking packages by notifications posted and shown"""
+    print("\n🔔 Testing notification parsing...")
+
+    notifications = parse_notification_counts(
+        "Current Notification Manager state:\n"
+        "  Notification List:\n"
+        "    NotificationRecord(0x0a1b2c3d: pkg=com.whatsapp user=UserHandle{0} id=1 tag=null importance=4 key=0|com.whatsapp|1|null|10172: Notification(channel=msg))\n"
+        "    NotificationRecord(0x0e4f5a6b: pkg=com.shein.app user=UserHandle{0} id=7 tag=null importance=3 key=0|com.shein.app|7|null|10233: Notification(channel=promo))\n"
+        "    NotificationRecord(0x0c7d8e9f: pkg=com.shein.app user=UserHandle{0} id=8 tag=null importance=3 key=0|com.shein.app|8|null|10233: Notification(channel=promo))\n"
+        "  Stats:\n"
+        "    AggregatedStats{\n"
+        "        key='__global',\n"
+        "        numPostedByApp=900,\n"
+        "    }\n"
+        "    AggregatedStats{\n"
+        "        key='com.shein.app',\n"
+        "        numEnqueuedByApp=352,\n"
+        "        numPostedByApp=340,\n"
+        "    }\n"
+        "    AggregatedStats{\n"
+        "        key='com.whatsapp',\n"
+        "        numPostedByApp=41,\n"
+        "    }\n"
+    )
+
+    assert notifications["posted"] == 381 and notifications["active"] == 3
+    assert [e["packageName"] for e in notifications["byPackage"]] == ["com.shein.app", "com.whatsapp"]
+    assert notifications["byPackage"][0] == {"packageName": "com.shein.app", "posted": 340, "active": 2}
+    # Without usage stats only the shown notifications rank
+    shade_only = parse_notification_counts(
+        "    NotificationRecord(0x01: pkg=com.a user=UserHandle{0} id=1)\n"
+        "    NotificationRecord(0x02: pkg=com.b user=UserHandle{0} id=1)\n"
+        "    NotificationRecord(0x03: pkg=com.b user=UserHandle{0} id=2)\n"
+    )
+    assert [e["packageName"] for e in shade_only["byPackage"]] == ["com.b", "com.a"]
+    assert parse_notification_counts("")["packageCount"] == 0
+
+    print("  └─ ✅ Notification parsing OK")
+    return True
+
+
+def test_storage_parsing():
+    """Test df parsing across layouts"""
+    print("\n💽 Testing storage parsing...")
+
+    toybox = (
+        "Filesystem            1K-blocks     Used Available Use% Mounted on\n"
+        "/dev/block/dm-8        115249236 60123456  55125780  53% /data\n"
+        "/dev/block/dm-12        62000000  2000000  60000000   4% /mnt/expand/1234-abcd\n"
+    )
+    storage = build_storage_info(parse_df(toybox))
+    assert storage["internal"]["totalKb"] == 115249236
+    assert storage["internal"]["usedKb"] == 60123456
+    assert storage["adoptableTotalKb"] == 62000000
+
+    # Adopted cards come from the storage manager, not a glob of /mnt/expand
+    volumes = (
+        "private mounted null\n"
+        "private:179,66 mounted 1234-abcd\n"
+        "private:179,130 unmounted 5678-ef01\n"
+    )
+    assert parse_adopted_volumes(volumes) == ["/mnt/expand/1234-abcd"]
+    assert parse_adopted_volumes("/system/bin/sh: sm: not found\n") == []
+    adb = FakeADB({"sm list-volumes private 2>/dev/null": volumes,
+                   "LC_ALL=C df -k /data /mnt/expand/1234-abcd": toybox})
+    assert SystemHealth(adb).get_storage_info()["adoptable"][0]["mountPoint"] == "/mnt/expand/1234-abcd"
+
+    # Filesystem name wrapped onto its own line
+    wrapped = (
+        "Filesystem 1K-blocks Used Available Use% Mounted on\n"
+        "/dev/block/platform/soc/1d84000.ufshc/by-name/userdata\n"
+        "  100000 40000 60000 40% /data\n"
+    )
+    entries = parse_df(wrapped)
+    assert len(entries) == 1 and entries[0]["mountPoint"] == "/data"
+
+    # Legacy toolbox df with human-readable sizes
+    legacy = (
+        "Filesystem             Size   Used   Free   Blksize\n"
+        "/data                  5.9G   2.0G   3.9G   4096\n"
+    )
+    entries = parse_df(legacy)
+    assert entries[0]["mountPoint"] == "/data"
+    assert entries[0]["availableKb"] == int(3.9 * 1024 ** 2)
+
+    assert build_storage_info(parse_df("")) is None
+
+    # Translated toybox header: read by position only when every row has the toybox shape
+    german = (
+        "Dateisystem     1K-Blöcke  Benutzt Verfügbar Verw% Eingehängt auf\n"
+        "/dev/block/dm-8 115249236 60123456  55125780   53% /data\n"
+    )
+    assert parse_df(german)[0]["availableKb"] == 55125780
+    assert parse_df(german + "/dev/fuse 100 200 50 99% /mnt\n") == [], "used + available above total"
+    assert parse_df(german.replace("55125780", "55.125.780")) == []
+
+    print("  └─ ✅ Storage parsing OK")
+    return True
+
+
+def test_thermal_parsing():
+    """Test thermal sensor parsing and unit normalisation"""
+    print("\n🌡️  Testing thermal parsing...")
+
+    output = (
+        "IsStatusOverride: false\n"
+        "Current temperatures from HAL:\n"
+        "\tTemperature{mValue=412.0, mType=0, mName=cpu0, mStatus=0}\n"
+        "\tTemperature{mValue=31500.0, mType=2, mName=battery, mStatus=0}\n"
+        "\tTemperature{mValue=34.6, mType=3, mName=skin-therm, mStatus=1}\n"
+        "Cached temperatures:\n"
+        "\tTemperature{mValue=20.0, mType=3, mName=skin-therm, mStatus=0}\n"
+    )
+    sensors = parse_thermal_sensors(output)
+    assert len(sensors) == 3
+    values = {s["name"]: s["valueC"] for s in sensors}
+    assert values == {"cpu0": 41.2, "battery": 31.5, "skin-therm": 34.6}
+    assert select_device_temperature(sensors)["name"] == "skin-therm"
+
+    assert parse_battery_temperature("  level: 80\n  temperature: 325\n") == 32.5
+    assert parse_thermal_sensors("no sensors") == []
+
+    print("  └─ ✅ Thermal parsing OK")
+    return True
+
+
+def test_power_parsing():
+    """Test charging state, wake locks and screen-on time"""
+    print("\n🔋 Testing power parsing...")
+
+    power = parse_power_state(
+        "POWER MANAGER (dumpsys power)\n"
+        "Power Manager State:\n"
+        "  mIsPowered=false\n"
+        "  mPlugType=0\n"
+        "  mBatteryLevel=62\n"
+        "Wake Locks: size=3\n"
+        "  PARTIAL_WAKE_LOCK              'NlpWakeLock' ACQ=-2s123ms (uid=10089 pid=2345)\n"
+        "  PARTIAL_WAKE_LOCK              '*job*/com.example/.SyncJob' ACQ=-40s (uid=10233 pid=4567)\n"
+        "  SCREEN_BRIGHT_WAKE_LOCK        'WindowManager' ON_AFTER_RELEASE ACQ=-1m (uid=1000 pid=1200)\n"
+    )
+    assert power == {"charging": False, "plugType": None, "batteryLevel": 62, "wakeLocks": 2}
+    assert parse_power_state("  mIsPowered=true\n  mPlugType=2\n")["plugType"] == "usb"
+    assert parse_power_state("") is None
+
+    stats = (
+        "Statistics since last charge:\n"
+        "  System starts: 0, currently on battery: true\n"
+        "  Screen on: 2h 3m 4s 567ms (13.5%) 45x, Interactive: 2h 1m 0s 12ms (13.3%)\n"
+    )
+    assert parse_screen_on_seconds(stats) == 7384
+    assert parse_screen_on_seconds("  Screen on: 45s 120ms (0.1%) 2x\n") == 45
+    assert parse_screen_on_seconds("") is None
+
+    print("  └─ ✅ Power parsing OK")
+    return True
+
+
+def test_performance_report():
+    """Test scoring a health snapshot into recommendations"""
+    print("\n🚀 Testing performance report...")
+    health = {
+        "memory": {"usagePercent": 90.0},
+        "storage": {"internal": {"usagePercent": 50.0}},
+        "services": {"count": 100, "byPackage": [
+            {"packageName": "com.example.heavy", "count": 5, "services": []},
+            {"packageName": "com.example.light", "count": 1, "services": []},
+        ]},
+        "temperature": {"temperatureC": 44.0, "sensors": [{"name": "skin", "status": 3}]},
+        "notifications": {"byPackage": [
+            {"packageName": "com.shein.app", "posted": 340, "active": 2},
+            {"packageName": "com.whatsapp", "posted": 41, "active": 1},
+        ]},
+    }
+    report = build_performance_report(health, {"window_animation_scale": 1.0, "animator_duration_scale": 1.5})
+    penalties = {f["id"]: f["penalty"] for f in report["factors"]}
+    assert penalties == {"memory": 20, "storage": 0, "services": 15, "wakelocks": 0, "thermal": 14, "animations": 10}
+    assert report["score"] == 41
+
+    actions = [r["action"]["command"] for r in report["recommendations"]]
+    assert actions[0] in ("get_cached_packages", "get_system_health")  # high severity first
+    assert "restrict_background" in actions and "set_animation_scale" in actions
+    assert "trim_caches" not in actions
+    restricted = [r for r in report["recommendations"] if r["action"]["command"] == "restrict_background"]
+    assert [r["action"]["args"]["packageName"] for r in restricted] == ["com.example.heavy"]
+    muted = [r for r in report["recommendations"] if r["action"]["command"] == "mute_notifications"]
+    assert [r["action"]["args"]["packageName"] for r in muted] == ["com.shein.app"]
+
+    # The same load scores worse on battery: services count earlier and wake locks start to matter
+    busy = {"services": {"count": 45, "byPackage": []}, "power": {"charging": True, "wakeLocks": 6}}
+    charging = build_performance_report(busy, {})
+    on_battery = build_performance_report({**busy, "power": {"charging": False, "wakeLocks": 6}}, {})
+    assert charging["score"] == 99 and on_battery["score"] == 89
+    assert "wakelocks" in [r["id"] for r in on_battery["recommendations"]]
+    assert "wakelocks" not in [r["id"] for r in charging["recommendations"]]
+
+    healthy = build_performance_report({}, {"window_animation_scale": 0.5})
+    assert healthy["score"] == 100 and healthy["recommendations"] == []
+
+    print("  └─ ✅ Performance report OK")
+    return True
+
+
+def main():
+    """Run all parser tests"""
+    tests = [test_cpu_parsing, test_cpu_sampling, test_monitor_restart, test_memory_parsing, test_services_parsing, test_notification_parsing,
+             test_storage_parsing, test_thermal_parsing, test_power_parsing, test_performance_report]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
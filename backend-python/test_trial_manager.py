@@ -0,0 +1,98 @@
+"""
+Test trial disables and their automatic re-enable
+Runs against a fake device - no ADB required
+"""
+import sys
+import os
+import tempfile
+import time
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from trial_manager import TrialManager
+
+
+class TrialDevice(FakeADB):
+    """Tracks disabled packages; packages in `broken` fail to toggle"""
+
+    def __init__(self):
+        super().__init__()
+        self.disabled = set()
+        self.broken = set()
+
+    def set_package_enabled(self, package, enabled):
+        if package in self.broken:
+            return {"success": False, "message": "Unknown package"}
+        (self.disabled.discard if enabled else self.disabled.add)(package)
+        return {"success": True, "message": f"{package} {'enabled' if enabled else 'disabled'}"}
+
+
+class FakeUndo:
+    def __init__(self):
+        self.records = []
+
+    def record(self, kind, target, result):
+        self.records.append((kind, target))
+
+
+def test_start_and_keep():
+    """Test that only disabled packages join a trial and keeping leaves them off"""
+    print("\n🧪 Testing trial start and keep...")
+    adb, undo = TrialDevice(), FakeUndo()
+    adb.broken.add("com.missing")
+    with tempfile.TemporaryDirectory() as data_dir:
+        trials = TrialManager(adb, undo, data_dir=data_dir)
+        assert not trials.start_trial(["com.a"], 0)["success"]
+        result = trials.start_trial(["com.a", "com.missing", "com.a"], 2)
+        assert result["success"] and result["trial"]["packages"] == ["com.a"]
+        assert adb.disabled == {"com.a"}
+        trial_id = result["trial"]["id"]
+        assert trials.keep(trial_id)["success"]
+        assert not trials.keep(trial_id)["success"]
+        assert adb.disabled == {"com.a"} and undo.records == [("disable", "com.a")]
+        assert trials.list_trials() == []
+        assert trials.list_trials(include_ended=True)[0]["status"] == "kept"
+    print("  └─ ✅ Start and keep OK")
+    return True
+
+
+def test_expiry():
+    """Test that expired trials are re-enabled, once, and only on their device"""
+    print("\n⏰ Testing expiry...")
+    adb, events = TrialDevice(), []
+    with tempfile.TemporaryDirectory() as data_dir:
+        trials = TrialManager(adb, emit_event=lambda kind, payload, job_id=None: events.append(kind),
+                              data_dir=data_dir)
+        trials.start_trial(["com.a", "com.b"], 1)
+        assert trials.check_expired() == []
+
+        # Another device is connected when the trial expires
+        adb.serial = "SERIAL2"
+        assert trials.check_expired(time.time() + 7200) == []
+        assert adb.disabled == {"com.a", "com.b"}
+
+        # Persisted across a restart and reverted once the device is back
+        adb.serial = "SERIAL1"
+        reverted = TrialManager(adb, emit_event=lambda kind, payload, job_id=None: events.append(kind),
+                                data_dir=data_dir).check_expired(time.time() + 7200)
+        assert len(reverted) == 1 and reverted[0]["trial"]["reason"] == "expired"
+        assert adb.disabled == set() and events == ["trial_reverted"]
+    print("  └─ ✅ Expiry OK")
+    return True
+
+
+def main():
+    """Run all trial manager tests"""
+    tests = [test_start_and_keep, test_expiry]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
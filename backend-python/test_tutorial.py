@@ -0,0 +1,113 @@
+"""
+Test the first-run tutorial state machine
+Runs against a fake device - no ADB required
+"""
+import sys
+import os
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from adb_operations import ADBError
+from tutorial import Tutorial
+
+
+class TutorialDevice(FakeADB):
+    """adb that is missing until `installed`, with the device states `adb devices` reports"""
+
+    def __init__(self):
+        super().__init__()
+        self.installed = False
+        self.states = {}
+
+    def get_adb_version(self):
+        if not self.installed:
+            raise ADBError("ADB not found. Please install Android SDK Platform Tools.")
+        return {"adbVersion": "1.0.41", "platformToolsVersion": "34.0.5"}
+
+    def get_device_states(self):
+        return self.states
+
+    def get_serial(self):
+        if "device" not in self.states.values():
+            raise ADBError("No device connected")
+        return super().get_serial()
+
+
+class FakeCache:
+    def __init__(self):
+        self.serials = set()
+
+    def has_listing(self, serial):
+        return serial in self.serials
+
+
+class FakeUndo:
+    def __init__(self):
+        self.entries = []
+
+    def get_undo_stack(self):
+        return self.entries
+
+
+def test_progress_follows_device():
+    """Test that steps only complete when the device state shows them done"""
+    print("\n🎓 Testing tutorial progress...")
+    adb, cache, undo = TutorialDevice(), FakeCache(), FakeUndo()
+    with tempfile.TemporaryDirectory() as data_dir:
+        tutorial = Tutorial(adb, cache, undo, data_dir)
+        state = tutorial.advance_tutorial()
+        assert state["current"] == "installAdb" and state["advanced"] == 0
+        assert "ADB not found" in state["message"]
+
+        adb.installed = True
+        adb.states = {"R58M123": "unauthorized"}
+        state = tutorial.advance_tutorial()
+        assert state["current"] == "authorize" and state["advanced"] == 2
+        assert state["message"] == "The device has not authorized this computer yet"
+
+        adb.states = {"R58M123": "device"}
+        cache.serials.add("SERIAL1")
+        state = tutorial.advance_tutorial()
+        assert state["current"] == "firstSafeRemoval"
+
+        # Progress survives a restart; completed steps stay done when unplugged
+        adb.states = {}
+        tutorial = Tutorial(adb, cache, undo, data_dir)
+        assert tutorial.get_tutorial_state()["current"] == "firstSafeRemoval"
+        undo.entries.append({"kind": "uninstall", "description": "Uninstalled com.facebook.katana"})
+        state = tutorial.advance_tutorial()
+        assert state["completed"] and state["current"] is None
+        assert [s["status"] for s in state["steps"]] == ["done"] * 5
+    print("  └─ ✅ Tutorial progress OK")
+    return True
+
+
+def test_skip_and_dismiss():
+    """Test skipping a step and hiding the tour"""
+    print("\n⏭️  Testing skip and dismiss...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        tutorial = Tutorial(TutorialDevice(), FakeCache(), FakeUndo(), data_dir)
+        state = tutorial.advance_tutorial(skip=True)
+        assert state["steps"][0]["status"] == "skipped" and state["current"] == "enableUsbDebugging"
+        assert tutorial.dismiss()["dismissed"]
+        assert Tutorial(TutorialDevice(), FakeCache(), FakeUndo(), data_dir).get_tutorial_state()["dismissed"]
+    print("  └─ ✅ Skip and dismiss OK")
+    return True
+
+
+def main():
+    """Run all tutorial tests"""
+    tests = [test_progress_follows_device, test_skip_and_dismiss]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,96 @@
+"""
+Test the undo stack
+Runs against a fake device - no ADB required
+"""
+import sys
+import os
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from undo_manager import UndoStack
+
+
+class UndoDevice(FakeADB):
+    """Records reinstalls and uninstalls; only com.samsung.bloat is a system package"""
+
+    def __init__(self):
+        super().__init__()
+        self.calls = []
+
+    def answer(self, command):
+        return "package:com.samsung.bloat\n" if "com.samsung.bloat" in command else ""
+
+    def reinstall_package(self, package):
+        self.calls.append(("reinstall", package))
+        return {"success": True, "message": f"Successfully reinstalled {package}"}
+
+    def uninstall_package(self, package):
+        self.calls.append(("uninstall", package))
+        return {"success": True, "message": f"Successfully uninstalled {package}"}
+
+
+class FakeTweaks:
+    def __init__(self):
+        self.calls = []
+
+    def restore_animation_scales(self, scales):
+        self.calls.append(("scales", scales))
+        return {"success": True, "message": "Animation scales restored"}
+
+    def set_background_mode(self, package, mode):
+        self.calls.append(("background", package, mode))
+        return {"success": False, "message": "appops failed"}
+
+
+def test_undo_order_and_reversibility():
+    """Test newest-first undo, irreversible reporting and stopping on failure"""
+    print("\n↩️  Testing undo stack...")
+    adb, tweaks = UndoDevice(), FakeTweaks()
+    with tempfile.TemporaryDirectory() as data_dir:
+        stack = UndoStack(adb, tweaks, data_dir)
+        stack.record("background", "com.example.app", {"success": True, "previous": "default"})
+        stack.record("uninstall", "com.samsung.bloat", {"success": True})
+        stack.record("uninstall", "com.example.userapp", {"success": True})
+        stack.record("animation_scale", None, {"success": True, "previous": {"window_animation_scale": 1.0}})
+        stack.record("uninstall", "com.failed", {"success": False})
+
+        entries = stack.get_undo_stack()
+        assert [e["kind"] for e in entries] == ["animation_scale", "uninstall", "uninstall", "background"]
+        assert [e["reversible"] for e in entries] == [True, False, True, True]
+        # Irreversible entries still name their package, for battery trends
+        assert stack.last_change("com.example.userapp")["kind"] == "uninstall"
+        assert stack.last_change("com.failed") is None and stack.last_change("com.example.app") is None
+
+        result = stack.undo(3)
+        assert [r["success"] for r in result["results"]] == [True, False, True]
+        assert result["results"][1]["reversible"] is False
+        assert tweaks.calls == [("scales", {"window_animation_scale": 1.0})]
+        assert adb.calls == [("reinstall", "com.samsung.bloat")]
+
+        # A failing inverse stays on the stack
+        result = stack.undo(5)
+        assert not result["success"]
+        assert [e["kind"] for e in stack.get_undo_stack()] == ["background"]
+
+        # The stack survives a restart
+        assert len(UndoStack(adb, tweaks, data_dir).get_undo_stack()) == 1
+    print("  └─ ✅ Undo stack OK")
+    return True
+
+
+def main():
+    """Run all undo tests"""
+    tests = [test_undo_order_and_reversibility]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,113 @@
+"""
+Test removing packages with or without their data, and whether a
+reinstall gets it back
+Runs against a fake device - no ADB required
+"""
+import sys
+import os
+sys.path.insert(0, os.path.dirname(__file__))
+
+from adb_operations import KEEP_DATA_NOTE, WIPE_DATA_NOTE, InvalidNameError, parse_user_data_inode
+from device_tweaks import DeviceTweaks
+from fake_adb import FakeADB
+from validation import is_overlay_id, is_package_name, is_permission_name
+
+
+def dumpsys(inode):
+    """`dumpsys package` of a package removed for user 0, whose data directory has `inode`"""
+    return (f"Packages:\n  Package [com.facebook.katana] (5d1e2f):\n    userId=10123\n"
+            f"    User 0: ceDataInode={inode} installed=false hidden=false suspended=false\n"
+            f"    User 10: ceDataInode=98765 installed=true hidden=false suspended=false\n")
+
+
+def test_keep_data():
+    """Test that -k is passed only when asked, and the result explains what happened to the data"""
+    print("\n🗑️  Testing keep-data uninstall...")
+    adb = FakeADB(sdk=33)
+    wiped = adb.uninstall_package("com.facebook.katana")
+    assert wiped["success"] and wiped["keepData"] is False and wiped["dataNote"] == WIPE_DATA_NOTE
+    assert adb.runs[-1][-5:] == ["pm", "uninstall", "--user", "0", "com.facebook.katana"]
+
+    kept = adb.uninstall_package("com.facebook.katana", keep_data=True)
+    assert kept["keepData"] is True and kept["dataNote"] == KEEP_DATA_NOTE
+    assert adb.runs[-1][-6:] == ["pm", "uninstall", "-k", "--user", "0", "com.facebook.katana"]
+
+    # Data kept by an earlier -k removal can still be deleted on its own
+    cleared = adb.wipe_package_data("com.facebook.katana")
+    assert cleared == {"success": True, "message": "Deleted app data of com.facebook.katana"}
+    assert adb.runs[-1][-5:] == ["pm", "clear", "--user", "0", "com.facebook.katana"]
+
+    adb.run_output = "Failed"
+    assert adb.wipe_package_data("com.facebook.katana")["message"] == "Failed to delete app data: Failed"
+    print("  └─ ✅ Keep-data uninstall OK")
+    return True
+
+
+def test_data_preserved():
+    """Test that a reinstall reports whether the data of a -k removal survived"""
+    print("\n♻️  Testing reinstall data detection...")
+    assert parse_user_data_inode(dumpsys(4242)) == 4242 and parse_user_data_inode(dumpsys(0), 10) == 98765
+    assert parse_user_data_inode("Packages:\n") is None
+
+    cases = [(dumpsys(4242), True, "Successfully reinstalled com.facebook.katana with its previous data"),
+             (dumpsys(0), False,
+              "Successfully reinstalled com.facebook.katana; its data was deleted, so it starts fresh"),
+             ("", None, "Successfully reinstalled com.facebook.katana")]
+    for output, preserved, message in cases:
+        adb = FakeADB({"dumpsys package com.facebook.katana": output}, sdk=33,
+                      run_output="Package com.facebook.katana installed for user: 0")
+        result = adb.reinstall_package("com.facebook.katana")
+        assert result == {"success": True, "message": message, "dataPreserved": preserved}, result
+        assert "dumpsys package com.facebook.katana" in adb.commands
+        assert adb.runs == [[adb.adb_path, "shell", "cmd", "package", "install-existing", "com.facebook.katana"]]
+    print("  └─ ✅ Reinstall data detection OK")
+    return True
+
+
+def test_invalid_names():
+    """Test that malformed package, permission and overlay names never reach the device shell"""
+    print("\n🛡️  Testing name validation...")
+    assert is_package_name("com.facebook.katana") and is_package_name("com.android.vending_2")
+    for bad in ["com.x; reboot", "com.x\n", "katana", "com..x", "com.9x", "com.x$(id)", "com.ｘ.y", None, "a." * 200]:
+        assert not is_package_name(bad), bad
+    assert is_permission_name("RECORD_AUDIO") and is_permission_name("com.google.android.c2dm.permission.RECEIVE")
+    assert not is_permission_name("RECORD_AUDIO && reboot")
+    assert is_overlay_id("com.android.theme.color.ocean") and is_overlay_id("com.android.shell:accent_color")
+    assert not is_overlay_id("com.android.theme;reboot")
+
+    adb = FakeADB(sdk=33)
+    injected = "com.facebook.katana; reboot"
+    for result in [adb.uninstall_package(injected), adb.uninstall_package(injected, keep_data=True),
+                   adb.wipe_package_data(injected), adb.set_package_enabled(injected, False),
+                   adb.reinstall_package(injected), adb.set_overlay_enabled("com.android.theme;reboot", True),
+                   DeviceTweaks(adb).restrict_background(injected),
+                   DeviceTweaks(adb).set_permission(injected, "RECORD_AUDIO", False),
+                   DeviceTweaks(adb).set_permission("com.facebook.katana", "RECORD_AUDIO;reboot", False)]:
+        assert result["success"] is False, result
+        assert result["message"].startswith(("Invalid", "Not a permission name")), result
+    try:
+        adb.get_apk_paths(injected)
+        assert False, "get_apk_paths accepted a malformed name"
+    except InvalidNameError:
+        pass
+    assert not any("reboot" in command for command in adb.commands), adb.commands
+    assert not any("reboot" in " ".join(run) for run in adb.runs), adb.runs
+    print("  └─ ✅ Name validation OK")
+    return True
+
+
+def main():
+    """Run all uninstall tests"""
+    tests = [test_keep_data, test_data_preserved, test_invalid_names]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,115 @@
+"""
+Test unused user app suggestions: install time parsing, the cutoff and the launcher/keyboard exclusion
+Runs against fakes - no ADB required
+"""
+import sys
+import os
+from datetime import datetime
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from unused_apps import DAY, UnusedApps, find_unused, parse_install_times
+
+
+NOW = datetime(2026, 6, 1, 12, 0, 0).timestamp()
+
+DUMPSYS_PACKAGES = """Packages:
+  Package [com.spotify.music] (5d2f1a3):
+    userId=10172
+    firstInstallTime=2024-01-10 09:30:00
+    lastUpdateTime=2026-05-01 10:00:00
+  Package [com.new.app] (aa01bb2):
+    userId=10201
+    firstInstallTime=2026-05-28 18:00:00
+  Package [com.android.chrome] (77c0de1):
+    userId=10101
+    firstInstallTime=2009-01-01 08:00:00
+"""
+
+LISTING = [
+    {"packageName": "com.spotify.music", "appName": "Spotify", "isSystem": False,
+     "lastUsed": NOW - 400 * DAY, "sizeBytes": 300_000_000},
+    {"packageName": "com.duolingo", "appName": "Duolingo", "isSystem": False,
+     "lastUsed": NOW - 200 * DAY, "sizeBytes": 900_000_000},
+    {"packageName": "com.whatsapp", "appName": "WhatsApp", "isSystem": False,
+     "lastUsed": NOW - 1 * DAY, "sizeBytes": 2_000_000_000},
+    {"packageName": "com.new.app", "appName": "New", "isSystem": False, "lastUsed": None, "sizeBytes": 10_000_000},
+    {"packageName": "com.swiftkey", "appName": "SwiftKey", "isSystem": False, "lastUsed": None, "sizeBytes": 50_000_000},
+    {"packageName": "com.android.chrome", "appName": "Chrome", "isSystem": True, "lastUsed": None, "sizeBytes": 500},
+]
+
+
+def make_adb():
+    """A phone using SwiftKey, answering the package dump otherwise"""
+    return FakeADB({"settings get secure default_input_method": "com.swiftkey/com.touchtype.KeyboardService\n"},
+                   default=DUMPSYS_PACKAGES)
+
+
+class FakeCache:
+    def prefetch_sizes(self):
+        pass
+
+    def prefetch_last_used(self):
+        pass
+
+    def packages(self):
+        return [dict(pkg) for pkg in LISTING]
+
+
+class FakeRestarter:
+    def get_launcher_package(self):
+        return "com.teslacoilsw.launcher"
+
+
+def test_parse_install_times():
+    """Test that install times are read per package block"""
+    print("\n🗓️  Testing install time parsing...")
+    times = parse_install_times(DUMPSYS_PACKAGES)
+    assert set(times) == {"com.spotify.music", "com.new.app", "com.android.chrome"}
+    assert times["com.new.app"] == datetime(2026, 5, 28, 18, 0, 0).timestamp()
+    assert parse_install_times("") == {}
+    print("  └─ ✅ Install time parsing OK")
+    return True
+
+
+def test_find_unused():
+    """Test the cutoff, never-opened apps, exclusions and ordering"""
+    print("\n🧹 Testing unused app detection...")
+    install_times = parse_install_times(DUMPSYS_PACKAGES)
+    install_times["com.swiftkey"] = NOW - 900 * DAY
+    apps = find_unused(LISTING, install_times, 180, now=NOW)
+    # Largest first; WhatsApp was used yesterday, the new app was installed last week, Chrome is a system app
+    assert [a["packageName"] for a in apps] == ["com.duolingo", "com.spotify.music", "com.swiftkey"]
+    assert apps[1]["daysUnused"] == 400 and apps[2]["lastUsed"] is None and apps[2]["daysUnused"] == 900
+    assert [a["packageName"] for a in find_unused(LISTING, install_times, 365, {"com.swiftkey"}, NOW)] == ["com.spotify.music"]
+    print("  └─ ✅ Unused app detection OK")
+    return True
+
+
+def test_suggestions():
+    """Test the summary and that the default keyboard is never suggested"""
+    print("\n💡 Testing suggestions...")
+    result = UnusedApps(make_adb(), FakeCache(), FakeRestarter()).get_suggestions(180, now=NOW)
+    names = [app["packageName"] for app in result["apps"]]
+    assert result["success"] and "com.swiftkey" not in names and "com.duolingo" in names
+    assert result["totalBytes"] == sum(app["sizeBytes"] for app in result["apps"])
+    assert result["message"].startswith(f"You haven't opened these {result['count']} apps in 6 months - reclaim")
+    print(f"  └─ ✅ {result['message']}")
+    return True
+
+
+def main():
+    """Run all unused app tests"""
+    tests = [test_parse_install_times, test_find_unused, test_suggestions]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
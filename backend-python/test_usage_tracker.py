@@ -0,0 +1,78 @@
+"""
+Test the AI usage tracker and monthly budget
+Runs against a temporary data directory - no network or API key required
+"""
+import sys
+import os
+import tempfile
+sys.path.insert(0, os.path.dirname(__file__))
+
+import network
+from ai_advisor import AIAdvisor
+from usage_tracker import AIUsageTracker, BudgetExceededError
+
+
+def test_set_budget():
+    """Test that only a dollar amount in range, or None, is accepted as a budget"""
+    print("\n💵 Testing budget validation...")
+    with tempfile.TemporaryDirectory() as tmp:
+        usage = AIUsageTracker(tmp)
+        for bad in ("5", True, -1, float("nan"), float("inf"), 10 ** 9, [5]):
+            result = usage.set_budget(bad)
+            assert result["success"] is False, bad
+        assert usage.get_stats()["monthlyBudgetUsd"] is None
+
+        assert usage.set_budget(5)["success"] and usage.set_budget(0.5)["success"]
+        assert AIUsageTracker(tmp).get_stats()["monthlyBudgetUsd"] == 0.5
+        assert usage.set_budget(None)["message"] == "Monthly AI budget removed"
+    print("  └─ ✅ Budget validation OK")
+    return True
+
+
+def test_check_budget():
+    """Test that a spent budget stops analysis and chat before any request is sent"""
+    print("\n🛑 Testing budget enforcement...")
+    with tempfile.TemporaryDirectory() as tmp:
+        usage = AIUsageTracker(tmp)
+        usage.set_budget(0.01)
+        usage.check_budget()  # Nothing spent yet
+        usage.record("perplexity", "sonar", {"prompt_tokens": 1000, "cost": {"total_cost": 0.02}})
+        try:
+            usage.check_budget()
+            assert False, "spent budget was not enforced"
+        except BudgetExceededError as e:
+            assert "$0.01" in str(e)
+
+        advisor = AIAdvisor()
+        advisor.api_key = "test-key"
+        advisor.usage = usage
+        post = network.post
+        network.post = lambda *args, **kwargs: (_ for _ in ()).throw(AssertionError("request was sent"))
+        try:
+            analysis = advisor.analyze_package("com.facebook.katana")
+            assert analysis["error"].startswith("Monthly AI budget") and analysis["safetyLevel"] == "unknown"
+            assert advisor.chat("Is com.facebook.katana safe to remove?")["content"].startswith("Monthly AI budget")
+        finally:
+            network.post = post
+
+        usage.set_budget(None)
+        usage.check_budget()
+    print("  └─ ✅ Budget enforcement OK")
+    return True
+
+
+def main():
+    """Run all usage tracker tests"""
+    tests = [test_set_budget, test_check_budget]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
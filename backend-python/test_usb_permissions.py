@@ -0,0 +1,189 @@
+"""
+Test Linux USB permission detection: the "no permissions" adb error, vendor
+lookup in sysfs, distro-aware guidance and writing the udev rule
+Runs against a fake sysfs tree and a fake password prompt - no ADB or root required
+"""
+import sys
+import os
+import tempfile
+from pathlib import Path
+from types import SimpleNamespace
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+import adb_operations
+import usb_permissions
+from adb_operations import ADBOperations, DevicePermissionError, parse_devices
+from usb_permissions import UsbPermissions, distro_family, parse_os_release, udev_rule, vendor_for
+
+
+NO_PERMISSIONS = ("List of devices attached\n"
+                  "R58M12345AB\tno permissions (user in plugdev group; are your udev rules wrong?); "
+                  "see [http://developer.android.com/tools/device.html] usb:1-2 transport_id:3\n")
+
+UBUNTU = 'NAME="Ubuntu"\nID=ubuntu\nID_LIKE=debian\nPRETTY_NAME="Ubuntu 24.04.1 LTS"\n'
+
+
+class StatesDevice(FakeADB):
+    """Reports `states` from adb devices"""
+
+    def __init__(self, states):
+        super().__init__()
+        self.states = states
+
+    def get_device_states(self):
+        return self.states
+
+
+def make_sysfs(root, devices):
+    for name, files in devices.items():
+        folder = Path(root) / name
+        folder.mkdir(parents=True)
+        for key, value in files.items():
+            (folder / key).write_text(value + "\n")
+
+
+def test_no_permissions_error():
+    """Test that adb's no permissions output becomes DevicePermissionError"""
+    print("\n🔒 Testing the no permissions error...")
+    assert parse_devices(NO_PERMISSIONS) == [{"serial": "R58M12345AB", "state": "no permissions"}]
+
+    outputs = []
+    original_run = adb_operations.subprocess.run
+
+    def fake_run(command, **kwargs):
+        return outputs.pop(0)
+
+    adb_operations.subprocess.run = fake_run
+    try:
+        adb = ADBOperations()
+        outputs.append(SimpleNamespace(returncode=0, stdout=NO_PERMISSIONS, stderr=""))
+        try:
+            adb.get_device_info()
+            assert False, "a blocked device was reported as connected"
+        except DevicePermissionError as e:
+            assert "udev" in str(e)
+
+        stderr = "error: insufficient permissions for device: user in plugdev group; are your udev rules wrong?"
+        outputs.append(SimpleNamespace(returncode=1, stdout="", stderr=stderr))
+        try:
+            adb.shell("getprop ro.product.model")
+            assert False, "the permission error was not raised"
+        except DevicePermissionError:
+            pass
+    finally:
+        adb_operations.subprocess.run = original_run
+    print("  └─ ✅ No permissions detected")
+    return True
+
+
+def test_distro_and_vendor():
+    """Test distribution families, vendor lookup and the rule line"""
+    print("\n🐧 Testing distro and vendor detection...")
+    assert distro_family(parse_os_release(UBUNTU)) == "debian"
+    assert distro_family(parse_os_release("ID=fedora\n")) == "fedora"
+    assert distro_family(parse_os_release("ID=endeavouros\nID_LIKE=arch\n")) == "arch"
+    assert distro_family(parse_os_release('ID="opensuse-tumbleweed"\n')) == "suse"
+    assert distro_family(parse_os_release("ID=nixos\n")) == "other"
+
+    usb = [{"vendorId": "1d6b", "serial": "0000:00:14.0"}, {"vendorId": "04e8", "serial": "R58M12345AB"},
+           {"vendorId": "18d1", "serial": "8A1X0ZZZZ"}]
+    assert vendor_for(["R58M12345AB"], usb) == "04e8"
+    assert vendor_for(["????????????"], usb) is None, "two Android makers plugged in: ambiguous"
+    assert vendor_for(["????????????"], usb[:2]) == "04e8"
+    assert udev_rule("04e8", "plugdev") == ('SUBSYSTEM=="usb", ATTR{idVendor}=="04e8", MODE="0660", '
+                                            'GROUP="plugdev", TAG+="uaccess"')
+    assert 'GROUP' not in udev_rule("04e8", None)
+    print("  └─ ✅ Detection OK")
+    return True
+
+
+def test_guidance():
+    """Test the help returned for a blocked device, and nothing elsewhere"""
+    print("\n🧭 Testing guidance...")
+    with tempfile.TemporaryDirectory() as tmp:
+        make_sysfs(f"{tmp}/usb", {"1-2": {"idVendor": "04e8", "idProduct": "6860", "serial": "R58M12345AB"}})
+        Path(f"{tmp}/os-release").write_text(UBUNTU)
+        usb = UsbPermissions(StatesDevice({"R58M12345AB": "no permissions"}), platform="linux",
+                             sys_root=f"{tmp}/usb", os_release_path=f"{tmp}/os-release",
+                             rules_path=f"{tmp}/51-android.rules")
+        help_info = usb.check()
+        assert help_info["needed"] and help_info["vendorId"] == "04e8" and help_info["vendorName"] == "Samsung"
+        assert help_info["distro"] == "Ubuntu 24.04.1 LTS" and help_info["family"] == "debian"
+        assert "apt install android-sdk-platform-tools-common" in help_info["steps"][0]
+        assert help_info["rule"] in help_info["steps"][1] and help_info["ruleInstalled"] is False
+
+        assert UsbPermissions(StatesDevice({"R58M12345AB": "device"}), platform="linux").check()["needed"] is False
+        assert UsbPermissions(StatesDevice({"R58M12345AB": "no permissions"}), platform="win32").check() == {
+            "needed": False, "platform": "win32"}
+    print("  └─ ✅ Guidance OK")
+    return True
+
+
+def test_install_rule():
+    """Test writing the rule through the password prompt, a cancelled prompt and a second install"""
+    print("\n📝 Testing rule installation...")
+    with tempfile.TemporaryDirectory() as tmp:
+        rules = Path(tmp) / "51-android.rules"
+        calls = []
+
+        def fake_run(command, **kwargs):
+            calls.append(command)
+            if command[:2] == ["sudo", "-n"] and command[2] == "sh":
+                staged, target = command[-2], command[-1]
+                Path(target).write_text(Path(staged).read_text())
+            return SimpleNamespace(returncode=0, stdout="", stderr="")
+
+        Path(f"{tmp}/os-release").write_text("ID=nixos\n")
+        usb = UsbPermissions(StatesDevice({}), platform="linux", sys_root=f"{tmp}/usb",
+                             os_release_path=f"{tmp}/os-release", rules_path=str(rules), run=fake_run)
+        saved_env = {key: os.environ.pop(key, None) for key in ("DISPLAY", "WAYLAND_DISPLAY")}
+        original_which = usb_permissions.shutil.which
+        # No graphical session: only passwordless sudo is available
+        usb_permissions.shutil.which = lambda name: "/usr/bin/sudo" if name == "sudo" else None
+        try:
+            assert usb.install_rule("not-a-vendor")["success"] is False
+            assert usb.install_rule("04e8\n")["success"] is False, "a newline would end the rule line"
+            result = usb.install_rule("04E8")
+            assert result["success"] and not result["alreadyInstalled"], result
+            assert 'ATTR{idVendor}=="04e8"' in rules.read_text()
+            assert "udevadm control --reload-rules" in calls[-1][4]
+
+            result = usb.install_rule("18d1")
+            text = rules.read_text()
+            assert result["success"] and '"04e8"' in text and '"18d1"' in text, "earlier rules are kept"
+            assert usb.install_rule("04e8")["alreadyInstalled"] is True
+
+            def cancelled(command, **kwargs):
+                return SimpleNamespace(returncode=126, stdout="", stderr="")
+
+            usb.run = cancelled
+            usb_permissions.shutil.which = lambda name: "/usr/bin/" + name
+            os.environ["DISPLAY"] = ":0"
+            result = usb.install_rule("2717")
+            assert result["success"] is False and "cancelled" in result["message"]
+        finally:
+            usb_permissions.shutil.which = original_which
+            for key, value in saved_env.items():
+                os.environ.pop(key, None)
+                if value is not None:
+                    os.environ[key] = value
+    print("  └─ ✅ Rule installation OK")
+    return True
+
+
+def main():
+    """Run all USB permission tests"""
+    tests = [test_no_permissions_error, test_distro_and_vendor, test_guidance, test_install_rule]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,181 @@
+"""
+Test daily snapshots and the weekly digest built from them
+Runs against canned device output - no ADB required
+"""
+import sys
+import os
+import tempfile
+import time
+sys.path.insert(0, os.path.dirname(__file__))
+
+from fake_adb import FakeADB
+from weekly_digest import (DAY, POWER_USE_COMMAND, PACKAGE_UIDS_COMMAND, WeeklyDigest, battery_trend, build_digest,
+                           package_power, parse_battery_hours, parse_package_uids, parse_power_use, top_drainers)
+
+
+BATTERYSTATS = """Statistics since last charge:
+  Time on battery: 5h 0m 0s 0ms (80.0%) realtime, 4h 10m 3s 12ms uptime
+  Estimated power use (mAh):
+    Capacity: 4000, Computed drain: 812, actual drain: 760-800
+    Screen: 301
+    UID u0a172: 120.5 ( cpu=80.1 wifi=40.4 )
+    UID u0a55: 30.2 ( cpu=30.2 )
+    Uid 1000: 90.0 ( cpu=90.0 )
+    UID u10a172: 12.0 ( cpu=12.0 )
+
+  All kernel wake locks:
+    UID u0a99: 999.0
+"""
+
+PACKAGE_UIDS = """package:com.facebook.katana uid:10172
+package:com.google.android.gms uid:10055
+package:com.google.android.gsf uid:10055
+"""
+
+
+class FakeHealth:
+    def __init__(self):
+        self.used = 20_000_000
+
+    def get_storage_info(self):
+        return {"internal": {"usedKb": self.used, "availableKb": 30_000_000}}
+
+
+class DigestDevice(FakeADB):
+    """Answers package lists and batterystats with canned output"""
+
+    def __init__(self):
+        super().__init__({POWER_USE_COMMAND: BATTERYSTATS, PACKAGE_UIDS_COMMAND: PACKAGE_UIDS})
+        self.packages = ["com.android.settings"]
+
+    def answer(self, command):
+        if command == "pm list packages":
+            return "".join(f"package:{p}\n" for p in self.packages)
+        return super().answer(command)
+
+
+def test_parse():
+    """Test batterystats and package uid parsing"""
+    print("\n🔋 Testing battery use parsing...")
+    usage = parse_power_use(BATTERYSTATS)
+    assert usage == {10172: 120.5, 10055: 30.2}  # System uid, other users and other sections ignored
+    uids = parse_package_uids(PACKAGE_UIDS)
+    assert uids[10055] == ["com.google.android.gms", "com.google.android.gsf"]
+    assert top_drainers(usage, uids) == [{"packageName": "com.facebook.katana", "mAh": 120.5},
+                                         {"packageName": "com.google.android.gms", "mAh": 30.2}]
+    assert parse_power_use("") == {}
+    assert package_power(usage, uids) == {"com.facebook.katana": 120.5, "com.google.android.gms": 30.2,
+                                          "com.google.android.gsf": 30.2}
+    assert parse_battery_hours(BATTERYSTATS) == 5.0 and parse_battery_hours("") is None
+    print("  └─ ✅ Battery use parsing OK")
+    return True
+
+
+def test_build_digest():
+    """Test the storage trend, new apps, drainer changes and suggestions"""
+    print("\n📅 Testing digest building...")
+    now = 100 * DAY
+    snapshots = [
+        {"timestamp": now - 9 * DAY, "storageUsedKb": 10_000_000, "storageAvailableKb": 5_000_000,
+         "packages": ["a.b.old"], "drainers": [{"packageName": "a.b.old", "mAh": 50.0}]},
+        {"timestamp": now - 7 * DAY - 1, "storageUsedKb": 10_000_000, "storageAvailableKb": 5_000_000,
+         "packages": ["a.b.old", "a.b.gone"], "drainers": [{"packageName": "a.b.old", "mAh": 50.0}]},
+        {"timestamp": now - DAY, "storageUsedKb": 11_400_000, "storageAvailableKb": 3_600_000,
+         "packages": ["a.b.old", "com.facebook.katana"],
+         "drainers": [{"packageName": "com.facebook.katana", "mAh": 120.5}]},
+    ]
+    digest = build_digest(snapshots, lambda p: "Safe", now)
+    assert digest["periodStart"] == now - 7 * DAY - 1  # Compared with the last snapshot before the week
+    assert digest["storage"]["changeKb"] == 1_400_000 and digest["storage"]["perDayKb"] == 233_333
+    assert digest["newApps"] == [{"packageName": "com.facebook.katana", "safetyLevel": "Safe"}]
+    assert digest["removedApps"] == ["a.b.gone"]
+    assert [d["packageName"] for d in digest["newDrainers"]] == ["com.facebook.katana"]
+    assert digest["goneDrainers"] == ["a.b.old"]
+    assert digest["suggestions"][0].startswith("Storage use grew by") and "full in about 15 days" in digest["suggestions"][0]
+    assert "1 of them are rated Safe" in digest["suggestions"][1]
+    assert digest["suggestions"][2].startswith("com.facebook.katana is newly among the top battery users")
+    assert build_digest(snapshots[:1], lambda p: "Safe", now) is None
+    print("  └─ ✅ Digest building OK")
+    return True
+
+
+def test_schedule():
+    """Test one snapshot a day and one digest (with its event) a week"""
+    print("\n⏰ Testing the digest schedule...")
+    with tempfile.TemporaryDirectory() as data_dir:
+        adb, health, events = DigestDevice(), FakeHealth(), []
+        digests = WeeklyDigest(adb, health, lambda kind, payload, job_id=None: events.append((kind, payload)), data_dir)
+        start = time.time() - 8 * DAY
+        assert digests.check(start) is None
+        assert digests.check(start + 3600) is None  # Same day: no second snapshot
+        assert len(digests._load("SERIAL1")["snapshots"]) == 1
+
+        adb.packages.append("com.facebook.katana")
+        health.used += 1_000_000
+        for day in range(1, 7):
+            assert digests.check(start + day * DAY) is None
+        digest = digests.check(start + 7 * DAY)
+        assert digest is not None and digest["newApps"][0]["packageName"] == "com.facebook.katana"
+        assert events[0][0] == "weekly_digest_ready" and events[0][1]["serial"] == "SERIAL1"
+        assert digests.check(start + 7 * DAY + 3600) is None  # Next digest is a week away
+        assert len(digests._load("SERIAL1")["snapshots"]) == 8
+
+        # A day later: today's snapshot is taken, the stored digest is returned
+        result = WeeklyDigest(adb, health, data_dir=data_dir).get_weekly_digest()
+        assert result["success"] and not result["preview"] and result["digest"]["generatedAt"] == start + 7 * DAY
+    print("  └─ ✅ Digest schedule OK")
+    return True
+
+
+def test_battery_trend():
+    """Test the per-package drain trend around a disable, and snapshots without battery figures"""
+    print("\n📉 Testing battery trends...")
+    package = "com.facebook.katana"
+    snapshots = [
+        {"timestamp": 1 * DAY, "packages": [package], "power": {package: 60.0}, "batteryHours": 10.0},
+        {"timestamp": 2 * DAY, "packages": [package], "power": {package: 100.0}, "batteryHours": 20.0},
+        {"timestamp": 3 * DAY, "packages": [package], "power": None, "batteryHours": None},
+        {"timestamp": 4 * DAY, "packages": [package], "power": {package: 12.0}, "batteryHours": 12.0},
+        {"timestamp": 5 * DAY, "packages": [], "power": {}, "batteryHours": 8.0},
+        {"timestamp": 6 * DAY, "packages": ["a.b.c"], "drainers": []},  # before per-package use was kept
+    ]
+    trend = battery_trend(snapshots, package, 0, {"kind": "disable", "timestamp": 3 * DAY})
+    assert [p["mAhPerHour"] for p in trend["points"]] == [6.0, 5.0, 1.0, 0.0]
+    assert trend["points"][-1]["installed"] is False
+    assert trend["beforeMAhPerHour"] == 5.5 and trend["afterMAhPerHour"] == 0.5 and trend["changePercent"] == -91
+    assert trend["message"] == ("Since com.facebook.katana was disabled its drain went down from 5.5 to "
+                                "0.5 mAh per hour on battery (-91%)")
+
+    assert battery_trend(snapshots, package, 2 * DAY, None)["message"] == (
+        "com.facebook.katana averaged 2.0 mAh per hour on battery")
+    pending = battery_trend(snapshots[:2], package, 0, {"kind": "uninstall", "timestamp": 3 * DAY})
+    assert pending["afterMAhPerHour"] is None and "uninstalled; the next daily snapshot" in pending["message"]
+    assert "No snapshot from before" in battery_trend(snapshots, package, 4 * DAY,
+                                                      {"kind": "disable", "timestamp": 3 * DAY})["message"]
+    assert battery_trend([], package, 0)["points"] == []
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        digests = WeeklyDigest(DigestDevice(), FakeHealth(), data_dir=data_dir)
+        result = digests.get_battery_trend(package, 7)
+        assert result["success"] and result["days"] == 7
+        assert result["points"][0]["mAh"] == 120.5 and result["points"][0]["mAhPerHour"] == 24.1
+        assert digests.get_battery_trend(package, 90)["success"] is False
+    print("  └─ ✅ Battery trends OK")
+    return True
+
+
+def main():
+    """Run all weekly digest tests"""
+    tests = [test_parse, test_build_digest, test_schedule, test_battery_trend]
+    failed = 0
+    for test in tests:
+        try:
+            test()
+        except AssertionError as e:
+            failed += 1
+            print(f"  └─ ❌ {test.__name__} failed: {e}")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,183 @@
+"""
+Trial Manager Module
+"Trial disable": packages are disabled for a limited time and re-enabled
+automatically unless the user confirms they can stay off - a low-risk way
+to find out whether a package is needed. Trials are persisted, so one that
+expires while the app is closed or the device is unplugged is reverted the
+next time the device is connected.
+"""
+import json
+import threading
+import time
+import uuid
+from pathlib import Path
+from typing import Dict, List, Optional
+from adb_operations import ADBOperations, ADBError
+from events import EmitFn, no_emit
+
+
+DEFAULT_TRIAL_HOURS = 24
+MIN_TRIAL_HOURS = 0.25
+MAX_TRIAL_HOURS = 24 * 14
+
+# Seconds between checks for expired trials
+CHECK_INTERVAL = 60
+
+# active: packages disabled, waiting for a decision; kept: the user confirmed
+# they can stay disabled; reverted: re-enabled (expired or a problem was reported)
+TRIAL_STATUSES = ("active", "kept", "reverted")
+
+
+class TrialManager:
+    """Disable packages on trial and re-enable them when the trial runs out"""
+
+    def __init__(self, adb_operations: ADBOperations, undo=None, emit_event: EmitFn = None, data_dir: str = None):
+        self.adb = adb_operations
+        self.undo = undo
+        self.emit_event = emit_event or no_emit
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.path = Path(data_dir) / "trials.json"
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        self._lock = threading.Lock()
+        self._stop = threading.Event()
+        try:
+            with open(self.path, 'r', encoding='utf-8') as f:
+                self._trials: List[Dict] = json.load(f)
+        except (OSError, json.JSONDecodeError):
+            self._trials = []
+
+    def _save(self):
+        with open(self.path, 'w', encoding='utf-8') as f:
+            json.dump(self._trials, f, indent=2)
+
+    def _serial(self) -> Optional[str]:
+        try:
+            return self.adb.get_serial()
+        except ADBError:
+            return None
+
+    def _find(self, trial_id: str) -> Optional[Dict]:
+        return next((t for t in self._trials if t["id"] == trial_id), None)
+
+    def start_trial(self, package_names: List[str], duration_hours: float = DEFAULT_TRIAL_HOURS) -> Dict:
+        """Disable packages for `duration_hours`; only those that could be disabled join the trial"""
+        try:
+            duration_hours = float(duration_hours)
+        except (TypeError, ValueError):
+            return {"success": False, "message": "Trial duration must be a number of hours"}
+        if not MIN_TRIAL_HOURS <= duration_hours <= MAX_TRIAL_HOURS:
+            return {"success": False,
+                    "message": f"Trial duration must be between {MIN_TRIAL_HOURS:g} and {MAX_TRIAL_HOURS} hours"}
+        if not package_names:
+            return {"success": False, "message": "No packages selected"}
+        serial = self._serial()
+        if serial is None:
+            return {"success": False, "message": "No device connected"}
+
+        results = []
+        for package in dict.fromkeys(package_names):
+            result = self.adb.set_package_enabled(package, False)
+            results.append({"packageName": package, "success": result["success"], "message": result["message"]})
+        disabled = [r["packageName"] for r in results if r["success"]]
+        if not disabled:
+            return {"success": False, "message": "No package could be disabled", "results": results}
+
+        now = time.time()
+        trial = {
+            "id": uuid.uuid4().hex[:12],
+            "serial": serial,
+            "packages": disabled,
+            "startedAt": now,
+            "expiresAt": now + duration_hours * 3600,
+            "status": "active",
+            "endedAt": None,
+            "reason": None,
+        }
+        with self._lock:
+            self._trials.append(trial)
+            self._save()
+        return {
+            "success": True,
+            "message": f"Disabled {len(disabled)} package(s) on trial for {duration_hours:g} h",
+            "trial": trial,
+            "results": results,
+        }
+
+    def list_trials(self, include_ended: bool = False) -> List[Dict]:
+        """Trials of the connected device, newest first"""
+        serial = self._serial()
+        with self._lock:
+            return [dict(t) for t in reversed(self._trials)
+                    if t["serial"] == serial and (include_ended or t["status"] == "active")]
+
+    def keep(self, trial_id: str) -> Dict:
+        """The packages are not missed: end the trial and leave them disabled"""
+        with self._lock:
+            trial = self._find(trial_id)
+            if trial is None or trial["status"] != "active":
+                return {"success": False, "message": "No such active trial"}
+            trial.update(status="kept", endedAt=time.time())
+            self._save()
+        for package in trial["packages"]:
+            if self.undo:
+                self.undo.record("disable", package, {"success": True})
+        return {"success": True, "message": f"{len(trial['packages'])} package(s) stay disabled", "trial": dict(trial)}
+
+    def revert(self, trial_id: str, reason: str = "problem") -> Dict:
+        """Re-enable the packages of a trial now, e.g. because something stopped working"""
+        with self._lock:
+            trial = self._find(trial_id)
+            if trial is None or trial["status"] != "active":
+                return {"success": False, "message": "No such active trial"}
+            if trial["serial"] != self._serial():
+                return {"success": False, "message": "The trial's device is not connected"}
+            results = []
+            for package in trial["packages"]:
+                result = self.adb.set_package_enabled(package, True)
+                results.append({"packageName": package, "success": result["success"], "message": result["message"]})
+            # Packages that failed to re-enable keep the trial active, so the next check retries
+            if all(r["success"] for r in results):
+                trial.update(status="reverted", endedAt=time.time(), reason=reason)
+                self._save()
+        enabled = sum(1 for r in results if r["success"])
+        return {
+            "success": enabled == len(results),
+            "message": f"Re-enabled {enabled} of {len(results)} package(s)",
+            "trial": dict(trial),
+            "results": results,
+        }
+
+    def check_expired(self, now: float = None) -> List[Dict]:
+        """Revert the connected device's expired trials; returns the revert results"""
+        now = time.time() if now is None else now
+        serial = self._serial()
+        if serial is None:
+            return []
+        with self._lock:
+            expired = [t["id"] for t in self._trials
+                       if t["serial"] == serial and t["status"] == "active" and t["expiresAt"] <= now]
+        reverted = []
+        for trial_id in expired:
+            result = self.revert(trial_id, reason="expired")
+            self.emit_event("trial_reverted", result, trial_id)
+            reverted.append(result)
+        return reverted
+
+    def start_scheduler(self, interval_seconds: float = CHECK_INTERVAL):
+        """Check for expired trials now and then periodically in a background thread"""
+        self._stop.clear()
+
+        def run():
+            while True:
+                try:
+                    self.check_expired()
+                except ADBError:
+                    pass  # Device went away; retried on the next check
+                if self._stop.wait(interval_seconds):
+                    break
+
+        threading.Thread(target=run, daemon=True).start()
+
+    def stop_scheduler(self):
+        self._stop.set()
@@ -0,0 +1,168 @@
+"""
+Tutorial Module
+First-run guided tour as a state machine: install adb → enable USB
+debugging → authorize the computer → first scan → first safe removal.
+A step only completes when the device shows it is really done, so the
+tour follows the user's actual progress instead of button clicks
+"""
+import json
+import threading
+import time
+from pathlib import Path
+from typing import Dict, List, Optional
+from adb_operations import ADBOperations, ADBError
+
+
+# (id, title, hint shown while the step is current)
+TUTORIAL_STEPS = [
+    ("installAdb", "Install adb",
+     "Install Android SDK Platform Tools and make sure `adb` is on your PATH"),
+    ("enableUsbDebugging", "Enable USB debugging",
+     "On the phone, tap Build number seven times in About phone, then turn on Developer options → USB debugging "
+     "and connect the cable"),
+    ("authorize", "Authorize this computer",
+     "Unlock the phone and tap Allow on the \"Allow USB debugging?\" prompt"),
+    ("firstScan", "Scan your apps",
+     "Open the package list to scan the apps installed on the device"),
+    ("firstSafeRemoval", "Remove a safe app",
+     "Pick a package rated Safe and uninstall it; it can be restored at any time"),
+]
+
+STEP_IDS = [step_id for step_id, _, _ in TUTORIAL_STEPS]
+
+
+class Tutorial:
+    """Track the guided tour, validating each step against the device"""
+
+    def __init__(self, adb_operations: ADBOperations, package_cache, undo, data_dir: str = None):
+        self.adb = adb_operations
+        self.package_cache = package_cache
+        self.undo = undo
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.path = Path(data_dir) / "tutorial.json"
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        self._lock = threading.Lock()
+        try:
+            with open(self.path, 'r', encoding='utf-8') as f:
+                self._state = json.load(f)
+        except (OSError, json.JSONDecodeError):
+            self._state = {}
+        self._state.setdefault("completed", {})  # step id -> completion time
+        self._state.setdefault("skipped", [])
+        self._state.setdefault("dismissed", False)
+
+    def _save(self):
+        with open(self.path, 'w', encoding='utf-8') as f:
+            json.dump(self._state, f, indent=2)
+
+    # ===== Step checks: None when done, else why not =====
+
+    def _adb_installed(self) -> Optional[str]:
+        try:
+            self.adb.get_adb_version()
+        except ADBError as e:
+            return str(e)
+        return None
+
+    def _device_states(self) -> Dict[str, str]:
+        try:
+            return self.adb.get_device_states()
+        except ADBError:
+            return {}
+
+    def _debugging_enabled(self) -> Optional[str]:
+        # An unauthorized device already has USB debugging on
+        return None if self._device_states() else "No device with USB debugging is connected"
+
+    def _authorized(self) -> Optional[str]:
+        states = set(self._device_states().values())
+        if "device" in states:
+            return None
+        if "unauthorized" in states:
+            return "The device has not authorized this computer yet"
+        return "No authorized device is connected"
+
+    def _scanned(self) -> Optional[str]:
+        try:
+            serial = self.adb.get_serial()
+        except ADBError as e:
+            return str(e)
+        return None if self.package_cache.has_listing(serial) else "The device's apps have not been scanned yet"
+
+    def _app_removed(self) -> Optional[str]:
+        removed = [e for e in self.undo.get_undo_stack() if e["kind"] in ("uninstall", "disable")]
+        return None if removed else "No app has been removed from this device yet"
+
+    def _check(self, step_id: str) -> Optional[str]:
+        return {
+            "installAdb": self._adb_installed,
+            "enableUsbDebugging": self._debugging_enabled,
+            "authorize": self._authorized,
+            "firstScan": self._scanned,
+            "firstSafeRemoval": self._app_removed,
+        }[step_id]()
+
+    # ===== State =====
+
+    def _current(self) -> Optional[str]:
+        done = set(self._state["completed"]) | set(self._state["skipped"])
+        return next((step_id for step_id in STEP_IDS if step_id not in done), None)
+
+    def get_tutorial_state(self) -> Dict:
+        """Steps with their status and the current step (None once the tour is over)"""
+        with self._lock:
+            current = self._current()
+            steps: List[Dict] = []
+            for step_id, title, hint in TUTORIAL_STEPS:
+                if step_id in self._state["completed"]:
+                    status = "done"
+                elif step_id in self._state["skipped"]:
+                    status = "skipped"
+                elif step_id == current:
+                    status = "current"
+                else:
+                    status = "pending"
+                steps.append({"id": step_id, "title": title, "hint": hint, "status": status})
+            return {
+                "steps": steps,
+                "current": current,
+                "completed": current is None,
+                "dismissed": self._state["dismissed"],
+            }
+
+    def advance_tutorial(self, skip: bool = False) -> Dict:
+        """
+        Complete the current step if the device shows it is done, moving on
+        as far as the device state allows. `skip` passes the current step
+        without checking it. `advanced` counts the steps completed and
+        `message` says why the current step is not done yet.
+        """
+        message = None
+        advanced = 0
+        with self._lock:
+            while True:
+                current = self._current()
+                if current is None:
+                    break
+                if skip:
+                    self._state["skipped"].append(current)
+                    skip = False
+                    continue
+                message = self._check(current)
+                if message:
+                    break
+                self._state["completed"][current] = time.time()
+                advanced += 1
+            self._save()
+        state = self.get_tutorial_state()
+        state["advanced"] = advanced
+        state["message"] = message
+        return state
+
+    def dismiss(self, dismissed: bool = True) -> Dict:
+        """Hide the tour (or show it again) without losing progress"""
+        with self._lock:
+            self._state["dismissed"] = bool(dismissed)
+            self._save()
+        return self.get_tutorial_state()
@@ -0,0 +1,219 @@
+"""
+Undo Manager Module
+A persisted, per-device undo stack over every mutating operation
+(uninstalls, reinstalls, disables, tweaks, app-op changes)
+"""
+import json
+import threading
+import time
+import uuid
+from pathlib import Path
+from typing import Callable, Dict, List, Optional
+from adb_operations import ADBOperations, ADBError, require_package_name
+
+
+# Oldest entries are dropped beyond this many
+MAX_UNDO_ENTRIES = 200
+
+# Inverse actions that take packages away, by the confirmation policy operation they fall under
+REMOVING_INVERSES = {"uninstall": "uninstall", "disable": "disable"}
+
+
+class UndoStack:
+    """Record inverse actions and replay them newest-first"""
+
+    def __init__(self, adb_operations: ADBOperations, tweaks, data_dir: str = None):
+        self.adb = adb_operations
+        self.tweaks = tweaks
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.path = Path(data_dir) / "undo.json"
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        self._lock = threading.Lock()
+        self._entries = self._load()
+
+    def _load(self) -> List[Dict]:
+        try:
+            with open(self.path, 'r', encoding='utf-8') as f:
+                entries = json.load(f)
+        except (OSError, json.JSONDecodeError):
+            return []
+        return entries if isinstance(entries, list) else []
+
+    def _save(self):
+        with open(self.path, 'w', encoding='utf-8') as f:
+            json.dump(self._entries, f, indent=2)
+
+    def _serial(self) -> Optional[str]:
+        try:
+            return self.adb.get_serial()
+        except ADBError:
+            return None
+
+    def _push(self, kind: str, description: str, inverse: Optional[Dict], reason: str = None, target: str = None):
+        entry = {
+            "id": uuid.uuid4().hex[:12],
+            "serial": self._serial(),
+            "kind": kind,
+            "target": target,
+            "description": description,
+            "timestamp": time.time(),
+            "reversible": inverse is not None,
+            "inverse": inverse,
+            "reason": reason,
+        }
+        with self._lock:
+            self._entries.append(entry)
+            del self._entries[:-MAX_UNDO_ENTRIES]
+            self._save()
+
+    # ===== Recording =====
+
+    def record(self, kind: str, target: str, result: Dict):
+        """
+        Record a successful operation from its result.
+
+        kind: "uninstall" | "reinstall" | "disable" | "enable" | "animation_scale" | "background" |
+              "notifications" | "permission" | "trim_caches" | "wipe_data" | "overlay_enable" |
+              "overlay_disable"
+        """
+        if not result.get("success"):
+            return
+
+        if kind == "uninstall":
+            if self._is_system_package(target):
+                self._push(kind, f"Uninstalled {target}", {"action": "reinstall", "packageName": target},
+                           target=target)
+            else:
+                # `pm uninstall --user 0` deletes the APK of a user app installed for one user only
+                self._push(kind, f"Uninstalled {target}", None,
+                           "User-installed app was fully removed; reinstall it from the store", target=target)
+        elif kind == "reinstall":
+            self._push(kind, f"Reinstalled {target}", {"action": "uninstall", "packageName": target}, target=target)
+        elif kind in ("disable", "enable"):
+            inverse = "enable" if kind == "disable" else "disable"
+            self._push(kind, f"{kind.capitalize()}d {target}", {"action": inverse, "packageName": target},
+                       target=target)
+        elif kind in ("overlay_enable", "overlay_disable"):
+            enabled = kind == "overlay_enable"
+            self._push(kind, f"{'Enabled' if enabled else 'Disabled'} overlay {target}",
+                       {"action": "overlay", "overlayId": target, "enabled": not enabled})
+        elif kind == "animation_scale":
+            self._push(kind, "Changed animation scale", {"action": "animation_scales", "scales": result["previous"]})
+        elif kind == "background":
+            self._push(kind, f"Changed background mode of {target}",
+                       {"action": "background_mode", "packageName": target, "mode": result["previous"]})
+        elif kind == "notifications":
+            self._push(kind, f"Changed notifications of {target}",
+                       {"action": "notification_mode", "packageName": target, "mode": result["previous"]})
+        elif kind == "permission":
+            self._push(kind, f"Changed {result['permission']} of {target}",
+                       {"action": "permission", "packageName": target, "permission": result["permission"],
+                        "granted": result["previous"]})
+        elif kind == "trim_caches":
+            self._push(kind, "Trimmed app caches", None, "Cleared caches cannot be restored")
+        elif kind == "wipe_data":
+            self._push(kind, f"Deleted app data of {target}", None, "Deleted app data cannot be restored")
+
+    def _is_system_package(self, package_name: str) -> bool:
+        try:
+            # -u includes system packages already uninstalled for user 0
+            output = self.adb.shell(f"pm list packages -s -u {require_package_name(package_name)}", timeout=15)
+        except ADBError:
+            return True  # Assume reversible; undo reports the failure if it is not
+        return f"package:{package_name}" in output.split()
+
+    # ===== Undo =====
+
+    def _apply(self, inverse: Dict) -> Dict:
+        action = inverse["action"]
+        if action == "reinstall":
+            return self.adb.reinstall_package(inverse["packageName"])
+        if action == "uninstall":
+            return self.adb.uninstall_package(inverse["packageName"])
+        if action in ("disable", "enable"):
+            return self.adb.set_package_enabled(inverse["packageName"], action == "enable")
+        if action == "overlay":
+            return self.adb.set_overlay_enabled(inverse["overlayId"], inverse["enabled"])
+        if action == "animation_scales":
+            return self.tweaks.restore_animation_scales(inverse["scales"])
+        if action == "background_mode":
+            return self.tweaks.set_background_mode(inverse["packageName"], inverse["mode"])
+        if action == "notification_mode":
+            return self.tweaks.set_notification_mode(inverse["packageName"], inverse["mode"])
+        if action == "permission":
+            return self.tweaks.set_permission(inverse["packageName"], inverse["permission"], inverse["granted"])
+        return {"success": False, "message": f"Unknown inverse action: {action}"}
+
+    def get_undo_stack(self) -> List[Dict]:
+        """Entries for the connected device, newest first"""
+        serial = self._serial()
+        return [
+            {k: v for k, v in entry.items() if k != "inverse"}
+            for entry in reversed(self._entries) if entry["serial"] == serial
+        ]
+
+    def packages_since(self, kind: str, since: float) -> List[str]:
+        """Packages of the connected device's `kind` entries recorded since `since`, oldest first"""
+        serial = self._serial()
+        with self._lock:
+            return list(dict.fromkeys(
+                entry["inverse"]["packageName"] for entry in self._entries
+                if entry["serial"] == serial and entry["kind"] == kind and entry["timestamp"] >= since
+                and entry["inverse"] and "packageName" in entry["inverse"]
+            ))
+
+    def last_change(self, package_name: str, kinds=("disable", "uninstall", "enable", "reinstall")) -> Optional[Dict]:
+        """{"kind", "timestamp"} of the connected device's latest `kinds` entry for the package"""
+        serial = self._serial()
+        with self._lock:
+            for entry in reversed(self._entries):
+                # Entries recorded before targets were kept still name the package in their inverse
+                target = entry.get("target") or (entry["inverse"] or {}).get("packageName")
+                if entry["serial"] == serial and entry["kind"] in kinds and target == package_name:
+                    return {"kind": entry["kind"], "timestamp": entry["timestamp"]}
+        return None
+
+    def undo(self, count: int = 1, confirm: Callable[[str, List[str]], Optional[Dict]] = None) -> Dict:
+        """
+        Reverse the last `count` operations on the connected device.
+
+        Irreversible operations are removed and reported. Undo stops at the
+        first inverse action that fails, leaving it on the stack. `confirm`
+        gets (operation, packages) for the inverses that uninstall or disable
+        (undoing a reinstall or an enable) and returns the failure to give
+        instead, in which case nothing is undone.
+        """
+        serial = self._serial()
+        results = []
+        with self._lock:
+            selected = [e for e in reversed(self._entries) if e["serial"] == serial][:max(1, int(count))]
+            if confirm is not None:
+                for action, operation in REMOVING_INVERSES.items():
+                    packages = [e["inverse"]["packageName"] for e in selected
+                                if e["reversible"] and e["inverse"]["action"] == action]
+                    refusal = confirm(operation, packages) if packages else None
+                    if refusal:
+                        return {**refusal, "results": []}
+            for entry in selected:
+                if not entry["reversible"]:
+                    self._entries.remove(entry)
+                    results.append({"id": entry["id"], "description": entry["description"], "success": False,
+                                    "reversible": False, "message": entry["reason"]})
+                    continue
+
+                outcome = self._apply(entry["inverse"])
+                results.append({"id": entry["id"], "description": entry["description"],
+                                "success": outcome.get("success", False), "reversible": True,
+                                "message": outcome.get("message", "")})
+                if not outcome.get("success"):
+                    break
+                self._entries.remove(entry)
+            self._save()
+
+        undone = sum(1 for r in results if r["success"])
+        if not results:
+            message = "Nothing to undo"
+        else:
+            message = f"Undid {undone} of {len(results)} operation(s)"
+        return {"success": undone > 0, "message": message, "results": results}
@@ -0,0 +1,138 @@
+"""
+Unused Apps Module
+Suggests user-installed apps to remove because they have not been opened
+for a long time ("you haven't opened these 12 apps in 6 months - reclaim
+3.2 GB"), from usage stats, install times and app sizes. Only third-party
+apps are suggested; preinstalled bloat is covered by the safety ratings
+"""
+import re
+import time
+from datetime import datetime
+from typing import Dict, List, Set
+from adb_operations import ADBOperations, ADBError
+import formatting
+
+
+DAY = 24 * 3600
+DEFAULT_UNUSED_DAYS = 180
+
+# `dumpsys package packages`: a "Package [com.foo] (1a2b3c):" block per package with "firstInstallTime=..."
+INSTALL_TIMES_COMMAND = "dumpsys package packages"
+PACKAGE_BLOCK_PATTERN = re.compile(r'^\s*Package \[([\w.]+)\]')
+FIRST_INSTALL_PATTERN = re.compile(r'^\s*firstInstallTime=(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2})')
+
+# "com.google.android.inputmethod.latin/com.android.inputmethod.latin.LatinIME"
+INPUT_METHOD_COMMAND = "settings get secure default_input_method"
+
+
+def parse_install_times(output: str) -> Dict[str, float]:
+    """package -> first install time from INSTALL_TIMES_COMMAND"""
+    times: Dict[str, float] = {}
+    package = None
+    for line in output.split('\n'):
+        block = PACKAGE_BLOCK_PATTERN.match(line)
+        if block:
+            package = block.group(1)
+            continue
+        installed = FIRST_INSTALL_PATTERN.match(line)
+        if installed and package and package not in times:
+            try:
+                times[package] = datetime.strptime(installed.group(1), "%Y-%m-%d %H:%M:%S").timestamp()
+            except ValueError:
+                pass
+    return times
+
+
+def _period(days: int) -> str:
+    """Readable period: "6 months" for 180 days, "2 weeks" for 14"""
+    if days >= 365 and days % 365 == 0:
+        return f"{days // 365} year{'s' if days > 365 else ''}"
+    if days >= 30 and days % 30 == 0:
+        return f"{days // 30} month{'s' if days > 30 else ''}"
+    if days % 7 == 0:
+        return f"{days // 7} week{'s' if days > 7 else ''}"
+    return f"{days} day{'s' if days != 1 else ''}"
+
+
+def find_unused(packages: List[Dict], install_times: Dict[str, float], days: int,
+                keep: Set[str] = frozenset(), now: float = None) -> List[Dict]:
+    """
+    User apps not opened for `days`, largest first. Listing entries carry
+    `lastUsed` and `sizeBytes`; apps never opened count from their install,
+    so one installed last week is not suggested, and apps with neither
+    time are left out
+    """
+    now = time.time() if now is None else now
+    cutoff = now - days * DAY
+    unused = []
+    for pkg in packages:
+        name = pkg["packageName"]
+        if pkg.get("isSystem") is not False or name in keep:
+            continue
+        last_used, installed_at = pkg.get("lastUsed"), install_times.get(name)
+        since = last_used or installed_at
+        if since is None or since >= cutoff:
+            continue
+        unused.append({
+            "packageName": name,
+            "appName": pkg.get("appName") or name,
+            "lastUsed": last_used,
+            "installedAt": installed_at,
+            "daysUnused": int((now - since) // DAY),
+            "sizeBytes": pkg.get("sizeBytes"),
+        })
+    unused.sort(key=lambda app: (-(app["sizeBytes"] or 0), app["packageName"]))
+    return unused
+
+
+class UnusedApps:
+    """Long-unused user apps of the connected device"""
+
+    def __init__(self, adb_operations: ADBOperations, package_cache, restarter):
+        self.adb = adb_operations
+        self.package_cache = package_cache
+        self.restarter = restarter
+
+    def _in_use(self) -> Set[str]:
+        """The default launcher and keyboard, which are used without ever being opened"""
+        keep = set()
+        try:
+            launcher = self.restarter.get_launcher_package()
+            if launcher:
+                keep.add(launcher)
+            keyboard = self.adb.shell(INPUT_METHOD_COMMAND, timeout=10).strip()
+            if '/' in keyboard:
+                keep.add(keyboard.split('/', 1)[0])
+        except ADBError:
+            pass
+        return keep
+
+    def get_suggestions(self, days: int = DEFAULT_UNUSED_DAYS, now: float = None) -> Dict:
+        """Apps not opened in `days` with the space removing them would free"""
+        days = max(1, int(days))
+        try:
+            self.package_cache.prefetch_sizes()
+            self.package_cache.prefetch_last_used()
+            packages = self.package_cache.packages()
+            install_times = parse_install_times(self.adb.shell(INSTALL_TIMES_COMMAND, timeout=60))
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+
+        apps = find_unused(packages, install_times, days, self._in_use(), now)
+        total = sum(app["sizeBytes"] or 0 for app in apps)
+        for app in apps:
+            app["formatted"] = formatting.formatted_fields({"sizeBytes": app["sizeBytes"]})
+        if apps:
+            message = (f"You haven't opened these {len(apps)} apps in {_period(days)}"
+                       + (f" - reclaim {formatting.format_size(total)}" if total else ""))
+        else:
+            message = f"No user app has gone unopened for {_period(days)}"
+        return {
+            "success": True,
+            "days": days,
+            "apps": apps,
+            "count": len(apps),
+            "totalBytes": total,
+            "formatted": formatting.formatted_fields({"totalBytes": total}),
+            "message": message,
+        }
@@ -0,0 +1,157 @@
+"""
+AI Usage Tracker Module
+Records requests/tokens per provider and enforces an optional monthly budget
+"""
+import json
+import math
+import sys
+import threading
+from datetime import datetime, timedelta
+from pathlib import Path
+from typing import Dict, Optional
+
+
+# Estimated USD prices: (input per 1M tokens, output per 1M tokens, per request fee)
+MODEL_PRICING = {
+    "sonar": (1.0, 1.0, 0.005),
+    "sonar-pro": (3.0, 15.0, 0.006),
+    "sonar-reasoning": (1.0, 5.0, 0.005),
+    "gpt-4-turbo-preview": (10.0, 30.0, 0.0),
+}
+DEFAULT_PRICING = (1.0, 1.0, 0.0)
+
+# Daily totals older than this are dropped; monthly totals are kept
+DAILY_HISTORY_DAYS = 90
+
+# Highest monthly budget accepted, in USD
+MAX_MONTHLY_BUDGET_USD = 100000
+
+
+class BudgetExceededError(Exception):
+    """Raised when the monthly AI budget has been used up"""
+    pass
+
+
+def estimate_cost(model: str, usage: Dict) -> float:
+    """Estimate the USD cost of one call from its `usage` block"""
+    # Perplexity reports the real cost on newer API versions
+    reported = usage.get("cost")
+    if isinstance(reported, dict) and isinstance(reported.get("total_cost"), (int, float)):
+        return float(reported["total_cost"])
+
+    input_price, output_price, request_fee = MODEL_PRICING.get(model, DEFAULT_PRICING)
+    prompt = usage.get("prompt_tokens", 0) or 0
+    completion = usage.get("completion_tokens", 0) or 0
+    return (prompt * input_price + completion * output_price) / 1_000_000 + request_fee
+
+
+def budget_error(value) -> Optional[str]:
+    """Why `value` is not a usable monthly budget, or None (None itself means no budget)"""
+    if value is None:
+        return None
+    if isinstance(value, bool) or not isinstance(value, (int, float)) or not math.isfinite(value):
+        return "Budget must be a number of US dollars"
+    if not 0 <= value <= MAX_MONTHLY_BUDGET_USD:
+        return f"Budget must be between 0 and {MAX_MONTHLY_BUDGET_USD} USD"
+    return None
+
+
+class AIUsageTracker:
+    """Persist daily and monthly AI usage totals"""
+
+    def __init__(self, data_dir: str = None):
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.path = Path(data_dir) / "ai_usage.json"
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        self._lock = threading.Lock()
+        self._data = self._load()
+
+    def _load(self) -> Dict:
+        try:
+            with open(self.path, 'r', encoding='utf-8') as f:
+                data = json.load(f)
+        except (OSError, json.JSONDecodeError):
+            data = {}
+        data.setdefault("daily", {})
+        data.setdefault("monthly", {})
+        data.setdefault("monthlyBudgetUsd", None)
+        error = budget_error(data["monthlyBudgetUsd"])
+        if error:
+            print(f"[Warning] Ignoring the monthly AI budget in {self.path}: {error}", file=sys.stderr)
+            data["monthlyBudgetUsd"] = None
+        return data
+
+    def _save(self):
+        with open(self.path, 'w', encoding='utf-8') as f:
+            json.dump(self._data, f, indent=2)
+
+    @staticmethod
+    def _add(bucket: Dict, provider: str, tokens: Dict, cost: float):
+        totals = bucket.setdefault(provider, {
+            "requests": 0, "promptTokens": 0, "completionTokens": 0, "totalTokens": 0, "estimatedCostUsd": 0.0
+        })
+        totals["requests"] += 1
+        totals["promptTokens"] += tokens["prompt"]
+        totals["completionTokens"] += tokens["completion"]
+        totals["totalTokens"] += tokens["prompt"] + tokens["completion"]
+        totals["estimatedCostUsd"] = round(totals["estimatedCostUsd"] + cost, 6)
+
+    def record(self, provider: str, model: str, usage: Optional[Dict]):
+        """Record one completed API call from the response's `usage` field"""
+        usage = usage or {}
+        tokens = {
+            "prompt": usage.get("prompt_tokens", 0) or 0,
+            "completion": usage.get("completion_tokens", 0) or 0,
+        }
+        cost = estimate_cost(model, usage)
+        now = datetime.now()
+
+        with self._lock:
+            self._add(self._data["daily"].setdefault(now.strftime("%Y-%m-%d"), {}), provider, tokens, cost)
+            self._add(self._data["monthly"].setdefault(now.strftime("%Y-%m"), {}), provider, tokens, cost)
+            cutoff = (now - timedelta(days=DAILY_HISTORY_DAYS)).strftime("%Y-%m-%d")
+            self._data["daily"] = {day: v for day, v in self._data["daily"].items() if day >= cutoff}
+            self._save()
+
+    def _month_cost(self, month: str) -> float:
+        return sum(p["estimatedCostUsd"] for p in self._data["monthly"].get(month, {}).values())
+
+    def check_budget(self):
+        """Raise BudgetExceededError if this month's spend has reached the budget"""
+        budget = self._data.get("monthlyBudgetUsd")
+        if budget is None:
+            return
+        spent = self._month_cost(datetime.now().strftime("%Y-%m"))
+        if spent >= budget:
+            raise BudgetExceededError(
+                f"Monthly AI budget of ${budget:.2f} reached (estimated ${spent:.2f} spent). "
+                "Raise or clear the budget in Settings to continue using AI features."
+            )
+
+    def set_budget(self, monthly_budget_usd: Optional[float]) -> Dict:
+        """Set the monthly budget in USD, or None to disable it"""
+        error = budget_error(monthly_budget_usd)
+        if error:
+            return {"success": False, "message": error}
+        with self._lock:
+            self._data["monthlyBudgetUsd"] = monthly_budget_usd
+            self._save()
+        if monthly_budget_usd is None:
+            return {"success": True, "message": "Monthly AI budget removed"}
+        return {"success": True, "message": f"Monthly AI budget set to ${monthly_budget_usd:.2f}"}
+
+    def get_stats(self) -> Dict:
+        """Today's and this month's usage, plus budget status"""
+        now = datetime.now()
+        month = now.strftime("%Y-%m")
+        budget = self._data.get("monthlyBudgetUsd")
+        spent = self._month_cost(month)
+        return {
+            "today": self._data["daily"].get(now.strftime("%Y-%m-%d"), {}),
+            "month": self._data["monthly"].get(month, {}),
+            "monthlyHistory": self._data["monthly"],
+            "monthlyBudgetUsd": budget,
+            "monthlySpentUsd": round(spent, 4),
+            "budgetRemainingUsd": round(max(0.0, budget - spent), 4) if budget is not None else None,
+        }
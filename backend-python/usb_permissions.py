@@ -0,0 +1,278 @@
+"""
+USB Permissions Module
+On Linux, adb may list a device as "no permissions" when no udev rule lets
+the desktop user open it. This module finds the device's USB vendor ID in
+sysfs, explains the fix for the detected distribution, and writes a udev rule
+for the vendor through pkexec (or sudo when it needs no password)
+"""
+import os
+import re
+import shutil
+import subprocess
+import sys
+import tempfile
+from pathlib import Path
+from typing import Callable, Dict, List, Optional
+from adb_operations import ADBOperations, ADBError
+
+try:
+    import grp
+except ImportError:  # Windows
+    grp = None
+
+
+RULES_PATH = "/etc/udev/rules.d/51-android-debloat-ai.rules"
+
+# USB vendor IDs of Android device makers, for when the serial is not in sysfs
+ANDROID_VENDORS = {
+    "18d1": "Google",
+    "04e8": "Samsung",
+    "2717": "Xiaomi",
+    "22d9": "OPPO",
+    "2d95": "vivo",
+    "2a70": "OnePlus",
+    "22b8": "Motorola",
+    "12d1": "Huawei",
+    "0bb4": "HTC",
+    "1004": "LG",
+    "0fce": "Sony",
+    "2e04": "HMD Global (Nokia)",
+    "1949": "Amazon",
+    "19d2": "ZTE",
+    "17ef": "Lenovo",
+    "0b05": "ASUS",
+    "05c6": "Qualcomm",
+    "0e8d": "MediaTek",
+}
+
+# Per distribution family: the group the rule grants, and the package that
+# ships maintained rules for every known vendor
+DISTRO_FAMILIES = {
+    "debian": {"group": "plugdev", "package": "android-sdk-platform-tools-common",
+               "install": "sudo apt install android-sdk-platform-tools-common"},
+    "fedora": {"group": None, "package": "android-tools", "install": "sudo dnf install android-tools"},
+    "arch": {"group": "adbusers", "package": "android-udev", "install": "sudo pacman -S android-udev"},
+    "suse": {"group": None, "package": "android-udev-rules", "install": "sudo zypper install android-udev-rules"},
+    "other": {"group": None, "package": None, "install": None},
+}
+
+VENDOR_ID_PATTERN = re.compile(r'^[0-9a-f]{4}$')
+
+# pkexec exit statuses for a dismissed or refused password prompt
+PKEXEC_CANCELLED = (126, 127)
+
+
+def parse_os_release(text: str) -> Dict[str, str]:
+    """KEY=value pairs of /etc/os-release, quotes removed"""
+    fields = {}
+    for line in text.split('\n'):
+        key, sep, value = line.strip().partition('=')
+        if sep and key and not key.startswith('#'):
+            fields[key] = value.strip().strip('"\'')
+    return fields
+
+
+def distro_family(os_release: Dict[str, str]) -> str:
+    """debian, fedora, arch, suse or other, from ID and ID_LIKE"""
+    ids = [os_release.get("ID", "").lower()] + os_release.get("ID_LIKE", "").lower().split()
+    for distro_id in ids:
+        if distro_id in ("debian", "ubuntu", "linuxmint", "pop", "elementary", "raspbian"):
+            return "debian"
+        if distro_id in ("fedora", "rhel", "centos", "rocky", "almalinux"):
+            return "fedora"
+        if distro_id in ("arch", "manjaro", "endeavouros"):
+            return "arch"
+        if distro_id.startswith("opensuse") or distro_id in ("suse", "sles"):
+            return "suse"
+    return "other"
+
+
+def udev_rule(vendor_id: str, group: Optional[str]) -> str:
+    """One rule giving the logged-in user (uaccess) and `group` access to the vendor's devices"""
+    group_part = f', GROUP="{group}"' if group else ""
+    return f'SUBSYSTEM=="usb", ATTR{{idVendor}}=="{vendor_id}", MODE="0660"{group_part}, TAG+="uaccess"'
+
+
+def read_usb_devices(sys_root: str) -> List[Dict[str, Optional[str]]]:
+    """[{"vendorId", "productId", "serial", "product"}] of the USB devices in sysfs"""
+    def read(folder: Path, name: str) -> Optional[str]:
+        try:
+            return (folder / name).read_text(encoding='utf-8', errors='replace').strip() or None
+        except OSError:
+            return None
+
+    devices = []
+    root = Path(sys_root)
+    if not root.is_dir():
+        return devices
+    for folder in sorted(root.iterdir()):
+        vendor_id = read(folder, "idVendor")
+        if vendor_id:
+            devices.append({
+                "vendorId": vendor_id.lower(),
+                "productId": read(folder, "idProduct"),
+                "serial": read(folder, "serial"),
+                "product": read(folder, "product"),
+            })
+    return devices
+
+
+def vendor_for(serials: List[str], usb_devices: List[Dict]) -> Optional[str]:
+    """Vendor ID of the USB device with one of `serials`, else of the only Android maker's device plugged in"""
+    for device in usb_devices:
+        if device["serial"] and device["serial"] in serials:
+            return device["vendorId"]
+    android = {device["vendorId"] for device in usb_devices if device["vendorId"] in ANDROID_VENDORS}
+    return android.pop() if len(android) == 1 else None
+
+
+class UsbPermissions:
+    """Detect the Linux "no permissions" adb state and install a udev rule for it"""
+
+    def __init__(self, adb_operations: ADBOperations, platform: str = sys.platform,
+                 sys_root: str = "/sys/bus/usb/devices", os_release_path: str = "/etc/os-release",
+                 rules_path: str = RULES_PATH, run: Callable = subprocess.run):
+        self.adb = adb_operations
+        self.platform = platform
+        self.sys_root = sys_root
+        self.os_release_path = os_release_path
+        self.rules_path = rules_path
+        # subprocess.run, replaced in tests
+        self.run = run
+
+    def _os_release(self) -> Dict[str, str]:
+        try:
+            with open(self.os_release_path, encoding='utf-8') as f:
+                return parse_os_release(f.read())
+        except OSError:
+            return {}
+
+    def _blocked_serials(self) -> List[str]:
+        try:
+            states = self.adb.get_device_states()
+        except ADBError:
+            return []
+        return [serial for serial, state in states.items() if state == "no permissions"]
+
+    def _installed_rules(self) -> str:
+        try:
+            with open(self.rules_path, encoding='utf-8') as f:
+                return f.read()
+        except OSError:
+            return ""
+
+    @staticmethod
+    def _group_id(group: Optional[str]) -> Optional[int]:
+        if not group or grp is None:
+            return None
+        try:
+            return grp.getgrnam(group).gr_gid
+        except KeyError:
+            return None
+
+    def _group_for(self, family: str) -> Optional[str]:
+        """The family's group when it exists here; the uaccess tag covers desktop sessions without one"""
+        group = DISTRO_FAMILIES[family]["group"]
+        return group if self._group_id(group) is not None else None
+
+    def check(self) -> Dict:
+        """Whether a connected device is blocked by USB permissions, with distro-aware steps to fix it"""
+        if not self.platform.startswith("linux"):
+            return {"needed": False, "platform": self.platform}
+        serials = self._blocked_serials()
+        if not serials:
+            return {"needed": False, "platform": self.platform}
+
+        os_release = self._os_release()
+        family = distro_family(os_release)
+        guidance = DISTRO_FAMILIES[family]
+        group = self._group_for(family)
+        vendor_id = vendor_for(serials, read_usb_devices(self.sys_root))
+        rule = udev_rule(vendor_id, group) if vendor_id else None
+
+        steps = []
+        if guidance["install"]:
+            steps.append(f"Install the {guidance['package']} package, which has rules for most phones: "
+                         f"{guidance['install']}")
+        if rule:
+            steps.append(f"Or let Debloat AI write this rule to {self.rules_path}: {rule}")
+        else:
+            steps.append("Or add a rule with your phone's USB vendor ID (run lsusb) to "
+                         f"{self.rules_path}: {udev_rule('<vendor id>', group)}")
+        if group and self._group_id(group) not in os.getgroups():
+            steps.append(f"Add yourself to the {group} group and log in again: sudo usermod -aG {group} $USER")
+        steps.append("Unplug the device and plug it back in")
+
+        return {
+            "needed": True,
+            "platform": self.platform,
+            "serials": serials,
+            "distro": os_release.get("PRETTY_NAME") or os_release.get("NAME") or "Linux",
+            "family": family,
+            "vendorId": vendor_id,
+            "vendorName": ANDROID_VENDORS.get(vendor_id) if vendor_id else None,
+            "group": group,
+            "rule": rule,
+            "rulePath": self.rules_path,
+            "ruleInstalled": bool(vendor_id) and f'"{vendor_id}"' in self._installed_rules(),
+            "canInstall": bool(rule) and self._elevation() is not None,
+            "steps": steps,
+        }
+
+    def _elevation(self) -> Optional[List[str]]:
+        """pkexec for a graphical password prompt, else sudo when it needs no password"""
+        if shutil.which("pkexec") and (os.environ.get("DISPLAY") or os.environ.get("WAYLAND_DISPLAY")):
+            return ["pkexec"]
+        if shutil.which("sudo"):
+            try:
+                if self.run(["sudo", "-n", "true"], capture_output=True, timeout=10).returncode == 0:
+                    return ["sudo", "-n"]
+            except (OSError, subprocess.TimeoutExpired):
+                pass
+        return None
+
+    def install_rule(self, vendor_id: Optional[str] = None) -> Dict:
+        """Add a rule for `vendor_id` (default: the blocked device's) and reload udev"""
+        help_info = self.check()
+        if vendor_id is None:
+            vendor_id = help_info.get("vendorId")
+        vendor_id = (vendor_id or "").lower()
+        if not self.platform.startswith("linux"):
+            return {"success": False, "message": "udev rules are only used on Linux"}
+        if not VENDOR_ID_PATTERN.fullmatch(vendor_id):
+            return {"success": False, "message": "Could not tell the device's USB vendor ID; run lsusb to find it"}
+
+        rule = udev_rule(vendor_id, self._group_for(distro_family(self._os_release())))
+        existing = self._installed_rules()
+        if f'"{vendor_id}"' in existing:
+            return {"success": True, "rule": rule, "rulePath": self.rules_path, "alreadyInstalled": True,
+                    "message": "The rule is already installed. Unplug the device and plug it back in."}
+        elevation = self._elevation()
+        if elevation is None:
+            return {"success": False, "rule": rule, "rulePath": self.rules_path,
+                    "message": f"No password prompt is available. Add this line to {self.rules_path} as root, "
+                               f"then run: sudo udevadm control --reload-rules && sudo udevadm trigger"}
+
+        header = "" if existing else "# Written by Debloat AI so adb can open these Android devices\n"
+        with tempfile.NamedTemporaryFile("w", suffix=".rules", delete=False, encoding='utf-8') as f:
+            f.write(existing.rstrip("\n") + "\n" if existing else header)
+            f.write(rule + "\n")
+            staged = f.name
+        script = ('install -m 0644 "$1" "$2" && udevadm control --reload-rules '
+                  '&& udevadm trigger --subsystem-match=usb')
+        try:
+            result = self.run(elevation + ["sh", "-c", script, "sh", staged, self.rules_path],
+                              capture_output=True, text=True, timeout=120)
+        except (OSError, subprocess.TimeoutExpired) as e:
+            return {"success": False, "rule": rule, "rulePath": self.rules_path,
+                    "message": f"Could not write the rule: {e}"}
+        finally:
+            Path(staged).unlink(missing_ok=True)
+        if result.returncode != 0:
+            cancelled = elevation[0] == "pkexec" and result.returncode in PKEXEC_CANCELLED
+            message = ("The password prompt was cancelled" if cancelled
+                       else f"Could not write the rule: {(result.stderr or '').strip() or result.returncode}")
+            return {"success": False, "rule": rule, "rulePath": self.rules_path, "message": message}
+        name = ANDROID_VENDORS.get(vendor_id, vendor_id)
+        return {"success": True, "rule": rule, "rulePath": self.rules_path, "alreadyInstalled": False,
+                "message": f"Added a udev rule for {name} devices. Unplug the device and plug it back in."}
@@ -0,0 +1,39 @@
+"""
+Validation Module
+The one definition of a package, permission and overlay name. These names
+are interpolated into `adb shell` command lines, so anything outside the
+strict ASCII forms below is refused before it reaches the device shell
+"""
+import re
+
+
+# Dot-separated segments, each starting with a letter, e.g. com.facebook.katana
+PACKAGE_NAME = re.compile(r'^[A-Za-z][A-Za-z0-9_]*(\.[A-Za-z][A-Za-z0-9_]*)+$')
+
+# "RECORD_AUDIO" or a dotted name such as android.permission.RECORD_AUDIO
+PERMISSION_NAME = re.compile(r'^[A-Za-z][A-Za-z0-9_]*(\.[A-Za-z0-9_]+)*$')
+
+# An overlay package, or "<package>:<name>" for fabricated runtime overlays
+OVERLAY_ID = re.compile(r'^[A-Za-z][A-Za-z0-9_]*(\.[A-Za-z][A-Za-z0-9_]*)+(:[A-Za-z0-9_.]+)?$')
+
+# Android rejects longer package names
+MAX_NAME_LENGTH = 255
+
+
+def _matches(pattern: re.Pattern, value) -> bool:
+    return isinstance(value, str) and len(value) <= MAX_NAME_LENGTH and pattern.fullmatch(value) is not None
+
+
+def is_package_name(value) -> bool:
+    """True for a well-formed package name"""
+    return _matches(PACKAGE_NAME, value)
+
+
+def is_permission_name(value) -> bool:
+    """True for a well-formed short or full permission name"""
+    return _matches(PERMISSION_NAME, value)
+
+
+def is_overlay_id(value) -> bool:
+    """True for a well-formed overlay identifier as listed by `cmd overlay list`"""
+    return _matches(OVERLAY_ID, value)
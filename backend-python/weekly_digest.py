@@ -0,0 +1,363 @@
+"""
+Weekly Digest Module
+Takes a small snapshot of each connected device once a day (storage use,
+installed packages, battery use per package) and turns a week of them into
+a digest: storage trend, apps that appeared, battery drainer changes and
+suggested actions. Snapshots are kept per device, so a week spans however
+many days the device was actually connected. The per-package battery use
+also gives each app's drain trend, to check that disabling it helped
+"""
+import json
+import re
+import threading
+import time
+from pathlib import Path
+from typing import Callable, Dict, List, Optional
+from adb_operations import ADBOperations, ADBError
+from events import EmitFn, no_emit
+import formatting
+from system_health import parse_duration_seconds
+
+
+DAY = 24 * 3600
+SNAPSHOT_INTERVAL = DAY
+DIGEST_INTERVAL = 7 * DAY
+# Seconds between checks for a due snapshot or digest
+CHECK_INTERVAL = 3600
+
+# Snapshots older than this are dropped; digests beyond this many are dropped
+SNAPSHOT_RETENTION = 35 * DAY
+MAX_DIGESTS = 12
+
+# Battery users kept per snapshot
+TOP_DRAINERS = 5
+
+POWER_USE_COMMAND = "dumpsys batterystats --charged"
+PACKAGE_UIDS_COMMAND = "pm list packages -U"
+
+# "Estimated power use (mAh):" section; "UID u0a172: 45.1 ( cpu=30.2 ...)" (Android 12+ writes UID, older Uid)
+POWER_SECTION_PATTERN = re.compile(r'^\s*Estimated power use \(mAh\):\s*$', re.MULTILINE)
+POWER_LINE_PATTERN = re.compile(r'^\s*(?:UID|Uid) u(\d+)a(\d+):\s*([\d.]+)')
+# "package:com.facebook.katana uid:10172"; shared user ids list several packages per uid
+PACKAGE_UID_PATTERN = re.compile(r'^package:(\S+)\s+uid:(\d+)', re.MULTILINE)
+
+# "Time on battery: 1d 2h 3m 4s 567ms (94.1%) realtime, ..." of the same output
+BATTERY_TIME_PATTERN = re.compile(r'^\s*Time on battery: ((?:\d+(?:ms|d|h|m|s) ?)+)', re.MULTILINE)
+
+# Days of battery trend returned unless asked otherwise
+DEFAULT_TREND_DAYS = 14
+
+# Undo entry kinds a battery trend is split at
+CHANGE_WORDS = {"disable": "disabled", "uninstall": "uninstalled", "enable": "enabled", "reinstall": "reinstalled"}
+
+# Android app uids start at 10000 ("u0a172" is 10172 for user 0)
+FIRST_APPLICATION_UID = 10000
+
+
+def parse_power_use(output: str) -> Dict[int, float]:
+    """app uid -> estimated mAh from the batterystats power section (user 0 only)"""
+    match = POWER_SECTION_PATTERN.search(output)
+    if not match:
+        return {}
+    usage = {}
+    for line in output[match.end():].split('\n')[1:]:
+        if not line.strip():
+            break
+        line_match = POWER_LINE_PATTERN.match(line)
+        if line_match and line_match.group(1) == "0":
+            uid = FIRST_APPLICATION_UID + int(line_match.group(2))
+            usage[uid] = usage.get(uid, 0.0) + float(line_match.group(3))
+    return usage
+
+
+def parse_package_uids(output: str) -> Dict[int, List[str]]:
+    """uid -> packages from PACKAGE_UIDS_COMMAND"""
+    uids: Dict[int, List[str]] = {}
+    for package, uid in PACKAGE_UID_PATTERN.findall(output):
+        uids.setdefault(int(uid), []).append(package)
+    return uids
+
+
+def top_drainers(usage: Dict[int, float], uids: Dict[int, List[str]], limit: int = TOP_DRAINERS) -> List[Dict]:
+    """[{"packageName", "mAh"}] of the heaviest app uids; shared uids are named after their first package"""
+    ranked = sorted(((mah, uid) for uid, mah in usage.items() if uid in uids and mah > 0), reverse=True)
+    return [{"packageName": sorted(uids[uid])[0], "mAh": round(mah, 1)} for mah, uid in ranked[:limit]]
+
+
+def parse_battery_hours(output: str) -> Optional[float]:
+    """Hours on battery since the last full charge, which the power figures cover"""
+    match = BATTERY_TIME_PATTERN.search(output)
+    if not match:
+        return None
+    return round(parse_duration_seconds(match.group(1)) / 3600, 2)
+
+
+def package_power(usage: Dict[int, float], uids: Dict[int, List[str]]) -> Dict[str, float]:
+    """package -> estimated mAh; every package of a shared uid gets the uid's total"""
+    return {package: round(mah, 2) for uid, mah in usage.items() if mah > 0 for package in uids.get(uid, [])}
+
+
+def battery_trend(snapshots: List[Dict], package_name: str, since: float,
+                  change: Optional[Dict] = None) -> Dict:
+    """
+    Drain of one package per snapshot since `since`, as mAh per hour on
+    battery (the batterystats figures restart at every full charge). With a
+    `change` ({"kind", "timestamp"}: the package's last disable or
+    uninstall) the average before it is compared with the average after
+    """
+    points = []
+    for snapshot in snapshots:
+        if snapshot["timestamp"] < since or snapshot.get("power") is None:
+            continue  # batterystats failed, or the snapshot is from before per-package use was kept
+        mah = snapshot["power"].get(package_name, 0.0)
+        hours = snapshot.get("batteryHours")
+        points.append({
+            "timestamp": snapshot["timestamp"],
+            "mAh": mah,
+            "batteryHours": hours,
+            "mAhPerHour": round(mah / hours, 3) if hours else None,
+            "installed": package_name in snapshot["packages"],
+        })
+
+    def average(selected: List[Dict]) -> Optional[float]:
+        rates = [point["mAhPerHour"] for point in selected if point["mAhPerHour"] is not None]
+        return round(sum(rates) / len(rates), 3) if rates else None
+
+    before = after = change_percent = None
+    changed = CHANGE_WORDS.get(change["kind"], change["kind"]) if change else None
+    if change:
+        before = average([point for point in points if point["timestamp"] < change["timestamp"]])
+        after = average([point for point in points if point["timestamp"] >= change["timestamp"]])
+        if before and after is not None:
+            change_percent = round((after - before) / before * 100)
+
+    if not points:
+        message = "No battery snapshots yet; one is taken each day the device is connected"
+    elif not change:
+        overall = average(points)
+        message = (f"{package_name} averaged {overall} mAh per hour on battery" if overall is not None
+                   else "The device has not run on battery since these snapshots")
+    elif before is None:
+        message = f"No snapshot from before {package_name} was {changed} to compare with"
+    elif after is None:
+        message = f"{package_name} was {changed}; the next daily snapshot shows whether its drain went down"
+    elif change_percent is None:
+        message = f"{package_name} used no measurable battery before it was {changed}"
+    else:
+        direction = "down" if change_percent <= 0 else "up"
+        message = (f"Since {package_name} was {changed} its drain went {direction} from {before} to "
+                   f"{after} mAh per hour on battery ({change_percent:+d}%)")
+
+    return {
+        "packageName": package_name,
+        "points": points,
+        "change": change,
+        "beforeMAhPerHour": before,
+        "afterMAhPerHour": after,
+        "changePercent": change_percent,
+        "message": message,
+    }
+
+
+def build_digest(snapshots: List[Dict], rating_for: Callable[[str], str], now: float = None) -> Optional[Dict]:
+    """
+    Digest of the last week of snapshots, compared with the last snapshot
+    before it (or the week's first when there is none); None with fewer
+    than two snapshots
+    """
+    now = time.time() if now is None else now
+    if len(snapshots) < 2:
+        return None
+    earlier = [s for s in snapshots if s["timestamp"] < now - DIGEST_INTERVAL]
+    baseline = earlier[-1] if earlier else snapshots[0]
+    latest = snapshots[-1]
+    days = max((latest["timestamp"] - baseline["timestamp"]) / DAY, 1 / 24)
+
+    storage = None
+    if baseline.get("storageUsedKb") is not None and latest.get("storageUsedKb") is not None:
+        change = latest["storageUsedKb"] - baseline["storageUsedKb"]
+        storage = {
+            "usedKb": latest["storageUsedKb"],
+            "availableKb": latest["storageAvailableKb"],
+            "changeKb": change,
+            "perDayKb": round(change / days),
+        }
+        # Sizes without the sign; changeKb says whether storage filled up or freed
+        storage["formatted"] = formatting.formatted_fields({key: abs(value) for key, value in storage.items()})
+
+    before, after = set(baseline["packages"]), set(latest["packages"])
+    new_apps = [{"packageName": p, "safetyLevel": rating_for(p)} for p in sorted(after - before)]
+    removed_apps = sorted(before - after)
+
+    old_top = {d["packageName"] for d in baseline.get("drainers", [])}
+    new_drainers = [d for d in latest.get("drainers", []) if d["packageName"] not in old_top]
+    gone_drainers = sorted(old_top - {d["packageName"] for d in latest.get("drainers", [])})
+
+    suggestions = []
+    if storage and storage["perDayKb"] > 0 and storage["availableKb"]:
+        days_left = storage["availableKb"] / storage["perDayKb"]
+        suggestion = f"Storage use grew by {storage['formatted']['change']}"
+        if days_left < 90:
+            suggestion += f"; at this rate internal storage is full in about {round(days_left)} days"
+        suggestions.append(suggestion)
+    removable = [app["packageName"] for app in new_apps if app["safetyLevel"] == "Safe"]
+    if new_apps:
+        suggestions.append(f"Review the {len(new_apps)} apps that appeared"
+                           + (f"; {len(removable)} of them are rated Safe to remove" if removable else ""))
+    for drainer in new_drainers:
+        suggestions.append(f"{drainer['packageName']} is newly among the top battery users; "
+                           f"check what it does in the background or try a trial disable")
+
+    return {
+        "generatedAt": now,
+        "periodStart": baseline["timestamp"],
+        "periodEnd": latest["timestamp"],
+        "snapshots": len([s for s in snapshots if s["timestamp"] >= baseline["timestamp"]]),
+        "storage": storage,
+        "newApps": new_apps,
+        "removedApps": removed_apps,
+        "topDrainers": latest.get("drainers", []),
+        "newDrainers": new_drainers,
+        "goneDrainers": gone_drainers,
+        "suggestions": suggestions,
+    }
+
+
+class WeeklyDigest:
+    """Daily device snapshots and the weekly digests built from them"""
+
+    def __init__(self, adb_operations: ADBOperations, health, emit_event: EmitFn = None, data_dir: str = None):
+        self.adb = adb_operations
+        self.health = health
+        self.emit_event = emit_event or no_emit
+        if data_dir is None:
+            data_dir = Path.home() / "DebloatAI"
+        self.dir = Path(data_dir) / "digests"
+        self.dir.mkdir(parents=True, exist_ok=True)
+        self._lock = threading.Lock()
+        self._stop = threading.Event()
+
+    def _load(self, serial: str) -> Dict:
+        try:
+            with open(self.dir / f"{serial}.json", 'r', encoding='utf-8') as f:
+                return json.load(f)
+        except (OSError, json.JSONDecodeError):
+            return {"snapshots": [], "digests": []}
+
+    def _save(self, serial: str, state: Dict):
+        with open(self.dir / f"{serial}.json", 'w', encoding='utf-8') as f:
+            json.dump(state, f, indent=2)
+
+    def take_snapshot(self, now: float = None) -> Dict:
+        """Storage use, installed packages and battery use per package of the connected device"""
+        storage = self.health.get_storage_info()
+        internal = storage["internal"] if storage else None
+        packages = sorted(line[len("package:"):].strip()
+                          for line in self.adb.shell("pm list packages", timeout=30).split('\n')
+                          if line.startswith("package:"))
+        drainers, power, battery_hours = [], None, None
+        try:
+            output = self.adb.shell(POWER_USE_COMMAND, timeout=60)
+            usage = parse_power_use(output)
+            uids = parse_package_uids(self.adb.shell(PACKAGE_UIDS_COMMAND, timeout=30))
+            drainers = top_drainers(usage, uids)
+            power = package_power(usage, uids)
+            battery_hours = parse_battery_hours(output)
+        except ADBError:
+            pass  # batterystats can time out on busy devices; the rest of the snapshot still counts
+        return {
+            "timestamp": time.time() if now is None else now,
+            "storageUsedKb": internal["usedKb"] if internal else None,
+            "storageAvailableKb": internal["availableKb"] if internal else None,
+            "packages": packages,
+            "drainers": drainers,
+            "power": power,
+            "batteryHours": battery_hours,
+        }
+
+    def check(self, now: float = None) -> Optional[Dict]:
+        """
+        Snapshot the connected device if its last snapshot is a day old, and
+        build a digest once a week of snapshots has passed since the last one.
+        Returns the new digest, which is also sent as `weekly_digest_ready`.
+        """
+        now = time.time() if now is None else now
+        try:
+            serial = self.adb.get_serial()
+        except ADBError:
+            return None
+        with self._lock:
+            state = self._load(serial)
+            snapshots = state["snapshots"]
+            if not snapshots or now - snapshots[-1]["timestamp"] >= SNAPSHOT_INTERVAL:
+                snapshots.append(self.take_snapshot(now))
+                state["snapshots"] = snapshots = [s for s in snapshots if s["timestamp"] >= now - SNAPSHOT_RETENTION]
+
+            digest = None
+            since = state["digests"][-1]["generatedAt"] if state["digests"] else snapshots[0]["timestamp"]
+            if now - since >= DIGEST_INTERVAL:
+                digest = build_digest(snapshots, self.adb.safety_rater(), now)
+                if digest is not None:
+                    state["digests"] = (state["digests"] + [digest])[-MAX_DIGESTS:]
+            self._save(serial, state)
+        if digest is not None:
+            self.emit_event("weekly_digest_ready", {**digest, "serial": serial})
+        return digest
+
+    def get_weekly_digest(self) -> Dict:
+        """
+        The connected device's latest digest; before the first one is due, a
+        `preview` built from the snapshots so far
+        """
+        try:
+            serial = self.adb.get_serial()
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        self.check()
+        with self._lock:
+            state = self._load(serial)
+        if state["digests"]:
+            return {"success": True, "digest": state["digests"][-1], "preview": False,
+                    "history": [d["generatedAt"] for d in state["digests"]]}
+        digest = build_digest(state["snapshots"], self.adb.safety_rater())
+        first = state["snapshots"][0]["timestamp"] if state["snapshots"] else time.time()
+        return {
+            "success": True,
+            "digest": digest,
+            "preview": True,
+            "history": [],
+            "nextDigestAt": first + DIGEST_INTERVAL,
+            "message": None if digest else "The first digest needs snapshots from at least two days",
+        }
+
+    def get_battery_trend(self, package_name: str, days: int = DEFAULT_TREND_DAYS,
+                          change: Optional[Dict] = None) -> Dict:
+        """The package's battery drain over the connected device's last `days` of snapshots"""
+        if not isinstance(days, int) or not 1 <= days <= SNAPSHOT_RETENTION // DAY:
+            return {"success": False, "message": f"days must be 1 to {SNAPSHOT_RETENTION // DAY}"}
+        try:
+            serial = self.adb.get_serial()
+        except ADBError as e:
+            return {"success": False, "message": str(e)}
+        self.check()
+        with self._lock:
+            snapshots = self._load(serial)["snapshots"]
+        return {"success": True, "days": days, **battery_trend(snapshots, package_name, time.time() - days * DAY, change)}
+
+    def start_scheduler(self, interval_seconds: float = CHECK_INTERVAL):
+        """Check for a due snapshot or digest now and then periodically in a background thread"""
+        self._stop.clear()
+
+        def run():
+            while True:
+                try:
+                    self.check()
+                except ADBError:
+                    pass  # Device went away mid-snapshot; retried on the next check
+                if self._stop.wait(interval_seconds):
+                    break
+
+        threading.Thread(target=run, daemon=True).start()
+
+    def stop_scheduler(self):
+        self._stop.set()
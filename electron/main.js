@@ -70,6 +70,12 @@ function startPythonProcess() {
           continue;
         }
 
+        // Unsolicited backend events (versioned envelope, no request id) go straight to the renderer
+        if (msg.event_version !== undefined) {
+          if (mainWindow) mainWindow.webContents.send('backend-event', msg);
+          continue;
+        }
+
         const cb = pendingRequests.get(msg.id);
         if (cb) {
           pendingRequests.delete(msg.id);
@@ -173,11 +179,24 @@ app.on('window-all-closed', () => {
   if (process.platform !== 'darwin') app.quit();
 });
 
-app.on('before-quit', () => {
-  if (pythonProcess) {
-    pythonProcess.stdin.end();
-    pythonProcess.kill();
-  }
+// The backend cancels its jobs, writes its logs and puts back temporary device
+// settings once stdin closes; it is only killed when that takes too long
+const BACKEND_SHUTDOWN_TIMEOUT_MS = 8000;
+let backendStopped = false;
+let backendStopping = false;
+
+app.on('before-quit', (event) => {
+  if (backendStopped || !pythonProcess || pythonProcess.exitCode !== null) return;
+  event.preventDefault();
+  if (backendStopping) return;
+  backendStopping = true;
+  const timer = setTimeout(() => pythonProcess.kill(), BACKEND_SHUTDOWN_TIMEOUT_MS);
+  pythonProcess.once('close', () => {
+    clearTimeout(timer);
+    backendStopped = true;
+    app.quit();
+  });
+  pythonProcess.stdin.end();
 });
 
 // ── IPC Handlers ─────────────────────────────────────────────────────
@@ -186,12 +205,12 @@ ipcMain.handle('get-device-info', async () => {
   return await callPython('get_device_info');
 });
 
-ipcMain.handle('list-packages', async (_event, packageType) => {
-  return await callPython('list_packages', { type: packageType || 'all' });
+ipcMain.handle('list-packages', async (_event, packageType, compact) => {
+  return await callPython('list_packages', { type: packageType || 'all', compact: !!compact });
 });
 
-ipcMain.handle('uninstall-package', async (_event, packageName) => {
-  return await callPython('uninstall_package', { packageName });
+ipcMain.handle('uninstall-package', async (_event, packageName, keepData, jobId, confirmation) => {
+  return await callPython('uninstall_package', { packageName, keepData: !!keepData, jobId, confirmation });
 });
 
 ipcMain.handle('reinstall-package', async (_event, packageName) => {
@@ -211,20 +230,20 @@ ipcMain.handle('parse-chat-command', async (_event, message) => {
   return await callPython('parse_chat_command', { message });
 });
 
-ipcMain.handle('execute-action', async (_event, executionResult, confirmed) => {
-  return await callPython('execute_action', { executionResult, confirmed });
+ipcMain.handle('execute-action', async (_event, executionResult, confirmed, confirmation) => {
+  return await callPython('execute_action', { executionResult, confirmed, confirmation });
 });
 
-ipcMain.handle('create-backup', async (_event, packages, deviceInfo) => {
-  return await callPython('create_backup', { packages, deviceInfo });
+ipcMain.handle('create-backup', async (_event, packages, deviceInfo, includeData) => {
+  return await callPython('create_backup', { packages, deviceInfo, includeData: !!includeData });
 });
 
-ipcMain.handle('list-backups', async () => {
-  return await callPython('list_backups');
+ipcMain.handle('list-backups', async (_event, allDevices) => {
+  return await callPython('list_backups', { allDevices: !!allDevices });
 });
 
-ipcMain.handle('restore-backup', async (_event, backupName) => {
-  return await callPython('restore_backup', { backupName });
+ipcMain.handle('restore-backup', async (_event, backupName, restoreData) => {
+  return await callPython('restore_backup', { backupName, restoreData: !!restoreData });
 });
 
 ipcMain.handle('delete-backup', async (_event, backupName) => {
@@ -234,3 +253,481 @@ ipcMain.handle('delete-backup', async (_event, backupName) => {
 ipcMain.handle('get-backup-path', async () => {
   return await callPython('get_backup_path');
 });
+
+// System Health
+ipcMain.handle('get-system-health', async () => {
+  return await callPython('get_system_health');
+});
+
+ipcMain.handle('start-health-monitor', async (_event, intervalSeconds) => {
+  return await callPython('start_health_monitor', { intervalSeconds: intervalSeconds || 5 });
+});
+
+ipcMain.handle('stop-health-monitor', async () => {
+  return await callPython('stop_health_monitor');
+});
+
+ipcMain.handle('clear-health-cache', async (_event, serial) => {
+  return await callPython('clear_health_cache', { serial });
+});
+
+// AI Usage
+ipcMain.handle('get-ai-usage-stats', async () => {
+  return await callPython('get_ai_usage_stats');
+});
+
+ipcMain.handle('set-ai-budget', async (_event, monthlyBudgetUsd) => {
+  return await callPython('set_ai_budget', { monthlyBudgetUsd });
+});
+
+// Settings
+ipcMain.handle('get-settings', async () => {
+  return await callPython('get_settings');
+});
+
+ipcMain.handle('update-settings', async (_event, settings) => {
+  return await callPython('update_settings', { settings });
+});
+
+ipcMain.handle('reset-settings', async () => {
+  return await callPython('reset_settings');
+});
+
+// App Labels
+ipcMain.handle('resolve-app-labels', async (_event, packages) => {
+  return await callPython('resolve_app_labels', { packages });
+});
+
+// Package Families
+ipcMain.handle('list-package-families', async (_event, type, includeSizes) => {
+  return await callPython('list_package_families', { type, includeSizes });
+});
+
+// Package Cache
+ipcMain.handle('get-cached-packages', async (_event, query) => {
+  return await callPython('get_cached_packages', query || {});
+});
+
+// Package Search
+ipcMain.handle('search-packages', async (_event, query, limit) => {
+  return await callPython('search_packages', { query, limit });
+});
+
+// Privacy
+ipcMain.handle('get-privacy-report', async () => {
+  return await callPython('get_privacy_report');
+});
+
+// Performance
+ipcMain.handle('get-performance-report', async () => {
+  return await callPython('get_performance_report');
+});
+
+ipcMain.handle('set-animation-scale', async (_event, scale) => {
+  return await callPython('set_animation_scale', { scale });
+});
+
+ipcMain.handle('trim-caches', async () => {
+  return await callPython('trim_caches');
+});
+
+ipcMain.handle('restrict-background', async (_event, packageName, restricted) => {
+  return await callPython('restrict_background', { packageName, restricted });
+});
+
+// Optimization
+ipcMain.handle('run-optimization', async (_event, plan, confirmation) => {
+  return await callPython('run_optimization', { plan, confirmation });
+});
+
+// Undo
+ipcMain.handle('get-undo-stack', async () => {
+  return await callPython('get_undo_stack');
+});
+
+ipcMain.handle('undo', async (_event, count, confirmation) => {
+  return await callPython('undo', { count, confirmation });
+});
+
+// Compatibility
+ipcMain.handle('get-compatibility', async () => {
+  return await callPython('get_compatibility');
+});
+
+// Capabilities
+ipcMain.handle('probe-device-capabilities', async () => {
+  return await callPython('probe_device_capabilities');
+});
+
+// Device Management
+ipcMain.handle('get-device-management', async () => {
+  return await callPython('get_device_management');
+});
+
+// App Data
+ipcMain.handle('wipe-package-data', async (_event, packageName, confirmation) => {
+  return await callPython('wipe_package_data', { packageName, confirmation });
+});
+
+// Backup Groups
+ipcMain.handle('list-backup-groups', async () => {
+  return await callPython('list_backup_groups');
+});
+
+// Backup Retention
+ipcMain.handle('preview-retention-cleanup', async (_event, keepPerDevice, maxAgeDays) => {
+  return await callPython('preview_retention_cleanup', { keepPerDevice, maxAgeDays });
+});
+
+ipcMain.handle('run-retention-cleanup', async () => {
+  return await callPython('run_retention_cleanup');
+});
+
+// Full Backups
+ipcMain.handle('create-full-backup', async (_event, packages, deviceInfo) => {
+  return await callPython('create_full_backup', { packages, deviceInfo });
+});
+
+// Session Report
+ipcMain.handle('export-session-report', async () => {
+  return await callPython('export_session_report');
+});
+
+// Package Streaming
+ipcMain.handle('stream-packages', async (_event, type) => {
+  return await callPython('stream_packages', { type });
+});
+
+ipcMain.handle('ack-package-chunk', async (_event, jobId, index) => {
+  return await callPython('ack_package_chunk', { jobId, index });
+});
+
+ipcMain.handle('cancel-package-stream', async (_event, jobId) => {
+  return await callPython('cancel_package_stream', { jobId });
+});
+
+// Device Nicknames
+ipcMain.handle('set-device-nickname', async (_event, serial, name) => {
+  return await callPython('set_device_nickname', { serial, name });
+});
+
+// Network
+ipcMain.handle('test-connection', async () => {
+  return await callPython('test_connection');
+});
+
+// Device Restart
+ipcMain.handle('restart-system-ui', async () => {
+  return await callPython('restart_system_ui');
+});
+
+ipcMain.handle('restart-launcher', async () => {
+  return await callPython('restart_launcher');
+});
+
+ipcMain.handle('soft-reboot', async (_event, confirmed) => {
+  return await callPython('soft_reboot', { confirmed });
+});
+
+ipcMain.handle('reboot-device', async (_event, mode, confirmed) => {
+  return await callPython('reboot_device', { mode, confirmed });
+});
+
+// Trial Disable
+ipcMain.handle('start-trial', async (_event, packages, durationHours, confirmation) => {
+  return await callPython('start_trial', { packages, durationHours, confirmation });
+});
+
+ipcMain.handle('list-trials', async (_event, includeEnded) => {
+  return await callPython('list_trials', { includeEnded });
+});
+
+ipcMain.handle('keep-trial', async (_event, trialId) => {
+  return await callPython('keep_trial', { trialId });
+});
+
+ipcMain.handle('revert-trial', async (_event, trialId) => {
+  return await callPython('revert_trial', { trialId });
+});
+
+// Selection Rules
+ipcMain.handle('validate-selection', async (_event, packages) => {
+  return await callPython('validate_selection', { packages });
+});
+
+// Special Access
+ipcMain.handle('get-special-access', async () => {
+  return await callPython('get_special_access');
+});
+
+ipcMain.handle('revoke-special-access', async (_event, packageName, access) => {
+  return await callPython('revoke_special_access', { packageName, access });
+});
+
+// Service Audit
+ipcMain.handle('get-service-audit', async () => {
+  return await callPython('get_service_audit');
+});
+
+// Tutorial
+ipcMain.handle('get-tutorial-state', async () => {
+  return await callPython('get_tutorial_state');
+});
+
+ipcMain.handle('advance-tutorial', async (_event, skip) => {
+  return await callPython('advance_tutorial', { skip });
+});
+
+ipcMain.handle('dismiss-tutorial', async (_event, dismissed) => {
+  return await callPython('dismiss_tutorial', { dismissed });
+});
+
+// Raw Dumps
+ipcMain.handle('list-raw-dump-services', async () => {
+  return await callPython('list_raw_dump_services');
+});
+
+ipcMain.handle('get-raw-dump', async (_event, service, packageName, page, pageLines, refresh) => {
+  return await callPython('get_raw_dump', { service, packageName, page, pageLines, refresh });
+});
+
+// Parser Diagnostics
+ipcMain.handle('list-parser-failures', async () => {
+  return await callPython('list_parser_failures');
+});
+
+ipcMain.handle('export-diagnostics', async (_event, includeOutput) => {
+  return await callPython('export_diagnostics', { includeOutput });
+});
+
+ipcMain.handle('clear-parser-failures', async () => {
+  return await callPython('clear_parser_failures');
+});
+
+// Prefetch
+ipcMain.handle('start-prefetch', async () => {
+  return await callPython('start_prefetch');
+});
+
+ipcMain.handle('get-prefetch-status', async () => {
+  return await callPython('get_prefetch_status');
+});
+
+// Cache diagnostics
+ipcMain.handle('get-cache-diagnostics', async () => {
+  return await callPython('get_cache_diagnostics');
+});
+
+// Device class
+ipcMain.handle('get-device-class', async () => {
+  return await callPython('get_device_class');
+});
+
+ipcMain.handle('get-class-presets', async () => {
+  return await callPython('get_class_presets');
+});
+
+// Stay Awake
+ipcMain.handle('hold-stay-awake', async (_event, reason) => {
+  return await callPython('hold_stay_awake', { reason });
+});
+
+ipcMain.handle('release-stay-awake', async (_event, holdId) => {
+  return await callPython('release_stay_awake', { holdId });
+});
+
+ipcMain.handle('get-stay-awake-status', async () => {
+  return await callPython('get_stay_awake_status');
+});
+
+// Job Journal
+ipcMain.handle('start-job', async (_event, kind, packages, options, confirmation) => {
+  return await callPython('start_job', { kind, packages, options, confirmation });
+});
+
+ipcMain.handle('finish-job', async (_event, jobId) => {
+  return await callPython('finish_job', { jobId });
+});
+
+ipcMain.handle('get-incomplete-jobs', async () => {
+  return await callPython('get_incomplete_jobs');
+});
+
+ipcMain.handle('resume-job', async (_event, jobId) => {
+  return await callPython('resume_job', { jobId });
+});
+
+ipcMain.handle('discard-job', async (_event, jobId) => {
+  return await callPython('discard_job', { jobId });
+});
+
+// Package Signatures
+ipcMain.handle('get-package-signature', async (_event, packageName) => {
+  return await callPython('get_package_signature', { packageName });
+});
+
+// Theme Overlays
+ipcMain.handle('list-overlays', async () => {
+  return await callPython('list_overlays');
+});
+
+ipcMain.handle('set-overlay-enabled', async (_event, overlayId, enabled) => {
+  return await callPython('set_overlay_enabled', { overlayId, enabled });
+});
+
+// APEX modules
+ipcMain.handle('list-apex-modules', async () => {
+  return await callPython('list_apex_modules');
+});
+
+// Analysis export
+ipcMain.handle('export-analyses', async (_event, packages, format) => {
+  return await callPython('export_analyses', { packages, format });
+});
+
+// Weekly digest
+ipcMain.handle('get-weekly-digest', async () => {
+  return await callPython('get_weekly_digest');
+});
+
+// Emergency recovery
+ipcMain.handle('emergency-recover', async (_event, confirmed, hours, restoreBackup, safeMode, confirmation) => {
+  return await callPython('emergency_recover', { confirmed, hours, restoreBackup, safeMode, confirmation });
+});
+
+// Backup Transfer
+ipcMain.handle('preview-backup-transfer', async (_event, backupName) => {
+  return await callPython('preview_backup_transfer', { backupName });
+});
+
+ipcMain.handle('apply-backup-transfer', async (_event, backupName) => {
+  return await callPython('apply_backup_transfer', { backupName });
+});
+
+// Unused Apps
+ipcMain.handle('get-unused-app-suggestions', async (_event, days) => {
+  return await callPython('get_unused_app_suggestions', { days });
+});
+
+// Notifications
+ipcMain.handle('mute-notifications', async (_event, packageName, muted) => {
+  return await callPython('mute_notifications', { packageName, muted });
+});
+
+// Events
+ipcMain.handle('subscribe-with-replay', async (_event, kinds) => {
+  return await callPython('subscribe_with_replay', { kinds });
+});
+
+// Runtime permissions
+ipcMain.handle('revoke-permission', async (_event, packageName, permission) => {
+  return await callPython('revoke_permission', { packageName, permission });
+});
+
+ipcMain.handle('grant-permission', async (_event, packageName, permission) => {
+  return await callPython('grant_permission', { packageName, permission });
+});
+
+// Clipboard Audit
+ipcMain.handle('get-clipboard-audit', async () => {
+  return await callPython('get_clipboard_audit');
+});
+
+// OEM database
+ipcMain.handle('get-oem-database', async () => {
+  return await callPython('get_oem_database');
+});
+
+// Crash reports
+ipcMain.handle('list-crash-reports', async () => {
+  return await callPython('list_crash_reports');
+});
+
+ipcMain.handle('clear-crash-reports', async () => {
+  return await callPython('clear_crash_reports');
+});
+
+// USB permissions (Linux)
+ipcMain.handle('get-usb-permission-help', async () => {
+  return await callPython('get_usb_permission_help');
+});
+
+ipcMain.handle('install-udev-rule', async (_event, vendorId) => {
+  return await callPython('install_udev_rule', { vendorId });
+});
+
+// Battery trend
+ipcMain.handle('get-battery-trend', async (_event, packageName, days) => {
+  return await callPython('get_battery_trend', { packageName, days });
+});
+
+// Charging sessions
+ipcMain.handle('get-charging-sessions', async (_event, limit) => {
+  return await callPython('get_charging_sessions', { limit });
+});
+
+// Screen mirror
+ipcMain.handle('launch-screen-mirror', async (_event, serial, download) => {
+  return await callPython('launch_screen_mirror', { serial, download });
+});
+
+ipcMain.handle('get-screen-mirror-status', async () => {
+  return await callPython('get_screen_mirror_status');
+});
+
+ipcMain.handle('stop-screen-mirror', async (_event, serial) => {
+  return await callPython('stop_screen_mirror', { serial });
+});
+
+// Guided actions
+ipcMain.handle('list-guided-actions', async () => {
+  return await callPython('list_guided_actions');
+});
+
+ipcMain.handle('open-settings-page', async (_event, page, packageName) => {
+  return await callPython('open_settings_page', { page, packageName });
+});
+
+ipcMain.handle('press-guided-key', async (_event, key) => {
+  return await callPython('press_guided_key', { key });
+});
+
+ipcMain.handle('guided-tap', async (_event, target) => {
+  return await callPython('guided_tap', { target });
+});
+
+// Device pages
+ipcMain.handle('open-app-info', async (_event, packageName) => {
+  return await callPython('open_app_info', { packageName });
+});
+
+ipcMain.handle('open-in-play-store', async (_event, packageName) => {
+  return await callPython('open_in_play_store', { packageName });
+});
+
+// Remote control
+ipcMain.handle('get-remote-status', async () => {
+  return await callPython('get_remote_status');
+});
+
+ipcMain.handle('regenerate-remote-token', async () => {
+  return await callPython('regenerate_remote_token');
+});
+
+// Confirmation Policy
+ipcMain.handle('get-confirmation-policy', async () => {
+  return await callPython('get_confirmation_policy');
+});
+
+ipcMain.handle('set-confirmation-role', async (_event, role) => {
+  return await callPython('set_confirmation_role', { role });
+});
+
+ipcMain.handle('get-confirmation-requirement', async (_event, operation, packages) => {
+  return await callPython('get_confirmation_requirement', { operation, packages });
+});
+
+// Profiles
+ipcMain.handle('list-profiles', async () => {
+  return await callPython('list_profiles');
+});
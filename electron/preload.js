@@ -7,8 +7,8 @@ contextBridge.exposeInMainWorld('electronAPI', {
   getDeviceInfo: () => ipcRenderer.invoke('get-device-info'),
   
   // Package operations
-  listPackages: (type) => ipcRenderer.invoke('list-packages', type),
-  uninstallPackage: (packageName) => ipcRenderer.invoke('uninstall-package', packageName),
+  listPackages: (type, compact) => ipcRenderer.invoke('list-packages', type, compact),
+  uninstallPackage: (packageName, keepData, jobId, confirmation) => ipcRenderer.invoke('uninstall-package', packageName, keepData, jobId, confirmation),
   reinstallPackage: (packageName) => ipcRenderer.invoke('reinstall-package', packageName),
   
   // AI operations
@@ -17,12 +17,240 @@ contextBridge.exposeInMainWorld('electronAPI', {
   
   // OpenClaw integration
   parseChatCommand: (message) => ipcRenderer.invoke('parse-chat-command', message),
-  executeAction: (executionResult, confirmed) => ipcRenderer.invoke('execute-action', executionResult, confirmed),
+  executeAction: (executionResult, confirmed, confirmation) => ipcRenderer.invoke('execute-action', executionResult, confirmed, confirmation),
   
   // Backup operations
-  createBackup: (packages, deviceInfo) => ipcRenderer.invoke('create-backup', packages, deviceInfo),
-  listBackups: () => ipcRenderer.invoke('list-backups'),
-  restoreBackup: (backupName) => ipcRenderer.invoke('restore-backup', backupName),
+  createBackup: (packages, deviceInfo, includeData) => ipcRenderer.invoke('create-backup', packages, deviceInfo, includeData),
+  listBackups: (allDevices) => ipcRenderer.invoke('list-backups', allDevices),
+  restoreBackup: (backupName, restoreData) => ipcRenderer.invoke('restore-backup', backupName, restoreData),
   deleteBackup: (backupName) => ipcRenderer.invoke('delete-backup', backupName),
   getBackupPath: () => ipcRenderer.invoke('get-backup-path'),
+
+  // System health
+  getSystemHealth: () => ipcRenderer.invoke('get-system-health'),
+  startHealthMonitor: (intervalSeconds) => ipcRenderer.invoke('start-health-monitor', intervalSeconds),
+  stopHealthMonitor: () => ipcRenderer.invoke('stop-health-monitor'),
+  clearHealthCache: (serial) => ipcRenderer.invoke('clear-health-cache', serial),
+
+  // AI Usage
+  getAiUsageStats: () => ipcRenderer.invoke('get-ai-usage-stats'),
+  setAiBudget: (monthlyBudgetUsd) => ipcRenderer.invoke('set-ai-budget', monthlyBudgetUsd),
+
+  // Settings
+  getSettings: () => ipcRenderer.invoke('get-settings'),
+  updateSettings: (settings) => ipcRenderer.invoke('update-settings', settings),
+  resetSettings: () => ipcRenderer.invoke('reset-settings'),
+
+  // App Labels
+  resolveAppLabels: (packages) => ipcRenderer.invoke('resolve-app-labels', packages),
+
+  // Package Families
+  listPackageFamilies: (type, includeSizes) => ipcRenderer.invoke('list-package-families', type, includeSizes),
+
+  // Package Cache
+  getCachedPackages: (query) => ipcRenderer.invoke('get-cached-packages', query),
+
+  // Package Search
+  searchPackages: (query, limit) => ipcRenderer.invoke('search-packages', query, limit),
+
+  // Privacy
+  getPrivacyReport: () => ipcRenderer.invoke('get-privacy-report'),
+
+  // Performance
+  getPerformanceReport: () => ipcRenderer.invoke('get-performance-report'),
+  setAnimationScale: (scale) => ipcRenderer.invoke('set-animation-scale', scale),
+  trimCaches: () => ipcRenderer.invoke('trim-caches'),
+  restrictBackground: (packageName, restricted) => ipcRenderer.invoke('restrict-background', packageName, restricted),
+
+  // Optimization
+  runOptimization: (plan, confirmation) => ipcRenderer.invoke('run-optimization', plan, confirmation),
+
+  // Undo
+  getUndoStack: () => ipcRenderer.invoke('get-undo-stack'),
+  undo: (count, confirmation) => ipcRenderer.invoke('undo', count, confirmation),
+
+  // Compatibility
+  getCompatibility: () => ipcRenderer.invoke('get-compatibility'),
+
+  // Capabilities
+  probeDeviceCapabilities: () => ipcRenderer.invoke('probe-device-capabilities'),
+
+  // Device Management
+  getDeviceManagement: () => ipcRenderer.invoke('get-device-management'),
+
+  // App Data
+  wipePackageData: (packageName, confirmation) => ipcRenderer.invoke('wipe-package-data', packageName, confirmation),
+
+  // Backup Groups
+  listBackupGroups: () => ipcRenderer.invoke('list-backup-groups'),
+
+  // Backup Retention
+  previewRetentionCleanup: (keepPerDevice, maxAgeDays) => ipcRenderer.invoke('preview-retention-cleanup', keepPerDevice, maxAgeDays),
+  runRetentionCleanup: () => ipcRenderer.invoke('run-retention-cleanup'),
+
+  // Full Backups
+  createFullBackup: (packages, deviceInfo) => ipcRenderer.invoke('create-full-backup', packages, deviceInfo),
+
+  // Session Report
+  exportSessionReport: () => ipcRenderer.invoke('export-session-report'),
+
+  // Package Streaming
+  startPackageStream: (type) => ipcRenderer.invoke('stream-packages', type),
+  ackPackageChunk: (jobId, index) => ipcRenderer.invoke('ack-package-chunk', jobId, index),
+  cancelPackageStream: (jobId) => ipcRenderer.invoke('cancel-package-stream', jobId),
+
+  // Device Nicknames
+  setDeviceNickname: (serial, name) => ipcRenderer.invoke('set-device-nickname', serial, name),
+
+  // Network
+  testConnection: () => ipcRenderer.invoke('test-connection'),
+
+  // Device Restart
+  restartSystemUi: () => ipcRenderer.invoke('restart-system-ui'),
+  restartLauncher: () => ipcRenderer.invoke('restart-launcher'),
+  softReboot: (confirmed) => ipcRenderer.invoke('soft-reboot', confirmed),
+  rebootDevice: (mode, confirmed) => ipcRenderer.invoke('reboot-device', mode, confirmed),
+
+  // Trial Disable
+  startTrial: (packages, durationHours, confirmation) => ipcRenderer.invoke('start-trial', packages, durationHours, confirmation),
+  listTrials: (includeEnded) => ipcRenderer.invoke('list-trials', includeEnded),
+  keepTrial: (trialId) => ipcRenderer.invoke('keep-trial', trialId),
+  revertTrial: (trialId) => ipcRenderer.invoke('revert-trial', trialId),
+
+  // Selection Rules
+  validateSelection: (packages) => ipcRenderer.invoke('validate-selection', packages),
+
+  // Special Access
+  getSpecialAccess: () => ipcRenderer.invoke('get-special-access'),
+  revokeSpecialAccess: (packageName, access) => ipcRenderer.invoke('revoke-special-access', packageName, access),
+
+  // Service Audit
+  getServiceAudit: () => ipcRenderer.invoke('get-service-audit'),
+
+  // Tutorial
+  getTutorialState: () => ipcRenderer.invoke('get-tutorial-state'),
+  advanceTutorial: (skip) => ipcRenderer.invoke('advance-tutorial', skip),
+  dismissTutorial: (dismissed) => ipcRenderer.invoke('dismiss-tutorial', dismissed),
+
+  // Raw Dumps
+  listRawDumpServices: () => ipcRenderer.invoke('list-raw-dump-services'),
+  getRawDump: (service, packageName, page, pageLines, refresh) => ipcRenderer.invoke('get-raw-dump', service, packageName, page, pageLines, refresh),
+
+  // Parser Diagnostics
+  listParserFailures: () => ipcRenderer.invoke('list-parser-failures'),
+  exportDiagnostics: (includeOutput) => ipcRenderer.invoke('export-diagnostics', includeOutput),
+  clearParserFailures: () => ipcRenderer.invoke('clear-parser-failures'),
+
+  // Prefetch
+  startPrefetch: () => ipcRenderer.invoke('start-prefetch'),
+  getPrefetchStatus: () => ipcRenderer.invoke('get-prefetch-status'),
+
+  // Cache diagnostics
+  getCacheDiagnostics: () => ipcRenderer.invoke('get-cache-diagnostics'),
+
+  // Device class
+  getDeviceClass: () => ipcRenderer.invoke('get-device-class'),
+  getClassPresets: () => ipcRenderer.invoke('get-class-presets'),
+
+  // Stay Awake
+  holdStayAwake: (reason) => ipcRenderer.invoke('hold-stay-awake', reason),
+  releaseStayAwake: (holdId) => ipcRenderer.invoke('release-stay-awake', holdId),
+  getStayAwakeStatus: () => ipcRenderer.invoke('get-stay-awake-status'),
+
+  // Job Journal
+  startJob: (kind, packages, options, confirmation) => ipcRenderer.invoke('start-job', kind, packages, options, confirmation),
+  finishJob: (jobId) => ipcRenderer.invoke('finish-job', jobId),
+  getIncompleteJobs: () => ipcRenderer.invoke('get-incomplete-jobs'),
+  resumeJob: (jobId) => ipcRenderer.invoke('resume-job', jobId),
+  discardJob: (jobId) => ipcRenderer.invoke('discard-job', jobId),
+
+  // Package Signatures
+  getPackageSignature: (packageName) => ipcRenderer.invoke('get-package-signature', packageName),
+
+  // Theme Overlays
+  listOverlays: () => ipcRenderer.invoke('list-overlays'),
+  setOverlayEnabled: (overlayId, enabled) => ipcRenderer.invoke('set-overlay-enabled', overlayId, enabled),
+
+  // APEX modules
+  listApexModules: () => ipcRenderer.invoke('list-apex-modules'),
+
+  // Analysis export
+  exportAnalyses: (packages, format) => ipcRenderer.invoke('export-analyses', packages, format),
+
+  // Weekly digest
+  getWeeklyDigest: () => ipcRenderer.invoke('get-weekly-digest'),
+
+  // Emergency recovery
+  emergencyRecover: (confirmed, hours, restoreBackup, safeMode, confirmation) => ipcRenderer.invoke('emergency-recover', confirmed, hours, restoreBackup, safeMode, confirmation),
+
+  // Backup Transfer
+  previewBackupTransfer: (backupName) => ipcRenderer.invoke('preview-backup-transfer', backupName),
+  applyBackupTransfer: (backupName) => ipcRenderer.invoke('apply-backup-transfer', backupName),
+
+  // Unused Apps
+  getUnusedAppSuggestions: (days) => ipcRenderer.invoke('get-unused-app-suggestions', days),
+
+  // Notifications
+  muteNotifications: (packageName, muted) => ipcRenderer.invoke('mute-notifications', packageName, muted),
+
+  // Events
+  subscribeWithReplay: (kinds) => ipcRenderer.invoke('subscribe-with-replay', kinds),
+
+  // Runtime permissions
+  revokePermission: (packageName, permission) => ipcRenderer.invoke('revoke-permission', packageName, permission),
+  grantPermission: (packageName, permission) => ipcRenderer.invoke('grant-permission', packageName, permission),
+
+  // Clipboard Audit
+  getClipboardAudit: () => ipcRenderer.invoke('get-clipboard-audit'),
+
+  // OEM database
+  getOemDatabase: () => ipcRenderer.invoke('get-oem-database'),
+
+  // Crash reports
+  listCrashReports: () => ipcRenderer.invoke('list-crash-reports'),
+  clearCrashReports: () => ipcRenderer.invoke('clear-crash-reports'),
+
+  // USB permissions (Linux)
+  getUsbPermissionHelp: () => ipcRenderer.invoke('get-usb-permission-help'),
+  installUdevRule: (vendorId) => ipcRenderer.invoke('install-udev-rule', vendorId),
+
+  // Battery trend
+  getBatteryTrend: (packageName, days) => ipcRenderer.invoke('get-battery-trend', packageName, days),
+
+  // Charging sessions
+  getChargingSessions: (limit) => ipcRenderer.invoke('get-charging-sessions', limit),
+
+  // Screen mirror
+  launchScreenMirror: (serial, download) => ipcRenderer.invoke('launch-screen-mirror', serial, download),
+  getScreenMirrorStatus: () => ipcRenderer.invoke('get-screen-mirror-status'),
+  stopScreenMirror: (serial) => ipcRenderer.invoke('stop-screen-mirror', serial),
+
+  // Guided actions
+  listGuidedActions: () => ipcRenderer.invoke('list-guided-actions'),
+  openSettingsPage: (page, packageName) => ipcRenderer.invoke('open-settings-page', page, packageName),
+  pressGuidedKey: (key) => ipcRenderer.invoke('press-guided-key', key),
+  guidedTap: (target) => ipcRenderer.invoke('guided-tap', target),
+
+  // Device pages
+  openAppInfo: (packageName) => ipcRenderer.invoke('open-app-info', packageName),
+  openInPlayStore: (packageName) => ipcRenderer.invoke('open-in-play-store', packageName),
+
+  // Remote control
+  getRemoteStatus: () => ipcRenderer.invoke('get-remote-status'),
+  regenerateRemoteToken: () => ipcRenderer.invoke('regenerate-remote-token'),
+
+  // Confirmation Policy
+  getConfirmationPolicy: () => ipcRenderer.invoke('get-confirmation-policy'),
+  setConfirmationRole: (role) => ipcRenderer.invoke('set-confirmation-role', role),
+  getConfirmationRequirement: (operation, packages) => ipcRenderer.invoke('get-confirmation-requirement', operation, packages),
+
+  // Profiles
+  listProfiles: () => ipcRenderer.invoke('list-profiles'),
+
+  // Backend events (health updates etc.) – returns an unsubscribe function
+  onBackendEvent: (callback) => {
+    const listener = (_event, message) => callback(message);
+    ipcRenderer.on('backend-event', listener);
+    return () => ipcRenderer.removeListener('backend-event', listener);
+  },
 });
@@ -1,13 +1,34 @@
 import React, { createContext, useContext, useState, useEffect, ReactNode } from 'react';
-import { api } from './utils/api';
+import {
+  api,
+  BackendEventKind,
+  Confirmation,
+  ConfirmationRequirement,
+  ExternalPackageChange,
+  JournaledJob,
+  SelectionViolation,
+} from './utils/api';
 import { motion, AnimatePresence } from 'framer-motion';
 import DevicePanel from './components/DevicePanel';
 import PackageList from './components/PackageList';
 import BackupManager from './components/BackupManager';
 import UninstallDialog from './components/UninstallDialog';
+import ConfirmDialog from './components/ConfirmDialog';
+import PolicyConfirmationFields, {
+  EMPTY_TYPED_CONFIRMATION,
+  TypedConfirmation,
+  typedConfirmationComplete,
+} from './components/PolicyConfirmationFields';
 import ThemeSelector from './components/ThemeSelector';
 import FloatingChat from './components/FloatingChat';
 import AIPackageAdvisor from './components/AIPackageAdvisor';
+import TrialBanner from './components/TrialBanner';
+import WeeklyDigestCard from './components/WeeklyDigestCard';
+import UnusedAppsCard from './components/UnusedAppsCard';
+import ResumeJobBanner from './components/ResumeJobBanner';
+import TutorialCard from './components/TutorialCard';
+import ParserFailureNotice from './components/ParserFailureNotice';
+import ChargingInsightsCard from './components/ChargingInsightsCard';
 import { THEMES, ThemeName, applyTheme } from './utils/themes';
 import {
   FiDownload,
@@ -19,6 +40,8 @@ import {
   FiAlertTriangle,
   FiZap,
   FiXOctagon,
+  FiClock,
+  FiShare2,
 } from 'react-icons/fi';
 import { 
   buttonHover, 
@@ -95,6 +118,11 @@ interface Notification {
 
 let notificationId = 0;
 
+// Events whose last instance describes current state, asked for again once the window has loaded
+const REPLAYED_EVENT_KINDS: BackendEventKind[] = [
+  'health_update', 'prefetch_progress', 'prefetch_complete', 'weekly_digest_ready', 'parser_failed', 'trial_reverted',
+];
+
 // Package Stats
 interface PackageStats {
   total: number;
@@ -120,8 +148,13 @@ const App: React.FC = () => {
   });
   const [showBackupManager, setShowBackupManager] = useState(false);
   const [confirmDialogOpen, setConfirmDialogOpen] = useState(false);
+  const [selectionViolations, setSelectionViolations] = useState<SelectionViolation[]>([]);
+  // What the confirmation policy asks before removing or disabling the selection
+  const [uninstallRequirement, setUninstallRequirement] = useState<ConfirmationRequirement | null>(null);
+  const [trialRequirement, setTrialRequirement] = useState<ConfirmationRequirement | null>(null);
+  const [trialTyped, setTrialTyped] = useState<TypedConfirmation>(EMPTY_TYPED_CONFIRMATION);
   const [filterBySafety, setFilterBySafety] = useState<string | null>(null);
-  const [packageData, setPackageData] = useState<Array<{packageName: string; safetyLevel: string}>>([]);
+  const [packageData, setPackageData] = useState<Array<{packageName: string; safetyLevel: string; partition?: string | null}>>([]);
   const [aiAdvisorPackage, setAiAdvisorPackage] = useState<string | null>(null);
   const [refreshTrigger, setRefreshTrigger] = useState(0);
   
@@ -141,20 +174,99 @@ const App: React.FC = () => {
     }, 5000);
   };
 
+  // Apps installed or removed on the phone itself; the backend already dropped its cached listing
+  useEffect(() => {
+    return api.onBackendEvent((event) => {
+      if (event.kind !== 'external_package_change') return;
+      const change = event.payload as ExternalPackageChange;
+      const parts = [
+        change.installed.length ? `${change.installed.length} installed` : '',
+        change.removed.length ? `${change.removed.length} removed` : '',
+      ].filter(Boolean);
+      addNotification(`ℹ️ Apps changed on the device: ${parts.join(', ')}`, 'info');
+      setSelectedPackages((prev) => new Set([...prev].filter((p) => !change.removed.includes(p))));
+      handleRefresh();
+    });
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  // Child components subscribe before this effect runs; resend what the backend emitted while the window loaded
+  useEffect(() => {
+    api.subscribeWithReplay(REPLAYED_EVENT_KINDS);
+  }, []);
+
   // Update stats when packages change
   useEffect(() => {
     setStats((prev) => ({ ...prev, selected: selectedPackages.size }));
   }, [selectedPackages]);
 
   // Handle uninstall selected
-  const handleUninstallSelected = () => {
+  const handleUninstallSelected = async () => {
     if (selectedPackages.size === 0) {
       addNotification('No packages selected', 'error');
       return;
     }
+    // Packages safe on their own can together remove every keyboard, launcher...
+    try {
+      const validation = await api.validateSelection(Array.from(selectedPackages));
+      setSelectionViolations(validation.violations);
+    } catch (error) {
+      setSelectionViolations([]);
+    }
+    const requirement = await api.getConfirmationRequirement('uninstall', Array.from(selectedPackages)).catch(() => null);
+    setUninstallRequirement(requirement);
     setConfirmDialogOpen(true);
   };
 
+  // Disable the selection for a day; the backend re-enables it unless the user keeps it off
+  const handleTrialSelected = async () => {
+    if (selectedPackages.size === 0) {
+      addNotification('No packages selected', 'error');
+      return;
+    }
+    const requirement = await api.getConfirmationRequirement('disable', Array.from(selectedPackages)).catch(() => null);
+    if (requirement && requirement.level !== 'none') {
+      setTrialTyped(EMPTY_TYPED_CONFIRMATION);
+      setTrialRequirement(requirement);
+      return;
+    }
+    await startTrial();
+  };
+
+  const startTrial = async (confirmation?: Confirmation) => {
+    setTrialRequirement(null);
+    const result = await api.startTrial(Array.from(selectedPackages), 24, confirmation);
+    addNotification(result.success ? `✅ ${result.message}` : `❌ ${result.message}`, result.success ? 'success' : 'error');
+    if (result.success) {
+      setSelectedPackages(new Set());
+      handleRefresh();
+    }
+  };
+
+  const confirmTrial = () => {
+    if (!trialRequirement) return;
+    startTrial({
+      confirmed: true,
+      typedPackages: trialRequirement.packagesToType.map((name) => (trialTyped.packages[name] ?? '').trim()),
+      phrase: trialRequirement.phrase ? trialTyped.phrase.trim() : undefined,
+    });
+  };
+
+  // Copy a Markdown digest of the selection's AI and local ratings, for asking forums for a second opinion
+  const handleShareSelected = async () => {
+    const result = await api.exportAnalyses(Array.from(selectedPackages), 'markdown');
+    if (!result.success || !result.report) {
+      addNotification(`❌ ${result.message}`, 'error');
+      return;
+    }
+    try {
+      await navigator.clipboard.writeText(result.report);
+      addNotification(`✅ Review copied to the clipboard, also saved to ${result.path}`, 'success');
+    } catch {
+      addNotification(`✅ ${result.message}`, 'success');
+    }
+  };
+
   // Handle backup selected packages
   const handleBackupSelected = async () => {
     if (selectedPackages.size === 0) {
@@ -177,35 +289,69 @@ const App: React.FC = () => {
     }
   };
 
-  // Confirm uninstall action
-  const confirmUninstall = async () => {
-    setConfirmDialogOpen(false);
-    
+  // Uninstall packages one by one; with a journaled job each result is saved so the batch can be resumed
+  const runUninstallBatch = async (packages: string[], keepData: boolean, jobId?: string, confirmation?: Confirmation) => {
     let successCount = 0;
     let failCount = 0;
+    let updateNote: string | undefined;
+    // Keep the device from sleeping mid-batch; released however the loop ends
+    const hold = packages.length > 1 ? await api.holdStayAwake('Batch uninstall').catch(() => null) : null;
 
-    for (const packageName of selectedPackages) {
-      try {
-        const result = await api.uninstallPackage(packageName);
+    try {
+      for (const packageName of packages) {
+        try {
+          const result = await api.uninstallPackage(packageName, keepData, jobId, confirmation);
 
-        if (result.success) {
-          successCount++;
-        } else {
+          if (result.confirmationRequired || result.blocked) {
+            addNotification(`❌ ${result.message}`, 'error');
+            break;
+          }
+          if (result.success) {
+            successCount++;
+            updateNote = updateNote ?? result.updateNote;
+          } else {
+            failCount++;
+          }
+        } catch (error) {
           failCount++;
         }
-      } catch (error) {
-        failCount++;
       }
+    } finally {
+      if (hold?.holdId) api.releaseStayAwake(hold.holdId).catch(() => undefined);
+      // Packages left pending (device unplugged) keep the job on offer for resuming
+      if (jobId) await api.finishJob(jobId).catch(() => undefined);
     }
 
     if (successCount > 0) {
       addNotification(`✅ Successfully uninstalled ${successCount} package(s)`, 'success');
+      if (updateNote) addNotification(`ℹ️ ${updateNote}`, 'info');
     }
     if (failCount > 0) {
       addNotification(`❌ Failed to uninstall ${failCount} package(s)`, 'error');
     }
+    handleRefresh();
+  };
+
+  // Confirm uninstall action
+  const confirmUninstall = async (keepData: boolean, confirmation: Confirmation) => {
+    setConfirmDialogOpen(false);
+    const packages = Array.from(selectedPackages);
 
+    const job = packages.length > 1
+      ? await api.startJob('uninstall', packages, { keepData }, confirmation).catch(() => null)
+      : null;
+    // The backend enforces the policy too; nothing is removed when it refuses the batch
+    if (job && (job.confirmationRequired || job.blocked)) {
+      addNotification(`❌ ${job.message}`, 'error');
+      return;
+    }
     setSelectedPackages(new Set());
+    await runUninstallBatch(packages, keepData, job?.jobId, confirmation);
+  };
+
+  const resumeJob = async (job: JournaledJob) => {
+    addNotification(`Resuming removal of ${job.pending.length} package(s)`, 'info');
+    await runUninstallBatch(job.pending, !!job.options.keepData, job.id);
   };
 
   return (
@@ -325,6 +471,9 @@ const App: React.FC = () => {
           borderRadius: '12px'
         }}>
           <DevicePanel onRefresh={handleRefresh} />
+          <TutorialCard refreshTrigger={refreshTrigger} />
+          <ChargingInsightsCard />
+          <ParserFailureNotice />
         </aside>
 
         {/* Main Content - Floating Panel */}
@@ -473,6 +622,15 @@ const App: React.FC = () => {
             </div>
           )}
           
+          {!showBackupManager && (
+            <>
+              <ResumeJobBanner refreshTrigger={refreshTrigger} onResume={resumeJob} onNotify={addNotification} />
+              <TrialBanner refreshTrigger={refreshTrigger} onNotify={addNotification} />
+              <WeeklyDigestCard onNotify={addNotification} />
+              <UnusedAppsCard onSelect={(packages) => setSelectedPackages(new Set(packages))} />
+            </>
+          )}
+
           {showBackupManager ? (
             <BackupManager />
           ) : (
@@ -718,6 +876,48 @@ const App: React.FC = () => {
               Backup
             </motion.button>
 
+            <motion.button
+              onClick={handleTrialSelected}
+              className="px-4 py-2 rounded-lg text-sm font-medium flex items-center gap-2"
+              style={{
+                background: 'rgba(59, 130, 246, 0.15)',
+                color: '#3B82F6',
+                border: 'none'
+              }}
+              whileHover={{
+                scale: 1.04,
+                y: -2,
+                boxShadow: '0 4px 16px rgba(59, 130, 246, 0.25)',
+                transition: { duration: 0.15 }
+              }}
+              whileTap={{ scale: 0.97 }}
+              title="Disable for 24 hours, then re-enable automatically unless you keep them disabled"
+            >
+              <FiClock className="w-4 h-4" />
+              Trial
+            </motion.button>
+
+            <motion.button
+              onClick={handleShareSelected}
+              className="px-4 py-2 rounded-lg text-sm font-medium flex items-center gap-2"
+              style={{
+                background: 'rgba(168, 85, 247, 0.15)',
+                color: '#A855F7',
+                border: 'none'
+              }}
+              whileHover={{
+                scale: 1.04,
+                y: -2,
+                boxShadow: '0 4px 16px rgba(168, 85, 247, 0.25)',
+                transition: { duration: 0.15 }
+              }}
+              whileTap={{ scale: 0.97 }}
+              title="Copy the AI and built-in ratings as Markdown to ask a forum for a second opinion"
+            >
+              <FiShare2 className="w-4 h-4" />
+              Share
+            </motion.button>
+
             <motion.button
               onClick={handleUninstallSelected}
               className="px-4 py-2 rounded-lg text-sm font-medium flex items-center gap-2"
@@ -823,8 +1023,25 @@ const App: React.FC = () => {
         packageCount={selectedPackages.size}
         hasDangerous={packageData.some(p => selectedPackages.has(p.packageName) && p.safetyLevel === 'Dangerous')}
         hasExpert={packageData.some(p => selectedPackages.has(p.packageName) && p.safetyLevel === 'Expert')}
+        violations={selectionViolations}
+        hasUnrestorable={packageData.some(p => selectedPackages.has(p.packageName) && (p.partition === 'vendor' || p.partition === 'apex'))}
+        requirement={uninstallRequirement}
       />
 
+      <ConfirmDialog
+        isOpen={trialRequirement !== null}
+        onConfirm={confirmTrial}
+        onCancel={() => setTrialRequirement(null)}
+        title="Disable for 24 Hours"
+        message={`Your organization's policy asks for confirmation before the ${trialRequirement?.role ?? ''} role disables ${selectedPackages.size} package(s).`}
+        isDangerous
+        confirmDisabled={!typedConfirmationComplete(trialRequirement, trialTyped)}
+      >
+        {trialRequirement && (
+          <PolicyConfirmationFields requirement={trialRequirement} typed={trialTyped} onChange={setTrialTyped} />
+        )}
+      </ConfirmDialog>
+
       {/* AI Package Advisor Sidebar - Rendered at root level to avoid stacking context issues */}
       <AIPackageAdvisor
         packageName={aiAdvisorPackage}
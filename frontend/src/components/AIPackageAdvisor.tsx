@@ -327,6 +327,23 @@ const AIPackageAdvisor: React.FC<AIPackageAdvisorProps> = ({ packageName, onClos
                   </div>
                 </motion.div>
 
+                {/* AI and local rating disagree; the local rating is the one applied */}
+                {data.conflict && (
+                  <motion.div
+                    className="rounded-lg px-4 py-3 flex items-start gap-2"
+                    style={{
+                      background: 'rgba(245, 158, 11, 0.12)',
+                      border: '1px solid rgba(245, 158, 11, 0.25)',
+                    }}
+                    variants={fadeSlideUp}
+                  >
+                    <FiAlertTriangle className="w-4 h-4 flex-shrink-0 mt-0.5" style={{ color: '#F59E0B' }} />
+                    <span className="text-sm" style={{ color: 'var(--theme-text-primary)' }}>
+                      {data.conflict.message}
+                    </span>
+                  </motion.div>
+                )}
+
                 {/* Purpose */}
                 <motion.div
                   className="rounded-lg px-4 py-4"
@@ -0,0 +1,50 @@
+import React, { useState } from 'react';
+import { api, ApexModule } from '../utils/api';
+
+/** APEX system modules with their version and the apps shipped inside them; read-only */
+const ApexModulesPanel: React.FC = () => {
+  const [modules, setModules] = useState<ApexModule[] | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const load = async () => {
+    setLoading(true);
+    try {
+      const result = await api.listApexModules();
+      setModules(result.modules);
+      setMessage(result.success ? null : result.message ?? 'Could not list system modules');
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <div className="flex flex-col gap-2">
+      <button
+        type="button"
+        onClick={load}
+        disabled={loading}
+        className="self-start px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white text-sm font-medium rounded-lg disabled:opacity-50"
+      >
+        {loading ? 'Loading…' : modules ? 'Refresh' : 'Show system modules'}
+      </button>
+      {message && <p className="text-xs text-gray-500 dark:text-gray-400">{message}</p>}
+      {modules && modules.length === 0 && !message && (
+        <p className="text-xs text-gray-500 dark:text-gray-400">No system modules on this device</p>
+      )}
+      {modules?.map((module) => (
+        <div key={module.packageName} className="text-xs text-gray-700 dark:text-gray-300">
+          <div className="flex items-center gap-2 py-0.5">
+            <span className="font-mono truncate">{module.packageName}</span>
+            {module.versionCode !== null && <span className="text-gray-400">v{module.versionCode}</span>}
+          </div>
+          {module.apps.map((app) => (
+            <div key={app} className="font-mono text-gray-500 dark:text-gray-400 pl-3 truncate">{app}</div>
+          ))}
+        </div>
+      ))}
+    </div>
+  );
+};
+
+export default ApexModulesPanel;
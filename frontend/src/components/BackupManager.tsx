@@ -1,5 +1,5 @@
 import React, { useState, useEffect } from 'react';
-import { api, BackupInfo } from '../utils/api';
+import { api, BackupInfo, BackupComplete, BackupProgress } from '../utils/api';
 import { motion, AnimatePresence } from 'framer-motion';
 import {
   FiRefreshCw,
@@ -11,6 +11,7 @@ import {
   FiSmartphone,
   FiRotateCcw,
   FiAlertCircle,
+  FiArrowRight,
 } from 'react-icons/fi';
 import { staggerContainer, staggerItem } from '../utils/animations';
 
@@ -20,16 +21,36 @@ const BackupManager: React.FC = () => {
   const [loading, setLoading] = useState<boolean>(false);
   const [selectedPackages, setSelectedPackages] = useState<string[]>([]);
   const [restoring, setRestoring] = useState<boolean>(false);
+  const [allDevices, setAllDevices] = useState<boolean>(false);
+  const [fullProgress, setFullProgress] = useState<BackupProgress | null>(null);
 
-  // Load backups on mount
+  // Load backups on mount and when the device filter changes
   useEffect(() => {
     loadBackups();
+  }, [allDevices]);
+
+  useEffect(() => {
     loadBackupPath();
   }, []);
 
+  // Full backups run in the background and report through backend events
+  useEffect(() => {
+    return api.onBackendEvent((event) => {
+      if (event.kind === 'backup_progress') {
+        setFullProgress(event.payload as BackupProgress);
+      } else if (event.kind === 'backup_complete') {
+        const result = event.payload as BackupComplete;
+        setFullProgress(null);
+        alert(result.success ? `✅ ${result.message}` : `❌ ${result.message}`);
+        loadBackups();
+      }
+    });
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
   const loadBackups = async () => {
     try {
-      const result = await api.listBackups();
+      const result = await api.listBackups(allDevices);
       setBackups(result);
     } catch (error) {
       console.error('Failed to load backups:', error);
@@ -69,6 +90,24 @@ const BackupManager: React.FC = () => {
     }
   };
 
+  const handleCreateFullBackup = async () => {
+    if (selectedPackages.length === 0) {
+      alert('Please select packages to backup');
+      return;
+    }
+
+    try {
+      const result = await api.createFullBackup(selectedPackages);
+      if (!result.success) {
+        alert(`❌ Failed to start full backup: ${result.message}`);
+      } else if (result.space?.warnings?.length) {
+        alert(`⚠️ ${result.space.warnings.join('\n')}`);
+      }
+    } catch (error) {
+      alert(`❌ Error: ${error}`);
+    }
+  };
+
   const handleRestore = async (filename: string) => {
     if (!confirm(`Restore backup "${filename}"? This will reinstall all packages in the backup.`)) {
       return;
@@ -78,13 +117,19 @@ const BackupManager: React.FC = () => {
     try {
       const result = await api.restoreBackup(filename);
 
-      if (result.success) {
+      // Packages the user has to deal with by hand
+      const attention = (result.results ?? [])
+        .filter((r) => r.status === 'notAvailable' || r.status === 'failed')
+        .map((r) => `• ${r.packageName}: ${r.message}${(r.remediation?.steps ?? []).map((step) => `\n    – ${step}`).join('')}`)
+        .join('\n');
+
+      if (result.success && !attention) {
         alert(
-          `✅ Restore complete!\n\nPackages: ${result.count ?? 0}`
+          `✅ Restore complete!\n\n${result.message}`
         );
       } else {
         alert(
-          `⚠️ Restore failed: ${result.message}`
+          `⚠️ ${result.message}${attention ? `\n\nNeeds attention:\n${attention}` : ''}`
         );
       }
     } catch (error) {
@@ -94,6 +139,41 @@ const BackupManager: React.FC = () => {
     }
   };
 
+  // Backups from other devices: preview the package mapping, then apply it
+  const handleTransfer = async (filename: string) => {
+    setRestoring(true);
+    try {
+      const preview = await api.previewBackupTransfer(filename);
+      if (!preview.success) {
+        alert(`❌ ${preview.message}`);
+        return;
+      }
+      const counts = preview.counts!;
+      const mapped = (preview.entries ?? [])
+        .filter((e) => e.status === 'mapped')
+        .map((e) => `• ${e.packageName} → ${e.target}`)
+        .join('\n');
+      const summary =
+        `${counts.present} already installed, ${counts.restorable + counts.installable} to restore, ` +
+        `${counts.mapped} replaced by this device's apps, ${counts.incompatible} vendor apps skipped, ` +
+        `${counts.missing} to get from the Play Store`;
+      if (!confirm(`Set up this device from "${filename}"?\n\n${summary}${mapped ? `\n\nReplacements:\n${mapped}` : ''}`)) {
+        return;
+      }
+
+      const result = await api.applyBackupTransfer(filename);
+      const missing = (result.entries ?? [])
+        .filter((e) => e.status === 'missing' || e.restored === false)
+        .map((e) => `• ${e.packageName}: ${e.message}`)
+        .join('\n');
+      alert(`${result.success ? '✅' : '⚠️'} ${result.message}${missing ? `\n\nNeeds attention:\n${missing}` : ''}`);
+    } catch (error) {
+      alert(`❌ Error: ${error}`);
+    } finally {
+      setRestoring(false);
+    }
+  };
+
   const handleDelete = async (filename: string) => {
     if (!confirm(`Delete backup "${filename}"? This action cannot be undone.`)) {
       return;
@@ -145,6 +225,14 @@ const BackupManager: React.FC = () => {
             >
               {backups.length} vault{backups.length !== 1 ? 's' : ''}
             </motion.p>
+            <label className="flex items-center gap-1.5 text-xs text-gray-600 dark:text-text-tertiary mt-1 cursor-pointer">
+              <input
+                type="checkbox"
+                checked={allDevices}
+                onChange={(e) => setAllDevices(e.target.checked)}
+              />
+              Show backups from all devices
+            </label>
           </div>
           <motion.button
             onClick={loadBackups}
@@ -255,6 +343,16 @@ const BackupManager: React.FC = () => {
               </motion.div>
               {loading ? 'Creating...' : 'Create'}
             </motion.button>
+            <motion.button
+              onClick={handleCreateFullBackup}
+              disabled={fullProgress !== null || selectedPackages.length === 0}
+              className="btn-ghost px-3 py-2 text-sm disabled:opacity-50"
+              title="Also export the APKs of third-party apps into a zip"
+              whileHover={{ scale: 1.05 }}
+              whileTap={{ scale: 0.95 }}
+            >
+              {fullProgress ? `${fullProgress.done}/${fullProgress.count}` : 'Full'}
+            </motion.button>
             <motion.button
               onClick={() => setSelectedPackages([])}
               disabled={selectedPackages.length === 0}
@@ -341,10 +439,10 @@ const BackupManager: React.FC = () => {
                             <FiPackage className="w-3 h-3 opacity-60" />
                             <span>{backup.packageCount} pkg</span>
                           </div>
-                          {backup.deviceInfo?.name && (
+                          {(backup.nickname || backup.deviceInfo?.name) && (
                             <div className="flex items-center gap-1.5 text-gray-600 dark:text-text-secondary">
                               <FiSmartphone className="w-3 h-3 opacity-60" />
-                              <span>{backup.deviceInfo.name}</span>
+                              <span title={backup.serial ?? undefined}>{backup.nickname || backup.deviceInfo.name}</span>
                             </div>
                           )}
                         </div>
@@ -374,6 +472,27 @@ const BackupManager: React.FC = () => {
                           </motion.div>
                           {restoring ? 'Restoring...' : 'Restore'}
                         </motion.button>
+                        {allDevices && (
+                          <motion.button
+                            onClick={() => handleTransfer(backup.name)}
+                            disabled={restoring}
+                            title="Restore on the connected device, replacing vendor apps with this device's own"
+                            className="px-3 py-2 text-xs font-medium rounded-lg disabled:opacity-50 flex items-center justify-center gap-1.5"
+                            style={{
+                              background: 'rgba(88,166,175,0.12)',
+                              border: 'none',
+                            }}
+                            whileHover={{ 
+                              y: -2, 
+                              background: 'rgba(88,166,175,0.18)',
+                              boxShadow: '0 0 8px rgba(88,166,175,0.12)'
+                            }}
+                            whileTap={{ scale: 0.95 }}
+                          >
+                            <FiArrowRight className="w-3 h-3" />
+                            Set up here
+                          </motion.button>
+                        )}
                         <motion.button
                           onClick={() => handleDelete(backup.name)}
                           className="px-3 py-2 text-xs font-medium rounded-lg flex items-center justify-center gap-1.5"
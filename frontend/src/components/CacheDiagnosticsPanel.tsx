@@ -0,0 +1,77 @@
+import React, { useState } from 'react';
+import { api, CacheDiagnostics } from '../utils/api';
+
+const formatAge = (seconds: number | null) => {
+  if (seconds === null) return '—';
+  if (seconds < 60) return `${Math.round(seconds)}s`;
+  if (seconds < 3600) return `${Math.round(seconds / 60)}m`;
+  if (seconds < 86400) return `${Math.round(seconds / 3600)}h`;
+  return `${Math.round(seconds / 86400)}d`;
+};
+
+/** Hit rates, sizes and ages of the backend caches, for finding out why something is slow */
+const CacheDiagnosticsPanel: React.FC = () => {
+  const [diagnostics, setDiagnostics] = useState<CacheDiagnostics | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const load = async () => {
+    setLoading(true);
+    try {
+      setDiagnostics(await api.getCacheDiagnostics());
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <div className="flex flex-col gap-2">
+      <button
+        type="button"
+        onClick={load}
+        disabled={loading}
+        className="self-start px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white text-sm font-medium rounded-lg disabled:opacity-50"
+      >
+        {loading ? 'Loading…' : diagnostics ? 'Refresh' : 'Show cache statistics'}
+      </button>
+      {diagnostics && (
+        <>
+          <table className="w-full text-xs text-gray-700 dark:text-gray-300">
+            <thead>
+              <tr className="text-left text-gray-500 dark:text-gray-400">
+                <th className="py-1">Cache</th>
+                <th>Entries</th>
+                <th>Hits / misses</th>
+                <th>Hit rate</th>
+                <th>Memory</th>
+                <th>Oldest / newest</th>
+              </tr>
+            </thead>
+            <tbody>
+              {diagnostics.caches.map((cache) => (
+                <tr key={cache.name} className="border-t border-gray-100 dark:border-gray-800">
+                  <td className="py-1 font-mono">{cache.name}</td>
+                  <td>{cache.entries}</td>
+                  <td>
+                    {cache.hits} / {cache.misses}
+                  </td>
+                  <td>{cache.formatted.hitRate ?? '—'}</td>
+                  <td>{cache.formatted.memory}</td>
+                  <td title={cache.maxAgeSeconds !== null ? `Stale after ${formatAge(cache.maxAgeSeconds)}` : undefined}>
+                    {formatAge(cache.oldestAgeSeconds)} / {formatAge(cache.newestAgeSeconds)}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+          <p className="text-xs text-gray-500 dark:text-gray-400">
+            {diagnostics.formatted.totalMemory} in total
+            {diagnostics.refreshing.length > 0 &&
+              `; refreshing ${diagnostics.refreshing.map(([cache, key]) => `${cache} (${key})`).join(', ')}`}
+          </p>
+        </>
+      )}
+    </div>
+  );
+};
+
+export default CacheDiagnosticsPanel;
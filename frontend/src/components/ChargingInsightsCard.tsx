@@ -0,0 +1,71 @@
+import React, { useEffect, useState } from 'react';
+import { FiBatteryCharging } from 'react-icons/fi';
+import { api, ChargingSession, ChargingSessions } from '../utils/api';
+
+const formatDuration = (seconds: number) => {
+  const hours = Math.floor(seconds / 3600);
+  const minutes = Math.round((seconds % 3600) / 60);
+  return hours > 0 ? `${hours}h ${minutes}m` : `${minutes}m`;
+};
+
+const describeSession = (session: ChargingSession) =>
+  `${session.startLevel}% → ${session.endLevel}% in ${formatDuration(session.durationSeconds)}` +
+  (session.peakTemperatureC != null ? `, peak ${session.peakTemperatureC}°C` : '');
+
+/** Charging speed and heat insights from the connected device's recent charge sessions */
+const ChargingInsightsCard: React.FC = () => {
+  const [charging, setCharging] = useState<ChargingSessions | null>(null);
+  const [expanded, setExpanded] = useState(false);
+
+  const load = () => {
+    api.getChargingSessions().then(setCharging).catch((error) => console.error('get_charging_sessions failed', error));
+  };
+
+  useEffect(() => {
+    load();
+    // The backend polls the battery once a minute
+    const interval = setInterval(load, 60000);
+    const unsubscribe = api.onBackendEvent((event) => {
+      if (event.kind === 'charging_session_ended') load();
+    });
+    return () => {
+      clearInterval(interval);
+      unsubscribe();
+    };
+  }, []);
+
+  if (!charging?.success || (charging.sessions.length === 0 && !charging.current)) return null;
+
+  return (
+    <div className="mt-4 p-3 rounded-lg text-xs" style={{ border: '1px solid var(--theme-border)', color: 'var(--theme-text-secondary)' }}>
+      <div className="flex items-center gap-2 mb-1" style={{ color: 'var(--theme-text-primary)' }}>
+        <FiBatteryCharging className="w-3.5 h-3.5" />
+        <span className="font-semibold flex-1">Charging</span>
+        {charging.sessions.length > 0 && (
+          <button type="button" onClick={() => setExpanded(!expanded)}>
+            {expanded ? 'Hide' : 'Sessions'}
+          </button>
+        )}
+      </div>
+      {charging.current && (
+        <p>
+          Charging now ({charging.current.charger.toUpperCase()}): {describeSession(charging.current)}
+        </p>
+      )}
+      {charging.insights.map((insight) => (
+        <p key={insight} className="mt-1">• {insight}</p>
+      ))}
+      {expanded && (
+        <ul className="mt-2 space-y-0.5 font-mono">
+          {charging.sessions.map((session) => (
+            <li key={session.id}>
+              {new Date(session.start * 1000).toLocaleDateString()} {session.charger}: {describeSession(session)}
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+};
+
+export default ChargingInsightsCard;
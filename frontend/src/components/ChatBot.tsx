@@ -50,6 +50,21 @@ export const ChatBot: React.FC<ChatBotProps> = ({ deviceName, onClose }) => {
     messagesEndRef.current?.scrollIntoView({ behavior: 'smooth' });
   }, [messages, loading]);
 
+  // A removal that paused for a disconnected device finishes in the background
+  useEffect(() => {
+    return api.onBackendEvent((event) => {
+      if (event.kind === 'chat_uninstall_complete') {
+        const result = event.payload as ActionExecutionResult;
+        setMessages(prev => [...prev, {
+          role: 'assistant',
+          content: result.success ? `✅ ${result.message}` : `❌ ${result.message}`,
+          timestamp: Date.now(),
+          id: messageUtils.generateId(),
+        }]);
+      }
+    });
+  }, []);
+
   // Focus input on mount
   useEffect(() => {
     inputRef.current?.focus();
@@ -242,7 +257,14 @@ export const ChatBot: React.FC<ChatBotProps> = ({ deviceName, onClose }) => {
 
         setMessages(prev => [...prev, assistantMessage]);
         await messageUtils.streamText(response, messageId, setMessages);
-        generateSuggestions(response);
+
+        // Prefer the provider's follow-up questions over local keyword suggestions
+        const related = typeof result === 'string' ? [] : result?.relatedQuestions || [];
+        if (related.length > 0) {
+          setSuggestedReplies(related.slice(0, 3));
+        } else {
+          generateSuggestions(response);
+        }
       }
     } catch (err) {
       console.error('Chat error:', err);
@@ -0,0 +1,53 @@
+import React, { useEffect, useState } from 'react';
+import { FiLayers } from 'react-icons/fi';
+import { api, ClassPreset } from '../utils/api';
+
+interface ClassPresetBarProps {
+  deviceId: string | null;
+  /** The listing has finished loading; presets only list installed packages */
+  ready: boolean;
+  selectedPackages: Set<string>;
+  onSelectionChange: (selected: Set<string>) => void;
+}
+
+/** One-click selections of the packages commonly removed on TVs, watches, tablets and cars */
+const ClassPresetBar: React.FC<ClassPresetBarProps> = ({ deviceId, ready, selectedPackages, onSelectionChange }) => {
+  const [presets, setPresets] = useState<ClassPreset[]>([]);
+
+  useEffect(() => {
+    if (!deviceId || !ready) {
+      setPresets([]);
+      return;
+    }
+    api.getClassPresets()
+      .then((result) => setPresets(result.presets))
+      .catch((error) => console.error('get_class_presets failed', error));
+  }, [deviceId, ready]);
+
+  if (presets.length === 0) return null;
+
+  const select = (preset: ClassPreset) => {
+    onSelectionChange(new Set([...selectedPackages, ...preset.packages]));
+  };
+
+  return (
+    <div className="flex flex-wrap items-center gap-2 mb-4 text-xs" style={{ color: 'var(--theme-text-secondary)' }}>
+      <FiLayers className="w-3.5 h-3.5" />
+      <span>Select:</span>
+      {presets.map((preset) => (
+        <button
+          key={preset.id}
+          type="button"
+          onClick={() => select(preset)}
+          title={`${preset.description}\n${preset.packages.join('\n')}`}
+          className="px-2.5 py-1 rounded-full"
+          style={{ border: '1px solid var(--theme-border)', color: 'var(--theme-text-primary)' }}
+        >
+          {preset.name} ({preset.packages.length})
+        </button>
+      ))}
+    </div>
+  );
+};
+
+export default ClassPresetBar;
@@ -9,6 +9,8 @@ interface ConfirmDialogProps {
   title: string;
   message: string;
   isDangerous?: boolean;
+  children?: React.ReactNode;  // Extra fields shown below the message
+  confirmDisabled?: boolean;
 }
 
 const ConfirmDialog: React.FC<ConfirmDialogProps> = ({
@@ -18,6 +20,8 @@ const ConfirmDialog: React.FC<ConfirmDialogProps> = ({
   title,
   message,
   isDangerous = false,
+  children,
+  confirmDisabled = false,
 }) => {
   const [showLearnMore, setShowLearnMore] = useState(false);
 
@@ -88,6 +92,8 @@ const ConfirmDialog: React.FC<ConfirmDialogProps> = ({
           {message}
         </p>
 
+        {children}
+
         {/* Learn More Section (Collapsible) */}
         {isDangerous && (
           <div className="mb-6">
@@ -140,20 +146,23 @@ const ConfirmDialog: React.FC<ConfirmDialogProps> = ({
           {/* Confirm Button */}
           <motion.button
             onClick={onConfirm}
+            disabled={confirmDisabled}
             className={
               'px-5 py-2.5 border font-medium text-base focus:outline-none focus:ring-2 rounded-lg ' +
-              (isDangerous
+              (confirmDisabled
+                ? 'bg-gray-400 dark:bg-gray-600 text-white border-gray-400 dark:border-gray-600 cursor-not-allowed'
+                : isDangerous
                 ? 'bg-red-600 hover:bg-red-700 text-white border-red-600 focus:ring-red-500'
                 : 'bg-green-600 hover:bg-green-700 text-white border-green-600 focus:ring-green-500')
             }
-            whileHover={{ 
+            whileHover={confirmDisabled ? {} : { 
               scale: 1.02, 
               y: -1,
               boxShadow: isDangerous 
                 ? '0 8px 20px rgba(239, 68, 68, 0.3)'
                 : '0 8px 20px rgba(34, 197, 94, 0.3)'
             }}
-            whileTap={{ scale: 0.98 }}
+            whileTap={confirmDisabled ? {} : { scale: 0.98 }}
             transition={{ type: 'spring' as const, stiffness: 400, damping: 17 }}
           >
             Confirm
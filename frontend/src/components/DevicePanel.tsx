@@ -9,12 +9,28 @@ import {
   FiCpu,
   FiHardDrive,
   FiBattery,
+  FiEdit2,
+  FiPower,
+  FiLifeBuoy,
+  FiMonitor,
 } from 'react-icons/fi';
+import { api, DeviceClass, PrefetchProgress, RecoveryPlan, RestartResult } from '../utils/api';
+import ConfirmDialog from './ConfirmDialog';
+import UsbPermissionNotice from './UsbPermissionNotice';
 import {
   staggerContainer,
   staggerItem,
 } from '../utils/animations';
 
+// Mirrors CLASS_LABELS in backend-python/device_class.py
+const DEVICE_CLASS_LABELS: Record<DeviceClass, string> = {
+  phone: 'Phone',
+  tablet: 'Tablet',
+  tv: 'Android TV',
+  watch: 'Wear OS watch',
+  automotive: 'Android Automotive',
+};
+
 interface DevicePanelProps {
   onRefresh?: () => void;
 }
@@ -24,6 +40,81 @@ const DevicePanel: React.FC<DevicePanelProps> = ({ onRefresh }) => {
   const { theme } = useTheme();
   const isLightMode = theme === 'light';
   const [isRefreshing, setIsRefreshing] = React.useState(false);
+  const [nicknameDraft, setNicknameDraft] = React.useState<string | null>(null);
+
+  const saveNickname = async () => {
+    if (!device || nicknameDraft === null) return;
+    const result = await api.setDeviceNickname(device.serial, nicknameDraft);
+    if (!result.success) console.warn('set_device_nickname failed:', result.message);
+    setNicknameDraft(null);
+    refresh();
+  };
+
+  // Background cache warm-up started by the backend when the device connects
+  const [prefetch, setPrefetch] = React.useState<PrefetchProgress | null>(null);
+
+  React.useEffect(() => {
+    return api.onBackendEvent((event) => {
+      if (event.kind === 'prefetch_progress') setPrefetch(event.payload as PrefetchProgress);
+      if (event.kind === 'prefetch_complete') setPrefetch(null);
+    });
+  }, []);
+
+  // Soft reboot and reboot close every app, so they are confirmed first
+  const [pendingRestart, setPendingRestart] = React.useState<'soft' | 'reboot' | null>(null);
+  const [restartMessage, setRestartMessage] = React.useState<string | null>(null);
+
+  const runRestart = async (action: () => Promise<RestartResult>) => {
+    const result = await action();
+    setRestartMessage(result.message);
+  };
+
+  const confirmRestart = async () => {
+    const kind = pendingRestart;
+    setPendingRestart(null);
+    if (kind === 'soft') await runRestart(() => api.softReboot(true));
+    if (kind === 'reboot') await runRestart(() => api.rebootDevice('system', true));
+  };
+
+  // Emergency recovery shows its plan first and runs only once confirmed
+  const [recoveryPlan, setRecoveryPlan] = React.useState<RecoveryPlan | null>(null);
+
+  const planRecovery = async () => {
+    const result = await api.emergencyRecover(false);
+    if (result.plan) setRecoveryPlan(result.plan);
+    else setRestartMessage(result.message);
+  };
+
+  const confirmRecovery = async () => {
+    setRecoveryPlan(null);
+    // The dialog is the confirmation; a policy asking for typed names refuses with a message
+    const result = await api.emergencyRecover(true, undefined, undefined, undefined, { confirmed: true });
+    const safeMode = result.steps?.find((step) => step.step === 'safeMode');
+    setRestartMessage(safeMode ? `${result.message}. ${safeMode.message}` : result.message);
+  };
+
+  // scrcpy is downloaded only once the user agrees to it
+  const [mirroring, setMirroring] = React.useState(false);
+  const [confirmMirrorDownload, setConfirmMirrorDownload] = React.useState(false);
+
+  const launchMirror = async (download = false) => {
+    setConfirmMirrorDownload(false);
+    setMirroring(true);
+    try {
+      const result = await api.launchScreenMirror(undefined, download);
+      if (result.needsDownload) setConfirmMirrorDownload(true);
+      else setRestartMessage(result.message);
+    } finally {
+      setMirroring(false);
+    }
+  };
+
+  const describePlan = (plan: RecoveryPlan) => [
+    plan.trials.length > 0 && `end ${plan.trials.length} running trial(s)`,
+    plan.reenable.length > 0 && `re-enable ${plan.reenable.length} package(s) disabled in the last day`,
+    plan.backup && `restore the backup ${plan.backup}`,
+    'reboot into safe mode',
+  ].filter(Boolean).join(', ');
 
   const handleRefresh = async () => {
     setIsRefreshing(true);
@@ -180,6 +271,39 @@ const DevicePanel: React.FC<DevicePanelProps> = ({ onRefresh }) => {
                 </span>
               </div>
               <div className="ml-9">
+                {/* User-chosen nickname, editable inline */}
+                {nicknameDraft !== null ? (
+                  <input
+                    autoFocus
+                    className="text-sm font-semibold w-full rounded px-1"
+                    style={{ color: 'var(--theme-text-primary)', background: 'transparent', border: '1px solid var(--theme-accent)' }}
+                    value={nicknameDraft}
+                    maxLength={40}
+                    placeholder="Nickname (empty to remove)"
+                    onChange={(e) => setNicknameDraft(e.target.value)}
+                    onKeyDown={(e) => {
+                      if (e.key === 'Enter') saveNickname();
+                      if (e.key === 'Escape') setNicknameDraft(null);
+                    }}
+                    onBlur={saveNickname}
+                  />
+                ) : (
+                  <div className="flex items-center gap-1.5">
+                    {device.nickname && (
+                      <span className="text-sm font-semibold truncate" style={{ color: 'var(--theme-accent)' }}>
+                        {device.nickname}
+                      </span>
+                    )}
+                    <button
+                      onClick={(e) => { e.stopPropagation(); setNicknameDraft(device.nickname ?? ''); }}
+                      className="opacity-60 hover:opacity-100"
+                      title={device.nickname ? 'Rename device' : 'Give this device a nickname'}
+                      aria-label="Edit device nickname"
+                    >
+                      <FiEdit2 className="w-3 h-3" style={{ color: 'var(--theme-text-secondary)' }} />
+                    </button>
+                  </div>
+                )}
                 {/* Friendly name: manufacturer + model */}
                 <div className="text-sm font-semibold leading-snug truncate" style={{ color: 'var(--theme-text-primary)' }}>
                   {device.manufacturer && device.manufacturer !== 'Unknown'
@@ -220,6 +344,8 @@ const DevicePanel: React.FC<DevicePanelProps> = ({ onRefresh }) => {
               </div>
               <div className="text-sm font-semibold ml-9 transition-colors duration-200" style={{ color: 'var(--theme-text-primary)' }}>
                 {device.androidVersion}
+                {device.fireOs && ` (Fire OS ${device.fireOs})`}
+                {device.deviceClass && device.deviceClass !== 'phone' && ` · ${DEVICE_CLASS_LABELS[device.deviceClass]}`}
               </div>
             </motion.div>
 
@@ -328,6 +454,7 @@ const DevicePanel: React.FC<DevicePanelProps> = ({ onRefresh }) => {
             <p className="text-xs transition-colors duration-200" style={{ color: 'var(--theme-text-secondary)', opacity: 0.7 }}>
               Connect your Android device via USB with debugging enabled
             </p>
+            <UsbPermissionNotice />
           </motion.div>
         )}
       </div>
@@ -381,6 +508,92 @@ const DevicePanel: React.FC<DevicePanelProps> = ({ onRefresh }) => {
           {loading || isRefreshing ? 'Refreshing...' : 'Refresh Packages'}
         </span>
       </motion.button>
+
+      {prefetch && (
+        <p className="text-xs mt-2 text-center" style={{ color: 'var(--theme-text-secondary)' }}>
+          Preparing data ({prefetch.index + 1}/{prefetch.total}): {prefetch.label}
+        </p>
+      )}
+
+      {/* Restart helpers: apply disabled components without a full reboot */}
+      {isConnected && (
+        <div className="mt-3">
+          <div className="grid grid-cols-2 gap-2">
+            {([
+              ['SystemUI', 'Restart the status bar and quick settings', () => runRestart(api.restartSystemUi)],
+              ['Launcher', 'Restart the home screen app', () => runRestart(api.restartLauncher)],
+              ['Soft Reboot', 'Restart the Android framework without rebooting', () => setPendingRestart('soft')],
+              ['Reboot', 'Reboot the device', () => setPendingRestart('reboot')],
+            ] as Array<[string, string, () => void]>).map(([label, title, onClick]) => (
+              <button
+                key={label}
+                type="button"
+                onClick={onClick}
+                className="text-xs flex items-center justify-center gap-1.5 py-2 rounded-lg"
+                style={{ border: '1px solid var(--theme-border)', color: 'var(--theme-text-secondary)' }}
+                title={title}
+              >
+                <FiPower className="w-3.5 h-3.5" />
+                {label}
+              </button>
+            ))}
+          </div>
+          <button
+            type="button"
+            onClick={() => launchMirror()}
+            disabled={mirroring}
+            className="w-full mt-2 text-xs flex items-center justify-center gap-1.5 py-2 rounded-lg disabled:opacity-50"
+            style={{ border: '1px solid var(--theme-border)', color: 'var(--theme-text-secondary)' }}
+            title="Show and control the device screen in a scrcpy window"
+          >
+            <FiMonitor className="w-3.5 h-3.5" />
+            {mirroring ? 'Starting Mirror...' : 'Mirror Screen'}
+          </button>
+          <button
+            type="button"
+            onClick={planRecovery}
+            className="w-full mt-2 text-xs flex items-center justify-center gap-1.5 py-2 rounded-lg"
+            style={{ border: '1px solid rgba(239, 68, 68, 0.4)', color: '#EF4444' }}
+            title="Device unstable after a removal? Undo recent changes and boot into safe mode"
+          >
+            <FiLifeBuoy className="w-3.5 h-3.5" />
+            Emergency Recovery
+          </button>
+          {restartMessage && (
+            <p className="text-xs mt-2" style={{ color: 'var(--theme-text-secondary)' }}>
+              {restartMessage}
+            </p>
+          )}
+        </div>
+      )}
+
+      <ConfirmDialog
+        isOpen={pendingRestart !== null}
+        onConfirm={confirmRestart}
+        onCancel={() => setPendingRestart(null)}
+        title={pendingRestart === 'soft' ? 'Soft Reboot' : 'Reboot Device'}
+        message={pendingRestart === 'soft'
+          ? 'Restarting the Android framework closes every app, like a reboot without the boot screen.'
+          : 'Rebooting disconnects the device until it has started again.'}
+        isDangerous
+      />
+
+      <ConfirmDialog
+        isOpen={recoveryPlan !== null}
+        onConfirm={confirmRecovery}
+        onCancel={() => setRecoveryPlan(null)}
+        title="Emergency Recovery"
+        message={recoveryPlan ? `This will ${describePlan(recoveryPlan)}.` : ''}
+        isDangerous
+      />
+
+      <ConfirmDialog
+        isOpen={confirmMirrorDownload}
+        onConfirm={() => launchMirror(true)}
+        onCancel={() => setConfirmMirrorDownload(false)}
+        title="Download scrcpy"
+        message="Screen mirroring uses scrcpy, which was not found on this computer. Download the latest release from GitHub (about 10 MB) into the DebloatAI folder?"
+      />
     </motion.div>
   );
 };
@@ -1,8 +1,9 @@
 import React, { useEffect, useState, useMemo, useCallback } from 'react';
-import { api, Package } from '../utils/api';
+import { api, BatteryTrend, GuidedActionResult, Package, PackageSignature } from '../utils/api';
 import { motion, AnimatePresence } from 'framer-motion';
 import { useDeviceMonitor } from '../hooks/useDeviceMonitor';
 import { useTheme } from '../App';
+import ClassPresetBar from './ClassPresetBar';
 import {
   FiPackage,
   FiAlertTriangle,
@@ -12,10 +13,24 @@ import {
   FiZap,
   FiXOctagon,
   FiInfo,
+  FiLock,
+  FiKey,
+  FiBattery,
+  FiSettings,
+  FiShoppingBag,
 } from 'react-icons/fi';
 
 type SafetyLevel = Package['safetyLevel'];
 
+// Mirrors INSTALL_SOURCE_LABELS in backend-python/provenance.py
+const INSTALL_SOURCE_LABELS: Record<NonNullable<Package['installSource']>, string> = {
+  googlePlay: 'Google Play',
+  otherStore: 'Other app store',
+  oemPreload: 'OEM preload',
+  carrier: 'Carrier',
+  sideloaded: 'Sideloaded',
+};
+
 interface PackageStats {
   total: number;
   safe: number;
@@ -30,7 +45,7 @@ interface PackageListProps {
   onSelectionChange: (selected: Set<string>) => void;
   onStatsChange: (stats: PackageStats) => void;
   filterBySafety?: string | null;
-  onPackageDataChange?: (packages: Array<{ packageName: string; safetyLevel: string }>) => void;
+  onPackageDataChange?: (packages: Array<{ packageName: string; safetyLevel: string; partition?: string | null }>) => void;
   onAiAdvisorOpen?: (packageName: string) => void;
   refreshTrigger?: number;
 }
@@ -76,6 +91,12 @@ interface PackageListItemProps {
 // ⚡ Bolt: Wrapped PackageListItem in React.memo to prevent unnecessary re-renders.
 // Now, only the clicked item will re-render instead of the entire list.
 const PackageListItem = React.memo(({ pkg, isSelected, isLightMode, toggleSelect, onAiAdvisorOpen }: PackageListItemProps) => {
+  // Apps controlled by device management (MDM) cannot be uninstalled; theme overlays are toggled in Settings
+  // instead, and system modules (APEX) are never removable
+  const isOverlay = !!pkg.overlayTarget;
+  const isApex = !!pkg.apexModule;
+  const isManaged = pkg.removable === false && !isOverlay && !isApex;
+  const isLocked = pkg.removable === false;
   return (
     <div
       className="package-card-hover pkg-fade-in"
@@ -88,7 +109,8 @@ const PackageListItem = React.memo(({ pkg, isSelected, isLightMode, toggleSelect
         border: isLightMode ? '1px solid rgba(0,0,0,0.10)' : '1px solid rgba(255,255,255,0.10)',
         borderRadius: '12px',
         padding: '14px 16px',
-        cursor: 'pointer',
+        cursor: isLocked ? 'not-allowed' : 'pointer',
+        opacity: isLocked ? 0.6 : 1,
         boxShadow: isSelected
           ? (isLightMode
             ? '0 0 18px rgba(46,196,182,0.20), 0 4px 14px rgba(0,0,0,0.08)'
@@ -96,13 +118,21 @@ const PackageListItem = React.memo(({ pkg, isSelected, isLightMode, toggleSelect
           : (isLightMode ? '0 2px 8px rgba(0,0,0,0.06)' : '0 2px 8px rgba(0,0,0,0.04)'),
         transition: 'all 0.15s ease',
       }}
-      onClick={() => toggleSelect(pkg.packageName)}
+      onClick={() => !isLocked && toggleSelect(pkg.packageName)}
+      title={
+        isOverlay
+          ? `Theme overlay for ${pkg.overlayTarget}: turn it on or off under Settings > Theme Overlays`
+          : isApex
+            ? `Part of the ${pkg.apexModule} system module, updated by Google; it cannot be removed`
+            : isManaged ? pkg.managedReason : undefined
+      }
     >
       <div className="flex items-center gap-3">
         {/* Checkbox */}
         <input
           type="checkbox"
           checked={isSelected}
+          disabled={isLocked}
           onChange={(e) => {
             e.stopPropagation();
             toggleSelect(pkg.packageName);
@@ -129,6 +159,24 @@ const PackageListItem = React.memo(({ pkg, isSelected, isLightMode, toggleSelect
           </div>
           <div className="text-xs font-mono truncate" style={{ color: isLightMode ? '#525252' : '#A0A0A0' }}>
             {pkg.packageName}
+            {pkg.installSource && (
+              <span
+                className="font-sans ml-2"
+                title={[
+                  pkg.installer ? `Installed by ${pkg.installer}` : 'No installer recorded',
+                  pkg.partition && `on the ${pkg.partition} partition`,
+                ].filter(Boolean).join(', ')}
+              >
+                · {INSTALL_SOURCE_LABELS[pkg.installSource]}
+              </span>
+            )}
+            {isOverlay && <span className="font-sans ml-2">· Overlay for {pkg.overlayTarget}</span>}
+            {isApex && <span className="font-sans ml-2">· System module</span>}
+            {pkg.platformSigned && (
+              <span className="font-sans ml-2" title="Signed with the platform key: runs with system privileges">
+                · Platform key
+              </span>
+            )}
           </div>
         </div>
 
@@ -148,6 +196,29 @@ const PackageListItem = React.memo(({ pkg, isSelected, isLightMode, toggleSelect
           <FiZap className="w-4 h-4" style={{ color: isLightMode ? '#2EC4B6' : '#58A6AF' }} />
         </button>
 
+        {/* Cached AI verdict from an earlier analysis */}
+        {pkg.aiRisk && (
+          <span
+            className="flex-shrink-0"
+            title={`AI: ${pkg.aiRisk.summary || pkg.aiRisk.riskCategory} (analyzed ${new Date(pkg.aiRisk.analyzedAt * 1000).toLocaleDateString()}${pkg.aiRisk.stale ? ', outdated' : ''})${pkg.aiRisk.conflict ? `\n\n⚠️ ${pkg.aiRisk.conflict.message}` : ''}`}
+            style={{
+              opacity: pkg.aiRisk.stale ? 0.6 : 1,
+              fontSize: '11px',
+              padding: '4px 8px',
+              borderRadius: '6px',
+              color: isLightMode ? '#2EC4B6' : '#58A6AF',
+              border: isLightMode ? '1px solid rgba(46, 196, 182, 0.30)' : '1px solid rgba(88, 166, 175, 0.30)',
+            }}
+          >
+            AI: {pkg.aiRisk.riskCategory}{pkg.aiRisk.conflict && ' ⚠️'}
+          </span>
+        )}
+
+        {/* Managed Badge */}
+        {isManaged && (
+          <FiLock className="w-4 h-4 flex-shrink-0" style={{ color: isLightMode ? '#525252' : '#A0A0A0' }} />
+        )}
+
         {/* Safety Badge */}
         <div className="flex-shrink-0">
           <span
@@ -181,18 +252,71 @@ const PackageList: React.FC<PackageListProps> = ({
   const [loading, setLoading] = useState<boolean>(false);
   const [search, setSearch] = useState<string>('');
   const [detailPackage, setDetailPackage] = useState<Package | null>(null);
+  const [detailSignature, setDetailSignature] = useState<PackageSignature | null>(null);
+  const [detailTrend, setDetailTrend] = useState<BatteryTrend | null>(null);
+  const [deviceActionMessage, setDeviceActionMessage] = useState<string | null>(null);
+
+  // Finish a task on the device itself, e.g. uninstall updates from App info
+  const runDeviceAction = async (action: () => Promise<GuidedActionResult>) => {
+    const result = await action();
+    setDeviceActionMessage(result.message);
+  };
+
   const { isConnected, deviceId } = useDeviceMonitor();
 
+  // Signature details are read on demand: they come from the full package dump
+  useEffect(() => {
+    setDetailSignature(null);
+    setDeviceActionMessage(null);
+    if (!detailPackage) return;
+    let active = true;
+    api.getPackageSignature(detailPackage.packageName)
+      .then((signature) => {
+        if (active) setDetailSignature(signature);
+      })
+      .catch((error) => console.error('get_package_signature failed', error));
+    return () => {
+      active = false;
+    };
+  }, [detailPackage]);
+
+  // Battery use per day comes from the daily digest snapshots
+  useEffect(() => {
+    setDetailTrend(null);
+    if (!detailPackage) return;
+    let active = true;
+    api.getBatteryTrend(detailPackage.packageName)
+      .then((trend) => {
+        if (active) setDetailTrend(trend);
+      })
+      .catch((error) => console.error('get_battery_trend failed', error));
+    return () => {
+      active = false;
+    };
+  }, [detailPackage]);
+
+  const streamRef = React.useRef<{ cancel: () => void } | null>(null);
+
   const fetchPackages = useCallback(async (retryCount = 0) => {
     setLoading(true);
+    streamRef.current?.cancel();
     try {
-      const res = await api.listPackages();
-      const pkgs = res ?? [];
+      // Rows appear as chunks arrive instead of after the whole list is built
+      let first = true;
+      const stream = api.streamPackages('all', (chunk) => {
+        setPackages((prev) => (first ? chunk : [...prev, ...chunk]));
+        if (first) setLoading(false);
+        first = false;
+      });
+      streamRef.current = stream;
+      const pkgs = await stream.done;
+      if (streamRef.current !== stream) return;  // Superseded by a newer fetch
+      streamRef.current = null;
       setPackages(pkgs);
 
       // Pass package data to parent for safety checking
       if (onPackageDataChange) {
-        onPackageDataChange(pkgs.map(p => ({ packageName: p.packageName, safetyLevel: p.safetyLevel })));
+        onPackageDataChange(pkgs.map(p => ({ packageName: p.packageName, safetyLevel: p.safetyLevel, partition: p.partition })));
       }
 
       // If device is connected but packages came back empty, retry (ADB may not be ready yet)
@@ -218,6 +342,9 @@ const PackageList: React.FC<PackageListProps> = ({
     }
   }, [onPackageDataChange]);
 
+  // Stop a running stream when the list goes away
+  useEffect(() => () => streamRef.current?.cancel(), []);
+
   // Fetch packages when device connects or changes
   useEffect(() => {
     if (isConnected && deviceId) {
@@ -325,6 +452,14 @@ const PackageList: React.FC<PackageListProps> = ({
         </p>
       </div>
 
+      {/* Presets for TVs, watches, tablets and cars */}
+      <ClassPresetBar
+        deviceId={deviceId}
+        ready={!loading && packages.length > 0}
+        selectedPackages={selectedPackages}
+        onSelectionChange={onSelectionChange}
+      />
+
       {/* Minimal Search Bar */}
       <div className="relative mb-5 group">
         <div className="absolute inset-y-0 left-0 pl-3.5 flex items-center pointer-events-none">
@@ -560,8 +695,90 @@ const PackageList: React.FC<PackageListProps> = ({
                     {getSafetyIcon(detailPackage.safetyLevel)} {detailPackage.safetyLevel}
                   </span>
                 </div>
+
+                {/* Signature */}
+                {detailSignature?.success && detailSignature.notes && detailSignature.notes.length > 0 && (
+                  <div
+                    style={{
+                      background: isLightMode ? 'rgba(0,0,0,0.02)' : 'rgba(255,255,255,0.03)',
+                      border: isLightMode ? '1px solid rgba(0,0,0,0.05)' : 'none',
+                      borderRadius: '12px',
+                      padding: '16px',
+                    }}
+                  >
+                    <div className="flex items-center gap-2 text-xs font-semibold text-text-tertiary uppercase tracking-wide mb-2.5">
+                      <FiKey className="w-3.5 h-3.5" />
+                      Signature
+                    </div>
+                    <ul className="text-sm text-text-primary space-y-1">
+                      {detailSignature.notes.map((note) => (
+                        <li key={note}>{note}</li>
+                      ))}
+                    </ul>
+                    {detailSignature.signaturePermissions && detailSignature.signaturePermissions.length > 0 && (
+                      <div
+                        className="font-mono text-xs text-text-secondary mt-2 break-all"
+                        title="Signature-level permissions held"
+                      >
+                        {detailSignature.signaturePermissions.join(', ')}
+                      </div>
+                    )}
+                  </div>
+                )}
+
+                {/* Battery trend */}
+                {detailTrend?.success && detailTrend.points && detailTrend.points.length > 0 && (
+                  <div
+                    style={{
+                      background: isLightMode ? 'rgba(0,0,0,0.02)' : 'rgba(255,255,255,0.03)',
+                      border: isLightMode ? '1px solid rgba(0,0,0,0.05)' : 'none',
+                      borderRadius: '12px',
+                      padding: '16px',
+                    }}
+                  >
+                    <div className="flex items-center gap-2 text-xs font-semibold text-text-tertiary uppercase tracking-wide mb-2.5">
+                      <FiBattery className="w-3.5 h-3.5" />
+                      Battery Use ({detailTrend.days} days)
+                    </div>
+                    <p className="text-sm text-text-primary">{detailTrend.message}</p>
+                    <div className="font-mono text-xs text-text-secondary mt-2 break-all">
+                      {detailTrend.points
+                        .map((point) => `${new Date(point.timestamp * 1000).toLocaleDateString()}: ${point.mAhPerHour ?? '-'}`)
+                        .join(', ')}
+                    </div>
+                  </div>
+                )}
               </div>
 
+              {/* Open the package's pages on the device */}
+              {isConnected && (
+                <div className="mt-4">
+                  <div className="grid grid-cols-2 gap-2">
+                    {([
+                      ['App Info', 'Open the App info page on the device', FiSettings, api.openAppInfo],
+                      ['Play Store', 'Open the store listing on the device', FiShoppingBag, api.openInPlayStore],
+                    ] as Array<[string, string, typeof FiSettings, (packageName: string) => Promise<GuidedActionResult>]>).map(
+                      ([label, title, Icon, action]) => (
+                        <button
+                          key={label}
+                          type="button"
+                          onClick={() => runDeviceAction(() => action(detailPackage.packageName))}
+                          className="btn-ghost text-sm flex items-center justify-center gap-1.5"
+                          style={{ padding: '10px', borderRadius: '10px' }}
+                          title={title}
+                        >
+                          <Icon className="w-3.5 h-3.5" />
+                          {label}
+                        </button>
+                      )
+                    )}
+                  </div>
+                  {deviceActionMessage && (
+                    <p className="text-xs text-text-secondary mt-2">{deviceActionMessage}</p>
+                  )}
+                </div>
+              )}
+
               {/* Modal Footer */}
               <motion.button
                 type="button"
@@ -0,0 +1,64 @@
+import React, { useEffect, useState } from 'react';
+import { FiAlertTriangle } from 'react-icons/fi';
+import { api, ParserFailure } from '../utils/api';
+
+/** Offers a diagnostics export when a metric parser could not read this device's output */
+const ParserFailureNotice: React.FC = () => {
+  const [failures, setFailures] = useState<ParserFailure[]>([]);
+  const [includeOutput, setIncludeOutput] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const loadFailures = () => {
+    api.listParserFailures().then(setFailures).catch((error) => console.error('list_parser_failures failed', error));
+  };
+
+  useEffect(() => {
+    loadFailures();
+    return api.onBackendEvent((event) => {
+      if (event.kind === 'parser_failed') loadFailures();
+    });
+  }, []);
+
+  const exportBundle = async () => {
+    const result = await api.exportDiagnostics(includeOutput);
+    setMessage(result.message);
+  };
+
+  const dismiss = async () => {
+    await api.clearParserFailures();
+    setFailures([]);
+    setMessage(null);
+  };
+
+  if (failures.length === 0) return null;
+
+  const parsers = Array.from(new Set(failures.map((failure) => failure.parser)));
+
+  return (
+    <div className="mt-4 p-3 rounded-lg text-xs" style={{ border: '1px solid var(--theme-border)', color: 'var(--theme-text-secondary)' }}>
+      <div className="flex items-center gap-2 mb-1" style={{ color: 'var(--theme-text-primary)' }}>
+        <FiAlertTriangle className="w-3.5 h-3.5" />
+        <span className="font-semibold">Some readings failed on your device</span>
+      </div>
+      <p>Could not read: {parsers.join(', ')}. Exporting diagnostics helps add support for this device.</p>
+      {failures.some((failure) => failure.reason === 'encoding') && (
+        <p className="mt-1">Your device's shell printed text in a language encoding Debloat AI cannot decode.</p>
+      )}
+      <label className="flex items-center gap-2 mt-2">
+        <input type="checkbox" checked={includeOutput} onChange={(e) => setIncludeOutput(e.target.checked)} />
+        Include the raw device output (may contain app names)
+      </label>
+      {message && <p className="mt-1 break-all">{message}</p>}
+      <div className="flex gap-3 mt-2">
+        <button type="button" onClick={exportBundle} style={{ color: 'var(--theme-accent)' }}>
+          Export diagnostics
+        </button>
+        <button type="button" onClick={dismiss}>
+          Dismiss
+        </button>
+      </div>
+    </div>
+  );
+};
+
+export default ParserFailureNotice;
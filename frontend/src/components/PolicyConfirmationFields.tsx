@@ -0,0 +1,84 @@
+import React from 'react';
+import { ConfirmationRequirement } from '../utils/api';
+
+/** What has been typed so far: package name inputs by package, and the phrase */
+export interface TypedConfirmation {
+  packages: Record<string, string>;
+  phrase: string;
+}
+
+export const EMPTY_TYPED_CONFIRMATION: TypedConfirmation = { packages: {}, phrase: '' };
+
+/** Whether everything the policy asks to be typed matches; blocked requirements never do */
+export const typedConfirmationComplete = (requirement: ConfirmationRequirement | null | undefined,
+                                          typed: TypedConfirmation) => {
+  if (!requirement) return true;
+  if (requirement.level === 'blocked') return false;
+  const namesTyped = requirement.packagesToType.every((name) => (typed.packages[name] ?? '').trim() === name);
+  return namesTyped && (requirement.phrase === null || typed.phrase.trim() === requirement.phrase);
+};
+
+interface PolicyConfirmationFieldsProps {
+  requirement: ConfirmationRequirement;
+  typed: TypedConfirmation;
+  onChange: (typed: TypedConfirmation) => void;
+}
+
+/** Inputs for the package names and phrase the confirmation policy of the active role asks for */
+const PolicyConfirmationFields: React.FC<PolicyConfirmationFieldsProps> = ({ requirement, typed, onChange }) => {
+  if (requirement.level === 'blocked') {
+    return (
+      <div className="p-3 mb-4 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 rounded-lg">
+        <p className="text-sm text-red-800 dark:text-red-200">
+          The <strong>{requirement.role}</strong> role may not {requirement.operation}{' '}
+          {requirement.blocked.join(', ')}. Ask someone with a higher role, or deselect them.
+        </p>
+      </div>
+    );
+  }
+  if (requirement.packagesToType.length === 0 && requirement.phrase === null) {
+    return null;
+  }
+
+  const inputClass = 'w-full px-3 py-2 text-sm font-mono border border-gray-300 dark:border-gray-700 ' +
+    'bg-white dark:bg-[#111] text-gray-900 dark:text-white rounded-lg focus:outline-none focus:ring-2 focus:ring-red-500';
+
+  return (
+    <div className="space-y-3 mb-4">
+      <p className="text-xs text-gray-600 dark:text-gray-400">
+        Your organization's policy for the <strong>{requirement.role}</strong> role asks you to type out:
+      </p>
+      {requirement.packagesToType.map((name) => (
+        <label key={name} className="block">
+          <span className="block text-xs font-mono text-gray-700 dark:text-gray-300 mb-1">{name}</span>
+          <input
+            type="text"
+            value={typed.packages[name] ?? ''}
+            onChange={(e) => onChange({ ...typed, packages: { ...typed.packages, [name]: e.target.value } })}
+            placeholder="Type the package name"
+            spellCheck={false}
+            autoComplete="off"
+            className={inputClass}
+          />
+        </label>
+      ))}
+      {requirement.phrase !== null && (
+        <label className="block">
+          <span className="block text-xs text-gray-700 dark:text-gray-300 mb-1">
+            Type “{requirement.phrase}”
+          </span>
+          <input
+            type="text"
+            value={typed.phrase}
+            onChange={(e) => onChange({ ...typed, phrase: e.target.value })}
+            spellCheck={false}
+            autoComplete="off"
+            className={inputClass}
+          />
+        </label>
+      )}
+    </div>
+  );
+};
+
+export default PolicyConfirmationFields;
@@ -0,0 +1,92 @@
+import React, { useEffect, useState } from 'react';
+import { api, RawDump, RawDumpService } from '../utils/api';
+
+/** Raw dumpsys output, for checking a metric against what the device reported */
+const RawDumpViewer: React.FC = () => {
+  const [services, setServices] = useState<Record<string, RawDumpService>>({});
+  const [service, setService] = useState('package');
+  const [packageName, setPackageName] = useState('');
+  const [dump, setDump] = useState<RawDump | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    api.listRawDumpServices().then(setServices).catch((error) => console.error('list_raw_dump_services failed', error));
+  }, []);
+
+  const load = async (page: number, refresh = false) => {
+    setLoading(true);
+    try {
+      const pkg = services[service]?.perPackage ? packageName.trim() || undefined : undefined;
+      setDump(await api.getRawDump(service, pkg, page, undefined, refresh));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const page = dump?.page ?? 0;
+  const inputClass = 'px-3 py-2 border border-gray-300 dark:border-gray-700 bg-white dark:bg-[#1a1a1a] text-sm focus:outline-none focus:ring-2 focus:ring-blue-500';
+
+  return (
+    <div className="flex flex-col gap-2">
+      <div className="flex flex-col sm:flex-row gap-2">
+        <select value={service} onChange={(e) => setService(e.target.value)} className={inputClass}>
+          {Object.entries(services).map(([name, info]) => (
+            <option key={name} value={name} title={info.usedFor}>
+              {name}
+            </option>
+          ))}
+        </select>
+        <input
+          type="text"
+          value={packageName}
+          onChange={(e) => setPackageName(e.target.value)}
+          disabled={!services[service]?.perPackage}
+          placeholder="Package (optional), e.g. com.facebook.katana"
+          className={`flex-1 disabled:opacity-50 ${inputClass}`}
+        />
+        <button
+          onClick={() => load(0, true)}
+          disabled={loading}
+          className="px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white text-sm font-medium rounded-lg disabled:opacity-50"
+        >
+          {loading ? 'Loading...' : 'Dump'}
+        </button>
+      </div>
+      {services[service] && (
+        <p className="text-xs text-gray-500 dark:text-gray-400">Used for: {services[service].usedFor}</p>
+      )}
+      {dump && !dump.success && <p className="text-xs text-red-600 dark:text-red-400">{dump.message}</p>}
+      {dump?.success && (
+        <>
+          <div className="flex items-center justify-between text-xs text-gray-500 dark:text-gray-400">
+            <code>{dump.command}</code>
+            <span>
+              Lines {dump.firstLine}–{Math.min((dump.firstLine ?? 1) + (dump.pageLines ?? 0) - 1, dump.totalLines ?? 0)} of {dump.totalLines}
+              {dump.truncated && ' (output cut off at the size limit)'}
+            </span>
+          </div>
+          <pre className="max-h-96 overflow-auto p-2 text-xs bg-gray-50 dark:bg-[#111] border border-gray-200 dark:border-gray-800 whitespace-pre">
+            {dump.text}
+          </pre>
+          <div className="flex items-center justify-end gap-3 text-xs">
+            <button onClick={() => load(page - 1)} disabled={loading || page === 0} className="disabled:opacity-40">
+              Previous
+            </button>
+            <span className="text-gray-500 dark:text-gray-400">
+              Page {page + 1} of {dump.totalPages}
+            </span>
+            <button
+              onClick={() => load(page + 1)}
+              disabled={loading || page + 1 >= (dump.totalPages ?? 1)}
+              className="disabled:opacity-40"
+            >
+              Next
+            </button>
+          </div>
+        </>
+      )}
+    </div>
+  );
+};
+
+export default RawDumpViewer;
@@ -0,0 +1,76 @@
+import React, { useEffect, useState } from 'react';
+import { FiRotateCw } from 'react-icons/fi';
+import { api, JournaledJob } from '../utils/api';
+
+interface ResumeJobBannerProps {
+  refreshTrigger?: number;
+  onResume: (job: JournaledJob) => void;
+  onNotify?: (message: string, type: 'success' | 'error' | 'info') => void;
+}
+
+/** Batch removals left unfinished by a crash, a closed window or an unplugged device */
+const ResumeJobBanner: React.FC<ResumeJobBannerProps> = ({ refreshTrigger, onResume, onNotify }) => {
+  const [jobs, setJobs] = useState<JournaledJob[]>([]);
+
+  const loadJobs = async () => {
+    try {
+      setJobs(await api.getIncompleteJobs());
+    } catch (error) {
+      console.error('get_incomplete_jobs failed', error);
+    }
+  };
+
+  useEffect(() => {
+    loadJobs();
+  }, [refreshTrigger]);
+
+  const resume = async (job: JournaledJob) => {
+    const result = await api.resumeJob(job.id);
+    if (result.success && result.job) {
+      setJobs((prev) => prev.filter((j) => j.id !== job.id));
+      onResume(result.job);
+    } else {
+      onNotify?.(result.message ?? 'Could not resume the job', 'error');
+    }
+  };
+
+  const discard = async (job: JournaledJob) => {
+    const result = await api.discardJob(job.id);
+    onNotify?.(result.message, result.success ? 'info' : 'error');
+    loadJobs();
+  };
+
+  if (jobs.length === 0) return null;
+
+  return (
+    <div className="mb-3 space-y-2">
+      {jobs.map((job) => (
+        <div
+          key={job.id}
+          className="flex items-center gap-3 px-3 py-2 rounded-lg text-xs"
+          style={{ border: '1px solid var(--theme-border)', color: 'var(--theme-text-secondary)' }}
+        >
+          <FiRotateCw className="w-4 h-4 flex-shrink-0" style={{ color: 'var(--theme-accent)' }} />
+          <span className="flex-1 truncate" title={job.pending.join('\n')}>
+            Removal interrupted: {job.completed.length} of {job.selection.length} done, {job.pending.length} left
+            {!job.deviceConnected && job.serial && ` (connect ${job.serial} to resume)`}
+          </span>
+          <button
+            type="button"
+            onClick={() => resume(job)}
+            disabled={!job.deviceConnected}
+            title="Remove the packages that are left"
+            className="disabled:opacity-50"
+          >
+            Resume
+          </button>
+          <button type="button" onClick={() => discard(job)} title="Forget the packages that are left">
+            Discard
+          </button>
+        </div>
+      ))}
+    </div>
+  );
+};
+
+export default ResumeJobBanner;
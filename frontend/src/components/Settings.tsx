@@ -1,7 +1,11 @@
 import React, { useState, useEffect } from 'react';
 import { motion } from 'framer-motion';
-import { api } from '../utils/api';
+import { api, ConfirmationPolicy, RemoteSettings, RemoteStatus } from '../utils/api';
 import { staggerContainer, staggerItem } from '../utils/animations';
+import RawDumpViewer from './RawDumpViewer';
+import CacheDiagnosticsPanel from './CacheDiagnosticsPanel';
+import ThemeOverlaysPanel from './ThemeOverlaysPanel';
+import ApexModulesPanel from './ApexModulesPanel';
 
 // Settings interface
 interface AppSettings {
@@ -35,12 +39,127 @@ const Settings: React.FC = () => {
   const [showAdvanced, setShowAdvanced] = useState(false);
   const [hasChanges, setHasChanges] = useState(false);
   const [saveStatus, setSaveStatus] = useState<'idle' | 'saving' | 'saved'>('idle');
+  // Stored by the backend, since it enforces it (null until loaded)
+  const [offlineMode, setOfflineMode] = useState<boolean | null>(null);
+  const [proxy, setProxy] = useState('');
+  const [caBundle, setCaBundle] = useState('');
+  const [baseUrl, setBaseUrl] = useState('');
+  const [plainLanguage, setPlainLanguage] = useState<boolean | null>(null);
+  const [stayAwake, setStayAwake] = useState<boolean | null>(null);
+  const [crashReports, setCrashReports] = useState<boolean | null>(null);
+  const [crashReportUrl, setCrashReportUrl] = useState('');
+  const [remote, setRemote] = useState<RemoteStatus | null>(null);
+  const [remoteHost, setRemoteHost] = useState('');
+  const [remotePort, setRemotePort] = useState('');
+  const [remoteMessage, setRemoteMessage] = useState<string | null>(null);
+  const [confirmationPolicy, setConfirmationPolicy] = useState<ConfirmationPolicy | null>(null);
+  const [roleMessage, setRoleMessage] = useState<string | null>(null);
+  const [networkStatus, setNetworkStatus] = useState<{ ok: boolean; message: string } | null>(null);
 
   // Load settings from localStorage on mount
   useEffect(() => {
     loadSettings();
+    api.getSettings()
+      .then((backend) => {
+        setOfflineMode(backend.network.offline);
+        setProxy(backend.network.proxy);
+        setCaBundle(backend.network.caBundle);
+        setBaseUrl(backend.ai.baseUrl);
+        setPlainLanguage(backend.ai.plainLanguage);
+        setStayAwake(backend.devices.stayAwakeDuringJobs);
+        setCrashReports(backend.diagnostics.crashReports);
+        setCrashReportUrl(backend.diagnostics.crashReportUrl);
+        setRemoteHost(backend.remote.host);
+        setRemotePort(String(backend.remote.port));
+      })
+      .catch((error) => console.error('Failed to load backend settings:', error));
+    api.getRemoteStatus().then(setRemote).catch((error) => console.error('get_remote_status failed', error));
+    api.getConfirmationPolicy()
+      .then(setConfirmationPolicy)
+      .catch((error) => console.error('get_confirmation_policy failed', error));
   }, []);
 
+  const saveConnectionSettings = async () => {
+    const result = await api.updateSettings({
+      network: { proxy: proxy.trim(), caBundle: caBundle.trim() },
+      ai: { baseUrl: baseUrl.trim() },
+    });
+    setNetworkStatus({ ok: result.success, message: result.success ? 'Connection settings saved' : result.message });
+    return result.success;
+  };
+
+  const testConnection = async () => {
+    if (!(await saveConnectionSettings())) return;
+    setNetworkStatus({ ok: true, message: 'Testing connection...' });
+    const result = await api.testConnection();
+    setNetworkStatus({ ok: result.success, message: result.message });
+  };
+
+  const toggleOfflineMode = async () => {
+    if (offlineMode === null) return;
+    const result = await api.updateSettings({ network: { offline: !offlineMode } });
+    if (result.success && result.settings) {
+      setOfflineMode(result.settings.network.offline);
+    } else {
+      console.error('Failed to change offline mode:', result.message);
+    }
+  };
+
+  const togglePlainLanguage = async () => {
+    if (plainLanguage === null) return;
+    const result = await api.updateSettings({ ai: { plainLanguage: !plainLanguage } });
+    if (result.success && result.settings) {
+      setPlainLanguage(result.settings.ai.plainLanguage);
+    } else {
+      console.error('Failed to change plain-language mode:', result.message);
+    }
+  };
+
+  const toggleStayAwake = async () => {
+    if (stayAwake === null) return;
+    const result = await api.updateSettings({ devices: { stayAwakeDuringJobs: !stayAwake } });
+    if (result.success && result.settings) {
+      setStayAwake(result.settings.devices.stayAwakeDuringJobs);
+    } else {
+      console.error('Failed to change stay-awake setting:', result.message);
+    }
+  };
+
+  const toggleCrashReports = async () => {
+    if (crashReports === null) return;
+    const result = await api.updateSettings({ diagnostics: { crashReports: !crashReports } });
+    if (result.success && result.settings) {
+      setCrashReports(result.settings.diagnostics.crashReports);
+    } else {
+      console.error('Failed to change crash report setting:', result.message);
+    }
+  };
+
+  const saveCrashReportUrl = async () => {
+    const result = await api.updateSettings({ diagnostics: { crashReportUrl: crashReportUrl.trim() } });
+    if (!result.success) {
+      console.error('Failed to save crash report endpoint:', result.message);
+    }
+  };
+
+  const updateRemote = async (changes: Partial<RemoteSettings>) => {
+    const result = await api.updateSettings({ remote: changes });
+    if (result.remote) setRemote(result.remote);
+    setRemoteMessage(result.success ? result.remote?.error ?? null : result.message);
+  };
+
+  const regenerateRemoteToken = async () => {
+    const result = await api.regenerateRemoteToken();
+    setRemote((current) => current && { ...current, token: result.token });
+    setRemoteMessage(result.message);
+  };
+
+  const changeConfirmationRole = async (role: string) => {
+    const result = await api.setConfirmationRole(role);
+    if (result.policy) setConfirmationPolicy(result.policy);
+    setRoleMessage(result.success ? null : result.message);
+  };
+
   // Apply settings when they change
   useEffect(() => {
     applySettings();
@@ -66,6 +185,9 @@ const Settings: React.FC = () => {
     try {
       setSaveStatus('saving');
       localStorage.setItem('app-settings', JSON.stringify(settings));
+      // Sizes and percents from the backend are formatted in this language
+      api.updateSettings({ display: { locale: settings.language } })
+        .then((result) => { if (!result.success) console.error('Failed to save display locale:', result.message); });
       setHasChanges(false);
       setSaveStatus('saved');
       setTimeout(() => setSaveStatus('idle'), 2000);
@@ -225,6 +347,41 @@ const Settings: React.FC = () => {
           </select>
         </motion.div>
 
+        {/* Plain-Language AI */}
+        <motion.div 
+          className="flex flex-col sm:flex-row sm:items-center sm:justify-between gap-2 sm:gap-0 py-3 border-b border-gray-100 dark:border-gray-800"
+          variants={staggerItem}
+        >
+          <div>
+            <label className="text-sm font-medium text-gray-900 dark:text-white">
+              Plain-Language AI Answers
+            </label>
+            <p className="text-xs text-gray-500 dark:text-gray-400 mt-0.5">
+              Short sentences and everyday words in AI analysis and chat
+            </p>
+          </div>
+          <button
+            onClick={togglePlainLanguage}
+            disabled={plainLanguage === null}
+            className={`
+              relative inline-flex h-6 w-11 items-center border-2 transition-colors self-start sm:self-auto rounded-full disabled:opacity-50
+              ${
+                plainLanguage
+                  ? 'bg-green-600 border-green-600'
+                  : 'bg-gray-200 dark:bg-gray-700 border-gray-200 dark:border-gray-700'
+              }
+            `}
+            role="switch"
+            aria-checked={!!plainLanguage}
+          >
+            <motion.span
+              className="inline-block h-4 w-4 bg-white rounded-full shadow-md"
+              animate={{ x: plainLanguage ? 20 : 2 }}
+              transition={{ type: 'spring' as const, stiffness: 500, damping: 30 }}
+            />
+          </button>
+        </motion.div>
+
         {/* Show Advanced Options */}
         <motion.div 
           className="flex flex-col sm:flex-row sm:items-center sm:justify-between gap-2 sm:gap-0 py-3"
@@ -318,6 +475,291 @@ const Settings: React.FC = () => {
             </div>
           </div>
         </div>
+
+        {/* Stay Awake During Jobs */}
+        <div className="flex flex-col sm:flex-row sm:items-center sm:justify-between gap-2 sm:gap-0 py-3 border-t border-gray-100 dark:border-gray-800">
+          <div>
+            <label className="text-sm font-medium text-gray-900 dark:text-white">
+              Keep Device Awake During Long Jobs
+            </label>
+            <p className="text-xs text-gray-500 dark:text-gray-400 mt-0.5">
+              Stops the screen sleeping over USB during batches, backups and optimizations; your setting is restored after
+            </p>
+          </div>
+          <button
+            onClick={toggleStayAwake}
+            disabled={stayAwake === null}
+            className={`
+              relative inline-flex h-6 w-11 items-center border-2 transition-colors self-start sm:self-auto rounded-full disabled:opacity-50
+              ${
+                stayAwake
+                  ? 'bg-green-600 border-green-600'
+                  : 'bg-gray-200 dark:bg-gray-700 border-gray-200 dark:border-gray-700'
+              }
+            `}
+            role="switch"
+            aria-checked={!!stayAwake}
+          >
+            <motion.span
+              className="inline-block h-4 w-4 bg-white rounded-full shadow-md"
+              animate={{ x: stayAwake ? 20 : 2 }}
+              transition={{ type: 'spring' as const, stiffness: 500, damping: 30 }}
+            />
+          </button>
+        </div>
+
+        {/* Crash Reports */}
+        <div className="flex flex-col sm:flex-row sm:items-center sm:justify-between gap-2 sm:gap-0 py-3 border-t border-gray-100 dark:border-gray-800">
+          <div>
+            <label className="text-sm font-medium text-gray-900 dark:text-white">
+              Save Crash Reports
+            </label>
+            <p className="text-xs text-gray-500 dark:text-gray-400 mt-0.5">
+              Writes the error and recent activity to ~/DebloatAI/crash_reports; no device serials are included
+            </p>
+            {crashReports && (
+              <input
+                type="text"
+                value={crashReportUrl}
+                onChange={(e) => setCrashReportUrl(e.target.value)}
+                onBlur={saveCrashReportUrl}
+                placeholder="Also send to https://... (optional)"
+                className="mt-2 w-full sm:w-72 px-2 py-1 text-xs rounded border border-gray-200 dark:border-gray-700 bg-white dark:bg-gray-900 text-gray-900 dark:text-white"
+              />
+            )}
+          </div>
+          <button
+            onClick={toggleCrashReports}
+            disabled={crashReports === null}
+            className={`
+              relative inline-flex h-6 w-11 items-center border-2 transition-colors self-start sm:self-auto rounded-full disabled:opacity-50
+              ${
+                crashReports
+                  ? 'bg-green-600 border-green-600'
+                  : 'bg-gray-200 dark:bg-gray-700 border-gray-200 dark:border-gray-700'
+              }
+            `}
+            role="switch"
+            aria-checked={!!crashReports}
+          >
+            <motion.span
+              className="inline-block h-4 w-4 bg-white rounded-full shadow-md"
+              animate={{ x: crashReports ? 20 : 2 }}
+              transition={{ type: 'spring' as const, stiffness: 500, damping: 30 }}
+            />
+          </button>
+        </div>
+
+        {/* Remote Control */}
+        <div className="flex flex-col sm:flex-row sm:items-center sm:justify-between gap-2 sm:gap-0 py-3 border-t border-gray-100 dark:border-gray-800">
+          <div>
+            <label className="text-sm font-medium text-gray-900 dark:text-white">
+              Remote Control
+            </label>
+            <p className="text-xs text-gray-500 dark:text-gray-400 mt-0.5">
+              Serves every backend command over HTTP for a browser or remote UI; clients need the token below
+            </p>
+            {remote?.enabled && (
+              <div className="mt-2 space-y-1 text-xs text-gray-500 dark:text-gray-400">
+                <div className="flex gap-2">
+                  <input
+                    type="text"
+                    value={remoteHost}
+                    onChange={(e) => setRemoteHost(e.target.value)}
+                    onBlur={() => updateRemote({ host: remoteHost.trim() })}
+                    aria-label="Listen address"
+                    className="w-40 px-2 py-1 rounded border border-gray-200 dark:border-gray-700 bg-white dark:bg-gray-900 text-gray-900 dark:text-white"
+                  />
+                  <input
+                    type="number"
+                    value={remotePort}
+                    onChange={(e) => setRemotePort(e.target.value)}
+                    onBlur={() => updateRemote({ port: Number(remotePort) })}
+                    aria-label="Port"
+                    className="w-24 px-2 py-1 rounded border border-gray-200 dark:border-gray-700 bg-white dark:bg-gray-900 text-gray-900 dark:text-white"
+                  />
+                </div>
+                {remote.url && <p className="font-mono">{remote.url}</p>}
+                {remote.running && !remote.loopbackOnly && (
+                  <p className="text-amber-600">Reachable from other computers on the network</p>
+                )}
+                <p className="font-mono break-all">
+                  Token: {remote.token}{' '}
+                  <button type="button" onClick={regenerateRemoteToken} className="underline">
+                    New token
+                  </button>
+                </p>
+              </div>
+            )}
+            {remoteMessage && <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">{remoteMessage}</p>}
+          </div>
+          <button
+            onClick={() => updateRemote({ enabled: !remote?.enabled })}
+            disabled={remote === null}
+            className={`
+              relative inline-flex h-6 w-11 items-center border-2 transition-colors self-start sm:self-auto rounded-full disabled:opacity-50
+              ${
+                remote?.enabled
+                  ? 'bg-green-600 border-green-600'
+                  : 'bg-gray-200 dark:bg-gray-700 border-gray-200 dark:border-gray-700'
+              }
+            `}
+            role="switch"
+            aria-checked={!!remote?.enabled}
+          >
+            <motion.span
+              className="inline-block h-4 w-4 bg-white rounded-full shadow-md"
+              animate={{ x: remote?.enabled ? 20 : 2 }}
+              transition={{ type: 'spring' as const, stiffness: 500, damping: 30 }}
+            />
+          </button>
+        </div>
+
+        {/* Confirmation Role */}
+        {confirmationPolicy?.custom && (
+          <div className="flex flex-col sm:flex-row sm:items-center sm:justify-between gap-2 sm:gap-0 py-3 border-t border-gray-100 dark:border-gray-800">
+            <div>
+              <label className="text-sm font-medium text-gray-900 dark:text-white">
+                Confirmation Role
+              </label>
+              <p className="text-xs text-gray-500 dark:text-gray-400 mt-0.5">
+                {confirmationPolicy.roleLocked
+                  ? 'Set by your organization in confirmation_policy.json'
+                  : 'How firmly removals must be confirmed, from confirmation_policy.json'}
+              </p>
+              {roleMessage && <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">{roleMessage}</p>}
+            </div>
+            <select
+              value={confirmationPolicy.role}
+              onChange={(e) => changeConfirmationRole(e.target.value)}
+              disabled={confirmationPolicy.roleLocked}
+              className="px-2 py-1 text-sm rounded border border-gray-200 dark:border-gray-700 bg-white dark:bg-gray-900 text-gray-900 dark:text-white disabled:opacity-50"
+            >
+              {confirmationPolicy.roles.map((role) => (
+                <option key={role} value={role}>{role}</option>
+              ))}
+            </select>
+          </div>
+        )}
+
+        {/* Theme Overlays */}
+        <div className="py-3 border-t border-gray-100 dark:border-gray-800">
+          <label className="text-sm font-medium text-gray-900 dark:text-white block mb-2">
+            Theme Overlays
+          </label>
+          <p className="text-xs text-gray-500 dark:text-gray-400 mb-3">
+            Icon shapes, colours and display cutouts; turning one off keeps it installed
+          </p>
+          <ThemeOverlaysPanel />
+        </div>
+
+        {/* System Modules */}
+        <div className="py-3 border-t border-gray-100 dark:border-gray-800">
+          <label className="text-sm font-medium text-gray-900 dark:text-white block mb-2">
+            System Modules
+          </label>
+          <p className="text-xs text-gray-500 dark:text-gray-400 mb-3">
+            APEX modules updated by Google through the Play Store; they and the apps inside them cannot be removed
+          </p>
+          <ApexModulesPanel />
+        </div>
+      </motion.section>
+
+      {/* Network Settings */}
+      <motion.section 
+        className="mb-6 md:mb-8"
+        initial={{ opacity: 0, y: 20 }}
+        animate={{ opacity: 1, y: 0 }}
+        transition={{ duration: 0.4, delay: 0.25 }}
+      >
+        <h3 className="text-base md:text-lg font-semibold text-gray-900 dark:text-white mb-4 pb-2 border-b border-gray-200 dark:border-gray-700">
+          Network
+        </h3>
+
+        {/* Offline Mode */}
+        <div className="flex flex-col sm:flex-row sm:items-center sm:justify-between gap-2 sm:gap-0 py-3">
+          <div>
+            <label className="text-sm font-medium text-gray-900 dark:text-white">
+              Offline Mode
+            </label>
+            <p className="text-xs text-gray-500 dark:text-gray-400 mt-0.5">
+              Block all internet access; AI analysis and chat fall back to the built-in safety ratings
+            </p>
+          </div>
+          <button
+            onClick={toggleOfflineMode}
+            disabled={offlineMode === null}
+            className={`
+              relative inline-flex h-6 w-11 items-center border-2 transition-colors self-start sm:self-auto rounded-full disabled:opacity-50
+              ${
+                offlineMode
+                  ? 'bg-green-600 border-green-600'
+                  : 'bg-gray-200 dark:bg-gray-700 border-gray-200 dark:border-gray-700'
+              }
+            `}
+            role="switch"
+            aria-checked={!!offlineMode}
+          >
+            <motion.span
+              className="inline-block h-4 w-4 bg-white rounded-full shadow-md"
+              animate={{ x: offlineMode ? 20 : 2 }}
+              transition={{ type: 'spring' as const, stiffness: 500, damping: 30 }}
+            />
+          </button>
+        </div>
+
+        {/* Proxy and CA bundle */}
+        <div className="py-3 border-t border-gray-100 dark:border-gray-800">
+          <label className="text-sm font-medium text-gray-900 dark:text-white block mb-1">
+            AI Endpoint, Proxy and Certificates
+          </label>
+          <p className="text-xs text-gray-500 dark:text-gray-400 mb-3">
+            For networks that block direct access to the AI provider. Leave empty to connect directly.
+          </p>
+          <div className="flex flex-col gap-2">
+            <input
+              type="text"
+              value={baseUrl}
+              onChange={(e) => setBaseUrl(e.target.value)}
+              placeholder="AI endpoint (LiteLLM / OpenRouter), e.g. https://openrouter.ai/api/v1"
+              className="px-3 py-2 border border-gray-300 dark:border-gray-700 bg-white dark:bg-[#1a1a1a] text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+            />
+            <input
+              type="text"
+              value={proxy}
+              onChange={(e) => setProxy(e.target.value)}
+              placeholder="Proxy, e.g. http://proxy.example.com:8080"
+              className="px-3 py-2 border border-gray-300 dark:border-gray-700 bg-white dark:bg-[#1a1a1a] text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+            />
+            <input
+              type="text"
+              value={caBundle}
+              onChange={(e) => setCaBundle(e.target.value)}
+              placeholder="Custom root certificate (PEM file path)"
+              className="px-3 py-2 border border-gray-300 dark:border-gray-700 bg-white dark:bg-[#1a1a1a] text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+            />
+            <div className="flex gap-2">
+              <button
+                onClick={saveConnectionSettings}
+                className="px-4 py-2 text-sm font-medium border border-gray-300 dark:border-gray-700 hover:bg-gray-50 dark:hover:bg-gray-800"
+              >
+                Save
+              </button>
+              <button
+                onClick={testConnection}
+                disabled={!!offlineMode}
+                className="px-4 py-2 text-sm font-medium text-white bg-green-600 hover:bg-green-700 disabled:opacity-50"
+              >
+                Test Connection
+              </button>
+            </div>
+            {networkStatus && (
+              <p className={`text-xs ${networkStatus.ok ? 'text-green-600 dark:text-green-400' : 'text-red-600 dark:text-red-400'}`}>
+                {networkStatus.message}
+              </p>
+            )}
+          </div>
+        </div>
       </motion.section>
 
       {/* Storage Settings */}
@@ -410,6 +852,28 @@ const Settings: React.FC = () => {
               className="w-full px-3 py-2.5 md:py-2 border border-gray-300 dark:border-gray-700 bg-white dark:bg-[#1a1a1a] text-sm focus:outline-none focus:ring-2 focus:ring-blue-500 min-h-[44px] md:min-h-0"
             />
           </div>
+
+          {/* Raw dumpsys viewer */}
+          <div className="py-3 border-t border-gray-100 dark:border-gray-800">
+            <label className="text-sm font-medium text-gray-900 dark:text-white block mb-2">
+              Raw Device Data
+            </label>
+            <p className="text-xs text-gray-500 dark:text-gray-400 mb-3">
+              The dumpsys output the app reads, to check a value that looks wrong
+            </p>
+            <RawDumpViewer />
+          </div>
+
+          {/* Cache statistics */}
+          <div className="py-3 border-t border-gray-100 dark:border-gray-800">
+            <label className="text-sm font-medium text-gray-900 dark:text-white block mb-2">
+              Cache Statistics
+            </label>
+            <p className="text-xs text-gray-500 dark:text-gray-400 mb-3">
+              How often cached data is reused, and how old and large it is
+            </p>
+            <CacheDiagnosticsPanel />
+          </div>
         </motion.section>
       )}
 
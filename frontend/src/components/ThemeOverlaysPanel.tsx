@@ -0,0 +1,77 @@
+import React, { useState } from 'react';
+import { api, Overlay } from '../utils/api';
+
+/** Runtime resource overlays grouped by the package they restyle, each with an on/off switch */
+const ThemeOverlaysPanel: React.FC = () => {
+  const [overlays, setOverlays] = useState<Overlay[] | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [busy, setBusy] = useState<string | null>(null);
+
+  const load = async () => {
+    setLoading(true);
+    try {
+      const result = await api.listOverlays();
+      setOverlays(result.overlays);
+      setMessage(result.success ? null : result.message ?? 'Could not list overlays');
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const toggle = async (overlay: Overlay) => {
+    setBusy(overlay.id);
+    try {
+      const result = await api.setOverlayEnabled(overlay.id, overlay.state !== 'enabled');
+      setMessage(result.message);
+      if (result.success) await load();
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const byTarget = (overlays ?? []).reduce<Record<string, Overlay[]>>((groups, overlay) => {
+    (groups[overlay.target] = groups[overlay.target] ?? []).push(overlay);
+    return groups;
+  }, {});
+
+  return (
+    <div className="flex flex-col gap-2">
+      <button
+        type="button"
+        onClick={load}
+        disabled={loading}
+        className="self-start px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white text-sm font-medium rounded-lg disabled:opacity-50"
+      >
+        {loading ? 'Loading…' : overlays ? 'Refresh' : 'Show overlays'}
+      </button>
+      {message && <p className="text-xs text-gray-500 dark:text-gray-400">{message}</p>}
+      {overlays && overlays.length === 0 && !message && (
+        <p className="text-xs text-gray-500 dark:text-gray-400">No overlays on this device</p>
+      )}
+      {Object.entries(byTarget).map(([target, group]) => (
+        <div key={target} className="text-xs text-gray-700 dark:text-gray-300">
+          <div className="font-mono text-gray-500 dark:text-gray-400 py-1">{target}</div>
+          {group.map((overlay) => (
+            <label
+              key={overlay.id}
+              className="flex items-center gap-2 py-0.5 pl-3"
+              title={overlay.locked ? 'Sets the navigation mode; change it in the device settings' : undefined}
+            >
+              <input
+                type="checkbox"
+                checked={overlay.state === 'enabled'}
+                disabled={overlay.locked || overlay.state === 'unavailable' || busy !== null}
+                onChange={() => toggle(overlay)}
+              />
+              <span className="font-mono truncate">{overlay.id}</span>
+              {overlay.state === 'unavailable' && <span className="text-gray-400">(not available)</span>}
+            </label>
+          ))}
+        </div>
+      ))}
+    </div>
+  );
+};
+
+export default ThemeOverlaysPanel;
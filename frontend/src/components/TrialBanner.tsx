@@ -0,0 +1,78 @@
+import React, { useEffect, useState } from 'react';
+import { FiClock } from 'react-icons/fi';
+import { api, Trial, TrialResult } from '../utils/api';
+
+interface TrialBannerProps {
+  refreshTrigger?: number;
+  onNotify?: (message: string, type: 'success' | 'error' | 'info') => void;
+}
+
+const formatRemaining = (expiresAt: number): string => {
+  const minutes = Math.max(0, Math.round((expiresAt * 1000 - Date.now()) / 60000));
+  if (minutes < 60) return `${minutes} min`;
+  const hours = Math.floor(minutes / 60);
+  return hours < 48 ? `${hours} h ${minutes % 60} min` : `${Math.floor(hours / 24)} days`;
+};
+
+/** Active trial disables, with the choice to keep the packages off or bring them back */
+const TrialBanner: React.FC<TrialBannerProps> = ({ refreshTrigger, onNotify }) => {
+  const [trials, setTrials] = useState<Trial[]>([]);
+
+  const loadTrials = async () => {
+    try {
+      setTrials(await api.listTrials());
+    } catch (error) {
+      console.error('list_trials failed', error);
+    }
+  };
+
+  useEffect(() => {
+    loadTrials();
+  }, [refreshTrigger]);
+
+  // Expired trials are re-enabled by the backend scheduler
+  useEffect(() => {
+    return api.onBackendEvent((event) => {
+      if (event.kind === 'trial_reverted') {
+        const result = event.payload as TrialResult;
+        onNotify?.(`Trial ended: ${result.message}`, result.success ? 'info' : 'error');
+        loadTrials();
+      }
+    });
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  const decide = async (trial: Trial, keep: boolean) => {
+    const result = keep ? await api.keepTrial(trial.id) : await api.revertTrial(trial.id);
+    onNotify?.(result.message, result.success ? 'success' : 'error');
+    loadTrials();
+  };
+
+  if (trials.length === 0) return null;
+
+  return (
+    <div className="mb-3 space-y-2">
+      {trials.map((trial) => (
+        <div
+          key={trial.id}
+          className="flex items-center gap-3 px-3 py-2 rounded-lg text-xs"
+          style={{ border: '1px solid var(--theme-border)', color: 'var(--theme-text-secondary)' }}
+        >
+          <FiClock className="w-4 h-4 flex-shrink-0" style={{ color: 'var(--theme-accent)' }} />
+          <span className="flex-1 truncate" title={trial.packages.join('\n')}>
+            Trial: {trial.packages.length} package{trial.packages.length !== 1 ? 's' : ''} disabled,
+            re-enabled in {formatRemaining(trial.expiresAt)}
+          </span>
+          <button type="button" onClick={() => decide(trial, true)} title="Nothing broke: keep the packages disabled">
+            Keep disabled
+          </button>
+          <button type="button" onClick={() => decide(trial, false)} title="Something stopped working: re-enable now">
+            Re-enable now
+          </button>
+        </div>
+      ))}
+    </div>
+  );
+};
+
+export default TrialBanner;
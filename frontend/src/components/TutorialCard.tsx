@@ -0,0 +1,69 @@
+import React, { useEffect, useState } from 'react';
+import { FiCheckCircle, FiCircle, FiX } from 'react-icons/fi';
+import { useDeviceMonitor } from '../hooks/useDeviceMonitor';
+import { api, TutorialState } from '../utils/api';
+
+interface TutorialCardProps {
+  refreshTrigger?: number;
+}
+
+/** First-run tour; steps complete when the backend sees them done on the device */
+const TutorialCard: React.FC<TutorialCardProps> = ({ refreshTrigger }) => {
+  const { device, isConnected } = useDeviceMonitor();
+  const [state, setState] = useState<TutorialState | null>(null);
+
+  // Re-check whenever the device or the package list changes
+  useEffect(() => {
+    api.advanceTutorial().then(setState).catch((error) => console.error('advance_tutorial failed', error));
+  }, [device?.serial, isConnected, refreshTrigger]);
+
+  if (!state || state.completed || state.dismissed) return null;
+
+  const current = state.steps.find((step) => step.id === state.current);
+
+  return (
+    <div className="mt-4 p-3 rounded-lg text-xs" style={{ border: '1px solid var(--theme-border)' }}>
+      <div className="flex items-center justify-between mb-2">
+        <span className="font-semibold" style={{ color: 'var(--theme-text-primary)' }}>Getting started</span>
+        <button type="button" onClick={() => api.dismissTutorial().then(setState)} title="Hide the tour">
+          <FiX className="w-3.5 h-3.5" style={{ color: 'var(--theme-text-secondary)' }} />
+        </button>
+      </div>
+      <ol className="space-y-1">
+        {state.steps.map((step) => (
+          <li
+            key={step.id}
+            className="flex items-center gap-2"
+            style={{
+              color: step.status === 'current' ? 'var(--theme-text-primary)' : 'var(--theme-text-secondary)',
+              textDecoration: step.status === 'skipped' ? 'line-through' : undefined,
+            }}
+          >
+            {step.status === 'done'
+              ? <FiCheckCircle className="w-3.5 h-3.5" style={{ color: 'var(--theme-accent)' }} />
+              : <FiCircle className="w-3.5 h-3.5" />}
+            {step.title}
+          </li>
+        ))}
+      </ol>
+      {current && (
+        <>
+          <p className="mt-2" style={{ color: 'var(--theme-text-secondary)' }}>{current.hint}</p>
+          {state.message && (
+            <p className="mt-1" style={{ color: 'var(--theme-text-secondary)', opacity: 0.7 }}>{state.message}</p>
+          )}
+          <div className="flex gap-3 mt-2">
+            <button type="button" onClick={() => api.advanceTutorial().then(setState)} style={{ color: 'var(--theme-accent)' }}>
+              Check again
+            </button>
+            <button type="button" onClick={() => api.advanceTutorial(true).then(setState)} style={{ color: 'var(--theme-text-secondary)' }}>
+              Skip step
+            </button>
+          </div>
+        </>
+      )}
+    </div>
+  );
+};
+
+export default TutorialCard;
@@ -1,14 +1,23 @@
 import { useState } from 'react';
 import { motion, AnimatePresence } from 'framer-motion';
 import { modalBackdrop, modalContent } from '../utils/animations';
+import { Confirmation, ConfirmationRequirement, SelectionViolation } from '../utils/api';
+import PolicyConfirmationFields, {
+  EMPTY_TYPED_CONFIRMATION,
+  TypedConfirmation,
+  typedConfirmationComplete,
+} from './PolicyConfirmationFields';
 
 interface UninstallDialogProps {
   isOpen: boolean;
   onClose: () => void;
-  onConfirm: () => void;
+  onConfirm: (keepData: boolean, confirmation: Confirmation) => void;
   packageCount: number;
   hasDangerous: boolean;
   hasExpert: boolean;
+  hasUnrestorable?: boolean;  // vendor or APEX packages install-existing may not restore
+  violations?: SelectionViolation[];  // Combination rules the whole selection breaks
+  requirement?: ConfirmationRequirement | null;  // What the confirmation policy asks of the active role
 }
 
 const UninstallDialog: React.FC<UninstallDialogProps> = ({
@@ -18,20 +27,34 @@ const UninstallDialog: React.FC<UninstallDialogProps> = ({
   packageCount,
   hasDangerous,
   hasExpert,
+  hasUnrestorable = false,
+  violations = [],
+  requirement = null,
 }) => {
   const [confirmed, setConfirmed] = useState(false);
+  const [keepData, setKeepData] = useState(false);
+  const [typed, setTyped] = useState<TypedConfirmation>(EMPTY_TYPED_CONFIRMATION);
+  const needsConfirmation = hasDangerous || hasExpert || violations.some((v) => v.severity === 'critical')
+    || (requirement !== null && requirement.level !== 'none');
+  const canConfirm = (confirmed || !needsConfirmation) && typedConfirmationComplete(requirement, typed);
 
   const handleConfirm = () => {
-    if (!confirmed && (hasDangerous || hasExpert)) {
-      return; // Require checkbox for dangerous/expert packages
+    if (!canConfirm) {
+      return; // Require checkbox for dangerous/expert packages, and whatever the policy asks to be typed
     }
-    onConfirm();
+    onConfirm(keepData, {
+      confirmed: true,
+      typedPackages: requirement?.packagesToType.map((name) => (typed.packages[name] ?? '').trim()),
+      phrase: requirement?.phrase ? typed.phrase.trim() : undefined,
+    });
     setConfirmed(false);
+    setTyped(EMPTY_TYPED_CONFIRMATION);
   };
 
   const handleClose = () => {
     onClose();
     setConfirmed(false);
+    setTyped(EMPTY_TYPED_CONFIRMATION);
   };
 
   return (
@@ -103,6 +126,45 @@ const UninstallDialog: React.FC<UninstallDialogProps> = ({
             </div>
           )}
 
+          {violations.map((violation) => (
+            <div
+              key={violation.rule}
+              className={violation.severity === 'critical'
+                ? 'p-3 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800'
+                : 'p-3 bg-orange-50 dark:bg-orange-900/20 border border-orange-200 dark:border-orange-800'}
+            >
+              <div className="flex items-start gap-2">
+                <span className="text-xl">{violation.severity === 'critical' ? '🚨' : '⚡'}</span>
+                <div>
+                  <div className="font-semibold text-gray-900 dark:text-white text-sm mb-1">
+                    {violation.message}
+                  </div>
+                  <p className="text-xs text-gray-700 dark:text-gray-300">
+                    The selection removes every {violation.rule} on the device: {violation.packages.join(', ')}
+                  </p>
+                </div>
+              </div>
+            </div>
+          ))}
+
+          {hasUnrestorable && (
+            <div className="p-3 bg-orange-50 dark:bg-orange-900/20 border border-orange-200 dark:border-orange-800">
+              <div className="flex items-start gap-2">
+                <span className="text-xl">📦</span>
+                <div>
+                  <div className="font-semibold text-orange-800 dark:text-orange-200 text-sm mb-1">
+                    May Not Be Restorable
+                  </div>
+                  <p className="text-xs text-orange-700 dark:text-orange-300">
+                    Some selected packages live on the <strong>vendor</strong> partition or in an
+                    <strong> APEX</strong> module. Backups and undo may not be able to reinstall them;
+                    a factory reset or firmware flash can be the only way back.
+                  </p>
+                </div>
+              </div>
+            </div>
+          )}
+
           {/* General Info */}
           <div className="p-3 bg-blue-50 dark:bg-blue-900/20 border border-blue-200 dark:border-blue-800">
             <div className="flex items-start gap-2">
@@ -122,8 +184,26 @@ const UninstallDialog: React.FC<UninstallDialogProps> = ({
           </div>
         </div>
 
+        {/* Keep Data Choice */}
+        <label className="flex items-start gap-3 mb-4 cursor-pointer">
+          <input
+            type="checkbox"
+            checked={keepData}
+            onChange={(e) => setKeepData(e.target.checked)}
+            className="mt-1 w-5 h-5 cursor-pointer"
+          />
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            Keep app data
+            <span className="block text-xs text-gray-500 dark:text-gray-400">
+              Reinstalling restores the app as it was, but the storage is not freed
+            </span>
+          </span>
+        </label>
+
+        {requirement && <PolicyConfirmationFields requirement={requirement} typed={typed} onChange={setTyped} />}
+
         {/* Confirmation Checkbox (Required for dangerous/expert) */}
-        {(hasDangerous || hasExpert) && (
+        {needsConfirmation && requirement?.level !== 'blocked' && (
           <label className="flex items-start gap-3 mb-6 cursor-pointer">
             <input
               type="checkbox"
@@ -150,18 +230,18 @@ const UninstallDialog: React.FC<UninstallDialogProps> = ({
           </motion.button>
           <motion.button
             onClick={handleConfirm}
-            disabled={(hasDangerous || hasExpert) && !confirmed}
+            disabled={!canConfirm}
             className={`flex-1 px-4 py-2.5 text-white min-h-[44px] rounded-lg ${
-              (hasDangerous || hasExpert) && !confirmed
+              !canConfirm
                 ? 'bg-gray-400 dark:bg-gray-600 cursor-not-allowed'
                 : 'bg-red-600 hover:bg-red-700 dark:bg-red-700 dark:hover:bg-red-800'
             }`}
-            whileHover={(hasDangerous || hasExpert) && !confirmed ? {} : { 
+            whileHover={!canConfirm ? {} : { 
               scale: 1.02, 
               y: -1,
               boxShadow: '0 8px 20px rgba(239, 68, 68, 0.3)'
             }}
-            whileTap={(hasDangerous || hasExpert) && !confirmed ? {} : { scale: 0.98 }}
+            whileTap={!canConfirm ? {} : { scale: 0.98 }}
             transition={{ type: 'spring' as const, stiffness: 400, damping: 17 }}
           >
             {hasDangerous ? '🚨 Uninstall Anyway' : hasExpert ? '⚡ Uninstall' : 'Uninstall'}
@@ -0,0 +1,70 @@
+import React, { useState } from 'react';
+import { FiClock } from 'react-icons/fi';
+import { api, UnusedAppSuggestions } from '../utils/api';
+
+interface UnusedAppsCardProps {
+  /** Select the suggested apps in the package list */
+  onSelect: (packages: string[]) => void;
+}
+
+const PERIODS = [
+  { days: 90, label: '3 months' },
+  { days: 180, label: '6 months' },
+  { days: 365, label: '1 year' },
+];
+
+/** User apps not opened for a while, largest first; looked up on demand since it reads sizes and usage stats */
+const UnusedAppsCard: React.FC<UnusedAppsCardProps> = ({ onSelect }) => {
+  const [days, setDays] = useState(180);
+  const [result, setResult] = useState<UnusedAppSuggestions | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const find = async () => {
+    setLoading(true);
+    try {
+      setResult(await api.getUnusedAppSuggestions(days));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const apps = result?.apps ?? [];
+
+  return (
+    <div
+      className="mb-3 px-3 py-2 rounded-lg text-xs"
+      style={{ border: '1px solid var(--theme-border)', color: 'var(--theme-text-secondary)' }}
+    >
+      <div className="flex items-center gap-3">
+        <FiClock className="w-4 h-4 flex-shrink-0" style={{ color: 'var(--theme-accent)' }} />
+        <span className="flex-1 truncate">{result ? result.message : 'Find user apps you have not opened in'}</span>
+        <select value={days} onChange={(e) => setDays(Number(e.target.value))} className="bg-transparent">
+          {PERIODS.map((period) => (
+            <option key={period.days} value={period.days}>{period.label}</option>
+          ))}
+        </select>
+        <button type="button" onClick={find} disabled={loading}>
+          {loading ? 'Checking…' : result ? 'Refresh' : 'Check'}
+        </button>
+        {apps.length > 0 && (
+          <button type="button" onClick={() => onSelect(apps.map((app) => app.packageName))}>
+            Select all
+          </button>
+        )}
+      </div>
+      {apps.length > 0 && (
+        <div className="mt-2 pl-7 space-y-0.5 max-h-32 overflow-y-auto">
+          {apps.map((app) => (
+            <div key={app.packageName} className="flex gap-2" title={app.packageName}>
+              <span className="flex-1 truncate">{app.appName}</span>
+              <span>{app.daysUnused} days</span>
+              <span className="w-16 text-right">{app.formatted.size ?? '—'}</span>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+};
+
+export default UnusedAppsCard;
@@ -0,0 +1,58 @@
+import React, { useEffect, useState } from 'react';
+import { FiLock } from 'react-icons/fi';
+import { api, UsbPermissionHelp } from '../utils/api';
+
+/** On Linux, explains and fixes a plugged-in device that adb may not open ("no permissions") */
+const UsbPermissionNotice: React.FC = () => {
+  const [help, setHelp] = useState<UsbPermissionHelp | null>(null);
+  const [installing, setInstalling] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const loadHelp = () => {
+    api.getUsbPermissionHelp().then(setHelp).catch((error) => console.error('get_usb_permission_help failed', error));
+  };
+
+  useEffect(() => {
+    loadHelp();
+    const interval = setInterval(loadHelp, 5000);
+    return () => clearInterval(interval);
+  }, []);
+
+  const installRule = async () => {
+    setInstalling(true);
+    try {
+      const result = await api.installUdevRule(help?.vendorId ?? undefined);
+      setMessage(result.message);
+      loadHelp();
+    } finally {
+      setInstalling(false);
+    }
+  };
+
+  if (!help?.needed) return null;
+
+  return (
+    <div className="mt-4 p-3 rounded-lg text-xs text-left" style={{ border: '1px solid var(--theme-border)', color: 'var(--theme-text-secondary)' }}>
+      <div className="flex items-center gap-2 mb-1" style={{ color: 'var(--theme-text-primary)' }}>
+        <FiLock className="w-3.5 h-3.5" />
+        <span className="font-semibold">
+          {help.vendorName ? `${help.vendorName} device` : 'A device'} found, but adb has no permission to open it
+        </span>
+      </div>
+      <p>On {help.distro}, USB access to phones is granted by udev rules.</p>
+      <ol className="list-decimal ml-4 mt-1 space-y-1">
+        {help.steps?.map((step) => (
+          <li key={step} className="break-all">{step}</li>
+        ))}
+      </ol>
+      {message && <p className="mt-1">{message}</p>}
+      {help.canInstall && !help.ruleInstalled && (
+        <button type="button" className="mt-2 disabled:opacity-50" onClick={installRule} disabled={installing} style={{ color: 'var(--theme-accent)' }}>
+          {installing ? 'Waiting for password...' : 'Install udev rule'}
+        </button>
+      )}
+    </div>
+  );
+};
+
+export default UsbPermissionNotice;
@@ -0,0 +1,85 @@
+import React, { useEffect, useState } from 'react';
+import { FiCalendar, FiX } from 'react-icons/fi';
+import { api, WeeklyDigest } from '../utils/api';
+import { storage } from '../utils/storage';
+
+// generatedAt of the last digest the user closed
+const DISMISSED_KEY = 'weekly-digest-dismissed';
+
+interface WeeklyDigestCardProps {
+  onNotify?: (message: string, type: 'success' | 'error' | 'info') => void;
+}
+
+/** The latest weekly digest until it is dismissed; a new one arrives with the weekly_digest_ready event */
+const WeeklyDigestCard: React.FC<WeeklyDigestCardProps> = ({ onNotify }) => {
+  const [digest, setDigest] = useState<WeeklyDigest | null>(null);
+  const [expanded, setExpanded] = useState(false);
+
+  const show = (next: WeeklyDigest | null | undefined) => {
+    if (next && storage.get<number>(DISMISSED_KEY) !== next.generatedAt) setDigest(next);
+  };
+
+  useEffect(() => {
+    api.getWeeklyDigest()
+      .then((result) => { if (result.success && !result.preview) show(result.digest); })
+      .catch((error) => console.error('get_weekly_digest failed', error));
+
+    return api.onBackendEvent((event) => {
+      if (event.kind === 'weekly_digest_ready') {
+        onNotify?.('Your weekly device digest is ready', 'info');
+        show(event.payload as WeeklyDigest);
+      }
+    });
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  if (!digest) return null;
+
+  const dismiss = () => {
+    storage.set(DISMISSED_KEY, digest.generatedAt);
+    setDigest(null);
+  };
+
+  const days = Math.max(1, Math.round((digest.periodEnd - digest.periodStart) / 86400));
+
+  return (
+    <div
+      className="mb-3 px-3 py-2 rounded-lg text-xs"
+      style={{ border: '1px solid var(--theme-border)', color: 'var(--theme-text-secondary)' }}
+    >
+      <div className="flex items-center gap-3">
+        <FiCalendar className="w-4 h-4 flex-shrink-0" style={{ color: 'var(--theme-accent)' }} />
+        <span className="flex-1 truncate">
+          Last {days} days: {digest.newApps.length} new app{digest.newApps.length !== 1 ? 's' : ''}
+          {digest.storage && `, storage ${digest.storage.changeKb >= 0 ? '+' : '-'}${digest.storage.formatted.change}`}
+          {digest.newDrainers.length > 0 && `, ${digest.newDrainers.length} new battery drainer${digest.newDrainers.length !== 1 ? 's' : ''}`}
+        </span>
+        <button type="button" onClick={() => setExpanded(!expanded)}>
+          {expanded ? 'Hide' : 'Details'}
+        </button>
+        <button type="button" onClick={dismiss} title="Dismiss until next week">
+          <FiX className="w-3.5 h-3.5" />
+        </button>
+      </div>
+      {expanded && (
+        <div className="mt-2 pl-7 space-y-1">
+          {digest.suggestions.map((suggestion) => (
+            <div key={suggestion}>• {suggestion}</div>
+          ))}
+          {digest.newApps.length > 0 && (
+            <div className="font-mono truncate" title={digest.newApps.map((app) => app.packageName).join('\n')}>
+              New: {digest.newApps.map((app) => `${app.packageName} (${app.safetyLevel})`).join(', ')}
+            </div>
+          )}
+          {digest.topDrainers.length > 0 && (
+            <div className="font-mono truncate">
+              Top battery: {digest.topDrainers.map((d) => `${d.packageName} ${d.mAh} mAh`).join(', ')}
+            </div>
+          )}
+        </div>
+      )}
+    </div>
+  );
+};
+
+export default WeeklyDigestCard;
@@ -0,0 +1,228 @@
+// Generated by backend-python/generate_ts_types.py from api_types.py - do not edit.
+// Run `python backend-python/generate_ts_types.py` after changing api_types.py.
+
+export type SafetyLevel =
+  | 'Safe'
+  | 'Caution'
+  | 'Expert'
+  | 'Dangerous';
+
+export type DeviceFeature =
+  | 'user_uninstall'
+  | 'install_existing'
+  | 'cmd_package'
+  | 'trim_caches'
+  | 'background_appop'
+  | 'usage_stats'
+  | 'thermal_service'
+  | 'appops'
+  | 'role_service'
+  | 'settings_command'
+  | 'adb_backup'
+  | 'am_crash'
+  | 'am_restart'
+  | 'overlay_command'
+  | 'apex_list'
+  | 'runtime_permissions'
+  | 'clipboard_history';
+
+export type DeviceClass =
+  | 'phone'
+  | 'tablet'
+  | 'tv'
+  | 'watch'
+  | 'automotive';
+
+export type BackendEventKind =
+  | 'health_update'
+  | 'device_lost'
+  | 'device_restored'
+  | 'chat_uninstall_complete'
+  | 'optimization_progress'
+  | 'optimization_complete'
+  | 'config_changed'
+  | 'backup_progress'
+  | 'backup_complete'
+  | 'package_chunk'
+  | 'trial_reverted'
+  | 'external_package_change'
+  | 'weekly_digest_ready'
+  | 'prefetch_progress'
+  | 'prefetch_complete'
+  | 'cache_updated'
+  | 'parser_failed'
+  | 'charging_session_ended';
+
+export interface DeviceInfo {
+  name: string;
+  serial: string;
+  nickname?: string | null;
+  model?: string;
+  product?: string;
+  manufacturer?: string;
+  androidVersion?: string;
+  batteryPercentage?: number | null;
+  storageAvailable?: string | null;
+  state: string;
+  capabilities?: Record<DeviceFeature, boolean>;
+  deviceClass?: DeviceClass;
+  fireOs?: string | null;
+}
+
+export interface RatingConflict {
+  kind: 'aiMoreLenient' | 'aiStricter';
+  localRating: SafetyLevel;
+  aiRiskCategory: SafetyLevel;
+  aiSafeToRemove: boolean;
+  enforced: 'local';
+  message: string;
+}
+
+export interface AIRisk {
+  riskCategory: SafetyLevel;
+  safeToRemove: boolean;
+  summary: string;
+  analyzedAt: number;
+  model: string | null;
+  confidence?: number | null;
+  citations?: string[];
+  stale?: boolean;
+  conflict?: RatingConflict;
+}
+
+export interface Package {
+  packageName: string;
+  appName: string;
+  safetyLevel: SafetyLevel;
+  labelSource?: 'device';
+  isSystem?: boolean;
+  installer?: string | null;
+  installSource?: 'googlePlay' | 'otherStore' | 'oemPreload' | 'carrier' | 'sideloaded';
+  partition?: 'system' | 'system_ext' | 'product' | 'vendor' | 'odm' | 'oem' | 'apex' | 'data' | null;
+  platformSigned?: boolean;
+  overlayTarget?: string;
+  apexModule?: string;
+  sizeBytes?: number | null;
+  lastUsed?: number | null;
+  removable?: boolean;
+  managedReason?: string;
+  aiRisk?: AIRisk;
+}
+
+export interface CompactPackageList {
+  format: 'compact';
+  version: number;
+  fields: string[];
+  stringFields: string[];
+  strings: string[];
+  rows: any[][];
+}
+
+export interface BackupInfo {
+  name: string;
+  serial: string | null;
+  nickname: string | null;
+  path: string;
+  timestamp: string;
+  packageCount: number;
+  deviceInfo: any;
+  full: boolean;
+  hasData: boolean;
+}
+
+export interface ZramInfo {
+  origDataKb: number;
+  comprDataKb: number;
+  memUsedKb: number;
+  diskSizeKb: number | null;
+  compressionRatio: number | null;
+}
+
+export interface MemoryInfo {
+  totalKb: number;
+  availableKb: number;
+  usedKb: number;
+  usagePercent: number | null;
+  swapTotalKb: number;
+  swapFreeKb: number;
+  swapUsedKb: number;
+  swapUsagePercent: number | null;
+  zram: ZramInfo | null;
+  formatted: Record<string, string | null>;
+}
+
+export interface StorageVolume {
+  filesystem: string;
+  mountPoint: string;
+  totalKb: number;
+  usedKb: number;
+  availableKb: number;
+  usagePercent: number | null;
+  formatted: Record<string, string | null>;
+}
+
+export interface StorageInfo {
+  internal: StorageVolume;
+  adoptable: StorageVolume[];
+  adoptableTotalKb: number;
+  adoptableAvailableKb: number;
+}
+
+export interface ThermalSensor {
+  name: string;
+  type: string;
+  valueC: number;
+  status: number | null;
+}
+
+export interface TemperatureInfo {
+  temperatureC: number;
+  source: string;
+  sensors: ThermalSensor[];
+}
+
+export interface PackageServices {
+  packageName: string;
+  count: number;
+  services: string[];
+}
+
+export interface ServicesInfo {
+  count: number;
+  packageCount: number;
+  byPackage: PackageServices[];
+}
+
+export interface PackageNotifications {
+  packageName: string;
+  posted: number;
+  active: number;
+}
+
+export interface NotificationsInfo {
+  posted: number;
+  active: number;
+  packageCount: number;
+  byPackage: PackageNotifications[];
+}
+
+export interface PowerInfo {
+  charging: boolean;
+  plugType: string | null;
+  batteryLevel: number | null;
+  wakeLocks: number;
+  screenOnSeconds: number | null;
+}
+
+export interface SystemHealth {
+  serial: string;
+  timestamp: number;
+  cpuUsage: number | null;
+  memory: MemoryInfo | null;
+  storage: StorageInfo | null;
+  temperature: TemperatureInfo | null;
+  services: ServicesInfo | null;
+  notifications: NotificationsInfo | null;
+  power: PowerInfo | null;
+  stale?: boolean;
+}
@@ -1,20 +1,7 @@
 // Shared TypeScript types for Debloat AI app components
 
-export type DeviceInfo = {
-  name: string;
-  model?: string | null;
-  androidVersion: string;
-  batteryPercentage?: number | null;
-  storageAvailable?: string | null; // e.g. "12.3 GB"
-};
-
-export type SafetyLevel = 'Safe' | 'Caution' | 'Expert' | 'Dangerous';
-
-export type Package = {
-  packageName: string;
-  appName: string;
-  safetyLevel: SafetyLevel;
-};
+// Device and package DTOs are generated from the backend (backend-python/api_types.py)
+export type { DeviceInfo, SafetyLevel, Package } from './types.generated';
 
 export interface Message {
   role: 'user' | 'assistant';
@@ -1,5 +1,7 @@
 // TypeScript interfaces for AI Package Analysis
 
+import type { RatingConflict } from '../types.generated';
+
 export interface PackageAnalysis {
   packageName: string;
   summary: string;
@@ -12,6 +14,8 @@ export interface PackageAnalysis {
   technicalDetails: string;
   bestCase: string;
   worstCase: string;
+  /** Set when the verdict contradicts the local rating, which is the one the app applies */
+  conflict?: RatingConflict;
 }
 
 export interface AnalysisState {
@@ -3,6 +3,28 @@
  * Replaces Tauri invoke() calls
  */
 
+import type {
+  BackendEventKind,
+  BackupInfo,
+  CompactPackageList,
+  DeviceClass,
+  DeviceFeature,
+  DeviceInfo,
+  Package,
+  RatingConflict,
+  SafetyLevel,
+  SystemHealth,
+} from '../types.generated';
+import { expandPackages } from './packageWire';
+
+// Shared DTOs are generated from backend-python/api_types.py
+export type * from '../types.generated';
+
+/** Event envelope version this frontend understands (see backend events.py) */
+export const SUPPORTED_EVENT_VERSION = 1;
+
+let warnedEventVersion = false;
+
 export const api = {
   // ===== Device Operations =====
   
@@ -12,12 +34,17 @@ export const api = {
   
   // ===== Package Operations =====
   
-  async listPackages(type?: 'all' | 'system' | 'user') {
-    return window.electronAPI.listPackages(type || 'all');
+  async listPackages(type?: 'all' | 'system' | 'user'): Promise<Package[]> {
+    // Requested in the compact wire format: far smaller for devices with thousands of packages
+    return expandPackages(await window.electronAPI.listPackages(type || 'all', true));
   },
   
-  async uninstallPackage(packageName: string) {
-    return window.electronAPI.uninstallPackage(packageName);
+  /**
+   * With a `jobId` from startJob, the result is journaled so the batch can be resumed;
+   * `confirmation` is only needed outside a job, when the confirmation policy asks for it
+   */
+  async uninstallPackage(packageName: string, keepData: boolean = false, jobId?: string, confirmation?: Confirmation) {
+    return window.electronAPI.uninstallPackage(packageName, keepData, jobId, confirmation);
   },
   
   async reinstallPackage(packageName: string) {
@@ -40,22 +67,22 @@ export const api = {
     return window.electronAPI.parseChatCommand(message);
   },
   
-  async executeAction(executionResult: any, confirmed: boolean) {
-    return window.electronAPI.executeAction(executionResult, confirmed);
+  async executeAction(executionResult: any, confirmed: boolean, confirmation?: Confirmation) {
+    return window.electronAPI.executeAction(executionResult, confirmed, confirmation);
   },
   
   // ===== Backup Operations =====
   
-  async createBackup(packages: string[], deviceInfo?: any) {
-    return window.electronAPI.createBackup(packages, deviceInfo);
+  async createBackup(packages: string[], deviceInfo?: any, includeData: boolean = false) {
+    return window.electronAPI.createBackup(packages, deviceInfo, includeData);
   },
   
-  async listBackups() {
-    return window.electronAPI.listBackups();
+  async listBackups(allDevices: boolean = false) {
+    return window.electronAPI.listBackups(allDevices);
   },
   
-  async restoreBackup(backupName: string) {
-    return window.electronAPI.restoreBackup(backupName);
+  async restoreBackup(backupName: string, restoreData: boolean = false) {
+    return window.electronAPI.restoreBackup(backupName, restoreData);
   },
   
   async deleteBackup(backupName: string) {
@@ -65,140 +92,2159 @@ export const api = {
   async getBackupPath() {
     return window.electronAPI.getBackupPath();
   },
-};
-
-// ===== TypeScript Declarations =====
-
-declare global {
-  interface Window {
-    electronAPI: {
-      // Device
-      getDeviceInfo: () => Promise<DeviceInfo>;
-      
-      // Packages
-      listPackages: (type?: string) => Promise<Package[]>;
-      uninstallPackage: (packageName: string) => Promise<UninstallResult>;
-      reinstallPackage: (packageName: string) => Promise<UninstallResult>;
-      
-      // AI
-      analyzePackage: (packageName: string, provider?: string) => Promise<PackageAnalysis>;
-      chatMessage: (message: string, history?: ChatMessage[]) => Promise<ChatResponse>;
-      
-      // OpenClaw Integration
-      parseChatCommand: (message: string) => Promise<CommandParseResult>;
-      executeAction: (executionResult: any, confirmed: boolean) => Promise<ActionExecutionResult>;
-      
-      // Backups
-      createBackup: (packages: string[], deviceInfo?: any) => Promise<BackupResult>;
-      listBackups: () => Promise<BackupInfo[]>;
-      restoreBackup: (backupName: string) => Promise<RestoreResult>;
-      deleteBackup: (backupName: string) => Promise<DeleteResult>;
-      getBackupPath: () => Promise<{ path: string }>;
-    };
-  }
-}
-
-// ===== Type Definitions =====
-
-export interface DeviceInfo {
-  name: string;
-  serial: string;
-  model?: string;
-  product?: string;
-  manufacturer?: string;
-  androidVersion?: string;
-  batteryPercentage?: number | null;
-  storageAvailable?: string | null;
-  state: string;
-}
-
-export interface Package {
-  packageName: string;
-  appName: string;
-  safetyLevel: 'Safe' | 'Caution' | 'Expert' | 'Dangerous';
-}
-
-export interface UninstallResult {
-  success: boolean;
-  message: string;
-}
-
-export interface PackageAnalysis {
-  packageName: string;
-  summary: string;
-  purpose: string;
-  dependencies: string[];
-  safeToRemove: boolean;
-  riskCategory: 'Safe' | 'Caution' | 'Expert' | 'Dangerous';
-  consequences: string[];
-  userReports: string[];
-  technicalDetails: string;
-  bestCase: string;
-  worstCase: string;
-}
-
-export interface ChatMessage {
-  role: 'user' | 'assistant' | 'system';
-  content: string;
-}
-
-export interface ChatResponse {
-  response: string;
-}
-
-export interface CommandParseResult {
-  type: 'chat' | 'action';
-  parsed: {
-    intent: string;
-    entities: any;
-    confidence: number;
-    actionable: boolean;
-    original_message: string;
-  };
-  execution: {
-    success: boolean;
-    action: string;
-    requires_confirmation: boolean;
-    data: any;
-    message: string;
-  } | null;
-}
-
-export interface ActionExecutionResult {
-  success: boolean;
-  message: string;
-  details?: any[];
-}
+  
+  // ===== System Health =====
+  
+  async getSystemHealth() {
+    return window.electronAPI.getSystemHealth();
+  },
+  
+  async startHealthMonitor(intervalSeconds: number = 5) {
+    return window.electronAPI.startHealthMonitor(intervalSeconds);
+  },
+  
+  async stopHealthMonitor() {
+    return window.electronAPI.stopHealthMonitor();
+  },
+  
+  async clearHealthCache(serial?: string) {
+    return window.electronAPI.clearHealthCache(serial);
+  },
+  
+  // ===== AI Usage =====
+  
+  async getAiUsageStats() {
+    return window.electronAPI.getAiUsageStats();
+  },
+  
+  async setAiBudget(monthlyBudgetUsd: number | null) {
+    return window.electronAPI.setAiBudget(monthlyBudgetUsd);
+  },
+  
+  // ===== Settings =====
+  
+  async getSettings() {
+    return window.electronAPI.getSettings();
+  },
+  
+  async updateSettings(settings: DeepPartial<BackendSettings>) {
+    return window.electronAPI.updateSettings(settings);
+  },
+  
+  async resetSettings() {
+    return window.electronAPI.resetSettings();
+  },
+  
+  // ===== App Labels =====
+  
+  async resolveAppLabels(packages: string[]) {
+    return window.electronAPI.resolveAppLabels(packages);
+  },
+  
+  // ===== Package Families =====
+  
+  async listPackageFamilies(type: 'all' | 'system' | 'user' = 'all', includeSizes: boolean = true) {
+    return window.electronAPI.listPackageFamilies(type, includeSizes);
+  },
+  
+  // ===== Package Cache =====
+  
+  async getCachedPackages(query: PackageQuery = {}): Promise<PackagePage> {
+    const page = await window.electronAPI.getCachedPackages({ ...query, compact: true });
+    return { ...page, items: expandPackages(page.items) };
+  },
+  
+  // ===== Package Search =====
+  
+  async searchPackages(query: string, limit: number = 50) {
+    return window.electronAPI.searchPackages(query, limit);
+  },
+  
+  // ===== Privacy =====
+  
+  async getPrivacyReport() {
+    return window.electronAPI.getPrivacyReport();
+  },
+  
+  // ===== Performance =====
+  
+  async getPerformanceReport() {
+    return window.electronAPI.getPerformanceReport();
+  },
+  
+  async setAnimationScale(scale: number) {
+    return window.electronAPI.setAnimationScale(scale);
+  },
+  
+  async trimCaches() {
+    return window.electronAPI.trimCaches();
+  },
+  
+  async restrictBackground(packageName: string, restricted: boolean = true) {
+    return window.electronAPI.restrictBackground(packageName, restricted);
+  },
+  
+  // ===== Optimization =====
+  
+  async runOptimization(plan: OptimizationPlan, confirmation?: Confirmation) {
+    return window.electronAPI.runOptimization(plan, confirmation);
+  },
+  
+  // ===== Undo =====
+  
+  async getUndoStack() {
+    return window.electronAPI.getUndoStack();
+  },
+  
+  async undo(count: number = 1, confirmation?: Confirmation) {
+    return window.electronAPI.undo(count, confirmation);
+  },
+  
+  // ===== Compatibility =====
+  
+  async getCompatibility() {
+    return window.electronAPI.getCompatibility();
+  },
+  
+  // ===== Capabilities =====
+  
+  async probeDeviceCapabilities() {
+    return window.electronAPI.probeDeviceCapabilities();
+  },
+  
+  // ===== Device Management =====
+  
+  async getDeviceManagement() {
+    return window.electronAPI.getDeviceManagement();
+  },
+  
+  // ===== App Data =====
+  
+  async wipePackageData(packageName: string, confirmation?: Confirmation) {
+    return window.electronAPI.wipePackageData(packageName, confirmation);
+  },
+  
+  // ===== Backup Groups =====
+  
+  async listBackupGroups() {
+    return window.electronAPI.listBackupGroups();
+  },
+  
+  // ===== Backup Retention =====
+  
+  async previewRetentionCleanup(keepPerDevice?: number, maxAgeDays?: number) {
+    return window.electronAPI.previewRetentionCleanup(keepPerDevice, maxAgeDays);
+  },
+  
+  async runRetentionCleanup() {
+    return window.electronAPI.runRetentionCleanup();
+  },
+  
+  // ===== Full Backups =====
+  
+  async createFullBackup(packages: string[], deviceInfo?: any) {
+    return window.electronAPI.createFullBackup(packages, deviceInfo);
+  },
+  
+  // ===== Session Report =====
+  
+  async exportSessionReport() {
+    return window.electronAPI.exportSessionReport();
+  },
+  
+  // ===== Package Streaming =====
+  
+  /**
+   * List packages chunk by chunk. Each chunk is acknowledged after the next
+   * paint, so the backend sizes chunks to how fast this window renders them.
+   * `done` resolves with the list (partial if `cancel` stopped the stream early).
+   */
+  streamPackages(
+    type: 'all' | 'system' | 'user',
+    onChunk: (packages: Package[], chunk: PackageChunk) => void,
+  ): { done: Promise<Package[]>; cancel: () => void } {
+    let jobId: string | null = null;
+    let cancelled = false;
+    const early: BackendEvent<PackageChunk>[] = [];
+    const all: Package[] = [];
+    let unsubscribe = () => {};
+    let finish = () => {};
 
-export interface BackupResult {
-  success: boolean;
-  backupName?: string;
-  backupPath?: string;
-  message?: string;
-  error?: string;
-}
+    const done = new Promise<Package[]>((resolve, reject) => {
+      finish = () => resolve(all);
+      const handle = (message: BackendEvent<PackageChunk>) => {
+        const chunk = message.payload;
+        const packages = expandPackages(chunk.packages);
+        all.push(...packages);
+        onChunk(packages, chunk);
+        if (chunk.done) {
+          unsubscribe();
+          resolve(all);
+        } else {
+          requestAnimationFrame(() => {
+            if (!cancelled) window.electronAPI.ackPackageChunk(message.job_id!, chunk.index);
+          });
+        }
+      };
 
-export interface BackupInfo {
-  name: string;
-  path: string;
-  timestamp: string;
-  packageCount: number;
-  deviceInfo: any;
-}
+      unsubscribe = api.onBackendEvent((message) => {
+        if (message.kind !== 'package_chunk' || cancelled) return;
+        // Chunks can arrive before the start call returns the job id
+        if (jobId === null) early.push(message);
+        else if (message.job_id === jobId) handle(message);
+      });
 
-export interface RestoreResult {
-  success: boolean;
-  packages?: string[];
-  count?: number;
-  restored?: number;
-  failed?: number;
-  errors?: string[];
-  message?: string;
-}
+      window.electronAPI.startPackageStream(type).then((start) => {
+        jobId = start.jobId;
+        early.filter((message) => message.job_id === jobId).forEach(handle);
+        early.length = 0;
+      }, (err) => {
+        unsubscribe();
+        reject(err);
+      });
+    });
 
-export interface DeleteResult {
-  success: boolean;
-  message: string;
+    const cancel = () => {
+      cancelled = true;
+      unsubscribe();
+      if (jobId) window.electronAPI.cancelPackageStream(jobId);
+      finish();
+    };
+    return { done, cancel };
+  },
+  
+  // ===== Device Nicknames =====
+  
+  async setDeviceNickname(serial: string, name: string) {
+    return window.electronAPI.setDeviceNickname(serial, name);
+  },
+  
+  // ===== Network =====
+  
+  async testConnection() {
+    return window.electronAPI.testConnection();
+  },
+  
+  // ===== Device Restart =====
+  
+  async restartSystemUi() {
+    return window.electronAPI.restartSystemUi();
+  },
+  
+  async restartLauncher() {
+    return window.electronAPI.restartLauncher();
+  },
+  
+  async softReboot(confirmed: boolean = false) {
+    return window.electronAPI.softReboot(confirmed);
+  },
+  
+  async rebootDevice(mode: RebootMode = 'system', confirmed: boolean = false) {
+    return window.electronAPI.rebootDevice(mode, confirmed);
+  },
+  
+  // ===== Trial Disable =====
+  
+  async startTrial(packages: string[], durationHours: number = 24, confirmation?: Confirmation) {
+    return window.electronAPI.startTrial(packages, durationHours, confirmation);
+  },
+  
+  async listTrials(includeEnded: boolean = false) {
+    return window.electronAPI.listTrials(includeEnded);
+  },
+  
+  async keepTrial(trialId: string) {
+    return window.electronAPI.keepTrial(trialId);
+  },
+  
+  async revertTrial(trialId: string) {
+    return window.electronAPI.revertTrial(trialId);
+  },
+  
+  // ===== Selection Rules =====
+  
+  async validateSelection(packages: string[]) {
+    return window.electronAPI.validateSelection(packages);
+  },
+  
+  // ===== Special Access =====
+  
+  async getSpecialAccess() {
+    return window.electronAPI.getSpecialAccess();
+  },
+  
+  async revokeSpecialAccess(packageName: string, access: SpecialAccess) {
+    return window.electronAPI.revokeSpecialAccess(packageName, access);
+  },
+  
+  // ===== Service Audit =====
+  
+  async getServiceAudit() {
+    return window.electronAPI.getServiceAudit();
+  },
+  
+  // ===== Tutorial =====
+  
+  async getTutorialState() {
+    return window.electronAPI.getTutorialState();
+  },
+  
+  async advanceTutorial(skip: boolean = false) {
+    return window.electronAPI.advanceTutorial(skip);
+  },
+  
+  async dismissTutorial(dismissed: boolean = true) {
+    return window.electronAPI.dismissTutorial(dismissed);
+  },
+  
+  // ===== Raw Dumps =====
+  
+  async listRawDumpServices() {
+    return window.electronAPI.listRawDumpServices();
+  },
+  
+  async getRawDump(service: string, packageName?: string, page?: number, pageLines?: number, refresh?: boolean) {
+    return window.electronAPI.getRawDump(service, packageName, page, pageLines, refresh);
+  },
+  
+  // ===== Parser Diagnostics =====
+  
+  async listParserFailures() {
+    return window.electronAPI.listParserFailures();
+  },
+  
+  async exportDiagnostics(includeOutput: boolean) {
+    return window.electronAPI.exportDiagnostics(includeOutput);
+  },
+  
+  async clearParserFailures() {
+    return window.electronAPI.clearParserFailures();
+  },
+  
+  // ===== Prefetch =====
+  
+  async startPrefetch() {
+    return window.electronAPI.startPrefetch();
+  },
+  
+  async getPrefetchStatus() {
+    return window.electronAPI.getPrefetchStatus();
+  },
+  
+  // ===== Cache diagnostics =====
+  
+  async getCacheDiagnostics() {
+    return window.electronAPI.getCacheDiagnostics();
+  },
+  
+  // ===== Device class =====
+  
+  async getDeviceClass() {
+    return window.electronAPI.getDeviceClass();
+  },
+  
+  async getClassPresets() {
+    return window.electronAPI.getClassPresets();
+  },
+  
+  // ===== Stay Awake =====
+  
+  async holdStayAwake(reason?: string) {
+    return window.electronAPI.holdStayAwake(reason);
+  },
+  
+  async releaseStayAwake(holdId: string) {
+    return window.electronAPI.releaseStayAwake(holdId);
+  },
+  
+  async getStayAwakeStatus() {
+    return window.electronAPI.getStayAwakeStatus();
+  },
+  
+  // ===== Job Journal =====
+  
+  async startJob(kind: JournaledJob['kind'], packages: string[], options?: JournaledJob['options'],
+                 confirmation?: Confirmation) {
+    return window.electronAPI.startJob(kind, packages, options, confirmation);
+  },
+  
+  async finishJob(jobId: string) {
+    return window.electronAPI.finishJob(jobId);
+  },
+  
+  async getIncompleteJobs() {
+    return window.electronAPI.getIncompleteJobs();
+  },
+  
+  async resumeJob(jobId: string) {
+    return window.electronAPI.resumeJob(jobId);
+  },
+  
+  async discardJob(jobId: string) {
+    return window.electronAPI.discardJob(jobId);
+  },
+  
+  // ===== Package Signatures =====
+  
+  async getPackageSignature(packageName: string) {
+    return window.electronAPI.getPackageSignature(packageName);
+  },
+  
+  // ===== Theme Overlays =====
+  
+  async listOverlays() {
+    return window.electronAPI.listOverlays();
+  },
+  
+  async setOverlayEnabled(overlayId: string, enabled: boolean) {
+    return window.electronAPI.setOverlayEnabled(overlayId, enabled);
+  },
+  
+  // ===== APEX modules =====
+  
+  async listApexModules() {
+    return window.electronAPI.listApexModules();
+  },
+  
+  // ===== Analysis export =====
+  
+  async exportAnalyses(packages?: string[], format: 'markdown' | 'csv' = 'markdown') {
+    return window.electronAPI.exportAnalyses(packages, format);
+  },
+  
+  // ===== Weekly digest =====
+  
+  async getWeeklyDigest() {
+    return window.electronAPI.getWeeklyDigest();
+  },
+  
+  // ===== Emergency recovery =====
+  
+  async emergencyRecover(confirmed: boolean = false, hours?: number, restoreBackup?: boolean, safeMode?: boolean,
+                         confirmation?: Confirmation) {
+    return window.electronAPI.emergencyRecover(confirmed, hours, restoreBackup, safeMode, confirmation);
+  },
+  
+  // ===== Backup Transfer =====
+  
+  async previewBackupTransfer(backupName: string) {
+    return window.electronAPI.previewBackupTransfer(backupName);
+  },
+  
+  async applyBackupTransfer(backupName: string) {
+    return window.electronAPI.applyBackupTransfer(backupName);
+  },
+  
+  // ===== Unused Apps =====
+  
+  async getUnusedAppSuggestions(days?: number) {
+    return window.electronAPI.getUnusedAppSuggestions(days);
+  },
+  
+  // ===== Notifications =====
+  
+  async muteNotifications(packageName: string, muted: boolean = true) {
+    return window.electronAPI.muteNotifications(packageName, muted);
+  },
+  
+  // ===== Events =====
+  
+  async subscribeWithReplay(kinds?: BackendEventKind[]) {
+    return window.electronAPI.subscribeWithReplay(kinds);
+  },
+  
+  // ===== Runtime permissions =====
+  
+  async revokePermission(packageName: string, permission: string) {
+    return window.electronAPI.revokePermission(packageName, permission);
+  },
+  
+  async grantPermission(packageName: string, permission: string) {
+    return window.electronAPI.grantPermission(packageName, permission);
+  },
+  
+  // ===== Clipboard Audit =====
+  
+  async getClipboardAudit() {
+    return window.electronAPI.getClipboardAudit();
+  },
+  
+  // ===== OEM database =====
+  
+  async getOemDatabase() {
+    return window.electronAPI.getOemDatabase();
+  },
+  
+  // ===== Crash reports =====
+  
+  async listCrashReports() {
+    return window.electronAPI.listCrashReports();
+  },
+  
+  async clearCrashReports() {
+    return window.electronAPI.clearCrashReports();
+  },
+  
+  // ===== USB permissions (Linux) =====
+  
+  async getUsbPermissionHelp() {
+    return window.electronAPI.getUsbPermissionHelp();
+  },
+  
+  async installUdevRule(vendorId?: string) {
+    return window.electronAPI.installUdevRule(vendorId);
+  },
+  
+  // ===== Battery trend =====
+  
+  async getBatteryTrend(packageName: string, days?: number) {
+    return window.electronAPI.getBatteryTrend(packageName, days);
+  },
+  
+  // ===== Charging sessions =====
+  
+  async getChargingSessions(limit?: number) {
+    return window.electronAPI.getChargingSessions(limit);
+  },
+  
+  // ===== Screen mirror =====
+  
+  async launchScreenMirror(serial?: string, download?: boolean) {
+    return window.electronAPI.launchScreenMirror(serial, download);
+  },
+  
+  async getScreenMirrorStatus() {
+    return window.electronAPI.getScreenMirrorStatus();
+  },
+  
+  async stopScreenMirror(serial?: string) {
+    return window.electronAPI.stopScreenMirror(serial);
+  },
+  
+  // ===== Guided actions =====
+  
+  async listGuidedActions() {
+    return window.electronAPI.listGuidedActions();
+  },
+  
+  async openSettingsPage(page: SettingsPage, packageName?: string) {
+    return window.electronAPI.openSettingsPage(page, packageName);
+  },
+  
+  async pressGuidedKey(key: GuidedKey) {
+    return window.electronAPI.pressGuidedKey(key);
+  },
+  
+  async guidedTap(target: GuidedTapTarget) {
+    return window.electronAPI.guidedTap(target);
+  },
+  
+  // ===== Device pages =====
+  
+  async openAppInfo(packageName: string) {
+    return window.electronAPI.openAppInfo(packageName);
+  },
+  
+  async openInPlayStore(packageName: string) {
+    return window.electronAPI.openInPlayStore(packageName);
+  },
+  
+  // ===== Remote control =====
+  
+  async getRemoteStatus() {
+    return window.electronAPI.getRemoteStatus();
+  },
+  
+  async regenerateRemoteToken() {
+    return window.electronAPI.regenerateRemoteToken();
+  },
+  
+  // ===== Confirmation Policy =====
+  
+  async getConfirmationPolicy() {
+    return window.electronAPI.getConfirmationPolicy();
+  },
+  
+  async setConfirmationRole(role: string) {
+    return window.electronAPI.setConfirmationRole(role);
+  },
+  
+  async getConfirmationRequirement(operation: ConfirmationRequirement['operation'], packages: string[]) {
+    return window.electronAPI.getConfirmationRequirement(operation, packages);
+  },
+  
+  // ===== Profiles =====
+  
+  async listProfiles() {
+    return window.electronAPI.listProfiles();
+  },
+  
+  // ===== Backend Events =====
+  
+  onBackendEvent(callback: (message: BackendEvent) => void) {
+    return window.electronAPI.onBackendEvent((message) => {
+      if (message.event_version !== SUPPORTED_EVENT_VERSION) {
+        if (!warnedEventVersion) {
+          warnedEventVersion = true;
+          console.warn(`Backend event version ${message.event_version} is not supported (expected ${SUPPORTED_EVENT_VERSION}); update the app`);
+        }
+        return;
+      }
+      callback(message);
+    });
+  },
+};
+
+// ===== TypeScript Declarations =====
+
+declare global {
+  interface Window {
+    electronAPI: {
+      // Device
+      getDeviceInfo: () => Promise<DeviceInfo>;
+      
+      // Packages
+      listPackages: (type?: string, compact?: boolean) => Promise<Package[] | CompactPackageList>;
+      uninstallPackage: (packageName: string, keepData?: boolean, jobId?: string, confirmation?: Confirmation) => Promise<UninstallResult>;
+      reinstallPackage: (packageName: string) => Promise<UninstallResult>;
+      
+      // AI
+      analyzePackage: (packageName: string, provider?: string) => Promise<PackageAnalysis>;
+      chatMessage: (message: string, history?: ChatMessage[]) => Promise<ChatResponse>;
+      
+      // OpenClaw Integration
+      parseChatCommand: (message: string) => Promise<CommandParseResult>;
+      executeAction: (executionResult: any, confirmed: boolean, confirmation?: Confirmation) => Promise<ActionExecutionResult>;
+      
+      // Backups
+      createBackup: (packages: string[], deviceInfo?: any, includeData?: boolean) => Promise<BackupResult>;
+      listBackups: (allDevices?: boolean) => Promise<BackupInfo[]>;
+      restoreBackup: (backupName: string, restoreData?: boolean) => Promise<RestoreResult>;
+      deleteBackup: (backupName: string) => Promise<DeleteResult>;
+      getBackupPath: () => Promise<{ path: string }>;
+      
+      // System Health
+      getSystemHealth: () => Promise<SystemHealth | null>;
+      startHealthMonitor: (intervalSeconds?: number) => Promise<StatusResult>;
+      stopHealthMonitor: () => Promise<StatusResult>;
+      clearHealthCache: (serial?: string) => Promise<StatusResult>;
+      
+      // AI Usage
+      getAiUsageStats: () => Promise<AIUsageStats>;
+      setAiBudget: (monthlyBudgetUsd: number | null) => Promise<StatusResult>;
+      
+      // Settings
+      getSettings: () => Promise<BackendSettings>;
+      updateSettings: (settings: DeepPartial<BackendSettings>) => Promise<SettingsUpdateResult>;
+      resetSettings: () => Promise<SettingsUpdateResult>;
+      
+      // App Labels
+      resolveAppLabels: (packages: string[]) => Promise<LabelResolution>;
+      
+      // Package Families
+      listPackageFamilies: (type?: 'all' | 'system' | 'user', includeSizes?: boolean) => Promise<PackageFamily[]>;
+      
+      // Package Cache
+      getCachedPackages: (query?: PackageQuery & { compact?: boolean }) => Promise<WirePackagePage>;
+      
+      // Package Search
+      searchPackages: (query: string, limit?: number) => Promise<PackageMatch[]>;
+      
+      // Privacy
+      getPrivacyReport: () => Promise<PrivacyReport>;
+      
+      // Performance
+      getPerformanceReport: () => Promise<PerformanceReport>;
+      setAnimationScale: (scale: number) => Promise<StatusResult>;
+      trimCaches: () => Promise<StatusResult>;
+      restrictBackground: (packageName: string, restricted?: boolean) => Promise<StatusResult>;
+      
+      // Optimization
+      runOptimization: (plan: OptimizationPlan, confirmation?: Confirmation) => Promise<OptimizationStart>;
+      
+      // Undo
+      getUndoStack: () => Promise<UndoEntry[]>;
+      undo: (count?: number, confirmation?: Confirmation) => Promise<UndoResult>;
+      
+      // Compatibility
+      getCompatibility: () => Promise<CompatibilityReport>;
+      
+      // Capabilities
+      probeDeviceCapabilities: () => Promise<CapabilityProbe>;
+      
+      // Device Management
+      getDeviceManagement: () => Promise<DeviceManagement>;
+      
+      // App Data
+      wipePackageData: (packageName: string, confirmation?: Confirmation) => Promise<UninstallResult>;
+      
+      // Backup Groups
+      listBackupGroups: () => Promise<BackupGroup[]>;
+      
+      // Backup Retention
+      previewRetentionCleanup: (keepPerDevice?: number, maxAgeDays?: number) => Promise<RetentionPreview>;
+      runRetentionCleanup: () => Promise<RetentionCleanupResult>;
+      
+      // Full Backups
+      createFullBackup: (packages: string[], deviceInfo?: any) => Promise<FullBackupStarted>;
+      
+      // Session Report
+      exportSessionReport: () => Promise<SessionReport>;
+      
+      // Package Streaming
+      startPackageStream: (type?: 'all' | 'system' | 'user') => Promise<PackageStreamStart>;
+      ackPackageChunk: (jobId: string, index: number) => Promise<StatusResult>;
+      cancelPackageStream: (jobId: string) => Promise<StatusResult>;
+      
+      // Device Nicknames
+      setDeviceNickname: (serial: string, name: string) => Promise<SettingsUpdateResult>;
+      
+      // Network
+      testConnection: () => Promise<ConnectionTest>;
+      
+      // Device Restart
+      restartSystemUi: () => Promise<RestartResult>;
+      restartLauncher: () => Promise<RestartResult>;
+      softReboot: (confirmed?: boolean) => Promise<RestartResult>;
+      rebootDevice: (mode?: RebootMode, confirmed?: boolean) => Promise<RestartResult>;
+      
+      // Trial Disable
+      startTrial: (packages: string[], durationHours?: number, confirmation?: Confirmation) => Promise<TrialResult>;
+      listTrials: (includeEnded?: boolean) => Promise<Trial[]>;
+      keepTrial: (trialId: string) => Promise<TrialResult>;
+      revertTrial: (trialId: string) => Promise<TrialResult>;
+      
+      // Selection Rules
+      validateSelection: (packages: string[]) => Promise<SelectionValidation>;
+      
+      // Special Access
+      getSpecialAccess: () => Promise<SpecialAccessInventory>;
+      revokeSpecialAccess: (packageName: string, access: SpecialAccess) => Promise<{ success: boolean; message: string; unsupported?: boolean }>;
+      
+      // Service Audit
+      getServiceAudit: () => Promise<ServiceAudit>;
+      
+      // Tutorial
+      getTutorialState: () => Promise<TutorialState>;
+      advanceTutorial: (skip?: boolean) => Promise<TutorialState>;
+      dismissTutorial: (dismissed?: boolean) => Promise<TutorialState>;
+      
+      // Raw Dumps
+      listRawDumpServices: () => Promise<Record<string, RawDumpService>>;
+      getRawDump: (service: string, packageName?: string, page?: number, pageLines?: number, refresh?: boolean) => Promise<RawDump>;
+      
+      // Parser Diagnostics
+      listParserFailures: () => Promise<ParserFailure[]>;
+      exportDiagnostics: (includeOutput: boolean) => Promise<DiagnosticsExport>;
+      clearParserFailures: () => Promise<{ success: boolean; message: string }>;
+      
+      // Prefetch
+      startPrefetch: () => Promise<{ success: boolean; message: string; jobId?: string }>;
+      getPrefetchStatus: () => Promise<PrefetchStatus | null>;
+      
+      // Cache diagnostics
+      getCacheDiagnostics: () => Promise<CacheDiagnostics>;
+      
+      // Device class
+      getDeviceClass: () => Promise<DeviceClassInfo>;
+      getClassPresets: () => Promise<ClassPresets>;
+      
+      // Stay Awake
+      holdStayAwake: (reason?: string) => Promise<StayAwakeHold>;
+      releaseStayAwake: (holdId: string) => Promise<{ success: boolean; restored?: boolean; message: string }>;
+      getStayAwakeStatus: () => Promise<StayAwakeStatus>;
+      
+      // Job Journal
+      startJob: (kind: JournaledJob['kind'], packages: string[], options?: JournaledJob['options'],
+                 confirmation?: Confirmation) => Promise<JobStartResult>;
+      finishJob: (jobId: string) => Promise<JobFinishResult>;
+      getIncompleteJobs: () => Promise<JournaledJob[]>;
+      resumeJob: (jobId: string) => Promise<{ success: boolean; job?: JournaledJob; message?: string }>;
+      discardJob: (jobId: string) => Promise<{ success: boolean; message: string }>;
+      
+      // Package Signatures
+      getPackageSignature: (packageName: string) => Promise<PackageSignature>;
+      
+      // Theme Overlays
+      listOverlays: () => Promise<OverlayList>;
+      setOverlayEnabled: (overlayId: string, enabled: boolean) => Promise<{ success: boolean; enabled?: boolean; locked?: boolean; unsupported?: boolean; message: string }>;
+      
+      // APEX modules
+      listApexModules: () => Promise<ApexModuleList>;
+      
+      // Analysis export
+      exportAnalyses: (packages?: string[], format?: 'markdown' | 'csv') => Promise<AnalysisExport>;
+      
+      // Weekly digest
+      getWeeklyDigest: () => Promise<WeeklyDigestResult>;
+      
+      // Emergency recovery
+      emergencyRecover: (confirmed?: boolean, hours?: number, restoreBackup?: boolean, safeMode?: boolean,
+                         confirmation?: Confirmation) => Promise<EmergencyRecoveryResult>;
+      
+      // Backup Transfer
+      previewBackupTransfer: (backupName: string) => Promise<BackupTransferResult>;
+      applyBackupTransfer: (backupName: string) => Promise<BackupTransferResult>;
+      
+      // Unused Apps
+      getUnusedAppSuggestions: (days?: number) => Promise<UnusedAppSuggestions>;
+      
+      // Notifications
+      muteNotifications: (packageName: string, muted?: boolean) => Promise<StatusResult>;
+      
+      // Runtime permissions
+      revokePermission: (packageName: string, permission: string) => Promise<PermissionChangeResult>;
+      grantPermission: (packageName: string, permission: string) => Promise<PermissionChangeResult>;
+      
+      // Clipboard Audit
+      getClipboardAudit: () => Promise<ClipboardAudit>;
+      
+      // OEM database
+      getOemDatabase: () => Promise<OemDatabaseSummary>;
+      
+      // Crash reports
+      listCrashReports: () => Promise<CrashReportSummary[]>;
+      clearCrashReports: () => Promise<{ success: boolean; removed: number; message: string }>;
+      
+      // USB permissions (Linux)
+      getUsbPermissionHelp: () => Promise<UsbPermissionHelp>;
+      installUdevRule: (vendorId?: string) => Promise<UdevRuleResult>;
+      
+      // Battery trend
+      getBatteryTrend: (packageName: string, days?: number) => Promise<BatteryTrend>;
+      
+      // Charging sessions
+      getChargingSessions: (limit?: number) => Promise<ChargingSessions>;
+      
+      // Screen mirror
+      launchScreenMirror: (serial?: string, download?: boolean) => Promise<ScreenMirrorResult>;
+      getScreenMirrorStatus: () => Promise<ScreenMirrorStatus>;
+      stopScreenMirror: (serial?: string) => Promise<ScreenMirrorStopResult>;
+      
+      // Guided actions
+      listGuidedActions: () => Promise<GuidedActionList>;
+      openSettingsPage: (page: SettingsPage, packageName?: string) => Promise<GuidedActionResult>;
+      pressGuidedKey: (key: GuidedKey) => Promise<GuidedActionResult>;
+      guidedTap: (target: GuidedTapTarget) => Promise<GuidedActionResult>;
+      
+      // Device pages
+      openAppInfo: (packageName: string) => Promise<GuidedActionResult>;
+      openInPlayStore: (packageName: string) => Promise<GuidedActionResult>;
+      
+      // Remote control
+      getRemoteStatus: () => Promise<RemoteStatus>;
+      regenerateRemoteToken: () => Promise<RemoteTokenResult>;
+      
+      // Confirmation Policy
+      getConfirmationPolicy: () => Promise<ConfirmationPolicy>;
+      setConfirmationRole: (role: string) => Promise<ConfirmationRoleResult>;
+      getConfirmationRequirement: (operation: ConfirmationRequirement['operation'], packages: string[]) => Promise<ConfirmationRequirement>;
+      
+      // Profiles
+      listProfiles: () => Promise<ProfileList>;
+      
+      // Events
+      subscribeWithReplay: (kinds?: BackendEventKind[]) => Promise<ReplayResult>;
+      
+      // Events
+      onBackendEvent: (callback: (message: BackendEvent) => void) => () => void;
+    };
+  }
+}
+
+// ===== Type Definitions =====
+
+export interface LabelResolution {
+  available: boolean;
+  labels: Record<string, string>;
+  unresolved: string[];
+  pending: string[];
+  message: string;
+}
+
+export interface UninstallResult extends PolicyRefusal {
+  success: boolean;
+  message: string;
+  managed?: boolean;
+  /** Fire OS does not let adb change this Amazon app */
+  protected?: boolean;
+  keepData?: boolean;
+  dataNote?: string;
+  /** Fire OS: the removed Amazon app may come back with an update */
+  updateNote?: string;
+  dataPreserved?: boolean | null;
+  remediation?: Remediation;  // reinstall_package: what to do about a known failure
+  details?: string;  // Raw package manager output behind the remediation
+}
+
+export interface Remediation {
+  failure: 'restricted' | 'unknownCommand' | 'notInstalledForUser' | 'notOnDevice';
+  summary: string;
+  steps: string[];
+}
+
+export interface PackageAnalysis {
+  packageName: string;
+  summary: string;
+  purpose: string;
+  dependencies: string[];
+  safeToRemove: boolean;
+  riskCategory: 'Safe' | 'Caution' | 'Expert' | 'Dangerous';
+  consequences: string[];
+  userReports: string[];
+  technicalDetails: string;
+  bestCase: string;
+  worstCase: string;
+  /** Self-reported, 0..1 */
+  confidence?: number;
+  /** Sources the answer was based on */
+  citations?: string[];
+  /** Set when the verdict contradicts the local rating, which is the one the app applies */
+  conflict?: RatingConflict;
+}
+
+export interface ChatMessage {
+  role: 'user' | 'assistant' | 'system';
+  content: string;
+}
+
+export interface SafetyWarning {
+  packageName: string;
+  safetyLevel: 'Dangerous';
+  reason: string;
+  sentence: string;
+}
+
+export interface ChatResponse {
+  response: string;
+  relatedQuestions?: string[];
+  safetyWarnings?: SafetyWarning[];
+}
+
+export interface CommandParseResult {
+  type: 'chat' | 'action';
+  parsed: {
+    intent: string;
+    entities: any;
+    confidence: number;
+    actionable: boolean;
+    original_message: string;
+  };
+  execution: {
+    success: boolean;
+    action: string;
+    requires_confirmation: boolean;
+    data: any;
+    message: string;
+  } | null;
+}
+
+export interface ActionExecutionResult extends PolicyRefusal {
+  success: boolean;
+  message: string;
+  details?: any[];
+  interrupted?: boolean;
+  // The device disconnected: the rest runs as job `jobId`, ending with a chat_uninstall_complete event
+  paused?: boolean;
+  jobId?: string;
+  pending?: string[];
+  discrepancies?: string[];
+}
+
+export interface BackupResult {
+  success: boolean;
+  backupName?: string;
+  backupPath?: string;
+  message?: string;
+  error?: string;
+  cleanup?: RetentionCleanupResult;
+  dataBackup?: AppDataResult;
+  insufficientSpace?: boolean;
+  space?: SpaceCheck;
+}
+
+/** Free space check before a data backup or APK export */
+export interface SpaceCheck {
+  success: boolean;
+  message: string;
+  requiredBytes?: number;
+  availableBytes?: number;
+  deviceAvailableBytes?: number | null;  // Only checked for data backups
+  formatted?: { required?: string | null; available?: string | null; deviceAvailable?: string | null };
+  warnings?: string[];
+  insufficientSpace?: boolean;
+}
+
+export interface AppDataResult {
+  success: boolean;
+  message: string;
+  unsupported?: boolean;
+  sizeBytes?: number;
+}
+
+export interface FullBackupStarted {
+  success: boolean;
+  message: string;
+  jobId?: string;
+  backupName?: string;
+  insufficientSpace?: boolean;
+  space?: SpaceCheck;
+}
+
+export interface BackupProgress {
+  backupName: string;
+  packageName: string;
+  done: number;
+  count: number;
+}
+
+export interface BackupComplete {
+  success: boolean;
+  backupName: string;
+  backupPath?: string;
+  apkPackages?: number;
+  skipped?: Record<string, string>;
+  message: string;
+}
+
+export interface ExpiredBackup extends BackupInfo {
+  reason: string;
+}
+
+export interface RetentionPreview {
+  backups: ExpiredBackup[];
+  count: number;
+  message: string;
+}
+
+export interface RetentionCleanupResult {
+  success: boolean;
+  deleted: string[];
+  errors: string[];
+  message: string;
+}
+
+export interface BackupGroup {
+  serial: string | null;
+  nickname: string | null;
+  deviceInfo: any;
+  latestTimestamp: string;
+  backups: BackupInfo[];
+}
+
+export type RestoreStatus = 'restored' | 'alreadyInstalled' | 'notAvailable' | 'failed';
+
+export interface RestorePackageResult {
+  packageName: string;
+  status: RestoreStatus;
+  message: string;
+  remediation?: Remediation;
+}
+
+export interface RestoreResult {
+  success: boolean;
+  packages?: string[];
+  count?: number;
+  restored?: number;
+  failed?: number;
+  results?: RestorePackageResult[];
+  dataRestore?: AppDataResult | null;
+  message?: string;
+}
+
+export interface DeleteResult {
+  success: boolean;
+  message: string;
+}
+
+export interface StatusResult {
+  success: boolean;
+  message: string;
+}
+
+export interface AIUsageTotals {
+  requests: number;
+  promptTokens: number;
+  completionTokens: number;
+  totalTokens: number;
+  estimatedCostUsd: number;
+}
+
+export interface AIUsageStats {
+  today: Record<string, AIUsageTotals>;
+  month: Record<string, AIUsageTotals>;
+  monthlyHistory: Record<string, Record<string, AIUsageTotals>>;
+  monthlyBudgetUsd: number | null;
+  monthlySpentUsd: number;
+  budgetRemainingUsd: number | null;
+}
+
+export interface AISettings {
+  model: 'sonar' | 'sonar-pro' | 'sonar-reasoning';
+  chatTemperature: number;
+  chatMaxTokens: number;
+  analysisTemperature: number;
+  analysisMaxTokens: number;
+  searchRecency: 'hour' | 'day' | 'week' | 'month' | 'year' | 'none';
+  historyLimit: number;
+  historyKeepRecent: number;
+  compactionMode: 'local' | 'model';
+  includeDeviceContext: boolean;
+  /** Look up package ids pasted into chat messages before sending them */
+  resolvePackageMentions: boolean;
+  /** Short, jargon-free AI answers */
+  plainLanguage: boolean;
+  /** Technical AI answers with package ids and adb commands */
+  expertMode: boolean;
+  /** OpenAI-compatible gateway URL; empty uses the provider's API */
+  baseUrl: string;
+  /** App model name -> model name at the endpoint */
+  modelAliases: Record<string, string>;
+}
+
+export interface BackupSettings {
+  keepPerDevice: number;
+  maxAgeDays: number;
+}
+
+export interface NetworkSettings {
+  /** Refuse every outbound request; AI features fall back to local ratings */
+  offline: boolean;
+  /** http://host:port proxy for AI requests; empty uses HTTP(S)_PROXY */
+  proxy: string;
+  /** PEM file with extra root certificates; empty uses the default store */
+  caBundle: string;
+}
+
+export interface DisplaySettings {
+  /** Language of the formatted sizes and percents in backend results */
+  locale: 'en' | 'es' | 'fr' | 'de';
+}
+
+export interface DeviceSettings {
+  /** serial -> user-chosen device name */
+  nicknames: Record<string, string>;
+  /** Keep the device awake on USB during long jobs */
+  stayAwakeDuringJobs: boolean;
+  /** Seconds between checks for apps installed or removed on the device itself; 0 turns them off */
+  watchPackagesSeconds: number;
+}
+
+export interface DiagnosticsSettings {
+  /** Save a local report when the backend crashes */
+  crashReports: boolean;
+  /** http(s) endpoint reports are also posted to; empty keeps them local */
+  crashReportUrl: string;
+}
+
+export interface RemoteSettings {
+  /** Serve the backend commands over HTTP for a browser or remote UI */
+  enabled: boolean;
+  /** Listen address; anything but loopback is reachable from the network */
+  host: string;
+  port: number;
+  /** Web origin allowed to call the API from a browser; empty allows none */
+  allowedOrigin: string;
+}
+
+export interface BackendSettings {
+  ai: AISettings;
+  backups: BackupSettings;
+  network: NetworkSettings;
+  display: DisplaySettings;
+  devices: DeviceSettings;
+  diagnostics: DiagnosticsSettings;
+  remote: RemoteSettings;
+}
+
+export type DeepPartial<T> = { [K in keyof T]?: Partial<T[K]> };
+
+export interface SettingsUpdateResult {
+  success: boolean;
+  message: string;
+  settings?: BackendSettings;
+  /** The remote server after a change to the remote section */
+  remote?: RemoteStatus;
+}
+
+export interface PackageFamily {
+  id: string;
+  name: string;
+  packageCount: number;
+  packages: string[];
+  safetyBreakdown: Record<'Safe' | 'Caution' | 'Expert' | 'Dangerous', number>;
+  worstSafetyLevel: 'Safe' | 'Caution' | 'Expert' | 'Dangerous';
+  removablePackages: string[];
+  totalSizeBytes: number | null;
+}
+
+export interface PackageQuery {
+  sortBy?: 'name' | 'appName' | 'size' | 'safety' | 'lastUsed';
+  direction?: 'asc' | 'desc';
+  offset?: number;
+  limit?: number;
+  filters?: {
+    search?: string;
+    safetyLevels?: Package['safetyLevel'][];
+    type?: 'all' | 'system' | 'user';
+  };
+}
+
+export interface PackagePage {
+  total: number;
+  offset: number;
+  limit: number;
+  items: Package[];
+  cachedAt: number | null;
+  /** Listing is older than the package max age; a re-list is running in the background */
+  stale: boolean;
+}
+
+/** PackagePage as sent by the backend when items are requested compact */
+export interface WirePackagePage extends Omit<PackagePage, 'items'> {
+  items: Package[] | CompactPackageList;
+}
+
+export interface PackageMatch extends Package {
+  matchScore: number;
+}
+
+export interface PrivacyFinding {
+  packageName: string;
+  detail: string;
+  category?: 'telemetry' | 'analytics' | 'ads';
+  /** One-click fix, e.g. revoking a preinstalled app's overlay access */
+  action?: BackendAction;
+}
+
+export interface PrivacyFactor {
+  id: 'telemetry' | 'trackers' | 'permissions' | 'overlays';
+  label: string;
+  count: number;
+  penalty: number;
+  packages: PrivacyFinding[];
+}
+
+export interface PrivacyReport {
+  score: number | null;
+  factors: PrivacyFactor[];
+  suggestedRemovals: { packageName: string; safetyLevel: Package['safetyLevel']; reason: string }[];
+  /** Preinstalled apps running accessibility, autofill or VPN services */
+  reviewItems: { packageName: string; kind: AuditedService; detail: string; active: boolean }[];
+  /** Apps that read the clipboard since boot; listed, not scored */
+  clipboard?: ClipboardAudit;
+  message?: string;
+}
+
+export type AuditedService = 'accessibility' | 'autofill' | 'vpn';
+
+export interface AuditedServiceEntry {
+  kind: AuditedService;
+  packageName: string;
+  appName: string;
+  component: string;
+  active: boolean;  // VPN apps: whether it is the always-on VPN
+  isSystem: boolean | null;
+  priority: 'high' | 'normal';  // high: owned by a preinstalled app
+  detail: string;
+}
+
+export interface ServiceAudit {
+  services: AuditedServiceEntry[];
+  unchecked: AuditedService[];
+  message?: string;
+}
+
+export type SpecialAccess = 'notificationListener' | 'usageAccess' | 'overlay';
+
+export interface SpecialAccessHolder {
+  packageName: string;
+  appName: string;
+  safetyLevel: Package['safetyLevel'] | null;
+  isSystem: boolean | null;  // Preinstalled apps come first
+  access: SpecialAccess[];
+  detail: string;
+}
+
+export interface SpecialAccessInventory {
+  packages: SpecialAccessHolder[];
+  unchecked: SpecialAccess[];  // Kinds the device could not be asked about
+  message?: string;
+}
+
+export interface BackendAction {
+  command: string;
+  args: Record<string, any>;
+  label: string;
+}
+
+export interface PerformanceFactor {
+  id: 'memory' | 'storage' | 'services' | 'wakelocks' | 'thermal' | 'animations';
+  label: string;
+  value: number | null;
+  penalty: number;
+}
+
+export interface PerformanceRecommendation {
+  id: string;
+  severity: 'high' | 'medium' | 'low';
+  message: string;
+  action: BackendAction;
+}
+
+export interface PerformanceReport {
+  score: number | null;
+  factors: PerformanceFactor[];
+  recommendations: PerformanceRecommendation[];
+  message?: string;
+}
+
+export interface OptimizationPlan {
+  removePackages?: string[];
+  restrictBackground?: string[];
+  trimCaches?: boolean;
+  animationScale?: number;
+}
+
+export type OptimizationStage = 'remove' | 'restrict' | 'trim' | 'tweaks';
+
+export interface OptimizationStart extends StatusResult, PolicyRefusal {
+  jobId?: string;
+  stages?: OptimizationStage[];
+}
+
+export interface ImpactMetrics {
+  memoryAvailableKb: number | null;
+  storageAvailableKb: number | null;
+  runningServices: number | null;
+  packageCount: number | null;
+}
+
+export interface OptimizationProgress {
+  stage: OptimizationStage;
+  label: string;
+  index: number;
+  total: number;
+  status: 'running' | 'done' | 'interrupted';
+  current?: string;
+  done?: number;
+  count?: number;
+  succeeded?: number;
+  attempted?: number;
+}
+
+export interface OptimizationSummary {
+  success: boolean;
+  interrupted: boolean;
+  stages: { stage: OptimizationStage; status: 'done' | 'interrupted' | 'skipped'; succeeded?: number; details: any[] }[];
+  before?: ImpactMetrics;
+  after?: ImpactMetrics;
+  impact?: ImpactMetrics;
+  durationSeconds?: number;
+  message?: string;
+}
+
+export interface UndoEntry {
+  id: string;
+  serial: string | null;
+  kind: 'uninstall' | 'reinstall' | 'animation_scale' | 'background' | 'trim_caches';
+  description: string;
+  timestamp: number;
+  reversible: boolean;
+  reason: string | null;
+}
+
+export interface UndoResult extends StatusResult, PolicyRefusal {
+  results: { id: string; description: string; success: boolean; reversible: boolean; message: string }[];
+}
+
+export interface ConfigChanged {
+  name: 'settings';
+  path: string;
+  success: boolean;
+  settings?: BackendSettings;
+  message?: string;
+}
+
+export interface CompatibilityReport {
+  adbVersion: string | null;
+  platformToolsVersion: string | null;
+  sdkLevel: number | null;
+  capabilities: Record<DeviceFeature, boolean>;
+  probed: Partial<Record<DeviceFeature, boolean>>;
+  requirements: Record<DeviceFeature, number>;
+  warnings: string[];
+}
+
+export interface CapabilityProbe {
+  serial: string | null;
+  capabilities: Record<DeviceFeature, boolean> | {};
+  probed: Partial<Record<DeviceFeature, boolean>>;
+  message?: string;
+}
+
+export interface DeviceOwner {
+  userId: number;
+  packageName: string;
+  component: string;
+  type: 'deviceOwner' | 'profileOwner';
+}
+
+export interface DeviceManagement {
+  managed: boolean;
+  deviceOwner: DeviceOwner | null;
+  profileOwners: DeviceOwner[];
+  managedPackages: Record<string, string>;
+}
+
+export interface SessionReport {
+  success: boolean;
+  report: string;
+  path: string;
+  message: string;
+}
+
+export interface PackageStreamStart {
+  jobId: string;
+  total: number;
+}
+
+/** Payload of a package_chunk event */
+export interface PackageChunk {
+  index: number;
+  packages: CompactPackageList;
+  sent: number;
+  total: number;
+  done: boolean;
+}
+
+export interface ConnectionTest {
+  success: boolean;
+  message: string;
+  url: string;
+  proxy: string | null;
+  caBundle: string | null;
+  statusCode?: number;
+  elapsedMs?: number;
+}
+
+export type RebootMode = 'system' | 'recovery' | 'bootloader';
+
+export interface RestartResult {
+  success: boolean;
+  message: string;
+  confirmationRequired?: boolean;  // Call again with confirmed: true after asking the user
+  unsupported?: boolean;
+  packageName?: string;  // restart_launcher: the launcher that was restarted
+}
+
+export interface Trial {
+  id: string;
+  serial: string;
+  packages: string[];
+  startedAt: number;
+  expiresAt: number;
+  status: 'active' | 'kept' | 'reverted';
+  endedAt: number | null;
+  reason: 'expired' | 'problem' | null;
+}
+
+/** Result of start_trial, keep_trial and revert_trial; also the trial_reverted event payload */
+export interface TrialResult extends PolicyRefusal {
+  success: boolean;
+  message: string;
+  trial?: Trial;
+  results?: Array<{ packageName: string; success: boolean; message: string }>;
+}
+
+export interface SelectionViolation {
+  rule: 'keyboard' | 'launcher' | 'webview' | 'dialer' | 'overlay' | 'apex';
+  severity: 'warning' | 'critical';
+  message: string;
+  packages: string[];  // Every provider of the role, all of them selected
+}
+
+export interface SelectionValidation {
+  valid: boolean;  // false when a critical rule is violated
+  violations: SelectionViolation[];
+  unchecked: string[];
+  message?: string;
+}
+
+export type TutorialStepId = 'installAdb' | 'enableUsbDebugging' | 'authorize' | 'firstScan' | 'firstSafeRemoval';
+
+export interface TutorialStep {
+  id: TutorialStepId;
+  title: string;
+  hint: string;
+  status: 'done' | 'skipped' | 'current' | 'pending';
+}
+
+export interface TutorialState {
+  steps: TutorialStep[];
+  current: TutorialStepId | null;  // null once every step is done or skipped
+  completed: boolean;
+  dismissed: boolean;
+  advanced?: number;  // advance_tutorial: steps completed by this call
+  message?: string | null;  // advance_tutorial: why the current step is not done yet
+}
+
+export interface RawDumpService {
+  /** What the app reads from this service */
+  usedFor: string;
+  perPackage: boolean;
+}
+
+export interface RawDump {
+  success: boolean;
+  message?: string;
+  unsupported?: boolean;
+  service?: string;
+  package?: string | null;
+  command?: string;
+  fetchedAt?: number;
+  /** Output was longer than the size limit and cut off */
+  truncated?: boolean;
+  text?: string;
+  page?: number;
+  pageLines?: number;
+  totalPages?: number;
+  totalLines?: number;
+  firstLine?: number;
+}
+
+export interface ParserFailure {
+  /** storage, thermal, battery or memory */
+  parser: string;
+  command: string;
+  firstSeen: number;
+  lastSeen: number;
+  count: number;
+  /** Length of the unreadable output */
+  outputChars: number;
+  /** encoding: the shell printed bytes that are not UTF-8; format: an unknown layout */
+  reason: ParserFailureReason;
+}
+
+export type ParserFailureReason = 'encoding' | 'format';
+
+/** Payload of the parser_failed event */
+export interface ParserFailedEvent {
+  parser: string;
+  command: string;
+  reason: ParserFailureReason;
+}
+
+export interface DiagnosticsExport {
+  success: boolean;
+  message: string;
+  path?: string;
+  failures?: number;
+}
+
+export type PrefetchStageId = 'deviceProfile' | 'packages' | 'sizes' | 'usageStats' | 'health';
+
+export interface PrefetchStage {
+  stage: PrefetchStageId;
+  label: string;
+  status: 'pending' | 'running' | 'done' | 'failed' | 'skipped';
+  message?: string;
+  durationSeconds?: number;
+}
+
+/** Payload of the prefetch_progress event */
+export interface PrefetchProgress extends PrefetchStage {
+  index: number;
+  total: number;
+  serial: string;
+}
+
+export interface PrefetchStatus {
+  id: string;
+  serial: string;
+  started: number;
+  finished: boolean;
+  stages: PrefetchStage[];
+}
+
+/** Payload of the cache_updated event */
+export interface CacheUpdate {
+  cache: 'packages' | 'health' | 'analysis';
+  /** Device serial, or the package name for analysis */
+  key: string;
+  fetchedAt?: number;
+  /** The refresh failed; the stale data stays cached */
+  error?: string;
+}
+
+export interface CacheReport {
+  name: 'packages' | 'health' | 'analysis' | 'labels' | 'rawDump' | 'deviceProfile';
+  entries: number;
+  hits: number;
+  misses: number;
+  hitRatePercent: number | null;
+  /** Approximate bytes held in memory */
+  memoryBytes: number;
+  oldestAgeSeconds: number | null;
+  newestAgeSeconds: number | null;
+  /** Age after which entries are served stale and refreshed */
+  maxAgeSeconds: number | null;
+  formatted: { hitRate: string | null; memory: string };
+}
+
+export interface CacheDiagnostics {
+  caches: CacheReport[];
+  totalMemoryBytes: number;
+  /** [cache, key] pairs being refreshed in the background */
+  refreshing: [string, string][];
+  formatted: { totalMemory: string };
+}
+
+export interface DeviceClassInfo {
+  deviceClass: DeviceClass | null;
+  label?: string;
+  /** ro.build.characteristics */
+  characteristics?: string;
+  /** The system features that decided the class */
+  features?: string[];
+  /** Fire OS version on Amazon devices, null elsewhere */
+  fireOs?: string | null;
+  updateNote?: string;
+  message?: string;
+}
+
+export interface ClassPreset {
+  id: string;
+  name: string;
+  description: string;
+  /** Set on device class presets */
+  deviceClass?: DeviceClass;
+  /** Set on platform presets (Amazon apps on Fire OS) */
+  platform?: 'fireOs';
+  /** Installed packages of the preset */
+  packages: string[];
+}
+
+export interface ClassPresets {
+  deviceClass: DeviceClass | null;
+  presets: ClassPreset[];
+  message?: string;
+}
+
+export interface StayAwakeHold {
+  success: boolean;
+  /** null when the setting is off or the device could not be changed */
+  holdId: string | null;
+}
+
+export interface StayAwakeStatus {
+  enabled: boolean;
+  holds: { holdId: string; serial: string; reason: string }[];
+  /** Serials whose original stay-awake setting is still to be put back */
+  pendingRestore: string[];
+}
+
+export interface JournaledJob {
+  id: string;
+  kind: 'uninstall';
+  serial: string | null;
+  options: { keepData?: boolean };
+  selection: string[];
+  completed: { packageName: string; success: boolean; message: string }[];
+  pending: string[];
+  startedAt: number;
+  updatedAt: number;
+  /** Only set by getIncompleteJobs: whether the job's device is the connected one */
+  deviceConnected?: boolean;
+}
+
+export interface JobStartResult extends PolicyRefusal {
+  success: boolean;
+  jobId?: string;
+  message?: string;
+}
+
+export interface JobFinishResult {
+  success: boolean;
+  finished?: boolean;
+  remaining?: number;
+  message: string;
+}
+
+export interface PackageSignature {
+  success: boolean;
+  packageName: string;
+  /** Signed with the platform key: runs with system privileges */
+  platformSigned?: boolean;
+  /** Privileged system app (priv-app) */
+  privileged?: boolean;
+  sharedUserId?: string | null;
+  signaturePermissions?: string[];
+  /** Plain-language summary of the fields above */
+  notes?: string[];
+  message?: string;
+}
+
+export interface Overlay {
+  /** Overlay id for toggling; Android 12+ fabricated overlays are "package:name" */
+  id: string;
+  packageName: string;
+  /** Package the overlay restyles */
+  target: string;
+  state: 'enabled' | 'disabled' | 'unavailable';
+  /** Left to the device's own settings (navigation mode) */
+  locked: boolean;
+}
+
+export interface OverlayList {
+  success: boolean;
+  overlays: Overlay[];
+  unsupported?: boolean;
+  message?: string;
+}
+
+export interface ApexModule {
+  packageName: string;
+  versionCode: number | null;
+  /** Apps shipped inside the module, as far as the last package listing saw */
+  apps: string[];
+}
+
+export interface ApexModuleList {
+  success: boolean;
+  modules: ApexModule[];
+  unsupported?: boolean;
+  message?: string;
+}
+
+export interface AnalysisExport {
+  success: boolean;
+  /** The digest text, ready to paste */
+  report?: string;
+  path?: string;
+  count?: number;
+  message: string;
+}
+
+export interface WeeklyDigest {
+  generatedAt: number;
+  periodStart: number;
+  periodEnd: number;
+  /** Daily snapshots the digest was built from */
+  snapshots: number;
+  storage: {
+    usedKb: number;
+    availableKb: number;
+    changeKb: number;
+    perDayKb: number;
+    formatted: Record<string, string | null>;
+  } | null;
+  newApps: { packageName: string; safetyLevel: 'Safe' | 'Caution' | 'Expert' | 'Dangerous' }[];
+  removedApps: string[];
+  topDrainers: { packageName: string; mAh: number }[];
+  newDrainers: { packageName: string; mAh: number }[];
+  goneDrainers: string[];
+  suggestions: string[];
+  /** Only on the weekly_digest_ready event */
+  serial?: string;
+}
+
+export interface WeeklyDigestResult {
+  success: boolean;
+  digest?: WeeklyDigest | null;
+  /** Built from the snapshots so far, before the first weekly digest is due */
+  preview?: boolean;
+  /** generatedAt of every stored digest */
+  history?: number[];
+  nextDigestAt?: number;
+  message?: string | null;
+}
+
+export interface RecoveryPlan {
+  /** Packages disabled through the app within `hours` that are still disabled */
+  reenable: string[];
+  /** Active trial disables, reverted first */
+  trials: string[];
+  /** Newest backup of the device, restored after re-enabling */
+  backup: string | null;
+}
+
+export interface EmergencyRecoveryResult extends PolicyRefusal {
+  success: boolean;
+  /** Set without `confirmed` (only the plan is returned), or with `requirement` when the policy refuses */
+  confirmationRequired?: boolean;
+  plan?: RecoveryPlan;
+  steps?: {
+    step: 'revertTrial' | 'reenable' | 'restoreBackup' | 'safeMode';
+    /** Trial id, package, backup name, or how safe mode was entered ('property' | 'powerMenu' | 'manual') */
+    target: string;
+    success: boolean;
+    message: string;
+  }[];
+  message: string;
+}
+
+export type TransferStatus = 'present' | 'restorable' | 'mapped' | 'installable' | 'incompatible' | 'missing';
+
+export interface TransferEntry {
+  packageName: string;
+  status: TransferStatus;
+  /** Package restored on this device: the same one, or its counterpart when `mapped` */
+  target: string | null;
+  /** Role of a mapped app ('gallery', 'dialer', ...) */
+  role?: string;
+  targetInstalled?: boolean;
+  /** Vendor of an incompatible system app */
+  vendor?: string;
+  /** Set by applyBackupTransfer for the packages it tried */
+  restored?: boolean;
+  message: string;
+}
+
+export interface BackupTransferResult {
+  success: boolean;
+  sourceSerial?: string | null;
+  sourceVendor?: string | null;
+  entries?: TransferEntry[];
+  counts?: Record<TransferStatus, number>;
+  restored?: number;
+  failed?: number;
+  message?: string;
+}
+
+/** Payload of `external_package_change` */
+export interface ExternalPackageChange {
+  serial: string;
+  installed: string[];
+  removed: string[];
+}
+
+export interface UnusedApp {
+  packageName: string;
+  appName: string;
+  /** Epoch seconds; null when usage stats have no record of it being opened */
+  lastUsed: number | null;
+  installedAt: number | null;
+  /** Days since it was last opened, or since its install when it never was */
+  daysUnused: number;
+  sizeBytes: number | null;
+  formatted: { size?: string | null };
+}
+
+export interface UnusedAppSuggestions {
+  success: boolean;
+  days?: number;
+  /** Largest first */
+  apps?: UnusedApp[];
+  count?: number;
+  totalBytes?: number;
+  formatted?: { total?: string | null };
+  message: string;
+}
+
+export interface ReplayResult {
+  success: boolean;
+  replayed?: BackendEventKind[];  // Kinds re-sent as events (marked `replayed`), in emit order
+  message?: string;
+}
+
+export interface PermissionChangeResult extends StatusResult {
+  /** Full permission name, e.g. android.permission.RECORD_AUDIO */
+  permission?: string;
+  /** Whether the permission was granted before the change */
+  previous?: boolean;
+  unsupported?: boolean;
+}
+
+export interface ClipboardRead {
+  packageName: string;
+  appName: string;
+  isSystem: boolean | null;
+  /** Seconds since the newest allowed read; null when every read was rejected */
+  lastReadSecondsAgo: number | null;
+  /** Read while the app was not on screen (and not a persistent system process) */
+  background: boolean;
+  rejected: boolean;
+}
+
+export interface ClipboardAudit {
+  /** False on Android 9 and older or when the device keeps no appops history */
+  available: boolean;
+  packages: ClipboardRead[];  // Most recent first
+  message?: string;
+}
+
+export interface OemDatabaseSummary {
+  /** OEM file matched from ro.product.manufacturer / ro.product.brand, null for other makers */
+  oem: string | null;
+  name: string | null;
+  /** Packages rated for this device, shared carrier and partner packs included */
+  packageCount: number;
+  /** Pack name -> packages it rates on this device */
+  packs: Record<string, number>;
+  /** Packs read so far; files are only read for the detected OEM */
+  loaded: string[];
+  /** OEM files shipped with the app */
+  available: string[];
+  /** Hand-maintained ratings merged over every pack (as pack "user") */
+  userDatabase: string | null;
+  message?: string;
+}
+
+/** A saved crash report, without its traceback and log */
+export interface CrashReportSummary {
+  id: string;
+  time: number;
+  /** command, thread, main loop or fatal */
+  kind: 'command' | 'thread' | 'main loop' | 'fatal';
+  command: string | null;
+  thread: string | null;
+  exceptionType: string;
+  message: string;
+  runtime: { python: string; os: string; osRelease: string; packaged: boolean };
+  path: string;
+  submitted: boolean;
+  /** Why posting to diagnostics.crashReportUrl failed */
+  submitError?: string;
+}
+
+/** Whether adb is blocked from a plugged-in device by Linux USB permissions, and how to fix it */
+export interface UsbPermissionHelp {
+  needed: boolean;
+  /** Python's sys.platform: linux, win32, darwin */
+  platform: string;
+  /** Serials adb lists as "no permissions" */
+  serials?: string[];
+  /** PRETTY_NAME from /etc/os-release */
+  distro?: string;
+  family?: 'debian' | 'fedora' | 'arch' | 'suse' | 'other';
+  /** USB vendor ID, e.g. 04e8; null when it could not be found */
+  vendorId?: string | null;
+  vendorName?: string | null;
+  /** plugdev or adbusers when the distribution has it */
+  group?: string | null;
+  rule?: string | null;
+  rulePath?: string;
+  ruleInstalled?: boolean;
+  /** A password prompt (pkexec or passwordless sudo) can write the rule */
+  canInstall?: boolean;
+  steps?: string[];
+}
+
+export interface UdevRuleResult {
+  success: boolean;
+  message: string;
+  rule?: string;
+  rulePath?: string;
+  alreadyInstalled?: boolean;
+}
+
+/** One daily snapshot of a package's battery use */
+export interface BatteryTrendPoint {
+  timestamp: number;
+  /** Estimated mAh since the last full charge */
+  mAh: number;
+  batteryHours: number | null;
+  /** mAh per hour on battery; null when the device had not run on battery */
+  mAhPerHour: number | null;
+  installed: boolean;
+}
+
+/** A package's battery drain over the last days, split at its last disable or uninstall */
+export interface BatteryTrend {
+  success: boolean;
+  packageName: string;
+  days?: number;
+  points?: BatteryTrendPoint[];
+  change?: { kind: 'disable' | 'uninstall' | 'enable' | 'reinstall'; timestamp: number } | null;
+  beforeMAhPerHour?: number | null;
+  afterMAhPerHour?: number | null;
+  changePercent?: number | null;
+  message: string;
+}
+
+/** One time the device was on a charger, from the minute-by-minute battery polls */
+export interface ChargingSession {
+  id: string;
+  start: number;
+  /** null while the session is ongoing */
+  end: number | null;
+  endedBy: 'ongoing' | 'unplugged' | 'disconnected';
+  charger: 'ac' | 'usb' | 'wireless' | 'dock';
+  durationSeconds: number;
+  startLevel: number;
+  endLevel: number;
+  gained: number;
+  percentPerHour: number | null;
+  peakTemperatureC: number | null;
+  averageTemperatureC: number | null;
+  /** From the charger's advertised current and voltage, when the device reports them */
+  maxChargingWatts: number | null;
+  hoursAtFull: number;
+}
+
+/** Payload of the charging_session_ended event */
+export type ChargingSessionEndedEvent = ChargingSession & { serial: string };
+
+export interface ChargingSessions {
+  success: boolean;
+  current?: ChargingSession | null;
+  /** Finished sessions, newest first */
+  sessions: ChargingSession[];
+  insights: string[];
+  message?: string;
+}
+
+export interface ScreenMirrorResult {
+  success: boolean;
+  message: string;
+  serial?: string;
+  /** scrcpy was not found; launch again with `download` to fetch it */
+  needsDownload?: boolean;
+  alreadyRunning?: boolean;
+  path?: string;
+}
+
+export interface ScreenMirrorStatus {
+  installed: boolean;
+  path: string | null;
+  /** Serials with an open mirror window */
+  running: string[];
+  /** A prebuilt scrcpy exists for this system */
+  canDownload: boolean;
+}
+
+export interface ScreenMirrorStopResult {
+  success: boolean;
+  stopped: string[];
+  message: string;
+}
+
+/** Mirrors SETTINGS_PAGES in backend-python/guided_actions.py */
+export type SettingsPage =
+  | 'developerOptions'
+  | 'aboutPhone'
+  | 'appInfo'
+  | 'appNotifications'
+  | 'allApps'
+  | 'defaultApps'
+  | 'usageAccess'
+  | 'batteryOptimization'
+  | 'storage';
+
+/** Mirrors GUIDED_KEYS in backend-python/guided_actions.py */
+export type GuidedKey = 'wake' | 'home' | 'back' | 'recents' | 'scrollUp' | 'scrollDown';
+
+/** Mirrors TAP_TARGETS in backend-python/guided_actions.py */
+export type GuidedTapTarget = 'buildNumber' | 'appStorage';
+
+export interface GuidedActionList {
+  pages: Record<SettingsPage, { description: string; perPackage: boolean }>;
+  keys: GuidedKey[];
+  tapTargets: Record<GuidedTapTarget, { description: string; page: SettingsPage }>;
+}
+
+export interface GuidedActionResult {
+  success: boolean;
+  message: string;
+  page?: SettingsPage;
+  packageName?: string | null;
+  key?: GuidedKey;
+  target?: GuidedTapTarget;
+  /** The build has no such settings page */
+  unsupported?: boolean;
+  /** Where open_in_play_store showed the listing */
+  openedIn?: 'Play Store' | 'app store' | 'browser';
+  /** The tap was refused: the focused window is protected or does not show the target */
+  refused?: boolean;
+  focus?: string | null;
+}
+
+export interface RemoteStatus {
+  running: boolean;
+  /** remote.enabled is on, or the backend was started with --serve */
+  enabled: boolean;
+  /** Base URL of the API ("http://127.0.0.1:8765/api/") while running */
+  url: string | null;
+  loopbackOnly: boolean;
+  /** Bearer token clients send in the Authorization header */
+  token: string;
+  /** Why the server could not start (port in use, bad address) */
+  error: string | null;
+}
+
+export interface RemoteTokenResult {
+  success: boolean;
+  token: string;
+  message: string;
+}
+
+/** Weakest to strongest; `blocked` cannot be confirmed at all */
+export type ConfirmationLevel = 'none' | 'confirm' | 'typedPackage' | 'typedPhrase' | 'blocked';
+
+export interface ConfirmationRequirement {
+  operation: 'uninstall' | 'disable' | 'recover';
+  role: string;
+  level: ConfirmationLevel;
+  packages: Array<{ packageName: string; safetyLevel: SafetyLevel; level: ConfirmationLevel }>;
+  packagesToType: string[];  // Names that must be typed out exactly
+  phrase: string | null;     // Set when level is typedPhrase
+  blocked: string[];         // Packages the role may not touch
+}
+
+/** What the user gave; sent along with uninstalls, data wipes, jobs, trials, optimizations, undo and recovery */
+export interface Confirmation {
+  confirmed: boolean;
+  typedPackages?: string[];
+  phrase?: string;
+}
+
+/** Set on results refused by the confirmation policy */
+export interface PolicyRefusal {
+  confirmationRequired?: boolean;
+  blocked?: boolean;
+  requirement?: ConfirmationRequirement;
+}
+
+export interface ConfirmationPolicy {
+  role: string;
+  roles: string[];
+  roleLocked: boolean;
+  levels: Record<SafetyLevel, ConfirmationLevel>;
+  disableLevels: Record<SafetyLevel, ConfirmationLevel>;
+  batch: ConfirmationLevel;
+  phrase: string;
+  path: string;
+  custom: boolean;  // A policy file exists
+}
+
+export interface ConfirmationRoleResult {
+  success: boolean;
+  message: string;
+  policy?: ConfirmationPolicy;
+}
+
+/** A named package selection from ~/DebloatAI/profiles/<id>.json */
+export interface Profile {
+  id: string;
+  name: string;
+  description: string;
+  packages: string[];
+}
+
+export interface ProfileList {
+  profiles: Profile[];
+  /** Files that did not parse or validate, with the reason */
+  skipped: { file: string; message: string }[];
+  path: string;
+}
+
+export interface BackendEvent<P = any> {
+  event_version: number;
+  job_id: string | null;
+  kind: BackendEventKind;
+  payload: P;
+  replayed?: boolean;  // Re-sent by subscribeWithReplay
 }
 
 export default api;
@@ -0,0 +1,22 @@
+/**
+ * Package wire format
+ * Decodes the compact columnar listings of backend-python/package_wire.py
+ */
+
+import type { CompactPackageList, Package } from '../types.generated';
+
+/** Package dicts from a compact listing; plain arrays pass through unchanged */
+export function expandPackages(list: Package[] | CompactPackageList): Package[] {
+  if (Array.isArray(list)) return list;
+
+  const stringFields = new Set(list.stringFields);
+  return list.rows.map((row) => {
+    const pkg: Record<string, unknown> = {};
+    list.fields.forEach((field, i) => {
+      const value = row[i];
+      if (value === null || value === undefined) return;
+      pkg[field] = stringFields.has(field) ? list.strings[value] : value;
+    });
+    return pkg as unknown as Package;
+  });
+}
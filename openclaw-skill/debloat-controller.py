@@ -8,17 +8,55 @@ Installation:
 3. Use from any chat app connected to OpenClaw
 """
 
-import subprocess
-import json
-import socket
-from typing import Dict, List, Any
+import os
+import sys
+from typing import Dict, List, Optional
+
+# The skill drives the same backend modules as the app, so device logic lives
+# in one place. Point DEBLOAT_AI_BACKEND at backend-python when the skill is
+# copied out of the repository.
+BACKEND_PATH = os.environ.get(
+    "DEBLOAT_AI_BACKEND",
+    os.path.abspath(os.path.join(os.path.dirname(__file__), "..", "backend-python")),
+)
+sys.path.insert(0, BACKEND_PATH)
+
+from adb_operations import ADBOperations, ADBError  # noqa: E402
+from backup_manager import BackupManager  # noqa: E402
+from fuzzy_search import search_packages  # noqa: E402
+
+
+# Safety levels reported as bloatware by scan_bloatware
+BLOATWARE_LEVELS = ("Safe", "Caution")
+
+# Packages shown per listing before "...and N more"
+LIST_PREVIEW = 10
+
+
+def format_packages(packages: List[Dict], limit: int = LIST_PREVIEW) -> str:
+    lines = [f"• {p['packageName']} ({p['appName']}, {p['safetyLevel']})" for p in packages[:limit]]
+    if len(packages) > limit:
+        lines.append(f"...and {len(packages) - limit} more")
+    return "\n".join(lines)
 
 
 class DebloatController:
     """Control Debloat AI application via OpenClaw"""
     
-    def __init__(self):
+    def __init__(self, adb: ADBOperations = None, backups: BackupManager = None):
         self.app_name = "Debloat AI"
+        self.adb = adb or ADBOperations()
+        self.backups = backups or BackupManager(adb_operations=self.adb)
+        self.pending_removal: Optional[str] = None
+    
+    def _resolve_package(self, name: str) -> Optional[Dict]:
+        """Package id or app name -> best matching installed package"""
+        packages = self.adb.list_packages()
+        exact = [p for p in packages if p["packageName"] == name]
+        if exact:
+            return exact[0]
+        matches = search_packages(packages, name, limit=1)
+        return matches[0] if matches else None
     
     def scan_bloatware(self) -> str:
         """
@@ -27,26 +65,20 @@ class DebloatController:
         Usage: "Scan my phone for bloatware"
         """
         try:
-            # This is a placeholder - actual implementation would communicate
-            # with the Debloat AI app via IPC or HTTP
-            return """
-🔍 Scanning for bloatware...
-
-Found 15 potential bloatware packages:
-• com.facebook.katana (Facebook)
-• com.facebook.system (Facebook System)
-• com.instagram.android (Instagram)
-• com.netflix.mediaclient (Netflix)
-• com.spotify.music (Spotify)
-...and 10 more
+            bloat = [p for p in self.adb.list_packages() if p["safetyLevel"] in BLOATWARE_LEVELS]
+        except ADBError as e:
+            return f"❌ Error scanning: {str(e)}"
+        if not bloat:
+            return "✅ No bloatware found on this device"
+        return f"""
+🔍 Found {len(bloat)} potential bloatware packages:
+{format_packages(bloat)}
 
 Use commands like:
 - "Remove Facebook" to uninstall
 - "Analyze com.facebook.katana" for details
 - "Show all packages" for full list
 """
-        except Exception as e:
-            return f"❌ Error scanning: {str(e)}"
     
     def list_packages(self, filter_type: str = "all") -> str:
         """
@@ -57,7 +89,13 @@ Use commands like:
         
         Usage: "Show all packages on my phone"
         """
-        return f"📦 Listing {filter_type} packages..."
+        if filter_type == "bloatware":
+            return self.scan_bloatware()
+        try:
+            packages = self.adb.list_packages(filter_type)
+        except ADBError as e:
+            return f"❌ Error listing packages: {str(e)}"
+        return f"📦 {len(packages)} {filter_type} packages:\n{format_packages(packages)}"
     
     def remove_package(self, package_name: str) -> str:
         """
@@ -68,12 +106,22 @@ Use commands like:
         
         Usage: "Remove Facebook from my phone"
         """
+        try:
+            package = self._resolve_package(package_name)
+        except ADBError as e:
+            return f"❌ Error: {str(e)}"
+        if package is None:
+            return f"❓ No installed package matches \"{package_name}\""
+        
         # Safety confirmation
+        self.pending_removal = package["packageName"]
+        warning = "\n🚨 This package is rated DANGEROUS and may break your device." if package["safetyLevel"] == "Dangerous" else ""
         return f"""
 ⚠️ **Confirm Package Removal**
 
-Package: {package_name}
-Action: Uninstall (ADB)
+Package: {package["packageName"]} ({package["appName"]})
+Safety: {package["safetyLevel"]}
+Action: Uninstall (ADB){warning}
 
 This will remove the package from your connected Android device.
 You can restore it later from backup.
@@ -83,14 +131,16 @@ Reply "confirm" to proceed or "cancel" to abort.
     
     def analyze_package(self, package_name: str) -> str:
         """
-        Get AI analysis of a package's safety
+        Get the safety rating of a package
         
         Args:
             package_name: Package name to analyze
         
         Usage: "Analyze com.facebook.katana"
         """
-        return f"🤖 Analyzing package: {package_name}\n\nFetching AI safety report..."
+        level = self.adb.safety_rater()(package_name)
+        return (f"🤖 {package_name} is rated {level}.\n\n"
+                "Open the AI advisor in Debloat AI for a full safety report.")
     
     def create_backup(self) -> str:
         """
@@ -98,13 +148,19 @@ Reply "confirm" to proceed or "cancel" to abort.
         
         Usage: "Create a backup of my packages"
         """
-        return """
-💾 Creating backup...
-
-Backup created: backup_2026-02-20_14-30.json
-Location: C:\\Users\\YourName\\AppData\\Roaming\\debloat-ai\\backups
+        try:
+            packages = [p["packageName"] for p in self.adb.list_packages()]
+            device_info = self.adb.get_device_info()
+        except ADBError as e:
+            return f"❌ Backup failed: {str(e)}"
+        result = self.backups.create_backup(packages, device_info)
+        if not result["success"]:
+            return f"❌ {result['message']}"
+        return f"""
+💾 Backup created: {result["backupName"]}
+Location: {self.backups.get_backup_path()}
 
-All current packages have been saved. You can restore them anytime.
+All {len(packages)} current packages have been saved. You can restore them anytime.
 """
     
     def get_device_info(self) -> str:
@@ -113,15 +169,17 @@ All current packages have been saved. You can restore them anytime.
         
         Usage: "Show my device info"
         """
-        return """
+        try:
+            info = self.adb.get_device_info()
+        except ADBError as e:
+            return f"❌ {str(e)}"
+        return f"""
 📱 **Connected Device**
 
-Model: Samsung Galaxy S24
-Manufacturer: Samsung
-Android Version: 14
-Serial: ABC123XYZ
-Battery: 85%
-Storage: 45.2 GB free
+Model: {info.get("model")}
+Manufacturer: {info.get("manufacturer")}
+Android Version: {info.get("androidVersion")}
+Serial: {info.get("serial")}
 
 Status: ✅ Connected via ADB
 """
@@ -134,10 +192,16 @@ Status: ✅ Connected via ADB
             action: Action to confirm
             confirmed: True to execute, False to cancel
         """
+        package, self.pending_removal = self.pending_removal, None
         if not confirmed:
             return "❌ Action cancelled"
+        if package is None:
+            return "❓ Nothing to confirm"
         
-        return f"✅ Executing: {action}..."
+        result = self.adb.uninstall_package(package)
+        if result["success"]:
+            return f"✅ Successfully removed {package}"
+        return f"❌ {result['message']}"
 
 
 # OpenClaw Skill Metadata